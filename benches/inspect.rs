@@ -3,8 +3,10 @@ use std::path::Path;
 
 use blazesym::inspect;
 use blazesym::inspect::Inspector;
+use blazesym::SymType;
 
 use criterion::measurement::Measurement;
+use criterion::Bencher;
 use criterion::BenchmarkGroup;
 
 
@@ -50,10 +52,77 @@ fn lookup_dwarf() {
     assert_eq!(result.addr, 0xffffffff8110ecb0);
 }
 
+/// Retrieve the names of `count` function symbols in the given source.
+fn function_names(src: &inspect::Source, count: usize) -> Vec<String> {
+    let inspector = Inspector::new();
+    let mut names = Vec::with_capacity(count);
+    let () = inspector
+        .for_each(src, |sym| {
+            if names.len() < count && sym.sym_type == SymType::Function {
+                let () = names.push(sym.name.to_string());
+            }
+        })
+        .unwrap();
+    names
+}
+
+/// Lookup many names in a DWARF file, either as part of a single batch
+/// or one-by-one.
+fn lookup_dwarf_many_impl<M>(b: &mut Bencher<'_, M>, batch: bool)
+where
+    M: Measurement,
+{
+    let elf_vmlinux = Path::new(&env!("CARGO_MANIFEST_DIR"))
+        .join("data")
+        .join("vmlinux-5.17.12-100.fc34.x86_64.elf");
+    let names = function_names(&inspect::Source::Elf(inspect::Elf::new(elf_vmlinux)), 500);
+    let names = names.iter().map(String::as_str).collect::<Vec<_>>();
+
+    let dwarf_vmlinux = Path::new(&env!("CARGO_MANIFEST_DIR"))
+        .join("data")
+        .join("vmlinux-5.17.12-100.fc34.x86_64.dwarf");
+    let src = inspect::Source::Elf(inspect::Elf::new(dwarf_vmlinux));
+    let inspector = Inspector::new();
+
+    let () = b.iter(|| {
+        if batch {
+            let results = inspector
+                .lookup(black_box(&src), black_box(&names))
+                .unwrap();
+            assert_eq!(results.len(), names.len());
+        } else {
+            for name in &names {
+                let results = inspector
+                    .lookup(black_box(&src), black_box(&[*name]))
+                    .unwrap();
+                assert_eq!(results.len(), 1);
+            }
+        }
+    });
+}
+
+/// Lookup 500 names in a DWARF file as part of a single request.
+fn lookup_dwarf_many_batch<M>(b: &mut Bencher<'_, M>)
+where
+    M: Measurement,
+{
+    lookup_dwarf_many_impl(b, true)
+}
+
+/// Lookup 500 names in a DWARF file, one request per name.
+fn lookup_dwarf_many_loop<M>(b: &mut Bencher<'_, M>)
+where
+    M: Measurement,
+{
+    lookup_dwarf_many_impl(b, false)
+}
+
 pub fn benchmark<M>(group: &mut BenchmarkGroup<'_, M>)
 where
     M: Measurement,
 {
     bench_fn!(group, lookup_dwarf);
     bench_fn!(group, lookup_elf);
+    bench_sub_fn!(group, lookup_dwarf_many_batch);
+    bench_sub_fn!(group, lookup_dwarf_many_loop);
 }
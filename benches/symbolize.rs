@@ -14,9 +14,12 @@ use blazesym::Addr;
 use blazesym::Pid;
 
 use criterion::measurement::Measurement;
+use criterion::BatchSize;
 use criterion::Bencher;
 use criterion::BenchmarkGroup;
 
+use tempfile::tempdir;
+
 
 /// Symbolize addresses in the current process.
 fn symbolize_process() {
@@ -100,6 +103,70 @@ fn symbolize_dwarf_no_lines() {
     assert_eq!(result.code_info.as_ref(), None);
 }
 
+/// Symbolize an address in a DWARF file, excluding inlined function
+/// information, end-to-end, i.e., including all necessary setup.
+fn symbolize_dwarf_no_inlined_fns() {
+    let dwarf_vmlinux = Path::new(&env!("CARGO_MANIFEST_DIR"))
+        .join("data")
+        .join("vmlinux-5.17.12-100.fc34.x86_64.dwarf");
+    let src = Source::Elf(Elf::new(dwarf_vmlinux));
+    let symbolizer = Symbolizer::builder().enable_inlined_fns(false).build();
+
+    let result = symbolizer
+        .symbolize_single(
+            black_box(&src),
+            black_box(Input::VirtOffset(0xffffffff8110ecb0)),
+        )
+        .unwrap()
+        .into_sym()
+        .unwrap();
+
+    assert_eq!(result.name, "abort_creds");
+    assert_eq!(result.code_info.as_ref().unwrap().line, Some(534));
+    assert!(result.inlined.is_empty());
+}
+
+/// Symbolize an address covered by inlined functions in a small DWARF
+/// file, end-to-end, with the provided settings.
+fn symbolize_dwarf_small(code_info: bool, inlined_fns: bool) {
+    let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+        .join("data")
+        .join("test-stable-addrs.bin");
+    let src = Source::Elf(Elf::new(path));
+    let symbolizer = Symbolizer::builder()
+        .enable_code_info(code_info)
+        .enable_inlined_fns(inlined_fns)
+        .build();
+
+    let result = symbolizer
+        .symbolize_single(black_box(&src), black_box(Input::VirtOffset(0x200020a)))
+        .unwrap()
+        .into_sym()
+        .unwrap();
+
+    assert_eq!(result.name, "factorial_inline_test");
+    assert_eq!(result.code_info.is_some(), code_info);
+    assert_eq!(result.inlined.is_empty(), !(code_info && inlined_fns));
+}
+
+/// Symbolize an address in a small DWARF file, excluding line and
+/// inlined function information.
+fn symbolize_dwarf_small_no_lines() {
+    symbolize_dwarf_small(false, false)
+}
+
+/// Symbolize an address in a small DWARF file, excluding inlined
+/// function information.
+fn symbolize_dwarf_small_no_inlined_fns() {
+    symbolize_dwarf_small(true, false)
+}
+
+/// Symbolize an address in a small DWARF file, including all
+/// information.
+fn symbolize_dwarf_small_all() {
+    symbolize_dwarf_small(true, true)
+}
+
 /// Symbolize an address in a DWARF file, end-to-end, i.e., including all
 /// necessary setup.
 fn symbolize_dwarf() {
@@ -122,6 +189,54 @@ fn symbolize_dwarf() {
     assert_eq!(result.code_info.as_ref().unwrap().line, Some(534));
 }
 
+/// Symbolize an address in a DWARF file, end-to-end, using the provided
+/// DWARF cache directory.
+fn symbolize_dwarf_with_cache_dir(cache_dir: &Path) {
+    let dwarf_vmlinux = Path::new(&env!("CARGO_MANIFEST_DIR"))
+        .join("data")
+        .join("vmlinux-5.17.12-100.fc34.x86_64.dwarf");
+    let src = Source::Elf(Elf::new(dwarf_vmlinux));
+    let symbolizer = Symbolizer::builder().set_cache_dir(cache_dir).build();
+
+    let result = symbolizer
+        .symbolize_single(
+            black_box(&src),
+            black_box(Input::VirtOffset(0xffffffff8110ecb0)),
+        )
+        .unwrap()
+        .into_sym()
+        .unwrap();
+
+    assert_eq!(result.name, "abort_creds");
+    assert_eq!(result.code_info.as_ref().unwrap().line, Some(534));
+}
+
+/// Symbolize an address in a DWARF file, end-to-end, starting out with
+/// an empty DWARF cache, which hence gets populated.
+fn symbolize_dwarf_cold_cache<M>(b: &mut Bencher<'_, M>)
+where
+    M: Measurement,
+{
+    let () = b.iter_batched(
+        || tempdir().unwrap(),
+        |cache_dir| symbolize_dwarf_with_cache_dir(cache_dir.path()),
+        BatchSize::PerIteration,
+    );
+}
+
+/// Symbolize an address in a DWARF file, end-to-end, using a populated
+/// DWARF cache.
+fn symbolize_dwarf_warm_cache<M>(b: &mut Bencher<'_, M>)
+where
+    M: Measurement,
+{
+    let cache_dir = tempdir().unwrap();
+    // Populate the cache.
+    let () = symbolize_dwarf_with_cache_dir(cache_dir.path());
+
+    let () = b.iter(|| symbolize_dwarf_with_cache_dir(cache_dir.path()));
+}
+
 /// Symbolize an address in a Gsym file, end-to-end, i.e., including all
 /// necessary setup.
 fn symbolize_gsym() {
@@ -184,7 +299,13 @@ where
     bench_fn!(group, symbolize_breakpad);
     bench_fn!(group, symbolize_elf);
     bench_fn!(group, symbolize_dwarf_no_lines);
+    bench_fn!(group, symbolize_dwarf_no_inlined_fns);
     bench_fn!(group, symbolize_dwarf);
+    bench_fn!(group, symbolize_dwarf_small_no_lines);
+    bench_fn!(group, symbolize_dwarf_small_no_inlined_fns);
+    bench_fn!(group, symbolize_dwarf_small_all);
+    bench_sub_fn!(group, symbolize_dwarf_cold_cache);
+    bench_sub_fn!(group, symbolize_dwarf_warm_cache);
     bench_fn!(group, symbolize_gsym);
     bench_sub_fn!(group, symbolize_gsym_multi_no_setup);
 }
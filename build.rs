@@ -55,6 +55,17 @@ where
 
 /// Run a command with the provided arguments.
 fn run<C, A, S>(command: C, args: A) -> Result<()>
+where
+    C: AsRef<OsStr>,
+    A: IntoIterator<Item = S> + Clone,
+    S: AsRef<OsStr>,
+{
+    run_with_env(command, args, &[])
+}
+
+/// Run a command with the provided arguments, setting the provided
+/// environment variables in addition to `PATH`.
+fn run_with_env<C, A, S>(command: C, args: A, envs: &[(&str, &OsStr)]) -> Result<()>
 where
     C: AsRef<OsStr>,
     A: IntoIterator<Item = S> + Clone,
@@ -65,6 +76,7 @@ where
         .stdout(Stdio::null())
         .env_clear()
         .envs(env::vars().filter(|(k, _)| k == "PATH"))
+        .envs(envs.iter().copied())
         .args(args.clone())
         .output()
         .map_err(|err| {
@@ -138,6 +150,7 @@ fn adjust_mtime(path: &Path) -> Result<()> {
 
 enum ArgSpec {
     SrcDashODst,
+    SrcDst,
 }
 
 /// Invoke `tool` to convert `src` into `dst`.
@@ -153,12 +166,17 @@ fn toolize_impl(
     println!("cargo:rerun-if-changed={}", src.display());
     println!("cargo:rerun-if-changed={}", dst.display());
 
+    let args2;
     let args3;
     let args = match arg_spec {
         ArgSpec::SrcDashODst => {
             args3 = [src.as_os_str(), "-o".as_ref(), dst.as_os_str()];
             args3.as_slice()
         }
+        ArgSpec::SrcDst => {
+            args2 = [src.as_os_str(), dst.as_os_str()];
+            args2.as_slice()
+        }
     };
 
     let () = run(
@@ -182,11 +200,47 @@ fn cc(src: &Path, dst: impl AsRef<OsStr>, options: &[&str]) {
     toolize_o("cc", src, dst, options)
 }
 
+/// Compile `src` into `dst` using `c++`.
+fn cxx(src: &Path, dst: impl AsRef<OsStr>, options: &[&str]) {
+    toolize_o("c++", src, dst, options)
+}
+
 /// Compile `src` into `dst` using `rustc`.
 fn rustc(src: &Path, dst: impl AsRef<OsStr>, options: &[&str]) {
     toolize_o("rustc", src, dst, options)
 }
 
+/// Compile the Go program `src` into `dst`, if a Go toolchain is
+/// available.
+fn go(src: &Path, dst: impl AsRef<OsStr>, options: &[&str]) {
+    let dst = src.with_file_name(dst);
+    println!("cargo:rerun-if-changed={}", src.display());
+    println!("cargo:rerun-if-changed={}", dst.display());
+
+    // Go binaries are only used for optional tests and we don't want
+    // to require a Go toolchain for running the test suite.
+    if run("go", ["version"]).is_err() {
+        return
+    }
+
+    // The Go toolchain insists on having a build cache and a module
+    // path, which it would otherwise derive from `HOME`.
+    let out_dir = PathBuf::from(env::var_os("OUT_DIR").unwrap());
+    let cache = out_dir.join("go-cache");
+    let path = out_dir.join("go-path");
+    let () = run_with_env(
+        "go",
+        [OsStr::new("build")]
+            .into_iter()
+            .chain(options.iter().map(OsStr::new))
+            .chain(["-o".as_ref(), dst.as_os_str(), src.as_os_str()]),
+        &[("GOCACHE", cache.as_os_str()), ("GOPATH", path.as_os_str())],
+    )
+    .expect("failed to run `go build`");
+
+    let () = adjust_mtime(&dst).unwrap();
+}
+
 /// Convert debug information contained in `src` into GSYM in `dst` using
 /// `llvm-gsymutil`.
 fn gsym(src: &Path, dst: impl AsRef<OsStr>) {
@@ -206,11 +260,34 @@ fn gsym(src: &Path, dst: impl AsRef<OsStr>) {
     let () = adjust_mtime(&dst).unwrap();
 }
 
+/// Convert the YAML description of a PDB file in `src` into an actual
+/// PDB file in `dst` using `llvm-pdbutil`.
+fn pdb(src: &Path, dst: impl AsRef<OsStr>) {
+    let dst = src.with_file_name(dst);
+    println!("cargo:rerun-if-changed={}", src.display());
+    println!("cargo:rerun-if-changed={}", dst.display());
+    println!("cargo:rerun-if-env-changed=LLVM_PDBUTIL");
+
+    let pdbutil = env::var_os("LLVM_PDBUTIL").unwrap_or_else(|| OsString::from("llvm-pdbutil"));
+    let mut pdb_arg = OsString::from("--pdb=");
+    let () = pdb_arg.push(&dst);
+
+    let () = run(pdbutil, ["yaml2pdb".as_ref(), src.as_os_str(), &pdb_arg])
+        .expect("failed to run `llvm-pdbutil`");
+
+    let () = adjust_mtime(&dst).unwrap();
+}
+
 /// Invoke `strip` on a copy of `src` placed at `dst`.
 fn strip(src: &Path, dst: impl AsRef<OsStr>, options: &[&str]) {
     toolize_o("strip", src, dst, options)
 }
 
+/// Invoke `objcopy` to convert `src` into `dst`.
+fn objcopy(src: &Path, dst: impl AsRef<OsStr>, options: &[&str]) {
+    toolize_impl("objcopy", ArgSpec::SrcDst, src, dst, options)
+}
+
 /// Strip all DWARF information from an ELF binary, in an attempt to
 /// leave only ELF symbols in place.
 fn elf(src: &Path, dst: impl AsRef<OsStr>) {
@@ -324,6 +401,146 @@ fn zip(_files: &[PathBuf], _dst: &Path) {
     unimplemented!()
 }
 
+/// Calculate the CRC-32 checksum of `data`, as used by zip archives.
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in data {
+        crc ^= u32::from(*byte);
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xedb88320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+/// Put files in a ZIP64 zip archive, uncompressed, with sizes stored
+/// in data descriptors and `prefix` prepended to the archive.
+///
+/// Off-the-shelf zip writers only use ZIP64 extensions for archives
+/// that actually require them, so we lay out the archive manually.
+fn zip64(files: &[PathBuf], prefix: &[u8], dst: &Path) {
+    use std::fs::read as read_file;
+    use std::fs::write as write_file;
+
+    let dst_dir = dst.parent().unwrap();
+    let page_size = page_size().unwrap();
+    // Offsets recorded in the archive are relative to its start, i.e.,
+    // they don't account for the prefix.
+    let mut zip = Vec::new();
+    let mut cd = Vec::new();
+    let mut cd_records = 0u64;
+
+    for file in files {
+        let contents = read_file(file).unwrap();
+        let path = file.strip_prefix(dst_dir).unwrap();
+        let name = path.to_str().unwrap().as_bytes();
+        let name_len = u16::try_from(name.len()).unwrap();
+        let crc = crc32(&contents);
+        let size = contents.len() as u64;
+        let offset = zip.len() as u64;
+
+        // The ZIP64 extra field, followed by a padding extra field
+        // making sure that the member data are page aligned inside the
+        // file, so that they can be mmap'ed directly.
+        let mut extra = Vec::new();
+        let () = extra.extend_from_slice(&0x0001u16.to_le_bytes());
+        let () = extra.extend_from_slice(&16u16.to_le_bytes());
+        // Sizes are only known once the data have been written and are
+        // reported in the data descriptor.
+        let () = extra.extend_from_slice(&[0; 16]);
+        let data_offset = prefix.len() + zip.len() + 30 + name.len() + extra.len() + 4;
+        let padding = (page_size - data_offset % page_size) % page_size;
+        let () = extra.extend_from_slice(&0xd935u16.to_le_bytes());
+        let () = extra.extend_from_slice(&u16::try_from(padding).unwrap().to_le_bytes());
+        let () = extra.resize(extra.len() + padding, 0);
+
+        let () = zip.extend_from_slice(&0x04034b50u32.to_le_bytes());
+        // Minimum version (4.5), flags (data descriptor present),
+        // compression (stored), last modification time & date.
+        let () = zip.extend_from_slice(&[45, 0, 8, 0, 0, 0, 0, 0, 0, 0]);
+        // CRC and sizes, as they are reported in the data descriptor.
+        let () = zip.extend_from_slice(&[0; 4]);
+        let () = zip.extend_from_slice(&u32::MAX.to_le_bytes());
+        let () = zip.extend_from_slice(&u32::MAX.to_le_bytes());
+        let () = zip.extend_from_slice(&name_len.to_le_bytes());
+        let () = zip.extend_from_slice(&u16::try_from(extra.len()).unwrap().to_le_bytes());
+        let () = zip.extend_from_slice(name);
+        let () = zip.extend_from_slice(&extra);
+        assert_eq!((prefix.len() + zip.len()) % page_size, 0);
+        let () = zip.extend_from_slice(&contents);
+
+        let () = zip.extend_from_slice(&0x08074b50u32.to_le_bytes());
+        let () = zip.extend_from_slice(&crc.to_le_bytes());
+        let () = zip.extend_from_slice(&size.to_le_bytes());
+        let () = zip.extend_from_slice(&size.to_le_bytes());
+
+        let () = cd.extend_from_slice(&0x02014b50u32.to_le_bytes());
+        // Version (4.5, Unix), minimum version (4.5), flags (data
+        // descriptor present), compression (stored), last modification
+        // time & date.
+        let () = cd.extend_from_slice(&[45, 3, 45, 0, 8, 0, 0, 0, 0, 0, 0, 0]);
+        let () = cd.extend_from_slice(&crc.to_le_bytes());
+        // Sizes are stored in the ZIP64 extra field.
+        let () = cd.extend_from_slice(&u32::MAX.to_le_bytes());
+        let () = cd.extend_from_slice(&u32::MAX.to_le_bytes());
+        let () = cd.extend_from_slice(&name_len.to_le_bytes());
+        let () = cd.extend_from_slice(&28u16.to_le_bytes());
+        // Comment length, disk, internal & external attributes.
+        let () = cd.extend_from_slice(&[0; 10]);
+        // The local file header offset is stored in the ZIP64 extra
+        // field.
+        let () = cd.extend_from_slice(&u32::MAX.to_le_bytes());
+        let () = cd.extend_from_slice(name);
+        let () = cd.extend_from_slice(&0x0001u16.to_le_bytes());
+        let () = cd.extend_from_slice(&24u16.to_le_bytes());
+        let () = cd.extend_from_slice(&size.to_le_bytes());
+        let () = cd.extend_from_slice(&size.to_le_bytes());
+        let () = cd.extend_from_slice(&offset.to_le_bytes());
+        cd_records += 1;
+    }
+
+    let cd_offset = zip.len() as u64;
+    let cd_size = cd.len() as u64;
+    let () = zip.extend_from_slice(&cd);
+
+    let eocd64_offset = zip.len() as u64;
+    let () = zip.extend_from_slice(&0x06064b50u32.to_le_bytes());
+    // Size of the remaining record.
+    let () = zip.extend_from_slice(&44u64.to_le_bytes());
+    // Version, minimum version, this disk, central directory disk.
+    let () = zip.extend_from_slice(&[45, 3, 45, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+    let () = zip.extend_from_slice(&cd_records.to_le_bytes());
+    let () = zip.extend_from_slice(&cd_records.to_le_bytes());
+    let () = zip.extend_from_slice(&cd_size.to_le_bytes());
+    let () = zip.extend_from_slice(&cd_offset.to_le_bytes());
+
+    let () = zip.extend_from_slice(&0x07064b50u32.to_le_bytes());
+    // Disk containing the ZIP64 end of central directory record.
+    let () = zip.extend_from_slice(&[0; 4]);
+    let () = zip.extend_from_slice(&eocd64_offset.to_le_bytes());
+    // Total number of disks.
+    let () = zip.extend_from_slice(&1u32.to_le_bytes());
+
+    let () = zip.extend_from_slice(&0x06054b50u32.to_le_bytes());
+    // All values are stored in the ZIP64 end of central directory
+    // record.
+    let () = zip.extend_from_slice(&[0xff; 16]);
+    // Comment length.
+    let () = zip.extend_from_slice(&[0; 2]);
+
+    let () = write_file(dst, [prefix, &zip].concat()).unwrap();
+
+    for file in files {
+        println!("cargo:rerun-if-changed={}", file.display());
+    }
+    println!("cargo:rerun-if-changed={}", dst.display());
+    let () = adjust_mtime(dst).unwrap();
+}
+
 
 fn cc_stable_addrs(dst: impl AsRef<OsStr>, options: &[&str]) {
     let data_dir = data_dir();
@@ -384,6 +601,29 @@ fn prepare_test_files() {
         "libtest-so-no-separate-code.so",
         &["-shared", "-fPIC", "-Wl,--build-id=md5,-z,noseparate-code"],
     );
+    cc(
+        &src,
+        "libtest-so-soname.so",
+        &["-shared", "-fPIC", "-Wl,-soname,libtest-so.so.1"],
+    );
+    cc(
+        &src,
+        "libtest-so-rpath.so",
+        &[
+            "-shared",
+            "-fPIC",
+            "-Wl,--disable-new-dtags,-rpath,/opt/lib:$ORIGIN/lib",
+        ],
+    );
+    cc(
+        &src,
+        "libtest-so-runpath.so",
+        &[
+            "-shared",
+            "-fPIC",
+            "-Wl,--enable-new-dtags,-rpath,/opt/lib:$ORIGIN/lib",
+        ],
+    );
     let src = data_dir.join("libtest-so.so");
     gsym(&src, "libtest-so.gsym");
     strip(&src, "libtest-so-stripped.so", &[]);
@@ -393,7 +633,36 @@ fn prepare_test_files() {
         &["--keep-symbol=the_ignored_answer"],
     );
 
+    // Strip DWARF debug information, as we are interested in
+    // symbolization based on Go's PC-line table.
+    let src = data_dir.join("test.go");
+    go(&src, "test-go.bin", &["-ldflags=-w"]);
+
+    let src = data_dir.join("test-so-32.c");
+    cc(
+        &src,
+        "libtest-so-32.so",
+        &[
+            "-m32",
+            "-shared",
+            "-fPIC",
+            "-nostdlib",
+            "-Wl,--build-id=sha1",
+        ],
+    );
+    cc(
+        &src,
+        "libtest-so-32-dwarf.so",
+        &["-m32", "-shared", "-fPIC", "-nostdlib", "-g", "-gz=zlib"],
+    );
+
     let src = data_dir.join("test-exe.c");
+    cc(&src, "test-plt.bin", &["-fcf-protection=none"]);
+    cc(
+        &src,
+        "test-plt-ibt.bin",
+        &["-fcf-protection=full", "-Wl,-z,ibtplt"],
+    );
     cc(&src, "test-no-debug.bin", &["-g0", "-Wl,--build-id=none"]);
     cc(&src, "test-dwarf-v2.bin", &["-gstrict-dwarf", "-gdwarf-2"]);
     cc(&src, "test-dwarf-v3.bin", &["-gstrict-dwarf", "-gdwarf-3"]);
@@ -415,9 +684,86 @@ fn prepare_test_files() {
     let src = data_dir.join("test-wait.c");
     cc(&src, "test-wait.bin", &[]);
 
+    let src = data_dir.join("test-wait-32.c");
+    cc(
+        &src,
+        "test-wait-32.bin",
+        &[
+            "-m32",
+            "-static",
+            "-nostdlib",
+            "-fno-pie",
+            "-no-pie",
+            "-Wl,--build-id=sha1",
+        ],
+    );
+
     let src = data_dir.join("test-mnt-ns.c");
     cc(&src, "test-mnt-ns.bin", &[]);
 
+    let src = data_dir.join("test-types.c");
+    cc(&src, "test-types.bin", &["-g"]);
+    cc(
+        &src,
+        "test-types-dwarf-v2.bin",
+        &["-g", "-gstrict-dwarf", "-gdwarf-2"],
+    );
+
+    let src = data_dir.join("test-deep-inline.c");
+    cc(&src, "test-deep-inline.bin", &["-g", "-O0"]);
+
+    let src = data_dir.join("test-label.c");
+    cc(&src, "test-label.bin", &["-g", "-O0"]);
+
+    let src = data_dir.join("test-label-asm.S");
+    cc(
+        &src,
+        "test-label-asm.bin",
+        &["-nostdlib", "-static", "-no-pie"],
+    );
+
+    let src = data_dir.join("test-cpp.cpp");
+    cxx(&src, "test-cpp.bin", &["-g"]);
+
+    let src = data_dir.join("test-rnglists.c");
+    cc(
+        &src,
+        "test-rnglists.bin",
+        &["-gdwarf-5", "-O2", "-freorder-blocks-and-partition"],
+    );
+
+    let src = data_dir.join("test-inline-origin.c");
+    cc(&src, "test-inline-origin.bin", &["-g", "-O2"]);
+
+    let src = data_dir.join("test-tls.c");
+    cc(&src, "libtest-tls.so", &["-shared", "-fPIC"]);
+
+    let src = data_dir.join("test-versioned.c");
+    let version_script = data_dir.join("test-versioned.map");
+    println!("cargo:rerun-if-changed={}", version_script.display());
+    cc(
+        &src,
+        "libtest-versioned.so",
+        &[
+            "-shared",
+            "-fPIC",
+            &format!("-Wl,--version-script={}", version_script.display()),
+        ],
+    );
+    let src = data_dir.join("libtest-versioned.so");
+    strip(&src, "libtest-versioned-stripped.so", &[]);
+    // Symbol versioning sections are identified by type; their names
+    // carry no meaning.
+    objcopy(
+        &src,
+        "libtest-versioned-renamed.so",
+        &[
+            "--rename-section=.gnu.version=.versym",
+            "--rename-section=.gnu.version_d=.verdef",
+            "--rename-section=.gnu.version_r=.verneed",
+        ],
+    );
+
     cc_stable_addrs(
         "test-stable-addrs.bin",
         &["-gdwarf-4", "-Wl,--build-id=none", "-O0"],
@@ -455,6 +801,9 @@ fn prepare_test_files() {
         syms(&src, "test-stable-addrs.sym");
     }
 
+    let src = data_dir.join("test-pe.pdb.yaml");
+    pdb(&src, "test-pe.pdb");
+
     let src = data_dir.join("kallsyms.xz");
     let mut dst = src.clone();
     assert!(dst.set_extension(""));
@@ -482,6 +831,18 @@ fn prepare_test_files() {
     ];
     let dst = data_dir.join("test.zip");
     zip(files.as_slice(), &dst);
+
+    let files = [
+        data_dir.join("zip-dir").join("test-no-debug.bin"),
+        data_dir.join("libtest-so.so"),
+    ];
+    let dst = data_dir.join("test-zip64.zip");
+    zip64(files.as_slice(), &[], &dst);
+
+    // APKs may be prefixed by arbitrary data, just like self-extracting
+    // archives.
+    let dst = data_dir.join("test-zip64-prefixed.zip");
+    zip64(files.as_slice(), b"#!/bin/sh\nexit 1\n", &dst);
 }
 
 /// Download a multi-part file split into `part_count` pieces.
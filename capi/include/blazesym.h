@@ -109,6 +109,12 @@ enum blaze_normalize_reason
    * The address belonged to an entity that is currently unsupported.
    */
   BLAZE_NORMALIZE_REASON_UNSUPPORTED,
+  /**
+   * The address belongs to an Android Runtime (ART) boot image
+   * (e.g., `boot.art` or `boot.oat`), which contains precompiled Java
+   * code and is not an ordinary ELF binary.
+   */
+  BLAZE_NORMALIZE_REASON_ART_BOOT_IMAGE,
 };
 #ifndef __cplusplus
 typedef uint8_t blaze_normalize_reason;
@@ -138,6 +144,10 @@ enum blaze_sym_type
    * The symbol is a variable.
    */
   BLAZE_SYM_VAR,
+  /**
+   * The symbol is a thread-local variable.
+   */
+  BLAZE_SYM_TLS,
 };
 #ifndef __cplusplus
 typedef uint8_t blaze_sym_type;
@@ -302,10 +312,14 @@ typedef struct blaze_user_meta_elf {
    * The optional build ID of the ELF file, if found.
    */
   uint8_t *build_id;
+  /**
+   * Whether the ELF file is the process' main executable.
+   */
+  bool is_main_exe;
   /**
    * Unused member available for future expansion.
    */
-  uint8_t reserved[8];
+  uint8_t reserved[7];
 } blaze_user_meta_elf;
 
 /**
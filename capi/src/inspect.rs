@@ -137,6 +137,8 @@ pub enum blaze_sym_type {
     BLAZE_SYM_FUNC,
     /// The symbol is a variable.
     BLAZE_SYM_VAR,
+    /// The symbol is a thread-local variable.
+    BLAZE_SYM_TLS,
 }
 
 impl From<SymType> for blaze_sym_type {
@@ -145,6 +147,7 @@ impl From<SymType> for blaze_sym_type {
             SymType::Undefined => blaze_sym_type::BLAZE_SYM_UNDEF,
             SymType::Function => blaze_sym_type::BLAZE_SYM_FUNC,
             SymType::Variable => blaze_sym_type::BLAZE_SYM_VAR,
+            SymType::Tls => blaze_sym_type::BLAZE_SYM_TLS,
             _ => unreachable!(),
         }
     }
@@ -210,6 +213,9 @@ fn convert_syms_list_to_c(syms_list: Vec<Vec<SymInfo>>) -> *const *const blaze_s
             addr,
             size,
             sym_type,
+            binding: _,
+            version: _,
+            is_ifunc: _,
             file_offset,
             obj_file_name,
         } in syms
@@ -519,6 +525,9 @@ mod tests {
             addr: 0xdeadbeef,
             size: 42,
             sym_type: SymType::Function,
+            binding: None,
+            version: None,
+            is_ifunc: false,
             file_offset: Some(1337),
             obj_file_name: Some(Path::new("/tmp/foobar.so").into()),
         }]];
@@ -531,6 +540,9 @@ mod tests {
                 addr: 0xdeadbeef,
                 size: 42,
                 sym_type: SymType::Function,
+                binding: None,
+                version: None,
+                is_ifunc: false,
                 file_offset: Some(1337),
                 obj_file_name: Some(Path::new("/tmp/foobar.so").into()),
             },
@@ -539,6 +551,9 @@ mod tests {
                 addr: 0xdeadbeef + 52,
                 size: 45,
                 sym_type: SymType::Undefined,
+                binding: None,
+                version: None,
+                is_ifunc: false,
                 file_offset: Some(1338),
                 obj_file_name: Some(Path::new("other.so").into()),
             },
@@ -552,6 +567,9 @@ mod tests {
                 addr: 0xdeadbeef,
                 size: 42,
                 sym_type: SymType::Function,
+                binding: None,
+                version: None,
+                is_ifunc: false,
                 file_offset: Some(1337),
                 obj_file_name: Some(Path::new("/tmp/foobar.so").into()),
             }],
@@ -560,6 +578,9 @@ mod tests {
                 addr: 0xdeadbeef + 52,
                 size: 45,
                 sym_type: SymType::Undefined,
+                binding: None,
+                version: None,
+                is_ifunc: false,
                 file_offset: Some(1338),
                 obj_file_name: Some(Path::new("other.so").into()),
             }],
@@ -572,6 +593,9 @@ mod tests {
             addr: 0xdeadbeef,
             size: 42,
             sym_type: SymType::Function,
+            binding: None,
+            version: None,
+            is_ifunc: false,
             file_offset: Some(1337),
             obj_file_name: Some(Path::new("/tmp/foobar.so").into()),
         };
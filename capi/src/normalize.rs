@@ -235,8 +235,10 @@ pub struct blaze_user_meta_elf {
     pub build_id_len: usize,
     /// The optional build ID of the ELF file, if found.
     pub build_id: *mut u8,
+    /// Whether the ELF file is the process' main executable.
+    pub is_main_exe: bool,
     /// Unused member available for future expansion.
-    pub reserved: [u8; 8],
+    pub reserved: [u8; 7],
 }
 
 impl blaze_user_meta_elf {
@@ -244,6 +246,8 @@ impl blaze_user_meta_elf {
         let Elf {
             path,
             build_id,
+            soname: _,
+            is_main_exe,
             _non_exhaustive: (),
         } = other;
 
@@ -267,7 +271,8 @@ impl blaze_user_meta_elf {
                     }
                 })
                 .unwrap_or_else(ptr::null_mut),
-            reserved: [0u8; 8],
+            is_main_exe,
+            reserved: [0u8; 7],
         };
         ManuallyDrop::new(slf)
     }
@@ -277,6 +282,7 @@ impl blaze_user_meta_elf {
             path,
             build_id_len,
             build_id,
+            is_main_exe,
             reserved: _,
         } = self;
 
@@ -290,6 +296,8 @@ impl blaze_user_meta_elf {
                         .into_vec(),
                 )
             }),
+            soname: None,
+            is_main_exe,
             _non_exhaustive: (),
         };
     }
@@ -312,6 +320,10 @@ pub enum blaze_normalize_reason {
     BLAZE_NORMALIZE_REASON_MISSING_COMPONENT,
     /// The address belonged to an entity that is currently unsupported.
     BLAZE_NORMALIZE_REASON_UNSUPPORTED,
+    /// The address belongs to an Android Runtime (ART) boot image
+    /// (e.g., `boot.art` or `boot.oat`), which contains precompiled Java
+    /// code and is not an ordinary ELF binary.
+    BLAZE_NORMALIZE_REASON_ART_BOOT_IMAGE,
 }
 
 impl From<Reason> for blaze_normalize_reason {
@@ -322,6 +334,7 @@ impl From<Reason> for blaze_normalize_reason {
                 blaze_normalize_reason::BLAZE_NORMALIZE_REASON_MISSING_COMPONENT
             }
             Reason::Unsupported => blaze_normalize_reason::BLAZE_NORMALIZE_REASON_UNSUPPORTED,
+            Reason::ArtBootImage => blaze_normalize_reason::BLAZE_NORMALIZE_REASON_ART_BOOT_IMAGE,
             _ => unreachable!(),
         }
     }
@@ -413,6 +426,17 @@ impl blaze_user_meta {
                     unknown: blaze_user_meta_unknown::from(unknown),
                 },
             },
+            // The vDSO and `vsyscall` page can't be symbolized from a
+            // file and don't have a C representation of their own.
+            UserMeta::Vdso(..) | UserMeta::Vsyscall(..) => Self {
+                kind: blaze_user_meta_kind::BLAZE_USER_META_UNKNOWN,
+                variant: blaze_user_meta_variant {
+                    unknown: blaze_user_meta_unknown::from(Unknown {
+                        reason: Reason::Unsupported,
+                        _non_exhaustive: (),
+                    }),
+                },
+            },
             _ => unreachable!(),
         };
         ManuallyDrop::new(slf)
@@ -683,11 +707,12 @@ mod tests {
             path: ptr::null_mut(),
             build_id_len: 0,
             build_id: ptr::null_mut(),
-            reserved: [0u8; 8],
+            is_main_exe: false,
+            reserved: [0u8; 7],
         };
         assert_eq!(
             format!("{elf:?}"),
-            "blaze_user_meta_elf { path: 0x0, build_id_len: 0, build_id: 0x0, reserved: [0, 0, 0, 0, 0, 0, 0, 0] }",
+            "blaze_user_meta_elf { path: 0x0, build_id_len: 0, build_id: 0x0, is_main_exe: false, reserved: [0, 0, 0, 0, 0, 0, 0] }",
         );
 
         let unknown = blaze_user_meta_unknown {
@@ -767,6 +792,8 @@ mod tests {
         let elf = Elf {
             path: PathBuf::from("/tmp/file.so"),
             build_id: Some(Cow::Borrowed(&[0x01, 0x02, 0x03, 0x04])),
+            soname: None,
+            is_main_exe: false,
             _non_exhaustive: (),
         };
 
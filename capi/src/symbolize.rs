@@ -80,7 +80,9 @@ impl From<blaze_symbolize_src_elf> for Elf {
         } = elf;
         Self {
             path: unsafe { from_cstr(path) },
+            fd: None,
             debug_syms,
+            base_addr: None,
             _non_exhaustive: (),
         }
     }
@@ -146,6 +148,7 @@ impl From<blaze_symbolize_src_kernel> for Kernel {
             kallsyms: (!kallsyms.is_null()).then(|| unsafe { from_cstr(kallsyms) }),
             kernel_image: (!kernel_image.is_null()).then(|| unsafe { from_cstr(kernel_image) }),
             debug_syms,
+            kaslr_offset: None,
             _non_exhaustive: (),
         }
     }
@@ -970,6 +973,7 @@ mod tests {
 
     use blazesym::inspect;
     use blazesym::normalize;
+    use blazesym::symbolize::Provenance;
     use blazesym::symbolize::Reason;
     use blazesym::Pid;
 
@@ -1194,6 +1198,7 @@ mod tests {
                 file: OsStr::new("a-file").into(),
                 line: Some(42),
                 column: Some(43),
+                comp_dir: None,
                 _non_exhaustive: (),
             }),
             inlined: vec![InlinedFn {
@@ -1203,11 +1208,21 @@ mod tests {
                     file: OsStr::new("another-file").into(),
                     line: Some(42),
                     column: Some(43),
+                    comp_dir: None,
                     _non_exhaustive: (),
                 }),
+                addr: None,
+                offset: None,
+                origin_addr: None,
                 _non_exhaustive: (),
             }]
             .into_boxed_slice(),
+            provenance: Provenance::Symbol,
+            is_synthetic: false,
+            raw_elf_sym: None,
+            compilation_unit: None,
+            section: None,
+            linkage_name: None,
             _non_exhaustive: (),
         })];
         let result = convert_symbolizedresults_to_c(results);
@@ -1226,9 +1241,18 @@ mod tests {
                 inlined: vec![InlinedFn {
                     name: "inlined_fn".into(),
                     code_info: None,
+                    addr: None,
+                    offset: None,
+                    origin_addr: None,
                     _non_exhaustive: (),
                 }]
                 .into_boxed_slice(),
+                provenance: Provenance::Symbol,
+                is_synthetic: false,
+                raw_elf_sym: None,
+                compilation_unit: None,
+                section: None,
+                linkage_name: None,
                 _non_exhaustive: (),
             }),
             Symbolized::Unknown(Reason::InvalidFileOffset),
@@ -1,3 +1,4 @@
+use std::io::BufRead;
 use std::path::PathBuf;
 
 use anyhow::Context as _;
@@ -6,10 +7,13 @@ use anyhow::Result;
 use blazesym::Addr;
 use blazesym::Pid;
 
+use clap::error::ErrorKind;
 use clap::ArgAction;
 use clap::Args as Arguments;
+use clap::CommandFactory as _;
 use clap::Parser;
 use clap::Subcommand;
+use clap::ValueEnum;
 
 
 /// Parse a PID from a string.
@@ -24,12 +28,90 @@ fn parse_pid(s: &str) -> Result<Pid> {
     Ok(Pid::from(pid))
 }
 
+/// The radix in which to interpret addresses lacking a `0x` prefix.
+#[derive(Clone, Copy, Debug, Default, PartialEq, ValueEnum)]
+pub enum Radix {
+    /// Interpret addresses containing hexadecimal letters (`a`-`f`) as
+    /// hexadecimal numbers and all others as decimal numbers.
+    #[value(name = "auto")]
+    Auto,
+    /// Interpret addresses as hexadecimal numbers.
+    #[default]
+    #[value(name = "16")]
+    Hex,
+    /// Interpret addresses as decimal numbers.
+    #[value(name = "10")]
+    Dec,
+}
+
+
+/// An address as provided by the user.
+///
+/// The address is only interpreted once the radix to use is known.
+#[derive(Clone, Debug)]
+pub struct AddrArg(String);
+
+impl AddrArg {
+    /// Interpret the address using the provided radix.
+    ///
+    /// Addresses with a `0x` prefix are always interpreted as
+    /// hexadecimal numbers.
+    pub fn to_addr(&self, radix: Radix) -> Result<Addr> {
+        let s = self.0.as_str();
+        let result = if let Some(hex) = s.strip_prefix("0x") {
+            Addr::from_str_radix(hex, 16)
+        } else {
+            match radix {
+                Radix::Auto
+                    if s.bytes()
+                        .any(|b| b.is_ascii_hexdigit() && !b.is_ascii_digit()) =>
+                {
+                    Addr::from_str_radix(s, 16)
+                }
+                Radix::Auto => s.parse::<Addr>(),
+                Radix::Hex => Addr::from_str_radix(s, 16),
+                Radix::Dec => s.parse::<Addr>(),
+            }
+        };
+        result.with_context(|| format!("failed to parse address `{s}`"))
+    }
+}
+
 /// Parse an address from a string.
-fn parse_addr(s: &str) -> Result<Addr> {
-    // In our world addresses are always represented in hex, with or without 0x
-    // prefix.
-    Addr::from_str_radix(s.trim_start_matches("0x"), 16)
-        .with_context(|| format!("failed to parse address: {s}"))
+fn parse_addr(s: &str) -> Result<AddrArg> {
+    // Make sure that the token could be an address in any of the
+    // supported radixes (decimal digits form a subset of hexadecimal
+    // ones), so that obviously invalid input is reported early on.
+    let arg = AddrArg(s.to_string());
+    let _addr = arg.to_addr(Radix::Hex)?;
+    Ok(arg)
+}
+
+/// Parse a source path mapping of the form `<from>=<to>`.
+fn parse_src_map(s: &str) -> Result<(PathBuf, PathBuf)> {
+    let (from, to) = s
+        .split_once('=')
+        .with_context(|| format!("invalid source path mapping `{s}`: expected <from>=<to>"))?;
+    Ok((PathBuf::from(from), PathBuf::from(to)))
+}
+
+/// Read addresses, one per line, from the provided reader.
+///
+/// Empty lines are ignored.
+pub fn read_addrs<R>(reader: R, radix: Radix) -> Result<Vec<Addr>>
+where
+    R: BufRead,
+{
+    reader
+        .lines()
+        .filter_map(|line| match line {
+            Ok(line) => {
+                let line = line.trim();
+                (!line.is_empty()).then(|| AddrArg(line.to_string()).to_addr(radix))
+            }
+            Err(err) => Some(Err(err).context("failed to read line")),
+        })
+        .collect()
 }
 
 
@@ -42,6 +124,43 @@ pub struct Args {
     /// Increase verbosity (can be supplied multiple times).
     #[arg(short = 'v', long = "verbose", global = true, action = ArgAction::Count)]
     pub verbosity: u8,
+    /// The radix in which to interpret addresses without `0x` prefix.
+    #[arg(long, global = true, value_enum, default_value = "16")]
+    pub radix: Radix,
+}
+
+impl Args {
+    /// Parse the program's arguments, reporting addresses that are
+    /// invalid in the selected radix as errors.
+    pub fn parse_and_validate() -> Self {
+        let args = Self::parse();
+        match args.validate_addrs() {
+            Ok(()) => args,
+            Err(err) => err.exit(),
+        }
+    }
+
+    /// Check that all addresses provided on the command line can be
+    /// interpreted in the selected radix.
+    fn validate_addrs(&self) -> Result<(), clap::Error> {
+        for addr in self.command.addrs() {
+            let _addr = addr.to_addr(self.radix).map_err(|err| {
+                Self::command().error(
+                    ErrorKind::ValueValidation,
+                    format!(
+                        "invalid address `{}` for radix `{}`: {}",
+                        addr.0,
+                        self.radix
+                            .to_possible_value()
+                            .map(|value| value.get_name().to_string())
+                            .unwrap_or_default(),
+                        err.root_cause(),
+                    ),
+                )
+            })?;
+        }
+        Ok(())
+    }
 }
 
 
@@ -58,6 +177,25 @@ pub enum Command {
     Symbolize(symbolize::Symbolize),
 }
 
+impl Command {
+    /// Retrieve the addresses provided to the command, if any.
+    fn addrs(&self) -> &[AddrArg] {
+        match self {
+            Self::Inspect(..) => &[],
+            Self::Normalize(normalize) => match normalize {
+                normalize::Normalize::User(normalize::User { addrs, .. }) => addrs,
+                normalize::Normalize::Modules(..) => &[],
+            },
+            Self::Symbolize(symbolize) => match symbolize {
+                symbolize::Symbolize::Breakpad(symbolize::Breakpad { addrs, .. })
+                | symbolize::Symbolize::Elf(symbolize::Elf { addrs, .. })
+                | symbolize::Symbolize::Gsym(symbolize::Gsym { addrs, .. })
+                | symbolize::Symbolize::Process(symbolize::Process { addrs, .. }) => addrs,
+            },
+        }
+    }
+}
+
 
 pub mod inspect {
     use super::*;
@@ -72,6 +210,9 @@ pub mod inspect {
         Lookup(Lookup),
         #[command(subcommand, name = "buildid")]
         BuildId(BuildId),
+        /// Report the kinds of symbols and debug information an ELF
+        /// file contains.
+        Info(Info),
     }
 
     /// A type representing the `inspect lookup` sub-command.
@@ -99,6 +240,13 @@ pub mod inspect {
         Elf { path: PathBuf },
     }
 
+    /// A type representing the `inspect info` sub-command.
+    #[derive(Debug, Arguments)]
+    pub struct Info {
+        /// The path to the ELF file.
+        pub path: PathBuf,
+    }
+
     #[derive(Debug, Arguments)]
     pub struct BreakpadLookup {
         /// The path to the Breakpad (*.sym) file.
@@ -142,6 +290,8 @@ pub mod normalize {
     pub enum Normalize {
         /// Normalize user space addresses.
         User(User),
+        /// List the modules loaded into a process.
+        Modules(Modules),
     }
 
     #[derive(Debug, Arguments)]
@@ -151,12 +301,26 @@ pub mod normalize {
         #[arg(value_parser = parse_pid)]
         pub pid: Pid,
         /// The addresses to normalize.
+        ///
+        /// If no addresses are provided, they are read from stdin, one
+        /// per line.
         #[arg(value_parser = parse_addr)]
-        pub addrs: Vec<Addr>,
+        pub addrs: Vec<AddrArg>,
         /// Disable the reading of build IDs of the corresponding binaries.
         #[clap(long)]
         pub no_build_ids: bool,
     }
+
+    #[derive(Debug, Arguments)]
+    pub struct Modules {
+        /// The PID of the process to list the modules of.
+        #[clap(short, long)]
+        #[arg(value_parser = parse_pid)]
+        pub pid: Pid,
+        /// Disable the reading of build IDs of the modules.
+        #[clap(long)]
+        pub no_build_ids: bool,
+    }
 }
 
 
@@ -164,6 +328,23 @@ pub mod symbolize {
     use super::*;
 
 
+    /// Options for the display of source code.
+    #[derive(Debug, Arguments)]
+    pub struct Src {
+        /// Show the source code line corresponding to each symbol and
+        /// inlined function, if available.
+        #[clap(long = "src")]
+        pub enabled: bool,
+        /// Map source paths starting with a prefix to a different
+        /// directory (`<from>=<to>`), e.g., a local checkout. Can be
+        /// supplied multiple times. Source files are only read from
+        /// directories mapped to. If no mapping is provided, source
+        /// files are read from the locations recorded in the debug
+        /// information.
+        #[clap(long = "src-map", value_parser = parse_src_map)]
+        pub maps: Vec<(PathBuf, PathBuf)>,
+    }
+
     /// A type representing the `symbolize` command.
     #[derive(Debug, Subcommand)]
     pub enum Symbolize {
@@ -181,9 +362,12 @@ pub mod symbolize {
         /// The addresses to symbolize.
         ///
         /// Addresses are assumed to be file offsets as they would be used on
-        /// the original (ELF/DWARF/...) source file.
+        /// the original (ELF/DWARF/...) source file. If no addresses are
+        /// provided, they are read from stdin, one per line.
         #[arg(value_parser = parse_addr)]
-        pub addrs: Vec<Addr>,
+        pub addrs: Vec<AddrArg>,
+        #[command(flatten)]
+        pub src: Src,
     }
 
     #[derive(Debug, Arguments)]
@@ -198,9 +382,12 @@ pub mod symbolize {
         ///
         /// Addresses are assumed to already be normalized to the file
         /// itself (i.e., with relocation and address randomization effects
-        /// removed).
+        /// removed). If no addresses are provided, they are read from
+        /// stdin, one per line.
         #[arg(value_parser = parse_addr)]
-        pub addrs: Vec<Addr>,
+        pub addrs: Vec<AddrArg>,
+        #[command(flatten)]
+        pub src: Src,
     }
 
     #[derive(Debug, Arguments)]
@@ -212,9 +399,12 @@ pub mod symbolize {
         ///
         /// Addresses are assumed to already be normalized to the file
         /// itself (i.e., with relocation and address randomization effects
-        /// removed).
+        /// removed). If no addresses are provided, they are read from
+        /// stdin, one per line.
         #[arg(value_parser = parse_addr)]
-        pub addrs: Vec<Addr>,
+        pub addrs: Vec<AddrArg>,
+        #[command(flatten)]
+        pub src: Src,
     }
 
     #[derive(Debug, Arguments)]
@@ -224,11 +414,16 @@ pub mod symbolize {
         #[arg(value_parser = parse_pid)]
         pub pid: Pid,
         /// The addresses to symbolize.
+        ///
+        /// If no addresses are provided, they are read from stdin, one
+        /// per line.
         #[arg(value_parser = parse_addr)]
-        pub addrs: Vec<Addr>,
+        pub addrs: Vec<AddrArg>,
         /// Disable the use of `/proc/<pid>/map_files/` entries and use
         /// symbolic paths instead.
         #[clap(long)]
         pub no_map_files: bool,
+        #[command(flatten)]
+        pub src: Src,
     }
 }
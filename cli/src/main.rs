@@ -3,6 +3,7 @@
 mod args;
 
 use std::cmp::max;
+use std::io::stdin;
 
 use anyhow::Context;
 use anyhow::Result;
@@ -17,7 +18,6 @@ use blazesym::symbolize::Symbolizer;
 use blazesym::Addr;
 use blazesym::SymType;
 
-use clap::Parser as _;
 
 use tracing::subscriber::set_global_default as set_global_subscriber;
 use tracing_subscriber::filter::LevelFilter;
@@ -60,6 +60,7 @@ fn print_sym_infos(sym_infos: &[inspect::SymInfo]) {
         let type_ = match sym.sym_type {
             SymType::Function => " [FUNC]",
             SymType::Variable => " [VAR]",
+            SymType::Tls => " [TLS]",
             _ => " [UNDEF]",
         };
         println!(
@@ -126,17 +127,46 @@ fn inspect(inspect: args::inspect::Inspect) -> Result<()> {
             }
             Ok(())
         }
+        args::inspect::Inspect::Info(args::inspect::Info { path }) => {
+            let src = inspect::Source::from(inspect::Elf::new(path));
+            let info = inspector.debug_info(&src)?;
+            let yes_no = |present| if present { "yes" } else { "no" };
+            let dwarf = match (info.dwarf_version, info.dwarf_compression) {
+                (Some(version), None) => format!("v{version}"),
+                (Some(version), Some(compression)) => format!("v{version} ({compression:?})"),
+                (None, Some(compression)) => format!("unknown version ({compression:?})"),
+                (None, None) => "no".to_string(),
+            };
+            println!("symtab:         {}", yes_no(info.symtab));
+            println!("dynsym:         {}", yes_no(info.dynsym));
+            println!("dwarf:          {dwarf}");
+            println!("debug link:     {}", yes_no(info.debug_link));
+            println!("minidebuginfo:  {}", yes_no(info.mini_debug_info));
+            println!("gsym:           {}", yes_no(info.gsym));
+            Ok(())
+        }
     }
 }
 
 
-fn normalize(normalize: args::normalize::Normalize) -> Result<()> {
+/// Interpret the provided addresses or, if none were provided, read
+/// them from stdin.
+fn addrs_or_stdin(addrs: Vec<args::AddrArg>, radix: args::Radix) -> Result<Vec<Addr>> {
+    if !addrs.is_empty() {
+        return addrs.iter().map(|addr| addr.to_addr(radix)).collect()
+    }
+    args::read_addrs(stdin().lock(), radix).context("failed to read addresses from stdin")
+}
+
+
+fn normalize(normalize: args::normalize::Normalize, radix: args::Radix) -> Result<()> {
     match normalize {
         args::normalize::Normalize::User(args::normalize::User {
             pid,
             addrs,
             no_build_ids,
         }) => {
+            let addrs = addrs_or_stdin(addrs, radix)?;
             let normalizer = Normalizer::builder()
                 .enable_build_ids(!no_build_ids)
                 .build();
@@ -148,134 +178,170 @@ fn normalize(normalize: args::normalize::Normalize) -> Result<()> {
 
                 let meta = &normalized.meta[*meta_idx];
                 match meta {
-                    normalize::UserMeta::Apk(normalize::Apk { path, .. }) => {
-                        println!("file offset {output:#x} in {}", path.display())
+                    normalize::UserMeta::Apk(..) | normalize::UserMeta::Elf(..) => {
+                        println!("file offset {output:#x} in {meta}")
                     }
-                    normalize::UserMeta::Elf(normalize::Elf { path, build_id, .. }) => {
-                        let build_id = format_build_id(build_id.as_deref());
-                        println!("file offset {output:#x} in {}{build_id}", path.display())
+                    normalize::UserMeta::Vdso(..) | normalize::UserMeta::Vsyscall(..) => {
+                        println!("offset {output:#x} in {meta}")
                     }
-                    normalize::UserMeta::Unknown(normalize::Unknown { .. }) => {
-                        println!("<unknown>")
-                    }
-                    // This is a bug and should be reported as such.
-                    _ => panic!("encountered unsupported user meta data: {meta:?}"),
+                    _ => println!("{meta}"),
                 }
             }
         }
+        args::normalize::Normalize::Modules(args::normalize::Modules { pid, no_build_ids }) => {
+            let normalizer = Normalizer::builder()
+                .enable_build_ids(!no_build_ids)
+                .build();
+            let modules = normalizer
+                .modules(pid)
+                .context("failed to enumerate modules")?;
+            for module in modules {
+                let normalize::Module {
+                    range,
+                    path,
+                    build_id,
+                    is_main_exe,
+                    kind,
+                    ..
+                } = module;
+                let name = match (kind, path) {
+                    (_, Some(path)) => path.display().to_string(),
+                    (normalize::ModuleKind::Vdso, None) => "[vdso]".to_string(),
+                    (_, None) => "<unknown>".to_string(),
+                };
+                let exe = if is_main_exe {
+                    " (main executable)"
+                } else {
+                    ""
+                };
+                let build_id = format_build_id(build_id.as_deref());
+                println!(
+                    "{:#016x}-{:#016x}: {name}{exe}{build_id}",
+                    range.start, range.end
+                );
+            }
+        }
     }
     Ok(())
 }
 
-fn print_frame(
-    name: &str,
-    addr_info: Option<(Addr, Addr, usize)>,
-    code_info: &Option<symbolize::CodeInfo>,
-) {
-    let code_info = code_info.as_ref().map(|code_info| {
-        let path = code_info.to_path();
-        let path = path.display();
-
-        match (code_info.line, code_info.column) {
-            (Some(line), Some(col)) => format!(" {path}:{line}:{col}"),
-            (Some(line), None) => format!(" {path}:{line}"),
-            (None, _) => format!(" {path}"),
-        }
-    });
-
-    if let Some((input_addr, addr, offset)) = addr_info {
-        // If we have various address information bits we have a new symbol.
-        println!(
-            "{input_addr:#0width$x}: {name} @ {addr:#x}+{offset:#x}{code_info}",
-            code_info = code_info.as_deref().unwrap_or(""),
-            width = ADDR_WIDTH
-        )
-    } else {
-        // Otherwise we are dealing with an inlined call.
+/// Print the source code line referenced by `code_info`, if available.
+fn print_src_line(ctx: &symbolize::SourceContext, code_info: Option<&symbolize::CodeInfo>) {
+    let lines = code_info.and_then(|code_info| ctx.code_info_lines(code_info, 0).ok().flatten());
+    if let Some(lines) = lines {
         println!(
-            "{:width$}  {name}{code_info} [inlined]",
-            " ",
-            code_info = code_info
-                .map(|info| format!(" @{info}"))
-                .as_deref()
-                .unwrap_or(""),
+            "{:width$}  {line:>6} | {text}",
+            "",
+            line = lines.line,
+            text = lines.text(),
             width = ADDR_WIDTH
-        )
+        );
     }
 }
 
 /// The handler for the 'symbolize' command.
-fn symbolize(symbolize: args::symbolize::Symbolize) -> Result<()> {
-    let symbolizer = Symbolizer::new();
-    let (src, input, addrs) = match symbolize {
-        args::symbolize::Symbolize::Breakpad(args::symbolize::Breakpad { path, ref addrs }) => {
+fn symbolize(symbolize: args::symbolize::Symbolize, radix: args::Radix) -> Result<()> {
+    let addrs;
+    let (src, input, src_args) = match symbolize {
+        args::symbolize::Symbolize::Breakpad(args::symbolize::Breakpad {
+            path,
+            addrs: addrs_,
+            src: src_args,
+        }) => {
             let src = symbolize::Source::from(symbolize::Breakpad::new(path));
-            let addrs = addrs.as_slice();
-            let input = symbolize::Input::FileOffset(addrs);
-            (src, input, addrs)
+            addrs = addrs_or_stdin(addrs_, radix)?;
+            let input = symbolize::Input::FileOffset(addrs.as_slice());
+            (src, input, src_args)
         }
         args::symbolize::Symbolize::Elf(args::symbolize::Elf {
             path,
             no_debug_syms,
-            ref addrs,
+            addrs: addrs_,
+            src: src_args,
         }) => {
             let mut elf = symbolize::Elf::new(path);
             elf.debug_syms = !no_debug_syms;
             let src = symbolize::Source::from(elf);
-            let addrs = addrs.as_slice();
-            let input = symbolize::Input::VirtOffset(addrs);
-            (src, input, addrs)
+            addrs = addrs_or_stdin(addrs_, radix)?;
+            let input = symbolize::Input::VirtOffset(addrs.as_slice());
+            (src, input, src_args)
         }
-        args::symbolize::Symbolize::Gsym(args::symbolize::Gsym { path, ref addrs }) => {
+        args::symbolize::Symbolize::Gsym(args::symbolize::Gsym {
+            path,
+            addrs: addrs_,
+            src: src_args,
+        }) => {
             let src = symbolize::Source::from(symbolize::GsymFile::new(path));
-            let addrs = addrs.as_slice();
-            let input = symbolize::Input::VirtOffset(addrs);
-            (src, input, addrs)
+            addrs = addrs_or_stdin(addrs_, radix)?;
+            let input = symbolize::Input::VirtOffset(addrs.as_slice());
+            (src, input, src_args)
         }
         args::symbolize::Symbolize::Process(args::symbolize::Process {
             pid,
-            ref addrs,
+            addrs: addrs_,
             no_map_files,
+            src: src_args,
         }) => {
             let mut process = symbolize::Process::new(pid);
             process.map_files = !no_map_files;
             let src = symbolize::Source::from(process);
-            let addrs = addrs.as_slice();
-            let input = symbolize::Input::AbsAddr(addrs);
-            (src, input, addrs)
+            addrs = addrs_or_stdin(addrs_, radix)?;
+            let input = symbolize::Input::AbsAddr(addrs.as_slice());
+            (src, input, src_args)
         }
     };
 
-    let syms = symbolizer
-        .symbolize(&src, input)
-        .context("failed to symbolize addresses")?;
+    let src_ctx = if src_args.enabled {
+        let mut ctx = symbolize::SourceContext::new();
+        if src_args.maps.is_empty() {
+            let () = ctx.set_source_map("/", "/");
+        }
+        for (from, to) in src_args.maps {
+            let () = ctx.set_source_map(from, to);
+        }
+        Some(ctx)
+    } else {
+        None
+    };
 
-    for (input_addr, sym) in addrs.iter().copied().zip(syms) {
-        match sym {
-            symbolize::Symbolized::Sym(symbolize::Sym {
-                name,
-                addr,
-                offset,
-                code_info,
-                inlined,
-                ..
-            }) => {
-                print_frame(&name, Some((input_addr, addr, offset)), &code_info);
-                for frame in inlined.iter() {
-                    print_frame(&frame.name, None, &frame.code_info);
+    let symbolizer = Symbolizer::builder()
+        .enable_line_table_fallback(true)
+        .enable_comp_dirs(src_ctx.is_some())
+        .build();
+    let format = symbolize::SymFormat {
+        // If we show source code we report inlined functions ourselves,
+        // interleaved with the respective source lines.
+        inlined_fns: src_ctx.is_none(),
+        inlined_indent: ADDR_WIDTH + 2,
+        ..Default::default()
+    };
+    let () = symbolizer
+        .symbolize_with(&src, input, |input_addr, sym| match sym {
+            symbolize::Symbolized::Sym(sym) => {
+                println!(
+                    "{input_addr:#0width$x}: {sym}",
+                    sym = sym.display(&format),
+                    width = ADDR_WIDTH
+                );
+                if let Some(ctx) = &src_ctx {
+                    let () = print_src_line(ctx, sym.code_info.as_ref());
+                    for inlined in sym.inlined.iter() {
+                        println!("{:width$}  {inlined}", "", width = ADDR_WIDTH);
+                        let () = print_src_line(ctx, inlined.code_info.as_ref());
+                    }
                 }
             }
             symbolize::Symbolized::Unknown(..) => {
                 println!("{input_addr:#0width$x}: <no-symbol>", width = ADDR_WIDTH)
             }
-        }
-    }
+        })
+        .context("failed to symbolize addresses")?;
     Ok(())
 }
 
 
 fn main() -> Result<()> {
-    let args = args::Args::parse();
+    let args = args::Args::parse_and_validate();
     let level = match args.verbosity {
         0 => LevelFilter::WARN,
         1 => LevelFilter::INFO,
@@ -294,7 +360,7 @@ fn main() -> Result<()> {
 
     match args.command {
         args::Command::Inspect(inspect) => self::inspect(inspect),
-        args::Command::Normalize(normalize) => self::normalize(normalize),
-        args::Command::Symbolize(symbolize) => self::symbolize(symbolize),
+        args::Command::Normalize(normalize) => self::normalize(normalize, args.radix),
+        args::Command::Symbolize(symbolize) => self::symbolize(symbolize, args.radix),
     }
 }
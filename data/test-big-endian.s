@@ -0,0 +1,20 @@
+# A minimal s390x (big-endian) object file for testing purposes.
+#
+# The checked in test-big-endian.o was generated via:
+# $ llvm-mc -triple=s390x-linux-gnu -filetype=obj -g -fdebug-compilation-dir=. \
+#     test-big-endian.s -o test-big-endian.o
+
+	.text
+	.globl	factorial
+	.type	factorial,@function
+factorial:
+	lghi	%r2, 1
+	br	%r14
+	.size	factorial, .-factorial
+
+	.globl	main
+	.type	main,@function
+main:
+	lghi	%r2, 0
+	br	%r14
+	.size	main, .-main
@@ -0,0 +1,21 @@
+/* A C++ program containing functions with and without mangled names,
+ * for testing handling of linkage names. */
+
+namespace test {
+
+__attribute__((noinline)) int
+mangled_function(int x) {
+  return x * 3;
+}
+
+} // namespace test
+
+extern "C" __attribute__((noinline)) int
+unmangled_function(int x) {
+  return x + 1;
+}
+
+int
+main(int argc, char *argv[]) {
+  return test::mangled_function(argc) + unmangled_function(argc);
+}
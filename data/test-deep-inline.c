@@ -0,0 +1,414 @@
+/* A program containing a deeply nested chain of inlined functions, for
+ * testing handling of pathological inline stacks. */
+
+#define INLINE __attribute__((always_inline)) static inline
+
+volatile int counter;
+
+INLINE void inline0(void) {
+  counter++;
+}
+
+INLINE void inline1(void) {
+  inline0();
+  counter++;
+}
+
+INLINE void inline2(void) {
+  inline1();
+  counter++;
+}
+
+INLINE void inline3(void) {
+  inline2();
+  counter++;
+}
+
+INLINE void inline4(void) {
+  inline3();
+  counter++;
+}
+
+INLINE void inline5(void) {
+  inline4();
+  counter++;
+}
+
+INLINE void inline6(void) {
+  inline5();
+  counter++;
+}
+
+INLINE void inline7(void) {
+  inline6();
+  counter++;
+}
+
+INLINE void inline8(void) {
+  inline7();
+  counter++;
+}
+
+INLINE void inline9(void) {
+  inline8();
+  counter++;
+}
+
+INLINE void inline10(void) {
+  inline9();
+  counter++;
+}
+
+INLINE void inline11(void) {
+  inline10();
+  counter++;
+}
+
+INLINE void inline12(void) {
+  inline11();
+  counter++;
+}
+
+INLINE void inline13(void) {
+  inline12();
+  counter++;
+}
+
+INLINE void inline14(void) {
+  inline13();
+  counter++;
+}
+
+INLINE void inline15(void) {
+  inline14();
+  counter++;
+}
+
+INLINE void inline16(void) {
+  inline15();
+  counter++;
+}
+
+INLINE void inline17(void) {
+  inline16();
+  counter++;
+}
+
+INLINE void inline18(void) {
+  inline17();
+  counter++;
+}
+
+INLINE void inline19(void) {
+  inline18();
+  counter++;
+}
+
+INLINE void inline20(void) {
+  inline19();
+  counter++;
+}
+
+INLINE void inline21(void) {
+  inline20();
+  counter++;
+}
+
+INLINE void inline22(void) {
+  inline21();
+  counter++;
+}
+
+INLINE void inline23(void) {
+  inline22();
+  counter++;
+}
+
+INLINE void inline24(void) {
+  inline23();
+  counter++;
+}
+
+INLINE void inline25(void) {
+  inline24();
+  counter++;
+}
+
+INLINE void inline26(void) {
+  inline25();
+  counter++;
+}
+
+INLINE void inline27(void) {
+  inline26();
+  counter++;
+}
+
+INLINE void inline28(void) {
+  inline27();
+  counter++;
+}
+
+INLINE void inline29(void) {
+  inline28();
+  counter++;
+}
+
+INLINE void inline30(void) {
+  inline29();
+  counter++;
+}
+
+INLINE void inline31(void) {
+  inline30();
+  counter++;
+}
+
+INLINE void inline32(void) {
+  inline31();
+  counter++;
+}
+
+INLINE void inline33(void) {
+  inline32();
+  counter++;
+}
+
+INLINE void inline34(void) {
+  inline33();
+  counter++;
+}
+
+INLINE void inline35(void) {
+  inline34();
+  counter++;
+}
+
+INLINE void inline36(void) {
+  inline35();
+  counter++;
+}
+
+INLINE void inline37(void) {
+  inline36();
+  counter++;
+}
+
+INLINE void inline38(void) {
+  inline37();
+  counter++;
+}
+
+INLINE void inline39(void) {
+  inline38();
+  counter++;
+}
+
+INLINE void inline40(void) {
+  inline39();
+  counter++;
+}
+
+INLINE void inline41(void) {
+  inline40();
+  counter++;
+}
+
+INLINE void inline42(void) {
+  inline41();
+  counter++;
+}
+
+INLINE void inline43(void) {
+  inline42();
+  counter++;
+}
+
+INLINE void inline44(void) {
+  inline43();
+  counter++;
+}
+
+INLINE void inline45(void) {
+  inline44();
+  counter++;
+}
+
+INLINE void inline46(void) {
+  inline45();
+  counter++;
+}
+
+INLINE void inline47(void) {
+  inline46();
+  counter++;
+}
+
+INLINE void inline48(void) {
+  inline47();
+  counter++;
+}
+
+INLINE void inline49(void) {
+  inline48();
+  counter++;
+}
+
+INLINE void inline50(void) {
+  inline49();
+  counter++;
+}
+
+INLINE void inline51(void) {
+  inline50();
+  counter++;
+}
+
+INLINE void inline52(void) {
+  inline51();
+  counter++;
+}
+
+INLINE void inline53(void) {
+  inline52();
+  counter++;
+}
+
+INLINE void inline54(void) {
+  inline53();
+  counter++;
+}
+
+INLINE void inline55(void) {
+  inline54();
+  counter++;
+}
+
+INLINE void inline56(void) {
+  inline55();
+  counter++;
+}
+
+INLINE void inline57(void) {
+  inline56();
+  counter++;
+}
+
+INLINE void inline58(void) {
+  inline57();
+  counter++;
+}
+
+INLINE void inline59(void) {
+  inline58();
+  counter++;
+}
+
+INLINE void inline60(void) {
+  inline59();
+  counter++;
+}
+
+INLINE void inline61(void) {
+  inline60();
+  counter++;
+}
+
+INLINE void inline62(void) {
+  inline61();
+  counter++;
+}
+
+INLINE void inline63(void) {
+  inline62();
+  counter++;
+}
+
+INLINE void inline64(void) {
+  inline63();
+  counter++;
+}
+
+INLINE void inline65(void) {
+  inline64();
+  counter++;
+}
+
+INLINE void inline66(void) {
+  inline65();
+  counter++;
+}
+
+INLINE void inline67(void) {
+  inline66();
+  counter++;
+}
+
+INLINE void inline68(void) {
+  inline67();
+  counter++;
+}
+
+INLINE void inline69(void) {
+  inline68();
+  counter++;
+}
+
+INLINE void inline70(void) {
+  inline69();
+  counter++;
+}
+
+INLINE void inline71(void) {
+  inline70();
+  counter++;
+}
+
+INLINE void inline72(void) {
+  inline71();
+  counter++;
+}
+
+INLINE void inline73(void) {
+  inline72();
+  counter++;
+}
+
+INLINE void inline74(void) {
+  inline73();
+  counter++;
+}
+
+INLINE void inline75(void) {
+  inline74();
+  counter++;
+}
+
+INLINE void inline76(void) {
+  inline75();
+  counter++;
+}
+
+INLINE void inline77(void) {
+  inline76();
+  counter++;
+}
+
+INLINE void inline78(void) {
+  inline77();
+  counter++;
+}
+
+INLINE void inline79(void) {
+  inline78();
+  counter++;
+}
+
+__attribute__((noinline)) void deep_inline(void) {
+  inline79();
+}
+
+int main(void) {
+  deep_inline();
+  return 0;
+}
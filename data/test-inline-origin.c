@@ -0,0 +1,24 @@
+/* A program containing a function that is both inlined into its caller
+ * and emitted out-of-line, for testing the reporting of the addresses
+ * of inlined functions' definitions. */
+
+__attribute__((noinline)) int
+leaf(int x) {
+  volatile int y = x;
+  return y * 3;
+}
+
+int
+inlined_fn(int x) {
+  return leaf(x) + leaf(x + 1);
+}
+
+__attribute__((noinline)) int
+caller(int x) {
+  return inlined_fn(x) * 2;
+}
+
+int
+main(int argc, char *argv[]) {
+  return caller(argc) + inlined_fn(argc);
+}
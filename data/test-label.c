@@ -0,0 +1,17 @@
+/* A program containing a function with a label, for testing handling
+ * of DW_TAG_label entries. */
+
+volatile int counter;
+
+__attribute__((noinline)) int with_label(int x) {
+  if (x > 3)
+    goto out;
+
+  counter++;
+out:
+  return counter;
+}
+
+int main(void) {
+  return with_label(4);
+}
@@ -0,0 +1,25 @@
+/* A program containing a function split into a hot and a cold part,
+ * for testing handling of non-contiguous DWARF address ranges. */
+
+#include <stdlib.h>
+
+__attribute__((noinline, cold)) static void
+report(int x) {
+  volatile int y = x;
+  (void)y;
+  abort();
+}
+
+__attribute__((noinline)) int
+split_fn(int x) {
+  if (__builtin_expect(x == 42, 0)) {
+    report(x);
+    x += 3;
+  }
+  return x * 2;
+}
+
+int
+main(int argc, char *argv[]) {
+  return split_fn(argc);
+}
@@ -0,0 +1,12 @@
+/* A shared object built as 32 bit ELF. It does not depend on libc, so
+ * that it can be built without 32 bit system libraries being present. */
+
+int the_answer(void) {
+  return 42;
+}
+
+int __attribute__((weak)) the_weak_answer(void) {
+  return 44;
+}
+
+int a_variable = 1337;
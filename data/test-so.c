@@ -10,6 +10,10 @@ int the_ignored_answer(void) {
   return 43;
 }
 
+int __attribute__((weak)) the_weak_answer(void) {
+  return 44;
+}
+
 int await_input(void) {
   fprintf(stdout, "%p\n", &await_input);
   fflush(stdout);
@@ -3,6 +3,7 @@
 
 int __attribute__((visibility("default"))) the_answer(void);
 int __attribute__((visibility("default"))) the_ignored_answer(void);
+int __attribute__((visibility("default"))) the_weak_answer(void);
 int __attribute__((visibility("default"))) await_input(void);
 
 #endif
@@ -0,0 +1,8 @@
+/* A shared object defining a couple of thread-local variables. */
+
+__thread int tls_init = 42;
+__thread long tls_zero;
+
+int *tls_init_ptr(void) {
+  return &tls_init;
+}
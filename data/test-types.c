@@ -0,0 +1,24 @@
+/* A program defining a couple of types for testing type information
+ * lookup. */
+
+#include <stdint.h>
+
+enum color {
+  RED,
+  GREEN = 5,
+  BLUE,
+};
+
+struct node {
+  struct node *next;
+  uint32_t values[4];
+  const enum color color;
+  char names[2][8];
+};
+
+struct node the_node;
+
+int
+main() {
+  return the_node.color;
+}
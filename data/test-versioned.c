@@ -0,0 +1,17 @@
+/* A shared object defining a symbol in multiple versions. Versions
+ * are assigned by the accompanying test-versioned.map version script. */
+
+int versioned_v1(void) {
+  return 1;
+}
+
+int versioned_v2(void) {
+  return 2;
+}
+
+int plain(void) {
+  return 3;
+}
+
+__asm__(".symver versioned_v1, versioned@VERS_1.0");
+__asm__(".symver versioned_v2, versioned@@VERS_2.0");
@@ -0,0 +1,38 @@
+/* A 32 bit executable that does not depend on libc. It reports the
+ * address of `the_answer` on stdout and then waits for input before
+ * exiting. */
+
+#define SYS_exit 1
+#define SYS_read 3
+#define SYS_write 4
+
+static long syscall3(long nr, long a, long b, long c) {
+  long ret;
+  __asm__ volatile("int $0x80"
+                   : "=a"(ret)
+                   : "a"(nr), "b"(a), "c"(b), "d"(c)
+                   : "memory");
+  return ret;
+}
+
+int __attribute__((noinline)) the_answer(void) {
+  return 42;
+}
+
+void _start(void) {
+  static char const digits[] = "0123456789abcdef";
+  unsigned long addr = (unsigned long)&the_answer;
+  char buf[2 + 2 * sizeof(addr) + 1];
+  unsigned int i;
+
+  buf[0] = '0';
+  buf[1] = 'x';
+  for (i = 0; i < 2 * sizeof(addr); i++) {
+    buf[2 + i] = digits[(addr >> (4 * (2 * sizeof(addr) - 1 - i))) & 0xf];
+  }
+  buf[sizeof(buf) - 1] = '\n';
+
+  syscall3(SYS_write, 1, (long)buf, sizeof(buf));
+  syscall3(SYS_read, 0, (long)buf, 1);
+  syscall3(SYS_exit, the_answer() == 42 ? 0 : 1, 0, 0);
+}
@@ -0,0 +1,17 @@
+// The sample program is used to generate test-go.bin.
+
+package main
+
+import "fmt"
+
+//go:noinline
+func fibonacci(n int) int {
+	if n < 2 {
+		return n
+	}
+	return fibonacci(n-1) + fibonacci(n-2)
+}
+
+func main() {
+	fmt.Println(fibonacci(10))
+}
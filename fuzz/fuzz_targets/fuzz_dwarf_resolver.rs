@@ -0,0 +1,56 @@
+#![no_main]
+
+use std::fs::File;
+use std::io::Write as _;
+use std::os::unix::io::FromRawFd as _;
+use std::panic::catch_unwind;
+use std::path::Path;
+use std::process::abort;
+use std::rc::Rc;
+
+use blazesym::dwarf::DwarfResolver;
+use blazesym::elf::ElfParser;
+use blazesym::symbolize::FindSymOpts;
+use blazesym::symbolize::Symbolize as _;
+
+use libfuzzer_sys::fuzz_target;
+
+
+/// Create an in-memory file containing `data`.
+fn memfd(data: &[u8]) -> File {
+    let fd = unsafe { libc::memfd_create(c"blazesym-fuzz".as_ptr(), libc::MFD_CLOEXEC) };
+    assert!(fd >= 0, "failed to create memfd");
+    let mut file = unsafe { File::from_raw_fd(fd) };
+    let () = file.write_all(data).unwrap();
+    file
+}
+
+
+fuzz_target!(|data: &[u8]| {
+    let file = memfd(data);
+
+    let result = catch_unwind(|| {
+        let parser = match ElfParser::open_file(&file, Path::new("<fuzz>")) {
+            Ok(parser) => parser,
+            Err(_err) => return,
+        };
+        let resolver = match DwarfResolver::from_parser(Rc::new(parser)) {
+            Ok(resolver) => resolver,
+            Err(_err) => return,
+        };
+
+        let opts = FindSymOpts::CODE_INFO_AND_INLINED
+            .with_inlined_fn_addrs(true)
+            .with_inlined_fn_origin_addrs(true)
+            .with_line_table_fallback(true);
+        for addr in [0, 0x1000, 0x2000100, u64::MAX] {
+            let _result = resolver.find_sym(addr, &opts);
+        }
+        let _result = resolver.coverage();
+    });
+
+    // Treat any panic as a finding.
+    if result.is_err() {
+        abort()
+    }
+});
@@ -0,0 +1,55 @@
+#![no_main]
+
+use std::fs::File;
+use std::io::Write as _;
+use std::os::unix::io::AsRawFd as _;
+use std::os::unix::io::FromRawFd as _;
+use std::panic::catch_unwind;
+use std::path::PathBuf;
+use std::process::abort;
+
+use blazesym::elf::ElfParser;
+use blazesym::inspect;
+use blazesym::inspect::Inspector;
+use blazesym::symbolize::FindSymOpts;
+
+use libfuzzer_sys::fuzz_target;
+
+
+/// Create an in-memory file containing `data`.
+fn memfd(data: &[u8]) -> File {
+    let fd = unsafe { libc::memfd_create(c"blazesym-fuzz".as_ptr(), libc::MFD_CLOEXEC) };
+    assert!(fd >= 0, "failed to create memfd");
+    let mut file = unsafe { File::from_raw_fd(fd) };
+    let () = file.write_all(data).unwrap();
+    file
+}
+
+
+fuzz_target!(|data: &[u8]| {
+    let file = memfd(data);
+    let path = PathBuf::from(format!("/proc/self/fd/{}", file.as_raw_fd()));
+
+    let result = catch_unwind(|| {
+        if let Ok(parser) = ElfParser::open_file(&file, &path) {
+            for addr in [0, 0x1000, 0x2000100, u64::MAX] {
+                let _result = parser.find_sym(addr, &FindSymOpts::BASIC);
+                let _result = parser.segment_for_addr(addr);
+            }
+        }
+
+        // Symbol lookup by name and iteration is only exposed via the
+        // `Inspector`.
+        let inspector = Inspector::new();
+        let mut elf = inspect::Elf::new(&path);
+        elf.debug_syms = false;
+        let src = inspect::Source::Elf(elf);
+        let _result = inspector.lookup(&src, &["main", "_start", ""]);
+        let _result = inspector.for_each(&src, |_sym| ());
+    });
+
+    // Treat any panic as a finding.
+    if result.is_err() {
+        abort()
+    }
+});
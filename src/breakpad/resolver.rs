@@ -15,6 +15,7 @@ use crate::mmap::Mmap;
 use crate::symbolize::CodeInfo;
 use crate::symbolize::FindSymOpts;
 use crate::symbolize::InlinedFn;
+use crate::symbolize::Provenance;
 use crate::symbolize::Reason;
 use crate::symbolize::ResolvedSym;
 use crate::symbolize::SrcLang;
@@ -38,6 +39,9 @@ impl<'func> From<&'func Function> for SymInfo<'func> {
             addr: func.addr,
             size: func.size as _,
             sym_type: SymType::Function,
+            binding: None,
+            version: None,
+            is_ifunc: false,
             file_offset: None,
             obj_file_name: None,
         }
@@ -133,6 +137,7 @@ impl BreakpadResolver {
             file: Cow::Borrowed(file),
             line: Some(source_line.line),
             column: None,
+            comp_dir: None,
             _non_exhaustive: (),
         };
 
@@ -147,6 +152,7 @@ impl BreakpadResolver {
                     file: Cow::Borrowed(file),
                     line: Some(inlinee.call_line),
                     column: None,
+                    comp_dir: None,
                     _non_exhaustive: (),
                 });
 
@@ -159,6 +165,9 @@ impl BreakpadResolver {
                 let inlined_fn = InlinedFn {
                     name: Cow::Borrowed(name),
                     code_info,
+                    addr: None,
+                    offset: None,
+                    origin_addr: None,
                     _non_exhaustive: (),
                 };
                 let () = inlined.push(inlined_fn);
@@ -196,6 +205,13 @@ impl Symbolize for BreakpadResolver {
             lang: SrcLang::Unknown,
             code_info: None,
             inlined: Box::new([]),
+            provenance: Provenance::Symbol,
+            is_synthetic: false,
+            raw_elf_sym: None,
+            compilation_unit: None,
+            version: None,
+            section: None,
+            linkage_name: None,
         };
         let () = self.fill_code_info(&mut sym, addr, opts, func)?;
 
@@ -205,12 +221,17 @@ impl Symbolize for BreakpadResolver {
 
 impl Inspect for BreakpadResolver {
     fn find_addr<'slf>(&'slf self, name: &str, opts: &FindAddrOpts) -> Result<Vec<SymInfo<'slf>>> {
-        if let SymType::Variable = opts.sym_type {
+        if let SymType::Variable | SymType::Tls = opts.sym_type {
             return Err(Error::with_unsupported(
                 "breakpad logic does not currently support variable lookup",
             ))
         }
 
+        // Breakpad files don't carry symbol binding information.
+        if opts.binding.is_some() {
+            return Ok(Vec::new())
+        }
+
         let syms = self
             .symbol_file
             .find_addr(name)
@@ -222,12 +243,16 @@ impl Inspect for BreakpadResolver {
 
     /// Perform an operation on each symbol.
     fn for_each(&self, opts: &FindAddrOpts, f: &mut dyn FnMut(&SymInfo<'_>)) -> Result<()> {
-        if let SymType::Variable = opts.sym_type {
+        if let SymType::Variable | SymType::Tls = opts.sym_type {
             return Err(Error::with_unsupported(
                 "breakpad logic does not currently support variable iteration",
             ))
         }
 
+        if opts.binding.is_some() {
+            return Ok(())
+        }
+
         for func in &self.symbol_file.functions {
             let sym = SymInfo::from(func);
             let () = f(&sym);
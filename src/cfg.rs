@@ -18,6 +18,16 @@ macro_rules! cfg_breakpad {
     }
 }
 
+macro_rules! cfg_dwarf {
+    ($($item:item)*) => {
+        $(
+            #[cfg(feature = "dwarf")]
+            #[cfg_attr(docsrs, doc(cfg(feature = "dwarf")))]
+            $item
+        )*
+    }
+}
+
 macro_rules! cfg_gsym {
     ($($item:item)*) => {
         $(
@@ -27,3 +37,33 @@ macro_rules! cfg_gsym {
         )*
     }
 }
+
+macro_rules! cfg_macho {
+    ($($item:item)*) => {
+        $(
+            #[cfg(feature = "macho")]
+            #[cfg_attr(docsrs, doc(cfg(feature = "macho")))]
+            $item
+        )*
+    }
+}
+
+macro_rules! cfg_pe {
+    ($($item:item)*) => {
+        $(
+            #[cfg(feature = "pe")]
+            #[cfg_attr(docsrs, doc(cfg(feature = "pe")))]
+            $item
+        )*
+    }
+}
+
+macro_rules! cfg_type_info {
+    ($($item:item)*) => {
+        $(
+            #[cfg(feature = "type-info")]
+            #[cfg_attr(docsrs, doc(cfg(feature = "type-info")))]
+            $item
+        )*
+    }
+}
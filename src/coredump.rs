@@ -0,0 +1,505 @@
+use std::ffi::OsStr;
+use std::os::unix::ffi::OsStrExt as _;
+use std::path::PathBuf;
+
+use crate::elf::types::Elf64_Ehdr;
+use crate::elf::types::Elf64_Nhdr;
+use crate::elf::types::Elf64_Phdr;
+use crate::elf::types::ET_CORE;
+use crate::elf::types::NT_FILE;
+use crate::elf::types::NT_GNU_BUILD_ID;
+use crate::elf::types::PF_R;
+use crate::elf::types::PF_W;
+use crate::elf::types::PF_X;
+use crate::elf::types::PT_LOAD;
+use crate::elf::types::PT_NOTE;
+use crate::elf::ElfParser;
+use crate::maps::EntryPath;
+use crate::maps::MapsEntry;
+use crate::maps::PathName;
+use crate::maps::Perms;
+use crate::util::ReadRaw as _;
+use crate::Error;
+use crate::IntoError as _;
+use crate::Result;
+
+
+/// A file backed memory mapping, as described by a core dump.
+#[derive(Debug)]
+pub(crate) struct CoreMapping {
+    /// The mapping, in the form of a proc maps entry.
+    ///
+    /// Both paths of the entry's [`EntryPath`] refer to the path as
+    /// recorded in the core dump.
+    pub entry: MapsEntry,
+    /// The build ID of the mapped file, if it could be recovered from
+    /// the memory image captured in the core dump.
+    pub build_id: Option<Vec<u8>>,
+}
+
+
+/// Find the descriptor of the first note with the given name and type
+/// in `data`.
+fn find_note<'data>(mut data: &'data [u8], name: &[u8], n_type: u32) -> Option<&'data [u8]> {
+    while !data.is_empty() {
+        let nhdr = data.read_pod_ref::<Elf64_Nhdr>()?;
+        let n_name = data.read_slice(nhdr.n_namesz as _)?;
+        let () = data.align(4)?;
+        let desc = data.read_slice(nhdr.n_descsz as _)?;
+        let () = data.align(4)?;
+
+        // The name is NUL terminated.
+        let n_name = n_name.strip_suffix(b"\0").unwrap_or(n_name);
+        if nhdr.n_type == n_type && n_name == name {
+            return Some(desc)
+        }
+    }
+    None
+}
+
+/// Find the `PT_LOAD` segment containing captured memory for `addr`.
+fn find_load_segment(phdrs: &[Elf64_Phdr], addr: u64) -> Option<&Elf64_Phdr> {
+    phdrs.iter().find(|phdr| {
+        phdr.p_type == PT_LOAD && (phdr.p_vaddr..phdr.p_vaddr + phdr.p_filesz).contains(&addr)
+    })
+}
+
+/// Attempt to read the build ID of the ELF file mapped at `addr`, using
+/// the memory image captured in the core dump.
+///
+/// Whether that is possible depends on what memory got dumped.
+/// Typically, the kernel includes the first page of each mapped ELF
+/// file, which usually contains the build ID note.
+fn read_build_id(parser: &ElfParser, phdrs: &[Elf64_Phdr], addr: u64) -> Option<Vec<u8>> {
+    let phdr = find_load_segment(phdrs, addr)?;
+    let data = parser.segment_data(phdr).ok()?;
+    let image = data.get((addr - phdr.p_vaddr) as usize..)?;
+
+    let mut ehdr_data = image;
+    let ehdr = ehdr_data.read_pod_ref::<Elf64_Ehdr>()?;
+    if ehdr.e_ident[0..4] != [0x7f, b'E', b'L', b'F'] {
+        return None
+    }
+
+    let elf_phdrs = image
+        .get(ehdr.e_phoff as usize..)?
+        .read_pod_slice_ref::<Elf64_Phdr>(ehdr.e_phnum.into())?;
+    elf_phdrs
+        .iter()
+        .filter(|phdr| phdr.p_type == PT_NOTE)
+        .find_map(|phdr| {
+            let notes = image
+                .get(phdr.p_offset as usize..)?
+                .read_slice(phdr.p_filesz as usize)?;
+            find_note(notes, b"GNU", NT_GNU_BUILD_ID)
+        })
+        .map(<[u8]>::to_vec)
+}
+
+/// Parse an `NT_FILE` note descriptor.
+fn parse_file_note(
+    parser: &ElfParser,
+    phdrs: &[Elf64_Phdr],
+    mut desc: &[u8],
+) -> Option<Vec<CoreMapping>> {
+    let count = desc.read_u64()?;
+    let page_size = desc.read_u64()?;
+    let ranges = (0..count)
+        .map(|_| Some((desc.read_u64()?, desc.read_u64()?, desc.read_u64()?)))
+        .collect::<Option<Vec<_>>>()?;
+
+    ranges
+        .into_iter()
+        .map(|(start, end, page_offset)| {
+            let path = desc.read_cstr()?;
+            let path = PathBuf::from(OsStr::from_bytes(path.to_bytes()));
+            let offset = page_offset.checked_mul(page_size)?;
+            let perms = find_load_segment(phdrs, start)
+                .map(|phdr| {
+                    let mut perms = Perms::empty();
+                    if phdr.p_flags & PF_R != 0 {
+                        perms |= Perms::READ;
+                    }
+                    if phdr.p_flags & PF_W != 0 {
+                        perms |= Perms::WRITE;
+                    }
+                    if phdr.p_flags & PF_X != 0 {
+                        perms |= Perms::EXEC;
+                    }
+                    perms
+                })
+                .unwrap_or(Perms::READ);
+            let build_id = if offset == 0 {
+                read_build_id(parser, phdrs, start)
+            } else {
+                None
+            };
+
+            let entry = MapsEntry {
+                range: start..end,
+                perms,
+                offset,
+                // Core dumps do not record device and inode
+                // information.
+                dev: (0, 0),
+                inode: 0,
+                path_name: Some(PathName::Path(EntryPath {
+                    maps_file: path.clone(),
+                    symbolic_path: path,
+                    deleted: false,
+                    _non_exhaustive: (),
+                })),
+                _non_exhaustive: (),
+            };
+            Some(CoreMapping { entry, build_id })
+        })
+        .collect()
+}
+
+/// Parse the file backed memory mappings described by the core dump
+/// represented by `parser`.
+///
+/// Only 64 bit core dumps of host byte order are supported, because
+/// the contents of notes and of the captured memory image are
+/// interpreted as such.
+///
+/// Mappings are reported sorted by their start address.
+pub(crate) fn parse(parser: &ElfParser) -> Result<Vec<CoreMapping>> {
+    if parser.file_type()? != ET_CORE {
+        return Err(Error::with_invalid_input(format!(
+            "{} is not a core dump",
+            parser.path().display()
+        )))
+    }
+
+    if parser.address_size()? != 8 {
+        return Err(Error::with_unsupported(format!(
+            "core dump {} is not a 64 bit ELF file",
+            parser.path().display()
+        )))
+    }
+
+    if parser.is_big_endian()? != cfg!(target_endian = "big") {
+        return Err(Error::with_unsupported(format!(
+            "core dump {} is not of host byte order",
+            parser.path().display()
+        )))
+    }
+
+    let phdrs = parser.program_headers()?;
+    for phdr in phdrs.iter().filter(|phdr| phdr.p_type == PT_NOTE) {
+        let notes = parser.segment_data(phdr)?;
+        if let Some(desc) = find_note(notes, b"CORE", NT_FILE) {
+            let mut mappings = parse_file_note(parser, phdrs, desc)
+                .ok_or_invalid_data(|| "failed to parse NT_FILE note")?;
+            let () = mappings.sort_by_key(|mapping| mapping.entry.range.start);
+            return Ok(mappings)
+        }
+    }
+
+    Err(Error::with_invalid_data(format!(
+        "core dump {} does not contain an NT_FILE note",
+        parser.path().display()
+    )))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::fs::read as read_file;
+    use std::io::Write as _;
+    use std::path::Path;
+
+    use tempfile::NamedTempFile;
+    use test_log::test;
+
+    use crate::elf::types::ELFCLASS32;
+    use crate::elf::types::ELFCLASS64;
+    use crate::elf::types::ELFDATA2LSB;
+    use crate::elf::types::ELFDATA2MSB;
+    use crate::normalize::buildid::read_elf_build_id;
+    use crate::symbolize::CoreDump;
+    use crate::symbolize::Input;
+    use crate::symbolize::Reason;
+    use crate::symbolize::Source;
+    use crate::symbolize::Symbolized;
+    use crate::symbolize::Symbolizer;
+    use crate::ErrorKind;
+
+
+    const PAGE_SIZE: u64 = 0x1000;
+
+
+    fn align_up(value: u64, align: u64) -> u64 {
+        (value + align - 1) / align * align
+    }
+
+    /// Create a minimal core dump file describing the provided file
+    /// backed mappings (`(start, end, page offset, path)`) and memory
+    /// contents (`(address, data)`).
+    fn make_core(mappings: &[(u64, u64, u64, &Path)], memory: &[(u64, &[u8])]) -> NamedTempFile {
+        fn phdr(data: &mut Vec<u8>, p_type: u32, offset: u64, vaddr: u64, size: u64) {
+            let () = data.extend_from_slice(&p_type.to_ne_bytes());
+            let () = data.extend_from_slice(&PF_R.to_ne_bytes());
+            let () = data.extend_from_slice(&offset.to_ne_bytes());
+            let () = data.extend_from_slice(&vaddr.to_ne_bytes());
+            let () = data.extend_from_slice(&0u64.to_ne_bytes());
+            let () = data.extend_from_slice(&size.to_ne_bytes());
+            let () = data.extend_from_slice(&size.to_ne_bytes());
+            let () = data.extend_from_slice(&PAGE_SIZE.to_ne_bytes());
+        }
+
+        let mut desc = Vec::new();
+        let () = desc.extend_from_slice(&(mappings.len() as u64).to_ne_bytes());
+        let () = desc.extend_from_slice(&PAGE_SIZE.to_ne_bytes());
+        for (start, end, page_offset, _path) in mappings {
+            let () = desc.extend_from_slice(&start.to_ne_bytes());
+            let () = desc.extend_from_slice(&end.to_ne_bytes());
+            let () = desc.extend_from_slice(&page_offset.to_ne_bytes());
+        }
+        for (_start, _end, _page_offset, path) in mappings {
+            let () = desc.extend_from_slice(path.as_os_str().as_bytes());
+            let () = desc.push(b'\0');
+        }
+        let () = desc.resize(align_up(desc.len() as u64, 4) as usize, 0);
+
+        let mut note = Vec::new();
+        let () = note.extend_from_slice(&5u32.to_ne_bytes());
+        let () = note.extend_from_slice(&(desc.len() as u32).to_ne_bytes());
+        let () = note.extend_from_slice(&NT_FILE.to_ne_bytes());
+        let () = note.extend_from_slice(b"CORE\0\0\0\0");
+        let () = note.extend_from_slice(&desc);
+
+        let phnum = 1 + memory.len();
+        let note_offset = 64 + 56 * phnum as u64;
+        let mut data_offset = align_up(note_offset + note.len() as u64, PAGE_SIZE);
+
+        let mut core = Vec::new();
+        let () = core.extend_from_slice(&[0x7f, b'E', b'L', b'F', 2, 1, 1]);
+        let () = core.resize(16, 0);
+        let () = core.extend_from_slice(&ET_CORE.to_ne_bytes());
+        let () = core.extend_from_slice(&62u16.to_ne_bytes());
+        let () = core.extend_from_slice(&1u32.to_ne_bytes());
+        let () = core.extend_from_slice(&0u64.to_ne_bytes());
+        let () = core.extend_from_slice(&64u64.to_ne_bytes());
+        let () = core.extend_from_slice(&0u64.to_ne_bytes());
+        let () = core.extend_from_slice(&0u32.to_ne_bytes());
+        let () = core.extend_from_slice(&64u16.to_ne_bytes());
+        let () = core.extend_from_slice(&56u16.to_ne_bytes());
+        let () = core.extend_from_slice(&(phnum as u16).to_ne_bytes());
+        let () = core.extend_from_slice(&64u16.to_ne_bytes());
+        let () = core.extend_from_slice(&0u16.to_ne_bytes());
+        let () = core.extend_from_slice(&0u16.to_ne_bytes());
+        assert_eq!(core.len(), 64);
+
+        let () = phdr(&mut core, PT_NOTE, note_offset, 0, note.len() as u64);
+        for (addr, data) in memory {
+            let () = phdr(&mut core, PT_LOAD, data_offset, *addr, data.len() as u64);
+            data_offset = align_up(data_offset + data.len() as u64, PAGE_SIZE);
+        }
+        let () = core.extend_from_slice(&note);
+
+        for (_addr, data) in memory {
+            let () = core.resize(align_up(core.len() as u64, PAGE_SIZE) as usize, 0);
+            let () = core.extend_from_slice(data);
+        }
+
+        let mut file = NamedTempFile::new().unwrap();
+        let () = file.write_all(&core).unwrap();
+        file
+    }
+
+    fn test_so() -> PathBuf {
+        Path::new(&env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join("libtest-so.so")
+    }
+
+    /// Check that we can parse the mappings described by a core dump.
+    #[test]
+    fn core_mapping_parsing() {
+        let so = test_so();
+        let image = read_file(&so).unwrap();
+        let image = &image[..PAGE_SIZE as usize];
+        let core = make_core(
+            &[
+                (0x7f0000001000, 0x7f0000002000, 1, &so),
+                (0x7f0000000000, 0x7f0000001000, 0, &so),
+            ],
+            &[(0x7f0000000000, image)],
+        );
+
+        let parser = ElfParser::open(core.path()).unwrap();
+        let mappings = parse(&parser).unwrap();
+        assert_eq!(mappings.len(), 2);
+
+        let mapping = &mappings[0];
+        assert_eq!(mapping.entry.range, 0x7f0000000000..0x7f0000001000);
+        assert_eq!(mapping.entry.offset, 0);
+        let path = mapping.entry.path_name.as_ref().unwrap().as_path().unwrap();
+        assert_eq!(path.symbolic_path, so);
+        let build_id = read_elf_build_id(&so).unwrap().unwrap();
+        assert_eq!(mapping.build_id.as_deref(), Some(build_id.as_ref()));
+
+        let mapping = &mappings[1];
+        assert_eq!(mapping.entry.range, 0x7f0000001000..0x7f0000002000);
+        assert_eq!(mapping.entry.offset, PAGE_SIZE);
+        assert_eq!(mapping.build_id, None);
+
+        // A regular ELF file is not a core dump.
+        let parser = ElfParser::open(&so).unwrap();
+        let err = parse(&parser).unwrap_err();
+        assert!(err.to_string().contains("is not a core dump"), "{err}");
+    }
+
+    /// Check that core dumps of foreign byte order or address size are
+    /// rejected.
+    #[test]
+    fn unsupported_core_rejection() {
+        /// Create an ELF header (without any other contents) of a core
+        /// dump of the given class and byte order.
+        fn make_ehdr(elf32: bool, foreign: bool) -> Vec<u8> {
+            let mut data = Vec::new();
+            macro_rules! push {
+                ($value:expr) => {{
+                    let value = $value;
+                    let value = if foreign { value.swap_bytes() } else { value };
+                    let () = data.extend_from_slice(&value.to_ne_bytes());
+                }};
+            }
+            macro_rules! push_addr {
+                ($value:expr) => {{
+                    if elf32 {
+                        push!($value as u32)
+                    } else {
+                        push!($value as u64)
+                    }
+                }};
+            }
+
+            let big_endian = cfg!(target_endian = "big") != foreign;
+            let () = data.extend_from_slice(&[0x7f, b'E', b'L', b'F']);
+            let () = data.push(if elf32 { ELFCLASS32 } else { ELFCLASS64 });
+            let () = data.push(if big_endian { ELFDATA2MSB } else { ELFDATA2LSB });
+            let () = data.push(1);
+            let () = data.resize(16, 0);
+            push!(ET_CORE);
+            push!(62u16);
+            push!(1u32);
+            push_addr!(0);
+            push_addr!(0);
+            push_addr!(0);
+            push!(0u32);
+            push!(if elf32 { 52u16 } else { 64u16 });
+            push!(if elf32 { 32u16 } else { 56u16 });
+            push!(0u16);
+            push!(if elf32 { 40u16 } else { 64u16 });
+            push!(0u16);
+            push!(0u16);
+            data
+        }
+
+        let data = make_ehdr(false, false);
+        let parser = ElfParser::open_from_bytes(&data, "core").unwrap();
+        let err = parse(&parser).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData, "{err}");
+
+        for (elf32, foreign) in [(false, true), (true, false), (true, true)] {
+            let data = make_ehdr(elf32, foreign);
+            let parser = ElfParser::open_from_bytes(&data, "core").unwrap();
+            assert_eq!(parser.file_type().unwrap(), ET_CORE);
+            let err = parse(&parser).unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::Unsupported, "{err}");
+        }
+    }
+
+    /// Check that we can symbolize addresses using a core dump.
+    #[test]
+    fn core_dump_symbolization() {
+        let so = test_so();
+        let image = read_file(&so).unwrap();
+        let image = &image[..PAGE_SIZE as usize];
+        // The address of `the_answer` in the mapping below.
+        let addr = 0x7f0000001129;
+        let missing = Path::new("/does/not/exist.so");
+        let mappings = [
+            (0x7f0000000000, 0x7f0000001000, 0, so.as_path()),
+            (0x7f0000001000, 0x7f0000002000, 1, so.as_path()),
+            (0x7f1000000000, 0x7f1000001000, 0, missing),
+        ];
+        let core = make_core(&mappings, &[(0x7f0000000000, image)]);
+
+        let src = Source::from(CoreDump::new(core.path()));
+        let symbolizer = Symbolizer::new();
+        let addrs = [0x7f1000000010, addr, 0x1000];
+        let results = symbolizer.symbolize(&src, Input::AbsAddr(&addrs)).unwrap();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0], Symbolized::Unknown(Reason::MissingFile));
+        assert_eq!(results[1].as_sym().unwrap().name, "the_answer");
+        assert_eq!(results[2], Symbolized::Unknown(Reason::Unmapped));
+
+        // Now make the core dump refer to a file relative to a sysroot.
+        let mappings = [
+            (
+                0x7f0000000000,
+                0x7f0000001000,
+                0,
+                Path::new("/libtest-so.so"),
+            ),
+            (
+                0x7f0000001000,
+                0x7f0000002000,
+                1,
+                Path::new("/libtest-so.so"),
+            ),
+        ];
+        let core = make_core(&mappings, &[(0x7f0000000000, image)]);
+        let mut core_dump = CoreDump::new(core.path());
+        core_dump.sysroot = Some(so.parent().unwrap().to_path_buf());
+        let src = Source::from(core_dump);
+        let result = symbolizer
+            .symbolize_single(&src, Input::AbsAddr(addr))
+            .unwrap();
+        assert_eq!(result.as_sym().unwrap().name, "the_answer");
+
+        // Corrupt the build ID as present in the core dump's memory
+        // image.
+        let image_orig = image;
+        let mut image = image.to_vec();
+        let build_id = read_elf_build_id(&so).unwrap().unwrap();
+        let idx = image
+            .windows(build_id.len())
+            .position(|window| window == build_id.as_ref())
+            .unwrap();
+        image[idx] = !image[idx];
+        let core = make_core(&mappings[..], &[(0x7f0000000000, &image)]);
+        let mut core_dump = CoreDump::new(core.path());
+        core_dump.sysroot = Some(so.parent().unwrap().to_path_buf());
+        let src = Source::from(core_dump);
+        let result = symbolizer
+            .symbolize_single(&src, Input::AbsAddr(addr))
+            .unwrap();
+        assert_eq!(result, Symbolized::Unknown(Reason::BuildIdMismatch));
+
+        // A backing file that is not an ELF file should not prevent
+        // symbolization of addresses in other files.
+        let mut not_elf = NamedTempFile::new().unwrap();
+        let () = not_elf.write_all(b"not an ELF file").unwrap();
+        let mappings = [
+            (0x7f0000000000, 0x7f0000001000, 0, not_elf.path()),
+            (0x7f1000000000, 0x7f1000001000, 0, so.as_path()),
+            (0x7f1000001000, 0x7f1000002000, 1, so.as_path()),
+        ];
+        let core = make_core(
+            &mappings,
+            &[(0x7f0000000000, image_orig), (0x7f1000000000, image_orig)],
+        );
+        let src = Source::from(CoreDump::new(core.path()));
+        let addrs = [0x7f0000000010, 0x7f1000001129];
+        let results = symbolizer.symbolize(&src, Input::AbsAddr(&addrs)).unwrap();
+        assert_eq!(results[0], Symbolized::Unknown(Reason::Unsupported));
+        assert_eq!(results[1].as_sym().unwrap().name, "the_answer");
+    }
+}
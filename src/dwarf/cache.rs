@@ -0,0 +1,527 @@
+//! On-disk caching of the DWARF unit index.
+//!
+//! Setting up a [`Units`][super::units::Units] object requires a pass
+//! over all compilation units to determine their languages and
+//! address ranges. For large binaries this pass can take seconds. The
+//! functionality in this module allows for storing the result in a
+//! cache directory and loading it back in subsequent runs instead of
+//! re-computing it.
+
+use std::ffi::CString;
+use std::ffi::OsStr;
+use std::fs;
+use std::fs::File;
+use std::io;
+use std::io::Write as _;
+use std::os::unix::ffi::OsStrExt as _;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process;
+use std::ptr;
+use std::time::Duration;
+use std::time::SystemTime;
+
+use crate::elf::ElfParser;
+use crate::log::debug;
+use crate::normalize::buildid::read_build_id_impl;
+use crate::util::FileId;
+use crate::util::ReadRaw as _;
+use crate::Error;
+use crate::ErrorExt as _;
+use crate::Result;
+
+use super::unit::UnitRange;
+
+
+/// The magic bytes at the start of every cache file.
+const MAGIC: &[u8; 8] = b"BLZDWIDX";
+/// The version of the cache file format.
+///
+/// This version has to be bumped whenever the format or the semantics
+/// of the cached data change, so that existing caches are rejected.
+const VERSION: u32 = 2;
+/// The extension of cache files.
+const EXTENSION: &str = "dwarf-index";
+/// The maximum combined size of all cache files in a cache directory.
+const MAX_SIZE: u64 = 256 * 1024 * 1024;
+/// The time after which an unused cache file is evicted.
+const MAX_AGE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+
+/// Hash `data` using the 64 bit FNV-1a hash function.
+///
+/// Contrary to the hashers provided by the standard library, the
+/// result is stable across Rust releases, which is a requirement for
+/// naming cache files.
+fn fnv1a(data: &[u8]) -> u64 {
+    data.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+
+/// The identity of a file whose unit index is cached.
+///
+/// A cache entry is only used if it was created for a file with the
+/// same identity as the one at hand.
+#[derive(Debug, PartialEq)]
+pub(super) enum CacheKey {
+    /// A file identified by its build ID and size.
+    ///
+    /// Such a key is independent of the file's location, meaning that
+    /// it matches, for example, regardless of whether the file is
+    /// accessed directly or through `/proc/<pid>/map_files/`. The
+    /// size distinguishes between files sharing a build ID, such as a
+    /// stripped binary and its separate debug information file.
+    BuildId { build_id: Vec<u8>, size: u64 },
+    /// A file without build ID, identified by the device and inode it
+    /// is stored in as well as its size and modification time.
+    File(FileId),
+}
+
+impl CacheKey {
+    /// Create a `CacheKey` for the file backing the provided parser.
+    pub(super) fn new(parser: &ElfParser) -> Result<Self> {
+        // Only files on disk have an identity that we can rely on.
+        let file_id = parser.file_id().ok_or_else(|| {
+            Error::with_unsupported(format!(
+                "`{}` is not backed by a file on disk",
+                parser.path().display()
+            ))
+        })?;
+
+        let slf = match read_build_id_impl(parser)? {
+            Some(build_id) => Self::BuildId {
+                build_id: build_id.to_vec(),
+                size: file_id.size,
+            },
+            None => Self::File(*file_id),
+        };
+        Ok(slf)
+    }
+
+    /// Retrieve the path of the cache file for this key inside `dir`.
+    fn cache_file(&self, dir: &Path) -> PathBuf {
+        let mut data = Vec::new();
+        let () = self.write(&mut data);
+        dir.join(format!("{:016x}.{EXTENSION}", fnv1a(&data)))
+    }
+
+    fn write(&self, data: &mut Vec<u8>) {
+        match self {
+            Self::BuildId { build_id, size } => {
+                let () = data.push(1);
+                let () = write_u64(data, build_id.len() as u64);
+                let () = data.extend_from_slice(build_id);
+                let () = write_u64(data, *size);
+            }
+            Self::File(FileId {
+                dev,
+                inode,
+                size,
+                mtime,
+            }) => {
+                let () = data.push(2);
+                let () = write_u64(data, *dev);
+                let () = write_u64(data, *inode);
+                let () = write_u64(data, *size);
+                let () = write_u64(data, mtime.0 as u64);
+                let () = write_u64(data, mtime.1 as u64);
+            }
+        }
+    }
+
+    /// Check whether the key serialized at the start of `data` matches
+    /// `self`.
+    fn matches(&self, data: &mut &[u8]) -> Option<bool> {
+        let mut key = Vec::new();
+        let () = self.write(&mut key);
+        Some(data.read_slice(key.len())? == key)
+    }
+}
+
+
+/// The cacheable result of the initial pass over all compilation
+/// units.
+#[derive(Clone, Debug)]
+pub(super) struct UnitIndex {
+    /// The `.debug_info` offset and language of each unit, in order.
+    pub units: Box<[(usize, Option<gimli::DwLang>)]>,
+    /// The sorted address ranges of all units.
+    pub unit_ranges: Box<[UnitRange]>,
+}
+
+impl UnitIndex {
+    fn write(&self, data: &mut Vec<u8>) {
+        let () = write_u64(data, self.units.len() as u64);
+        for (offset, lang) in self.units.iter() {
+            let () = write_u64(data, *offset as u64);
+            // Zero is not a valid language code and we use it to
+            // represent an unknown language.
+            let lang = lang.map(|lang| lang.0).unwrap_or(0);
+            let () = data.extend_from_slice(&lang.to_ne_bytes());
+        }
+
+        let () = write_u64(data, self.unit_ranges.len() as u64);
+        for range in self.unit_ranges.iter() {
+            let () = write_u64(data, range.range.begin);
+            let () = write_u64(data, range.range.end);
+            let () = write_u64(data, range.unit_id as u64);
+            let () = write_u64(data, range.max_end);
+        }
+    }
+
+    fn read(data: &mut &[u8]) -> Option<Self> {
+        let count = usize::try_from(data.read_u64()?).ok()?;
+        let units = (0..count)
+            .map(|_| {
+                let offset = usize::try_from(data.read_u64()?).ok()?;
+                let lang = match data.read_u16()? {
+                    0 => None,
+                    lang => Some(gimli::DwLang(lang)),
+                };
+                Some((offset, lang))
+            })
+            .collect::<Option<Box<[_]>>>()?;
+
+        let count = usize::try_from(data.read_u64()?).ok()?;
+        let unit_ranges = (0..count)
+            .map(|_| {
+                let begin = data.read_u64()?;
+                let end = data.read_u64()?;
+                let unit_id = usize::try_from(data.read_u64()?).ok()?;
+                let max_end = data.read_u64()?;
+                // A unit ID referencing a non-existent unit indicates a
+                // corrupted cache.
+                (unit_id < units.len()).then_some(UnitRange {
+                    unit_id,
+                    max_end,
+                    range: gimli::Range { begin, end },
+                })
+            })
+            .collect::<Option<Box<[_]>>>()?;
+
+        let slf = Self { units, unit_ranges };
+        Some(slf)
+    }
+}
+
+
+fn write_u64(data: &mut Vec<u8>, value: u64) {
+    data.extend_from_slice(&value.to_ne_bytes())
+}
+
+
+/// Load the unit index for the file identified by `key` from the cache
+/// directory `dir`.
+///
+/// `None` is returned if no matching and valid cache entry exists.
+pub(super) fn load(dir: &Path, key: &CacheKey) -> Option<UnitIndex> {
+    let path = key.cache_file(dir);
+    let data = match fs::read(&path) {
+        Ok(data) => data,
+        Err(err) => {
+            debug!(
+                "failed to read DWARF index cache `{}`: {err}",
+                path.display()
+            );
+            return None
+        }
+    };
+
+    let mut data = data.as_slice();
+    if data.read_slice(MAGIC.len())? != MAGIC || data.read_u32()? != VERSION {
+        debug!(
+            "ignoring DWARF index cache `{}` of unsupported format",
+            path.display()
+        );
+        return None
+    }
+
+    if !key.matches(&mut data)? {
+        debug!("ignoring stale DWARF index cache `{}`", path.display());
+        return None
+    }
+
+    let index = UnitIndex::read(&mut data);
+    if index.is_none() {
+        debug!("ignoring corrupted DWARF index cache `{}`", path.display());
+        return None
+    }
+
+    // Mark the entry as recently used, so that it is evicted last.
+    if let Err(err) = set_mtime(&path, None) {
+        debug!(
+            "failed to update modification time of DWARF index cache `{}`: {err}",
+            path.display()
+        );
+    }
+    index
+}
+
+
+/// Store the unit index for the file identified by `key` in the cache
+/// directory `dir`.
+pub(super) fn store(dir: &Path, key: &CacheKey, index: &UnitIndex) -> Result<()> {
+    let mut data = Vec::new();
+    let () = data.extend_from_slice(MAGIC);
+    let () = data.extend_from_slice(&VERSION.to_ne_bytes());
+    let () = key.write(&mut data);
+    let () = index.write(&mut data);
+
+    let () = fs::create_dir_all(dir)
+        .with_context(|| format!("failed to create cache directory `{}`", dir.display()))?;
+
+    // Write to a temporary file first and rename it afterwards, so that
+    // concurrent readers never observe a partially written cache file.
+    let path = key.cache_file(dir);
+    let tmp_path = path.with_extension(format!("tmp.{}", process::id()));
+    let () = File::create(&tmp_path)
+        .and_then(|mut file| file.write_all(&data))
+        .with_context(|| format!("failed to write `{}`", tmp_path.display()))?;
+    let () = fs::rename(&tmp_path, &path)
+        .with_context(|| format!("failed to rename `{}`", tmp_path.display()))?;
+
+    if let Err(err) = evict(dir, MAX_SIZE, MAX_AGE) {
+        debug!(
+            "failed to evict entries from DWARF index cache `{}`: {err}",
+            dir.display()
+        );
+    }
+    Ok(())
+}
+
+
+/// Set the modification (and access) time of the file at `path` to
+/// `time` or, if `None`, the current time.
+fn set_mtime(path: &Path, time: Option<SystemTime>) -> Result<()> {
+    let path = CString::new(path.as_os_str().as_bytes()).map_err(Error::with_invalid_input)?;
+    let times = match time {
+        Some(time) => {
+            let time = time
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map_err(Error::with_invalid_input)?;
+            let time = libc::timespec {
+                tv_sec: time.as_secs() as _,
+                tv_nsec: time.subsec_nanos() as _,
+            };
+            Some([time, time])
+        }
+        None => None,
+    };
+    let times_ptr = times
+        .as_ref()
+        .map(|times| times.as_ptr())
+        .unwrap_or(ptr::null());
+
+    // SAFETY: `path` is a valid NUL terminated string and `times_ptr`
+    //         is either NULL or points to an array of two `timespec`
+    //         objects.
+    let rc = unsafe { libc::utimensat(libc::AT_FDCWD, path.as_ptr(), times_ptr, 0) };
+    if rc < 0 {
+        return Err(Error::from(io::Error::last_os_error()))
+    }
+    Ok(())
+}
+
+
+/// Evict cache files from `dir`.
+///
+/// Files that have not been used for longer than `max_age` are
+/// removed. If the remaining ones exceed `max_size` in total, the least
+/// recently used ones are removed until they no longer do.
+fn evict(dir: &Path, max_size: u64, max_age: Duration) -> Result<()> {
+    let now = SystemTime::now();
+    let mut files = Vec::new();
+
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension() != Some(OsStr::new(EXTENSION)) {
+            continue
+        }
+
+        // Other processes may evict concurrently, so the file may be
+        // gone already.
+        let meta = match fs::metadata(&path) {
+            Ok(meta) => meta,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+            Err(err) => return Err(err.into()),
+        };
+        let mtime = meta.modified()?;
+        let expired = now
+            .duration_since(mtime)
+            .map(|age| age > max_age)
+            .unwrap_or(false);
+
+        if expired {
+            let () = remove_file(&path)?;
+        } else {
+            let () = files.push((mtime, meta.len(), path));
+        }
+    }
+
+    let mut total = files.iter().map(|(_mtime, size, _path)| size).sum::<u64>();
+    let () = files.sort_by_key(|(mtime, _size, _path)| *mtime);
+
+    for (_mtime, size, path) in files {
+        if total <= max_size {
+            break
+        }
+        let () = remove_file(&path)?;
+        total -= size;
+    }
+    Ok(())
+}
+
+
+/// Remove the file at `path`, tolerating its absence.
+fn remove_file(path: &Path) -> Result<()> {
+    match fs::remove_file(path) {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::env;
+
+    use tempfile::tempdir;
+
+
+    /// Check that we can store a unit index and load it back, and that
+    /// mismatching entries are rejected.
+    #[test]
+    fn store_load_round_trip() {
+        let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join("test-stable-addrs.bin");
+        let parser = ElfParser::open(&path).unwrap();
+        let key = CacheKey::new(&parser).unwrap();
+        let index = UnitIndex {
+            units: Box::new([(0, Some(gimli::DW_LANG_C11)), (0x42, None)]),
+            unit_ranges: Box::new([UnitRange {
+                unit_id: 1,
+                max_end: 0x2000,
+                range: gimli::Range {
+                    begin: 0x1000,
+                    end: 0x2000,
+                },
+            }]),
+        };
+
+        let dir = tempdir().unwrap();
+        assert!(load(dir.path(), &key).is_none());
+
+        let () = store(dir.path(), &key, &index).unwrap();
+        let loaded = load(dir.path(), &key).unwrap();
+        assert_eq!(&*loaded.units, &*index.units);
+        assert_eq!(loaded.unit_ranges.len(), 1);
+        assert_eq!(loaded.unit_ranges[0].unit_id, 1);
+        assert_eq!(loaded.unit_ranges[0].max_end, 0x2000);
+        assert_eq!(loaded.unit_ranges[0].range, index.unit_ranges[0].range);
+
+        // An entry for a file with different properties should be
+        // rejected.
+        let mut other = CacheKey::new(&parser).unwrap();
+        match &mut other {
+            CacheKey::File(file_id) => file_id.mtime.1 += 1,
+            CacheKey::BuildId { .. } => panic!("unexpected key: {other:?}"),
+        }
+        let _size = fs::copy(key.cache_file(dir.path()), other.cache_file(dir.path())).unwrap();
+        assert!(load(dir.path(), &other).is_none());
+
+        // So should one of a different format version.
+        let path = key.cache_file(dir.path());
+        let mut data = fs::read(&path).unwrap();
+        let () = data[MAGIC.len()..MAGIC.len() + 4].copy_from_slice(&(VERSION + 1).to_ne_bytes());
+        let () = fs::write(&path, data).unwrap();
+        assert!(load(dir.path(), &key).is_none());
+    }
+
+    /// Check that cache keys identify files by build ID, if present,
+    /// and by file system identity otherwise.
+    #[test]
+    fn cache_key_identity() {
+        let data_dir = Path::new(&env!("CARGO_MANIFEST_DIR")).join("data");
+        let dir = tempdir().unwrap();
+
+        // A file with build ID is identified independent of its
+        // location.
+        let src = data_dir.join("test-dwarf-v5.bin");
+        let dst = dir.path().join("test-dwarf-v5.bin");
+        let _size = fs::copy(&src, &dst).unwrap();
+        let key = CacheKey::new(&ElfParser::open(&src).unwrap()).unwrap();
+        assert!(matches!(key, CacheKey::BuildId { .. }), "{key:?}");
+        let copy = CacheKey::new(&ElfParser::open(&dst).unwrap()).unwrap();
+        assert_eq!(copy, key);
+        assert_eq!(copy.cache_file(dir.path()), key.cache_file(dir.path()));
+
+        // A file without build ID is identified by its inode, among
+        // other things.
+        let src = data_dir.join("test-stable-addrs.bin");
+        let dst = dir.path().join("test-stable-addrs.bin");
+        let _size = fs::copy(&src, &dst).unwrap();
+        let key = CacheKey::new(&ElfParser::open(&src).unwrap()).unwrap();
+        assert!(matches!(key, CacheKey::File(..)), "{key:?}");
+        let copy = CacheKey::new(&ElfParser::open(&dst).unwrap()).unwrap();
+        assert_ne!(copy, key);
+        assert_ne!(copy.cache_file(dir.path()), key.cache_file(dir.path()));
+
+        // Parsers not backed by a file can't be identified.
+        let data = fs::read(&src).unwrap();
+        let parser = ElfParser::open_from_bytes(&data, "test-stable-addrs.bin").unwrap();
+        assert!(CacheKey::new(&parser).is_err());
+    }
+
+    /// Check that our hash function, used for naming cache files,
+    /// produces the expected (stable) values.
+    #[test]
+    fn fnv1a_hashing() {
+        assert_eq!(fnv1a(b""), 0xcbf29ce484222325);
+        assert_eq!(fnv1a(b"a"), 0xaf63dc4c8601ec8c);
+        assert_eq!(fnv1a(b"foobar"), 0x85944171f73967e8);
+    }
+
+    /// Check that expired and least recently used cache files are
+    /// evicted.
+    #[test]
+    fn cache_eviction() {
+        let dir = tempdir().unwrap();
+        let now = SystemTime::now();
+        let day = Duration::from_secs(24 * 60 * 60);
+        let files = [
+            ("expired.dwarf-index", now - 2 * day),
+            ("old.dwarf-index", now - Duration::from_secs(20)),
+            ("older.dwarf-index", now - Duration::from_secs(30)),
+            ("new.dwarf-index", now),
+            ("unrelated.txt", now - 2 * day),
+        ];
+
+        for (name, mtime) in files {
+            let path = dir.path().join(name);
+            let () = fs::write(&path, [0; 100]).unwrap();
+            let () = set_mtime(&path, Some(mtime)).unwrap();
+        }
+
+        let () = evict(dir.path(), 250, day).unwrap();
+
+        let mut remaining = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect::<Vec<_>>();
+        let () = remaining.sort();
+        assert_eq!(
+            remaining,
+            ["new.dwarf-index", "old.dwarf-index", "unrelated.txt"]
+        );
+
+        // Nothing else should be evicted if within bounds.
+        let () = evict(dir.path(), 250, day).unwrap();
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 3);
+    }
+}
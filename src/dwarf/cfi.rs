@@ -0,0 +1,218 @@
+//! Extraction of unwind information from `.eh_frame` call frame
+//! information.
+//!
+//! Call frame information describes, for each instruction of a
+//! function, how to compute the Canonical Frame Address (CFA) and
+//! where the caller's registers, including the return address, were
+//! saved. It is what allows for unwinding the stack without relying on
+//! frame pointers.
+
+use gimli::BaseAddresses;
+use gimli::CfaRule;
+use gimli::EhFrame;
+use gimli::RegisterRule;
+use gimli::RunTimeEndian;
+use gimli::UnwindContext;
+use gimli::UnwindSection as _;
+
+use crate::elf::ElfParser;
+use crate::Addr;
+use crate::Error;
+use crate::Result;
+
+use super::reader::reader;
+use super::reader::R;
+
+
+/// Unwind information for a single instruction, as derived from call
+/// frame information.
+///
+/// All register numbers are DWARF register numbers, as defined by the
+/// architecture's ABI (e.g., `7` for `rsp` and `16` for the return
+/// address on x86-64).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UnwindInfo {
+    /// The register whose value the Canonical Frame Address (CFA) is
+    /// based on.
+    pub cfa_reg: u16,
+    /// The offset of the CFA from the value of `cfa_reg`.
+    pub cfa_offset: i64,
+    /// The offset from the CFA at which the return address is saved.
+    pub ra_offset: i64,
+    /// The registers saved by the function, along with the offset from
+    /// the CFA at which each is saved, in register number order.
+    ///
+    /// The return address is not included.
+    pub saved_regs: Vec<(u16, i64)>,
+    /// The struct is non-exhaustive and open to extension.
+    #[doc(hidden)]
+    pub _non_exhaustive: (),
+}
+
+
+/// An unwinder based on the `.eh_frame` section of an ELF file.
+pub(crate) struct CfiUnwinder<'prs> {
+    eh_frame: EhFrame<R<'prs>>,
+    bases: BaseAddresses,
+}
+
+impl<'prs> CfiUnwinder<'prs> {
+    /// Create a `CfiUnwinder` for the ELF file backing `parser`.
+    ///
+    /// `None` is returned if the file does not contain an `.eh_frame`
+    /// section.
+    pub(crate) fn new(parser: &'prs ElfParser) -> Result<Option<Self>> {
+        let idx = match parser.find_section(".eh_frame")? {
+            Some(idx) => idx,
+            None => return Ok(None),
+        };
+        let shdrs = parser.section_headers()?;
+        let data = parser.section_data(idx)?;
+        let endian = if parser.is_big_endian()? {
+            RunTimeEndian::Big
+        } else {
+            RunTimeEndian::Little
+        };
+
+        let mut eh_frame = EhFrame::from(reader(data, endian));
+        let () = eh_frame.set_address_size(parser.address_size()?);
+
+        let mut bases = BaseAddresses::default().set_eh_frame(shdrs[idx].sh_addr);
+        if let Some(idx) = parser.find_section(".text")? {
+            bases = bases.set_text(shdrs[idx].sh_addr);
+        }
+        if let Some(idx) = parser.find_section(".got")? {
+            bases = bases.set_got(shdrs[idx].sh_addr);
+        }
+
+        let slf = Self { eh_frame, bases };
+        Ok(Some(slf))
+    }
+
+    /// Retrieve the unwind information for the instruction at `addr`.
+    ///
+    /// `None` is returned if no Frame Description Entry (FDE) covers
+    /// the address.
+    pub(crate) fn unwind_info(&self, addr: Addr) -> Result<Option<UnwindInfo>> {
+        let result = self
+            .eh_frame
+            .fde_for_address(&self.bases, addr, EhFrame::cie_from_offset);
+        let fde = match result {
+            Ok(fde) => fde,
+            Err(gimli::Error::NoUnwindInfoForAddress) => return Ok(None),
+            Err(err) => return Err(Error::from(err)),
+        };
+
+        let mut ctx = UnwindContext::new();
+        let row = fde.unwind_info_for_address(&self.eh_frame, &self.bases, &mut ctx, addr)?;
+
+        let (cfa_reg, cfa_offset) = match row.cfa() {
+            CfaRule::RegisterAndOffset { register, offset } => (register.0, *offset),
+            CfaRule::Expression(..) => {
+                return Err(Error::with_unsupported(format!(
+                    "CFA at {addr:#x} is described by an unsupported DWARF expression"
+                )))
+            }
+        };
+
+        let ra = fde.cie().return_address_register();
+        let ra_offset = match row.register(ra) {
+            RegisterRule::Offset(offset) => offset,
+            rule => {
+                return Err(Error::with_unsupported(format!(
+                    "return address at {addr:#x} is described by unsupported rule {rule:?}"
+                )))
+            }
+        };
+
+        // Registers that were not saved (or are recovered in ways that
+        // we do not support) are simply not reported.
+        let mut saved_regs = row
+            .registers()
+            .filter_map(|(reg, rule)| match rule {
+                RegisterRule::Offset(offset) if *reg != ra => Some((reg.0, *offset)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let () = saved_regs.sort_by_key(|(reg, _offset)| *reg);
+
+        let info = UnwindInfo {
+            cfa_reg,
+            cfa_offset,
+            ra_offset,
+            saved_regs,
+            _non_exhaustive: (),
+        };
+        Ok(Some(info))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::env;
+    use std::path::Path;
+
+
+    /// Check that we can retrieve unwind information at different
+    /// points of a function's prologue.
+    #[test]
+    fn unwind_info_lookup() {
+        let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join("test-stable-addrs.bin");
+        let parser = ElfParser::open(&path).unwrap();
+        let unwinder = CfiUnwinder::new(&parser).unwrap().unwrap();
+
+        const RSP: u16 = 7;
+        const RBP: u16 = 6;
+
+        // `factorial` at 0x2000100 starts with `push %rbp; mov %rsp,%rbp`.
+        let info = unwinder.unwind_info(0x2000100).unwrap().unwrap();
+        assert_eq!(info.cfa_reg, RSP);
+        assert_eq!(info.cfa_offset, 8);
+        assert_eq!(info.ra_offset, -8);
+        assert_eq!(info.saved_regs, Vec::new());
+
+        let info = unwinder.unwind_info(0x2000101).unwrap().unwrap();
+        assert_eq!(info.cfa_reg, RSP);
+        assert_eq!(info.cfa_offset, 16);
+        assert_eq!(info.ra_offset, -8);
+        assert_eq!(info.saved_regs, vec![(RBP, -16)]);
+
+        let info = unwinder.unwind_info(0x2000110).unwrap().unwrap();
+        assert_eq!(info.cfa_reg, RBP);
+        assert_eq!(info.cfa_offset, 16);
+        assert_eq!(info.saved_regs, vec![(RBP, -16)]);
+
+        assert_eq!(unwinder.unwind_info(0x1000).unwrap(), None);
+    }
+
+    /// Check that we can retrieve unwind information from a 32 bit ELF
+    /// file.
+    #[test]
+    fn unwind_info_lookup_elf32() {
+        let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join("libtest-so-32.so");
+        let parser = ElfParser::open(&path).unwrap();
+        let unwinder = CfiUnwinder::new(&parser).unwrap().unwrap();
+
+        const ESP: u16 = 4;
+        const EBP: u16 = 5;
+
+        // `the_answer` at 0x1000 starts with `push %ebp; mov %esp,%ebp`.
+        let info = unwinder.unwind_info(0x1000).unwrap().unwrap();
+        assert_eq!(info.cfa_reg, ESP);
+        assert_eq!(info.cfa_offset, 4);
+        assert_eq!(info.ra_offset, -4);
+        assert_eq!(info.saved_regs, Vec::new());
+
+        let info = unwinder.unwind_info(0x1003).unwrap().unwrap();
+        assert_eq!(info.cfa_reg, EBP);
+        assert_eq!(info.cfa_offset, 8);
+        assert_eq!(info.saved_regs, vec![(EBP, -8)]);
+    }
+}
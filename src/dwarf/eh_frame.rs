@@ -0,0 +1,170 @@
+//! Synthesis of function symbols from `.eh_frame` call frame
+//! information.
+//!
+//! Even fully stripped binaries usually retain call frame information
+//! for the purpose of unwinding. Each Frame Description Entry (FDE)
+//! covers the address range of a single function, meaning that we can
+//! use them to determine function boundaries in the absence of any
+//! other symbol source.
+
+use gimli::BaseAddresses;
+use gimli::CieOrFde;
+use gimli::EhFrame;
+use gimli::RunTimeEndian;
+use gimli::UnwindSection as _;
+
+use crate::elf::ElfParser;
+use crate::log::debug;
+use crate::symbolize::Provenance;
+use crate::symbolize::ResolvedSym;
+use crate::symbolize::SrcLang;
+use crate::Addr;
+use crate::Result;
+
+use super::reader::reader;
+
+
+/// A function as described by a single FDE.
+#[derive(Debug)]
+struct EhFrameFn {
+    /// The function's start address.
+    addr: Addr,
+    /// The function's size.
+    size: usize,
+    /// The synthesized name of the function.
+    name: String,
+}
+
+
+/// A sorted table of functions derived from `.eh_frame` FDEs.
+#[derive(Debug)]
+pub(crate) struct EhFrameFns {
+    fns: Box<[EhFrameFn]>,
+}
+
+impl EhFrameFns {
+    /// Parse the `.eh_frame` section of the ELF file backing `parser`.
+    ///
+    /// A file without `.eh_frame` section results in an empty table.
+    pub(crate) fn parse(parser: &ElfParser) -> Result<Self> {
+        let idx = match parser.find_section(".eh_frame")? {
+            Some(idx) => idx,
+            None => return Ok(Self { fns: Box::new([]) }),
+        };
+        let eh_frame_addr = parser.section_headers()?[idx].sh_addr;
+        let data = parser.section_data(idx)?;
+        let endian = if parser.is_big_endian()? {
+            RunTimeEndian::Big
+        } else {
+            RunTimeEndian::Little
+        };
+
+        let mut eh_frame = EhFrame::from(reader(data, endian));
+        let () = eh_frame.set_address_size(parser.address_size()?);
+
+        let mut bases = BaseAddresses::default().set_eh_frame(eh_frame_addr);
+        if let Some(idx) = parser.find_section(".text")? {
+            bases = bases.set_text(parser.section_headers()?[idx].sh_addr);
+        }
+
+        let mut fns = Vec::new();
+        let mut entries = eh_frame.entries(&bases);
+        loop {
+            let entry = match entries.next() {
+                Ok(Some(entry)) => entry,
+                Ok(None) => break,
+                Err(err) => {
+                    // We treat call frame information as best-effort
+                    // and just use whatever we parsed successfully.
+                    debug!("failed to parse .eh_frame entry: {err}");
+                    break
+                }
+            };
+
+            if let CieOrFde::Fde(partial) = entry {
+                let fde = match partial.parse(EhFrame::cie_from_offset) {
+                    Ok(fde) => fde,
+                    Err(err) => {
+                        debug!("failed to parse .eh_frame FDE: {err}");
+                        continue
+                    }
+                };
+
+                let addr = fde.initial_address();
+                let size = fde.len();
+                if size == 0 {
+                    continue
+                }
+
+                let () = fns.push(EhFrameFn {
+                    addr,
+                    size: size as usize,
+                    name: format!("fn_{addr:#x}"),
+                });
+            }
+        }
+
+        let () = fns.sort_by_key(|f| f.addr);
+        let () = fns.dedup_by_key(|f| f.addr);
+
+        let slf = Self {
+            fns: fns.into_boxed_slice(),
+        };
+        Ok(slf)
+    }
+
+    /// Find the function containing `addr`, if any.
+    pub(crate) fn find_sym(&self, addr: Addr) -> Option<ResolvedSym<'_>> {
+        let idx = self.fns.partition_point(|f| f.addr <= addr);
+        let f = self.fns.get(idx.checked_sub(1)?)?;
+        if addr - f.addr >= f.size as u64 {
+            return None
+        }
+
+        let sym = ResolvedSym {
+            name: &f.name,
+            addr: f.addr,
+            size: Some(f.size),
+            lang: SrcLang::Unknown,
+            code_info: None,
+            inlined: Box::new([]),
+            provenance: Provenance::EhFrame,
+            is_synthetic: true,
+            raw_elf_sym: None,
+            compilation_unit: None,
+            version: None,
+            section: None,
+            linkage_name: None,
+        };
+        Some(sym)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::env;
+    use std::path::Path;
+
+
+    /// Check that we can derive function boundaries from `.eh_frame`.
+    #[test]
+    fn eh_frame_fn_lookup() {
+        let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join("test-stable-addrs-stripped.bin");
+        let parser = ElfParser::open(&path).unwrap();
+        let fns = EhFrameFns::parse(&parser).unwrap();
+
+        let sym = fns.find_sym(0x2000100 + 4).unwrap();
+        assert_eq!(sym.name, "fn_0x2000100");
+        assert_eq!(sym.addr, 0x2000100);
+        assert_eq!(sym.size, Some(0x2b));
+        assert_eq!(sym.provenance, Provenance::EhFrame);
+
+        assert!(fns.find_sym(0x2000100 + 0x2b).is_none());
+        assert!(fns.find_sym(0x1000).is_none());
+    }
+}
@@ -59,12 +59,12 @@ fn name_entry<'dwarf>(
         match entries.read_attribute(*spec) {
             Ok(ref attr) => match attr.name() {
                 gimli::DW_AT_linkage_name | gimli::DW_AT_MIPS_linkage_name => {
-                    if let Ok(val) = units.dwarf().attr_string(unit, attr.value()) {
+                    if let Some(val) = units.attr_string(unit, attr.value())? {
                         return Ok(Some(val))
                     }
                 }
                 gimli::DW_AT_name => {
-                    if let Ok(val) = units.dwarf().attr_string(unit, attr.value()) {
+                    if let Some(val) = units.attr_string(unit, attr.value())? {
                         name = Some(val);
                     }
                 }
@@ -106,13 +106,33 @@ fn name_attr<'dwarf>(
             name_entry(unit, offset, units, recursion_limit)
         }
         // TODO: Need to handle `AttributeValue::DebugInfoRefSup`.
-        _ => Ok(None),
+        _ => {
+            let () = units.unsupported_form()?;
+            Ok(None)
+        }
+    }
+}
+
+
+/// Convert a `DW_AT_abstract_origin` attribute value into a
+/// `.debug_info` section offset.
+fn origin_attr(
+    attr: gimli::AttributeValue<R>,
+    unit: &gimli::Unit<R<'_>>,
+) -> Option<gimli::DebugInfoOffset> {
+    match attr {
+        gimli::AttributeValue::UnitRef(offset) => offset.to_debug_info_offset(&unit.header),
+        gimli::AttributeValue::DebugInfoRef(offset) => Some(offset),
+        _ => None,
     }
 }
 
 
 pub(super) struct InlinedFunction<'dwarf> {
     pub(crate) name: Option<R<'dwarf>>,
+    /// The `.debug_info` offset of the entry describing the abstract
+    /// instance of the inlined function, if present.
+    pub(crate) abstract_origin: Option<gimli::DebugInfoOffset>,
     pub(crate) call_file: Option<u64>,
     pub(crate) call_line: u32,
     pub(crate) call_column: u32,
@@ -161,7 +181,6 @@ impl<'dwarf> InlinedFunctions<'dwarf> {
             units,
             &mut inlined_functions,
             &mut inlined_addresses,
-            0,
         )?;
 
         // Sort ranges in "breadth-first traversal order", i.e. first by
@@ -186,11 +205,12 @@ impl<'dwarf> InlinedFunctions<'dwarf> {
         })
     }
 
-    /// Build the list of inlined functions that contain `probe`.
+    /// Build the list of inlined functions that contain `probe`, along
+    /// with the respective address range containing it.
     pub(super) fn find_inlined_functions(
         &self,
         probe: u64,
-    ) -> vec::IntoIter<&InlinedFunction<'dwarf>> {
+    ) -> vec::IntoIter<(&InlinedFunction<'dwarf>, &gimli::Range)> {
         // `inlined_functions` is ordered from outside to inside.
         let mut inlined_functions = Vec::new();
         let mut inlined_addresses = &self.inlined_addresses[..];
@@ -214,8 +234,8 @@ impl<'dwarf> InlinedFunctions<'dwarf> {
                 }
             });
             if let Ok(index) = search {
-                let function_index = inlined_addresses[index].function;
-                inlined_functions.push(&self.inlined_functions[function_index]);
+                let address = &inlined_addresses[index];
+                inlined_functions.push((&self.inlined_functions[address.function], &address.range));
                 inlined_addresses = &inlined_addresses[index + 1..];
             } else {
                 break
@@ -233,7 +253,7 @@ impl<'dwarf> InlinedFunctions<'dwarf> {
 /// `function` field.
 #[derive(Debug)]
 pub(crate) struct FunctionAddress {
-    range: gimli::Range,
+    pub(crate) range: gimli::Range,
     /// An index into `Functions::functions`.
     pub(crate) function: usize,
 }
@@ -242,9 +262,17 @@ pub(crate) struct FunctionAddress {
 pub(crate) struct Function<'dwarf> {
     pub(crate) dw_die_offset: gimli::UnitOffset<<R<'dwarf> as gimli::Reader>::Offset>,
     /// The function's name, if present.
+    ///
+    /// The linkage name is preferred over the "plain" name.
     pub(crate) name: Option<R<'dwarf>>,
+    /// The function's linkage name, if present.
+    pub(crate) linkage_name: Option<R<'dwarf>>,
     /// The function's range (begin and end address).
     pub(crate) range: Option<gimli::Range>,
+    /// The `.debug_info` offset of the entry describing the abstract
+    /// instance of the function, if this is a concrete out-of-line
+    /// instance of it.
+    pub(crate) abstract_origin: Option<gimli::DebugInfoOffset>,
     /// List of inlined function calls.
     pub(super) inlined_functions: OnceCell<InlinedFunctions<'dwarf>>,
 }
@@ -254,7 +282,9 @@ impl Debug for Function<'_> {
         let Self {
             dw_die_offset,
             name,
+            linkage_name: _,
             range,
+            abstract_origin: _,
             inlined_functions: _,
         } = self;
 
@@ -273,12 +303,29 @@ impl Debug for Function<'_> {
 }
 
 
+/// A `DW_TAG_label` entry with a name and an address.
+#[derive(Debug)]
+pub(crate) struct Label<'dwarf> {
+    /// The label's name.
+    pub(crate) name: R<'dwarf>,
+    /// The address the label refers to.
+    pub(crate) addr: u64,
+}
+
+
 #[derive(Debug)]
 pub(crate) struct Functions<'dwarf> {
     /// List of all `DW_TAG_subprogram` details in the unit.
     pub(crate) functions: Box<[Function<'dwarf>]>,
     /// List of `DW_TAG_subprogram` address ranges in the unit.
     pub(crate) addresses: Box<[FunctionAddress]>,
+    /// List of all named `DW_TAG_label` entries with an address in the
+    /// unit, sorted by address.
+    pub(crate) labels: Box<[Label<'dwarf>]>,
+    /// Mapping from `.debug_info` offsets of function entries and
+    /// their abstract origins to indexes into `functions`, sorted by
+    /// offset. Constructed lazily, on first use.
+    pub(crate) origins: OnceCell<Box<[(gimli::DebugInfoOffset, usize)]>>,
 }
 
 impl<'dwarf> Functions<'dwarf> {
@@ -288,31 +335,35 @@ impl<'dwarf> Functions<'dwarf> {
     ) -> Result<Self, Error> {
         let mut functions = Vec::new();
         let mut addresses = Vec::new();
+        let mut labels = Vec::new();
         let mut entries = unit.entries_raw(None)?;
         while !entries.is_empty() {
             let dw_die_offset = entries.next_offset();
             if let Some(abbrev) = entries.read_abbreviation()? {
                 if abbrev.tag() == gimli::DW_TAG_subprogram {
                     let mut name = None;
+                    let mut linkage_name = None;
+                    let mut abstract_origin = None;
                     let mut ranges = RangeAttributes::default();
                     for spec in abbrev.attributes() {
                         match entries.read_attribute(*spec) {
                             Ok(ref attr) => {
                                 match attr.name() {
                                     gimli::DW_AT_linkage_name | gimli::DW_AT_MIPS_linkage_name => {
-                                        if let Ok(val) =
-                                            units.dwarf().attr_string(unit, attr.value())
-                                        {
+                                        if let Some(val) = units.attr_string(unit, attr.value())? {
                                             name = Some(val);
+                                            linkage_name = Some(val);
                                         }
                                     }
                                     gimli::DW_AT_name => {
                                         if name.is_none() {
-                                            name =
-                                                units.dwarf().attr_string(unit, attr.value()).ok();
+                                            name = units.attr_string(unit, attr.value())?;
                                         }
                                     }
                                     gimli::DW_AT_abstract_origin | gimli::DW_AT_specification => {
+                                        if attr.name() == gimli::DW_AT_abstract_origin {
+                                            abstract_origin = origin_attr(attr.value(), unit);
+                                        }
                                         if name.is_none() {
                                             name = name_attr(attr.value(), unit, units, 16)?;
                                         }
@@ -325,7 +376,7 @@ impl<'dwarf> Functions<'dwarf> {
                                             ranges.low_pc =
                                                 Some(units.dwarf().address(unit, index)?);
                                         }
-                                        _ => {}
+                                        _ => units.unsupported_form()?,
                                     },
                                     gimli::DW_AT_high_pc => match attr.value() {
                                         gimli::AttributeValue::Addr(val) => {
@@ -338,7 +389,7 @@ impl<'dwarf> Functions<'dwarf> {
                                         gimli::AttributeValue::Udata(val) => {
                                             ranges.size = Some(val)
                                         }
-                                        _ => {}
+                                        _ => units.unsupported_form()?,
                                     },
                                     gimli::DW_AT_ranges => {
                                         ranges.ranges_offset =
@@ -363,11 +414,36 @@ impl<'dwarf> Functions<'dwarf> {
                         let function = Function {
                             dw_die_offset,
                             name,
+                            linkage_name,
                             range: ranges.bounds(),
+                            abstract_origin,
                             inlined_functions: OnceCell::new(),
                         };
                         functions.push(function);
                     }
+                } else if abbrev.tag() == gimli::DW_TAG_label {
+                    let mut name = None;
+                    let mut addr = None;
+                    for spec in abbrev.attributes() {
+                        let attr = entries.read_attribute(*spec)?;
+                        match attr.name() {
+                            gimli::DW_AT_name => {
+                                name = units.attr_string(unit, attr.value())?;
+                            }
+                            gimli::DW_AT_low_pc => match attr.value() {
+                                gimli::AttributeValue::Addr(val) => addr = Some(val),
+                                gimli::AttributeValue::DebugAddrIndex(index) => {
+                                    addr = Some(units.dwarf().address(unit, index)?);
+                                }
+                                _ => units.unsupported_form()?,
+                            },
+                            _ => {}
+                        }
+                    }
+
+                    if let (Some(name), Some(addr)) = (name, addr) {
+                        labels.push(Label { name, addr });
+                    }
                 } else {
                     entries.skip_attributes(abbrev.attributes())?;
                 }
@@ -383,10 +459,13 @@ impl<'dwarf> Functions<'dwarf> {
         // compiler can detect and remove functions with identical code.  In that case
         // we'll nondeterministically return one of them.
         addresses.sort_by_key(|x| x.range.begin);
+        labels.sort_by_key(|label| label.addr);
 
         Ok(Functions {
             functions: functions.into_boxed_slice(),
             addresses: addresses.into_boxed_slice(),
+            labels: labels.into_boxed_slice(),
+            origins: OnceCell::new(),
         })
     }
 
@@ -416,9 +495,68 @@ impl<'dwarf> Functions<'dwarf> {
             })
             .ok()
     }
+
+    /// Find the label closest preceding `probe`.
+    ///
+    /// Labels don't have an extent. We consider a label to refer to all
+    /// code up to the next label or function.
+    pub(crate) fn find_label(&self, probe: u64) -> Option<&Label<'dwarf>> {
+        let idx = self.labels.partition_point(|label| label.addr <= probe);
+        let label = &self.labels[idx.checked_sub(1)?];
+
+        // A function located between the label and `probe` (or one
+        // containing the label itself) terminates the code the label
+        // refers to.
+        let idx = self
+            .addresses
+            .partition_point(|address| address.range.begin <= probe);
+        if let Some(address) = idx.checked_sub(1).map(|idx| &self.addresses[idx]) {
+            if address.range.end > label.addr {
+                return None
+            }
+        }
+        Some(label)
+    }
+
+    /// Find the function with address information that is either
+    /// described by the entry at `offset` directly or that is a
+    /// concrete instance of it.
+    pub(crate) fn find_origin(
+        &self,
+        offset: gimli::DebugInfoOffset,
+        unit: &gimli::Unit<R<'dwarf>>,
+    ) -> Option<&Function<'dwarf>> {
+        let origins = self.origins.get_or_init(|| {
+            let mut origins = self
+                .functions
+                .iter()
+                .enumerate()
+                .flat_map(|(idx, function)| {
+                    let offset = function.dw_die_offset.to_debug_info_offset(&unit.header);
+                    [offset, function.abstract_origin]
+                        .into_iter()
+                        .flatten()
+                        .map(move |offset| (offset, idx))
+                })
+                .collect::<Vec<_>>();
+            let () = origins.sort_by_key(|(offset, _idx)| offset.0);
+            origins.into_boxed_slice()
+        });
+
+        let idx = origins
+            .binary_search_by_key(&offset.0, |(offset, _idx)| offset.0)
+            .ok()?;
+        let (_offset, idx) = origins[idx];
+        Some(&self.functions[idx])
+    }
 }
 
 impl<'dwarf> Function<'dwarf> {
+    /// Parse all `DW_TAG_inlined_subroutine` entries below the entry
+    /// at `depth`, including nested ones.
+    ///
+    /// Traversal happens iteratively, so that even pathologically
+    /// deep inline trees cannot exhaust the stack.
     fn parse_children(
         entries: &mut gimli::EntriesRaw<'_, '_, R<'dwarf>>,
         depth: isize,
@@ -426,10 +564,22 @@ impl<'dwarf> Function<'dwarf> {
         units: &Units<'dwarf>,
         inlined_functions: &mut Vec<InlinedFunction<'dwarf>>,
         inlined_addresses: &mut Vec<InlinedFunctionAddress>,
-        inlined_depth: usize,
     ) -> Result<(), Error> {
+        // The DIE depths of the inlined subroutines that we are
+        // currently nested in, from outside to inside. The length of
+        // the stack is the call depth of newly found inlined
+        // subroutines.
+        let mut stack = Vec::<isize>::new();
+
         loop {
             let next_depth = entries.next_depth();
+            while let Some(inlined_depth) = stack.last() {
+                if next_depth > *inlined_depth {
+                    break
+                }
+                let _inlined_depth = stack.pop();
+            }
+
             if next_depth <= depth {
                 return Ok(())
             }
@@ -442,13 +592,13 @@ impl<'dwarf> Function<'dwarf> {
                         InlinedFunction::parse(
                             entries,
                             abbrev,
-                            next_depth,
                             unit,
                             units,
                             inlined_functions,
                             inlined_addresses,
-                            inlined_depth,
+                            stack.len(),
                         )?;
+                        let () = stack.push(next_depth);
                     }
                     _ => {
                         entries.skip_attributes(abbrev.attributes())?;
@@ -485,11 +635,12 @@ impl<'dwarf> Function<'dwarf> {
 }
 
 impl<'dwarf> InlinedFunction<'dwarf> {
-    #[allow(clippy::too_many_arguments)]
+    /// Parse the attributes of a `DW_TAG_inlined_subroutine` entry.
+    ///
+    /// Children are not parsed and are left for the caller to handle.
     fn parse(
         entries: &mut gimli::EntriesRaw<'_, '_, R<'dwarf>>,
         abbrev: &gimli::Abbreviation,
-        depth: isize,
         unit: &gimli::Unit<R<'dwarf>>,
         units: &Units<'dwarf>,
         inlined_functions: &mut Vec<InlinedFunction<'dwarf>>,
@@ -498,6 +649,7 @@ impl<'dwarf> InlinedFunction<'dwarf> {
     ) -> Result<(), Error> {
         let mut ranges = RangeAttributes::default();
         let mut name = None;
+        let mut abstract_origin = None;
         let mut call_file = None;
         let mut call_line = 0;
         let mut call_column = 0;
@@ -509,7 +661,7 @@ impl<'dwarf> InlinedFunction<'dwarf> {
                         gimli::AttributeValue::DebugAddrIndex(index) => {
                             ranges.low_pc = Some(units.dwarf().address(unit, index)?);
                         }
-                        _ => {}
+                        _ => units.unsupported_form()?,
                     },
                     gimli::DW_AT_high_pc => match attr.value() {
                         gimli::AttributeValue::Addr(val) => ranges.high_pc = Some(val),
@@ -517,23 +669,26 @@ impl<'dwarf> InlinedFunction<'dwarf> {
                             ranges.high_pc = Some(units.dwarf().address(unit, index)?);
                         }
                         gimli::AttributeValue::Udata(val) => ranges.size = Some(val),
-                        _ => {}
+                        _ => units.unsupported_form()?,
                     },
                     gimli::DW_AT_ranges => {
                         ranges.ranges_offset =
                             units.dwarf().attr_ranges_offset(unit, attr.value())?;
                     }
                     gimli::DW_AT_linkage_name | gimli::DW_AT_MIPS_linkage_name => {
-                        if let Ok(val) = units.dwarf().attr_string(unit, attr.value()) {
+                        if let Some(val) = units.attr_string(unit, attr.value())? {
                             name = Some(val);
                         }
                     }
                     gimli::DW_AT_name => {
                         if name.is_none() {
-                            name = units.dwarf().attr_string(unit, attr.value()).ok();
+                            name = units.attr_string(unit, attr.value())?;
                         }
                     }
                     gimli::DW_AT_abstract_origin | gimli::DW_AT_specification => {
+                        if attr.name() == gimli::DW_AT_abstract_origin {
+                            abstract_origin = origin_attr(attr.value(), unit);
+                        }
                         if name.is_none() {
                             name = name_attr(attr.value(), unit, units, 16)?;
                         }
@@ -554,14 +709,18 @@ impl<'dwarf> InlinedFunction<'dwarf> {
                             if fi > 0 || unit.header.version() >= 5 {
                                 call_file = Some(fi);
                             }
+                        } else {
+                            let () = units.unsupported_form()?;
                         }
                     }
-                    gimli::DW_AT_call_line => {
-                        call_line = attr.udata_value().unwrap_or(0) as u32;
-                    }
-                    gimli::DW_AT_call_column => {
-                        call_column = attr.udata_value().unwrap_or(0) as u32;
-                    }
+                    gimli::DW_AT_call_line => match attr.udata_value() {
+                        Some(line) => call_line = line as u32,
+                        None => units.unsupported_form()?,
+                    },
+                    gimli::DW_AT_call_column => match attr.udata_value() {
+                        Some(column) => call_column = column as u32,
+                        None => units.unsupported_form()?,
+                    },
                     _ => {}
                 },
                 Err(e) => return Err(e),
@@ -571,6 +730,7 @@ impl<'dwarf> InlinedFunction<'dwarf> {
         let function_index = inlined_functions.len();
         inlined_functions.push(InlinedFunction {
             name,
+            abstract_origin,
             call_file,
             call_line,
             call_column,
@@ -583,16 +743,7 @@ impl<'dwarf> InlinedFunction<'dwarf> {
                 function: function_index,
             });
         })?;
-
-        Function::parse_children(
-            entries,
-            depth,
-            unit,
-            units,
-            inlined_functions,
-            inlined_addresses,
-            inlined_depth + 1,
-        )
+        Ok(())
     }
 }
 
@@ -618,7 +769,9 @@ mod tests {
         let func = Function {
             dw_die_offset: gimli::UnitOffset(24),
             name: None,
+            linkage_name: None,
             range: None,
+            abstract_origin: None,
             inlined_functions: OnceCell::new(),
         };
         assert_ne!(format!("{func:?}"), "");
@@ -626,6 +779,8 @@ mod tests {
         let funcs = Functions {
             functions: Box::default(),
             addresses: Box::default(),
+            labels: Box::default(),
+            origins: OnceCell::new(),
         };
         assert_ne!(format!("{funcs:?}"), "");
     }
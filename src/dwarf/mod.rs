@@ -1,10 +1,22 @@
+//! Functionality for working with DWARF debug information.
+
+mod cache;
+mod cfi;
+mod eh_frame;
 mod function;
 mod lines;
 mod location;
 mod range;
 mod reader;
 mod resolver;
+#[cfg(feature = "type-info")]
+mod type_info;
 mod unit;
 mod units;
 
-pub(crate) use self::resolver::DwarfResolver;
+pub use self::cfi::UnwindInfo;
+pub(crate) use self::eh_frame::EhFrameFns;
+pub use self::resolver::AbbreviationsCacheStrategy;
+pub use self::resolver::Builder;
+pub use self::resolver::Coverage;
+pub use self::resolver::DwarfResolver;
@@ -30,6 +30,11 @@ pub(crate) struct RangeAttributes<R: gimli::Reader> {
     pub(crate) low_pc: Option<u64>,
     pub(crate) high_pc: Option<u64>,
     pub(crate) size: Option<u64>,
+    /// The offset of the entity's range list, if any.
+    ///
+    /// For DWARF 5 this may originate from a `DW_FORM_rnglistx` index,
+    /// which has already been resolved against the unit's
+    /// `DW_AT_rnglists_base` at this point.
     pub(crate) ranges_offset: Option<gimli::RangeListsOffset<R::Offset>>,
 }
 
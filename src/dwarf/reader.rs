@@ -1,18 +1,16 @@
 use gimli::EndianSlice;
+use gimli::RunTimeEndian;
 use gimli::SectionId;
 
 use crate::elf::ElfParser;
+#[cfg(feature = "macho")]
+use crate::macho::MachOParser;
 use crate::Result;
 
 
-#[cfg(target_endian = "little")]
-type Endianess = gimli::LittleEndian;
-#[cfg(target_endian = "big")]
-type Endianess = gimli::BigEndian;
-
 /// The gimli reader type we currently use. Could be made generic if
 /// need be, but we keep things simple while we can.
-pub(crate) type R<'dat> = EndianSlice<'dat, Endianess>;
+pub(crate) type R<'dat> = EndianSlice<'dat, RunTimeEndian>;
 
 
 pub(super) fn load_section(parser: &ElfParser, id: SectionId) -> Result<R<'_>> {
@@ -22,10 +20,23 @@ pub(super) fn load_section(parser: &ElfParser, id: SectionId) -> Result<R<'_>> {
         // Make sure to return empty data if a section does not exist.
         None => &[],
     };
+    let endian = if parser.is_big_endian()? {
+        RunTimeEndian::Big
+    } else {
+        RunTimeEndian::Little
+    };
+    Ok(reader(data, endian))
+}
+
+#[cfg(feature = "macho")]
+pub(super) fn load_macho_section(parser: &MachOParser, id: SectionId) -> Result<R<'_>> {
+    // Make sure to return empty data if a section does not exist.
+    let data = parser.dwarf_section_data(id.name()).unwrap_or(&[]);
+    Ok(reader(data, RunTimeEndian::default()))
+}
 
-    #[cfg(target_endian = "little")]
-    let reader = EndianSlice::new(data, gimli::LittleEndian);
-    #[cfg(target_endian = "big")]
-    let reader = EndianSlice::new(data, gimli::BigEndian);
-    Ok(reader)
+/// Create a reader for the provided section data, interpreting it
+/// using the given byte order.
+pub(super) fn reader(data: &[u8], endian: RunTimeEndian) -> R<'_> {
+    EndianSlice::new(data, endian)
 }
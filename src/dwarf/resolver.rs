@@ -7,21 +7,32 @@ use std::fmt::Result as FmtResult;
 use std::mem;
 use std::mem::swap;
 use std::ops::Deref as _;
-#[cfg(test)]
+use std::ops::Range;
 use std::path::Path;
 use std::rc::Rc;
 
-use gimli::AbbreviationsCacheStrategy;
 use gimli::Dwarf;
+use gimli::RunTimeEndian;
+use gimli::SectionId;
 
+use crate::elf::types::SHF_EXECINSTR;
+use crate::elf::types::SHT_NOBITS;
 use crate::elf::ElfParser;
 use crate::error::IntoCowStr;
 use crate::inspect::FindAddrOpts;
 use crate::inspect::Inspect;
 use crate::inspect::SymInfo;
+#[cfg(feature = "type-info")]
+use crate::inspect::TypeInfo;
+use crate::inspect::UnitInfo;
+use crate::log::debug;
+use crate::log::warn;
+#[cfg(feature = "macho")]
+use crate::macho::MachOParser;
 use crate::symbolize::CodeInfo;
 use crate::symbolize::FindSymOpts;
 use crate::symbolize::InlinedFn;
+use crate::symbolize::Provenance;
 use crate::symbolize::Reason;
 use crate::symbolize::ResolvedSym;
 use crate::symbolize::SrcLang;
@@ -32,9 +43,14 @@ use crate::ErrorExt;
 use crate::Result;
 use crate::SymType;
 
+use super::cache;
+use super::cache::CacheKey;
+use super::cfi::CfiUnwinder;
+use super::cfi::UnwindInfo;
 use super::function::Function;
 use super::location::Location;
 use super::reader;
+use super::reader::R;
 use super::unit::Unit;
 use super::units::Units;
 
@@ -67,51 +83,386 @@ impl From<Option<gimli::DwLang>> for SrcLang {
                 gimli::DW_LANG_C_plus_plus
                 | gimli::DW_LANG_C_plus_plus_03
                 | gimli::DW_LANG_C_plus_plus_11
-                | gimli::DW_LANG_C_plus_plus_14,
+                | gimli::DW_LANG_C_plus_plus_14
+                | gimli::DW_LANG_C_plus_plus_17
+                | gimli::DW_LANG_C_plus_plus_20,
             ) => SrcLang::Cpp,
+            Some(
+                gimli::DW_LANG_C
+                | gimli::DW_LANG_C89
+                | gimli::DW_LANG_C99
+                | gimli::DW_LANG_C11
+                | gimli::DW_LANG_C17,
+            ) => SrcLang::C,
+            Some(gimli::DW_LANG_Go) => SrcLang::Go,
+            Some(gimli::DW_LANG_Swift) => SrcLang::Swift,
+            Some(gimli::DW_LANG_Zig) => SrcLang::Zig,
             _ => SrcLang::Unknown,
         }
     }
 }
 
 
+/// Calculate the number of bytes of `text` covered by any of the
+/// provided `ranges`.
+///
+/// `text` is expected to contain non-overlapping ranges, whereas
+/// `ranges` may overlap arbitrarily.
+fn covered_bytes(text: &[Range<u64>], mut ranges: Vec<gimli::Range>) -> u64 {
+    let () = ranges.sort_by_key(|range| range.begin);
+
+    // Merge overlapping and adjacent ranges, so that we don't count
+    // any byte twice.
+    let mut merged = Vec::<Range<u64>>::with_capacity(ranges.len());
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.begin <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range.begin..range.end),
+        }
+    }
+
+    merged
+        .iter()
+        .map(|range| {
+            text.iter()
+                .map(|text| {
+                    let begin = range.start.max(text.start);
+                    let end = range.end.min(text.end);
+                    end.saturating_sub(begin)
+                })
+                .sum::<u64>()
+        })
+        .sum()
+}
+
+
+/// Statistics about how much of a binary's code is covered by debug
+/// information.
+///
+/// Objects of this type are created by [`DwarfResolver::coverage`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Coverage {
+    /// The number of bytes in executable sections.
+    pub text_bytes: u64,
+    /// The number of bytes in executable sections for which line
+    /// information is available.
+    pub line_covered_bytes: u64,
+    /// The number of bytes in executable sections that are attributed
+    /// to a function.
+    pub func_covered_bytes: u64,
+    /// The struct is non-exhaustive and open to extension.
+    #[doc(hidden)]
+    pub _non_exhaustive: (),
+}
+
+
+/// The strategy for caching DWARF abbreviation tables.
+///
+/// Each compilation unit references a table of abbreviations that
+/// describes the layout of its debug information entries. Cached
+/// tables are parsed once and shared by all units referencing them.
+/// Tables that are not cached are parsed separately for each unit
+/// when it is first used.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum AbbreviationsCacheStrategy {
+    /// Cache only tables that are referenced by more than one
+    /// compilation unit.
+    #[default]
+    Duplicates,
+    /// Cache all tables.
+    All,
+}
+
+impl From<AbbreviationsCacheStrategy> for gimli::AbbreviationsCacheStrategy {
+    fn from(strategy: AbbreviationsCacheStrategy) -> Self {
+        match strategy {
+            AbbreviationsCacheStrategy::Duplicates => Self::Duplicates,
+            AbbreviationsCacheStrategy::All => Self::All,
+        }
+    }
+}
+
+
+/// A builder for configurable construction of [`DwarfResolver`]
+/// objects.
+#[derive(Clone, Debug, Default)]
+pub struct Builder {
+    /// The strategy to use for caching abbreviation tables.
+    abbrev_cache_strategy: AbbreviationsCacheStrategy,
+    /// Whether to report DWARF data that can't be interpreted as an
+    /// error.
+    strict: bool,
+}
+
+impl Builder {
+    /// Set the strategy to use for caching abbreviation tables.
+    ///
+    /// By default, only tables referenced by multiple compilation
+    /// units are cached ([`AbbreviationsCacheStrategy::Duplicates`]).
+    /// All other tables are parsed on demand, once the unit
+    /// referencing them is used, and never if it is not.
+    ///
+    /// With [`AbbreviationsCacheStrategy::All`], all tables are
+    /// parsed eagerly as part of creating the resolver. That increases
+    /// up-front cost as well as memory usage for tables belonging to
+    /// units that ultimately are never consulted, but avoids any
+    /// parsing of abbreviations later on. It hence may pay off if a
+    /// large fraction of units is going to be used, such as when
+    /// symbolizing addresses scattered all over a binary or when
+    /// iterating over all symbols. Binaries in which tables are
+    /// generally shared between units (as can be the case for those
+    /// built with link-time optimization) see little difference
+    /// between the two strategies.
+    pub fn set_abbreviations_cache_strategy(
+        mut self,
+        strategy: AbbreviationsCacheStrategy,
+    ) -> Self {
+        self.abbrev_cache_strategy = strategy;
+        self
+    }
+
+    /// Enable/disable strict mode.
+    ///
+    /// By default, DWARF data that can't be interpreted, such as
+    /// attributes of unsupported forms or malformed line programs, is
+    /// ignored on a best-effort basis, possibly leading to partial or
+    /// empty results. In strict mode, such data causes an error of
+    /// kind [`ErrorKind::InvalidDwarf`][crate::ErrorKind::InvalidDwarf]
+    /// to be reported instead. Strict mode is meant for validating
+    /// the output of a toolchain, for example.
+    ///
+    /// Problems concerning the entirety of the debug information, such
+    /// as compilation units of unexpected kind or line programs not
+    /// matching the DWARF version of their unit, are reported when the
+    /// resolver is created. All others surface when the affected data
+    /// is first accessed.
+    pub fn set_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Create a [`DwarfResolver`] using the DWARF information contained
+    /// in the ELF file represented by the provided [`ElfParser`].
+    pub fn build_from_parser(self, parser: Rc<ElfParser>) -> Result<DwarfResolver> {
+        DwarfResolver::from_parser_impl(parser, None, &self)
+    }
+
+    /// Create a [`DwarfResolver`] from DWARF sections supplied by the
+    /// provided `loader`.
+    ///
+    /// Please refer to [`DwarfResolver::from_sections`] for details.
+    pub fn build_from_sections<F>(self, loader: F) -> Result<DwarfResolver>
+    where
+        F: FnMut(&str) -> Result<Option<Vec<u8>>>,
+    {
+        DwarfResolver::from_sections_impl(loader, &self)
+    }
+}
+
+
 /// DwarfResolver provides abilities to query DWARF information of binaries.
-pub(crate) struct DwarfResolver {
+pub struct DwarfResolver {
     /// The lazily parsed compilation units of the DWARF file.
     // SAFETY: We must not hand out references with a 'static lifetime to
     //         this member. Rather, they should never outlive `self`.
     //         Furthermore, this member has to be listed before `parser`
-    //         to make sure we never end up with a dangling reference.
+    //         and `sections` to make sure we never end up with a
+    //         dangling reference.
     units: Units<'static>,
-    parser: Rc<ElfParser>,
+    /// The ELF file containing the debug information, if any.
+    parser: Option<Rc<ElfParser>>,
+    /// Whether to consult the ELF file's symbols for addresses not
+    /// covered by any DWARF function.
+    elf_sym_fallback: bool,
+    /// Debug section data owned by the resolver, if it was created from
+    /// user supplied sections.
+    _sections: Box<[Box<[u8]>]>,
+    /// The Mach-O file containing the debug information, if any.
+    #[cfg(feature = "macho")]
+    _macho: Option<Rc<MachOParser>>,
 }
 
 impl DwarfResolver {
-    /// Retrieve the resolver's underlying `ElfParser`.
-    pub fn parser(&self) -> &Rc<ElfParser> {
-        &self.parser
+    /// Retrieve the resolver's underlying `ElfParser`, if any.
+    ///
+    /// A resolver created using [`DwarfResolver::from_sections`] is not
+    /// backed by an ELF file.
+    pub fn parser(&self) -> Option<&Rc<ElfParser>> {
+        self.parser.as_ref()
+    }
+
+    /// Retrieve a [`Builder`] object for configurable construction of
+    /// a [`DwarfResolver`].
+    #[inline]
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+
+    fn from_dwarf(
+        mut dwarf: Dwarf<R<'static>>,
+        parser: Option<Rc<ElfParser>>,
+        sections: Box<[Box<[u8]>]>,
+        cache_dir: Option<&Path>,
+        builder: &Builder,
+    ) -> Result<Self> {
+        let Builder {
+            abbrev_cache_strategy,
+            strict,
+        } = builder;
+
+        // Cache abbreviations (which will cause them to be
+        // automatically reused across compilation units), which can
+        // speed up parsing of debug information potentially
+        // dramatically, depending on debug information layout and how
+        // much effort the linker spent on optimizing it.
+        let () = dwarf.populate_abbreviations_cache((*abbrev_cache_strategy).into());
+
+        // Only files on disk can be identified reliably and, hence, be
+        // cached. For everything else we silently fall back to parsing.
+        // In strict mode we always parse, because only doing so
+        // validates the debug information.
+        let cache = cache_dir
+            .filter(|_| !strict)
+            .zip(parser.as_deref())
+            .and_then(|(dir, parser)| match CacheKey::new(parser) {
+                Ok(key) => Some((dir, key)),
+                Err(err) => {
+                    debug!("not caching DWARF unit index: {err}");
+                    None
+                }
+            });
+
+        let units = match cache.as_ref().and_then(|(dir, key)| cache::load(dir, key)) {
+            Some(index) => Units::from_index(dwarf, index)?,
+            None => {
+                let units = Units::parse(dwarf, *strict)?;
+                if let Some((dir, key)) = &cache {
+                    if let Err(err) = cache::store(dir, key, &units.index()) {
+                        warn!("failed to store DWARF unit index in cache: {err}");
+                    }
+                }
+                units
+            }
+        };
+        let slf = Self {
+            units,
+            parser,
+            elf_sym_fallback: true,
+            _sections: sections,
+            #[cfg(feature = "macho")]
+            _macho: None,
+        };
+        Ok(slf)
     }
 
+    /// Create a `DwarfResolver` using the DWARF information contained in
+    /// the ELF file represented by the provided [`ElfParser`].
     pub fn from_parser(parser: Rc<ElfParser>) -> Result<Self, Error> {
+        Self::from_parser_with_cache(parser, None, false)
+    }
+
+    /// Create a `DwarfResolver` for the ELF file represented by the
+    /// provided [`ElfParser`], consulting and populating the on-disk
+    /// cache in `cache_dir`, if provided.
+    ///
+    /// Please refer to [`Builder::set_strict`] for details on `strict`.
+    pub(crate) fn from_parser_with_cache(
+        parser: Rc<ElfParser>,
+        cache_dir: Option<&Path>,
+        strict: bool,
+    ) -> Result<Self> {
+        let builder = Builder::default().set_strict(strict);
+        Self::from_parser_impl(parser, cache_dir, &builder)
+    }
+
+    fn from_parser_impl(
+        parser: Rc<ElfParser>,
+        cache_dir: Option<&Path>,
+        builder: &Builder,
+    ) -> Result<Self> {
         // SAFETY: We own the `ElfParser` and make sure that it stays
         //         around while the `Units` object uses it. As such, it
         //         is fine to conjure a 'static lifetime here.
         let static_parser =
             unsafe { mem::transmute::<&ElfParser, &'static ElfParser>(parser.deref()) };
         let mut load_section = |section| reader::load_section(static_parser, section);
-        let mut dwarf = Dwarf::load(&mut load_section)?;
-        // Cache abbreviations (which will cause them to be
-        // automatically reused across compilation units), which can
-        // speed up parsing of debug information potentially
-        // dramatically, depending on debug information layout and how
-        // much effort the linker spent on optimizing it.
-        let () = dwarf.populate_abbreviations_cache(AbbreviationsCacheStrategy::Duplicates);
+        let dwarf = Dwarf::load(&mut load_section)?;
+        Self::from_dwarf(dwarf, Some(parser), Box::default(), cache_dir, builder)
+    }
 
-        let units = Units::parse(dwarf)?;
-        let slf = Self { units, parser };
+    /// Create a `DwarfResolver` for a standalone debug information
+    /// file, such as one produced by `objcopy --only-keep-debug`.
+    ///
+    /// Contrary to [`DwarfResolver::from_parser`], the ELF symbols of
+    /// the file are not consulted for addresses not covered by DWARF
+    /// function information.
+    pub(crate) fn from_debug_file(
+        parser: Rc<ElfParser>,
+        cache_dir: Option<&Path>,
+        strict: bool,
+    ) -> Result<Self> {
+        let mut slf = Self::from_parser_with_cache(parser, cache_dir, strict)?;
+        slf.elf_sym_fallback = false;
+        Ok(slf)
+    }
+
+    /// Create a `DwarfResolver` using the DWARF information contained in
+    /// the `__DWARF` segment of the Mach-O file represented by the
+    /// provided [`MachOParser`].
+    #[cfg(feature = "macho")]
+    pub(crate) fn from_macho(parser: Rc<MachOParser>) -> Result<Self> {
+        // SAFETY: We own the `MachOParser` and make sure that it stays
+        //         around while the `Units` object uses it. As such, it
+        //         is fine to conjure a 'static lifetime here.
+        let static_parser =
+            unsafe { mem::transmute::<&MachOParser, &'static MachOParser>(parser.deref()) };
+        let mut load_section = |section| reader::load_macho_section(static_parser, section);
+        let dwarf = Dwarf::load(&mut load_section)?;
+        let mut slf = Self::from_dwarf(dwarf, None, Box::default(), None, &Builder::default())?;
+        slf._macho = Some(parser);
         Ok(slf)
     }
 
+    /// Create a `DwarfResolver` from DWARF sections supplied by the
+    /// provided `loader`.
+    ///
+    /// `loader` is invoked with the name of each DWARF section of
+    /// interest (e.g., `.debug_info` or `.debug_line`) and should return
+    /// the section's data, or `None` if the section is not available.
+    ///
+    /// Because no ELF file backs the resolver, ELF symbols are not
+    /// consulted during symbolization and file offsets are not
+    /// reported.
+    pub fn from_sections<F>(loader: F) -> Result<Self>
+    where
+        F: FnMut(&str) -> Result<Option<Vec<u8>>>,
+    {
+        Self::from_sections_impl(loader, &Builder::default())
+    }
+
+    fn from_sections_impl<F>(mut loader: F, builder: &Builder) -> Result<Self>
+    where
+        F: FnMut(&str) -> Result<Option<Vec<u8>>>,
+    {
+        let mut sections = Vec::new();
+        let mut load_section = |id: SectionId| -> Result<R<'static>> {
+            let data = loader(id.name())
+                .with_context(|| format!("failed to load section `{}`", id.name()))?
+                .unwrap_or_default()
+                .into_boxed_slice();
+            // SAFETY: The section data is heap allocated and we keep it
+            //         around, unmodified, for as long as the `Units`
+            //         object uses it. As such, it is fine to conjure a
+            //         'static lifetime here.
+            let static_data = unsafe { mem::transmute::<&[u8], &'static [u8]>(data.deref()) };
+            let () = sections.push(data);
+            Ok(reader::reader(static_data, RunTimeEndian::default()))
+        };
+        let dwarf = Dwarf::load(&mut load_section)?;
+        Self::from_dwarf(dwarf, None, sections.into_boxed_slice(), None, builder)
+    }
+
     /// Open a binary to load and parse .debug_line for later uses.
     ///
     /// `filename` is the name of an ELF binary/or shared object that
@@ -121,6 +472,163 @@ impl DwarfResolver {
         let parser = ElfParser::open(filename)?;
         Self::from_parser(Rc::new(parser))
     }
+
+    /// Find the address of the first instruction attributed to the
+    /// given source file and line.
+    ///
+    /// `file` may be a full path or a relative one, in which case it
+    /// matches all source files whose path ends with it (e.g.,
+    /// `src/main.c` matches `/build/project/src/main.c`). If multiple
+    /// addresses map to the line, for example because it got inlined,
+    /// the lowest one is reported.
+    pub fn find_line_info(&self, file: &Path, line: u32) -> Result<Option<Addr>> {
+        let addr = self.units.find_line_addr(file, line)?;
+        Ok(addr)
+    }
+
+    /// Invoke `f` for each unique source file that contributed to the
+    /// binary, as recorded in the compilation units' names and line
+    /// program file tables.
+    pub(crate) fn for_each_source_file(&self, f: &mut dyn FnMut(&Path)) -> Result<()> {
+        let () = self.units.for_each_source_file(f)?;
+        Ok(())
+    }
+
+    /// Retrieve the chain of functions inlined at `addr`, outermost
+    /// first, without the enclosing (non-inlined) function.
+    ///
+    /// The result corresponds to
+    /// [`Sym::inlined`][crate::symbolize::Sym::inlined] as reported when
+    /// symbolizing `addr` with inlined function reporting enabled, with
+    /// each function being reported along with the source code location
+    /// inside of it. However, the enclosing symbol is not looked up, making
+    /// this method cheaper for analyses only interested in inlining.
+    ///
+    /// Addresses not belonging to any function have no inlined
+    /// functions.
+    pub fn inlined_at(&self, addr: Addr) -> Result<Vec<(String, Option<CodeInfo<'_>>)>> {
+        let (function, unit) = match self.units.find_function(addr)? {
+            Some(data) => data,
+            None => return Ok(Vec::new()),
+        };
+        let opts = FindSymOpts::CODE_INFO_AND_INLINED;
+        let mut direct_code_info = self
+            .units
+            .find_location(addr)?
+            .map(|(location, unit)| code_info(location, unit, &self.units, &opts))
+            .transpose()?;
+        let inlined = self
+            .units
+            .inlined_fns(addr, function, unit, &mut direct_code_info, &opts)?;
+        let inlined = inlined
+            .into_iter()
+            .map(|inlined_fn| (inlined_fn.name.into_owned(), inlined_fn.code_info))
+            .collect();
+        Ok(inlined)
+    }
+
+    /// Retrieve information about the compilation unit containing the
+    /// code at `addr`.
+    ///
+    /// `None` is returned if no unit covers the address.
+    pub fn unit_info(&self, addr: Addr) -> Result<Option<UnitInfo>> {
+        let unit = match self.units.find_unit_by_addr(addr)? {
+            Some(unit) => unit,
+            None => return Ok(None),
+        };
+        let producer = unit
+            .producer(&self.units)?
+            .map(|producer| String::from_utf8_lossy(producer.slice()).into_owned());
+        let language = unit.language();
+        let info = UnitInfo {
+            path: unit.path(&self.units)?,
+            producer,
+            lang: language.into(),
+            language: language.map(|language| language.0),
+            comp_dir: unit.comp_dir(&self.units)?.map(Path::to_path_buf),
+            version: unit.header().version(),
+            _non_exhaustive: (),
+        };
+        Ok(Some(info))
+    }
+
+    /// Retrieve the number of inlined functions covering `addr`.
+    ///
+    /// This number corresponds to the length of
+    /// [`Sym::inlined`][crate::symbolize::Sym::inlined] as reported
+    /// when symbolizing `addr` with inlined function reporting
+    /// enabled. However, no names or source code locations are looked
+    /// up, making this method considerably cheaper for use cases only
+    /// interested in the inline depth.
+    ///
+    /// Addresses not belonging to any function have an inline depth of
+    /// zero.
+    pub fn inlined_fn_count(&self, addr: Addr) -> Result<usize> {
+        let count = match self.units.find_function(addr)? {
+            Some((function, unit)) => self.units.count_inlined_functions(addr, function, unit)?,
+            None => 0,
+        };
+        Ok(count)
+    }
+
+    /// Look up information about the type with the given name.
+    ///
+    /// Structs, unions, enums, primitive types, and typedefs are
+    /// considered. If multiple types share the name, the first one
+    /// encountered is reported.
+    #[cfg(feature = "type-info")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "type-info")))]
+    pub fn type_info(&self, name: &str) -> Result<Option<TypeInfo>> {
+        let info = self.units.find_type(name)?;
+        Ok(info)
+    }
+
+    /// Calculate how much of the binary's code is covered by line
+    /// information and by functions.
+    ///
+    /// Coverage is determined by intersecting the address ranges of
+    /// line number programs and functions with those of executable
+    /// sections.
+    ///
+    /// Coverage can only be calculated for resolvers backed by an ELF
+    /// file.
+    pub fn coverage(&self) -> Result<Coverage> {
+        let parser = self.parser.as_ref().ok_or_else(|| {
+            Error::with_unsupported("coverage calculation requires ELF section information")
+        })?;
+        let text = parser
+            .section_headers()?
+            .iter()
+            .filter(|shdr| shdr.sh_flags & SHF_EXECINSTR != 0 && shdr.sh_type != SHT_NOBITS)
+            .map(|shdr| shdr.sh_addr..shdr.sh_addr + shdr.sh_size)
+            .collect::<Vec<_>>();
+        let (line_ranges, func_ranges) = self.units.coverage_ranges()?;
+
+        let coverage = Coverage {
+            text_bytes: text.iter().map(|range| range.end - range.start).sum(),
+            line_covered_bytes: covered_bytes(&text, line_ranges),
+            func_covered_bytes: covered_bytes(&text, func_ranges),
+            _non_exhaustive: (),
+        };
+        Ok(coverage)
+    }
+
+    /// Retrieve the unwind information for the instruction at `addr`,
+    /// as derived from the `.eh_frame` call frame information of the
+    /// ELF file.
+    ///
+    /// `None` is returned if no call frame information covers the
+    /// address. Unwind information can only be retrieved for resolvers
+    /// backed by an ELF file.
+    pub fn unwind_info_at(&self, addr: Addr) -> Result<Option<UnwindInfo>> {
+        let parser = self.parser.as_ref().ok_or_else(|| {
+            Error::with_unsupported("unwind information retrieval requires an ELF file")
+        })?;
+        match CfiUnwinder::new(parser)? {
+            Some(unwinder) => unwinder.unwind_info(addr),
+            None => Ok(None),
+        }
+    }
 }
 
 impl Symbolize for DwarfResolver {
@@ -132,6 +640,10 @@ impl Symbolize for DwarfResolver {
                 .map(|name| name.to_string())
                 .transpose()?
                 .unwrap_or("");
+            let linkage_name = function
+                .linkage_name
+                .map(|name| name.to_string())
+                .transpose()?;
             let fn_addr = function.range.map(|range| range.begin).unwrap_or(0);
             let size = function
                 .range
@@ -143,6 +655,13 @@ impl Symbolize for DwarfResolver {
                 lang: unit.language().into(),
                 code_info: None,
                 inlined: Box::new([]),
+                provenance: Provenance::Symbol,
+                is_synthetic: false,
+                raw_elf_sym: None,
+                compilation_unit: unit.path(&self.units)?,
+                version: None,
+                section: None,
+                linkage_name,
             }
         } else {
             // Fall back to checking ELF for the symbol corresponding to
@@ -153,9 +672,68 @@ impl Symbolize for DwarfResolver {
             // as a fall back we support cases where ELF *does* contain
             // symbol, and we amend its information with the source code
             // information from DWARF.
-            match self.parser.find_sym(addr, opts)? {
+            let result =
+                if let Some(parser) = self.parser.as_ref().filter(|_| self.elf_sym_fallback) {
+                    parser.find_sym(addr, opts)?
+                } else {
+                    Err(Reason::unknown_addr(addr, self.units.addr_bounds()))
+                };
+
+            match result {
                 Ok(sym) => sym,
-                Err(reason) => return Ok(Err(reason)),
+                Err(reason) => {
+                    // The line table fallback requires code information
+                    // and we don't want to touch the line table if none
+                    // was asked for.
+                    if !opts.code_info() || !opts.line_table_fallback() {
+                        return Ok(Err(reason))
+                    }
+
+                    // If asked to, attribute the address to a source
+                    // code location via the line table, even if no
+                    // symbol covers it (e.g., because it belongs to
+                    // compiler generated code). A `DW_TAG_label`
+                    // outside of any function preceding the address,
+                    // if any, provides the symbol name.
+                    let mut sym = if let Some((label, unit)) = self.units.find_label(addr)? {
+                        ResolvedSym {
+                            name: label.name.to_string()?,
+                            addr: label.addr,
+                            size: None,
+                            lang: unit.language().into(),
+                            code_info: None,
+                            inlined: Box::new([]),
+                            provenance: Provenance::Label,
+                            is_synthetic: true,
+                            raw_elf_sym: None,
+                            compilation_unit: unit.path(&self.units)?,
+                            version: None,
+                            section: None,
+                            linkage_name: None,
+                        }
+                    } else {
+                        ResolvedSym {
+                            name: "",
+                            addr,
+                            size: None,
+                            lang: SrcLang::Unknown,
+                            code_info: None,
+                            inlined: Box::new([]),
+                            provenance: Provenance::LineTable,
+                            is_synthetic: true,
+                            raw_elf_sym: None,
+                            compilation_unit: None,
+                            version: None,
+                            section: None,
+                            linkage_name: None,
+                        }
+                    };
+                    let () = self.units.fill_code_info(&mut sym, addr, opts, None)?;
+                    if sym.code_info.is_none() {
+                        return Ok(Err(reason))
+                    }
+                    return Ok(Ok(sym))
+                }
             }
         };
 
@@ -165,52 +743,103 @@ impl Symbolize for DwarfResolver {
     }
 }
 
+impl DwarfResolver {
+    /// Convert a [`Function`] found by name into a [`SymInfo`].
+    fn function_sym_info<'slf>(
+        &'slf self,
+        function: &'slf Function<'_>,
+        opts: &FindAddrOpts,
+    ) -> Result<SymInfo<'slf>> {
+        // SANITY: We found the function by name, so it must have the
+        //         name attribute set.
+        let name = function.name.unwrap().to_string().unwrap();
+        let addr = function
+            .range
+            .as_ref()
+            .map(|range| range.begin as Addr)
+            .unwrap_or(0);
+        let size = function
+            .range
+            .as_ref()
+            .and_then(|range| range.end.checked_sub(range.begin))
+            .map(|size| usize::try_from(size).unwrap_or(usize::MAX))
+            .unwrap_or(0);
+        let info = SymInfo {
+            name: Cow::Borrowed(name),
+            addr,
+            size,
+            sym_type: SymType::Function,
+            binding: None,
+            version: None,
+            is_ifunc: false,
+            file_offset: self
+                .parser
+                .as_ref()
+                .filter(|_parser| opts.offset_in_file)
+                .map(|parser| parser.find_file_offset(addr))
+                .transpose()?
+                .flatten(),
+            obj_file_name: self
+                .parser
+                .as_ref()
+                .map(|parser| Cow::Borrowed(parser.path())),
+        };
+        Ok(info)
+    }
+}
+
 impl Inspect for DwarfResolver {
     /// Find information about a symbol given its name.
     ///
     /// # Notes
     /// - lookup of variables is not currently supported
     fn find_addr<'slf>(&'slf self, name: &str, opts: &FindAddrOpts) -> Result<Vec<SymInfo<'slf>>> {
-        if let SymType::Variable = opts.sym_type {
+        if let SymType::Variable | SymType::Tls = opts.sym_type {
             return Err(Error::with_unsupported("not implemented"))
         }
 
+        // We don't track symbol binding information for DWARF data.
+        if opts.binding.is_some() {
+            return Ok(Vec::new())
+        }
+
         let syms = self
             .units
             .find_name(name)
-            .map(|result| {
-                match result {
-                    Ok(function) => {
-                        // SANITY: We found the function by name, so it must have the
-                        //         name attribute set.
-                        let name = function.name.unwrap().to_string().unwrap();
-                        let addr = function
-                            .range
-                            .as_ref()
-                            .map(|range| range.begin as Addr)
-                            .unwrap_or(0);
-                        let size = function
-                            .range
-                            .as_ref()
-                            .and_then(|range| range.end.checked_sub(range.begin))
-                            .map(|size| usize::try_from(size).unwrap_or(usize::MAX))
-                            .unwrap_or(0);
-                        let info = SymInfo {
-                            name: Cow::Borrowed(name),
-                            addr,
-                            size,
-                            sym_type: SymType::Function,
-                            file_offset: opts
-                                .offset_in_file
-                                .then(|| self.parser.find_file_offset(addr))
-                                .transpose()?
-                                .flatten(),
-                            obj_file_name: Some(Cow::Borrowed(self.parser.path())),
-                        };
-                        Ok(info)
-                    }
-                    Err(err) => Err(Error::from(err)),
-                }
+            .map(|result| match result {
+                Ok(function) => self.function_sym_info(function, opts),
+                Err(err) => Err(Error::from(err)),
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(syms)
+    }
+
+    /// Find information about symbols given their names, walking the
+    /// DWARF compilation units only once.
+    fn find_addrs<'slf>(
+        &'slf self,
+        names: &[&str],
+        opts: &FindAddrOpts,
+    ) -> Result<Vec<Vec<SymInfo<'slf>>>> {
+        if let SymType::Variable | SymType::Tls = opts.sym_type {
+            return Err(Error::with_unsupported("not implemented"))
+        }
+
+        // We don't track symbol binding information for DWARF data.
+        if opts.binding.is_some() {
+            return Ok(vec![Vec::new(); names.len()])
+        }
+
+        let syms = self
+            .units
+            .find_names(names)?
+            .into_iter()
+            .map(|functions| {
+                functions
+                    .into_iter()
+                    .map(|function| self.function_sym_info(function, opts))
+                    .collect::<Result<Vec<_>>>()
             })
             .collect::<Result<Vec<_>>>()?;
 
@@ -251,71 +880,17 @@ impl<'dwarf> Units<'dwarf> {
             return Ok(())
         }
 
-        let direct_location = if let Some(direct_location) = self.find_location(addr)? {
-            direct_location
-        } else {
-            return Ok(())
-        };
-
-        let Location {
-            dir,
-            file,
-            line,
-            column,
-        } = direct_location;
-
-        let mut direct_code_info = CodeInfo {
-            dir: Some(Cow::Borrowed(dir)),
-            file: Cow::Borrowed(file),
-            line,
-            column: column.map(|col| col.try_into().unwrap_or(u16::MAX)),
-            _non_exhaustive: (),
-        };
+        let (direct_location, direct_unit) =
+            if let Some((direct_location, unit)) = self.find_location(addr)? {
+                (direct_location, unit)
+            } else {
+                return Ok(())
+            };
 
+        let mut direct_code_info = Some(code_info(direct_location, direct_unit, self, opts)?);
         let inlined = if opts.inlined_fns() {
             if let Some((function, unit)) = data {
-                if let Some(inline_stack) = self.find_inlined_functions(addr, function, unit)? {
-                    let mut inlined = Vec::<InlinedFn>::with_capacity(inline_stack.len());
-                    for result in inline_stack {
-                        let (name, location) = result?;
-                        let mut code_info = location.map(|location| {
-                            let Location {
-                                dir,
-                                file,
-                                line,
-                                column,
-                            } = location;
-
-                            CodeInfo {
-                                dir: Some(Cow::Borrowed(dir)),
-                                file: Cow::Borrowed(file),
-                                line,
-                                column: column.map(|col| col.try_into().unwrap_or(u16::MAX)),
-                                _non_exhaustive: (),
-                            }
-                        });
-
-                        // For each frame we need to move the code information
-                        // up by one layer.
-                        if let Some(ref mut last_code_info) =
-                            inlined.last_mut().map(|f| &mut f.code_info)
-                        {
-                            let () = swap(&mut code_info, last_code_info);
-                        } else if let Some(code_info) = &mut code_info {
-                            let () = swap(code_info, &mut direct_code_info);
-                        }
-
-                        let inlined_fn = InlinedFn {
-                            name: Cow::Borrowed(name),
-                            code_info,
-                            _non_exhaustive: (),
-                        };
-                        let () = inlined.push(inlined_fn);
-                    }
-                    inlined
-                } else {
-                    Vec::new()
-                }
+                self.inlined_fns(addr, function, unit, &mut direct_code_info, opts)?
             } else {
                 Vec::new()
             }
@@ -323,11 +898,103 @@ impl<'dwarf> Units<'dwarf> {
             Vec::new()
         };
 
-        sym.code_info = Some(direct_code_info);
+        sym.code_info = direct_code_info;
         sym.inlined = inlined.into_boxed_slice();
 
         Ok(())
     }
+
+    /// Collect the chain of functions inlined into `function` at
+    /// `addr`, outermost first.
+    ///
+    /// `direct_code_info` is the source code location of `addr`
+    /// itself, if known. As each inlined function is reported with the
+    /// location inside of it (as opposed to that of its call), code
+    /// information is moved up by one layer, with `direct_code_info`
+    /// being adjusted to refer to the call of the outermost inlined
+    /// function.
+    fn inlined_fns<'slf>(
+        &'slf self,
+        addr: Addr,
+        function: &'slf Function<'dwarf>,
+        unit: &'slf Unit<'dwarf>,
+        direct_code_info: &mut Option<CodeInfo<'slf>>,
+        opts: &FindSymOpts,
+    ) -> Result<Vec<InlinedFn<'slf>>> {
+        let inline_stack = match self.find_inlined_functions(
+            addr,
+            function,
+            unit,
+            opts.inlined_fn_origin_addrs(),
+        )? {
+            Some(inline_stack) => inline_stack,
+            None => return Ok(Vec::new()),
+        };
+
+        let mut inlined = Vec::<InlinedFn>::with_capacity(inline_stack.len());
+        for result in inline_stack {
+            let (name, location, inlined_addr, origin_addr) = result?;
+            let mut code_info = location
+                .map(|location| code_info(location, unit, self, opts))
+                .transpose()?;
+
+            // For each frame we need to move the code information
+            // up by one layer.
+            if let Some(ref mut last_code_info) = inlined.last_mut().map(|f| &mut f.code_info) {
+                let () = swap(&mut code_info, last_code_info);
+            } else if code_info.is_some() {
+                let () = swap(&mut code_info, direct_code_info);
+            }
+
+            let (inlined_addr, offset) = if opts.inlined_fn_addrs() {
+                let offset = usize::try_from(addr - inlined_addr).unwrap_or(usize::MAX);
+                (Some(inlined_addr), Some(offset))
+            } else {
+                (None, None)
+            };
+
+            let inlined_fn = InlinedFn {
+                name: Cow::Borrowed(name),
+                code_info,
+                addr: inlined_addr,
+                offset,
+                origin_addr,
+                _non_exhaustive: (),
+            };
+            let () = inlined.push(inlined_fn);
+        }
+        Ok(inlined)
+    }
+}
+
+
+/// Convert a [`Location`] belonging to `unit` into a [`CodeInfo`].
+fn code_info<'slf, 'dwarf: 'slf>(
+    location: Location<'slf>,
+    unit: &'slf Unit<'dwarf>,
+    units: &Units<'dwarf>,
+    opts: &FindSymOpts,
+) -> Result<CodeInfo<'slf>, gimli::Error> {
+    let Location {
+        dir,
+        file,
+        line,
+        column,
+    } = location;
+
+    let info = CodeInfo {
+        dir: Some(Cow::Borrowed(dir)),
+        file: Cow::Borrowed(file),
+        line,
+        column: column.map(|col| col.try_into().unwrap_or(u16::MAX)),
+        comp_dir: if opts.comp_dirs() {
+            unit.comp_dir(units)?.map(Cow::Borrowed)
+        } else {
+            None
+        },
+        _non_exhaustive: (),
+    };
+    Ok(info)
 }
 
 
@@ -341,6 +1008,7 @@ mod tests {
 
     use test_log::test;
 
+    use crate::inspect::SymBinding;
     use crate::ErrorKind;
 
 
@@ -367,6 +1035,27 @@ mod tests {
         assert_eq!(format!("{err:#}"), format!("failed to read: {inner}"));
     }
 
+    /// Check that we map DWARF language constants to the expected
+    /// `SrcLang` variants.
+    #[test]
+    fn language_mapping() {
+        assert_eq!(SrcLang::from(None), SrcLang::Unknown);
+        assert_eq!(SrcLang::from(Some(gimli::DW_LANG_Rust)), SrcLang::Rust);
+        assert_eq!(
+            SrcLang::from(Some(gimli::DW_LANG_C_plus_plus_17)),
+            SrcLang::Cpp
+        );
+        assert_eq!(SrcLang::from(Some(gimli::DW_LANG_C99)), SrcLang::C);
+        assert_eq!(SrcLang::from(Some(gimli::DW_LANG_C11)), SrcLang::C);
+        assert_eq!(SrcLang::from(Some(gimli::DW_LANG_Go)), SrcLang::Go);
+        assert_eq!(SrcLang::from(Some(gimli::DW_LANG_Swift)), SrcLang::Swift);
+        assert_eq!(SrcLang::from(Some(gimli::DW_LANG_Zig)), SrcLang::Zig);
+        assert_eq!(
+            SrcLang::from(Some(gimli::DW_LANG_Fortran90)),
+            SrcLang::Unknown
+        );
+    }
+
     /// Check that we can find the source code location of an address.
     #[test]
     fn source_location_finding() {
@@ -375,16 +1064,404 @@ mod tests {
             .join("test-stable-addrs.bin");
         let resolver = DwarfResolver::open(bin_name.as_ref()).unwrap();
 
+        let sym = resolver
+            .find_sym(0x2000100, &FindSymOpts::CODE_INFO)
+            .unwrap()
+            .unwrap();
+        assert_eq!(sym.lang, SrcLang::C);
+        let info = sym.code_info.unwrap();
+        assert_ne!(info.dir, Some(Cow::Owned(PathBuf::new())));
+        assert_eq!(info.file, OsStr::new("test-stable-addrs.c"));
+        assert_eq!(info.line, Some(10));
+        assert!(info.column.is_some());
+    }
+
+    /// Make sure that the abbreviations cache strategy does not affect
+    /// symbolization results.
+    #[test]
+    fn abbrev_cache_strategies() {
+        let bin_name = Path::new(&env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join("test-stable-addrs.bin");
+        let parser = Rc::new(ElfParser::open(&bin_name).unwrap());
+
+        let syms = [
+            AbbreviationsCacheStrategy::Duplicates,
+            AbbreviationsCacheStrategy::All,
+        ]
+        .map(|strategy| {
+            let resolver = DwarfResolver::builder()
+                .set_abbreviations_cache_strategy(strategy)
+                .build_from_parser(parser.clone())
+                .unwrap();
+            let sym = resolver
+                .find_sym(0x200020a, &FindSymOpts::CODE_INFO_AND_INLINED)
+                .unwrap()
+                .unwrap();
+            assert_eq!(sym.name, "factorial_inline_test");
+            assert_eq!(sym.inlined.len(), 2);
+            format!("{sym:?}")
+        });
+        assert_eq!(syms[0], syms[1]);
+    }
+
+    /// Check that strict mode accepts valid debug information, but
+    /// reports DWARF data that we can't interpret.
+    #[test]
+    fn strict_mode() {
+        for bin in [
+            "test-stable-addrs.bin",
+            "test-stable-addrs-lto.bin",
+            "test-dwarf-v2.bin",
+            "test-dwarf-v3.bin",
+            "test-dwarf-v4.bin",
+            "test-dwarf-v5.bin",
+            "test-deep-inline.bin",
+            "test-rs.bin",
+        ] {
+            let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+                .join("data")
+                .join(bin);
+            let parser = Rc::new(ElfParser::open(&path).unwrap());
+            let resolver = DwarfResolver::builder()
+                .set_strict(true)
+                .build_from_parser(parser)
+                .unwrap();
+            // Calculating the coverage parses functions and line
+            // programs of all units.
+            let _coverage = resolver.coverage().unwrap();
+        }
+
+        let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join("test-stable-addrs.bin");
+        let parser = ElfParser::open(&path).unwrap();
+        let loader = |name: &str| {
+            let mut data = parser
+                .find_section(name)?
+                .map(|idx| parser.section_data(idx))
+                .transpose()?
+                .map(<[u8]>::to_vec);
+            if name == ".debug_abbrev" {
+                // SANITY: The binary contains debug information.
+                let data = data.as_mut().unwrap();
+                // The first abbreviation describes the root DIE of the
+                // first unit. Make it look like that of a function.
+                assert_eq!(data[..2], [1, gimli::DW_TAG_compile_unit.0 as u8]);
+                data[1] = gimli::DW_TAG_subprogram.0 as u8;
+            }
+            Ok(data)
+        };
+
+        let resolver = DwarfResolver::builder()
+            .build_from_sections(loader)
+            .unwrap();
+        let sym = resolver
+            .find_sym(0x2000100, &FindSymOpts::BASIC)
+            .unwrap()
+            .unwrap();
+        assert_eq!(sym.name, "factorial");
+
+        let err = DwarfResolver::builder()
+            .set_strict(true)
+            .build_from_sections(loader)
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidDwarf);
+        assert!(err.to_string().contains("DW_TAG_subprogram"), "{err}");
+    }
+
+    /// Check that we can find the address corresponding to a source
+    /// code location.
+    #[test]
+    fn line_info_finding() {
+        let bin_name = Path::new(&env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join("test-stable-addrs.bin");
+        let resolver = DwarfResolver::open(bin_name.as_ref()).unwrap();
+
+        let addr = resolver
+            .find_line_info(Path::new("test-stable-addrs.c"), 10)
+            .unwrap();
+        assert_eq!(addr, Some(0x2000100));
+
+        // The address should map back to the same line.
         let info = resolver
-            .find_sym(0x2000100, &FindSymOpts::CodeInfo)
+            .find_sym(addr.unwrap(), &FindSymOpts::CODE_INFO)
             .unwrap()
             .unwrap()
             .code_info
             .unwrap();
-        assert_ne!(info.dir, Some(Cow::Owned(PathBuf::new())));
+        assert_eq!(info.line, Some(10));
+
+        let addr = resolver
+            .find_line_info(Path::new("test-stable-addrs.c"), 100000)
+            .unwrap();
+        assert_eq!(addr, None);
+
+        let addr = resolver
+            .find_line_info(Path::new("does-not-exist.c"), 10)
+            .unwrap();
+        assert_eq!(addr, None);
+
+        // Partial file name components must not match.
+        let addr = resolver.find_line_info(Path::new("addrs.c"), 10).unwrap();
+        assert_eq!(addr, None);
+    }
+
+    /// Check that we fall back to line table information for addresses
+    /// not covered by any symbol, if asked to.
+    #[test]
+    fn line_table_fallback() {
+        let bin_name = Path::new(&env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join("test-stable-addrs-stripped-elf-with-dwarf.bin");
+        let resolver = DwarfResolver::open(bin_name.as_ref()).unwrap();
+
+        // The address is part of the `dummy` assembly "function", which
+        // has no DWARF function information and, because ELF symbols
+        // got stripped, no symbol either. It is covered by the line
+        // table, though.
+        let addr = 0x20000aa;
+        let reason = resolver
+            .find_sym(addr, &FindSymOpts::CODE_INFO)
+            .unwrap()
+            .unwrap_err();
+        assert_eq!(reason, Reason::MissingSyms);
+
+        let opts = FindSymOpts::CODE_INFO.with_line_table_fallback(true);
+        let sym = resolver.find_sym(addr, &opts).unwrap().unwrap();
+        assert_eq!(sym.name, "");
+        assert_eq!(sym.addr, addr);
+        assert_eq!(sym.size, None);
+        assert_eq!(sym.provenance, Provenance::LineTable);
+        let info = sym.code_info.unwrap();
+        assert_eq!(info.file, OsStr::new("test-stable-addrs.c"));
+        assert!(info.line.is_some());
+
+        // Without code information the flag has no effect.
+        let opts = FindSymOpts::BASIC.with_line_table_fallback(true);
+        let result = resolver.find_sym(addr, &opts).unwrap();
+        assert!(result.is_err(), "{result:?}");
+    }
+
+    /// Make sure that we only perform the work necessary for the
+    /// requested level of symbolization, without the level affecting
+    /// the basic symbol information reported.
+    #[test]
+    fn find_sym_work_by_level() {
+        let bin_name = Path::new(&env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join("test-stable-addrs.bin");
+
+        let test = |addr: Addr, opts: &FindSymOpts, lines: bool, inlined: bool| {
+            let resolver = DwarfResolver::open(bin_name.as_ref()).unwrap();
+            let (lines_before, inlined_before) = resolver.units.parse_state();
+            let sym = resolver.find_sym(addr, opts).unwrap().unwrap();
+            let (lines_after, inlined_after) = resolver.units.parse_state();
+            assert_eq!(lines_after > lines_before, lines, "{opts:?}");
+            assert_eq!(inlined_after > inlined_before, inlined, "{opts:?}");
+            assert_eq!(!sym.inlined.is_empty(), inlined, "{opts:?}");
+            (sym.name.to_string(), sym.addr, sym.size, sym.lang)
+        };
+
+        // An address inside of `foo`, in a unit declaring its address
+        // range and, hence, having its line information parsed lazily.
+        let addr = 0x200005f;
+        let basic = test(addr, &FindSymOpts::BASIC, false, false);
+        let code_info = test(addr, &FindSymOpts::CODE_INFO, true, false);
+        assert_eq!(basic.0, "foo");
+        assert_eq!(basic, code_info);
+
+        // Not even the line table fallback should touch line
+        // information if no code information is requested.
+        let opts = FindSymOpts::BASIC.with_line_table_fallback(true);
+        let _sym = test(addr, &opts, false, false);
+
+        // An address inside `factorial_inline_test` covered by two
+        // levels of inlined functions. Line information for its unit
+        // is parsed eagerly, because the unit's address ranges are
+        // inferred from it.
+        let addr = 0x200020a;
+        let basic = test(addr, &FindSymOpts::BASIC, false, false);
+        let code_info = test(addr, &FindSymOpts::CODE_INFO, false, false);
+        let inlined = test(addr, &FindSymOpts::CODE_INFO_AND_INLINED, false, true);
+        assert_eq!(basic.0, "factorial_inline_test");
+        assert_eq!(basic, code_info);
+        assert_eq!(basic, inlined);
+    }
+
+    /// Check that we can count inlined functions without
+    /// materializing them.
+    #[test]
+    fn inlined_fn_counting() {
+        let bin_name = Path::new(&env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join("test-stable-addrs.bin");
+        let resolver = DwarfResolver::open(bin_name.as_ref()).unwrap();
+
+        for addr in [0x200005f, 0x200020a, 0x1000] {
+            let count = resolver.inlined_fn_count(addr).unwrap();
+            let inlined = resolver
+                .find_sym(addr, &FindSymOpts::CODE_INFO_AND_INLINED)
+                .unwrap()
+                .map(|sym| sym.inlined.len())
+                .unwrap_or(0);
+            assert_eq!(count, inlined, "{addr:#x}");
+        }
+
+        assert_eq!(resolver.inlined_fn_count(0x200020a).unwrap(), 2);
+    }
+
+    /// Check that `DwarfResolver::inlined_at` reports the same inline
+    /// chain as a full symbol lookup.
+    #[test]
+    fn inlined_fn_chain() {
+        let bin_name = Path::new(&env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join("test-stable-addrs.bin");
+        let resolver = DwarfResolver::open(bin_name.as_ref()).unwrap();
+
+        for addr in [0x200005f, 0x200020a, 0x1000] {
+            let chain = resolver.inlined_at(addr).unwrap();
+            let inlined = resolver
+                .find_sym(addr, &FindSymOpts::CODE_INFO_AND_INLINED)
+                .unwrap()
+                .map(|sym| {
+                    sym.inlined
+                        .iter()
+                        .map(|inlined_fn| {
+                            (inlined_fn.name.to_string(), inlined_fn.code_info.clone())
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+            assert_eq!(chain, inlined, "{addr:#x}");
+        }
+
+        let chain = resolver.inlined_at(0x200020a).unwrap();
+        let names = chain
+            .iter()
+            .map(|(name, _code_info)| name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            [
+                "factorial_inline_wrapper",
+                "factorial_2nd_layer_inline_wrapper"
+            ]
+        );
+        let code_info = chain[1].1.as_ref().unwrap();
+        assert_eq!(code_info.file, OsStr::new("test-stable-addrs.c"));
+        assert_eq!(code_info.line, Some(23));
+    }
+
+    /// Check that we can work with DWARF data stored in big-endian byte
+    /// order.
+    #[test]
+    fn big_endian_line_info() {
+        let bin_name = Path::new(&env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join("test-big-endian.o");
+        let resolver = DwarfResolver::open(bin_name.as_ref()).unwrap();
+
+        let addr = resolver
+            .find_line_info(Path::new("test-big-endian.s"), 18)
+            .unwrap();
+        assert_eq!(addr, Some(0x6));
+
+        let opts = FindSymOpts::CODE_INFO.with_line_table_fallback(true);
+        let sym = resolver.find_sym(0x8, &opts).unwrap().unwrap();
+        let info = sym.code_info.unwrap();
+        assert_eq!(info.file, OsStr::new("test-big-endian.s"));
+        assert_eq!(info.line, Some(18));
+    }
+
+    /// Check that we can create a `DwarfResolver` from user supplied
+    /// section data.
+    #[test]
+    fn sections_loading() {
+        let bin_name = Path::new(&env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join("test-stable-addrs.bin");
+        let parser = ElfParser::open(bin_name.as_ref()).unwrap();
+        let mut requested = Vec::new();
+        let loader = |name: &str| {
+            let () = requested.push(name.to_string());
+            let data = parser
+                .find_section(name)?
+                .map(|idx| parser.section_data(idx))
+                .transpose()?
+                .map(<[u8]>::to_vec);
+            Ok(data)
+        };
+        let resolver = DwarfResolver::from_sections(loader).unwrap();
+        assert!(requested.iter().any(|name| name == ".debug_info"));
+        assert!(resolver.parser().is_none());
+
+        let sym = resolver
+            .find_sym(0x2000100, &FindSymOpts::CODE_INFO)
+            .unwrap()
+            .unwrap();
+        assert_eq!(sym.name, "factorial");
+        assert_eq!(sym.addr, 0x2000100);
+        let info = sym.code_info.unwrap();
         assert_eq!(info.file, OsStr::new("test-stable-addrs.c"));
         assert_eq!(info.line, Some(10));
-        assert!(info.column.is_some());
+
+        let opts = FindAddrOpts {
+            offset_in_file: true,
+            sym_type: SymType::Function,
+            binding: None,
+        };
+        let syms = resolver.find_addr("factorial", &opts).unwrap();
+        assert_eq!(syms.len(), 1);
+        assert_eq!(syms[0].addr, 0x2000100);
+        assert_eq!(syms[0].file_offset, None);
+        assert_eq!(syms[0].obj_file_name, None);
+
+        let err = resolver.coverage().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Unsupported);
+
+        // Errors reported by the loader should be propagated.
+        let err =
+            DwarfResolver::from_sections(|_name| Err(Error::with_not_found("nope"))).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::NotFound);
+    }
+
+    /// Check that we can symbolize addresses in a function consisting
+    /// of multiple non-contiguous ranges, as recorded in a DWARF 5
+    /// `.debug_rnglists` section.
+    #[test]
+    fn symbolize_dwarf5_rnglists() {
+        let bin_name = Path::new(&env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join("test-rnglists.bin");
+        let resolver = DwarfResolver::open(bin_name.as_ref()).unwrap();
+        let parser = resolver.parser().unwrap();
+        let rnglists = parser.find_section(".debug_rnglists").unwrap();
+        assert!(rnglists.is_some());
+
+        let opts = FindAddrOpts {
+            offset_in_file: false,
+            sym_type: SymType::Function,
+            binding: None,
+        };
+        // The compiler moved the unlikely branch of `split_fn` into a
+        // separate `split_fn.cold` part, placed away from the hot one.
+        for name in ["split_fn", "split_fn.cold"] {
+            let syms = parser.find_addr(name, &opts).unwrap();
+            assert_eq!(syms.len(), 1, "{name}");
+            let addr = syms[0].addr;
+
+            let sym = resolver
+                .find_sym(addr, &FindSymOpts::BASIC)
+                .unwrap()
+                .unwrap();
+            assert_eq!(sym.name, "split_fn", "{name}");
+
+            let info = resolver.unit_info(addr).unwrap().unwrap();
+            assert_eq!(info.version, 5);
+        }
     }
 
     /// Check that we can look up a symbol in DWARF debug information.
@@ -396,6 +1473,7 @@ mod tests {
         let opts = FindAddrOpts {
             offset_in_file: false,
             sym_type: SymType::Function,
+            binding: None,
         };
         let resolver = DwarfResolver::open(test_dwarf.as_ref()).unwrap();
 
@@ -407,6 +1485,109 @@ mod tests {
         assert_eq!(symbol.addr, 0x2000100);
     }
 
+    /// Check that we can look up multiple symbols at once.
+    #[test]
+    fn lookup_symbols() {
+        let test_dwarf = Path::new(&env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join("test-stable-addrs-stripped-elf-with-dwarf.bin");
+        let opts = FindAddrOpts {
+            offset_in_file: false,
+            sym_type: SymType::Function,
+            binding: None,
+        };
+        let resolver = DwarfResolver::open(test_dwarf.as_ref()).unwrap();
+
+        let names = [
+            "factorial",
+            "does-not-exist",
+            "factorial_wrapper",
+            "factorial",
+        ];
+        let syms = resolver.find_addrs(&names, &opts).unwrap();
+        assert_eq!(syms.len(), names.len());
+        assert_eq!(syms[0].len(), 1);
+        assert_eq!(syms[0][0].addr, 0x2000100);
+        assert!(syms[1].is_empty());
+        assert_eq!(syms[3], syms[0]);
+
+        // Results should be equivalent to those of individual look ups.
+        for (name, syms) in names.iter().zip(syms) {
+            assert_eq!(syms, resolver.find_addr(name, &opts).unwrap());
+        }
+
+        // Binding filters are unsupported with DWARF and result in no
+        // matches.
+        let opts = FindAddrOpts {
+            binding: Some(SymBinding::Global),
+            ..opts
+        };
+        let syms = resolver.find_addrs(&names, &opts).unwrap();
+        assert_eq!(syms.len(), names.len());
+        assert!(syms.iter().all(Vec::is_empty));
+    }
+
+    /// Check that we correctly calculate the number of covered bytes.
+    #[test]
+    fn covered_bytes_calculation() {
+        let range = |begin, end| gimli::Range { begin, end };
+        let text = |start, end| start..end;
+
+        assert_eq!(covered_bytes(&[], vec![range(0, 10)]), 0);
+        assert_eq!(covered_bytes(&[text(0, 10)], vec![]), 0);
+        assert_eq!(covered_bytes(&[text(0, 10)], vec![range(2, 4)]), 2);
+        // Overlapping ranges are counted only once.
+        assert_eq!(
+            covered_bytes(&[text(0, 10)], vec![range(5, 8), range(2, 6)]),
+            6
+        );
+        // Parts outside of `text` are not counted.
+        assert_eq!(
+            covered_bytes(&[0..10, 20..30], vec![range(8, 22), range(29, 40)]),
+            5
+        );
+    }
+
+    /// Check that we can calculate the coverage of a binary.
+    #[test]
+    fn coverage_calculation() {
+        let bin_name = Path::new(&env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join("test-stable-addrs.bin");
+        let resolver = DwarfResolver::open(bin_name.as_ref()).unwrap();
+        let coverage = resolver.coverage().unwrap();
+        assert_ne!(coverage.text_bytes, 0);
+        assert_ne!(coverage.line_covered_bytes, 0);
+        assert_ne!(coverage.func_covered_bytes, 0);
+        assert!(coverage.line_covered_bytes <= coverage.text_bytes);
+        assert!(coverage.func_covered_bytes <= coverage.text_bytes);
+
+        let bin_name = Path::new(&env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join("test-stable-addrs-no-dwarf.bin");
+        let resolver = DwarfResolver::open(bin_name.as_ref()).unwrap();
+        let coverage = resolver.coverage().unwrap();
+        assert_ne!(coverage.text_bytes, 0);
+        assert_eq!(coverage.line_covered_bytes, 0);
+        assert_eq!(coverage.func_covered_bytes, 0);
+    }
+
+    /// Check that we can retrieve unwind information through a
+    /// `DwarfResolver`.
+    #[test]
+    fn unwind_info_retrieval() {
+        let bin_name = Path::new(&env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join("test-stable-addrs.bin");
+        let resolver = DwarfResolver::open(bin_name.as_ref()).unwrap();
+        let info = resolver.unwind_info_at(0x2000104).unwrap().unwrap();
+        // After the prologue of `factorial` the CFA is based on `rbp`.
+        assert_eq!(info.cfa_reg, 6);
+        assert_eq!(info.cfa_offset, 16);
+        assert_eq!(info.ra_offset, -8);
+        assert_eq!(resolver.unwind_info_at(0x1000).unwrap(), None);
+    }
+
     /// Check that we fail to look up variables.
     #[test]
     fn unsupported_ops() {
@@ -416,6 +1597,7 @@ mod tests {
         let opts = FindAddrOpts {
             offset_in_file: false,
             sym_type: SymType::Variable,
+            binding: None,
         };
         let resolver = DwarfResolver::open(test_dwarf.as_ref()).unwrap();
 
@@ -0,0 +1,367 @@
+use crate::inspect::EnumVariant;
+use crate::inspect::Field;
+use crate::inspect::TypeInfo;
+
+use super::reader::R;
+use super::units::Units;
+
+type Error = gimli::Error;
+type Entry<'abbrev, 'unit, 'dwarf> = gimli::DebuggingInformationEntry<'abbrev, 'unit, R<'dwarf>>;
+type UnitOffset = gimli::UnitOffset<usize>;
+
+
+/// The maximum nesting depth of types that we resolve. Anything deeper
+/// is reported as opaque.
+const MAX_DEPTH: usize = 64;
+
+
+fn void() -> TypeInfo {
+    TypeInfo::Primitive {
+        name: "void".to_string(),
+        size: 0,
+    }
+}
+
+fn opaque(name: Option<String>) -> TypeInfo {
+    TypeInfo::Opaque { name }
+}
+
+/// Read an unsigned integer attribute as `usize`.
+fn usize_attr(entry: &Entry<'_, '_, '_>, name: gimli::DwAt) -> Result<Option<usize>, Error> {
+    let value = entry
+        .attr_value(name)?
+        .and_then(|value| value.udata_value())
+        .and_then(|value| usize::try_from(value).ok());
+    Ok(value)
+}
+
+
+/// Read the `DW_AT_data_member_location` attribute of a member,
+/// falling back to `DW_AT_data_bit_offset`.
+///
+/// `None` is returned if the location is described by an expression
+/// other than a plain constant offset.
+fn member_offset(
+    unit: &gimli::Unit<R<'_>>,
+    entry: &Entry<'_, '_, '_>,
+) -> Result<Option<usize>, Error> {
+    match entry.attr_value(gimli::DW_AT_data_member_location)? {
+        Some(gimli::AttributeValue::Exprloc(expr)) => {
+            // Older DWARF versions describe the offset as a
+            // `DW_OP_plus_uconst` operation. Anything more involved
+            // (e.g., virtual base classes) depends on the object being
+            // inspected and can't be captured by a fixed offset.
+            let mut ops = expr.operations(unit.encoding());
+            let offset = match (ops.next()?, ops.next()?) {
+                (Some(gimli::Operation::PlusConstant { value }), None) => {
+                    usize::try_from(value).ok()
+                }
+                _ => None,
+            };
+            Ok(offset)
+        }
+        Some(value) => Ok(value
+            .udata_value()
+            .and_then(|value| usize::try_from(value).ok())),
+        None => {
+            let offset = usize_attr(entry, gimli::DW_AT_data_bit_offset)?.unwrap_or(0) / 8;
+            Ok(Some(offset))
+        }
+    }
+}
+
+
+/// Helper for converting DWARF type DIEs into [`TypeInfo`] objects.
+struct Resolver<'units, 'dwarf> {
+    units: &'units Units<'dwarf>,
+    /// Aggregate types currently being resolved, used for detecting
+    /// self-referential types.
+    stack: Vec<(gimli::UnitSectionOffset, UnitOffset)>,
+    /// Whether we are currently resolving the target of a pointer, in
+    /// which case named aggregates are only referenced by name. Doing
+    /// so bounds the amount of work (and the size of the result) for
+    /// types that reference each other.
+    in_pointee: bool,
+}
+
+impl<'dwarf> Resolver<'_, 'dwarf> {
+    fn name(
+        &self,
+        unit: &gimli::Unit<R<'dwarf>>,
+        entry: &Entry<'_, '_, 'dwarf>,
+    ) -> Result<Option<String>, Error> {
+        if let Some(value) = entry.attr_value(gimli::DW_AT_name)? {
+            let name = self.units.dwarf().attr_string(unit, value)?;
+            Ok(Some(name.to_string_lossy().into_owned()))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Check whether the type referenced by `entry` (if any) is an
+    /// unsigned one.
+    fn is_unsigned(&self, unit: &gimli::Unit<R<'dwarf>>, entry: &Entry<'_, '_, 'dwarf>) -> bool {
+        let offset = match entry.attr_value(gimli::DW_AT_type) {
+            Ok(Some(gimli::AttributeValue::UnitRef(offset))) => offset,
+            _ => return false,
+        };
+        let encoding = unit
+            .entry(offset)
+            .ok()
+            .and_then(|entry| entry.attr_value(gimli::DW_AT_encoding).ok().flatten());
+        matches!(
+            encoding,
+            Some(gimli::AttributeValue::Encoding(
+                gimli::DW_ATE_unsigned | gimli::DW_ATE_unsigned_char | gimli::DW_ATE_boolean
+            ))
+        )
+    }
+
+    /// Resolve the type referenced by the `DW_AT_type` attribute of
+    /// `entry`. An absent attribute denotes `void`.
+    fn resolve_type_attr(
+        &mut self,
+        unit: &gimli::Unit<R<'dwarf>>,
+        entry: &Entry<'_, '_, 'dwarf>,
+        depth: usize,
+    ) -> Result<TypeInfo, Error> {
+        match entry.attr_value(gimli::DW_AT_type)? {
+            Some(gimli::AttributeValue::UnitRef(offset)) => self.resolve(unit, offset, depth),
+            Some(gimli::AttributeValue::DebugInfoRef(offset)) => {
+                let (unit, offset) = self.units.find_unit(offset)?;
+                self.resolve(unit, offset, depth)
+            }
+            // TODO: Need to handle `AttributeValue::DebugInfoRefSup`.
+            Some(_) => Ok(opaque(None)),
+            None => Ok(void()),
+        }
+    }
+
+    fn resolve_fields(
+        &mut self,
+        unit: &gimli::Unit<R<'dwarf>>,
+        offset: UnitOffset,
+        depth: usize,
+    ) -> Result<Option<Vec<Field>>, Error> {
+        let mut fields = Vec::new();
+        let mut tree = unit.entries_tree(Some(offset))?;
+        let mut children = tree.root()?.children();
+        while let Some(child) = children.next()? {
+            let entry = child.entry();
+            if entry.tag() != gimli::DW_TAG_member {
+                continue
+            }
+
+            let offset = match member_offset(unit, entry)? {
+                Some(offset) => offset,
+                None => return Ok(None),
+            };
+            let field = Field {
+                name: self.name(unit, entry)?,
+                offset,
+                ty: self.resolve_type_attr(unit, entry, depth)?,
+                _non_exhaustive: (),
+            };
+            let () = fields.push(field);
+        }
+        Ok(Some(fields))
+    }
+
+    fn resolve_variants(
+        &self,
+        unit: &gimli::Unit<R<'dwarf>>,
+        offset: UnitOffset,
+        unsigned: bool,
+    ) -> Result<Vec<EnumVariant>, Error> {
+        let mut variants = Vec::new();
+        let mut tree = unit.entries_tree(Some(offset))?;
+        let mut children = tree.root()?.children();
+        while let Some(child) = children.next()? {
+            let entry = child.entry();
+            if entry.tag() != gimli::DW_TAG_enumerator {
+                continue
+            }
+
+            let value = entry
+                .attr_value(gimli::DW_AT_const_value)?
+                .and_then(|value| {
+                    if unsigned {
+                        value.udata_value().map(|value| value as i64)
+                    } else {
+                        value.sdata_value()
+                    }
+                });
+            let variant = EnumVariant {
+                name: self.name(unit, entry)?.unwrap_or_default(),
+                value: value.unwrap_or(0),
+                _non_exhaustive: (),
+            };
+            let () = variants.push(variant);
+        }
+        Ok(variants)
+    }
+
+    fn resolve_array(
+        &mut self,
+        unit: &gimli::Unit<R<'dwarf>>,
+        offset: UnitOffset,
+        entry: &Entry<'_, '_, 'dwarf>,
+        depth: usize,
+    ) -> Result<TypeInfo, Error> {
+        let mut counts = Vec::new();
+        let mut tree = unit.entries_tree(Some(offset))?;
+        let mut children = tree.root()?.children();
+        while let Some(child) = children.next()? {
+            let entry = child.entry();
+            if entry.tag() != gimli::DW_TAG_subrange_type {
+                continue
+            }
+
+            let count = if let Some(count) = usize_attr(entry, gimli::DW_AT_count)? {
+                count
+            } else if let Some(bound) = usize_attr(entry, gimli::DW_AT_upper_bound)? {
+                bound + 1
+            } else {
+                0
+            };
+            let () = counts.push(count);
+        }
+
+        if counts.is_empty() {
+            let () = counts.push(0);
+        }
+
+        let mut ty = self.resolve_type_attr(unit, entry, depth)?;
+        for count in counts.into_iter().rev() {
+            ty = TypeInfo::Array {
+                elem: Box::new(ty),
+                count,
+            };
+        }
+        Ok(ty)
+    }
+
+    fn resolve(
+        &mut self,
+        unit: &gimli::Unit<R<'dwarf>>,
+        offset: UnitOffset,
+        depth: usize,
+    ) -> Result<TypeInfo, Error> {
+        if depth == 0 {
+            return Ok(opaque(None))
+        }
+        let depth = depth - 1;
+
+        let entry = unit.entry(offset)?;
+        let name = self.name(unit, &entry)?;
+        if entry.attr_value(gimli::DW_AT_declaration)?.is_some() {
+            return Ok(opaque(name))
+        }
+
+        let size = usize_attr(&entry, gimli::DW_AT_byte_size)?;
+        let info = match entry.tag() {
+            gimli::DW_TAG_base_type => TypeInfo::Primitive {
+                name: name.unwrap_or_default(),
+                size: size.unwrap_or(0),
+            },
+            gimli::DW_TAG_structure_type | gimli::DW_TAG_class_type | gimli::DW_TAG_union_type
+                if self.in_pointee && name.is_some() =>
+            {
+                opaque(name)
+            }
+            gimli::DW_TAG_structure_type | gimli::DW_TAG_class_type | gimli::DW_TAG_union_type => {
+                let key = (unit.header.offset(), offset);
+                let fields = if self.stack.contains(&key) {
+                    Vec::new()
+                } else {
+                    let () = self.stack.push(key);
+                    let fields = self.resolve_fields(unit, offset, depth);
+                    let _key = self.stack.pop();
+                    match fields? {
+                        Some(fields) => fields,
+                        None => return Ok(opaque(name)),
+                    }
+                };
+                let size = size.unwrap_or(0);
+
+                if entry.tag() == gimli::DW_TAG_union_type {
+                    TypeInfo::Union { name, size, fields }
+                } else {
+                    TypeInfo::Struct { name, size, fields }
+                }
+            }
+            gimli::DW_TAG_enumeration_type => TypeInfo::Enum {
+                name,
+                size: size.unwrap_or(0),
+                variants: self.resolve_variants(unit, offset, self.is_unsigned(unit, &entry))?,
+            },
+            gimli::DW_TAG_pointer_type
+            | gimli::DW_TAG_reference_type
+            | gimli::DW_TAG_rvalue_reference_type => {
+                let in_pointee = self.in_pointee;
+                self.in_pointee = true;
+                let pointee = self.resolve_type_attr(unit, &entry, depth);
+                self.in_pointee = in_pointee;
+                TypeInfo::Pointer {
+                    pointee: Box::new(pointee?),
+                }
+            }
+            gimli::DW_TAG_array_type => self.resolve_array(unit, offset, &entry, depth)?,
+            gimli::DW_TAG_typedef => TypeInfo::Typedef {
+                name: name.unwrap_or_default(),
+                ty: Box::new(self.resolve_type_attr(unit, &entry, depth)?),
+            },
+            gimli::DW_TAG_const_type
+            | gimli::DW_TAG_volatile_type
+            | gimli::DW_TAG_restrict_type
+            | gimli::DW_TAG_atomic_type => self.resolve_type_attr(unit, &entry, depth)?,
+            _ => opaque(name),
+        };
+        Ok(info)
+    }
+}
+
+
+/// Find the type with the given name in the provided unit and convert
+/// it into a [`TypeInfo`].
+///
+/// Only named type definitions are considered, i.e., declarations as
+/// well as variables are ignored.
+pub(super) fn find_type<'dwarf>(
+    units: &Units<'dwarf>,
+    unit: &gimli::Unit<R<'dwarf>>,
+    name: &str,
+) -> Result<Option<TypeInfo>, Error> {
+    let mut entries = unit.entries();
+    while let Some((_, entry)) = entries.next_dfs()? {
+        match entry.tag() {
+            gimli::DW_TAG_structure_type
+            | gimli::DW_TAG_class_type
+            | gimli::DW_TAG_union_type
+            | gimli::DW_TAG_enumeration_type
+            | gimli::DW_TAG_base_type
+            | gimli::DW_TAG_typedef => (),
+            _ => continue,
+        }
+
+        if entry.attr_value(gimli::DW_AT_declaration)?.is_some() {
+            continue
+        }
+
+        let matches = match entry.attr_value(gimli::DW_AT_name)? {
+            Some(value) => units.dwarf().attr_string(unit, value)?.slice() == name.as_bytes(),
+            None => false,
+        };
+
+        if matches {
+            let mut resolver = Resolver {
+                units,
+                stack: Vec::new(),
+                in_pointee: false,
+            };
+            let info = resolver.resolve(unit, entry.offset(), MAX_DEPTH)?;
+            return Ok(Some(info))
+        }
+    }
+    Ok(None)
+}
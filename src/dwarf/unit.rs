@@ -25,10 +25,18 @@
 // > IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
 // > DEALINGS IN THE SOFTWARE.
 
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::ffi::OsStr;
+use std::os::unix::ffi::OsStrExt as _;
+use std::path::Path;
+use std::path::PathBuf;
+
 use crate::once::OnceCell;
 
 use super::function::Function;
 use super::function::Functions;
+use super::function::Label;
 use super::lines::Lines;
 use super::location::Location;
 use super::location::LocationRangeUnitIter;
@@ -36,6 +44,7 @@ use super::reader::R;
 use super::units::Units;
 
 
+#[derive(Clone, Debug)]
 pub(super) struct UnitRange {
     pub unit_id: usize,
     pub max_end: u64,
@@ -45,7 +54,10 @@ pub(super) struct UnitRange {
 
 pub(super) struct Unit<'dwarf> {
     offset: gimli::DebugInfoOffset<<R<'dwarf> as gimli::Reader>::Offset>,
-    dw_unit: gimli::Unit<R<'dwarf>>,
+    header: gimli::UnitHeader<R<'dwarf>>,
+    /// The parsed unit, constructed lazily from `header`, on first
+    /// use.
+    dw_unit: OnceCell<gimli::Unit<R<'dwarf>>>,
     lang: Option<gimli::DwLang>,
     lines: OnceCell<Lines<'dwarf>>,
     funcs: OnceCell<Functions<'dwarf>>,
@@ -54,12 +66,14 @@ pub(super) struct Unit<'dwarf> {
 impl<'dwarf> Unit<'dwarf> {
     pub(super) fn new(
         offset: gimli::DebugInfoOffset<<R<'dwarf> as gimli::Reader>::Offset>,
-        unit: gimli::Unit<R<'dwarf>>,
+        header: gimli::UnitHeader<R<'dwarf>>,
+        unit: OnceCell<gimli::Unit<R<'dwarf>>>,
         lang: Option<gimli::DwLang>,
         lines: OnceCell<Lines<'dwarf>>,
     ) -> Self {
         Self {
             offset,
+            header,
             dw_unit: unit,
             lang,
             lines,
@@ -73,7 +87,7 @@ impl<'dwarf> Unit<'dwarf> {
         &'unit self,
         units: &Units<'dwarf>,
     ) -> Result<&'unit Functions<'dwarf>, gimli::Error> {
-        let unit = &self.dw_unit;
+        let unit = self.dw_unit(units)?;
         let functions = self.parse_functions_dwarf_and_unit(unit, units)?;
         Ok(functions)
     }
@@ -84,7 +98,7 @@ impl<'dwarf> Unit<'dwarf> {
         &'unit self,
         units: &Units<'dwarf>,
     ) -> Result<&'unit Functions<'dwarf>, gimli::Error> {
-        let unit = &self.dw_unit;
+        let unit = self.dw_unit(units)?;
 
         self.funcs.get_or_try_init(|| {
             let funcs = Functions::parse(unit, units)?;
@@ -99,12 +113,13 @@ impl<'dwarf> Unit<'dwarf> {
     ) -> Result<Option<&Lines<'dwarf>>, gimli::Error> {
         // NB: line information is always stored in the main debug file so this does not
         // need to handle DWOs.
-        let ilnp = match self.dw_unit.line_program {
+        let unit = self.dw_unit(units)?;
+        let ilnp = match unit.line_program {
             Some(ref ilnp) => ilnp,
             None => return Ok(None),
         };
         self.lines
-            .get_or_try_init(|| Lines::parse(&self.dw_unit, ilnp.clone(), units.dwarf()))
+            .get_or_try_init(|| Lines::parse(unit, ilnp.clone(), units.dwarf()))
             .map(Some)
     }
 
@@ -131,12 +146,22 @@ impl<'dwarf> Unit<'dwarf> {
         self.funcs.get_or_try_init(|| Functions::parse(unit, units))
     }
 
+    /// Retrieve the address ranges covered by the unit's functions.
+    pub(super) fn function_ranges(
+        &self,
+        units: &Units<'dwarf>,
+    ) -> Result<impl Iterator<Item = &gimli::Range>, gimli::Error> {
+        let unit = self.dw_unit(units)?;
+        let functions = self.parse_functions_dwarf_and_unit(unit, units)?;
+        Ok(functions.addresses.iter().map(|address| &address.range))
+    }
+
     pub(super) fn find_function(
         &self,
         probe: u64,
         units: &Units<'dwarf>,
     ) -> Result<Option<&Function<'dwarf>>, gimli::Error> {
-        let unit = &self.dw_unit;
+        let unit = self.dw_unit(units)?;
         let functions = self.parse_functions_dwarf_and_unit(unit, units)?;
         let function = match functions.find_address(probe) {
             Some(address) => {
@@ -149,12 +174,39 @@ impl<'dwarf> Unit<'dwarf> {
         Ok(function)
     }
 
+    /// Find the `DW_TAG_label` entry closest preceding `probe` that is
+    /// not shadowed by a function.
+    pub(super) fn find_label(
+        &self,
+        probe: u64,
+        units: &Units<'dwarf>,
+    ) -> Result<Option<&Label<'dwarf>>, gimli::Error> {
+        let unit = self.dw_unit(units)?;
+        let functions = self.parse_functions_dwarf_and_unit(unit, units)?;
+        let label = functions.find_label(probe);
+        Ok(label)
+    }
+
+    /// Find the function with address information that either is
+    /// described by the entry at `offset` or is a concrete instance of
+    /// it.
+    pub(super) fn find_origin_function(
+        &self,
+        offset: gimli::DebugInfoOffset,
+        units: &Units<'dwarf>,
+    ) -> Result<Option<&Function<'dwarf>>, gimli::Error> {
+        let unit = self.dw_unit(units)?;
+        let functions = self.parse_functions_dwarf_and_unit(unit, units)?;
+        let function = functions.find_origin(offset, unit);
+        Ok(function)
+    }
+
     pub(super) fn find_name<'slf>(
         &'slf self,
         name: &str,
         units: &Units<'dwarf>,
     ) -> Result<Option<&'slf Function<'dwarf>>, gimli::Error> {
-        let unit = &self.dw_unit;
+        let unit = self.dw_unit(units)?;
         let functions = self.parse_functions_dwarf_and_unit(unit, units)?;
         for func in functions.functions.iter() {
             let name = Some(name.as_bytes());
@@ -165,16 +217,106 @@ impl<'dwarf> Unit<'dwarf> {
         Ok(None)
     }
 
+    /// Find the functions with any of the names in `indices`, which
+    /// maps each name to the indices in `funcs` that should receive
+    /// matching functions.
+    ///
+    /// Just like [`Unit::find_name`], only the first function with a
+    /// given name is reported.
+    pub(super) fn find_names<'slf>(
+        &'slf self,
+        indices: &HashMap<&[u8], Vec<usize>>,
+        units: &Units<'dwarf>,
+        funcs: &mut [Vec<&'slf Function<'dwarf>>],
+    ) -> Result<(), gimli::Error> {
+        let unit = self.dw_unit(units)?;
+        let functions = self.parse_functions_dwarf_and_unit(unit, units)?;
+        let mut found = HashSet::new();
+        for func in functions.functions.iter() {
+            let name = match func.name.as_ref() {
+                Some(name) => name.slice(),
+                None => continue,
+            };
+
+            if let Some(idxs) = indices.get(name) {
+                if found.insert(name) {
+                    for idx in idxs {
+                        let () = funcs[*idx].push(func);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// Retrieve the unit's debug info offset.
     #[inline]
     pub(super) fn offset(&self) -> gimli::DebugInfoOffset<<R<'dwarf> as gimli::Reader>::Offset> {
         self.offset
     }
 
-    /// Retrieve the underlying [`gimli::Unit`] object.
+    /// Retrieve the unit's header.
     #[inline]
-    pub(super) fn dw_unit(&self) -> &gimli::Unit<R<'dwarf>> {
-        &self.dw_unit
+    pub(super) fn header(&self) -> &gimli::UnitHeader<R<'dwarf>> {
+        &self.header
+    }
+
+    /// Retrieve the underlying [`gimli::Unit`] object, parsing it if
+    /// that has not happened yet.
+    pub(super) fn dw_unit(
+        &self,
+        units: &Units<'dwarf>,
+    ) -> Result<&gimli::Unit<R<'dwarf>>, gimli::Error> {
+        self.dw_unit
+            .get_or_try_init(|| units.dwarf().unit(self.header))
+    }
+
+    /// Retrieve the path to the compilation unit's primary source file,
+    /// as recorded in its `DW_AT_name` attribute and interpreted
+    /// relative to its `DW_AT_comp_dir`, if present.
+    pub(super) fn path(&self, units: &Units<'dwarf>) -> Result<Option<PathBuf>, gimli::Error> {
+        let unit = self.dw_unit(units)?;
+        let name = match unit.name {
+            Some(name) => Path::new(OsStr::from_bytes(name.slice())),
+            None => return Ok(None),
+        };
+        let path = match unit.comp_dir {
+            Some(dir) => Path::new(OsStr::from_bytes(dir.slice())).join(name),
+            None => name.to_path_buf(),
+        };
+        Ok(Some(path))
+    }
+
+    /// Retrieve the compilation unit's compilation directory, as
+    /// recorded in its `DW_AT_comp_dir` attribute.
+    pub(super) fn comp_dir(
+        &self,
+        units: &Units<'dwarf>,
+    ) -> Result<Option<&'dwarf Path>, gimli::Error> {
+        let unit = self.dw_unit(units)?;
+        let dir = unit
+            .comp_dir
+            .map(|dir| Path::new(OsStr::from_bytes(dir.slice())));
+        Ok(dir)
+    }
+
+    /// Retrieve the identification of the compiler that produced the
+    /// compilation unit, as recorded in its `DW_AT_producer`
+    /// attribute.
+    pub(super) fn producer(
+        &self,
+        units: &Units<'dwarf>,
+    ) -> Result<Option<R<'dwarf>>, gimli::Error> {
+        let unit = self.dw_unit(units)?;
+        let mut entries = unit.entries();
+        let entry = match entries.next_dfs()? {
+            Some((_depth, entry)) => entry,
+            None => return Ok(None),
+        };
+        match entry.attr_value(gimli::DW_AT_producer)? {
+            Some(value) => units.attr_string(unit, value),
+            None => Ok(None),
+        }
     }
 
     /// Attempt to retrieve the compilation unit's source code language.
@@ -182,4 +324,28 @@ impl<'dwarf> Unit<'dwarf> {
     pub(super) fn language(&self) -> Option<gimli::DwLang> {
         self.lang
     }
+
+    /// Check whether the [`gimli::Unit`] object has been created.
+    #[cfg(test)]
+    pub(super) fn is_parsed(&self) -> bool {
+        self.dw_unit.get().is_some()
+    }
+
+    /// Check whether the unit's line information has been parsed and
+    /// count the functions whose inlined functions have been parsed.
+    #[cfg(test)]
+    pub(super) fn parse_state(&self) -> (bool, usize) {
+        let inlined = self
+            .funcs
+            .get()
+            .map(|funcs| {
+                funcs
+                    .functions
+                    .iter()
+                    .filter(|func| func.inlined_functions.get().is_some())
+                    .count()
+            })
+            .unwrap_or(0);
+        (self.lines.get().is_some(), inlined)
+    }
 }
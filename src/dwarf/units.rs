@@ -25,16 +25,28 @@
 // > IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
 // > DEALINGS IN THE SOFTWARE.
 
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::path::Path;
+use std::path::PathBuf;
+
+#[cfg(feature = "type-info")]
+use crate::inspect::TypeInfo;
 use crate::log::warn;
 use crate::once::OnceCell;
+use crate::Error;
 use crate::ErrorExt as _;
 use crate::Result;
 
+use super::cache::UnitIndex;
 use super::function::Function;
+use super::function::Label;
 use super::lines::Lines;
 use super::location::Location;
 use super::range::RangeAttributes;
 use super::reader::R;
+#[cfg(feature = "type-info")]
+use super::type_info;
 use super::unit::Unit;
 use super::unit::UnitRange;
 
@@ -58,10 +70,13 @@ pub(crate) struct Units<'dwarf> {
     unit_ranges: Box<[UnitRange]>,
     /// All units along with meta-data.
     units: Box<[Unit<'dwarf>]>,
+    /// Whether to report problems with the DWARF data that could
+    /// otherwise be worked around as errors.
+    strict: bool,
 }
 
 impl<'dwarf> Units<'dwarf> {
-    pub(crate) fn parse(sections: gimli::Dwarf<R<'dwarf>>) -> Result<Self> {
+    pub(crate) fn parse(sections: gimli::Dwarf<R<'dwarf>>, strict: bool) -> Result<Self> {
         // Find all the references to compilation units in .debug_aranges.
         // Note that we always also iterate through all of .debug_info to
         // find compilation units, because .debug_aranges may be missing some.
@@ -105,6 +120,23 @@ impl<'dwarf> Units<'dwarf> {
                     None => continue,
                 };
 
+                if strict
+                    && !matches!(
+                        abbrev.tag(),
+                        gimli::DW_TAG_compile_unit
+                            | gimli::DW_TAG_partial_unit
+                            | gimli::DW_TAG_skeleton_unit
+                    )
+                {
+                    return Err(Error::from(gimli::Error::UnsupportedUnitType)).with_context(|| {
+                        format!(
+                            "encountered unexpected tag {} for DWARF unit @ {}",
+                            abbrev.tag(),
+                            format_offset(header.offset())
+                        )
+                    })
+                }
+
                 let mut ranges = RangeAttributes::default();
                 for spec in abbrev.attributes() {
                     let attr = entries.read_attribute(*spec)?;
@@ -114,6 +146,9 @@ impl<'dwarf> Units<'dwarf> {
                             gimli::AttributeValue::DebugAddrIndex(index) => {
                                 ranges.low_pc = Some(sections.address(&dw_unit, index)?);
                             }
+                            _ if strict => {
+                                return Err(Error::from(gimli::Error::UnsupportedAttributeForm))
+                            }
                             _ => {}
                         },
                         gimli::DW_AT_high_pc => match attr.value() {
@@ -122,6 +157,9 @@ impl<'dwarf> Units<'dwarf> {
                                 ranges.high_pc = Some(sections.address(&dw_unit, index)?);
                             }
                             gimli::AttributeValue::Udata(val) => ranges.size = Some(val),
+                            _ if strict => {
+                                return Err(Error::from(gimli::Error::UnsupportedAttributeForm))
+                            }
                             _ => {}
                         },
                         gimli::DW_AT_ranges => {
@@ -185,14 +223,47 @@ impl<'dwarf> Units<'dwarf> {
                 }
             }
 
+            if strict {
+                if let Some(ref ilnp) = dw_unit.line_program {
+                    // DWARF 5 changed the way the file table is
+                    // indexed, so the unit and its line program have to
+                    // agree on whether it is in use.
+                    let unit_version = dw_unit.header.version();
+                    let line_version = ilnp.header().version();
+                    if (unit_version >= 5) != (line_version >= 5) {
+                        return Err(Error::from(gimli::Error::UnknownVersion(u64::from(
+                            line_version,
+                        ))))
+                        .with_context(|| {
+                            format!(
+                                "line program version {line_version} does not match version {unit_version} of DWARF unit @ {}",
+                                format_offset(header.offset())
+                            )
+                        })
+                    }
+                }
+            }
+
             let lines = OnceCell::new();
             if !have_unit_range {
                 // The unit did not declare any ranges.
                 // Try to get some ranges from the line program sequences.
                 if let Some(ref ilnp) = dw_unit.line_program {
-                    if let Ok(lines) =
-                        lines.get_or_try_init(|| Lines::parse(&dw_unit, ilnp.clone(), &sections))
-                    {
+                    let result =
+                        lines.get_or_try_init(|| Lines::parse(&dw_unit, ilnp.clone(), &sections));
+                    let lines = match result {
+                        Ok(lines) => Some(lines),
+                        Err(err) if strict => {
+                            return Err(Error::from(err)).with_context(|| {
+                                format!(
+                                    "failed to parse line program of DWARF unit @ {}",
+                                    format_offset(header.offset())
+                                )
+                            })
+                        }
+                        Err(_) => None,
+                    };
+                    if let Some(lines) = lines {
                         for sequence in lines.sequences.iter() {
                             unit_ranges.push(UnitRange {
                                 range: gimli::Range {
@@ -207,7 +278,13 @@ impl<'dwarf> Units<'dwarf> {
                 }
             }
 
-            res_units.push(Unit::new(offset, dw_unit, lang, lines))
+            res_units.push(Unit::new(
+                offset,
+                header,
+                OnceCell::from(dw_unit),
+                lang,
+                lines,
+            ))
         }
 
         // Sort this for faster lookups.
@@ -225,10 +302,78 @@ impl<'dwarf> Units<'dwarf> {
             dwarf: sections,
             unit_ranges: unit_ranges.into_boxed_slice(),
             units: res_units.into_boxed_slice(),
+            strict,
+        };
+        Ok(slf)
+    }
+
+    /// Create a `Units` object based on a previously retrieved
+    /// [`UnitIndex`], sparing us the pass over all compilation units
+    /// that [`Units::parse`] performs.
+    ///
+    /// If the index does not match the debug information, we fall back
+    /// to a regular parse.
+    pub(super) fn from_index(sections: gimli::Dwarf<R<'dwarf>>, index: UnitIndex) -> Result<Self> {
+        let mut cached = index.units.iter();
+        let mut next = cached.next();
+        let mut units = Vec::with_capacity(index.units.len());
+        let mut headers = sections.units();
+
+        while let Some(header) = headers.next()? {
+            let (cached_offset, lang) = match next {
+                Some((offset, lang)) => (*offset, *lang),
+                None => break,
+            };
+            let offset = match header.offset().as_debug_info_offset() {
+                Some(offset) => offset,
+                None => continue,
+            };
+            // Units not present in the index were skipped during the
+            // original parse (e.g., because they are type units).
+            if offset.0 < cached_offset {
+                continue
+            } else if offset.0 > cached_offset {
+                break
+            }
+
+            // The `gimli::Unit` object is only created once the unit
+            // is actually used.
+            let () = units.push(Unit::new(
+                offset,
+                header,
+                OnceCell::new(),
+                lang,
+                OnceCell::new(),
+            ));
+            next = cached.next();
+        }
+
+        if next.is_some() {
+            warn!("cached DWARF unit index does not match debug information; ignoring it");
+            return Self::parse(sections, false)
+        }
+
+        let slf = Self {
+            dwarf: sections,
+            unit_ranges: index.unit_ranges,
+            units: units.into_boxed_slice(),
+            strict: false,
         };
         Ok(slf)
     }
 
+    /// Retrieve the cacheable [`UnitIndex`] describing this object.
+    pub(super) fn index(&self) -> UnitIndex {
+        UnitIndex {
+            units: self
+                .units
+                .iter()
+                .map(|unit| (unit.offset().0, unit.language()))
+                .collect(),
+            unit_ranges: self.unit_ranges.clone(),
+        }
+    }
+
     /// Find the unit containing the given offset, and convert the
     /// offset into a unit offset.
     pub(super) fn find_unit(
@@ -241,14 +386,10 @@ impl<'dwarf> Units<'dwarf> {
         ),
         gimli::Error,
     > {
-        let unit = match self
-            .units
-            .binary_search_by_key(&offset.0, |unit| unit.offset().0)
-        {
-            // There is never a DIE at the unit offset or before the first unit.
-            Ok(_) | Err(0) => return Err(gimli::Error::NoEntryAtGivenOffset),
-            Err(i) => self.units[i - 1].dw_unit(),
-        };
+        let unit = self
+            .find_unit_containing(offset)
+            .ok_or(gimli::Error::NoEntryAtGivenOffset)?
+            .dw_unit(self)?;
 
         let unit_offset = offset
             .to_unit_offset(&unit.header)
@@ -256,6 +397,47 @@ impl<'dwarf> Units<'dwarf> {
         Ok((unit, unit_offset))
     }
 
+    /// Find the unit containing the given offset.
+    fn find_unit_containing(
+        &self,
+        offset: gimli::DebugInfoOffset<<R<'_> as gimli::Reader>::Offset>,
+    ) -> Option<&Unit<'dwarf>> {
+        match self
+            .units
+            .binary_search_by_key(&offset.0, |unit| unit.offset().0)
+        {
+            // There is never a DIE at the unit offset or before the first unit.
+            Ok(_) | Err(0) => None,
+            Err(i) => Some(&self.units[i - 1]),
+        }
+    }
+
+    /// Find the start address of the out-of-line definition of the
+    /// function whose abstract instance is described by the entry at
+    /// `origin`.
+    ///
+    /// Concrete out-of-line instances are searched for in `unit`
+    /// (typically the unit containing the reference to `origin`) as
+    /// well as the unit containing `origin` itself.
+    fn find_origin_addr(
+        &self,
+        origin: gimli::DebugInfoOffset,
+        unit: &Unit<'dwarf>,
+    ) -> Result<Option<u64>, gimli::Error> {
+        let mut function = unit.find_origin_function(origin, self)?;
+        if function.is_none() {
+            if let Some(origin_unit) = self.find_unit_containing(origin) {
+                if origin_unit.offset() != unit.offset() {
+                    function = origin_unit.find_origin_function(origin, self)?;
+                }
+            }
+        }
+        let addr = function
+            .and_then(|function| function.range)
+            .map(|range| range.begin);
+        Ok(addr)
+    }
+
     /// Finds the CUs for the function address given.
     ///
     /// There might be multiple CUs whose range contains this address.
@@ -335,22 +517,58 @@ impl<'dwarf> Units<'dwarf> {
         Ok(None)
     }
 
+    /// Find the `DW_TAG_label` entry that `probe` is attributed to, if
+    /// any.
+    pub(super) fn find_label(
+        &self,
+        probe: u64,
+    ) -> Result<Option<(&Label<'dwarf>, &Unit<'dwarf>)>, gimli::Error> {
+        for unit in self.find_units(probe) {
+            if let Some(label) = unit.find_label(probe, self)? {
+                return Ok(Some((label, unit)))
+            }
+        }
+        Ok(None)
+    }
+
+    /// Count the inlined functions that contain `probe`, without
+    /// retrieving their names or locations.
+    pub(super) fn count_inlined_functions(
+        &self,
+        probe: u64,
+        function: &Function<'dwarf>,
+        unit: &Unit<'dwarf>,
+    ) -> Result<usize, gimli::Error> {
+        let inlined_fns = function.parse_inlined_functions(unit.dw_unit(self)?, self)?;
+        let count = inlined_fns.find_inlined_functions(probe).len();
+        Ok(count)
+    }
+
     /// Find the list of inlined functions that contain `probe`.
+    ///
+    /// For each function, reported are its name, the location of the
+    /// call, the start address of the range containing `probe`, and,
+    /// if `origin_addrs` is `true`, the start address of its
+    /// out-of-line definition, if any.
     pub(super) fn find_inlined_functions<'slf>(
         &'slf self,
         probe: u64,
         function: &'slf Function<'dwarf>,
         unit: &'slf Unit<'dwarf>,
+        origin_addrs: bool,
     ) -> Result<
         Option<
             impl ExactSizeIterator<
-                    Item = Result<(&'dwarf str, Option<Location<'slf>>), gimli::Error>,
+                    Item = Result<
+                        (&'dwarf str, Option<Location<'slf>>, u64, Option<u64>),
+                        gimli::Error,
+                    >,
                 > + 'slf,
         >,
         gimli::Error,
     > {
-        let inlined_fns = function.parse_inlined_functions(unit.dw_unit(), self)?;
-        let iter = inlined_fns.find_inlined_functions(probe).map(|inlined_fn| {
+        let inlined_fns = function.parse_inlined_functions(unit.dw_unit(self)?, self)?;
+        let iter = inlined_fns.find_inlined_functions(probe).map(move |(inlined_fn, range)| {
             let name = inlined_fn
                 .name
                 .map(|name| name.to_string())
@@ -379,17 +597,42 @@ impl<'dwarf> Units<'dwarf> {
             } else {
                 None
             };
-            Ok((name, code_info))
+
+            let origin_addr = match inlined_fn.abstract_origin {
+                Some(origin) if origin_addrs => self.find_origin_addr(origin, unit)?,
+                _ => None,
+            };
+            Ok((name, code_info, range.begin, origin_addr))
         });
         Ok(Some(iter))
     }
 
+    /// Find the unit containing the code at the given address.
+    ///
+    /// Because unit ranges may overlap, a unit is only reported if it
+    /// contains a function or line information for the address.
+    pub(super) fn find_unit_by_addr(
+        &self,
+        probe: u64,
+    ) -> Result<Option<&Unit<'dwarf>>, gimli::Error> {
+        if let Some((_function, unit)) = self.find_function(probe)? {
+            return Ok(Some(unit))
+        }
+        if let Some((_location, unit)) = self.find_location(probe)? {
+            return Ok(Some(unit))
+        }
+        Ok(None)
+    }
+
     /// Find the source file and line corresponding to the given virtual memory
-    /// address.
-    pub fn find_location(&self, probe: u64) -> Result<Option<Location<'_>>, gimli::Error> {
+    /// address, along with the unit containing this information.
+    pub fn find_location(
+        &self,
+        probe: u64,
+    ) -> Result<Option<(Location<'_>, &Unit<'dwarf>)>, gimli::Error> {
         for unit in self.find_units(probe) {
             if let Some(location) = unit.find_location(probe, self)? {
-                return Ok(Some(location))
+                return Ok(Some((location, unit)))
             }
         }
         Ok(None)
@@ -404,6 +647,142 @@ impl<'dwarf> Units<'dwarf> {
             .filter_map(move |unit| unit.find_name(name, self).transpose())
     }
 
+    /// Find the functions with the given names.
+    ///
+    /// This method is equivalent to calling [`Units::find_name`] for
+    /// each of the names, but walks the compilation units only once.
+    /// Functions are reported grouped by name, in the order of `names`.
+    pub(super) fn find_names(
+        &self,
+        names: &[&str],
+    ) -> Result<Vec<Vec<&Function<'dwarf>>>, gimli::Error> {
+        let mut indices = HashMap::<&[u8], Vec<usize>>::with_capacity(names.len());
+        for (idx, name) in names.iter().enumerate() {
+            let () = indices.entry(name.as_bytes()).or_default().push(idx);
+        }
+
+        let mut funcs = vec![Vec::new(); names.len()];
+        for unit in self.units.iter() {
+            let () = unit.find_names(&indices, self, &mut funcs)?;
+        }
+        Ok(funcs)
+    }
+
+    /// Find the lowest address attributed to the given source file and
+    /// line.
+    ///
+    /// A relative `file` matches all source files whose path ends with
+    /// it.
+    pub(super) fn find_line_addr(
+        &self,
+        file: &Path,
+        line: u32,
+    ) -> Result<Option<u64>, gimli::Error> {
+        let mut addr = None::<u64>;
+
+        for unit in self.units.iter() {
+            let lines = match unit.parse_lines(self)? {
+                Some(lines) => lines,
+                None => continue,
+            };
+
+            let matches = lines
+                .files
+                .iter()
+                .map(|(dir, name)| dir.join(name).ends_with(file))
+                .collect::<Vec<_>>();
+            if !matches.contains(&true) {
+                continue
+            }
+
+            let rows = lines
+                .sequences
+                .iter()
+                .flat_map(|sequence| sequence.rows.iter())
+                .filter(|row| {
+                    row.line == line
+                        && usize::try_from(row.file_index)
+                            .ok()
+                            .and_then(|idx| matches.get(idx))
+                            .copied()
+                            .unwrap_or(false)
+                });
+
+            for row in rows {
+                addr = Some(addr.map_or(row.address, |addr| addr.min(row.address)));
+            }
+        }
+        Ok(addr)
+    }
+
+    /// Invoke `f` for each unique source file referenced by any of the
+    /// units, either as the unit's primary source file or as part of
+    /// its line program's file table.
+    ///
+    /// Relative paths are interpreted relative to the unit's
+    /// compilation directory, if any.
+    pub(super) fn for_each_source_file(
+        &self,
+        f: &mut dyn FnMut(&Path),
+    ) -> Result<(), gimli::Error> {
+        let mut seen = HashSet::<PathBuf>::new();
+        let mut report = |path: PathBuf| {
+            if !seen.contains(&path) {
+                let () = f(&path);
+                let _inserted = seen.insert(path);
+            }
+        };
+
+        for unit in self.units.iter() {
+            if let Some(path) = unit.path(self)? {
+                let () = report(path);
+            }
+
+            if let Some(lines) = unit.parse_lines(self)? {
+                let comp_dir = unit.comp_dir(self)?;
+                for (dir, file) in lines.files.iter() {
+                    let path = match comp_dir {
+                        Some(comp_dir) => comp_dir.join(dir).join(file),
+                        None => dir.join(file),
+                    };
+                    let () = report(path);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Collect the address ranges covered by line information and by
+    /// functions, respectively.
+    pub(super) fn coverage_ranges(
+        &self,
+    ) -> Result<(Vec<gimli::Range>, Vec<gimli::Range>), gimli::Error> {
+        let mut line_ranges = Vec::new();
+        let mut func_ranges = Vec::new();
+
+        for unit in self.units.iter() {
+            if let Some(lines) = unit.parse_lines(self)? {
+                let () = line_ranges.extend(lines.sequences.iter().map(|sequence| gimli::Range {
+                    begin: sequence.start,
+                    end: sequence.end,
+                }));
+            }
+            let () = func_ranges.extend(unit.function_ranges(self)?.copied());
+        }
+        Ok((line_ranges, func_ranges))
+    }
+
+    /// Find the type with the given name in any of the units.
+    #[cfg(feature = "type-info")]
+    pub(super) fn find_type(&self, name: &str) -> Result<Option<TypeInfo>, gimli::Error> {
+        for unit in self.units.iter() {
+            if let Some(info) = type_info::find_type(self, unit.dw_unit(self)?, name)? {
+                return Ok(Some(info))
+            }
+        }
+        Ok(None)
+    }
+
     /// Initialize all function data structures. This is used for benchmarks.
     #[cfg(test)]
     #[cfg(feature = "nightly")]
@@ -435,11 +814,57 @@ impl<'dwarf> Units<'dwarf> {
         Ok(())
     }
 
+    /// Retrieve the address range `[start, end)` spanned by all units.
+    pub(super) fn addr_bounds(&self) -> Option<(u64, u64)> {
+        let first = self.unit_ranges.first()?;
+        let last = self.unit_ranges.last()?;
+        Some((first.range.begin, last.max_end))
+    }
+
     /// Retrieve the underlying [`gimli::Dwarf`] object.
     #[inline]
     pub(super) fn dwarf(&self) -> &gimli::Dwarf<R<'dwarf>> {
         &self.dwarf
     }
+
+    /// Retrieve the string value of the given attribute.
+    ///
+    /// Values of a form that cannot be interpreted as a string are
+    /// ignored, unless in strict mode.
+    pub(super) fn attr_string(
+        &self,
+        unit: &gimli::Unit<R<'dwarf>>,
+        value: gimli::AttributeValue<R<'dwarf>>,
+    ) -> Result<Option<R<'dwarf>>, gimli::Error> {
+        match self.dwarf.attr_string(unit, value) {
+            Ok(string) => Ok(Some(string)),
+            Err(err) if self.strict => Err(err),
+            Err(_) => Ok(None),
+        }
+    }
+
+    /// Handle an attribute value of a form that we do not support,
+    /// which is ignored unless in strict mode.
+    #[inline]
+    pub(super) fn unsupported_form(&self) -> Result<(), gimli::Error> {
+        if self.strict {
+            Err(gimli::Error::UnsupportedAttributeForm)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Count the units with parsed line information and the functions
+    /// with parsed inlined functions.
+    #[cfg(test)]
+    pub(super) fn parse_state(&self) -> (usize, usize) {
+        self.units.iter().map(Unit::parse_state).fold(
+            (0, 0),
+            |(lines, inlined), (unit_lines, unit_inlined)| {
+                (lines + usize::from(unit_lines), inlined + unit_inlined)
+            },
+        )
+    }
 }
 
 
@@ -460,6 +885,7 @@ mod tests {
 
     use test_log::test;
 
+    use crate::dwarf::function::Functions;
     use crate::dwarf::reader;
     use crate::elf::ElfParser;
 
@@ -496,7 +922,7 @@ mod tests {
             let parser = ElfParser::open(bin_name.as_ref()).unwrap();
             let mut load_section = |section| reader::load_section(&parser, section);
             let dwarf = Dwarf::<R>::load(&mut load_section).unwrap();
-            let units = Units::parse(dwarf).unwrap();
+            let units = Units::parse(dwarf, false).unwrap();
 
             // Double check that we actually did what we set out to do
             // by checking that we can find a function that we know
@@ -506,8 +932,9 @@ mod tests {
             assert_eq!(func.name.unwrap().to_string().unwrap(), "fibonacci");
 
             let addr = func.range.as_ref().unwrap().begin;
-            let loc = units.find_location(addr).unwrap().unwrap();
+            let (loc, unit) = units.find_location(addr).unwrap().unwrap();
             assert_ne!(loc.dir, Path::new(""));
+            assert!(unit.comp_dir(&units).unwrap().is_some());
             assert_eq!(loc.file, OsStr::new("test-exe.c"));
             assert_eq!(loc.line.unwrap(), 4);
 
@@ -537,7 +964,7 @@ mod tests {
             let parser = ElfParser::open(bin_name.as_ref()).unwrap();
             let mut load_section = |section| reader::load_section(&parser, section);
             let dwarf = Dwarf::<R>::load(&mut load_section).unwrap();
-            let units = Units::parse(dwarf).unwrap();
+            let units = Units::parse(dwarf, false).unwrap();
 
             // Bogus address typically somewhere in kernel space but
             // unlikely to be in any of our binaries.
@@ -547,10 +974,126 @@ mod tests {
             assert!(func.is_none());
 
             let loc = units.find_location(bogus_addr).unwrap();
-            assert_eq!(loc, None);
+            assert!(loc.is_none());
         }
     }
 
+    /// Check that we can parse `DW_TAG_label` entries and attribute
+    /// addresses to them.
+    #[test]
+    fn label_parsing() {
+        let bin_name = Path::new(&env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join("test-label.bin");
+
+        let parser = ElfParser::open(bin_name.as_ref()).unwrap();
+        let mut load_section = |section| reader::load_section(&parser, section);
+        let dwarf = Dwarf::<R>::load(&mut load_section).unwrap();
+        let units = Units::parse(dwarf, false).unwrap();
+
+        let func = units.find_name("with_label").next().unwrap().unwrap();
+        let addr = func.range.as_ref().unwrap().begin;
+        let (_func, unit) = units.find_function(addr).unwrap().unwrap();
+
+        let functions = Functions::parse(unit.dw_unit(&units).unwrap(), &units).unwrap();
+        let label = functions
+            .labels
+            .iter()
+            .find(|label| label.name.to_string().unwrap() == "out")
+            .unwrap();
+        let label_addr = label.addr;
+        let range = func.range.as_ref().unwrap();
+        assert!(range.begin <= label_addr && label_addr < range.end);
+
+        // The label is located inside of `with_label` and so the
+        // function shadows it.
+        assert!(functions.find_label(label_addr + 1).is_none());
+
+        // A label outside of any function is found for the addresses
+        // following it.
+        let bin_name = Path::new(&env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join("test-label-asm.bin");
+        let parser = ElfParser::open(bin_name.as_ref()).unwrap();
+        let mut load_section = |section| reader::load_section(&parser, section);
+        let dwarf = Dwarf::<R>::load(&mut load_section).unwrap();
+        let units = Units::parse(dwarf, false).unwrap();
+
+        let func = units.find_name("_start").next().unwrap().unwrap();
+        let range = func.range.as_ref().unwrap();
+        let unit = &units.units[0];
+        let functions = Functions::parse(unit.dw_unit(&units).unwrap(), &units).unwrap();
+        assert_eq!(functions.labels.len(), 1);
+        let label = &functions.labels[0];
+        assert_eq!(label.name.to_string().unwrap(), "asm_label");
+        assert_eq!(label.addr, range.end);
+
+        assert!(functions.find_label(range.begin).is_none());
+        assert!(functions.find_label(label.addr - 1).is_none());
+        for addr in [label.addr, label.addr + 1] {
+            let found = functions.find_label(addr).unwrap();
+            assert_eq!(found.addr, label.addr);
+        }
+    }
+
+    /// Check that units created from a [`UnitIndex`] are only parsed
+    /// once used.
+    #[test]
+    fn lazy_unit_parsing() {
+        let bin_name = Path::new(&env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join("test-stable-addrs.bin");
+        let parser = ElfParser::open(bin_name.as_ref()).unwrap();
+        let mut load_section = |section| reader::load_section(&parser, section);
+
+        let dwarf = Dwarf::<R>::load(&mut load_section).unwrap();
+        let units = Units::parse(dwarf, false).unwrap();
+        assert!(units.units.len() > 1);
+        assert!(units.units.iter().all(Unit::is_parsed));
+        let index = units.index();
+
+        let dwarf = Dwarf::<R>::load(&mut load_section).unwrap();
+        let units = Units::from_index(dwarf, index).unwrap();
+        assert!(!units.units.iter().any(Unit::is_parsed));
+
+        let mut funcs = units.find_name("factorial");
+        let func = funcs.next().unwrap().unwrap();
+        let addr = func.range.as_ref().unwrap().begin;
+        let (func, _unit) = units.find_function(addr).unwrap().unwrap();
+        assert_eq!(func.name.unwrap().to_string().unwrap(), "factorial");
+    }
+
+    /// Benchmark the creation of a `Units` object from scratch.
+    #[cfg(feature = "nightly")]
+    #[bench]
+    fn bench_units_parsing(b: &mut Bencher) {
+        let bin_name = env::current_exe().unwrap();
+        let parser = ElfParser::open(bin_name.as_ref()).unwrap();
+        let mut load_section = |section| reader::load_section(&parser, section);
+
+        let () = b.iter(|| {
+            let dwarf = Dwarf::<R>::load(&mut load_section).unwrap();
+            let _units = black_box(Units::parse(black_box(dwarf), false).unwrap());
+        });
+    }
+
+    /// Benchmark the creation of a `Units` object from a previously
+    /// retrieved [`UnitIndex`], as happens on a warm start.
+    #[cfg(feature = "nightly")]
+    #[bench]
+    fn bench_units_from_index(b: &mut Bencher) {
+        let bin_name = env::current_exe().unwrap();
+        let parser = ElfParser::open(bin_name.as_ref()).unwrap();
+        let mut load_section = |section| reader::load_section(&parser, section);
+        let dwarf = Dwarf::<R>::load(&mut load_section).unwrap();
+        let index = Units::parse(dwarf, false).unwrap().index();
+
+        let () = b.iter(|| {
+            let dwarf = Dwarf::<R>::load(&mut load_section).unwrap();
+            let _units = black_box(Units::from_index(black_box(dwarf), index.clone()).unwrap());
+        });
+    }
+
     /// Benchmark the parsing of all functions, end-to-end.
     #[cfg(feature = "nightly")]
     #[bench]
@@ -561,7 +1104,7 @@ mod tests {
 
         let () = b.iter(|| {
             let dwarf = Dwarf::<R>::load(&mut load_section).unwrap();
-            let units = Units::parse(black_box(dwarf)).unwrap();
+            let units = Units::parse(black_box(dwarf), false).unwrap();
             let _funcs = black_box(units.parse_functions().unwrap());
         });
     }
@@ -592,7 +1135,7 @@ mod tests {
 
         let () = b.iter(|| {
             let dwarf = Dwarf::<R>::load(&mut load_section).unwrap();
-            let units = Units::parse(black_box(dwarf)).unwrap();
+            let units = Units::parse(black_box(dwarf), false).unwrap();
             let _lines = black_box(units.parse_inlined_functions().unwrap());
         });
     }
@@ -623,7 +1166,7 @@ mod tests {
 
         let () = b.iter(|| {
             let dwarf = Dwarf::<R>::load(&mut load_section).unwrap();
-            let units = Units::parse(black_box(dwarf)).unwrap();
+            let units = Units::parse(black_box(dwarf), false).unwrap();
             let _lines = black_box(units.parse_lines().unwrap());
         });
     }
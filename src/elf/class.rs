@@ -0,0 +1,390 @@
+//! Conversion of 32 bit ELF data into its 64 bit equivalent.
+//!
+//! Our ELF parser works directly on top of the raw file data and
+//! interprets it using the ELF64 data structures. For `ELFCLASS32`
+//! files we hence create widened copies of all structures of interest.
+//! Given that widened structures no longer fit into the space the
+//! originals occupied, they are logically appended to the file's data
+//! and the headers referencing them are adjusted accordingly.
+//! Everything else, including offsets into other sections, stays valid
+//! that way and can still be read from the original data.
+
+use std::mem::size_of;
+use std::slice;
+
+use crate::util::ReadRaw as _;
+
+use super::types::ByteSwap;
+use super::types::Elf32_Chdr;
+use super::types::Elf32_Dyn;
+use super::types::Elf32_Ehdr;
+use super::types::Elf32_Phdr;
+use super::types::Elf32_Shdr;
+use super::types::Elf32_Sym;
+use super::types::Elf64_Chdr;
+use super::types::Elf64_Dyn;
+use super::types::Elf64_Ehdr;
+use super::types::Elf64_Phdr;
+use super::types::Elf64_Shdr;
+use super::types::Elf64_Sym;
+use super::types::EI_CLASS;
+use super::types::ELFCLASS32;
+use super::types::PN_XNUM;
+use super::types::SHF_COMPRESSED;
+use super::types::SHT_DYNAMIC;
+use super::types::SHT_DYNSYM;
+use super::types::SHT_NOBITS;
+use super::types::SHT_SYMTAB;
+
+
+/// Check whether `data` contains a 32 bit ELF file.
+pub(crate) fn is_elf32(data: &[u8]) -> bool {
+    matches!(
+        data.get(..=EI_CLASS),
+        Some([0x7f, b'E', b'L', b'F', ELFCLASS32])
+    )
+}
+
+
+/// Read the `T` at `offset` in `data`, converting it to host byte
+/// order if `foreign` is `true`.
+fn read_at<T>(data: &[u8], offset: usize, foreign: bool) -> Option<T>
+where
+    T: ByteSwap,
+{
+    let value = data.get(offset..)?.read_pod::<T>()?;
+    if foreign {
+        Some(value.byte_swap())
+    } else {
+        Some(value)
+    }
+}
+
+/// Write `value` to `offset` in `data`, converting it to foreign byte
+/// order if `foreign` is `true`.
+fn write_at<T>(data: &mut [u8], offset: usize, value: T, foreign: bool) -> Option<()>
+where
+    T: ByteSwap,
+{
+    let value = if foreign { value.byte_swap() } else { value };
+    let bytes = data.get_mut(offset..offset.checked_add(size_of::<T>())?)?;
+    // SAFETY: `bytes` is valid for writes of `sizeof(T)` bytes and we
+    //         don't make any assumption about its alignment.
+    let () = unsafe { bytes.as_mut_ptr().cast::<T>().write_unaligned(value) };
+    Some(())
+}
+
+/// The widened structures of a 32 bit ELF file.
+///
+/// Widened structures no longer fit into the space the originals
+/// occupied. They are hence kept separately from the file's data and
+/// are logically located past its end, starting at offset `base`. The
+/// widened ELF header logically replaces the original one.
+#[derive(Debug)]
+pub(crate) struct Widened {
+    /// The widened ELF header, followed by all other widened
+    /// structures.
+    // Storage is `u64` based to satisfy the alignment requirements of
+    // all ELF data structures.
+    storage: Vec<u64>,
+    /// The number of bytes of `storage` in use.
+    len: usize,
+    /// The logical offset of the widened structures following the ELF
+    /// header.
+    base: usize,
+}
+
+impl Widened {
+    fn new(file_len: usize) -> Option<Self> {
+        // Very small files may not even be able to hold the widened
+        // header, which the remaining structures must not overlap.
+        let base = file_len.checked_add(7)? & !7;
+        let base = base.max(size_of::<Elf64_Ehdr>());
+        let mut slf = Self {
+            storage: Vec::new(),
+            len: 0,
+            base,
+        };
+        let _offset = slf.reserve::<Elf64_Ehdr>(1)?;
+        Some(slf)
+    }
+
+    fn bytes(&self) -> &[u8] {
+        // SAFETY: `storage` is at least `len` bytes in size and any
+        //         bit pattern is a valid `u8`.
+        unsafe { slice::from_raw_parts(self.storage.as_ptr().cast::<u8>(), self.len) }
+    }
+
+    fn bytes_mut(&mut self) -> &mut [u8] {
+        // SAFETY: `storage` is at least `len` bytes in size and any
+        //         bit pattern is a valid `u8`.
+        unsafe { slice::from_raw_parts_mut(self.storage.as_mut_ptr().cast::<u8>(), self.len) }
+    }
+
+    /// Extend the storage by room for `count` objects of type `T`,
+    /// suitably aligned for all ELF data structures, returning the
+    /// storage offset of the first one.
+    fn reserve<T>(&mut self, count: usize) -> Option<usize> {
+        let offset = self.len.checked_add(7)? & !7;
+        let len = offset.checked_add(count.checked_mul(size_of::<T>())?)?;
+        let () = self.storage.resize(len.checked_add(7)? / 8, 0);
+        self.len = len;
+        Some(offset)
+    }
+
+    /// Convert a storage offset into the logical file offset it
+    /// represents.
+    fn logical(&self, offset: usize) -> u64 {
+        (self.base + offset - size_of::<Elf64_Ehdr>()) as u64
+    }
+
+    /// Retrieve the bytes of the widened ELF header.
+    pub(crate) fn ehdr(&self) -> &[u8] {
+        &self.bytes()[..size_of::<Elf64_Ehdr>()]
+    }
+
+    /// Retrieve the widened structures following the ELF header,
+    /// logically located at [`base`][Self::base].
+    pub(crate) fn structs(&self) -> &[u8] {
+        &self.bytes()[size_of::<Elf64_Ehdr>()..]
+    }
+
+    /// Retrieve the logical offset of [`structs`][Self::structs].
+    pub(crate) fn base(&self) -> u64 {
+        self.base as u64
+    }
+
+    /// Retrieve the size of the file's data with all widened
+    /// structures applied.
+    pub(crate) fn total_len(&self) -> usize {
+        self.base + self.structs().len()
+    }
+
+    /// Write the file's data, `file`, with all widened structures
+    /// applied into `buf`, which has to be
+    /// [`total_len`][Self::total_len] bytes in size.
+    pub(crate) fn apply(&self, file: &[u8], buf: &mut [u8]) {
+        let () = buf[..file.len()].copy_from_slice(file);
+        let () = buf[self.base..].copy_from_slice(self.structs());
+        let () = buf[..size_of::<Elf64_Ehdr>()].copy_from_slice(self.ehdr());
+    }
+}
+
+/// Widen the `count` objects of type `S` starting at `offset` in
+/// `data`, appending the converted `D` objects to `widened` and
+/// returning their logical offset.
+fn widen_array<S, D>(
+    data: &[u8],
+    offset: usize,
+    count: usize,
+    widened: &mut Widened,
+    foreign: bool,
+) -> Option<u64>
+where
+    S: ByteSwap,
+    D: ByteSwap + for<'s> From<&'s S>,
+{
+    let new_offset = widened.reserve::<D>(count)?;
+    let bytes = widened.bytes_mut();
+    for idx in 0..count {
+        let value = read_at::<S>(data, offset.checked_add(idx * size_of::<S>())?, foreign)?;
+        let () = write_at(
+            bytes,
+            new_offset + idx * size_of::<D>(),
+            D::from(&value),
+            foreign,
+        )?;
+    }
+    Some(widened.logical(new_offset))
+}
+
+/// Convert the 32 bit ELF data in `data` into its 64 bit equivalent.
+///
+/// If `foreign` is `true`, `data` is expected to be in foreign byte
+/// order and the converted data will be as well.
+///
+/// Only the structures our parser interprets by size are converted:
+/// the ELF, section, and program headers, symbols, `.dynamic`
+/// entries, and compression headers. Data of sections overlapping with the
+/// widened ELF header gets relocated. Everything else, including the `e_ident`
+/// bytes, is left untouched. `None` is returned if the data are malformed.
+pub(crate) fn widen_elf(data: &[u8], foreign: bool) -> Option<Widened> {
+    let ehdr = read_at::<Elf32_Ehdr>(data, 0, foreign)?;
+
+    let mut shdrs = Vec::new();
+    let shoff = usize::try_from(ehdr.e_shoff).ok()?;
+    if shoff != 0 {
+        let shdr = read_at::<Elf32_Shdr>(data, shoff, foreign)?;
+        let shnum = if ehdr.e_shnum == 0 {
+            usize::try_from(shdr.sh_size).ok()?
+        } else {
+            usize::from(ehdr.e_shnum)
+        };
+
+        for idx in 0..shnum {
+            let offset = shoff.checked_add(idx.checked_mul(size_of::<Elf32_Shdr>())?)?;
+            let shdr = read_at::<Elf32_Shdr>(data, offset, foreign)?;
+            let () = shdrs.push(Elf64_Shdr::from(&shdr));
+        }
+    }
+
+    let phnum = if ehdr.e_phnum == PN_XNUM {
+        shdrs.first()?.sh_info as usize
+    } else {
+        usize::from(ehdr.e_phnum)
+    };
+    let phoff = usize::try_from(ehdr.e_phoff).ok()?;
+
+    let mut widened = Widened::new(data.len())?;
+    for shdr in shdrs.iter_mut() {
+        if shdr.sh_type == SHT_NOBITS {
+            continue
+        }
+
+        let offset = usize::try_from(shdr.sh_offset).ok()?;
+        let size = usize::try_from(shdr.sh_size).ok()?;
+
+        if shdr.sh_flags & SHF_COMPRESSED != 0 {
+            // The compression header precedes the compressed data
+            // inside the section, so we have to relocate the section
+            // as a whole.
+            let chdr = read_at::<Elf32_Chdr>(data, offset, foreign)?;
+            let payload =
+                data.get(offset.checked_add(size_of::<Elf32_Chdr>())?..offset.checked_add(size)?)?;
+            let new_offset = widened.reserve::<Elf64_Chdr>(1)?;
+            let bytes = widened.bytes_mut();
+            let () = write_at(bytes, new_offset, Elf64_Chdr::from(&chdr), foreign)?;
+            let payload_offset = widened.reserve::<u8>(payload.len())?;
+            let () = widened.bytes_mut()[payload_offset..].copy_from_slice(payload);
+            shdr.sh_offset = widened.logical(new_offset);
+            shdr.sh_size = (size_of::<Elf64_Chdr>() + payload.len()) as u64;
+            continue
+        }
+
+        match shdr.sh_type {
+            SHT_SYMTAB | SHT_DYNSYM => {
+                let count = size / size_of::<Elf32_Sym>();
+                shdr.sh_offset = widen_array::<Elf32_Sym, Elf64_Sym>(
+                    data,
+                    offset,
+                    count,
+                    &mut widened,
+                    foreign,
+                )?;
+                shdr.sh_size = (count * size_of::<Elf64_Sym>()) as u64;
+                shdr.sh_entsize = size_of::<Elf64_Sym>() as u64;
+            }
+            SHT_DYNAMIC => {
+                let count = size / size_of::<Elf32_Dyn>();
+                shdr.sh_offset = widen_array::<Elf32_Dyn, Elf64_Dyn>(
+                    data,
+                    offset,
+                    count,
+                    &mut widened,
+                    foreign,
+                )?;
+                shdr.sh_size = (count * size_of::<Elf64_Dyn>()) as u64;
+                shdr.sh_entsize = size_of::<Elf64_Dyn>() as u64;
+            }
+            // The widened ELF header logically covers data directly
+            // following the original one. That is typically where
+            // program headers reside, which we relocate anyway, but
+            // object files may contain section data there.
+            _ if size != 0 && offset < size_of::<Elf64_Ehdr>() => {
+                let section = data.get(offset..offset.checked_add(size)?)?;
+                let new_offset = widened.reserve::<u8>(size)?;
+                let () = widened.bytes_mut()[new_offset..].copy_from_slice(section);
+                shdr.sh_offset = widened.logical(new_offset);
+            }
+            _ => (),
+        }
+    }
+
+    let new_phoff = if phnum != 0 {
+        widen_array::<Elf32_Phdr, Elf64_Phdr>(data, phoff, phnum, &mut widened, foreign)?
+    } else {
+        0
+    };
+
+    let new_shoff = if !shdrs.is_empty() {
+        let new_shoff = widened.reserve::<Elf64_Shdr>(shdrs.len())?;
+        let bytes = widened.bytes_mut();
+        for (idx, shdr) in shdrs.into_iter().enumerate() {
+            let offset = new_shoff + idx * size_of::<Elf64_Shdr>();
+            let () = write_at(bytes, offset, shdr, foreign)?;
+        }
+        widened.logical(new_shoff)
+    } else {
+        0
+    };
+
+    let mut ehdr = Elf64_Ehdr::from(&ehdr);
+    ehdr.e_phoff = new_phoff;
+    ehdr.e_shoff = new_shoff;
+    ehdr.e_ehsize = size_of::<Elf64_Ehdr>() as u16;
+    ehdr.e_phentsize = size_of::<Elf64_Phdr>() as u16;
+    ehdr.e_shentsize = size_of::<Elf64_Shdr>() as u16;
+    let () = write_at(widened.bytes_mut(), 0, ehdr, foreign)?;
+
+    Some(widened)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::env;
+    use std::fs::read;
+    use std::path::Path;
+
+
+    /// Check that we correctly detect 32 bit ELF data.
+    #[test]
+    fn elf32_detection() {
+        let mut data = *b"\x7fELF\x01\x01\x01";
+        assert!(is_elf32(&data));
+        assert!(!is_elf32(&data[..4]));
+
+        data[EI_CLASS] = 2;
+        assert!(!is_elf32(&data));
+    }
+
+    /// Check that widening 32 bit ELF data results in a consistent
+    /// ELF64 representation.
+    #[test]
+    fn widening() {
+        let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join("libtest-so-32.so");
+        let data = read(path).unwrap();
+        assert!(is_elf32(&data));
+
+        let original = (&*data).read_pod::<Elf32_Ehdr>().unwrap();
+        let widened = widen_elf(&data, false).unwrap();
+        assert!(widened.base() >= data.len() as u64);
+        let mut buf = vec![0; widened.total_len()];
+        let () = widened.apply(&data, &mut buf);
+        let widened = buf;
+        let ehdr = (&*widened).read_pod::<Elf64_Ehdr>().unwrap();
+        assert_eq!(ehdr.e_ident, original.e_ident);
+        assert_eq!(ehdr.e_machine, original.e_machine);
+        assert_eq!(ehdr.e_phnum, original.e_phnum);
+        assert_eq!(ehdr.e_shnum, original.e_shnum);
+
+        let phdrs = widened
+            .get(ehdr.e_phoff as usize..)
+            .unwrap()
+            .read_pod_slice_ref::<Elf64_Phdr>(ehdr.e_phnum.into())
+            .unwrap();
+        for (idx, phdr) in phdrs.iter().enumerate() {
+            let offset = original.e_phoff as usize + idx * size_of::<Elf32_Phdr>();
+            let orig = read_at::<Elf32_Phdr>(&data, offset, false).unwrap();
+            assert_eq!(phdr.p_type, orig.p_type);
+            assert_eq!(phdr.p_flags, orig.p_flags);
+            assert_eq!(phdr.p_offset, u64::from(orig.p_offset));
+            assert_eq!(phdr.p_vaddr, u64::from(orig.p_vaddr));
+            assert_eq!(phdr.p_memsz, u64::from(orig.p_memsz));
+        }
+    }
+}
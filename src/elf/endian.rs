@@ -0,0 +1,346 @@
+//! Conversion of ELF data between byte orders.
+//!
+//! Our ELF parser works directly on top of the raw file data and
+//! interprets it in the host's byte order. For files of foreign byte
+//! order we hence create a copy of the data in which all structures
+//! of interest have been converted.
+
+use std::mem::size_of;
+use std::ops::Range;
+
+use crate::util::ReadRaw as _;
+
+use super::types::ByteSwap;
+use super::types::Elf64_Chdr;
+use super::types::Elf64_Dyn;
+use super::types::Elf64_Ehdr;
+use super::types::Elf64_Nhdr;
+use super::types::Elf64_Phdr;
+use super::types::Elf64_Rela;
+use super::types::Elf64_Shdr;
+use super::types::Elf64_Sym;
+use super::types::Elf64_Verdaux;
+use super::types::Elf64_Verdef;
+use super::types::Elf64_Vernaux;
+use super::types::Elf64_Verneed;
+use super::types::EI_CLASS;
+use super::types::EI_DATA;
+use super::types::ELFCLASS32;
+use super::types::ELFDATA2LSB;
+use super::types::ELFDATA2MSB;
+use super::types::PN_XNUM;
+use super::types::PT_NOTE;
+use super::types::SHF_COMPRESSED;
+use super::types::SHT_DYNAMIC;
+use super::types::SHT_DYNSYM;
+use super::types::SHT_GNU_HASH;
+use super::types::SHT_GNU_VERDEF;
+use super::types::SHT_GNU_VERNEED;
+use super::types::SHT_GNU_VERSYM;
+use super::types::SHT_NOBITS;
+use super::types::SHT_NOTE;
+use super::types::SHT_RELA;
+use super::types::SHT_SYMTAB;
+
+
+/// The `EI_DATA` value describing the host's byte order.
+#[cfg(target_endian = "little")]
+const ELFDATA_NATIVE: u8 = ELFDATA2LSB;
+#[cfg(target_endian = "big")]
+const ELFDATA_NATIVE: u8 = ELFDATA2MSB;
+
+
+/// Check whether the ELF data in `data` is of a byte order different
+/// from that of the host.
+pub(crate) fn is_foreign(data: &[u8]) -> bool {
+    match data.get(..=EI_DATA) {
+        Some([0x7f, b'E', b'L', b'F', _, encoding]) => {
+            matches!(*encoding, ELFDATA2LSB | ELFDATA2MSB) && *encoding != ELFDATA_NATIVE
+        }
+        _ => false,
+    }
+}
+
+
+/// Swap the byte order of the `T` at `offset` in `data`, returning
+/// the value in host byte order.
+fn swap_at<T>(data: &mut [u8], offset: usize, to_native: bool) -> Option<T>
+where
+    T: ByteSwap,
+{
+    let bytes = data.get_mut(offset..offset.checked_add(size_of::<T>())?)?;
+    let value = (&*bytes).read_pod::<T>()?;
+    let swapped = value.byte_swap();
+    // SAFETY: `bytes` is valid for writes of `sizeof(T)` bytes and we
+    //         don't make any assumption about its alignment.
+    let () = unsafe { bytes.as_mut_ptr().cast::<T>().write_unaligned(swapped) };
+
+    if to_native {
+        (&*bytes).read_pod::<T>()
+    } else {
+        Some(value)
+    }
+}
+
+/// Swap the byte order of all `T` objects stored back-to-back in the
+/// `size` bytes starting at `offset`.
+fn swap_array<T>(data: &mut [u8], offset: usize, size: usize, to_native: bool) -> Option<()>
+where
+    T: ByteSwap,
+{
+    for idx in 0..size / size_of::<T>() {
+        let _value = swap_at::<T>(data, offset + idx * size_of::<T>(), to_native)?;
+    }
+    Some(())
+}
+
+/// Swap the byte order of the headers of all notes in `range`.
+fn swap_notes(data: &mut [u8], range: Range<usize>, align: u64, to_native: bool) -> Option<()> {
+    // Notes are four byte aligned, except for those in sections or
+    // segments explicitly requiring eight byte alignment.
+    let align = if align == 8 { 8 } else { 4 };
+    let align_up = |value: usize| Some(value.checked_add(align - 1)? & !(align - 1));
+
+    let mut offset = range.start;
+    while offset.checked_add(size_of::<Elf64_Nhdr>())? <= range.end {
+        let nhdr = swap_at::<Elf64_Nhdr>(data, offset, to_native)?;
+        let name_end = (offset + size_of::<Elf64_Nhdr>()).checked_add(nhdr.n_namesz as usize)?;
+        let desc = align_up(name_end)?;
+        offset = align_up(desc.checked_add(nhdr.n_descsz as usize)?)?;
+    }
+    Some(())
+}
+
+/// Swap the byte order of the `.gnu.hash` section of `size` bytes
+/// starting at `offset`.
+fn swap_gnu_hash(
+    data: &mut [u8],
+    offset: usize,
+    size: usize,
+    elf32: bool,
+    to_native: bool,
+) -> Option<()> {
+    // In 32 bit ELF files the section consists solely of 32 bit words.
+    if elf32 {
+        return swap_array::<u32>(data, offset, size, to_native)
+    }
+
+    // Otherwise, the header consists of four 32 bit words, the third
+    // of which is the number of 64 bit Bloom filter words following
+    // it. Buckets and hash chain values make up the remainder, again
+    // using 32 bit words.
+    let _nbuckets = swap_at::<u32>(data, offset, to_native)?;
+    let _symoffset = swap_at::<u32>(data, offset + 4, to_native)?;
+    let bloom_size = swap_at::<u32>(data, offset + 8, to_native)? as usize;
+    let _bloom_shift = swap_at::<u32>(data, offset + 12, to_native)?;
+
+    let bloom_offset = offset + 16;
+    let bloom_bytes = bloom_size.checked_mul(size_of::<u64>())?;
+    let () = swap_array::<u64>(data, bloom_offset, bloom_bytes, to_native)?;
+
+    let rest_offset = bloom_offset.checked_add(bloom_bytes)?;
+    let rest_size = size.checked_sub(16 + bloom_bytes)?;
+    swap_array::<u32>(data, rest_offset, rest_size, to_native)
+}
+
+/// Swap the byte order of the `count` version definitions starting
+/// at `offset`, as found in a `.gnu.version_d` section.
+fn swap_verdefs(data: &mut [u8], offset: usize, count: u32, to_native: bool) -> Option<()> {
+    let mut def = offset;
+    for _ in 0..count {
+        let verdef = swap_at::<Elf64_Verdef>(data, def, to_native)?;
+        let mut aux = def.checked_add(verdef.vd_aux as usize)?;
+        for _ in 0..verdef.vd_cnt {
+            let verdaux = swap_at::<Elf64_Verdaux>(data, aux, to_native)?;
+            if verdaux.vda_next == 0 {
+                break
+            }
+            aux = aux.checked_add(verdaux.vda_next as usize)?;
+        }
+
+        if verdef.vd_next == 0 {
+            break
+        }
+        def = def.checked_add(verdef.vd_next as usize)?;
+    }
+    Some(())
+}
+
+/// Swap the byte order of the `count` version dependencies starting
+/// at `offset`, as found in a `.gnu.version_r` section.
+fn swap_verneeds(data: &mut [u8], offset: usize, count: u32, to_native: bool) -> Option<()> {
+    let mut need = offset;
+    for _ in 0..count {
+        let verneed = swap_at::<Elf64_Verneed>(data, need, to_native)?;
+        let mut aux = need.checked_add(verneed.vn_aux as usize)?;
+        for _ in 0..verneed.vn_cnt {
+            let vernaux = swap_at::<Elf64_Vernaux>(data, aux, to_native)?;
+            if vernaux.vna_next == 0 {
+                break
+            }
+            aux = aux.checked_add(vernaux.vna_next as usize)?;
+        }
+
+        if verneed.vn_next == 0 {
+            break
+        }
+        need = need.checked_add(verneed.vn_next as usize)?;
+    }
+    Some(())
+}
+
+/// Convert the ELF data in `data` between foreign and host byte
+/// order.
+///
+/// If `to_native` is `true`, `data` is expected to be in foreign byte
+/// order, otherwise it is assumed to be in host byte order.
+///
+/// Only the structures our parser interprets are converted: the ELF,
+/// section, and program headers, symbols, `.dynamic` entries,
+/// relocations, GNU hash tables, symbol versioning sections, note
+/// headers, and compression headers. Everything else, including the
+/// `e_ident` bytes, is left untouched. Conversion stops at the first
+/// malformed structure, in which case `None` is returned.
+pub(crate) fn swap_elf(data: &mut [u8], to_native: bool) -> Option<()> {
+    let ehdr = swap_at::<Elf64_Ehdr>(data, 0, to_native)?;
+    // 32 bit ELF files have been widened already, but some section
+    // contents still differ in layout.
+    let elf32 = ehdr.e_ident[EI_CLASS] == ELFCLASS32;
+
+    let mut shdrs = Vec::new();
+    let shoff = usize::try_from(ehdr.e_shoff).ok()?;
+    if shoff != 0 {
+        let shdr = swap_at::<Elf64_Shdr>(data, shoff, to_native)?;
+        let shnum = if ehdr.e_shnum == 0 {
+            usize::try_from(shdr.sh_size).ok()?
+        } else {
+            usize::from(ehdr.e_shnum)
+        };
+        let () = shdrs.push(shdr);
+
+        for idx in 1..shnum {
+            let offset = shoff.checked_add(idx.checked_mul(size_of::<Elf64_Shdr>())?)?;
+            let shdr = swap_at::<Elf64_Shdr>(data, offset, to_native)?;
+            let () = shdrs.push(shdr);
+        }
+    }
+
+    let phnum = if ehdr.e_phnum == PN_XNUM {
+        shdrs.first()?.sh_info as usize
+    } else {
+        usize::from(ehdr.e_phnum)
+    };
+    let phoff = usize::try_from(ehdr.e_phoff).ok()?;
+    let mut phdrs = Vec::with_capacity(phnum);
+    for idx in 0..phnum {
+        let offset = phoff.checked_add(idx.checked_mul(size_of::<Elf64_Phdr>())?)?;
+        let phdr = swap_at::<Elf64_Phdr>(data, offset, to_native)?;
+        let () = phdrs.push(phdr);
+    }
+
+    let mut note_ranges = Vec::new();
+    for shdr in shdrs {
+        if shdr.sh_type == SHT_NOBITS {
+            continue
+        }
+
+        let offset = usize::try_from(shdr.sh_offset).ok()?;
+        let size = usize::try_from(shdr.sh_size).ok()?;
+
+        if shdr.sh_flags & SHF_COMPRESSED != 0 {
+            // The compressed data itself is opaque to us, but the
+            // compression header preceding it is not.
+            let _chdr = swap_at::<Elf64_Chdr>(data, offset, to_native)?;
+            continue
+        }
+
+        match shdr.sh_type {
+            SHT_SYMTAB | SHT_DYNSYM => swap_array::<Elf64_Sym>(data, offset, size, to_native)?,
+            SHT_DYNAMIC => swap_array::<Elf64_Dyn>(data, offset, size, to_native)?,
+            SHT_RELA => swap_array::<Elf64_Rela>(data, offset, size, to_native)?,
+            SHT_GNU_HASH => swap_gnu_hash(data, offset, size, elf32, to_native)?,
+            SHT_GNU_VERSYM => swap_array::<u16>(data, offset, size, to_native)?,
+            SHT_GNU_VERDEF => swap_verdefs(data, offset, shdr.sh_info, to_native)?,
+            SHT_GNU_VERNEED => swap_verneeds(data, offset, shdr.sh_info, to_native)?,
+            SHT_NOTE => {
+                let range = offset..offset.checked_add(size)?;
+                let () = swap_notes(data, range.clone(), shdr.sh_addralign, to_native)?;
+                let () = note_ranges.push(range);
+            }
+            _ => (),
+        }
+    }
+
+    // Note segments typically overlap with note sections, which we
+    // already converted. However, files such as core dumps may contain
+    // notes only referenced by program headers.
+    for phdr in phdrs {
+        if phdr.p_type != PT_NOTE {
+            continue
+        }
+
+        let offset = usize::try_from(phdr.p_offset).ok()?;
+        let size = usize::try_from(phdr.p_filesz).ok()?;
+        let range = offset..offset.checked_add(size)?;
+        if note_ranges
+            .iter()
+            .any(|other| other.start < range.end && range.start < other.end)
+        {
+            continue
+        }
+        let () = swap_notes(data, range, phdr.p_align, to_native)?;
+    }
+    Some(())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::env;
+    use std::fs::read;
+    use std::path::Path;
+
+
+    /// Check that converting ELF data to foreign byte order and back
+    /// results in the original data.
+    #[test]
+    fn conversion_round_trip() {
+        for file in ["test-stable-addrs.bin", "libtest-versioned.so"] {
+            let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+                .join("data")
+                .join(file);
+            let data = read(path).unwrap();
+            assert!(!is_foreign(&data));
+
+            let mut converted = data.clone();
+            let () = swap_elf(&mut converted, false).unwrap();
+            assert_ne!(converted, data);
+
+            let () = swap_elf(&mut converted, true).unwrap();
+            assert_eq!(converted, data);
+        }
+    }
+
+    /// Check that we correctly detect ELF data of foreign byte order.
+    #[test]
+    fn foreign_detection() {
+        let mut data = *b"\x7fELF\x02\x01\x01";
+        assert!(!is_foreign(&data[..3]));
+
+        data[EI_DATA] = ELFDATA_NATIVE;
+        assert!(!is_foreign(&data));
+
+        data[EI_DATA] = if ELFDATA_NATIVE == ELFDATA2LSB {
+            ELFDATA2MSB
+        } else {
+            ELFDATA2LSB
+        };
+        assert!(is_foreign(&data));
+
+        // Invalid encodings are not considered foreign.
+        data[EI_DATA] = 0;
+        assert!(!is_foreign(&data));
+    }
+}
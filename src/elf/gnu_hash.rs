@@ -0,0 +1,139 @@
+//! Support for GNU style symbol hash tables (`.gnu.hash`).
+//!
+//! The table consists of a header, a Bloom filter, a bucket array, and
+//! a hash value array. Only dynamic symbols starting at index
+//! `symoffset` are covered by the table. We currently only use the
+//! Bloom filter.
+//!
+//! Bloom filter words have the native word size of the ELF class, i.e.,
+//! they are 32 bit wide for `ELFCLASS32` and 64 bit wide for
+//! `ELFCLASS64` files.
+
+use crate::util::ReadRaw as _;
+use crate::Error;
+use crate::Result;
+
+
+/// Calculate the GNU hash of a symbol name.
+pub(crate) fn gnu_hash(name: &[u8]) -> u32 {
+    name.iter().fold(5381u32, |hash, byte| {
+        hash.wrapping_mul(33).wrapping_add(u32::from(*byte))
+    })
+}
+
+
+/// The words making up a GNU hash table's Bloom filter.
+#[derive(Debug)]
+enum Bloom<'dat> {
+    /// The Bloom filter of a 32 bit ELF file.
+    Elf32(&'dat [u32]),
+    /// The Bloom filter of a 64 bit ELF file.
+    Elf64(&'dat [u64]),
+}
+
+
+/// A parsed GNU hash table, as found in the `.gnu.hash` section of an
+/// ELF file.
+#[derive(Debug)]
+pub(crate) struct GnuHashTable<'dat> {
+    /// The index of the first dynamic symbol covered by the table.
+    symoffset: u32,
+    /// The shift count used for deriving the second Bloom filter bit.
+    bloom_shift: u32,
+    /// The Bloom filter words.
+    bloom: Bloom<'dat>,
+}
+
+impl<'dat> GnuHashTable<'dat> {
+    /// Parse a GNU hash table from the contents of a `.gnu.hash`
+    /// section of an ELF file with addresses of `address_size` bytes.
+    pub(crate) fn parse(mut data: &'dat [u8], address_size: u8) -> Result<Self> {
+        let mut read_u32 = || {
+            data.read_u32()
+                .ok_or_else(|| Error::with_invalid_data("failed to read GNU hash table header"))
+        };
+        let nbuckets = read_u32()?;
+        let symoffset = read_u32()?;
+        let bloom_size = read_u32()?;
+        let bloom_shift = read_u32()?;
+
+        if bloom_size == 0 {
+            return Err(Error::with_invalid_data(
+                "GNU hash table has empty Bloom filter",
+            ))
+        }
+
+        let bloom = if address_size == 4 {
+            data.read_pod_slice_ref::<u32>(bloom_size as usize)
+                .map(Bloom::Elf32)
+        } else {
+            data.read_pod_slice_ref::<u64>(bloom_size as usize)
+                .map(Bloom::Elf64)
+        }
+        .ok_or_else(|| Error::with_invalid_data("failed to read GNU hash Bloom filter"))?;
+        // We don't currently use the buckets and hash chain for
+        // anything, but still make sure that they are present.
+        let _buckets = data
+            .read_pod_slice_ref::<u32>(nbuckets as usize)
+            .ok_or_else(|| Error::with_invalid_data("failed to read GNU hash buckets"))?;
+
+        let slf = Self {
+            symoffset,
+            bloom_shift,
+            bloom,
+        };
+        Ok(slf)
+    }
+
+    /// Retrieve the index of the first dynamic symbol covered by the
+    /// table.
+    #[inline]
+    pub(crate) fn symoffset(&self) -> usize {
+        self.symoffset as usize
+    }
+
+    /// Check whether the Bloom filter indicates that a symbol with the
+    /// given hash may be present in the table.
+    ///
+    /// A result of `false` means that the symbol is definitely not
+    /// present.
+    pub(crate) fn bloom_may_contain(&self, hash: u32) -> bool {
+        match self.bloom {
+            Bloom::Elf32(words) => {
+                let bits = u32::BITS;
+                let word = words[(hash / bits) as usize % words.len()];
+                let mask = (1u32 << (hash % bits)) | (1u32 << ((hash >> self.bloom_shift) % bits));
+                word & mask == mask
+            }
+            Bloom::Elf64(words) => {
+                let bits = u64::BITS;
+                let word = words[(hash / bits) as usize % words.len()];
+                let mask = (1u64 << (hash % bits)) | (1u64 << ((hash >> self.bloom_shift) % bits));
+                word & mask == mask
+            }
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+
+    /// Check that we calculate GNU hash values correctly.
+    #[test]
+    fn hash_calculation() {
+        assert_eq!(gnu_hash(b""), 0x00001505);
+        assert_eq!(gnu_hash(b"printf"), 0x156b2bb8);
+        assert_eq!(gnu_hash(b"exit"), 0x7c967e3f);
+        assert_eq!(gnu_hash(b"syscall"), 0xbac212a0);
+    }
+
+    /// Make sure that we reject malformed tables.
+    #[test]
+    fn malformed_table() {
+        let err = GnuHashTable::parse(&[0x1, 0x0], 8).unwrap_err();
+        assert!(err.to_string().contains("header"), "{err}");
+    }
+}
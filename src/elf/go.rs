@@ -0,0 +1,651 @@
+//! Symbolization of Go binaries using the Go PC-line table.
+//!
+//! The Go toolchain emits a table mapping program counters to function
+//! names, source files, and line numbers into every binary it builds,
+//! typically as part of the `.gopclntab` section. The Go runtime
+//! relies on this table for stack traces, which is why it is retained
+//! even if the binary is stripped of DWARF debug information (e.g., by
+//! building with `-ldflags=-w`).
+//!
+//! We understand the layouts used by Go 1.18 and later as well as the
+//! older ones of Go 1.16 and Go 1.2. Refer to the `debug/gosym`
+//! package of the Go standard library for the reference
+//! implementation.
+
+use std::borrow::Cow;
+use std::ffi::CStr;
+use std::ffi::OsStr;
+use std::ops::Range;
+use std::os::unix::ffi::OsStrExt as _;
+use std::path::Path;
+
+use crate::inspect::FindAddrOpts;
+use crate::log::debug;
+use crate::symbolize::CodeInfo;
+use crate::symbolize::FindSymOpts;
+use crate::symbolize::Provenance;
+use crate::symbolize::ResolvedSym;
+use crate::symbolize::SrcLang;
+use crate::util::ReadRaw as _;
+use crate::Addr;
+use crate::Result;
+
+use super::types::SHF_ALLOC;
+use super::types::SHT_NOBITS;
+use super::ElfParser;
+
+
+/// The magic number of a Go 1.2 to 1.15 PC-line table.
+const GO12_MAGIC: u32 = 0xfffffffb;
+/// The magic number of a Go 1.16 and 1.17 PC-line table.
+const GO116_MAGIC: u32 = 0xfffffffa;
+/// The magic number of a Go 1.18 and 1.19 PC-line table.
+const GO118_MAGIC: u32 = 0xfffffff0;
+/// The magic number of a Go 1.20+ PC-line table.
+const GO120_MAGIC: u32 = 0xfffffff1;
+
+
+/// The layout of a PC-line table.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Version {
+    Go12,
+    Go116,
+    /// The layout used by Go 1.18 and later. Go 1.20 merely changed
+    /// the semantics of fields we don't care about.
+    Go118,
+}
+
+
+/// Read the `u32` at `offset` in `data`.
+fn read_u32(data: &[u8], offset: usize, big_endian: bool) -> Option<u32> {
+    let bytes = data.get(offset..offset.checked_add(4)?)?;
+    // SANITY: We retrieved a slice of exactly four bytes above.
+    let bytes = <[u8; 4]>::try_from(bytes).unwrap();
+    if big_endian {
+        Some(u32::from_be_bytes(bytes))
+    } else {
+        Some(u32::from_le_bytes(bytes))
+    }
+}
+
+/// Read the pointer sized value at `offset` in `data`.
+fn read_uintptr(data: &[u8], offset: usize, ptr_size: usize, big_endian: bool) -> Option<u64> {
+    match ptr_size {
+        4 => read_u32(data, offset, big_endian).map(u64::from),
+        8 => {
+            let bytes = data.get(offset..offset.checked_add(8)?)?;
+            // SANITY: We retrieved a slice of exactly eight bytes above.
+            let bytes = <[u8; 8]>::try_from(bytes).unwrap();
+            if big_endian {
+                Some(u64::from_be_bytes(bytes))
+            } else {
+                Some(u64::from_le_bytes(bytes))
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Read the NUL terminated string at `offset` in `data`.
+fn read_cstr(data: &[u8], offset: usize) -> Option<&CStr> {
+    data.get(offset..)?.read_cstr()
+}
+
+
+/// A parsed PC-line table header, providing access to the table's
+/// contents.
+///
+/// All offsets are relative to the start of the table.
+#[derive(Debug)]
+struct Table {
+    version: Version,
+    big_endian: bool,
+    /// The instruction size quantum, in bytes.
+    quantum: u64,
+    /// The size of a pointer, in bytes.
+    ptr_size: usize,
+    /// The number of functions in the table.
+    nfunc: usize,
+    /// The address relative to which function entries are stored (Go
+    /// 1.18+ only).
+    text_start: Addr,
+    funcnametab: usize,
+    cutab: usize,
+    filetab: usize,
+    pctab: usize,
+    functab: usize,
+    /// The offset relative to which function data offsets in
+    /// `functab` are stored.
+    funcdata: usize,
+}
+
+impl Table {
+    /// Parse the header of the PC-line table in `data`.
+    ///
+    /// `text_addr` is the address of the `.text` section, which is
+    /// used if the table does not contain a usable text start
+    /// address.
+    fn parse(data: &[u8], big_endian: bool, text_addr: Option<Addr>) -> Option<Self> {
+        let magic = read_u32(data, 0, big_endian)?;
+        let version = match magic {
+            GO12_MAGIC => Version::Go12,
+            GO116_MAGIC => Version::Go116,
+            GO118_MAGIC | GO120_MAGIC => Version::Go118,
+            _ => return None,
+        };
+
+        let (pad1, pad2, quantum, ptr_size) = match data.get(4..8)? {
+            [pad1, pad2, quantum, ptr_size] => (*pad1, *pad2, *quantum, *ptr_size),
+            _ => unreachable!(),
+        };
+        if pad1 != 0 || pad2 != 0 || !matches!(quantum, 1 | 2 | 4) || !matches!(ptr_size, 4 | 8) {
+            return None
+        }
+        let ptr_size = usize::from(ptr_size);
+        let word = |idx: usize| -> Option<usize> {
+            let value = read_uintptr(data, 8 + idx * ptr_size, ptr_size, big_endian)?;
+            usize::try_from(value).ok()
+        };
+
+        let nfunc = word(0)?;
+        let mut slf = Self {
+            version,
+            big_endian,
+            quantum: u64::from(quantum),
+            ptr_size,
+            nfunc,
+            text_start: 0,
+            funcnametab: 0,
+            cutab: 0,
+            filetab: 0,
+            pctab: 0,
+            functab: 0,
+            funcdata: 0,
+        };
+
+        match version {
+            Version::Go118 => {
+                // The text start address may be subject to a dynamic
+                // relocation (for position independent executables),
+                // in which case the file does not contain it.
+                slf.text_start = match word(2)? {
+                    0 => text_addr?,
+                    addr => addr as Addr,
+                };
+                slf.funcnametab = word(3)?;
+                slf.cutab = word(4)?;
+                slf.filetab = word(5)?;
+                slf.pctab = word(6)?;
+                slf.functab = word(7)?;
+                slf.funcdata = slf.functab;
+            }
+            Version::Go116 => {
+                slf.funcnametab = word(2)?;
+                slf.cutab = word(3)?;
+                slf.filetab = word(4)?;
+                slf.pctab = word(5)?;
+                slf.functab = word(6)?;
+                slf.funcdata = slf.functab;
+            }
+            Version::Go12 => {
+                slf.functab = 8 + ptr_size;
+                let functab_size = nfunc
+                    .checked_mul(2)?
+                    .checked_add(1)?
+                    .checked_mul(ptr_size)?;
+                slf.filetab =
+                    read_u32(data, slf.functab.checked_add(functab_size)?, big_endian)? as usize;
+            }
+        }
+
+        let functab_size = nfunc
+            .checked_mul(2)?
+            .checked_add(1)?
+            .checked_mul(slf.functab_field_size())?;
+        let _functab = data.get(slf.functab..slf.functab.checked_add(functab_size)?)?;
+        Some(slf)
+    }
+
+    /// The size of a single `functab` field, in bytes.
+    fn functab_field_size(&self) -> usize {
+        match self.version {
+            Version::Go118 => 4,
+            Version::Go12 | Version::Go116 => self.ptr_size,
+        }
+    }
+
+    /// Read the `functab` field with index `idx`.
+    fn functab_field(&self, data: &[u8], idx: usize) -> Option<u64> {
+        let size = self.functab_field_size();
+        let offset = self.functab.checked_add(idx.checked_mul(size)?)?;
+        read_uintptr(data, offset, size, self.big_endian)
+    }
+
+    /// Retrieve the entry address of the function with index `idx`.
+    ///
+    /// `idx` may equal the number of functions, in which case the end
+    /// address of the last function is reported.
+    fn entry(&self, data: &[u8], idx: usize) -> Option<Addr> {
+        let value = self.functab_field(data, idx.checked_mul(2)?)?;
+        match self.version {
+            Version::Go118 => self.text_start.checked_add(value),
+            Version::Go12 | Version::Go116 => Some(value),
+        }
+    }
+
+    /// Read the `n`-th 32 bit field of the function data starting at
+    /// `func`, following the entry address.
+    fn func_field(&self, data: &[u8], func: usize, n: usize) -> Option<u32> {
+        let entry_size = match self.version {
+            Version::Go118 => 4,
+            Version::Go12 | Version::Go116 => self.ptr_size,
+        };
+        let offset = func.checked_add(entry_size + (n - 1) * 4)?;
+        read_u32(data, offset, self.big_endian)
+    }
+
+    /// Evaluate the PC-value table at `offset` in `pctab` for the
+    /// function starting at `entry`, reporting the value for `addr`.
+    fn pc_value(&self, data: &[u8], offset: u32, entry: Addr, addr: Addr) -> Option<i32> {
+        if offset == 0 {
+            return None
+        }
+
+        let mut data = data.get(self.pctab.checked_add(offset as usize)?..)?;
+        let mut value = -1i32;
+        let mut pc = entry;
+        let mut first = true;
+        loop {
+            let (uvdelta, _) = data.read_u64_leb128()?;
+            if uvdelta == 0 && !first {
+                return None
+            }
+            // Value deltas are zig-zag encoded.
+            let vdelta = if uvdelta & 1 != 0 {
+                !(uvdelta >> 1)
+            } else {
+                uvdelta >> 1
+            };
+            let (pcdelta, _) = data.read_u64_leb128()?;
+            pc = pc.checked_add(pcdelta.checked_mul(self.quantum)?)?;
+            value = value.wrapping_add(vdelta as i32);
+            if addr < pc {
+                return Some(value)
+            }
+            first = pc == entry;
+        }
+    }
+
+    /// Look up the name of the source file with number `file_no` of
+    /// the compilation unit with offset `cu_offset`.
+    fn file_name<'data>(
+        &self,
+        data: &'data [u8],
+        cu_offset: u32,
+        file_no: i32,
+    ) -> Option<&'data CStr> {
+        let file_no = u32::try_from(file_no).ok()?;
+        match self.version {
+            Version::Go116 | Version::Go118 => {
+                let idx = cu_offset.checked_add(file_no)? as usize;
+                let offset = read_u32(
+                    data,
+                    self.cutab.checked_add(idx.checked_mul(4)?)?,
+                    self.big_endian,
+                )?;
+                if offset == u32::MAX {
+                    return None
+                }
+                read_cstr(data, self.filetab.checked_add(offset as usize)?)
+            }
+            Version::Go12 => {
+                let offset = self
+                    .filetab
+                    .checked_add((file_no as usize).checked_mul(4)?)?;
+                let offset = read_u32(data, offset, self.big_endian)?;
+                read_cstr(data, offset as usize)
+            }
+        }
+    }
+
+    /// Retrieve source code information for `addr` inside the function
+    /// with data at `func` that starts at `entry`.
+    fn code_info<'data>(
+        &self,
+        data: &'data [u8],
+        func: usize,
+        entry: Addr,
+        addr: Addr,
+    ) -> Option<CodeInfo<'data>> {
+        let pcfile = self.func_field(data, func, 5)?;
+        let pcln = self.func_field(data, func, 6)?;
+        let cu_offset = match self.version {
+            Version::Go116 | Version::Go118 => self.func_field(data, func, 8)?,
+            Version::Go12 => 0,
+        };
+
+        let line = self.pc_value(data, pcln, entry, addr)?;
+        let file_no = self.pc_value(data, pcfile, entry, addr)?;
+        let path = self.file_name(data, cu_offset, file_no)?;
+        let path = Path::new(OsStr::from_bytes(path.to_bytes()));
+        let (dir, file) = match (path.parent(), path.file_name()) {
+            (Some(dir), Some(file)) if !dir.as_os_str().is_empty() => (Some(dir), file),
+            _ => (None, path.as_os_str()),
+        };
+
+        let info = CodeInfo {
+            dir: dir.map(Cow::Borrowed),
+            file: Cow::Borrowed(file),
+            line: u32::try_from(line).ok().filter(|line| *line != 0),
+            column: None,
+            comp_dir: None,
+            _non_exhaustive: (),
+        };
+        Some(info)
+    }
+
+    /// Find the function containing `addr`.
+    fn find_sym<'data>(
+        &self,
+        data: &'data [u8],
+        addr: Addr,
+        opts: &FindSymOpts,
+    ) -> Option<ResolvedSym<'data>> {
+        // Find the last function starting at or before `addr`.
+        let (mut lo, mut hi) = (0, self.nfunc);
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            if self.entry(data, mid)? <= addr {
+                lo = mid + 1;
+            } else {
+                hi = mid;
+            }
+        }
+        let idx = lo.checked_sub(1)?;
+        let entry = self.entry(data, idx)?;
+        // The table is terminated by the end address of the last
+        // function, so there always is a "next" entry.
+        let end = self.entry(data, idx + 1)?;
+        if addr >= end {
+            return None
+        }
+
+        let func_offset = self.functab_field(data, idx * 2 + 1)?;
+        let func = self
+            .funcdata
+            .checked_add(usize::try_from(func_offset).ok()?)?;
+        let name_offset = self.func_field(data, func, 1)?;
+        let name = read_cstr(data, self.funcnametab.checked_add(name_offset as usize)?)?;
+        let name = name.to_str().ok()?;
+
+        let code_info = if opts.code_info() {
+            self.code_info(data, func, entry, addr)
+        } else {
+            None
+        };
+
+        let sym = ResolvedSym {
+            name,
+            addr: entry,
+            size: usize::try_from(end - entry).ok(),
+            lang: SrcLang::Go,
+            code_info,
+            inlined: Box::new([]),
+            provenance: Provenance::Symbol,
+            is_synthetic: false,
+            raw_elf_sym: None,
+            compilation_unit: None,
+            version: None,
+            section: None,
+            linkage_name: None,
+        };
+        Some(sym)
+    }
+}
+
+
+/// Locate the PC-line table inside the ELF file backing `parser`,
+/// reporting the index of the section containing it as well as its
+/// range inside this section's data.
+fn locate(parser: &ElfParser) -> Result<Option<(usize, Range<usize>)>> {
+    if let Some(idx) = parser.find_section(".gopclntab")? {
+        let len = parser.section_data(idx)?.len();
+        return Ok(Some((idx, 0..len)))
+    }
+
+    // Position independent and externally linked binaries don't have
+    // a dedicated section, but the table is still delimited by the
+    // `runtime.pclntab` and `runtime.epclntab` symbols. Symbol lookup
+    // by name is comparably expensive, though, so only bother for
+    // files that were actually produced by the Go toolchain.
+    if parser.find_section(".go.buildinfo")?.is_none()
+        && parser.find_section(".note.go.buildid")?.is_none()
+    {
+        return Ok(None)
+    }
+
+    let opts = FindAddrOpts::default();
+    let start = parser.find_addr("runtime.pclntab", &opts)?;
+    let end = parser.find_addr("runtime.epclntab", &opts)?;
+    let (start, end) = match (start.first(), end.first()) {
+        (Some(start), Some(end)) if start.addr < end.addr => (start.addr, end.addr),
+        _ => return Ok(None),
+    };
+
+    let shdrs = parser.section_headers()?;
+    let idx = shdrs.iter().position(|shdr| {
+        shdr.sh_type != SHT_NOBITS
+            && shdr.sh_flags & SHF_ALLOC != 0
+            && shdr.sh_addr <= start
+            && end <= shdr.sh_addr.saturating_add(shdr.sh_size)
+    });
+    let idx = match idx {
+        Some(idx) => idx,
+        None => return Ok(None),
+    };
+    let addr = shdrs[idx].sh_addr;
+    let range = (start - addr) as usize..(end - addr) as usize;
+    Ok(Some((idx, range)))
+}
+
+
+/// The Go PC-line table of an ELF file.
+#[derive(Debug)]
+pub(crate) struct GoPclntab {
+    /// The index of the section containing the table.
+    section: usize,
+    /// The range of the table inside the section's data.
+    range: Range<usize>,
+    table: Table,
+}
+
+impl GoPclntab {
+    /// Find and parse the PC-line table of the ELF file backing
+    /// `parser`.
+    ///
+    /// `None` is returned if the file does not contain such a table
+    /// or if it has a layout we don't understand.
+    pub(crate) fn parse(parser: &ElfParser) -> Result<Option<Self>> {
+        let (section, range) = match locate(parser)? {
+            Some(location) => location,
+            None => return Ok(None),
+        };
+        let data = match parser.section_data(section)?.get(range.clone()) {
+            Some(data) => data,
+            None => return Ok(None),
+        };
+        let text_addr = parser
+            .find_section(".text")?
+            .map(|idx| parser.section_headers().map(|shdrs| shdrs[idx].sh_addr))
+            .transpose()?;
+
+        let table = match Table::parse(data, parser.is_big_endian()?, text_addr) {
+            Some(table) => table,
+            None => {
+                debug!("failed to parse Go PC-line table header");
+                return Ok(None)
+            }
+        };
+
+        let slf = Self {
+            section,
+            range,
+            table,
+        };
+        Ok(Some(slf))
+    }
+
+    /// Find the function containing `addr`, if any.
+    pub(crate) fn find_sym<'parser>(
+        &self,
+        parser: &'parser ElfParser,
+        addr: Addr,
+        opts: &FindSymOpts,
+    ) -> Result<Option<ResolvedSym<'parser>>> {
+        let data = parser.section_data(self.section)?;
+        // SANITY: We checked that the range is valid when parsing the
+        //         table.
+        let data = data.get(self.range.clone()).unwrap();
+        Ok(self.table.find_sym(data, addr, opts))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::env;
+
+
+    /// Append a `u32` to `data`.
+    fn push_u32(data: &mut Vec<u8>, value: u32) {
+        let () = data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    /// Synthesize a Go 1.18 style PC-line table containing two
+    /// functions.
+    fn synthesize_table() -> Vec<u8> {
+        let header_size = 8 + 8 * 8;
+        let funcnametab = header_size;
+        let names = b"main.foo\0main.bar\0";
+        let cutab = funcnametab + names.len();
+        let filetab = cutab + 4;
+        let files = b"/src/main.go\0";
+        let pctab = filetab + files.len();
+        let pcs = [
+            // A leading zero byte, so that no table has offset zero.
+            &[0][..],
+            // `main.foo` line numbers: 10 for 0x10 bytes, then 11 for
+            // another 0x10 bytes.
+            &[22, 0x10, 2, 0x10, 0],
+            // File numbers: 0 for 0x20 bytes.
+            &[2, 0x20, 0],
+            // `main.bar` line numbers: 20 for 0x10 bytes.
+            &[42, 0x10, 0],
+        ];
+        let pc_offsets = pcs
+            .iter()
+            .scan(0, |offset, pc| {
+                let current = *offset;
+                *offset += pc.len();
+                Some(current as u32)
+            })
+            .collect::<Vec<_>>();
+        let functab = pctab + pcs.iter().map(|pc| pc.len()).sum::<usize>();
+
+        let mut data = Vec::new();
+        push_u32(&mut data, GO120_MAGIC);
+        let () = data.extend_from_slice(&[0, 0, 1, 8]);
+        for value in [2, 1, 0x1000, funcnametab, cutab, filetab, pctab, functab] {
+            let () = data.extend_from_slice(&(value as u64).to_le_bytes());
+        }
+        let () = data.extend_from_slice(names);
+        push_u32(&mut data, 0);
+        let () = data.extend_from_slice(files);
+        for pc in pcs {
+            let () = data.extend_from_slice(pc);
+        }
+
+        // Function data follow the five `functab` fields.
+        let func_size = 4 + 8 * 4;
+        for (entry, func) in [(0x0, 0), (0x20, 1)] {
+            push_u32(&mut data, entry);
+            push_u32(&mut data, 5 * 4 + func * func_size);
+        }
+        push_u32(&mut data, 0x30);
+
+        for (entry, name, pcln) in [(0x0, 0, pc_offsets[1]), (0x20, 9, pc_offsets[3])] {
+            push_u32(&mut data, entry);
+            // name, args, deferreturn, pcsp, pcfile, pcln, npcdata,
+            // cuOffset
+            for value in [name, 0, 0, 0, pc_offsets[2], pcln, 0, 0] {
+                push_u32(&mut data, value);
+            }
+        }
+        data
+    }
+
+    /// Check that we can look up functions and their source code
+    /// information in a PC-line table.
+    #[test]
+    fn pclntab_lookup() {
+        let data = synthesize_table();
+        let table = Table::parse(&data, false, None).unwrap();
+        assert_eq!(table.version, Version::Go118);
+        assert_eq!(table.nfunc, 2);
+
+        let opts = FindSymOpts::CODE_INFO;
+        assert!(table.find_sym(&data, 0xfff, &opts).is_none());
+
+        let sym = table.find_sym(&data, 0x1000, &opts).unwrap();
+        assert_eq!(sym.name, "main.foo");
+        assert_eq!(sym.addr, 0x1000);
+        assert_eq!(sym.size, Some(0x20));
+        assert_eq!(sym.lang, SrcLang::Go);
+        let info = sym.code_info.unwrap();
+        assert_eq!(info.dir, Some(Cow::Borrowed(Path::new("/src"))));
+        assert_eq!(info.file, OsStr::new("main.go"));
+        assert_eq!(info.line, Some(10));
+
+        let sym = table.find_sym(&data, 0x1018, &opts).unwrap();
+        assert_eq!(sym.name, "main.foo");
+        assert_eq!(sym.code_info.unwrap().line, Some(11));
+
+        let sym = table.find_sym(&data, 0x102f, &opts).unwrap();
+        assert_eq!(sym.name, "main.bar");
+        assert_eq!(sym.addr, 0x1020);
+        assert_eq!(sym.size, Some(0x10));
+        assert_eq!(sym.code_info.unwrap().line, Some(20));
+
+        let sym = table.find_sym(&data, 0x1020, &FindSymOpts::BASIC).unwrap();
+        assert_eq!(sym.code_info, None);
+
+        assert!(table.find_sym(&data, 0x1030, &opts).is_none());
+    }
+
+    /// Make sure that we reject data that do not contain a PC-line
+    /// table.
+    #[test]
+    fn invalid_pclntab() {
+        let mut data = synthesize_table();
+        // A truncated `functab` is rejected.
+        assert!(Table::parse(&data[..data.len() - 80], false, None).is_none());
+        // As is a table in unexpected byte order.
+        assert!(Table::parse(&data, true, None).is_none());
+        // Or one with an unknown magic number.
+        data[0] = 0xf2;
+        assert!(Table::parse(&data, false, None).is_none());
+    }
+
+    /// Check that non-Go binaries are reported as not having a
+    /// PC-line table.
+    #[test]
+    fn no_pclntab() {
+        let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join("test-stable-addrs.bin");
+        let parser = ElfParser::open(&path).unwrap();
+        assert!(GoPclntab::parse(&parser).unwrap().is_none());
+    }
+}
@@ -0,0 +1,177 @@
+//! Support for ELF data read on demand from an arbitrary reader.
+//!
+//! The ELF parser works on top of a contiguous range of memory
+//! representing the file's data. For readers we reserve an anonymous
+//! memory mapping spanning the entire file, but only populate the
+//! parts of it that are actually accessed. Unpopulated parts are
+//! never referenced, which allows us to fill them in while references
+//! to others are being held.
+
+use std::cell::RefCell;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::slice;
+
+use crate::mmap::MmapMut;
+use crate::ErrorExt as _;
+use crate::IntoError as _;
+use crate::Result;
+
+
+/// The granularity at which data are read, in bytes.
+const CHUNK_SIZE: usize = 4096;
+
+
+/// A helper trait combining [`Read`] and [`Seek`], so that both can
+/// be used through a single trait object.
+pub(crate) trait ReadSeek: Read + Seek {}
+
+impl<T> ReadSeek for T where T: Read + Seek {}
+
+
+/// ELF data populated from a reader on demand.
+pub(crate) struct LazyData {
+    /// The reader providing the data.
+    reader: RefCell<Box<dyn ReadSeek>>,
+    /// The memory backing the data read so far.
+    mmap: MmapMut,
+    /// Whether a given chunk of `mmap` has been populated already.
+    loaded: RefCell<Vec<bool>>,
+}
+
+impl LazyData {
+    /// Create a `LazyData` object for the provided reader.
+    pub fn new(mut reader: Box<dyn ReadSeek>) -> Result<Self> {
+        let len = reader
+            .seek(SeekFrom::End(0))
+            .context("failed to determine size of ELF data")?;
+        let len = usize::try_from(len)
+            .ok()
+            .ok_or_invalid_data(|| "ELF data are too large")?;
+
+        let slf = Self {
+            reader: RefCell::new(reader),
+            mmap: MmapMut::anon(len)?,
+            loaded: RefCell::new(vec![false; (len + CHUNK_SIZE - 1) / CHUNK_SIZE]),
+        };
+        Ok(slf)
+    }
+
+    /// Retrieve the total length of the data, in bytes.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.mmap.len()
+    }
+
+    /// Read the chunks in `chunks` into memory.
+    fn load(&self, chunks: &[usize]) -> Result<()> {
+        let (first, last) = match (chunks.first(), chunks.last()) {
+            (Some(first), Some(last)) => (*first, *last),
+            _ => return Ok(()),
+        };
+        let start = first * CHUNK_SIZE;
+        let end = ((last + 1) * CHUNK_SIZE).min(self.len());
+
+        // SAFETY: The range is within the bounds of the mapping and
+        //         none of the chunks it covers has been populated yet,
+        //         meaning that no references to it exist.
+        let buf =
+            unsafe { slice::from_raw_parts_mut(self.mmap.as_mut_ptr().add(start), end - start) };
+        let mut reader = self.reader.borrow_mut();
+        let _pos = reader
+            .seek(SeekFrom::Start(start as u64))
+            .with_context(|| format!("failed to seek to ELF data at offset {start:#x}"))?;
+        let () = reader
+            .read_exact(buf)
+            .with_context(|| format!("failed to read ELF data at offset {start:#x}"))?;
+        Ok(())
+    }
+
+    /// Retrieve `len` bytes of data starting at `offset`, reading
+    /// them if they have not been accessed before.
+    ///
+    /// `None` is returned if the requested range is out of bounds.
+    pub fn get(&self, offset: u64, len: usize) -> Result<Option<&[u8]>> {
+        let start = match usize::try_from(offset) {
+            Ok(start) => start,
+            Err(_) => return Ok(None),
+        };
+        let end = match start.checked_add(len) {
+            Some(end) if end <= self.len() => end,
+            _ => return Ok(None),
+        };
+
+        if start != end {
+            let mut loaded = self.loaded.borrow_mut();
+            // Contiguous chunks are read in one go.
+            let mut missing = Vec::new();
+            for chunk in start / CHUNK_SIZE..=(end - 1) / CHUNK_SIZE {
+                if loaded[chunk] {
+                    let () = self.load(&missing)?;
+                    let () = missing.clear();
+                } else {
+                    let () = missing.push(chunk);
+                }
+            }
+            let () = self.load(&missing)?;
+
+            for chunk in start / CHUNK_SIZE..=(end - 1) / CHUNK_SIZE {
+                loaded[chunk] = true;
+            }
+        }
+
+        // SAFETY: The range is within the bounds of the mapping and
+        //         has been populated. It will never be written to
+        //         again.
+        let data = unsafe { slice::from_raw_parts(self.mmap.as_mut_ptr().add(start), len) };
+        Ok(Some(data))
+    }
+}
+
+impl Debug for LazyData {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.debug_struct("LazyData")
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io::Cursor;
+
+    use test_log::test;
+
+
+    /// Check that we can retrieve data from a `LazyData` object.
+    #[test]
+    fn lazy_data_reading() {
+        let data = (0..3 * CHUNK_SIZE + 17)
+            .map(|i| i as u8)
+            .collect::<Vec<_>>();
+        let lazy = LazyData::new(Box::new(Cursor::new(data.clone()))).unwrap();
+        assert_eq!(lazy.len(), data.len());
+
+        let slice = lazy.get(CHUNK_SIZE as u64 + 3, 2).unwrap().unwrap();
+        assert_eq!(slice, &data[CHUNK_SIZE + 3..CHUNK_SIZE + 5]);
+
+        // Reading across already loaded and not yet loaded chunks should
+        // work and not affect previously handed out data.
+        let all = lazy.get(0, data.len()).unwrap().unwrap();
+        assert_eq!(all, data.as_slice());
+        assert_eq!(slice, &data[CHUNK_SIZE + 3..CHUNK_SIZE + 5]);
+
+        let empty = lazy.get(data.len() as u64, 0).unwrap().unwrap();
+        assert_eq!(empty, &[] as &[u8]);
+
+        assert_eq!(lazy.get(data.len() as u64, 1).unwrap(), None);
+        assert_eq!(lazy.get(u64::MAX, 1).unwrap(), None);
+    }
+}
@@ -1,10 +1,22 @@
+//! Functionality for working with ELF files.
+
+mod class;
+mod endian;
+mod gnu_hash;
+#[cfg(feature = "go")]
+mod go;
+mod lazy;
 mod parser;
+mod plt;
 #[cfg_attr(not(feature = "dwarf"), allow(unused_variables))]
 mod resolver;
 #[allow(dead_code, non_camel_case_types)]
 pub(crate) mod types;
 
-pub(crate) use parser::ElfParser;
+pub use parser::ElfParser;
+pub use parser::SymbolTableIndexing;
+pub use plt::PltEntry;
 pub(crate) use resolver::ElfResolverData;
+pub use types::Elf64_Phdr;
 
 pub use resolver::ElfResolver;
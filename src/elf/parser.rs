@@ -1,23 +1,38 @@
 use std::borrow::Cow;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::ffi::OsStr;
 use std::fmt::Debug;
 use std::fmt::Formatter;
 use std::fmt::Result as FmtResult;
 use std::fs::File;
+use std::io::Read;
+use std::io::Seek;
 use std::mem;
 use std::ops::Deref as _;
+use std::os::unix::ffi::OsStrExt as _;
+use std::os::unix::io::BorrowedFd;
 use std::path::Path;
 use std::path::PathBuf;
+use std::slice;
 
 use crate::insert_map::InsertMap;
+use crate::inspect::DebugCompression;
+use crate::inspect::DebugInfo;
 use crate::inspect::FindAddrOpts;
+use crate::inspect::SymBinding;
 use crate::inspect::SymInfo;
+use crate::inspect::SymVersion;
 use crate::mmap::Mmap;
 use crate::once::OnceCell;
 use crate::symbolize::FindSymOpts;
+use crate::symbolize::Provenance;
+use crate::symbolize::RawElfSym;
 use crate::symbolize::Reason;
 use crate::symbolize::ResolvedSym;
 use crate::symbolize::SrcLang;
 use crate::util::find_match_or_lower_bound_by_key;
+use crate::util::FileId;
 use crate::util::ReadRaw as _;
 use crate::Addr;
 use crate::Error;
@@ -26,18 +41,67 @@ use crate::IntoError as _;
 use crate::Result;
 use crate::SymType;
 
+use super::class;
+use super::class::Widened;
+use super::endian;
+use super::gnu_hash::gnu_hash;
+use super::gnu_hash::GnuHashTable;
+use super::lazy::LazyData;
+use super::lazy::ReadSeek;
+use super::plt::PltEntry;
+use super::plt::PltStubs;
 use super::types::Elf64_Chdr;
+use super::types::Elf64_Dyn;
 use super::types::Elf64_Ehdr;
 use super::types::Elf64_Phdr;
 use super::types::Elf64_Shdr;
 use super::types::Elf64_Sym;
+use super::types::Elf64_Verdaux;
+use super::types::Elf64_Verdef;
+use super::types::Elf64_Vernaux;
+use super::types::Elf64_Verneed;
+use super::types::DT_NEEDED;
+use super::types::DT_NULL;
+use super::types::DT_RPATH;
+use super::types::DT_RUNPATH;
+use super::types::DT_SONAME;
+use super::types::DT_VERDEF;
+use super::types::DT_VERDEFNUM;
+use super::types::DT_VERNEED;
+use super::types::DT_VERNEEDNUM;
+use super::types::DT_VERSYM;
+use super::types::EI_CLASS;
+use super::types::EI_DATA;
+use super::types::EI_NIDENT;
+use super::types::ELFCLASS32;
 use super::types::ELFCOMPRESS_ZLIB;
 use super::types::ELFCOMPRESS_ZSTD;
+use super::types::ELFDATA2MSB;
+use super::types::EM_AARCH64;
+use super::types::EM_ARM;
+use super::types::PF_X;
 use super::types::PN_XNUM;
 use super::types::PT_LOAD;
+use super::types::PT_TLS;
+use super::types::SHF_ALLOC;
 use super::types::SHF_COMPRESSED;
+use super::types::SHF_TLS;
 use super::types::SHN_UNDEF;
 use super::types::SHN_XINDEX;
+use super::types::SHT_DYNSYM;
+use super::types::SHT_GNU_VERDEF;
+use super::types::SHT_GNU_VERNEED;
+use super::types::SHT_GNU_VERSYM;
+use super::types::SHT_NOBITS;
+use super::types::STB_GLOBAL;
+use super::types::STB_GNU_UNIQUE;
+use super::types::STB_LOCAL;
+use super::types::STB_WEAK;
+use super::types::VERSYM_HIDDEN;
+use super::types::VERSYM_VERSION;
+use super::types::VER_FLG_BASE;
+use super::types::VER_NDX_GLOBAL;
+use super::types::VER_NDX_LOCAL;
 
 
 fn symbol_name<'mmap>(strtab: &'mmap [u8], sym: &Elf64_Sym) -> Result<&'mmap str> {
@@ -53,82 +117,328 @@ fn symbol_name<'mmap>(strtab: &'mmap [u8], sym: &Elf64_Sym) -> Result<&'mmap str
     Ok(name)
 }
 
+/// Split a symbol name into its base name and the symbol version
+/// encoded in it, if any.
+///
+/// Versions are encoded using the notation of the GNU toolchain, as
+/// found in `.symtab` sections: `name@@version` denotes the default
+/// version of a symbol, whereas `name@version` denotes a hidden one.
+fn split_version(name: &str) -> (&str, Option<SymVersion<'_>>) {
+    if let Some((base, version)) = name.split_once('@') {
+        let (version, hidden) = match version.strip_prefix('@') {
+            Some(version) => (version, false),
+            None => (version, true),
+        };
+
+        if !base.is_empty() && !version.is_empty() {
+            let version = SymVersion {
+                name: Cow::Borrowed(version),
+                hidden,
+            };
+            return (base, Some(version))
+        }
+    }
+    (name, None)
+}
+
+/// Check whether the given symbol is an ARM or AArch64 mapping symbol
+/// (`$a`, `$t`, `$x`, or `$d`, optionally followed by a `.`-separated
+/// suffix).
+///
+/// Mapping symbols merely mark the kind of data at an address and do
+/// not represent anything users would be interested in.
+fn is_mapping_sym(strtab: &[u8], sym: &Elf64_Sym) -> bool {
+    match strtab.get(sym.st_name as usize..) {
+        Some([b'$', b'a' | b't' | b'x' | b'd', rest @ ..]) => {
+            matches!(rest.first(), None | Some(b'\0') | Some(b'.'))
+        }
+        _ => false,
+    }
+}
+
+/// Retrieve the address of a symbol.
+///
+/// On ARM, bit zero of the value of a function symbol is set for Thumb
+/// code. It is not part of the address, though, and is cleared if
+/// `thumb` is `true`.
+#[inline]
+fn sym_addr(sym: &Elf64_Sym, thumb: bool) -> Addr {
+    if thumb && sym.matches(SymType::Function) {
+        (sym.st_value & !1) as Addr
+    } else {
+        sym.st_value as Addr
+    }
+}
+
+/// Rank a symbol's binding, with lower values denoting stronger
+/// bindings.
+#[inline]
+fn binding_rank(sym: &Elf64_Sym) -> u8 {
+    match sym.binding() {
+        STB_GLOBAL | STB_GNU_UNIQUE => 0,
+        STB_WEAK => 1,
+        STB_LOCAL => 2,
+        _ => 3,
+    }
+}
+
+/// Compute the running maximum of the end addresses of the provided
+/// symbols, in order.
+///
+/// The result allows for quickly determining the range of symbols
+/// that may cover an address: no symbol at or before index `i` ends
+/// past `max_ends[i]`.
+fn max_sym_ends<'syms>(
+    syms: &'syms [&Elf64_Sym],
+    thumb: bool,
+) -> impl Iterator<Item = Addr> + 'syms {
+    syms.iter().scan(0, move |max_end, sym| {
+        let end = sym_addr(sym, thumb).saturating_add(sym.st_size);
+        *max_end = end.max(*max_end);
+        Some(*max_end)
+    })
+}
+
+/// Check whether the section described by `shdr` occupies its address
+/// range in memory during process execution.
+fn occupies_memory(shdr: &Elf64_Shdr) -> bool {
+    // Thread-local `SHT_NOBITS` sections (i.e., `.tbss`) merely serve
+    // as a template for per-thread data. They do not occupy the
+    // address range they nominally cover, which commonly overlaps with
+    // that of subsequent sections.
+    let tbss = shdr.sh_type == SHT_NOBITS && shdr.sh_flags & SHF_TLS != 0;
+    shdr.sh_flags & SHF_ALLOC != 0 && shdr.sh_size != 0 && !tbss
+}
+
+/// Find the symbol of type `type_` covering `addr`.
+///
+/// `symtab` is expected to be sorted by address and `max_ends` to
+/// contain the running maximum of symbol end addresses (see
+/// [`max_sym_ends`]).
+///
+/// Symbol tables may contain overlapping symbols. In such a case the
+/// symbol with the smallest range containing `addr` is reported. Of
+/// multiple such symbols, the one with the strongest binding is
+/// picked and, if that is not decisive either, the first one in
+/// address order.
 fn find_sym<'mmap>(
     symtab: &[&Elf64_Sym],
+    max_ends: &[Addr],
     strtab: &'mmap [u8],
+    thumb: bool,
     addr: Addr,
     type_: SymType,
 ) -> Result<Option<ResolvedSym<'mmap>>> {
-    match find_match_or_lower_bound_by_key(symtab, addr, |sym| sym.st_value as Addr) {
+    debug_assert_eq!(symtab.len(), max_ends.len());
+
+    let qualifies = |sym: &Elf64_Sym| sym.matches(type_) && sym.st_shndx != SHN_UNDEF;
+    let end = symtab.partition_point(|sym| sym_addr(sym, thumb) <= addr);
+
+    let mut best = None::<&Elf64_Sym>;
+    for idx in (0..end).rev() {
+        if max_ends[idx] <= addr {
+            // None of the remaining symbols extends far enough to cover
+            // `addr`.
+            break
+        }
+
+        let sym = symtab[idx];
+        let sym_start = sym_addr(sym, thumb);
+        if !qualifies(sym) || sym.st_size == 0 || addr >= sym_start.saturating_add(sym.st_size) {
+            continue
+        }
+
+        // We are iterating in reverse order, so on a tie we replace the
+        // current best match, ending up with the first one.
+        let key = |sym: &Elf64_Sym| (sym.st_size, binding_rank(sym));
+        if best.map_or(true, |best| key(sym) <= key(best)) {
+            best = Some(sym);
+        }
+    }
+
+    // In ELF, a symbol size of 0 indicates "no size or an unknown size"
+    // (see elf(5)). We take our chances and report such symbols on a
+    // best-effort basis, if they are the closest ones preceding `addr`.
+    if best.is_none() {
+        if let Some(last) = end.checked_sub(1) {
+            let start = sym_addr(symtab[last], thumb);
+            let first = symtab[..end].partition_point(|sym| sym_addr(sym, thumb) < start);
+            best = symtab[first..end]
+                .iter()
+                .filter(|sym| qualifies(sym) && sym.st_size == 0)
+                .min_by_key(|sym| binding_rank(sym))
+                .copied();
+        }
+    }
+
+    match best {
+        Some(sym) => {
+            let sym_start = sym_addr(sym, thumb);
+            debug_assert!(
+                sym_start <= addr && (sym.st_size == 0 || addr - sym_start < sym.st_size),
+                "symbol {sym:?} does not contain address {addr:#x}"
+            );
+            let sym = resolved_sym(strtab, sym, sym_start)?;
+            Ok(Some(sym))
+        }
         None => Ok(None),
-        Some(idx) => {
-            for sym in symtab[idx..].iter() {
-                if sym.st_value as Addr > addr {
-                    // Once we are seeing start addresses past the provided
-                    // address, we can no longer be dealing with a match and
-                    // stop the search.
-                    break
-                }
+    }
+}
 
-                // In ELF, a symbol size of 0 indicates "no size or an unknown
-                // size" (see elf(5)). We take our changes and report these on a
-                // best-effort basis.
-                if sym.matches(type_)
-                    && sym.st_shndx != SHN_UNDEF
-                    && (sym.st_size == 0 || addr < sym.st_value + sym.st_size)
-                {
-                    let sym = ResolvedSym {
-                        name: symbol_name(strtab, sym)?,
-                        addr: sym.st_value as Addr,
-                        size: if sym.st_size == 0 {
-                            None
-                        } else {
-                            Some(usize::try_from(sym.st_size).unwrap_or(usize::MAX))
-                        },
-                        // ELF does not carry any source code language
-                        // information.
-                        lang: SrcLang::Unknown,
-                        // ELF doesn't carry source code location
-                        // information.
-                        code_info: None,
-                        inlined: Box::new([]),
-                    };
-                    return Ok(Some(sym))
-                }
-            }
-            Ok(None)
+/// Find the symbol of type `type_` covering `addr` by scanning `syms`
+/// linearly.
+///
+/// Contrary to [`find_sym`], `syms` do not have to be sorted. The
+/// symbol reported is the same one `find_sym` would report for the
+/// sorted equivalent.
+fn scan_sym<'mmap, 'sym, I>(
+    syms: I,
+    strtab: &'mmap [u8],
+    thumb: bool,
+    addr: Addr,
+    type_: SymType,
+) -> Result<Option<ResolvedSym<'mmap>>>
+where
+    I: Iterator<Item = &'sym Elf64_Sym> + Clone,
+{
+    let qualifies = |sym: &Elf64_Sym| sym.matches(type_) && sym.st_shndx != SHN_UNDEF;
+
+    let mut best = None::<(&Elf64_Sym, Addr)>;
+    // The start address of the closest symbol preceding `addr`.
+    let mut last_start = None::<Addr>;
+    for sym in syms.clone() {
+        let sym_start = sym_addr(sym, thumb);
+        if sym_start > addr {
+            continue
+        }
+        last_start = last_start.max(Some(sym_start));
+
+        if !qualifies(sym) || sym.st_size == 0 || addr >= sym_start.saturating_add(sym.st_size) {
+            continue
         }
+
+        // Symbols are visited in storage order, so on a tie we keep
+        // the current best match, which is the first one.
+        let key = |sym: &Elf64_Sym, start| (sym.st_size, binding_rank(sym), start);
+        if best.map_or(true, |(best, best_start)| {
+            key(sym, sym_start) < key(best, best_start)
+        }) {
+            best = Some((sym, sym_start));
+        }
+    }
+
+    // See `find_sym` for the handling of zero-sized symbols.
+    if best.is_none() {
+        if let Some(last_start) = last_start {
+            best = syms
+                .filter(|sym| sym_addr(sym, thumb) == last_start)
+                .filter(|sym| qualifies(sym) && sym.st_size == 0)
+                .min_by_key(|sym| binding_rank(sym))
+                .map(|sym| (sym, last_start));
+        }
+    }
+
+    match best {
+        Some((sym, sym_start)) => {
+            let sym = resolved_sym(strtab, sym, sym_start)?;
+            Ok(Some(sym))
+        }
+        None => Ok(None),
     }
 }
 
+/// Find the defined symbol starting closest before or at `addr`,
+/// irrespective of its size.
+fn find_preceding_sym<'mmap>(
+    symtab: &[&Elf64_Sym],
+    strtab: &'mmap [u8],
+    thumb: bool,
+    addr: Addr,
+) -> Result<Option<ResolvedSym<'mmap>>> {
+    let end = symtab.partition_point(|sym| sym_addr(sym, thumb) <= addr);
+    symtab[..end]
+        .iter()
+        .rev()
+        .find(|sym| sym.matches(SymType::Undefined) && sym.st_shndx != SHN_UNDEF)
+        .map(|sym| resolved_sym(strtab, sym, sym_addr(sym, thumb)))
+        .transpose()
+}
 
-#[cfg(feature = "zlib")]
-fn decompress_zlib(data: &[u8]) -> Result<Vec<u8>> {
-    use miniz_oxide::inflate::decompress_to_vec_zlib;
+/// Create a [`ResolvedSym`] for the symbol `sym`, starting at `addr`.
+fn resolved_sym<'mmap>(
+    strtab: &'mmap [u8],
+    sym: &Elf64_Sym,
+    addr: Addr,
+) -> Result<ResolvedSym<'mmap>> {
+    let (name, version) = split_version(symbol_name(strtab, sym)?);
+    let sym = ResolvedSym {
+        name,
+        addr,
+        size: if sym.st_size == 0 {
+            None
+        } else {
+            Some(usize::try_from(sym.st_size).unwrap_or(usize::MAX))
+        },
+        // ELF does not carry any source code language information.
+        lang: SrcLang::Unknown,
+        // ELF doesn't carry source code location information.
+        code_info: None,
+        inlined: Box::new([]),
+        provenance: Provenance::Symbol,
+        is_synthetic: true,
+        raw_elf_sym: Some(RawElfSym {
+            st_value: sym.st_value,
+            st_size: sym.st_size,
+            _non_exhaustive: (),
+        }),
+        compilation_unit: None,
+        version,
+        section: None,
+        linkage_name: None,
+    };
+    Ok(sym)
+}
 
-    match decompress_to_vec_zlib(data) {
-        Ok(data) => Ok(data),
+
+#[cfg(feature = "zlib")]
+fn decompress_zlib(data: &[u8], out: &mut [u8]) -> Result<()> {
+    use miniz_oxide::inflate::decompress_slice_iter_to_slice;
+
+    match decompress_slice_iter_to_slice(out, [data].into_iter(), true, false) {
+        Ok(len) if len == out.len() => Ok(()),
+        Ok(len) => Err(Error::with_invalid_data(format!(
+            "zlib decompression produced {len} bytes instead of expected {}",
+            out.len()
+        ))),
         Err(err) => Err(Error::with_invalid_data(format!(
-            "zlib decompression failed: {err}"
+            "zlib decompression failed: {err:?}"
         ))),
     }
 }
 
 #[cfg(not(feature = "zlib"))]
-fn decompress_zlib(_data: &[u8]) -> Result<Vec<u8>> {
+fn decompress_zlib(_data: &[u8], _out: &mut [u8]) -> Result<()> {
     Err(Error::with_unsupported(
         "ELF section is zlib compressed but zlib compression support is not enabled",
     ))
 }
 
 #[cfg(feature = "zstd")]
-fn decompress_zstd(data: &[u8]) -> Result<Vec<u8>> {
-    use zstd::stream::decode_all;
-    decode_all(data).context("zstd decompression failed")
+fn decompress_zstd(data: &[u8], out: &mut [u8]) -> Result<()> {
+    use zstd::bulk::decompress_to_buffer;
+
+    let len = decompress_to_buffer(data, out).context("zstd decompression failed")?;
+    if len != out.len() {
+        return Err(Error::with_invalid_data(format!(
+            "zstd decompression produced {len} bytes instead of expected {}",
+            out.len()
+        )))
+    }
+    Ok(())
 }
 
 #[cfg(not(feature = "zstd"))]
-fn decompress_zstd(_data: &[u8]) -> Result<Vec<u8>> {
+fn decompress_zstd(_data: &[u8], _out: &mut [u8]) -> Result<()> {
     Err(Error::with_unsupported(
         "ELF section is zstd compressed but zstd compression support is not enabled",
     ))
@@ -150,23 +460,82 @@ struct EhdrExt<'mmap> {
 
 #[derive(Debug)]
 struct SymbolTableCache<'mmap> {
-    /// The cached symbols (in address order).
+    /// The cached symbols (in address order), followed by all
+    /// thread-local symbols (in TLS offset order).
     syms: Box<[&'mmap Elf64_Sym]>,
+    /// The index of the first thread-local symbol in `syms`.
+    tls_idx: usize,
+    /// The running maximum of symbol end addresses (or TLS offsets),
+    /// separately for both parts of `syms`.
+    max_ends: Box<[Addr]>,
+    /// Whether function symbol values may have the Thumb bit set.
+    thumb: bool,
     /// The string table.
     strs: &'mmap [u8],
     /// The cached name to symbol index table (in dictionary order).
     str2sym: OnceCell<Box<[(&'mmap str, usize)]>>,
+    /// The cached address range spanned by all defined symbols.
+    addr_bounds: OnceCell<Option<(Addr, Addr)>>,
 }
 
 impl<'mmap> SymbolTableCache<'mmap> {
-    fn new(syms: Vec<&'mmap Elf64_Sym>, strs: &'mmap [u8]) -> Self {
+    fn new(syms: Vec<&'mmap Elf64_Sym>, tls_idx: usize, thumb: bool, strs: &'mmap [u8]) -> Self {
+        let max_ends = max_sym_ends(&syms[..tls_idx], thumb)
+            .chain(max_sym_ends(&syms[tls_idx..], false))
+            .collect();
+
         Self {
             syms: syms.into_boxed_slice(),
+            tls_idx,
+            max_ends,
+            thumb,
             strs,
             str2sym: OnceCell::new(),
+            addr_bounds: OnceCell::new(),
+        }
+    }
+
+    /// Retrieve all symbols residing in the virtual address space, in
+    /// address order.
+    fn addr_syms(&self) -> &[&'mmap Elf64_Sym] {
+        &self.syms[..self.tls_idx]
+    }
+
+    /// Retrieve all thread-local symbols, in TLS offset order.
+    fn tls_syms(&self) -> &[&'mmap Elf64_Sym] {
+        &self.syms[self.tls_idx..]
+    }
+
+    /// Find the symbol of type `type_` covering `addr`, which is
+    /// interpreted as an offset into the TLS block for thread-local
+    /// symbols.
+    fn find_sym(&self, addr: Addr, type_: SymType) -> Result<Option<ResolvedSym<'mmap>>> {
+        if type_ == SymType::Tls {
+            let syms = self.tls_syms();
+            let max_ends = &self.max_ends[self.tls_idx..];
+            find_sym(syms, max_ends, self.strs, false, addr, type_)
+        } else {
+            let syms = self.addr_syms();
+            let max_ends = &self.max_ends[..self.tls_idx];
+            find_sym(syms, max_ends, self.strs, self.thumb, addr, type_)
         }
     }
 
+    /// Retrieve the address range `[start, end)` spanned by all
+    /// defined symbols residing in the virtual address space.
+    fn addr_bounds(&self) -> Option<(Addr, Addr)> {
+        *self.addr_bounds.get_or_init(|| {
+            self.addr_syms()
+                .iter()
+                .filter(|sym| sym.st_shndx != SHN_UNDEF)
+                .map(|sym| {
+                    let start = sym_addr(sym, self.thumb);
+                    (start, start.saturating_add(sym.st_size))
+                })
+                .reduce(|(start1, end1), (start2, end2)| (start1.min(start2), end1.max(end2)))
+        })
+    }
+
     fn create_str2sym<F>(&self, mut filter: F) -> Result<Vec<(&'mmap str, usize)>>
     where
         F: FnMut(&Elf64_Sym) -> bool,
@@ -174,18 +543,12 @@ impl<'mmap> SymbolTableCache<'mmap> {
         let mut str2sym = self
             .syms
             .iter()
-            .filter(|sym| filter(sym))
             .enumerate()
+            .filter(|(_i, sym)| filter(sym))
             .map(|(i, sym)| {
-                let name = self
-                    .strs
-                    .get(sym.st_name as usize..)
-                    .ok_or_invalid_input(|| "string table index out of bounds")?
-                    .read_cstr()
-                    .ok_or_invalid_input(|| "no valid string found in string table")?
-                    .to_str()
-                    .map_err(Error::with_invalid_data)
-                    .context("invalid symbol name")?;
+                // Symbols are indexed by their base name, irrespective
+                // of any version encoded in it.
+                let (name, _version) = split_version(symbol_name(self.strs, sym)?);
                 Ok((name, i))
             })
             .collect::<Result<Vec<_>>>()?;
@@ -212,9 +575,116 @@ impl<'mmap> SymbolTableCache<'mmap> {
 }
 
 
+/// Symbol versioning information for the dynamic symbol table.
+#[derive(Debug, Default)]
+struct VersionCache<'mmap> {
+    /// The `.dynsym` symbols, in table order.
+    dynsyms: &'mmap [Elf64_Sym],
+    /// The `.gnu.version` entries, one for each `.dynsym` symbol.
+    versyms: &'mmap [u16],
+    /// The names of all defined and required versions, by version
+    /// index.
+    names: HashMap<u16, &'mmap str>,
+}
+
+impl<'mmap> VersionCache<'mmap> {
+    /// Look up the version of the provided symbol.
+    ///
+    /// `None` is returned for unversioned symbols as well as for ones
+    /// not contained in the `.dynsym` section.
+    fn version(&self, sym: &Elf64_Sym) -> Option<SymVersion<'mmap>> {
+        let range = self.dynsyms.as_ptr_range();
+        let ptr = sym as *const Elf64_Sym;
+        if !range.contains(&ptr) {
+            return None
+        }
+
+        let idx = (ptr as usize - range.start as usize) / mem::size_of::<Elf64_Sym>();
+        let versym = *self.versyms.get(idx)?;
+        let ndx = versym & VERSYM_VERSION;
+        if ndx == VER_NDX_LOCAL || ndx == VER_NDX_GLOBAL {
+            return None
+        }
+
+        let name = self.names.get(&ndx)?;
+        let version = SymVersion {
+            name: Cow::Borrowed(name),
+            hidden: versym & VERSYM_HIDDEN != 0,
+        };
+        Some(version)
+    }
+}
+
+
+/// The raw ELF data that we are about to parse.
+enum ElfData<'mmap> {
+    /// ELF data that are readily available in memory.
+    Mapped(&'mmap [u8]),
+    /// The data of a 32 bit ELF file along with its widened
+    /// structures.
+    Widened {
+        /// The original file data.
+        file: &'mmap [u8],
+        /// The widened ELF header, logically replacing the original
+        /// one.
+        ehdr: &'mmap [u8],
+        /// All other widened structures, logically located at `base`.
+        structs: &'mmap [u8],
+        /// The logical offset of `structs`.
+        base: u64,
+    },
+    /// ELF data that are read on demand.
+    Lazy(&'mmap LazyData),
+}
+
+impl<'mmap> ElfData<'mmap> {
+    /// Retrieve `len` bytes of data starting at `offset`.
+    ///
+    /// `None` is returned if the requested range is out of bounds.
+    fn get(&self, offset: u64, len: usize) -> Result<Option<&'mmap [u8]>> {
+        fn get_impl(data: &[u8], offset: u64, len: usize) -> Option<&[u8]> {
+            usize::try_from(offset)
+                .ok()
+                .and_then(|offset| data.get(offset..))
+                .and_then(|mut data| data.read_slice(len))
+        }
+
+        match self {
+            Self::Mapped(data) => Ok(get_impl(data, offset, len)),
+            Self::Widened {
+                file,
+                ehdr,
+                structs,
+                base,
+            } => {
+                let data = if offset < ehdr.len() as u64 {
+                    get_impl(ehdr, offset, len)
+                } else if offset >= *base {
+                    get_impl(structs, offset - base, len)
+                } else {
+                    get_impl(file, offset, len)
+                };
+                Ok(data)
+            }
+            Self::Lazy(lazy) => lazy.get(offset, len),
+        }
+    }
+}
+
+
+/// Address lookup information for the sections occupying memory.
+struct SectionAddrs {
+    /// The indices of the sections, sorted by address.
+    idxs: Box<[usize]>,
+    /// The running maximum of the sections' end addresses, in the
+    /// order of `idxs`.
+    max_ends: Box<[Addr]>,
+}
+
+
 struct Cache<'mmap> {
-    /// A slice of the raw ELF data that we are about to parse.
-    elf_data: &'mmap [u8],
+    /// The raw ELF data that we are about to parse.
+    elf_data: ElfData<'mmap>,
     /// The cached ELF header.
     ehdr: OnceCell<EhdrExt<'mmap>>,
     /// The cached ELF section headers.
@@ -226,11 +696,18 @@ struct Cache<'mmap> {
     symtab: OnceCell<SymbolTableCache<'mmap>>,
     /// The cached dynamic symbol table.
     dynsym: OnceCell<SymbolTableCache<'mmap>>,
+    /// The cached entries of the `.dynamic` section, along with the
+    /// string table they reference.
+    dynamic: OnceCell<(&'mmap [Elf64_Dyn], &'mmap [u8])>,
+    /// The cached symbol versioning information.
+    versions: OnceCell<VersionCache<'mmap>>,
+    /// The cached address lookup information for sections.
+    section_addrs: OnceCell<SectionAddrs>,
 }
 
 impl<'mmap> Cache<'mmap> {
     /// Create a new `Cache` using the provided raw ELF object data.
-    fn new(elf_data: &'mmap [u8]) -> Self {
+    fn new(elf_data: ElfData<'mmap>) -> Self {
         Self {
             elf_data,
             ehdr: OnceCell::new(),
@@ -239,6 +716,9 @@ impl<'mmap> Cache<'mmap> {
             phdrs: OnceCell::new(),
             symtab: OnceCell::new(),
             dynsym: OnceCell::new(),
+            dynamic: OnceCell::new(),
+            versions: OnceCell::new(),
+            section_addrs: OnceCell::new(),
         }
     }
 
@@ -250,12 +730,13 @@ impl<'mmap> Cache<'mmap> {
             .get(idx)
             .ok_or_invalid_input(|| format!("ELF section index ({idx}) out of bounds"))?;
 
+        let size = usize::try_from(shdr.sh_size)
+            .ok()
+            .ok_or_invalid_data(|| "failed to read section data: invalid size")?;
         let data = self
             .elf_data
-            .get(shdr.sh_offset as usize..)
-            .ok_or_invalid_data(|| "failed to read section data: invalid offset")?
-            .read_slice(shdr.sh_size as usize)
-            .ok_or_invalid_data(|| "failed to read section data: invalid size")?;
+            .get(shdr.sh_offset, size)?
+            .ok_or_invalid_data(|| "failed to read section data: invalid offset or size")?;
         Ok((shdr, data))
     }
 
@@ -274,7 +755,7 @@ impl<'mmap> Cache<'mmap> {
     fn read_first_shdr(&self, ehdr: &Elf64_Ehdr) -> Result<&'mmap Elf64_Shdr> {
         let shdr = self
             .elf_data
-            .get(ehdr.e_shoff as usize..)
+            .get(ehdr.e_shoff, mem::size_of::<Elf64_Shdr>())?
             .ok_or_invalid_data(|| "Elf64_Ehdr::e_shoff is invalid")?
             .read_pod_ref::<Elf64_Shdr>()
             .ok_or_invalid_data(|| "failed to read Elf64_Shdr")?;
@@ -282,8 +763,10 @@ impl<'mmap> Cache<'mmap> {
     }
 
     fn parse_ehdr(&self) -> Result<EhdrExt<'mmap>> {
-        let mut elf_data = self.elf_data;
-        let ehdr = elf_data
+        let ehdr = self
+            .elf_data
+            .get(0, mem::size_of::<Elf64_Ehdr>())?
+            .ok_or_invalid_data(|| "failed to read Elf64_Ehdr")?
             .read_pod_ref::<Elf64_Ehdr>()
             .ok_or_invalid_data(|| "failed to read Elf64_Ehdr")?;
         if !(ehdr.e_ident[0] == 0x7f
@@ -301,7 +784,11 @@ impl<'mmap> Cache<'mmap> {
         // or equal to SHN_LORESERVE, e_shnum holds the value zero and the real
         // number of entries in the section header table is held in the sh_size
         // member of the initial entry in section header table."
-        let shnum = if ehdr.e_shnum == 0 {
+        let shnum = if ehdr.e_shnum == 0 && ehdr.e_shoff == 0 {
+            // There is no section header table at all, as is common
+            // for core dumps, for example.
+            0
+        } else if ehdr.e_shnum == 0 {
             let shdr = self.read_first_shdr(ehdr)?;
             usize::try_from(shdr.sh_size).ok().ok_or_invalid_data(|| {
                 format!(
@@ -340,9 +827,13 @@ impl<'mmap> Cache<'mmap> {
 
     fn parse_shdrs(&self) -> Result<&'mmap [Elf64_Shdr]> {
         let ehdr = self.ensure_ehdr()?;
+        let size = ehdr
+            .shnum
+            .checked_mul(mem::size_of::<Elf64_Shdr>())
+            .ok_or_invalid_data(|| "failed to read Elf64_Shdr")?;
         let shdrs = self
             .elf_data
-            .get(ehdr.ehdr.e_shoff as usize..)
+            .get(ehdr.ehdr.e_shoff, size)?
             .ok_or_invalid_data(|| "Elf64_Ehdr::e_shoff is invalid")?
             .read_pod_slice_ref::<Elf64_Shdr>(ehdr.shnum)
             .ok_or_invalid_data(|| "failed to read Elf64_Shdr")?;
@@ -353,11 +844,48 @@ impl<'mmap> Cache<'mmap> {
         self.shdrs.get_or_try_init(|| self.parse_shdrs()).copied()
     }
 
+    fn parse_section_addrs(&self) -> Result<SectionAddrs> {
+        let shdrs = self.ensure_shdrs()?;
+        let mut idxs = shdrs
+            .iter()
+            .enumerate()
+            .filter(|(_idx, shdr)| occupies_memory(shdr))
+            .map(|(idx, _shdr)| idx)
+            .collect::<Vec<_>>();
+        // The sort is stable, meaning that sections at the same address
+        // stay in section header table order.
+        let () = idxs.sort_by_key(|idx| shdrs[*idx].sh_addr);
+
+        let max_ends = idxs
+            .iter()
+            .scan(0, |max_end, idx| {
+                let shdr = &shdrs[*idx];
+                let end = shdr.sh_addr.saturating_add(shdr.sh_size);
+                *max_end = end.max(*max_end);
+                Some(*max_end)
+            })
+            .collect();
+        let addrs = SectionAddrs {
+            idxs: idxs.into_boxed_slice(),
+            max_ends,
+        };
+        Ok(addrs)
+    }
+
+    fn ensure_section_addrs(&self) -> Result<&SectionAddrs> {
+        self.section_addrs
+            .get_or_try_init(|| self.parse_section_addrs())
+    }
+
     fn parse_phdrs(&self) -> Result<&'mmap [Elf64_Phdr]> {
         let ehdr = self.ensure_ehdr()?;
+        let size = ehdr
+            .phnum
+            .checked_mul(mem::size_of::<Elf64_Phdr>())
+            .ok_or_invalid_data(|| "failed to read Elf64_Phdr")?;
         let phdrs = self
             .elf_data
-            .get(ehdr.ehdr.e_phoff as usize..)
+            .get(ehdr.ehdr.e_phoff, size)?
             .ok_or_invalid_data(|| "Elf64_Ehdr::e_phoff is invalid")?
             .read_pod_slice_ref::<Elf64_Phdr>(ehdr.phnum)
             .ok_or_invalid_data(|| "failed to read Elf64_Phdr")?;
@@ -442,12 +970,69 @@ impl<'mmap> Cache<'mmap> {
         Ok(None)
     }
 
-    fn parse_syms(&self, section: &str) -> Result<Vec<&'mmap Elf64_Sym>> {
+    /// Find the first section of the given type.
+    fn find_section_by_type(&self, sh_type: u32) -> Result<Option<usize>> {
+        let shdrs = self.ensure_shdrs()?;
+        let idx = shdrs
+            .iter()
+            .enumerate()
+            .skip(1)
+            .find(|(_idx, shdr)| shdr.sh_type == sh_type)
+            .map(|(idx, _shdr)| idx);
+        Ok(idx)
+    }
+
+    /// Retrieve the value of the first `.dynamic` entry with the given
+    /// tag.
+    fn dynamic_val(&self, tag: i64) -> Result<Option<u64>> {
+        let (dyns, _strs) = self.ensure_dynamic()?;
+        let val = dyns
+            .iter()
+            .find(|dyn_| dyn_.d_tag == tag)
+            .map(|dyn_| dyn_.d_val);
+        Ok(val)
+    }
+
+    /// Find the symbol versioning section of type `sh_type`.
+    ///
+    /// Sections are located by type and not by name, as names are not
+    /// authoritative. If no section has the expected type, we fall back
+    /// to the section at the address recorded in the `.dynamic` entry
+    /// with the given tag, which is what the dynamic loader uses.
+    fn find_version_section(&self, sh_type: u32, tag: i64) -> Result<Option<usize>> {
+        if let Some(idx) = self.find_section_by_type(sh_type)? {
+            return Ok(Some(idx))
+        }
+
+        let addr = match self.dynamic_val(tag)? {
+            Some(addr) if addr != 0 => addr,
+            _ => return Ok(None),
+        };
+        let shdrs = self.ensure_shdrs()?;
+        let idx = shdrs
+            .iter()
+            .enumerate()
+            .skip(1)
+            .find(|(_idx, shdr)| shdr.sh_addr == addr && occupies_memory(shdr))
+            .map(|(idx, _shdr)| idx);
+        Ok(idx)
+    }
+
+    /// Check whether function symbol values may have the Thumb bit
+    /// set.
+    fn is_thumb(&self) -> Result<bool> {
+        let ehdr = self.ensure_ehdr()?;
+        Ok(ehdr.ehdr.e_machine == EM_ARM)
+    }
+
+    /// Retrieve the raw, unfiltered symbols of the given symbol table
+    /// section, in the order in which they are stored.
+    fn raw_syms(&self, section: &str) -> Result<&'mmap [Elf64_Sym]> {
         let idx = if let Some(idx) = self.find_section(section)? {
             idx
         } else {
             // The symbol table does not exists. Fake an empty one.
-            return Ok(Vec::new())
+            return Ok(&[])
         };
         let mut syms = self.section_data(idx)?;
 
@@ -458,29 +1043,79 @@ impl<'mmap> Cache<'mmap> {
         }
 
         let count = syms.len() / mem::size_of::<Elf64_Sym>();
-        let mut syms = syms
+        let syms = syms
             .read_pod_slice_ref::<Elf64_Sym>(count)
-            .ok_or_invalid_data(|| "failed to read symbol table contents")?
+            .ok_or_invalid_data(|| "failed to read symbol table contents")?;
+        Ok(syms)
+    }
+
+    /// Create a predicate checking whether a symbol referencing
+    /// `strs` is one we support.
+    fn sym_filter<'strs>(&self, strs: &'strs [u8]) -> Result<impl Fn(&Elf64_Sym) -> bool + 'strs> {
+        let ehdr = self.ensure_ehdr()?;
+        let arm = matches!(ehdr.ehdr.e_machine, EM_ARM | EM_AARCH64);
+        let filter = move |sym: &Elf64_Sym| {
+            sym.matches(SymType::Undefined) && !(arm && is_mapping_sym(strs, sym))
+        };
+        Ok(filter)
+    }
+
+    /// Parse the symbols of the given symbol table section, returning
+    /// them along with the index of the first thread-local symbol.
+    fn parse_syms(&self, section: &str, strs: &[u8]) -> Result<(Vec<&'mmap Elf64_Sym>, usize)> {
+        let thumb = self.is_thumb()?;
+        let filter = self.sym_filter(strs)?;
+        // The values of thread-local symbols are offsets into the TLS
+        // block and not virtual addresses. Hence, we keep them
+        // separate, so as to not confuse them with regular symbols.
+        let (mut tls_syms, mut syms) = self
+            .raw_syms(section)?
             .iter()
             // Filter out any symbols that we do not support.
-            .filter(|sym| sym.matches(SymType::Undefined))
-            .collect::<Vec<&Elf64_Sym>>();
+            .filter(|sym| filter(sym))
+            .partition::<Vec<&Elf64_Sym>, _>(|sym| sym.matches(SymType::Tls));
         // Order symbols by address and those with equal address descending by
         // size.
-        let () = syms.sort_by(|sym1, sym2| {
-            sym1.st_value
-                .cmp(&sym2.st_value)
+        let cmp = |sym1: &&Elf64_Sym, sym2: &&Elf64_Sym| {
+            sym_addr(sym1, thumb)
+                .cmp(&sym_addr(sym2, thumb))
                 .then_with(|| sym1.st_size.cmp(&sym2.st_size).reverse())
-        });
+        };
+        let () = syms.sort_by(cmp);
+        let () = tls_syms.sort_by(cmp);
 
-        Ok(syms)
+        let tls_idx = syms.len();
+        let () = syms.append(&mut tls_syms);
+        Ok((syms, tls_idx))
+    }
+
+    /// Scan the symbol table `section`, referencing the string table
+    /// `strs_section`, for the symbol of type `type_` covering `addr`,
+    /// without building an index.
+    fn scan_syms(
+        &self,
+        section: &str,
+        strs_section: &str,
+        addr: Addr,
+        type_: SymType,
+    ) -> Result<Option<ResolvedSym<'mmap>>> {
+        let strs = self.parse_strs(strs_section)?;
+        let filter = self.sym_filter(strs)?;
+        let tls = type_ == SymType::Tls;
+        // The Thumb bit is only ever set on function addresses.
+        let thumb = !tls && self.is_thumb()?;
+        let syms = self
+            .raw_syms(section)?
+            .iter()
+            .filter(|sym| filter(sym) && sym.matches(SymType::Tls) == tls);
+        scan_sym(syms, strs, thumb, addr, type_)
     }
 
     fn ensure_symtab_cache(&self) -> Result<&SymbolTableCache<'mmap>> {
         self.symtab.get_or_try_init(|| {
-            let syms = self.parse_syms(".symtab")?;
             let strtab = self.parse_strs(".strtab")?;
-            let cache = SymbolTableCache::new(syms, strtab);
+            let (syms, tls_idx) = self.parse_syms(".symtab", strtab)?;
+            let cache = SymbolTableCache::new(syms, tls_idx, self.is_thumb()?, strtab);
             Ok(cache)
         })
     }
@@ -490,23 +1125,19 @@ impl<'mmap> Cache<'mmap> {
             // TODO: We really should check the `.dynamic` section for
             //       information on what symbol and string tables to
             //       use instead of hard coding names here.
-            let syms = self.parse_syms(".dynsym")?;
             let dynstr = self.parse_strs(".dynstr")?;
-            let cache = SymbolTableCache::new(syms, dynstr);
+            let (syms, tls_idx) = self.parse_syms(".dynsym", dynstr)?;
+            let cache = SymbolTableCache::new(syms, tls_idx, self.is_thumb()?, dynstr);
             Ok(cache)
         })
     }
 
+    #[cfg(test)]
     fn ensure_symtab(&self) -> Result<&[&'mmap Elf64_Sym]> {
         let symtab = self.ensure_symtab_cache()?;
         Ok(&symtab.syms)
     }
 
-    fn ensure_dynsym(&self) -> Result<&[&'mmap Elf64_Sym]> {
-        let dynsym = self.ensure_dynsym_cache()?;
-        Ok(&dynsym.syms)
-    }
-
     fn parse_strs(&self, section: &str) -> Result<&'mmap [u8]> {
         let strs = if let Some(idx) = self.find_section(section)? {
             self.section_data(idx)?
@@ -516,6 +1147,188 @@ impl<'mmap> Cache<'mmap> {
         Ok(strs)
     }
 
+    fn parse_dynamic(&self) -> Result<(&'mmap [Elf64_Dyn], &'mmap [u8])> {
+        let idx = if let Some(idx) = self.find_section(".dynamic")? {
+            idx
+        } else {
+            // Statically linked binaries do not have a dynamic section.
+            return Ok((&[], &[]))
+        };
+        let (shdr, mut data) = self.section_data_raw(idx)?;
+
+        if data.len() % mem::size_of::<Elf64_Dyn>() != 0 {
+            return Err(Error::with_invalid_data(
+                "size of dynamic section is invalid",
+            ))
+        }
+
+        let count = data.len() / mem::size_of::<Elf64_Dyn>();
+        let dyns = data
+            .read_pod_slice_ref::<Elf64_Dyn>(count)
+            .ok_or_invalid_data(|| "failed to read dynamic section contents")?;
+        // Anything past the terminating `DT_NULL` entry is meaningless.
+        let count = dyns
+            .iter()
+            .position(|dyn_| dyn_.d_tag == DT_NULL)
+            .unwrap_or(count);
+        // The section header's `sh_link` refers to the string table
+        // used by entries.
+        let (_shdr, strs) = self.section_data_raw(shdr.sh_link as usize)?;
+        Ok((&dyns[..count], strs))
+    }
+
+    fn ensure_dynamic(&self) -> Result<(&'mmap [Elf64_Dyn], &'mmap [u8])> {
+        let dynamic = self.dynamic.get_or_try_init(|| self.parse_dynamic())?;
+        Ok(*dynamic)
+    }
+
+    /// Read a version name from the string table `strs`.
+    fn version_name(strs: &'mmap [u8], offset: u32) -> Result<&'mmap str> {
+        let name = strs
+            .get(offset as usize..)
+            .ok_or_invalid_data(|| "version name string table offset out of bounds")?
+            .read_cstr()
+            .ok_or_invalid_data(|| "no valid string found in string table")?
+            .to_str()
+            .map_err(Error::with_invalid_data)
+            .context("invalid version name")?;
+        Ok(name)
+    }
+
+    /// Parse the version definitions contained in the
+    /// `.gnu.version_d` section at index `idx`, recording their names
+    /// by version index in `names`.
+    fn parse_verdefs(&self, idx: usize, names: &mut HashMap<u16, &'mmap str>) -> Result<()> {
+        let (shdr, data) = self.section_data_raw(idx)?;
+        let strs = self.section_data(shdr.sh_link as usize)?;
+        // `sh_info` holds the number of version definitions, but not
+        // all producers fill it in.
+        let count = match shdr.sh_info {
+            0 => self.dynamic_val(DT_VERDEFNUM)?.unwrap_or(0),
+            count => u64::from(count),
+        };
+
+        let mut offset = 0usize;
+        for _ in 0..count {
+            let verdef = data
+                .get(offset..)
+                .and_then(|mut data| data.read_pod::<Elf64_Verdef>())
+                .ok_or_invalid_data(|| "failed to read Elf64_Verdef")?;
+            // The base definition merely names the file itself and is
+            // never referenced by symbols.
+            if verdef.vd_flags & VER_FLG_BASE == 0 {
+                // The first auxiliary entry carries the version's name;
+                // any others name its parents.
+                let verdaux = data
+                    .get(offset.saturating_add(verdef.vd_aux as usize)..)
+                    .and_then(|mut data| data.read_pod::<Elf64_Verdaux>())
+                    .ok_or_invalid_data(|| "failed to read Elf64_Verdaux")?;
+                let name = Self::version_name(strs, verdaux.vda_name)?;
+                let _prev = names.insert(verdef.vd_ndx, name);
+            }
+
+            if verdef.vd_next == 0 {
+                break
+            }
+            offset = offset.saturating_add(verdef.vd_next as usize);
+        }
+        Ok(())
+    }
+
+    /// Parse the version dependencies contained in the
+    /// `.gnu.version_r` section at index `idx`, recording their names
+    /// by version index in `names`.
+    fn parse_verneeds(&self, idx: usize, names: &mut HashMap<u16, &'mmap str>) -> Result<()> {
+        let (shdr, data) = self.section_data_raw(idx)?;
+        let strs = self.section_data(shdr.sh_link as usize)?;
+        // `sh_info` holds the number of version dependencies, but not
+        // all producers fill it in.
+        let count = match shdr.sh_info {
+            0 => self.dynamic_val(DT_VERNEEDNUM)?.unwrap_or(0),
+            count => u64::from(count),
+        };
+
+        let mut offset = 0usize;
+        for _ in 0..count {
+            let verneed = data
+                .get(offset..)
+                .and_then(|mut data| data.read_pod::<Elf64_Verneed>())
+                .ok_or_invalid_data(|| "failed to read Elf64_Verneed")?;
+
+            let mut aux_offset = offset.saturating_add(verneed.vn_aux as usize);
+            for _ in 0..verneed.vn_cnt {
+                let vernaux = data
+                    .get(aux_offset..)
+                    .and_then(|mut data| data.read_pod::<Elf64_Vernaux>())
+                    .ok_or_invalid_data(|| "failed to read Elf64_Vernaux")?;
+                let name = Self::version_name(strs, vernaux.vna_name)?;
+                let _prev = names.insert(vernaux.vna_other, name);
+
+                if vernaux.vna_next == 0 {
+                    break
+                }
+                aux_offset = aux_offset.saturating_add(vernaux.vna_next as usize);
+            }
+
+            if verneed.vn_next == 0 {
+                break
+            }
+            offset = offset.saturating_add(verneed.vn_next as usize);
+        }
+        Ok(())
+    }
+
+    fn parse_versions(&self) -> Result<VersionCache<'mmap>> {
+        let versym_idx = if let Some(idx) = self.find_version_section(SHT_GNU_VERSYM, DT_VERSYM)? {
+            idx
+        } else {
+            // Symbols are unversioned.
+            return Ok(VersionCache::default())
+        };
+        // The version symbol table's `sh_link` refers to the symbol
+        // table it annotates.
+        let shdrs = self.ensure_shdrs()?;
+        let link = shdrs[versym_idx].sh_link as usize;
+        let dynsym_idx = match shdrs.get(link) {
+            Some(shdr) if shdr.sh_type == SHT_DYNSYM => link,
+            _ => match self.find_section(".dynsym")? {
+                Some(idx) => idx,
+                None => return Ok(VersionCache::default()),
+            },
+        };
+
+        let mut data = self.section_data(dynsym_idx)?;
+        let count = data.len() / mem::size_of::<Elf64_Sym>();
+        let dynsyms = data
+            .read_pod_slice_ref::<Elf64_Sym>(count)
+            .ok_or_invalid_data(|| "failed to read symbol table contents")?;
+
+        let mut data = self.section_data(versym_idx)?;
+        let count = data.len() / mem::size_of::<u16>();
+        let versyms = data
+            .read_pod_slice_ref::<u16>(count)
+            .ok_or_invalid_data(|| "failed to read symbol version table contents")?;
+
+        let mut names = HashMap::new();
+        if let Some(idx) = self.find_version_section(SHT_GNU_VERDEF, DT_VERDEF)? {
+            let () = self.parse_verdefs(idx, &mut names)?;
+        }
+        if let Some(idx) = self.find_version_section(SHT_GNU_VERNEED, DT_VERNEED)? {
+            let () = self.parse_verneeds(idx, &mut names)?;
+        }
+
+        let cache = VersionCache {
+            dynsyms,
+            versyms,
+            names,
+        };
+        Ok(cache)
+    }
+
+    fn ensure_versions(&self) -> Result<&VersionCache<'mmap>> {
+        self.versions.get_or_try_init(|| self.parse_versions())
+    }
+
     fn ensure_str2symtab(&self) -> Result<&[(&'mmap str, usize)]> {
         let symtab = self.ensure_symtab_cache()?;
         let str2sym = symtab.ensure_str2sym(|_sym| true)?;
@@ -528,14 +1341,11 @@ impl<'mmap> Cache<'mmap> {
         let str2sym = dynsym.ensure_str2sym(|sym| {
             // We filter out all the symbols that already exist in symtab,
             // to prevent any duplicates from showing up.
-            let result = find_sym(
-                &symtab.syms,
-                symtab.strs,
-                sym.st_value,
-                // SANITY: We filter out all unsupported symbol types,
-                //         so this conversion should always succeed.
-                SymType::try_from(sym).unwrap(),
-            );
+            // SANITY: We filter out all unsupported symbol types, so
+            //         this conversion should always succeed.
+            let type_ = SymType::try_from(sym).unwrap();
+            let addr = sym_addr(sym, dynsym.thumb);
+            let result = symtab.find_sym(addr, type_);
             !matches!(result, Ok(Some(_)))
         })?;
         Ok(str2sym)
@@ -549,25 +1359,78 @@ impl Debug for Cache<'_> {
 }
 
 
-/// A parser for ELF64 files.
+/// The way in which ELF symbol tables are consulted for address
+/// lookups.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum SymbolTableIndexing {
+    /// Parse `.symtab` and `.dynsym` in their entirety on first use,
+    /// building an index sorted by address.
+    ///
+    /// Building the index is linear in the number of symbols plus the
+    /// cost of sorting them, but afterwards each lookup is
+    /// logarithmic. This mode hence pays off when looking up more than
+    /// a handful of addresses.
+    #[default]
+    Eager,
+    /// Scan `.symtab` and `.dynsym` linearly on each lookup, without
+    /// building an index.
+    ///
+    /// This mode avoids the up-front cost (and memory) of building the
+    /// index, which can be substantial for binaries with many
+    /// symbols, and is preferable for one-shot lookups. Lookups of
+    /// addresses not covered by any symbol still build the index, as
+    /// do lookups by name. Once built, the index is used for all
+    /// lookups.
+    Lazy,
+}
+
+
+/// A parser for ELF files.
+///
+/// Both 32 bit and 64 bit ELF files are supported.
 #[derive(Debug)]
-pub(crate) struct ElfParser {
+pub struct ElfParser {
     /// A cache for relevant parts of the ELF file.
     // SAFETY: We must not hand out references with a 'static lifetime to
     //         this member. Rather, they should never outlive `self`.
-    //         Furthermore, this member has to be listed before `_mmap`
-    //         to make sure we never end up with a dangling reference.
+    //         Furthermore, this member has to be listed before `_mmap`,
+    //         `_converted`, `_widened`, and `_lazy` to make sure we
+    //         never end up with a dangling reference.
     cache: Cache<'static>,
     /// A mapping from section index to decompressed section data.
+    ///
+    /// Decompressed data are backed by anonymous memory mappings
+    /// instead of heap allocations, so that they are returned to the
+    /// system in their entirety once the parser is dropped.
     // Note that conceptually this member would be best contained in the
     // `Cache` type, however, lifetimes get very hairy once we move it
     // in there. Given that it is an implementation detail, we can live
     // with this slightly counter-intuitive split.
-    decompressed: InsertMap<usize, Vec<u8>>,
+    decompressed: InsertMap<usize, Mmap>,
+    /// The stubs of the procedure linkage table, lazily parsed on
+    /// first use.
+    plt: OnceCell<PltStubs>,
     /// The memory mapped file.
     _mmap: Mmap,
+    /// A copy of the file's data converted to host byte order and the
+    /// ELF64 layout, if the file is of foreign byte order. Empty
+    /// otherwise.
+    // Storage is `u64` based to satisfy the alignment requirements of
+    // all ELF data structures.
+    _converted: Box<[u64]>,
+    /// The widened structures of a 32 bit ELF file of host byte order.
+    _widened: Option<Widened>,
+    /// The ELF data read on demand, if the parser was created for a
+    /// reader.
+    _lazy: Option<Box<LazyData>>,
+    /// How symbol tables are consulted for address lookups.
+    symbol_table_indexing: SymbolTableIndexing,
     /// The path to the ELF file being worked on.
     path: PathBuf,
+    /// The identity of the file being worked on, if the parser was
+    /// created for a file on disk.
+    file_id: Option<FileId>,
 }
 
 impl ElfParser {
@@ -576,8 +1439,30 @@ impl ElfParser {
     where
         P: Into<PathBuf>,
     {
+        let meta = file
+            .metadata()
+            .context("failed to retrieve file metadata")?;
         let mmap = Mmap::map(file).context("failed to memory map file")?;
-        Ok(Self::from_mmap(mmap, path))
+        let mut parser = Self::from_mmap(mmap, path);
+        parser.file_id = Some(FileId::from(&meta));
+        Ok(parser)
+    }
+
+    /// Create an `ElfParser` from a file descriptor.
+    ///
+    /// This constructor is useful in contexts where files cannot be
+    /// opened by path, e.g., because they are handed out by a
+    /// privileged broker process. `label` is used in place of the
+    /// file's path, e.g., when reporting errors.
+    pub fn open_fd<P>(fd: BorrowedFd<'_>, label: P) -> Result<Self>
+    where
+        P: Into<PathBuf>,
+    {
+        let file = fd
+            .try_clone_to_owned()
+            .map(File::from)
+            .context("failed to duplicate file descriptor")?;
+        Self::open_file(&file, label)
     }
 
     /// Create an `ElfParser` from mmap'ed data.
@@ -586,17 +1471,65 @@ impl ElfParser {
         P: Into<PathBuf>,
     {
         fn from_mmap_impl(mmap: Mmap, path: PathBuf) -> ElfParser {
-            // We transmute the mmap's lifetime to static here as that is a
-            // necessity for self-referentiality.
+            let foreign = endian::is_foreign(&mmap);
+            // Conversion errors are not fatal here: they will surface
+            // as parse errors once the offending data is accessed.
+            let widened = if class::is_elf32(&mmap) {
+                class::widen_elf(&mmap, foreign)
+            } else {
+                None
+            };
+
+            // We transmute the data's lifetime to static here as that is
+            // a necessity for self-referentiality.
             // SAFETY: We never hand out any 'static references to cache
-            //         data.
-            let elf_data = unsafe { mem::transmute(mmap.deref()) };
+            //         data. The data is heap allocated or memory mapped,
+            //         meaning that it does not move along with `self`.
+            let transmute = |data| unsafe { mem::transmute::<&[u8], &'static [u8]>(data) };
+
+            let mut converted = Box::<[u64]>::default();
+            let elf_data = if foreign {
+                let len = widened
+                    .as_ref()
+                    .map(Widened::total_len)
+                    .unwrap_or(mmap.len());
+                converted = vec![0; (len + 7) / 8].into_boxed_slice();
+                // SAFETY: The buffer is at least `len` bytes in size and
+                //         any bit pattern is valid for `u64`.
+                let bytes =
+                    unsafe { slice::from_raw_parts_mut(converted.as_mut_ptr().cast::<u8>(), len) };
+                if let Some(widened) = &widened {
+                    let () = widened.apply(&mmap, bytes);
+                } else {
+                    let () = bytes.copy_from_slice(&mmap);
+                }
+                let _result = endian::swap_elf(bytes, true);
+                ElfData::Mapped(transmute(bytes))
+            } else if let Some(widened) = &widened {
+                // Only the widened structures live on the heap. All
+                // other data are read directly from the mapping.
+                ElfData::Widened {
+                    file: transmute(&mmap),
+                    ehdr: transmute(widened.ehdr()),
+                    structs: transmute(widened.structs()),
+                    base: widened.base(),
+                }
+            } else {
+                ElfData::Mapped(transmute(&mmap))
+            };
+            let widened = widened.filter(|_| !foreign);
 
             let parser = ElfParser {
                 _mmap: mmap,
+                _converted: converted,
+                _widened: widened,
+                _lazy: None,
                 decompressed: InsertMap::new(),
+                plt: OnceCell::new(),
                 cache: Cache::new(elf_data),
+                symbol_table_indexing: SymbolTableIndexing::default(),
                 path,
+                file_id: None,
             };
             parser
         }
@@ -604,20 +1537,101 @@ impl ElfParser {
         from_mmap_impl(mmap, path.into())
     }
 
-    /// Create an `ElfParser` for a path.
-    pub fn open(path: &Path) -> Result<ElfParser> {
-        let file =
-            File::open(path).with_context(|| format!("failed to open {}", path.display()))?;
-        Self::open_file(&file, path)
+    /// Create an `ElfParser` for ELF data residing in memory.
+    ///
+    /// This constructor is useful if the ELF data are not backed by a
+    /// file, e.g., because they were extracted from an archive or
+    /// received over the network. `name` is used in place of the
+    /// file's path, e.g., when reporting errors.
+    ///
+    /// The data are copied into an anonymous memory mapping, as
+    /// parsing relies on ELF data structures being suitably aligned,
+    /// which is not a given for arbitrary byte slices.
+    pub fn open_from_bytes(data: &[u8], name: &str) -> Result<ElfParser> {
+        let mmap = Mmap::builder()
+            .map_data(data)
+            .with_context(|| format!("failed to map ELF data of {name}"))?;
+        Ok(Self::from_mmap(mmap, name))
     }
 
-    /// Retrieve the data corresponding to the ELF section at index
-    /// `idx`, optionally decompressing it if it is compressed.
+    /// Create an `ElfParser` for ELF data provided by a reader.
     ///
-    /// If the section is compressed the resulting decompressed data
-    /// will be cached for the life time of this object.
-    pub fn section_data(&self, idx: usize) -> Result<&[u8]> {
-        let (shdr, mut data) = self.cache.section_data_raw(idx)?;
+    /// Data are read on demand as they are accessed, through seeks
+    /// and reads on `reader`, instead of being memory mapped. This
+    /// constructor is useful for data that are not directly accessible
+    /// as a file, e.g., because they have to be decrypted first or are
+    /// stored remotely. `label` is used in place of the file's path,
+    /// e.g., when reporting errors.
+    ///
+    /// 32 bit ELF files as well as those of foreign byte order have to
+    /// be converted before they can be parsed and are read in their
+    /// entirety up front.
+    pub fn from_reader<R, P>(reader: R, label: P) -> Result<Self>
+    where
+        R: Read + Seek + 'static,
+        P: Into<PathBuf>,
+    {
+        fn from_reader_impl(reader: Box<dyn ReadSeek>, path: PathBuf) -> Result<ElfParser> {
+            let lazy = Box::new(LazyData::new(reader)?);
+            let ident = lazy.get(0, EI_NIDENT)?.unwrap_or(&[]);
+
+            if endian::is_foreign(ident) || class::is_elf32(ident) {
+                let len = lazy.len();
+                let data = lazy
+                    .get(0, len)?
+                    .ok_or_invalid_data(|| "failed to read ELF data")?;
+                let mmap = Mmap::builder().map_data(data)?;
+                return Ok(ElfParser::from_mmap(mmap, path))
+            }
+
+            // SAFETY: We never hand out any 'static references to cache
+            //         data. The data is heap allocated and does not
+            //         move along with `self`.
+            let elf_data = unsafe { mem::transmute::<&LazyData, &'static LazyData>(&*lazy) };
+            let parser = ElfParser {
+                _mmap: Mmap::builder().map_data(&[])?,
+                _converted: Box::default(),
+                _widened: None,
+                _lazy: Some(lazy),
+                decompressed: InsertMap::new(),
+                plt: OnceCell::new(),
+                cache: Cache::new(ElfData::Lazy(elf_data)),
+                symbol_table_indexing: SymbolTableIndexing::default(),
+                path,
+                file_id: None,
+            };
+            Ok(parser)
+        }
+
+        from_reader_impl(Box::new(reader), label.into())
+    }
+
+    /// Create an `ElfParser` for a path.
+    pub fn open(path: &Path) -> Result<ElfParser> {
+        let file =
+            File::open(path).with_context(|| format!("failed to open {}", path.display()))?;
+        Self::open_file(&file, path)
+    }
+
+    /// Set how symbol tables are consulted for address lookups.
+    ///
+    /// By default, symbol tables are indexed on first use
+    /// ([`SymbolTableIndexing::Eager`]), which is the better choice
+    /// for repeated lookups. Please refer to [`SymbolTableIndexing`]
+    /// for details on the tradeoffs involved.
+    pub fn set_symbol_table_indexing(&mut self, indexing: SymbolTableIndexing) {
+        self.symbol_table_indexing = indexing;
+    }
+
+    /// Retrieve the data corresponding to the ELF section at index
+    /// `idx`, optionally decompressing it if it is compressed.
+    ///
+    /// If the section is compressed the resulting decompressed data
+    /// will be cached for the life time of this object. As parsers are
+    /// shared between ELF and DWARF resolvers created for the same
+    /// file, each section is decompressed at most once.
+    pub fn section_data(&self, idx: usize) -> Result<&[u8]> {
+        let (shdr, mut data) = self.cache.section_data_raw(idx)?;
 
         if shdr.sh_flags & SHF_COMPRESSED != 0 {
             let data = self.decompressed.get_or_try_insert(idx, || {
@@ -627,22 +1641,27 @@ impl ElfParser {
                     .read_pod_ref::<Elf64_Chdr>()
                     .ok_or_invalid_data(|| "failed to read Elf64_Chdr")?;
 
-                let decompressed = match chdr.ch_type {
-                    t if t == ELFCOMPRESS_ZLIB => decompress_zlib(data),
-                    t if t == ELFCOMPRESS_ZSTD => decompress_zstd(data),
-                    _ => Err(Error::with_unsupported(format!(
-                        "ELF section is compressed with unknown compression algorithm ({})",
-                        chdr.ch_type
-                    ))),
-                }?;
-                debug_assert_eq!(
-                    decompressed.len(),
-                    chdr.ch_size as _,
-                    "decompressed ELF section data does not have expected length"
-                );
-                Ok(decompressed)
+                let decompress = match chdr.ch_type {
+                    t if t == ELFCOMPRESS_ZLIB => decompress_zlib,
+                    t if t == ELFCOMPRESS_ZSTD => decompress_zstd,
+                    _ => {
+                        return Err(Error::with_unsupported(format!(
+                            "ELF section is compressed with unknown compression algorithm ({})",
+                            chdr.ch_type
+                        )))
+                    }
+                };
+                let len = usize::try_from(chdr.ch_size)
+                    .ok()
+                    .ok_or_invalid_data(|| "decompressed ELF section is too large")?;
+
+                // We decompress directly into an anonymous memory
+                // mapping of the expected size. Doing so avoids
+                // intermediate buffers and keeps peak memory usage
+                // to the size of the decompressed data.
+                Mmap::builder().map_anon(len, |buf| decompress(data, buf))
             })?;
-            Ok(data.as_slice())
+            Ok(data.deref())
         } else {
             Ok(data)
         }
@@ -656,32 +1675,336 @@ impl ElfParser {
         Ok(index)
     }
 
+    /// Retrieve the data of the section with the given name,
+    /// decompressing it if necessary.
+    ///
+    /// This is a convenience wrapper around [`ElfParser::find_section`]
+    /// and [`ElfParser::section_data`]. The returned slice borrows
+    /// directly from the underlying mapping for uncompressed sections
+    /// and from the cached decompressed copy otherwise, i.e., no
+    /// copying takes place in either case. `None` is returned if no
+    /// such section exists.
+    pub fn find_section_data(&self, name: &str) -> Result<Option<&[u8]>> {
+        match self.find_section(name)? {
+            Some(idx) => self.section_data(idx).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Find the name of the section containing the given virtual
+    /// address.
+    ///
+    /// Only sections occupying memory during process execution (i.e.,
+    /// those with the `SHF_ALLOC` flag set) are considered. That
+    /// includes `SHT_NOBITS` sections such as `.bss`, which do not
+    /// have any data in the file, but excludes `.tbss`, which only
+    /// describes the layout of thread-local data.
+    ///
+    /// Sections should not overlap in a valid ELF file, but some
+    /// linker scripts produce ones that do. In such a case the
+    /// section with the smallest range containing `addr` is reported.
+    pub fn section_for_addr(&self, addr: Addr) -> Result<Option<&str>> {
+        let shdrs = self.cache.ensure_shdrs()?;
+        let SectionAddrs { idxs, max_ends } = self.cache.ensure_section_addrs()?;
+        let end = idxs.partition_point(|idx| shdrs[*idx].sh_addr <= addr);
+
+        let mut best = None::<usize>;
+        for i in (0..end).rev() {
+            if max_ends[i] <= addr {
+                // None of the remaining sections extends far enough to
+                // cover `addr`.
+                break
+            }
+
+            let idx = idxs[i];
+            let shdr = &shdrs[idx];
+            if addr >= shdr.sh_addr.saturating_add(shdr.sh_size) {
+                continue
+            }
+
+            // We are iterating in reverse order, so on a tie we replace
+            // the current best match, ending up with the first one.
+            if best.map_or(true, |best| shdr.sh_size <= shdrs[best].sh_size) {
+                best = Some(idx);
+            }
+        }
+
+        match best {
+            Some(idx) => {
+                let name = self.cache.section_name(idx)?;
+                Ok(Some(name))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Retrieve the strings referenced by all `.dynamic` entries with
+    /// the given tag, in order.
+    fn dynamic_strs(&self, tag: i64) -> Result<Vec<&OsStr>> {
+        let (dyns, strs) = self.cache.ensure_dynamic()?;
+        dyns.iter()
+            .filter(|dyn_| dyn_.d_tag == tag)
+            .map(|dyn_| {
+                let s = strs
+                    .get(dyn_.d_val as usize..)
+                    .ok_or_invalid_data(|| "dynamic string table offset out of bounds")?
+                    .read_cstr()
+                    .ok_or_invalid_data(|| "no valid string found in dynamic string table")?;
+                Ok(OsStr::from_bytes(s.to_bytes()))
+            })
+            .collect()
+    }
+
+    /// Retrieve the colon separated search paths stored in all
+    /// `.dynamic` entries with the given tag.
+    fn dynamic_paths(&self, tag: i64) -> Result<Vec<PathBuf>> {
+        let paths = self
+            .dynamic_strs(tag)?
+            .into_iter()
+            .flat_map(|paths| paths.as_bytes().split(|b| *b == b':'))
+            .filter(|path| !path.is_empty())
+            .map(|path| PathBuf::from(OsStr::from_bytes(path)))
+            .collect();
+        Ok(paths)
+    }
+
+    /// Retrieve the names of the shared objects this ELF file depends
+    /// on, as recorded in `DT_NEEDED` entries of the `.dynamic`
+    /// section.
+    ///
+    /// Names are reported in the order in which they appear, which
+    /// determines the order in which the dynamic linker loads them.
+    pub fn needed_libs(&self) -> Result<Vec<String>> {
+        self.dynamic_strs(DT_NEEDED)?
+            .into_iter()
+            .map(|name| {
+                name.to_str()
+                    .map(str::to_string)
+                    .ok_or_invalid_data(|| "shared object name is not valid UTF-8")
+            })
+            .collect()
+    }
+
+    /// Retrieve the shared object name recorded in the `DT_SONAME`
+    /// entry of the `.dynamic` section, if any.
+    pub fn soname(&self) -> Result<Option<String>> {
+        self.dynamic_strs(DT_SONAME)?
+            .into_iter()
+            .next()
+            .map(|name| {
+                name.to_str()
+                    .map(str::to_string)
+                    .ok_or_invalid_data(|| "shared object name is not valid UTF-8")
+            })
+            .transpose()
+    }
+
+    /// Retrieve the names of all symbols in the symbol table section
+    /// `section`, in table order.
+    pub(crate) fn raw_sym_names(&self, section: &str) -> Result<Vec<&[u8]>> {
+        let idx = match self.find_section(section)? {
+            Some(idx) => idx,
+            None => return Ok(Vec::new()),
+        };
+        let (shdr, mut data) = self.cache.section_data_raw(idx)?;
+        let (_shdr, strs) = self.cache.section_data_raw(shdr.sh_link as usize)?;
+        let count = data.len() / mem::size_of::<Elf64_Sym>();
+        data.read_pod_slice_ref::<Elf64_Sym>(count)
+            .ok_or_invalid_data(|| format!("failed to read {section} contents"))?
+            .iter()
+            .map(|sym| {
+                let name = strs
+                    .get(sym.st_name as usize..)
+                    .and_then(|mut strs| strs.read_cstr())
+                    .ok_or_invalid_data(|| "failed to read symbol name")?;
+                Ok(name.to_bytes())
+            })
+            .collect()
+    }
+
+    /// Calculate the false positive rate of the Bloom filter of the
+    /// `.gnu.hash` section.
+    ///
+    /// All named symbols of the `.symtab` and `.dynsym` sections that
+    /// are not covered by the hash table are tested against the Bloom
+    /// filter. Each one it does not reject is a false positive. The
+    /// reported rate is the ratio of false positives to tested
+    /// symbols, or `0.0` if there are no such symbols.
+    ///
+    /// This functionality is mostly useful for analyzing the quality
+    /// of `.gnu.hash` sections, e.g., when tuning linker behavior.
+    /// `None` is returned if the file does not contain a `.gnu.hash`
+    /// section.
+    pub fn gnu_hash_bloom_false_positive_rate(&self) -> Result<Option<f64>> {
+        let idx = match self.find_section(".gnu.hash")? {
+            Some(idx) => idx,
+            None => return Ok(None),
+        };
+        let table = GnuHashTable::parse(self.section_data(idx)?, self.address_size()?)?;
+        let dynsyms = self.raw_sym_names(".dynsym")?;
+        let hashed = dynsyms
+            .get(table.symoffset()..)
+            .unwrap_or(&[])
+            .iter()
+            .collect::<HashSet<_>>();
+        let candidates = self
+            .raw_sym_names(".symtab")?
+            .into_iter()
+            .chain(
+                dynsyms
+                    .get(..table.symoffset())
+                    .unwrap_or(&dynsyms)
+                    .iter()
+                    .copied(),
+            )
+            .filter(|name| !name.is_empty() && !hashed.contains(name))
+            .collect::<HashSet<_>>();
+
+        if candidates.is_empty() {
+            return Ok(Some(0.0))
+        }
+
+        let false_positives = candidates
+            .iter()
+            .filter(|name| table.bloom_may_contain(gnu_hash(name)))
+            .count();
+        let rate = false_positives as f64 / candidates.len() as f64;
+        Ok(Some(rate))
+    }
+
+    /// Retrieve the library search paths recorded in `DT_RPATH` entries
+    /// of the `.dynamic` section.
+    ///
+    /// Dynamic string tokens such as `$ORIGIN` are reported verbatim
+    /// and not expanded.
+    pub fn rpath(&self) -> Result<Vec<PathBuf>> {
+        self.dynamic_paths(DT_RPATH)
+    }
+
+    /// Retrieve the library search paths recorded in `DT_RUNPATH`
+    /// entries of the `.dynamic` section.
+    ///
+    /// Dynamic string tokens such as `$ORIGIN` are reported verbatim
+    /// and not expanded.
+    pub fn runpath(&self) -> Result<Vec<PathBuf>> {
+        self.dynamic_paths(DT_RUNPATH)
+    }
+
+    /// Retrieve the stubs of the procedure linkage table, parsing them
+    /// on first use.
+    pub(crate) fn plt_stubs(&self) -> Result<&PltStubs> {
+        self.plt.get_or_try_init(|| PltStubs::parse(self))
+    }
+
+    /// Retrieve the entries of the procedure linkage table (PLT).
+    ///
+    /// Each entry maps the address of a PLT stub (in `.plt` or, if
+    /// present, `.plt.sec`) to the name of the imported function it
+    /// calls, as determined by the `.rela.plt` relocation and
+    /// `.dynsym` symbol belonging to the stub's slot. Entries are
+    /// sorted by address.
+    ///
+    /// Only x86-64 PLT layouts are currently understood. For other
+    /// architectures an empty list is returned.
+    pub fn plt_entries(&self) -> Result<Vec<PltEntry>> {
+        let entries = self.plt_stubs()?.entries().collect();
+        Ok(entries)
+    }
+
+    /// Find the symbol covering the address `addr`.
+    ///
+    /// The address is expected to be a virtual offset, as it would be
+    /// found in the ELF file (i.e., without any relocation applied).
+    ///
+    /// If enabled via [`FindSymOpts`], addresses inside PLT stubs
+    /// are reported as the function being called by the stub,
+    /// suffixed with `@plt`.
     pub fn find_sym(
         &self,
         addr: Addr,
         opts: &FindSymOpts,
     ) -> Result<Result<ResolvedSym<'_>, Reason>> {
-        // ELF doesn't carry any source code or inlining information.
-        let _opts = opts;
+        if opts.plt_syms() {
+            if let Some(mut sym) = self.plt_stubs()?.find_sym(addr) {
+                sym.section = self.section_for_addr(sym.addr)?;
+                return Ok(Ok(sym))
+            }
+        }
 
-        let symtab_cache = self.cache.ensure_symtab_cache()?;
-        if let Some(sym) = find_sym(
-            &symtab_cache.syms,
-            symtab_cache.strs,
-            addr,
-            SymType::Undefined,
-        )? {
-            return Ok(Ok(sym))
+        let mut sym = match self.find_sym_impl(addr, opts.nearest_preceding())? {
+            Ok(sym) => sym,
+            Err(reason) => return Ok(Err(reason)),
+        };
+
+        sym.section = self.section_for_addr(sym.addr)?;
+
+        if !opts.raw_elf_syms() {
+            sym.raw_elf_sym = None;
         }
+        if !opts.sym_versions() {
+            sym.version = None;
+        } else if sym.version.is_none() {
+            sym.version = self.dynsym_version(sym.name, sym.addr)?;
+        }
+        Ok(Ok(sym))
+    }
 
+    fn find_sym_impl(
+        &self,
+        addr: Addr,
+        nearest_preceding: bool,
+    ) -> Result<Result<ResolvedSym<'_>, Reason>> {
+        if self.symbol_table_indexing == SymbolTableIndexing::Lazy
+            && self.cache.symtab.get().is_none()
+        {
+            if let Some(sym) = self.scan_sym(addr)? {
+                return Ok(Ok(sym))
+            }
+        }
+
+        // ELF doesn't carry any source code or inlining information.
+        let symtab_cache = self.cache.ensure_symtab_cache()?;
         let dynsym_cache = self.cache.ensure_dynsym_cache()?;
-        if let Some(sym) = find_sym(
-            &dynsym_cache.syms,
-            dynsym_cache.strs,
-            addr,
-            SymType::Undefined,
-        )? {
-            return Ok(Ok(sym))
+
+        // Addresses inside the TLS initialization image are matched
+        // against thread-local symbols first, based on their offset
+        // into the TLS block.
+        if let Some(phdr) = self.tls_segment()? {
+            if (phdr.p_vaddr..phdr.p_vaddr + phdr.p_memsz).contains(&addr) {
+                let offset = addr - phdr.p_vaddr;
+                for cache in [symtab_cache, dynsym_cache] {
+                    if let Some(mut sym) = cache.find_sym(offset, SymType::Tls)? {
+                        sym.addr += phdr.p_vaddr;
+                        return Ok(Ok(sym))
+                    }
+                }
+            }
+        }
+
+        for cache in [symtab_cache, dynsym_cache] {
+            if let Some(sym) = cache.find_sym(addr, SymType::Undefined)? {
+                return Ok(Ok(sym))
+            }
+        }
+
+        if nearest_preceding {
+            let mut nearest = None::<ResolvedSym<'_>>;
+            for cache in [symtab_cache, dynsym_cache] {
+                if let Some(sym) =
+                    find_preceding_sym(cache.addr_syms(), cache.strs, cache.thumb, addr)?
+                {
+                    // On a tie, stick with the `.symtab` symbol.
+                    if nearest
+                        .as_ref()
+                        .map_or(true, |nearest| sym.addr > nearest.addr)
+                    {
+                        nearest = Some(sym);
+                    }
+                }
+            }
+            if let Some(sym) = nearest {
+                return Ok(Ok(sym))
+            }
         }
 
         // At this point we haven't found a symbol for the given
@@ -691,11 +2014,89 @@ impl ElfParser {
         let reason = if symtab_cache.syms.is_empty() {
             Reason::MissingSyms
         } else {
-            Reason::UnknownAddr
+            let bounds = [symtab_cache, dynsym_cache]
+                .into_iter()
+                .filter_map(SymbolTableCache::addr_bounds)
+                .reduce(|(start1, end1), (start2, end2)| (start1.min(start2), end1.max(end2)));
+            Reason::unknown_addr(addr, bounds)
         };
         Ok(Err(reason))
     }
 
+    /// Look up the symbol covering `addr` by scanning the symbol
+    /// tables, giving precedence to the same symbols an index based
+    /// lookup would.
+    fn scan_sym(&self, addr: Addr) -> Result<Option<ResolvedSym<'_>>> {
+        let tables = [(".symtab", ".strtab"), (".dynsym", ".dynstr")];
+
+        if let Some(phdr) = self.tls_segment()? {
+            if (phdr.p_vaddr..phdr.p_vaddr + phdr.p_memsz).contains(&addr) {
+                let offset = addr - phdr.p_vaddr;
+                for (syms, strs) in tables {
+                    if let Some(mut sym) = self.cache.scan_syms(syms, strs, offset, SymType::Tls)? {
+                        sym.addr += phdr.p_vaddr;
+                        return Ok(Some(sym))
+                    }
+                }
+            }
+        }
+
+        for (syms, strs) in tables {
+            if let Some(sym) = self.cache.scan_syms(syms, strs, addr, SymType::Undefined)? {
+                return Ok(Some(sym))
+            }
+        }
+        Ok(None)
+    }
+
+    /// Compute the load bias of the ELF file when loaded at
+    /// `actual_base_addr`.
+    ///
+    /// The load bias is the difference between the address at which
+    /// the file got loaded (e.g., as reported in `/proc/<pid>/maps`)
+    /// and its preferred base address, which is the lowest virtual
+    /// address of any loadable segment. Adding the load bias to a
+    /// virtual address as found in the file results in the
+    /// corresponding runtime address.
+    pub fn compute_load_bias(&self, actual_base_addr: Addr) -> Result<i64> {
+        let preferred_base_addr = self
+            .program_headers()?
+            .iter()
+            .filter(|phdr| phdr.p_type == PT_LOAD)
+            .map(|phdr| phdr.p_vaddr)
+            .min()
+            .ok_or_invalid_data(|| "ELF file does not contain any loadable segments")?;
+        Ok(actual_base_addr.wrapping_sub(preferred_base_addr) as i64)
+    }
+
+    /// Find the symbol covering the runtime address `runtime_addr` of
+    /// an ELF file loaded with the given load bias.
+    ///
+    /// This method is a convenience wrapper around
+    /// [`find_sym`][Self::find_sym] that removes the load bias (as
+    /// computed by [`compute_load_bias`][Self::compute_load_bias])
+    /// from the address before the lookup.
+    pub fn find_sym_at_runtime_addr(
+        &self,
+        runtime_addr: Addr,
+        load_bias: i64,
+        opts: &FindSymOpts,
+    ) -> Result<Result<ResolvedSym<'_>, Reason>> {
+        let addr = i128::from(runtime_addr) - i128::from(load_bias);
+        match Addr::try_from(addr) {
+            Ok(addr) => self.find_sym(addr, opts),
+            Err(_) => Ok(Err(Reason::UnknownAddr)),
+        }
+    }
+
+    /// Retrieve the program header of the thread-local storage
+    /// segment, if any.
+    fn tls_segment(&self) -> Result<Option<&Elf64_Phdr>> {
+        let phdrs = self.cache.ensure_phdrs()?;
+        let phdr = phdrs.iter().find(|phdr| phdr.p_type == PT_TLS);
+        Ok(phdr)
+    }
+
     /// Calculate the file offset of the given symbol.
     ///
     /// # Notes
@@ -704,6 +2105,18 @@ impl ElfParser {
     fn file_offset(&self, shdrs: &[Elf64_Shdr], sym: &Elf64_Sym) -> Result<u64> {
         debug_assert_ne!(sym.st_shndx, SHN_UNDEF);
 
+        if sym.matches(SymType::Tls) {
+            // The value of a thread-local symbol is an offset into the
+            // TLS initialization image.
+            let phdr = self.tls_segment()?.ok_or_invalid_data(|| {
+                format!(
+                    "thread-local symbol at offset {:#x} without TLS segment",
+                    sym.st_value
+                )
+            })?;
+            return Ok(phdr.p_offset + sym.st_value)
+        }
+
         let section = shdrs
             .get(usize::from(sym.st_shndx))
             .ok_or_invalid_input(|| {
@@ -712,17 +2125,70 @@ impl ElfParser {
                     sym.st_shndx, sym.st_value
                 )
             })?;
-        Ok(sym.st_value - section.sh_addr + section.sh_offset)
+        let addr = sym_addr(sym, self.cache.is_thumb()?);
+        Ok(addr - section.sh_addr + section.sh_offset)
+    }
+
+    /// Determine the version of the symbol `sym` at address `addr`,
+    /// as found in a symbol table with string table `strs`.
+    ///
+    /// The version encoded in the symbol's name takes precedence.
+    /// Otherwise, the version is looked up in `.gnu.version`, either
+    /// directly if `sym` is part of `.dynsym`, or by means of the
+    /// `.dynsym` symbol of the same name and address.
+    fn sym_version<'slf>(
+        &'slf self,
+        sym: &Elf64_Sym,
+        strs: &'slf [u8],
+        addr: Addr,
+    ) -> Result<Option<SymVersion<'slf>>> {
+        let (name, version) = split_version(symbol_name(strs, sym)?);
+        if version.is_some() {
+            return Ok(version)
+        }
+
+        let versions = self.cache.ensure_versions()?;
+        if let Some(version) = versions.version(sym) {
+            return Ok(Some(version))
+        }
+        self.dynsym_version(name, addr)
+    }
+
+    /// Look up the version of the `.dynsym` symbol with the given name
+    /// and address, if any.
+    fn dynsym_version(&self, name: &str, addr: Addr) -> Result<Option<SymVersion<'_>>> {
+        let versions = self.cache.ensure_versions()?;
+        if versions.versyms.is_empty() {
+            return Ok(None)
+        }
+
+        let dynsym = self.cache.ensure_dynsym_cache()?;
+        let syms = dynsym.addr_syms();
+        let thumb = dynsym.thumb;
+        if let Some(idx) = find_match_or_lower_bound_by_key(syms, addr, |sym| sym_addr(sym, thumb))
+        {
+            for sym in &syms[idx..] {
+                if sym_addr(sym, thumb) != addr {
+                    break
+                }
+                if symbol_name(dynsym.strs, sym)? == name {
+                    return Ok(versions.version(sym))
+                }
+            }
+        }
+        Ok(None)
     }
 
     fn find_addr_impl<'slf>(
         &'slf self,
         name: &str,
+        version: Option<&SymVersion<'_>>,
         opts: &FindAddrOpts,
         shdrs: &'slf [Elf64_Shdr],
-        syms: &[&'slf Elf64_Sym],
+        cache: &'slf SymbolTableCache<'_>,
         str2sym: &'slf [(&'slf str, usize)],
     ) -> Result<Vec<SymInfo<'slf>>> {
+        let thumb = self.cache.is_thumb()?;
         let r = find_match_or_lower_bound_by_key(str2sym, name, |&(name, _i)| name);
         match r {
             Some(idx) => {
@@ -731,18 +2197,37 @@ impl ElfParser {
                     if *name_visit != name {
                         break
                     }
-                    let sym_ref = &syms.get(*sym_i).ok_or_invalid_input(|| {
+                    let sym_ref = &cache.syms.get(*sym_i).ok_or_invalid_input(|| {
                         format!("symbol table index ({sym_i}) out of bounds")
                     })?;
-                    if sym_ref.st_shndx != SHN_UNDEF {
+                    if sym_ref.st_shndx != SHN_UNDEF && sym_ref.matches_binding(opts.binding) {
+                        let addr = sym_addr(sym_ref, thumb);
+                        let sym_version = self.sym_version(sym_ref, cache.strs, addr)?;
+                        if let Some(version) = version {
+                            // A query for `name@version` matches any
+                            // definition of the given version, while one
+                            // for `name@@version` only matches the default
+                            // one.
+                            let matches = sym_version.as_ref().map(|sym_version| {
+                                sym_version.name == version.name
+                                    && (version.hidden || !sym_version.hidden)
+                            });
+                            if matches != Some(true) {
+                                continue
+                            }
+                        }
+
                         found.push(SymInfo {
                             name: Cow::Borrowed(name_visit),
-                            addr: sym_ref.st_value as Addr,
+                            addr,
                             size: sym_ref.st_size as usize,
                             // SANITY: We filter out all unsupported symbol
                             //         types, so this conversion should always
                             //         succeed.
                             sym_type: SymType::try_from(**sym_ref).unwrap(),
+                            binding: SymBinding::try_from(**sym_ref).ok(),
+                            version: sym_version,
+                            is_ifunc: sym_ref.is_ifunc(),
                             file_offset: opts
                                 .offset_in_file
                                 .then(|| self.file_offset(shdrs, sym_ref))
@@ -757,29 +2242,35 @@ impl ElfParser {
         }
     }
 
+    /// Find information about the symbol(s) with the given name.
+    ///
+    /// `name` may refer to a specific symbol version, using the
+    /// `name@version` (any definition of the given version) or
+    /// `name@@version` (only the default definition) notation.
     pub(crate) fn find_addr<'slf>(
         &'slf self,
         name: &str,
         opts: &FindAddrOpts,
     ) -> Result<Vec<SymInfo<'slf>>> {
+        let (name, version) = split_version(name);
         let shdrs = self.cache.ensure_shdrs()?;
-        let symtab = self.cache.ensure_symtab()?;
+        let symtab = self.cache.ensure_symtab_cache()?;
         let str2symtab = self.cache.ensure_str2symtab()?;
-        let syms = self.find_addr_impl(name, opts, shdrs, symtab, str2symtab)?;
+        let syms = self.find_addr_impl(name, version.as_ref(), opts, shdrs, symtab, str2symtab)?;
         if !syms.is_empty() {
             return Ok(syms)
         }
 
-        let dynsym = self.cache.ensure_dynsym()?;
+        let dynsym = self.cache.ensure_dynsym_cache()?;
         let str2dynsym = self.cache.ensure_str2dynsym()?;
-        let syms = self.find_addr_impl(name, opts, shdrs, dynsym, str2dynsym)?;
+        let syms = self.find_addr_impl(name, version.as_ref(), opts, shdrs, dynsym, str2dynsym)?;
         Ok(syms)
     }
 
     fn for_each_sym_impl<F>(
         &self,
         opts: &FindAddrOpts,
-        syms: &[&Elf64_Sym],
+        cache: &SymbolTableCache<'_>,
         str2sym: &[(&str, usize)],
         mut f: F,
     ) -> Result<()>
@@ -787,20 +2278,29 @@ impl ElfParser {
         F: FnMut(&SymInfo<'_>),
     {
         let shdrs = self.cache.ensure_shdrs()?;
+        let thumb = self.cache.is_thumb()?;
 
         for (name, idx) in str2sym {
-            let sym = &syms
+            let sym = &cache
+                .syms
                 .get(*idx)
                 .ok_or_invalid_input(|| format!("symbol table index ({idx}) out of bounds"))?;
-            if sym.matches(opts.sym_type) && sym.st_shndx != SHN_UNDEF {
+            if sym.matches(opts.sym_type)
+                && sym.matches_binding(opts.binding)
+                && sym.st_shndx != SHN_UNDEF
+            {
+                let addr = sym_addr(sym, thumb);
                 let sym_info = SymInfo {
                     name: Cow::Borrowed(name),
-                    addr: sym.st_value as Addr,
+                    addr,
                     size: sym.st_size as usize,
                     // SANITY: We filter out all unsupported symbol
                     //         types, so this conversion should always
                     //         succeed.
                     sym_type: SymType::try_from(**sym).unwrap(),
+                    binding: SymBinding::try_from(**sym).ok(),
+                    version: self.sym_version(sym, cache.strs, addr)?,
+                    is_ifunc: sym.is_ifunc(),
                     file_offset: opts
                         .offset_in_file
                         .then(|| self.file_offset(shdrs, sym))
@@ -820,31 +2320,64 @@ impl ElfParser {
         opts: &FindAddrOpts,
         mut f: &mut dyn FnMut(&SymInfo<'_>),
     ) -> Result<()> {
-        let symtab = self.cache.ensure_symtab()?;
+        let symtab = self.cache.ensure_symtab_cache()?;
         let str2symtab = self.cache.ensure_str2symtab()?;
         let () = self.for_each_sym_impl(opts, symtab, str2symtab, &mut f)?;
 
-        let dynsym = self.cache.ensure_dynsym()?;
+        let dynsym = self.cache.ensure_dynsym_cache()?;
         let str2dynsym = self.cache.ensure_str2dynsym()?;
         let () = self.for_each_sym_impl(opts, dynsym, str2dynsym, &mut f)?;
 
         Ok(())
     }
 
+    /// Retrieve all weak (`STB_WEAK`) function and variable symbols
+    /// defined in the ELF file.
+    ///
+    /// Symbols are reported in implementation defined order that should
+    /// not be relied on.
+    pub fn weak_symbols(&self) -> Result<Vec<SymInfo<'_>>> {
+        let opts = FindAddrOpts {
+            offset_in_file: true,
+            sym_type: SymType::Undefined,
+            binding: Some(SymBinding::Weak),
+        };
+        let mut syms = Vec::new();
+        let () = self.for_each(&opts, &mut |sym| syms.push(sym.to_owned()))?;
+        Ok(syms)
+    }
+
     /// Find the file offset of the symbol at address `addr`.
     // If possible, use the constant-time [`file_offset`][Self::file_offset]
     // method instead.
     pub(crate) fn find_file_offset(&self, addr: Addr) -> Result<Option<u64>> {
+        let offset = self
+            .segment_for_addr(addr)?
+            .map(|phdr| addr - phdr.p_vaddr + phdr.p_offset);
+        Ok(offset)
+    }
+
+    /// Find the loadable segment (i.e., the `PT_LOAD` program header)
+    /// containing the virtual address `vaddr`, if any.
+    pub fn segment_for_addr(&self, vaddr: Addr) -> Result<Option<&Elf64_Phdr>> {
         let phdrs = self.program_headers()?;
-        let offset = phdrs.iter().find_map(|phdr| {
-            if phdr.p_type == PT_LOAD {
-                if (phdr.p_vaddr..phdr.p_vaddr + phdr.p_memsz).contains(&addr) {
-                    return Some(addr - phdr.p_vaddr + phdr.p_offset)
-                }
-            }
-            None
+        let phdr = phdrs.iter().find(|phdr| {
+            phdr.p_type == PT_LOAD && (phdr.p_vaddr..phdr.p_vaddr + phdr.p_memsz).contains(&vaddr)
         });
-        Ok(offset)
+        Ok(phdr)
+    }
+
+    /// Check whether the virtual address `vaddr` lies in an executable
+    /// loadable segment.
+    ///
+    /// Addresses not covered by any loadable segment are reported as not
+    /// executable.
+    pub fn is_executable(&self, vaddr: Addr) -> Result<bool> {
+        let exec = self
+            .segment_for_addr(vaddr)?
+            .map(|phdr| phdr.p_flags & PF_X != 0)
+            .unwrap_or(false);
+        Ok(exec)
     }
 
     #[cfg(test)]
@@ -865,6 +2398,163 @@ impl ElfParser {
         Ok(phdrs)
     }
 
+    /// Retrieve the machine architecture the ELF file is targeting.
+    ///
+    /// The returned value is one of the `EM_*` constants as defined by
+    /// the ELF specification (e.g., `62` for x86-64 and `183` for
+    /// AArch64).
+    pub fn machine(&self) -> Result<u16> {
+        let ehdr = self.cache.ensure_ehdr()?;
+        Ok(ehdr.ehdr.e_machine)
+    }
+
+    /// Check whether the ELF file stores data in big-endian byte order.
+    pub(crate) fn is_big_endian(&self) -> Result<bool> {
+        let ehdr = self.cache.ensure_ehdr()?;
+        Ok(ehdr.ehdr.e_ident[EI_DATA] == ELFDATA2MSB)
+    }
+
+    /// Retrieve the size of an address, in bytes, of the target the ELF
+    /// file is meant for.
+    pub(crate) fn address_size(&self) -> Result<u8> {
+        let ehdr = self.cache.ensure_ehdr()?;
+        let size = if ehdr.ehdr.e_ident[EI_CLASS] == ELFCLASS32 {
+            4
+        } else {
+            8
+        };
+        Ok(size)
+    }
+
+    /// Retrieve the ELF file type (`ET_*`).
+    pub(crate) fn file_type(&self) -> Result<u16> {
+        let ehdr = self.cache.ensure_ehdr()?;
+        Ok(ehdr.ehdr.e_type)
+    }
+
+    /// Read the DWARF version from the header of the first unit
+    /// contained in the provided `.debug_info` section data.
+    fn dwarf_version(&self, mut data: &[u8]) -> Result<Option<u16>> {
+        // Section contents are never converted to the host's byte
+        // order, so we may have to do that here.
+        let swap = self.is_big_endian()? != cfg!(target_endian = "big");
+        let length = match data.read_u32() {
+            Some(length) if swap => length.swap_bytes(),
+            Some(length) => length,
+            None => return Ok(None),
+        };
+        // A length of 0xffffffff indicates the 64 bit DWARF format, in
+        // which case the actual length follows.
+        if length == 0xffffffff && data.read_u64().is_none() {
+            return Ok(None)
+        }
+        let version = data
+            .read_u16()
+            .map(|version| if swap { version.swap_bytes() } else { version });
+        Ok(version)
+    }
+
+    /// Find the section of a given name, provided that it has data in
+    /// the file.
+    ///
+    /// Sections without data, such as those left behind by
+    /// `objcopy --only-keep-debug` or `strip`, are treated as absent.
+    fn find_non_empty_section(&self, name: &str) -> Result<Option<(usize, &Elf64_Shdr)>> {
+        let shdrs = self.cache.ensure_shdrs()?;
+        let shdr = self
+            .find_section(name)?
+            .and_then(|idx| Some((idx, shdrs.get(idx)?)))
+            .filter(|(_idx, shdr)| shdr.sh_type != SHT_NOBITS && shdr.sh_size != 0);
+        Ok(shdr)
+    }
+
+    /// Check whether the file contains a (non-empty) `.symtab` section.
+    pub fn has_symbols(&self) -> Result<bool> {
+        let symtab = self.find_non_empty_section(".symtab")?;
+        Ok(symtab.is_some())
+    }
+
+    /// Check whether the file contains DWARF debug information, i.e., a
+    /// (non-empty) `.debug_info` section.
+    pub fn has_debug_info(&self) -> Result<bool> {
+        let debug_info = self.find_non_empty_section(".debug_info")?;
+        Ok(debug_info.is_some())
+    }
+
+    /// Check whether the file is stripped, i.e., contains neither a
+    /// `.symtab` section nor DWARF debug information.
+    ///
+    /// Note that a stripped file may still contain dynamic symbols in
+    /// its `.dynsym` section.
+    pub fn is_stripped(&self) -> Result<bool> {
+        Ok(!self.has_symbols()? && !self.has_debug_info()?)
+    }
+
+    /// Gather information about the kinds of symbols and debug
+    /// information present in the file.
+    ///
+    /// Only section headers and the very first DWARF unit header are
+    /// looked at. [`DebugInfo::gsym`] is not filled in, as it does not
+    /// relate to the ELF file itself.
+    pub(crate) fn debug_info(&self) -> Result<DebugInfo> {
+        let section = |name| self.find_non_empty_section(name);
+
+        let (dwarf_version, dwarf_compression) = match section(".debug_info")? {
+            Some((idx, shdr)) => {
+                let compression = if shdr.sh_flags & SHF_COMPRESSED != 0 {
+                    let (_shdr, mut data) = self.cache.section_data_raw(idx)?;
+                    let chdr = data
+                        .read_pod_ref::<Elf64_Chdr>()
+                        .ok_or_invalid_data(|| "failed to read ELF compression header")?;
+                    let compression = match chdr.ch_type {
+                        ELFCOMPRESS_ZLIB => DebugCompression::Zlib,
+                        ELFCOMPRESS_ZSTD => DebugCompression::Zstd,
+                        _ => DebugCompression::Unknown,
+                    };
+                    Some(compression)
+                } else {
+                    None
+                };
+
+                // Decompression of the section may not be supported
+                // (e.g., because the corresponding feature is
+                // disabled), in which case we just can't report the
+                // version.
+                let version = match self.section_data(idx) {
+                    Ok(data) => self.dwarf_version(data)?,
+                    Err(_err) => None,
+                };
+                (version, compression)
+            }
+            None => (None, None),
+        };
+
+        let info = DebugInfo {
+            symtab: section(".symtab")?.is_some(),
+            dynsym: section(".dynsym")?.is_some(),
+            dwarf_version,
+            dwarf_compression,
+            debug_link: section(".gnu_debuglink")?.is_some(),
+            mini_debug_info: section(".gnu_debugdata")?.is_some(),
+            gsym: false,
+            _non_exhaustive: (),
+        };
+        Ok(info)
+    }
+
+    /// Retrieve the file data of the provided segment.
+    pub(crate) fn segment_data(&self, phdr: &Elf64_Phdr) -> Result<&[u8]> {
+        let size = usize::try_from(phdr.p_filesz)
+            .ok()
+            .ok_or_invalid_data(|| "failed to read segment data: invalid size")?;
+        let data = self
+            .cache
+            .elf_data
+            .get(phdr.p_offset, size)?
+            .ok_or_invalid_data(|| "failed to read segment data: invalid offset or size")?;
+        Ok(data)
+    }
+
     /// Translate a file offset into a virtual offset.
     pub(crate) fn file_offset_to_virt_offset(&self, offset: u64) -> Result<Option<Addr>> {
         let phdrs = self.program_headers()?;
@@ -905,6 +2595,14 @@ impl ElfParser {
     pub fn path(&self) -> &Path {
         &self.path
     }
+
+    /// Retrieve the identity of the file this object operates on, if
+    /// it was created for a file on disk.
+    #[cfg_attr(not(feature = "dwarf"), allow(dead_code))]
+    #[inline]
+    pub(crate) fn file_id(&self) -> Option<&FileId> {
+        self.file_id.as_ref()
+    }
 }
 
 
@@ -912,11 +2610,13 @@ impl ElfParser {
 mod tests {
     use super::*;
 
+    use super::super::types::SHF_EXECINSTR;
     use super::super::types::SHN_LORESERVE;
+    use super::super::types::SHT_PROGBITS;
+    use super::super::types::STT_FUNC;
 
     use std::env;
     use std::env::current_exe;
-    use std::io::Seek as _;
     use std::io::Write as _;
     use std::mem::size_of;
     use std::slice;
@@ -925,6 +2625,120 @@ mod tests {
 
     use test_log::test;
 
+    use crate::testing::make_elf_bytes;
+    use crate::testing::make_elf_bytes_with_sections;
+
+
+    /// Check that we can parse ELF data residing in memory, using the
+    /// members of a zip archive.
+    #[cfg(feature = "apk")]
+    #[test]
+    fn elf_from_bytes() {
+        use crate::zip::Archive;
+
+        let data = Path::new(&env!("CARGO_MANIFEST_DIR")).join("data");
+        let archive = Archive::open(data.join("test.zip")).unwrap();
+        let mut count = 0;
+        for entry in archive.entries() {
+            let entry = entry.unwrap();
+            let name = entry.path.to_str().unwrap();
+            let parser = ElfParser::open_from_bytes(entry.data, name).unwrap();
+            assert_eq!(parser.path(), entry.path);
+
+            let file = data.join(entry.path.file_name().unwrap());
+            let expected = ElfParser::open(&file).unwrap();
+            let summarize = |parser: &ElfParser| {
+                parser
+                    .section_headers()
+                    .unwrap()
+                    .iter()
+                    .map(|shdr| (shdr.sh_name, shdr.sh_addr, shdr.sh_size))
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(summarize(&parser), summarize(&expected));
+            assert_eq!(
+                parser.find_section(".text").unwrap(),
+                expected.find_section(".text").unwrap()
+            );
+            count += 1;
+        }
+        assert_eq!(count, 4);
+
+        let parser = ElfParser::open_from_bytes(b"not an ELF file", "garbage").unwrap();
+        assert!(parser.section_headers().is_err());
+    }
+
+    /// Check that we can parse ELF data provided by a reader, reading
+    /// only what is needed.
+    #[test]
+    fn elf_from_reader() {
+        use std::cell::Cell;
+        use std::io;
+        use std::io::Cursor;
+        use std::io::SeekFrom;
+        use std::rc::Rc;
+
+        /// A reader keeping track of the number of bytes read.
+        struct CountingReader {
+            file: File,
+            count: Rc<Cell<usize>>,
+        }
+
+        impl Read for CountingReader {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                let count = self.file.read(buf)?;
+                let () = self.count.set(self.count.get() + count);
+                Ok(count)
+            }
+        }
+
+        impl Seek for CountingReader {
+            fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+                self.file.seek(pos)
+            }
+        }
+
+        let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join("test-stable-addrs.bin");
+        let count = Rc::new(Cell::new(0));
+        let reader = CountingReader {
+            file: File::open(&path).unwrap(),
+            count: count.clone(),
+        };
+        let parser = ElfParser::from_reader(reader, &path).unwrap();
+        assert_eq!(parser.path(), path);
+
+        let expected = ElfParser::open(&path).unwrap();
+        let opts = FindAddrOpts {
+            offset_in_file: true,
+            ..Default::default()
+        };
+        let syms = parser.find_addr("factorial", &opts).unwrap();
+        assert_eq!(syms, expected.find_addr("factorial", &opts).unwrap());
+        assert_eq!(syms.len(), 1);
+        let sym = parser
+            .find_sym(syms[0].addr, &FindSymOpts::BASIC)
+            .unwrap()
+            .unwrap();
+        assert_eq!(sym.name, "factorial");
+
+        // Debug information should not have been read.
+        let len = path.metadata().unwrap().len() as usize;
+        assert!(count.get() < len, "{} >= {len}", count.get());
+        assert!(parser.has_debug_info().unwrap());
+
+        // 32 bit files are converted up front, but should work as well.
+        let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join("libtest-so-32.so");
+        let parser = ElfParser::from_reader(File::open(&path).unwrap(), &path).unwrap();
+        let syms = parser.find_addr("the_answer", &opts).unwrap();
+        assert_eq!(syms.len(), 1);
+
+        let parser = ElfParser::from_reader(Cursor::new(b"not an ELF file"), "garbage").unwrap();
+        assert!(parser.section_headers().is_err());
+    }
 
     /// Exercise the `Debug` representation of various types.
     #[test]
@@ -1111,7 +2925,7 @@ mod tests {
 
         let (name, addr, size) = parser.pick_symtab_addr();
 
-        let sym = parser.find_sym(addr, &FindSymOpts::Basic).unwrap().unwrap();
+        let sym = parser.find_sym(addr, &FindSymOpts::BASIC).unwrap().unwrap();
         assert_eq!(sym.addr, addr);
         assert_eq!(sym.name, name);
         assert_eq!(sym.size, Some(size));
@@ -1143,6 +2957,7 @@ mod tests {
         let opts = FindAddrOpts {
             offset_in_file: true,
             sym_type: SymType::Function,
+            binding: None,
         };
         let parser = ElfParser::open(bin_name.as_ref()).unwrap();
         let () = parser
@@ -1175,6 +2990,822 @@ mod tests {
         assert_ne!(syms[0].addr, syms[1].addr);
     }
 
+    /// Check that we can enumerate weak symbols and filter symbols by
+    /// their binding.
+    #[test]
+    fn weak_symbol_lookup() {
+        let so = Path::new(&env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join("libtest-so.so");
+
+        let parser = ElfParser::open(so.as_ref()).unwrap();
+        let syms = parser.weak_symbols().unwrap();
+        let sym = syms
+            .iter()
+            .find(|sym| sym.name == "the_weak_answer")
+            .unwrap();
+        assert_eq!(sym.binding, Some(SymBinding::Weak));
+        assert_eq!(sym.sym_type, SymType::Function);
+        assert!(syms.iter().all(|sym| sym.binding == Some(SymBinding::Weak)));
+        assert!(!syms.iter().any(|sym| sym.name == "the_answer"));
+
+        let opts = FindAddrOpts::default();
+        let syms = parser.find_addr("the_answer", &opts).unwrap();
+        assert_eq!(syms.len(), 1);
+        assert_eq!(syms[0].binding, Some(SymBinding::Global));
+
+        let opts = FindAddrOpts {
+            binding: Some(SymBinding::Weak),
+            ..Default::default()
+        };
+        let syms = parser.find_addr("the_answer", &opts).unwrap();
+        assert!(syms.is_empty(), "{syms:?}");
+        let syms = parser.find_addr("the_weak_answer", &opts).unwrap();
+        assert_eq!(syms.len(), 1);
+    }
+
+    /// Check that we can look up symbols by runtime address after
+    /// applying a load bias.
+    #[test]
+    fn runtime_addr_lookup() {
+        let so = Path::new(&env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join("libtest-so.so");
+        let parser = ElfParser::open(so.as_ref()).unwrap();
+        let opts = FindAddrOpts::default();
+        let syms = parser.find_addr("the_answer", &opts).unwrap();
+        let addr = syms[0].addr;
+
+        // Shared objects are linked at a preferred base address of
+        // zero.
+        let base = 0x7f0000000000;
+        let bias = parser.compute_load_bias(base).unwrap();
+        assert_eq!(bias, base as i64);
+
+        let sym = parser
+            .find_sym_at_runtime_addr(base + addr, bias, &FindSymOpts::BASIC)
+            .unwrap()
+            .unwrap();
+        assert_eq!(sym.name, "the_answer");
+        assert_eq!(sym.addr, addr);
+
+        // A negative bias is applied correctly as well.
+        let bias = parser.compute_load_bias(0).unwrap();
+        assert_eq!(bias, 0);
+        let sym = parser
+            .find_sym_at_runtime_addr(addr - 0x10, -0x10, &FindSymOpts::BASIC)
+            .unwrap()
+            .unwrap();
+        assert_eq!(sym.name, "the_answer");
+
+        let result = parser
+            .find_sym_at_runtime_addr(0x10, 0x20, &FindSymOpts::BASIC)
+            .unwrap();
+        assert_eq!(result, Err(Reason::UnknownAddr));
+    }
+
+    /// Check that we can find the segment containing an address and
+    /// determine whether it is executable.
+    #[test]
+    fn segment_lookup() {
+        let bin_name = Path::new(&env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join("test-stable-addrs-no-dwarf.bin");
+
+        let parser = ElfParser::open(bin_name.as_ref()).unwrap();
+        // `factorial` resides at address 0x2000100, inside `.text`.
+        let phdr = parser.segment_for_addr(0x2000100).unwrap().unwrap();
+        assert_eq!(phdr.p_type, PT_LOAD);
+        assert!((phdr.p_vaddr..phdr.p_vaddr + phdr.p_memsz).contains(&0x2000100));
+        assert!(parser.is_executable(0x2000100).unwrap());
+
+        // `a_variable` resides at address 0x2001100, inside `.data`.
+        let phdr = parser.segment_for_addr(0x2001100).unwrap().unwrap();
+        assert_eq!(phdr.p_flags & PF_X, 0);
+        assert!(!parser.is_executable(0x2001100).unwrap());
+
+        // An address not covered by any segment.
+        assert!(parser.segment_for_addr(0xffffffff).unwrap().is_none());
+        assert!(!parser.is_executable(0xffffffff).unwrap());
+    }
+
+    /// Check that we can retrieve dependency information from the
+    /// `.dynamic` section.
+    #[test]
+    fn dynamic_section_parsing() {
+        let data = Path::new(&env!("CARGO_MANIFEST_DIR")).join("data");
+
+        let parser = ElfParser::open(&data.join("libtest-so.so")).unwrap();
+        assert_eq!(parser.needed_libs().unwrap(), vec!["libc.so.6"]);
+        assert_eq!(parser.soname().unwrap(), None);
+        assert_eq!(parser.rpath().unwrap(), Vec::<PathBuf>::new());
+        assert_eq!(parser.runpath().unwrap(), Vec::<PathBuf>::new());
+
+        let paths = vec![PathBuf::from("/opt/lib"), PathBuf::from("$ORIGIN/lib")];
+        let parser = ElfParser::open(&data.join("libtest-so-rpath.so")).unwrap();
+        assert_eq!(parser.rpath().unwrap(), paths);
+        assert_eq!(parser.runpath().unwrap(), Vec::<PathBuf>::new());
+
+        let parser = ElfParser::open(&data.join("libtest-so-runpath.so")).unwrap();
+        assert_eq!(parser.rpath().unwrap(), Vec::<PathBuf>::new());
+        assert_eq!(parser.runpath().unwrap(), paths);
+
+        let parser = ElfParser::open(&data.join("libtest-so-soname.so")).unwrap();
+        assert_eq!(parser.soname().unwrap().as_deref(), Some("libtest-so.so.1"));
+
+        // A binary linked without any libraries does not have any
+        // dependencies.
+        let parser = ElfParser::open(&data.join("test-stable-addrs.bin")).unwrap();
+        assert_eq!(parser.needed_libs().unwrap(), Vec::<String>::new());
+    }
+
+    /// Check that we can calculate the false positive rate of the
+    /// `.gnu.hash` Bloom filter.
+    #[test]
+    fn gnu_hash_bloom_rate() {
+        let data = Path::new(&env!("CARGO_MANIFEST_DIR")).join("data");
+
+        for file in ["libtest-so.so", "libtest-so-32.so"] {
+            let parser = ElfParser::open(&data.join(file)).unwrap();
+            let rate = parser
+                .gnu_hash_bloom_false_positive_rate()
+                .unwrap()
+                .unwrap();
+            assert!((0.0..0.01).contains(&rate), "{file}: {rate}");
+
+            // The Bloom filter must not reject any of the symbols
+            // actually covered by the table.
+            let idx = parser.find_section(".gnu.hash").unwrap().unwrap();
+            let data = parser.section_data(idx).unwrap();
+            let table = GnuHashTable::parse(data, parser.address_size().unwrap()).unwrap();
+            let names = parser.raw_sym_names(".dynsym").unwrap();
+            assert!(names.len() > table.symoffset(), "{file}");
+            for name in &names[table.symoffset()..] {
+                assert!(table.bloom_may_contain(gnu_hash(name)), "{file}");
+            }
+        }
+
+        // A statically linked binary has no `.gnu.hash` section.
+        let parser = ElfParser::open(&data.join("test-stable-addrs.bin")).unwrap();
+        assert_eq!(parser.gnu_hash_bloom_false_positive_rate().unwrap(), None);
+    }
+
+    /// Check that we can retrieve section data by section name.
+    #[test]
+    fn section_data_by_name() {
+        let data = Path::new(&env!("CARGO_MANIFEST_DIR")).join("data");
+        let parser = ElfParser::open(&data.join("test-stable-addrs.bin")).unwrap();
+        let debug_info = parser.find_section_data(".debug_info").unwrap().unwrap();
+        assert!(!debug_info.is_empty());
+
+        let idx = parser.find_section(".debug_info").unwrap().unwrap();
+        let data2 = parser.section_data(idx).unwrap();
+        assert_eq!(debug_info.as_ptr(), data2.as_ptr());
+        assert_eq!(debug_info.len(), data2.len());
+
+        assert_eq!(parser.find_section_data(".does-not-exist").unwrap(), None);
+
+        // Compressed sections are transparently decompressed.
+        #[cfg(feature = "zlib")]
+        {
+            let path = data.join("test-stable-addrs-compressed-debug-zlib.bin");
+            let parser = ElfParser::open(&path).unwrap();
+            let debug_info = parser.find_section_data(".debug_info").unwrap().unwrap();
+            // The first compilation unit header should carry the
+            // expected DWARF version if data got decompressed.
+            assert_eq!(debug_info[4..6], 4u16.to_le_bytes());
+        }
+    }
+
+    /// Check that we correctly detect whether an ELF file carries
+    /// symbols and debug information.
+    #[test]
+    fn stripped_detection() {
+        #[track_caller]
+        fn test(file: &str, symbols: bool, debug_info: bool) {
+            let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+                .join("data")
+                .join(file);
+            let parser = ElfParser::open(&path).unwrap();
+            assert_eq!(parser.has_symbols().unwrap(), symbols);
+            assert_eq!(parser.has_debug_info().unwrap(), debug_info);
+            assert_eq!(parser.is_stripped().unwrap(), !symbols && !debug_info);
+        }
+
+        test("test-stable-addrs.bin", true, true);
+        test("test-stable-addrs-no-dwarf.bin", true, false);
+        test("test-stable-addrs-stripped-elf-with-dwarf.bin", false, true);
+        test("test-stable-addrs-stripped.bin", false, false);
+    }
+
+    /// Check that we report the correct machine architecture of an ELF
+    /// file.
+    #[test]
+    #[cfg(target_arch = "x86_64")]
+    fn machine_reporting() {
+        let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join("libtest-so.so");
+        let parser = ElfParser::open(&path).unwrap();
+        assert_eq!(parser.machine().unwrap(), super::super::types::EM_X86_64);
+    }
+
+    /// Check that we can parse ELF files of foreign byte order.
+    #[test]
+    #[cfg(target_endian = "little")]
+    fn big_endian_parsing() {
+        let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join("test-big-endian.o");
+        let parser = ElfParser::open(&path).unwrap();
+        assert!(parser.is_big_endian().unwrap());
+        assert_eq!(parser.machine().unwrap(), 22);
+
+        let sym = parser.find_sym(0x8, &FindSymOpts::BASIC).unwrap().unwrap();
+        assert_eq!(sym.name, "main");
+        assert_eq!(sym.addr, 0x6);
+        assert_eq!(sym.size, Some(6));
+
+        let opts = FindAddrOpts::default();
+        let syms = parser.find_addr("factorial", &opts).unwrap();
+        assert_eq!(syms.len(), 1);
+        assert_eq!(syms[0].addr, 0x0);
+        assert_eq!(syms[0].size, 6);
+    }
+
+    /// Check that we can look up symbols in a synthesized ELF file.
+    #[test]
+    fn synthesized_elf_lookup() {
+        let data = make_elf_bytes(&[
+            ("foo", 0x1000, 0x10),
+            ("bar", 0x1010, 0x20),
+            ("baz", 0x1010, 0x8),
+            ("dup", 0x2000, 0x4),
+            ("dup", 0x3000, 0x4),
+        ]);
+        let mmap = Mmap::builder().map_data(&data).unwrap();
+        let parser = ElfParser::from_mmap(mmap, "<synthesized>");
+
+        let sym = parser
+            .find_sym(0x100f, &FindSymOpts::BASIC)
+            .unwrap()
+            .unwrap();
+        assert_eq!(sym.name, "foo");
+        assert_eq!(sym.addr, 0x1000);
+        assert_eq!(sym.size, Some(0x10));
+
+        // Of two symbols at the same address, the smaller one is
+        // reported if it covers the address.
+        let sym = parser
+            .find_sym(0x1014, &FindSymOpts::BASIC)
+            .unwrap()
+            .unwrap();
+        assert_eq!(sym.name, "baz");
+        let sym = parser
+            .find_sym(0x1018, &FindSymOpts::BASIC)
+            .unwrap()
+            .unwrap();
+        assert_eq!(sym.name, "bar");
+
+        let err = parser
+            .find_sym(0x1030, &FindSymOpts::BASIC)
+            .unwrap()
+            .unwrap_err();
+        assert_eq!(err, Reason::BetweenSyms);
+
+        let opts = FindAddrOpts::default();
+        let syms = parser.find_addr("baz", &opts).unwrap();
+        assert_eq!(syms.len(), 1);
+        assert_eq!(syms[0].addr, 0x1010);
+        assert_eq!(syms[0].size, 0x8);
+        assert_eq!(syms[0].sym_type, SymType::Function);
+
+        let mut addrs = parser
+            .find_addr("dup", &opts)
+            .unwrap()
+            .into_iter()
+            .map(|sym| sym.addr)
+            .collect::<Vec<_>>();
+        let () = addrs.sort();
+        assert_eq!(addrs, vec![0x2000, 0x3000]);
+        assert!(parser.find_addr("qux", &opts).unwrap().is_empty());
+
+        let mut names = Vec::new();
+        let () = parser
+            .for_each(&opts, &mut |sym| names.push(sym.name.to_string()))
+            .unwrap();
+        let () = names.sort();
+        assert_eq!(names, vec!["bar", "baz", "dup", "dup", "foo"]);
+    }
+
+    /// Check that we pick the expected symbol for addresses covered by
+    /// overlapping, duplicate, or zero-sized symbols.
+    #[test]
+    fn overlapping_sym_selection() {
+        let strtab = b"\0outer\0inner\0weak\0global\0zero1\0zero2\0";
+        let sym = |st_name, binding, st_value, st_size| Elf64_Sym {
+            st_name,
+            st_info: (binding << 4) | STT_FUNC,
+            st_other: 0,
+            st_shndx: 1,
+            st_value,
+            st_size,
+        };
+        // Symbols sorted in the same way we do when parsing them.
+        let syms = [
+            sym(1, STB_GLOBAL, 0x1000, 0x100),
+            sym(7, STB_GLOBAL, 0x1040, 0x20),
+            sym(13, STB_WEAK, 0x1080, 0x10),
+            sym(18, STB_GLOBAL, 0x1080, 0x10),
+            sym(25, STB_LOCAL, 0x2000, 0),
+            sym(31, STB_GLOBAL, 0x2000, 0),
+        ];
+        let syms = syms.iter().collect::<Vec<_>>();
+        let max_ends = max_sym_ends(&syms, false).collect::<Vec<_>>();
+        let find = |addr| {
+            find_sym(&syms, &max_ends, strtab, false, addr, SymType::Undefined)
+                .unwrap()
+                .map(|sym| sym.name)
+        };
+
+        assert_eq!(find(0xfff), None);
+        assert_eq!(find(0x1010), Some("outer"));
+        // The smallest symbol covering an address wins.
+        assert_eq!(find(0x1048), Some("inner"));
+        assert_eq!(find(0x1060), Some("outer"));
+        // Of symbols with equal ranges, the one with the strongest
+        // binding wins.
+        assert_eq!(find(0x1084), Some("global"));
+        assert_eq!(find(0x10ff), Some("outer"));
+        assert_eq!(find(0x1100), None);
+        // The same holds for zero-sized symbols, which are only
+        // considered if they are the closest preceding ones.
+        assert_eq!(find(0x2000), Some("zero2"));
+        assert_eq!(find(0x2345), Some("zero2"));
+
+        // Scanning the unsorted symbols should yield the same results.
+        for addr in (0xff0..0x2400).step_by(4) {
+            let scanned = scan_sym(
+                syms.iter().rev().copied(),
+                strtab,
+                false,
+                addr,
+                SymType::Undefined,
+            )
+            .unwrap()
+            .map(|sym| sym.name);
+            assert_eq!(scanned, find(addr), "{addr:#x}");
+        }
+    }
+
+    /// Check that lazy symbol table indexing reports the same symbols
+    /// as eager indexing, without building an index for lookups of
+    /// covered addresses.
+    #[test]
+    fn lazy_symbol_table_indexing() {
+        let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join("test-stable-addrs.bin");
+        let eager = ElfParser::open(&path).unwrap();
+        let mut lazy = ElfParser::open(&path).unwrap();
+        let () = lazy.set_symbol_table_indexing(SymbolTableIndexing::Lazy);
+
+        let sym = lazy
+            .find_sym(0x2000100, &FindSymOpts::BASIC)
+            .unwrap()
+            .unwrap();
+        assert_eq!(sym.name, "factorial");
+        assert!(lazy.cache.symtab.get().is_none());
+
+        let addrs = eager
+            .cache
+            .ensure_symtab_cache()
+            .unwrap()
+            .addr_syms()
+            .iter()
+            .flat_map(|sym| [sym.st_value, sym.st_value + sym.st_size / 2])
+            .collect::<Vec<_>>();
+        assert!(!addrs.is_empty());
+
+        for addr in addrs {
+            let expected = eager.find_sym(addr, &FindSymOpts::BASIC).unwrap();
+            let sym = lazy.find_sym(addr, &FindSymOpts::BASIC).unwrap();
+            assert_eq!(
+                sym.map(|sym| (sym.name, sym.addr)),
+                expected.map(|sym| (sym.name, sym.addr)),
+                "{addr:#x}"
+            );
+        }
+    }
+
+    /// Check that we can retrieve PLT entries and that `find_sym`
+    /// reports PLT stubs if asked to.
+    #[test]
+    fn plt_entry_retrieval() {
+        let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join("test-plt.bin");
+        let parser = ElfParser::open(&path).unwrap();
+        let idx = parser.find_section(".plt").unwrap().unwrap();
+        let plt = parser.section_headers().unwrap()[idx].sh_addr;
+
+        let entries = parser.plt_entries().unwrap();
+        let entries = entries
+            .iter()
+            .map(|entry| (entry.addr, entry.name.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            entries,
+            vec![(plt + 16, "printf"), (plt + 32, "__isoc99_scanf")]
+        );
+
+        let opts = FindSymOpts::BASIC.with_plt_syms(true);
+        let sym = parser.find_sym(plt + 32 + 3, &opts).unwrap().unwrap();
+        assert_eq!(sym.name, "__isoc99_scanf@plt");
+        assert_eq!(sym.addr, plt + 32);
+        assert_eq!(sym.provenance, Provenance::Plt);
+
+        let sym = parser.find_sym(plt + 32 + 3, &FindSymOpts::BASIC).unwrap();
+        assert_ne!(sym.map(|sym| sym.name), Ok("__isoc99_scanf@plt"));
+
+        let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join("libtest-so-32.so");
+        let parser = ElfParser::open(&path).unwrap();
+        assert_eq!(parser.plt_entries().unwrap(), Vec::new());
+    }
+
+    /// Check that we can determine the section containing an address.
+    #[test]
+    fn section_for_addr_lookup() {
+        let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join("test-stable-addrs.bin");
+        let parser = ElfParser::open(&path).unwrap();
+
+        assert_eq!(parser.section_for_addr(0x2000100).unwrap(), Some(".text"));
+        assert_eq!(parser.section_for_addr(0x2001100).unwrap(), Some(".data"));
+        // Debug sections are not loaded and, hence, never reported.
+        assert_eq!(parser.section_for_addr(0x0).unwrap(), None);
+        assert_eq!(parser.section_for_addr(0x1000000).unwrap(), None);
+
+        let sym = parser
+            .find_sym(0x2000100, &FindSymOpts::BASIC)
+            .unwrap()
+            .unwrap();
+        assert_eq!(sym.name, "factorial");
+        assert_eq!(sym.section, Some(".text"));
+    }
+
+    /// Check that we report the sections of various types containing an
+    /// address as expected.
+    #[test]
+    fn section_for_addr_section_types() {
+        let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join("libtest-tls.so");
+        let parser = ElfParser::open(&path).unwrap();
+        let shdrs = parser.cache.ensure_shdrs().unwrap();
+        let shdr = |name| &shdrs[parser.find_section(name).unwrap().unwrap()];
+
+        // Every section occupying memory should be reported for all the
+        // addresses it covers.
+        for (idx, shdr) in shdrs.iter().enumerate() {
+            if !occupies_memory(shdr) {
+                continue
+            }
+
+            let name = parser.cache.section_name(idx).unwrap();
+            let first = shdr.sh_addr;
+            let last = shdr.sh_addr + shdr.sh_size - 1;
+            assert_eq!(parser.section_for_addr(first).unwrap(), Some(name));
+            assert_eq!(parser.section_for_addr(last).unwrap(), Some(name));
+        }
+
+        for name in [
+            ".note.gnu.build-id",
+            ".dynsym",
+            ".text",
+            ".eh_frame",
+            ".tdata",
+            ".init_array",
+            ".dynamic",
+            ".data",
+        ] {
+            let shdr = shdr(name);
+            assert_ne!(shdr.sh_type, SHT_NOBITS, "{name}");
+            assert_eq!(
+                parser.section_for_addr(shdr.sh_addr).unwrap(),
+                Some(name),
+                "{name}"
+            );
+        }
+
+        // `.bss` has no data in the file, but does occupy memory.
+        let bss = shdr(".bss");
+        assert_eq!(bss.sh_type, SHT_NOBITS);
+        assert_eq!(
+            parser
+                .section_for_addr(bss.sh_addr + bss.sh_size - 1)
+                .unwrap(),
+            Some(".bss")
+        );
+        assert_eq!(
+            parser.section_for_addr(bss.sh_addr + bss.sh_size).unwrap(),
+            None
+        );
+
+        // `.tbss` never occupies memory and is not reported for any of
+        // the addresses it nominally covers.
+        let tbss = shdr(".tbss");
+        assert_eq!(tbss.sh_type, SHT_NOBITS);
+        for addr in tbss.sh_addr..tbss.sh_addr + tbss.sh_size {
+            assert_ne!(parser.section_for_addr(addr).unwrap(), Some(".tbss"));
+        }
+
+        // Non-allocated sections are never reported.
+        let comment = shdr(".comment");
+        assert_eq!(comment.sh_flags & SHF_ALLOC, 0);
+        assert_ne!(
+            parser.section_for_addr(comment.sh_addr).unwrap(),
+            Some(".comment")
+        );
+    }
+
+    /// Check that we report the most specific section when sections
+    /// overlap.
+    #[test]
+    fn section_for_addr_overlapping() {
+        let data = make_elf_bytes_with_sections(
+            &[("foo", 0x1000, 0x10)],
+            &[
+                (".outer", SHT_PROGBITS, SHF_ALLOC, 0x10000, 0x1000),
+                (".inner", SHT_PROGBITS, SHF_ALLOC, 0x10400, 0x100),
+                (".inner2", SHT_PROGBITS, SHF_ALLOC, 0x10400, 0x100),
+                (".tbss", SHT_NOBITS, SHF_ALLOC | SHF_TLS, 0x10800, 0x100),
+                (".empty", SHT_PROGBITS, SHF_ALLOC, 0x11000, 0),
+                (".bss", SHT_NOBITS, SHF_ALLOC, 0x12000, 0x1000),
+                (".nonalloc", SHT_PROGBITS, 0, 0x14000, 0x1000),
+                (
+                    ".exec",
+                    SHT_PROGBITS,
+                    SHF_ALLOC | SHF_EXECINSTR,
+                    0x13000,
+                    0x10,
+                ),
+            ],
+        );
+        let mmap = Mmap::builder().map_data(&data).unwrap();
+        let parser = ElfParser::from_mmap(mmap, "<synthesized>");
+        let find = |addr| parser.section_for_addr(addr).unwrap();
+
+        assert_eq!(find(0x1008), Some(".text"));
+        assert_eq!(find(0x10000), Some(".outer"));
+        assert_eq!(find(0x103ff), Some(".outer"));
+        // Of two equally sized sections, the first one in the section
+        // header table is reported.
+        assert_eq!(find(0x10400), Some(".inner"));
+        assert_eq!(find(0x104ff), Some(".inner"));
+        assert_eq!(find(0x10500), Some(".outer"));
+        assert_eq!(find(0x10880), Some(".outer"));
+        assert_eq!(find(0x10fff), Some(".outer"));
+        assert_eq!(find(0x11000), None);
+        assert_eq!(find(0x12000), Some(".bss"));
+        assert_eq!(find(0x12fff), Some(".bss"));
+        assert_eq!(find(0x13008), Some(".exec"));
+        assert_eq!(find(0x13010), None);
+        assert_eq!(find(0x14000), None);
+        assert_eq!(find(Addr::MAX), None);
+    }
+
+    /// Check that overlapping symbols are handled gracefully even if
+    /// they are not sorted in the symbol table.
+    #[test]
+    fn unsorted_overlapping_sym_lookup() {
+        let data = make_elf_bytes(&[
+            ("inner", 0x1040, 0x20),
+            ("dup1", 0x1080, 0x10),
+            ("outer", 0x1000, 0x100),
+            ("dup2", 0x1080, 0x10),
+        ]);
+        let mmap = Mmap::builder().map_data(&data).unwrap();
+        let parser = ElfParser::from_mmap(mmap, "<synthesized>");
+        let find = |addr| {
+            parser
+                .find_sym(addr, &FindSymOpts::BASIC)
+                .unwrap()
+                .map(|sym| sym.name)
+        };
+
+        assert_eq!(find(0x1000), Ok("outer"));
+        assert_eq!(find(0x1040), Ok("inner"));
+        assert_eq!(find(0x1060), Ok("outer"));
+        // Of otherwise equal symbols, the first one in the symbol table
+        // is reported.
+        assert_eq!(find(0x1088), Ok("dup1"));
+        assert_eq!(find(0x1090), Ok("outer"));
+        assert_eq!(find(0x1100), Err(Reason::AfterLastSym));
+    }
+
+    /// Check that we report the nearest preceding symbol for addresses
+    /// not covered by any symbol, if asked to.
+    #[test]
+    fn nearest_preceding_sym_lookup() {
+        let data = make_elf_bytes(&[("foo", 0x1000, 0x10), ("bar", 0x1010, 0x20)]);
+        let mmap = Mmap::builder().map_data(&data).unwrap();
+        let parser = ElfParser::from_mmap(mmap, "<synthesized>");
+        let opts = FindSymOpts::BASIC.with_nearest_preceding(true);
+
+        let sym = parser.find_sym(0x1018, &opts).unwrap().unwrap();
+        assert_eq!(sym.name, "bar");
+        assert_eq!(sym.size, Some(0x20));
+
+        let sym = parser.find_sym(0x1042, &opts).unwrap().unwrap();
+        assert_eq!(sym.name, "bar");
+        assert_eq!(sym.addr, 0x1010);
+        assert_eq!(sym.size, Some(0x20));
+
+        let err = parser.find_sym(0xfff, &opts).unwrap().unwrap_err();
+        assert_eq!(err, Reason::BeforeFirstSym);
+    }
+
+    /// Check that we can parse 32 bit ELF files.
+    #[test]
+    fn elf32_parsing() {
+        let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join("libtest-so-32.so");
+        let parser = ElfParser::open(&path).unwrap();
+        // `EM_386`
+        assert_eq!(parser.machine().unwrap(), 3);
+        assert!(!parser.is_big_endian().unwrap());
+        assert_ne!(parser.find_section(".text").unwrap(), None);
+
+        let opts = FindAddrOpts {
+            offset_in_file: true,
+            ..Default::default()
+        };
+        let syms = parser.find_addr("the_answer", &opts).unwrap();
+        assert_eq!(syms.len(), 1);
+        let the_answer = &syms[0];
+        assert_eq!(the_answer.sym_type, SymType::Function);
+        assert_ne!(the_answer.size, 0);
+        assert_ne!(the_answer.file_offset, None);
+
+        let syms = parser.find_addr("a_variable", &opts).unwrap();
+        assert_eq!(syms.len(), 1);
+        assert_eq!(syms[0].sym_type, SymType::Variable);
+        assert_eq!(syms[0].size, 4);
+
+        let sym = parser
+            .find_sym(the_answer.addr + 1, &FindSymOpts::BASIC)
+            .unwrap()
+            .unwrap();
+        assert_eq!(sym.name, "the_answer");
+        assert_eq!(sym.addr, the_answer.addr);
+
+        let file_offset = the_answer.file_offset.unwrap();
+        assert_eq!(
+            parser.file_offset_to_virt_offset(file_offset).unwrap(),
+            Some(the_answer.addr)
+        );
+
+        assert_eq!(parser.needed_libs().unwrap(), Vec::<String>::new());
+    }
+
+    /// Check that we correctly split versioned symbol names.
+    #[test]
+    fn version_splitting() {
+        assert_eq!(split_version("foo"), ("foo", None));
+        assert_eq!(split_version("foo@"), ("foo@", None));
+        assert_eq!(split_version("@@V1"), ("@@V1", None));
+
+        let (name, version) = split_version("foo@V1");
+        assert_eq!(name, "foo");
+        let version = version.unwrap();
+        assert_eq!(version.name, "V1");
+        assert!(version.hidden);
+
+        let (name, version) = split_version("foo@@V2");
+        assert_eq!(name, "foo");
+        let version = version.unwrap();
+        assert_eq!(version.name, "V2");
+        assert!(!version.hidden);
+    }
+
+    /// Check that we can look up versioned symbols, by name as well as
+    /// by address.
+    #[test]
+    fn versioned_symbol_lookup() {
+        fn test(file: &str) {
+            let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+                .join("data")
+                .join(file);
+            let parser = ElfParser::open(&path).unwrap();
+            let opts = FindAddrOpts::default();
+
+            let mut syms = parser.find_addr("versioned", &opts).unwrap();
+            let () = syms.sort_by_key(|sym| sym.addr);
+            let versions = syms
+                .iter()
+                .map(|sym| {
+                    let version = sym.version.as_ref().unwrap();
+                    (version.name.as_ref(), version.hidden)
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(versions, vec![("VERS_1.0", true), ("VERS_2.0", false)]);
+
+            let syms = parser.find_addr("versioned@VERS_1.0", &opts).unwrap();
+            assert_eq!(syms.len(), 1);
+            let v1 = syms[0].addr;
+            // A hidden version is never the default one.
+            let syms = parser.find_addr("versioned@@VERS_1.0", &opts).unwrap();
+            assert_eq!(syms, Vec::new());
+
+            let syms = parser.find_addr("versioned@VERS_2.0", &opts).unwrap();
+            assert_eq!(syms.len(), 1);
+            let v2 = syms[0].addr;
+            let syms = parser.find_addr("versioned@@VERS_2.0", &opts).unwrap();
+            assert_eq!(syms.len(), 1);
+            assert_eq!(syms[0].addr, v2);
+            assert_ne!(v1, v2);
+
+            // `plain` got its version assigned solely by the version
+            // script.
+            let syms = parser.find_addr("plain@@VERS_1.0", &opts).unwrap();
+            assert_eq!(syms.len(), 1);
+            let plain = syms[0].addr;
+            let syms = parser.find_addr("plain@VERS_2.0", &opts).unwrap();
+            assert_eq!(syms, Vec::new());
+
+            let opts = FindSymOpts::BASIC.with_sym_versions(true);
+            let sym = parser.find_sym(plain, &opts).unwrap().unwrap();
+            assert_eq!(sym.name, "plain");
+            let version = sym.version.unwrap();
+            assert_eq!(version.name, "VERS_1.0");
+            assert!(!version.hidden);
+
+            let sym = parser
+                .find_sym(plain, &FindSymOpts::BASIC)
+                .unwrap()
+                .unwrap();
+            assert_eq!(sym.name, "plain");
+            assert_eq!(sym.version, None);
+        }
+
+        test("libtest-versioned.so");
+        test("libtest-versioned-stripped.so");
+        test("libtest-versioned-renamed.so");
+    }
+
+    /// Check that we can look up thread-local symbols and resolve
+    /// addresses inside the TLS segment.
+    #[test]
+    fn tls_symbol_lookup() {
+        let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join("libtest-tls.so");
+        let parser = ElfParser::open(&path).unwrap();
+        let phdr = parser.tls_segment().unwrap().unwrap();
+
+        let opts = FindAddrOpts {
+            offset_in_file: true,
+            sym_type: SymType::Tls,
+            ..Default::default()
+        };
+        let syms = parser.find_addr("tls_init", &opts).unwrap();
+        assert_eq!(syms.len(), 1);
+        let sym = &syms[0];
+        assert_eq!(sym.sym_type, SymType::Tls);
+        assert_eq!(sym.size, 4);
+        assert!(sym.addr < phdr.p_memsz);
+        assert_eq!(sym.file_offset, Some(phdr.p_offset + sym.addr));
+
+        let tls_init = sym.addr;
+        let syms = parser.find_addr("tls_zero", &opts).unwrap();
+        assert_eq!(syms.len(), 1);
+        let tls_zero = syms[0].addr;
+        assert_ne!(tls_zero, tls_init);
+
+        // An address inside the TLS segment should be resolved to the
+        // thread-local symbol at the corresponding offset.
+        let addr = phdr.p_vaddr + tls_zero + 1;
+        let sym = parser.find_sym(addr, &FindSymOpts::BASIC).unwrap().unwrap();
+        assert_eq!(sym.name, "tls_zero");
+        assert_eq!(sym.addr, phdr.p_vaddr + tls_zero);
+        assert_eq!(sym.size, Some(8));
+
+        // Regular symbols should be unaffected.
+        let syms = parser
+            .find_addr("tls_init_ptr", &FindAddrOpts::default())
+            .unwrap();
+        let sym = parser
+            .find_sym(syms[0].addr, &FindSymOpts::BASIC)
+            .unwrap()
+            .unwrap();
+        assert_eq!(sym.name, "tls_init_ptr");
+    }
+
     /// Make sure that we do not report a symbol if there is no conceivable
     /// match.
     #[test]
@@ -1208,7 +3839,16 @@ mod tests {
             },
         ];
 
-        let result = find_sym(&symtab, strtab, 0x10d20, SymType::Function).unwrap();
+        let max_ends = max_sym_ends(&symtab, false).collect::<Vec<_>>();
+        let result = find_sym(
+            &symtab,
+            &max_ends,
+            strtab,
+            false,
+            0x10d20,
+            SymType::Function,
+        )
+        .unwrap();
         assert_eq!(result, None);
     }
 
@@ -1218,7 +3858,8 @@ mod tests {
     fn lookup_symbol_with_unknown_size() {
         fn test(symtab: &[&Elf64_Sym]) {
             let strtab = b"\x00__libc_init_first\x00versionsort64\x00";
-            let sym = find_sym(symtab, strtab, 0x29d00, SymType::Function)
+            let max_ends = max_sym_ends(symtab, false).collect::<Vec<_>>();
+            let sym = find_sym(symtab, &max_ends, strtab, false, 0x29d00, SymType::Function)
                 .unwrap()
                 .unwrap();
             assert_eq!(sym.name, "__libc_init_first");
@@ -1228,7 +3869,7 @@ mod tests {
             // Because the symbol has a size of 0 and is the only conceivable
             // match, we report it on the basis that ELF reserves these for "no
             // size or an unknown size" cases.
-            let sym = find_sym(symtab, strtab, 0x29d90, SymType::Function)
+            let sym = find_sym(symtab, &max_ends, strtab, false, 0x29d90, SymType::Function)
                 .unwrap()
                 .unwrap();
             assert_eq!(sym.name, "__libc_init_first");
@@ -1238,7 +3879,8 @@ mod tests {
             // Note that despite of the first symbol (the invalid one; present
             // by default and reserved by ELF), is not being reported here
             // because it has an `st_shndx` value of `SHN_UNDEF`.
-            let result = find_sym(symtab, strtab, 0x1, SymType::Function).unwrap();
+            let result =
+                find_sym(symtab, &max_ends, strtab, false, 0x1, SymType::Function).unwrap();
             assert_eq!(result, None);
         }
 
@@ -1272,4 +3914,104 @@ mod tests {
         test(&symtab);
         test(&symtab[0..2]);
     }
+
+    /// Check that we correctly identify ARM and AArch64 mapping
+    /// symbols.
+    #[test]
+    fn mapping_symbol_detection() {
+        let strtab = b"\x00$a\x00$t.42\x00$d\x00$x\x00$ab\x00$\x00main\x00";
+        let sym = |st_name| Elf64_Sym {
+            st_name,
+            st_info: 0,
+            st_other: 0,
+            st_shndx: 0,
+            st_value: 0,
+            st_size: 0,
+        };
+
+        assert!(is_mapping_sym(strtab, &sym(0x1)));
+        assert!(is_mapping_sym(strtab, &sym(0x4)));
+        assert!(is_mapping_sym(strtab, &sym(0xa)));
+        assert!(is_mapping_sym(strtab, &sym(0xd)));
+        assert!(!is_mapping_sym(strtab, &sym(0x10)));
+        assert!(!is_mapping_sym(strtab, &sym(0x14)));
+        assert!(!is_mapping_sym(strtab, &sym(0x16)));
+        assert!(!is_mapping_sym(strtab, &sym(0x0)));
+        assert!(!is_mapping_sym(strtab, &sym(0xdeadbeef)));
+    }
+
+    /// Check that the Thumb bit of ARM function symbols is disregarded
+    /// as part of address lookups.
+    #[test]
+    fn lookup_thumb_symbol() {
+        let strtab = b"\x00thumb_fn\x00data\x00";
+        let symtab = [
+            &Elf64_Sym {
+                st_name: 0x1,
+                st_info: 0x12,
+                st_other: 0x0,
+                st_shndx: 0xb,
+                st_value: 0x1001,
+                st_size: 0x10,
+            },
+            &Elf64_Sym {
+                st_name: 0xa,
+                st_info: 0x11,
+                st_other: 0x0,
+                st_shndx: 0xc,
+                st_value: 0x2001,
+                st_size: 0x3,
+            },
+        ];
+        let thumb_ends = max_sym_ends(&symtab, true).collect::<Vec<_>>();
+        let ends = max_sym_ends(&symtab, false).collect::<Vec<_>>();
+
+        for addr in [0x1000, 0x1001, 0x100f] {
+            let sym = find_sym(&symtab, &thumb_ends, strtab, true, addr, SymType::Undefined)
+                .unwrap()
+                .unwrap();
+            assert_eq!(sym.name, "thumb_fn");
+            assert_eq!(sym.addr, 0x1000);
+            assert_eq!(sym.size, Some(0x10));
+        }
+
+        let result = find_sym(
+            &symtab,
+            &thumb_ends,
+            strtab,
+            true,
+            0x1010,
+            SymType::Undefined,
+        )
+        .unwrap();
+        assert_eq!(result, None);
+
+        // Without Thumb semantics the symbol starts one byte later.
+        let result = find_sym(&symtab, &ends, strtab, false, 0x1000, SymType::Undefined).unwrap();
+        assert_eq!(result, None);
+
+        // Only function symbols are subject to Thumb bit handling.
+        let result = find_sym(
+            &symtab,
+            &thumb_ends,
+            strtab,
+            true,
+            0x2000,
+            SymType::Undefined,
+        )
+        .unwrap();
+        assert_eq!(result, None);
+        let sym = find_sym(
+            &symtab,
+            &thumb_ends,
+            strtab,
+            true,
+            0x2001,
+            SymType::Undefined,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(sym.name, "data");
+        assert_eq!(sym.addr, 0x2001);
+    }
 }
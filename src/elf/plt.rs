@@ -0,0 +1,256 @@
+//! Synthesis of symbols for procedure linkage table (PLT) stubs.
+//!
+//! Calls to functions imported from shared objects go through small
+//! stubs in the `.plt` section (or, for binaries built with Indirect
+//! Branch Tracking, the `.plt.sec` section). These stubs are not
+//! covered by any symbol. However, the `n`-th stub belongs to the
+//! `n`-th relocation in `.rela.plt`, which in turn references the
+//! imported function's dynamic symbol. That allows us to name them
+//! `<function>@plt`, as tools such as `objdump(1)` do.
+
+use std::mem::size_of;
+use std::str;
+
+use crate::log::debug;
+use crate::symbolize::Provenance;
+use crate::symbolize::ResolvedSym;
+use crate::symbolize::SrcLang;
+use crate::util::ReadRaw as _;
+use crate::Addr;
+use crate::IntoError as _;
+use crate::Result;
+
+use super::types::Elf64_Rela;
+use super::types::Elf64_Shdr;
+use super::types::EM_X86_64;
+use super::types::R_X86_64_JUMP_SLOT;
+use super::ElfParser;
+
+
+/// The size of a PLT entry on x86-64, used if the section does not
+/// report one.
+const X86_64_PLT_ENTRY_SIZE: u64 = 16;
+/// The suffix used for naming PLT stubs.
+const PLT_SUFFIX: &str = "@plt";
+
+
+/// An entry of the procedure linkage table (PLT).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PltEntry {
+    /// The address of the PLT stub.
+    pub addr: Addr,
+    /// The name of the imported function called by the stub.
+    pub name: String,
+    /// The struct is non-exhaustive and open to extension.
+    #[doc(hidden)]
+    pub _non_exhaustive: (),
+}
+
+
+/// A single PLT stub.
+#[derive(Debug)]
+struct PltStub {
+    /// The stub's start address.
+    addr: Addr,
+    /// The stub's size.
+    size: usize,
+    /// The name of the stub, i.e., the name of the function it
+    /// calls suffixed with `@plt`.
+    name: String,
+}
+
+
+/// A sorted table of PLT stubs.
+#[derive(Debug)]
+pub(crate) struct PltStubs {
+    stubs: Box<[PltStub]>,
+}
+
+impl PltStubs {
+    /// Gather the PLT stubs of the ELF file backing `parser`.
+    ///
+    /// Only the x86-64 PLT layouts are understood. For any other
+    /// architecture, or if the PLT does not have the expected layout,
+    /// the resulting table is empty.
+    pub(crate) fn parse(parser: &ElfParser) -> Result<Self> {
+        let empty = || Self {
+            stubs: Box::new([]),
+        };
+
+        if parser.machine()? != EM_X86_64 || parser.address_size()? != 8 {
+            return Ok(empty())
+        }
+
+        let idx = match parser.find_section(".rela.plt")? {
+            Some(idx) => idx,
+            None => return Ok(empty()),
+        };
+        let mut data = parser.section_data(idx)?;
+        let count = data.len() / size_of::<Elf64_Rela>();
+        let relas = data
+            .read_pod_slice_ref::<Elf64_Rela>(count)
+            .ok_or_invalid_data(|| "failed to read .rela.plt contents")?;
+        let dynsyms = parser.raw_sym_names(".dynsym")?;
+        // The names of the functions called by each of the PLT slots.
+        // Slots for anything but jump slot relocations (e.g.,
+        // `R_X86_64_IRELATIVE` ones) stay unnamed.
+        let names = relas
+            .iter()
+            .map(|rela| {
+                if rela.type_() != R_X86_64_JUMP_SLOT {
+                    return None
+                }
+                dynsyms
+                    .get(rela.sym() as usize)
+                    .and_then(|name| str::from_utf8(name).ok())
+                    .filter(|name| !name.is_empty())
+            })
+            .collect::<Vec<_>>();
+
+        let shdrs = parser.section_headers()?;
+        let mut stubs = Vec::new();
+        // The `.plt` section starts with a header entry used for lazy
+        // binding, followed by one entry per slot.
+        if let Some(idx) = parser.find_section(".plt")? {
+            let () = add_stubs(&shdrs[idx], 1, &names, &mut stubs);
+        }
+        // With Indirect Branch Tracking, calls go through the entries
+        // of `.plt.sec`, which lacks the header.
+        if let Some(idx) = parser.find_section(".plt.sec")? {
+            let () = add_stubs(&shdrs[idx], 0, &names, &mut stubs);
+        }
+        let () = stubs.sort_by_key(|stub| stub.addr);
+
+        let slf = Self {
+            stubs: stubs.into_boxed_slice(),
+        };
+        Ok(slf)
+    }
+
+    /// Retrieve the PLT entries described by the stubs, sorted by
+    /// address.
+    pub(crate) fn entries(&self) -> impl Iterator<Item = PltEntry> + '_ {
+        self.stubs.iter().map(|stub| PltEntry {
+            addr: stub.addr,
+            name: stub
+                .name
+                .strip_suffix(PLT_SUFFIX)
+                .unwrap_or(&stub.name)
+                .to_string(),
+            _non_exhaustive: (),
+        })
+    }
+
+    /// Find the PLT stub containing `addr`, if any.
+    pub(crate) fn find_sym(&self, addr: Addr) -> Option<ResolvedSym<'_>> {
+        let idx = self.stubs.partition_point(|stub| stub.addr <= addr);
+        let stub = self.stubs.get(idx.checked_sub(1)?)?;
+        if addr - stub.addr >= stub.size as u64 {
+            return None
+        }
+
+        let sym = ResolvedSym {
+            name: &stub.name,
+            addr: stub.addr,
+            size: Some(stub.size),
+            lang: SrcLang::Unknown,
+            code_info: None,
+            inlined: Box::new([]),
+            provenance: Provenance::Plt,
+            is_synthetic: true,
+            raw_elf_sym: None,
+            compilation_unit: None,
+            version: None,
+            section: None,
+            linkage_name: None,
+        };
+        Some(sym)
+    }
+}
+
+
+/// Add stubs for the PLT section described by `shdr`, in which the
+/// entry for the first slot is preceded by `skip` other entries.
+///
+/// Sections whose size does not match the number of slots are
+/// ignored, as we can't tell which entry belongs to which slot.
+fn add_stubs(shdr: &Elf64_Shdr, skip: usize, names: &[Option<&str>], stubs: &mut Vec<PltStub>) {
+    let entsize = if shdr.sh_entsize != 0 {
+        shdr.sh_entsize
+    } else {
+        X86_64_PLT_ENTRY_SIZE
+    };
+
+    if shdr.sh_size != (names.len() + skip) as u64 * entsize {
+        debug!(
+            "PLT section of {} bytes has unexpected layout for {} slots",
+            shdr.sh_size,
+            names.len()
+        );
+        return
+    }
+
+    let () = stubs.extend(names.iter().enumerate().filter_map(|(slot, name)| {
+        let name = (*name)?;
+        let stub = PltStub {
+            addr: shdr.sh_addr + (slot + skip) as u64 * entsize,
+            size: entsize as usize,
+            name: format!("{name}{PLT_SUFFIX}"),
+        };
+        Some(stub)
+    }));
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::env;
+    use std::path::Path;
+
+
+    /// Check that we can name PLT stubs, both with and without
+    /// `.plt.sec` section.
+    #[test]
+    fn plt_stub_lookup() {
+        let data = Path::new(&env!("CARGO_MANIFEST_DIR")).join("data");
+
+        let parser = ElfParser::open(&data.join("test-plt.bin")).unwrap();
+        let stubs = PltStubs::parse(&parser).unwrap();
+        let plt = parser.find_section(".plt").unwrap().unwrap();
+        let plt = &parser.section_headers().unwrap()[plt];
+        // The first entry is the PLT header, which is not a stub.
+        assert!(stubs.find_sym(plt.sh_addr).is_none());
+        let sym = stubs.find_sym(plt.sh_addr + 16 + 4).unwrap();
+        assert_eq!(sym.name, "printf@plt");
+        assert_eq!(sym.addr, plt.sh_addr + 16);
+        assert_eq!(sym.size, Some(16));
+        assert_eq!(sym.provenance, Provenance::Plt);
+        let sym = stubs.find_sym(plt.sh_addr + 32).unwrap();
+        assert_eq!(sym.name, "__isoc99_scanf@plt");
+        assert!(stubs.find_sym(plt.sh_addr + 48).is_none());
+
+        let parser = ElfParser::open(&data.join("test-plt-ibt.bin")).unwrap();
+        let stubs = PltStubs::parse(&parser).unwrap();
+        let plt_sec = parser.find_section(".plt.sec").unwrap().unwrap();
+        let plt_sec = &parser.section_headers().unwrap()[plt_sec];
+        let sym = stubs.find_sym(plt_sec.sh_addr + 4).unwrap();
+        assert_eq!(sym.name, "printf@plt");
+        assert_eq!(sym.addr, plt_sec.sh_addr);
+        let sym = stubs.find_sym(plt_sec.sh_addr + 16).unwrap();
+        assert_eq!(sym.name, "__isoc99_scanf@plt");
+    }
+
+    /// Make sure that files without (understood) PLT result in an empty
+    /// table.
+    #[test]
+    fn no_plt_stubs() {
+        let data = Path::new(&env!("CARGO_MANIFEST_DIR")).join("data");
+        for file in ["test-stable-addrs.bin", "libtest-so-32.so"] {
+            let parser = ElfParser::open(&data.join(file)).unwrap();
+            let stubs = PltStubs::parse(&parser).unwrap();
+            assert!(stubs.stubs.is_empty(), "{file}");
+        }
+    }
+}
@@ -1,16 +1,28 @@
 use std::fmt::Debug;
 use std::fmt::Formatter;
 use std::fmt::Result as FmtResult;
+use std::fs::File;
+use std::mem::take;
 use std::ops::Deref as _;
+use std::os::unix::io::BorrowedFd;
 use std::path::Path;
 use std::rc::Rc;
 
+#[cfg(feature = "tracing")]
+use tracing::field::Empty;
+#[cfg(feature = "tracing")]
+use tracing::Span;
+
 #[cfg(feature = "dwarf")]
 use crate::dwarf::DwarfResolver;
+#[cfg(feature = "dwarf")]
+use crate::dwarf::EhFrameFns;
 use crate::file_cache::FileCache;
+use crate::inspect::find_addrs_dedup;
 use crate::inspect::FindAddrOpts;
 use crate::inspect::Inspect;
 use crate::inspect::SymInfo;
+use crate::inspect::UnitInfo;
 use crate::once::OnceCell;
 use crate::symbolize::FindSymOpts;
 use crate::symbolize::Reason;
@@ -21,6 +33,8 @@ use crate::Addr;
 use crate::Error;
 use crate::Result;
 
+#[cfg(feature = "go")]
+use super::go::GoPclntab;
 use super::ElfParser;
 
 #[derive(Clone, Debug)]
@@ -44,8 +58,51 @@ impl FileCache<ElfResolverData> {
         &'slf self,
         path: &Path,
         debug_syms: bool,
+    ) -> Result<&'slf Rc<ElfResolver>> {
+        self.elf_resolver_with_cache(path, debug_syms, None, false)
+    }
+
+    /// Retrieve the ELF resolver for the file at `path`, creating it
+    /// if necessary.
+    ///
+    /// If provided, `cache_dir` is used for caching the DWARF unit
+    /// index on disk. `strict_dwarf` controls whether DWARF data that
+    /// can't be interpreted is reported as an error.
+    pub(crate) fn elf_resolver_with_cache<'slf>(
+        &'slf self,
+        path: &Path,
+        debug_syms: bool,
+        cache_dir: Option<&Path>,
+        strict_dwarf: bool,
     ) -> Result<&'slf Rc<ElfResolver>> {
         let (file, cell) = self.entry(path)?;
+        Self::elf_resolver_impl(file, cell, path, debug_syms, cache_dir, strict_dwarf)
+    }
+
+    /// Retrieve the ELF resolver for the file referenced by `fd`,
+    /// creating it if necessary.
+    ///
+    /// `label` is used in place of the file's path.
+    pub(crate) fn elf_resolver_for_fd<'slf>(
+        &'slf self,
+        fd: BorrowedFd<'_>,
+        label: &Path,
+        debug_syms: bool,
+        cache_dir: Option<&Path>,
+        strict_dwarf: bool,
+    ) -> Result<&'slf Rc<ElfResolver>> {
+        let (file, cell) = self.entry_fd(fd)?;
+        Self::elf_resolver_impl(file, cell, label, debug_syms, cache_dir, strict_dwarf)
+    }
+
+    fn elf_resolver_impl<'slf>(
+        file: &File,
+        cell: &'slf OnceCell<ElfResolverData>,
+        path: &Path,
+        debug_syms: bool,
+        cache_dir: Option<&Path>,
+        strict_dwarf: bool,
+    ) -> Result<&'slf Rc<ElfResolver>> {
         let resolver = if let Some(data) = cell.get() {
             if debug_syms {
                 data.dwarf.get_or_try_init(|| {
@@ -54,7 +111,12 @@ impl FileCache<ElfResolverData> {
                     //         initializing the `dwarf` part of it, the
                     //         `elf` part *must* be present.
                     let parser = data.elf.get().unwrap().parser().clone();
-                    let resolver = ElfResolver::from_parser(parser, debug_syms)?;
+                    let resolver = ElfResolver::from_parser_with_cache(
+                        parser,
+                        debug_syms,
+                        cache_dir,
+                        strict_dwarf,
+                    )?;
                     let resolver = Rc::new(resolver);
                     Result::<_, Error>::Ok(resolver)
                 })?
@@ -65,7 +127,12 @@ impl FileCache<ElfResolverData> {
                     //         initializing the `elf` part of it, the
                     //         `dwarf` part *must* be present.
                     let parser = data.dwarf.get().unwrap().parser().clone();
-                    let resolver = ElfResolver::from_parser(parser, debug_syms)?;
+                    let resolver = ElfResolver::from_parser_with_cache(
+                        parser,
+                        debug_syms,
+                        cache_dir,
+                        strict_dwarf,
+                    )?;
                     let resolver = Rc::new(resolver);
                     Result::<_, Error>::Ok(resolver)
                 })?
@@ -73,7 +140,8 @@ impl FileCache<ElfResolverData> {
             .clone()
         } else {
             let parser = Rc::new(ElfParser::open_file(file, path)?);
-            let resolver = ElfResolver::from_parser(parser, debug_syms)?;
+            let resolver =
+                ElfResolver::from_parser_with_cache(parser, debug_syms, cache_dir, strict_dwarf)?;
             Rc::new(resolver)
         };
 
@@ -105,6 +173,15 @@ impl FileCache<ElfResolverData> {
 /// The symbol resolver for a single ELF file.
 pub struct ElfResolver {
     backend: ElfBackend,
+    /// The machine architecture (`EM_*`) of the ELF file.
+    machine: u16,
+    /// Functions derived from `.eh_frame` call frame information,
+    /// lazily parsed on first use.
+    #[cfg(feature = "dwarf")]
+    eh_frame: OnceCell<EhFrameFns>,
+    /// The Go PC-line table, if any, lazily parsed on first use.
+    #[cfg(feature = "go")]
+    go: OnceCell<Option<GoPclntab>>,
 }
 
 impl ElfResolver {
@@ -118,10 +195,33 @@ impl ElfResolver {
         Self::from_parser(parser, true)
     }
 
-    pub(crate) fn from_parser(parser: Rc<ElfParser>, _debug_syms: bool) -> Result<Self> {
+    /// Create an `ElfResolver` for the provided [`ElfParser`].
+    ///
+    /// This constructor allows for configuration of the parser before
+    /// use, e.g., by means of
+    /// [`ElfParser::set_symbol_table_indexing`].
+    pub fn from_elf_parser(parser: ElfParser) -> Result<Self> {
+        Self::from_parser(Rc::new(parser), true)
+    }
+
+    pub(crate) fn from_parser(parser: Rc<ElfParser>, debug_syms: bool) -> Result<Self> {
+        Self::from_parser_with_cache(parser, debug_syms, None, false)
+    }
+
+    /// Create an `ElfResolver` for the provided [`ElfParser`], using
+    /// `cache_dir` (if provided) for caching the DWARF unit index on
+    /// disk.
+    pub(crate) fn from_parser_with_cache(
+        parser: Rc<ElfParser>,
+        _debug_syms: bool,
+        _cache_dir: Option<&Path>,
+        _strict_dwarf: bool,
+    ) -> Result<Self> {
+        let machine = parser.machine()?;
+
         #[cfg(feature = "dwarf")]
         let backend = if _debug_syms {
-            let dwarf = DwarfResolver::from_parser(parser)?;
+            let dwarf = DwarfResolver::from_parser_with_cache(parser, _cache_dir, _strict_dwarf)?;
             let backend = ElfBackend::Dwarf(Rc::new(dwarf));
             backend
         } else {
@@ -131,40 +231,195 @@ impl ElfResolver {
         #[cfg(not(feature = "dwarf"))]
         let backend = ElfBackend::Elf(parser);
 
-        let resolver = ElfResolver { backend };
+        let resolver = ElfResolver {
+            backend,
+            machine,
+            #[cfg(feature = "dwarf")]
+            eh_frame: OnceCell::new(),
+            #[cfg(feature = "go")]
+            go: OnceCell::new(),
+        };
         Ok(resolver)
     }
 
     pub(crate) fn parser(&self) -> &Rc<ElfParser> {
         match &self.backend {
             #[cfg(feature = "dwarf")]
-            ElfBackend::Dwarf(dwarf) => dwarf.parser(),
+            // SANITY: A DWARF resolver created for an ELF file is
+            //         always backed by an `ElfParser`.
+            ElfBackend::Dwarf(dwarf) => dwarf.parser().unwrap(),
             ElfBackend::Elf(parser) => parser,
         }
     }
 
+    /// Invoke `f` for each unique source file that contributed to the
+    /// binary, as recorded in DWARF debug information.
+    ///
+    /// No source files are reported if the resolver is not backed by
+    /// DWARF.
+    pub(crate) fn for_each_source_file(&self, f: &mut dyn FnMut(&Path)) -> Result<()> {
+        match &self.backend {
+            #[cfg(feature = "dwarf")]
+            ElfBackend::Dwarf(dwarf) => dwarf.for_each_source_file(f),
+            ElfBackend::Elf(_) => Ok(()),
+        }
+    }
+
+    /// Retrieve information about the DWARF compilation unit
+    /// containing the code at `addr`.
+    ///
+    /// No unit is reported if the resolver is not backed by DWARF.
+    pub(crate) fn unit_info(&self, addr: Addr) -> Result<Option<UnitInfo>> {
+        match &self.backend {
+            #[cfg(feature = "dwarf")]
+            ElfBackend::Dwarf(dwarf) => dwarf.unit_info(addr),
+            ElfBackend::Elf(_) => Ok(None),
+        }
+    }
+
+    /// Retrieve the DWARF resolver backing this resolver, if any.
+    #[cfg(feature = "type-info")]
+    pub(crate) fn dwarf_resolver(&self) -> Option<&DwarfResolver> {
+        match &self.backend {
+            ElfBackend::Dwarf(dwarf) => Some(dwarf),
+            ElfBackend::Elf(_) => None,
+        }
+    }
+
+    /// Retrieve the machine architecture the ELF file is targeting.
+    ///
+    /// Please refer to [`ElfParser::machine`] for details.
+    #[inline]
+    pub fn machine(&self) -> u16 {
+        self.machine
+    }
+
     /// Retrieve the path to the ELF file represented by this resolver.
     pub(crate) fn path(&self) -> &Path {
         match &self.backend {
             #[cfg(feature = "dwarf")]
-            ElfBackend::Dwarf(dwarf) => dwarf.parser().path(),
+            ElfBackend::Dwarf(_) => self.parser().path(),
             ElfBackend::Elf(parser) => parser.path(),
         }
     }
-}
 
-impl Symbolize for ElfResolver {
-    #[cfg_attr(feature = "tracing", crate::log::instrument(fields(addr = format_args!("{addr:#x}"))))]
-    fn find_sym(&self, addr: Addr, opts: &FindSymOpts) -> Result<Result<ResolvedSym<'_>, Reason>> {
+    /// Amend `sym`, which was resolved by means other than the ELF
+    /// symbol tables, with information from the ELF symbol covering
+    /// `addr`, if requested.
+    #[cfg(any(feature = "dwarf", feature = "go"))]
+    fn add_elf_sym_info<'slf>(
+        &'slf self,
+        sym: &mut ResolvedSym<'slf>,
+        addr: Addr,
+        opts: &FindSymOpts,
+    ) -> Result<()> {
+        if opts.raw_elf_syms() || opts.sym_versions() {
+            if let Ok(elf_sym) = self.parser().find_sym(addr, opts)? {
+                sym.raw_elf_sym = elf_sym.raw_elf_sym;
+                // Neither DWARF nor the Go PC-line table have a notion
+                // of symbol versions. Only borrow the version of the
+                // ELF symbol if it actually is the one reported.
+                if elf_sym.name == sym.name && elf_sym.addr == sym.addr {
+                    sym.version = elf_sym.version;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Resolve `addr` using the most capable backend available.
+    fn find_sym_impl(
+        &self,
+        addr: Addr,
+        opts: &FindSymOpts,
+    ) -> Result<Result<ResolvedSym<'_>, Reason>> {
+        // PLT stubs are not covered by any symbol, but may be
+        // attributed to a preceding symbol of unknown size (such as
+        // `_init`) by both DWARF and ELF lookups. So check for them
+        // first.
+        if opts.plt_syms() {
+            if let Some(sym) = self.parser().plt_stubs()?.find_sym(addr) {
+                let () = record_backend("plt", &sym);
+                return Ok(Ok(sym))
+            }
+        }
+
         #[cfg(feature = "dwarf")]
         if let ElfBackend::Dwarf(dwarf) = &self.backend {
-            if let Ok(sym) = dwarf.find_sym(addr, opts)? {
+            if let Ok(mut sym) = dwarf.find_sym(addr, opts)? {
+                let () = self.add_elf_sym_info(&mut sym, addr, opts)?;
+                let () = record_backend("dwarf", &sym);
                 return Ok(Ok(sym))
             }
         }
 
+        // Go binaries carry a PC-line table that provides function
+        // names as well as source code information and that is
+        // retained even if DWARF debug information got stripped.
+        #[cfg(feature = "go")]
+        {
+            let pclntab = self
+                .go
+                .get_or_try_init(|| GoPclntab::parse(self.parser()))?;
+            if let Some(pclntab) = pclntab {
+                if let Some(mut sym) = pclntab.find_sym(self.parser(), addr, opts)? {
+                    let () = self.add_elf_sym_info(&mut sym, addr, opts)?;
+                    let () = record_backend("go", &sym);
+                    return Ok(Ok(sym))
+                }
+            }
+        }
+
         let parser = self.parser();
         let result = parser.find_sym(addr, opts)?;
+
+        #[cfg(feature = "dwarf")]
+        if result.is_err() && opts.eh_frame_fallback() {
+            let fns = self
+                .eh_frame
+                .get_or_try_init(|| EhFrameFns::parse(parser))?;
+            if let Some(sym) = fns.find_sym(addr) {
+                let () = record_backend("eh_frame", &sym);
+                return Ok(Ok(sym))
+            }
+        }
+
+        if let Ok(sym) = &result {
+            let () = record_backend("elf", sym);
+        }
+        Ok(result)
+    }
+}
+
+/// Record the backend that resolved an address, along with the number
+/// of inlined functions reported, as fields of the current span.
+#[cfg(feature = "tracing")]
+fn record_backend(backend: &str, sym: &ResolvedSym<'_>) {
+    let span = Span::current();
+    let _span = span.record("backend", backend);
+    let _span = span.record("inlined", sym.inlined.len());
+}
+
+#[cfg(not(feature = "tracing"))]
+#[inline]
+fn record_backend(_backend: &str, _sym: &ResolvedSym<'_>) {}
+
+
+impl Symbolize for ElfResolver {
+    #[cfg_attr(feature = "tracing", crate::log::instrument(fields(
+        addr = format_args!("{addr:#x}"),
+        backend = Empty,
+        inlined = Empty,
+    )))]
+    fn find_sym(&self, addr: Addr, opts: &FindSymOpts) -> Result<Result<ResolvedSym<'_>, Reason>> {
+        let mut result = self.find_sym_impl(addr, opts)?;
+        // Only the ELF symbol table based lookup attributes symbols to
+        // sections on its own. Do so for all other backends here.
+        if let Ok(sym) = &mut result {
+            if sym.section.is_none() {
+                sym.section = self.parser().section_for_addr(sym.addr)?;
+            }
+        }
         Ok(result)
     }
 }
@@ -191,6 +446,32 @@ impl Inspect for ElfResolver {
         Ok(syms)
     }
 
+    fn find_addrs<'slf>(
+        &'slf self,
+        names: &[&str],
+        opts: &FindAddrOpts,
+    ) -> Result<Vec<Vec<SymInfo<'slf>>>> {
+        let parser = self.parser();
+
+        #[cfg(feature = "dwarf")]
+        if let ElfBackend::Dwarf(dwarf) = &self.backend {
+            // DWARF lookup happens in a single pass over all units. We
+            // only fall back to the symbol table for names that it could
+            // not resolve.
+            let mut dwarf_syms = dwarf.find_addrs(names, opts)?;
+            return find_addrs_dedup(names, |idx, name| {
+                let syms = take(&mut dwarf_syms[idx]);
+                if !syms.is_empty() {
+                    Ok(syms)
+                } else {
+                    parser.find_addr(name, opts)
+                }
+            })
+        }
+
+        find_addrs_dedup(names, |_idx, name| parser.find_addr(name, opts))
+    }
+
     fn for_each(&self, opts: &FindAddrOpts, f: &mut dyn FnMut(&SymInfo<'_>)) -> Result<()> {
         let parser = self.parser();
         parser.deref().for_each(opts, f)
@@ -212,6 +493,8 @@ impl Debug for ElfResolver {
 mod tests {
     use super::*;
 
+    use crate::symbolize::Provenance;
+
 
     /// Exercise the `Debug` representation of various types.
     #[test]
@@ -244,4 +527,54 @@ mod tests {
         assert_eq!(parser.find_file_offset(0x0).unwrap(), None);
         assert_eq!(parser.find_file_offset(0xffffffffffffffff).unwrap(), None);
     }
+
+    /// Check that addresses inside of PLT stubs are only attributed to
+    /// the called function if asked for.
+    #[test]
+    fn plt_syms() {
+        let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join("test-plt-ibt.bin");
+        let parser = Rc::new(ElfParser::open(&path).unwrap());
+        let idx = parser.find_section(".plt.sec").unwrap().unwrap();
+        let addr = parser.section_headers().unwrap()[idx].sh_addr + 16 + 5;
+
+        for debug_syms in [false, true] {
+            let resolver = ElfResolver::from_parser(parser.clone(), debug_syms).unwrap();
+            // By default, the address is attributed to `_init`, which
+            // has no size.
+            let opts = FindSymOpts::BASIC;
+            let sym = resolver.find_sym(addr, &opts).unwrap().unwrap();
+            assert_eq!(sym.name, "_init");
+
+            let opts = FindSymOpts::BASIC.with_plt_syms(true);
+            let sym = resolver.find_sym(addr, &opts).unwrap().unwrap();
+            assert_eq!(sym.name, "__isoc99_scanf@plt");
+            assert_eq!(sym.addr, addr - 5);
+            assert_eq!(sym.provenance, Provenance::Plt);
+        }
+    }
+
+    /// Make sure that ELF and DWARF resolvers created for the same
+    /// file share decompressed section data.
+    #[cfg(all(feature = "dwarf", feature = "zlib"))]
+    #[test]
+    fn decompressed_data_sharing() {
+        let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join("test-stable-addrs-compressed-debug-zlib.bin");
+
+        let cache = FileCache::<ElfResolverData>::builder().build();
+        let dwarf = cache.elf_resolver(&path, true).unwrap().clone();
+        let elf = cache.elf_resolver(&path, false).unwrap().clone();
+        assert!(Rc::ptr_eq(dwarf.parser(), elf.parser()));
+
+        let idx = elf.parser().find_section(".debug_info").unwrap().unwrap();
+        let data1 = dwarf.parser().section_data(idx).unwrap();
+        let data2 = elf.parser().section_data(idx).unwrap();
+        assert!(!data1.is_empty());
+        // The section should have been decompressed only once, with
+        // both resolvers referencing the very same data.
+        assert_eq!(data1.as_ptr(), data2.as_ptr());
+    }
 }
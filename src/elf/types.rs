@@ -1,7 +1,29 @@
+use crate::inspect::SymBinding;
 use crate::util::Pod;
 use crate::SymType;
 
-const EI_NIDENT: usize = 16;
+/// The size of `e_ident`.
+pub(crate) const EI_NIDENT: usize = 16;
+/// The index of the file class byte in `e_ident`.
+pub(crate) const EI_CLASS: usize = 4;
+/// The index of the data encoding byte in `e_ident`.
+pub(crate) const EI_DATA: usize = 5;
+
+/// 32 bit objects.
+pub(crate) const ELFCLASS32: u8 = 1;
+/// 64 bit objects.
+pub(crate) const ELFCLASS64: u8 = 2;
+
+/// Two's complement, little-endian data encoding.
+pub(crate) const ELFDATA2LSB: u8 = 1;
+/// Two's complement, big-endian data encoding.
+pub(crate) const ELFDATA2MSB: u8 = 2;
+
+type Elf32_Addr = u32;
+type Elf32_Half = u16;
+type Elf32_Off = u32;
+type Elf32_Sword = i32;
+type Elf32_Word = u32;
 
 type Elf64_Addr = u64;
 type Elf64_Half = u16;
@@ -11,6 +33,38 @@ type Elf64_Xword = u64;
 
 pub(crate) const ET_EXEC: u16 = 2;
 pub(crate) const ET_DYN: u16 = 3;
+pub(crate) const ET_CORE: u16 = 4;
+
+pub(crate) const EM_ARM: u16 = 40;
+pub(crate) const EM_X86_64: u16 = 62;
+pub(crate) const EM_AARCH64: u16 = 183;
+
+
+/// A trait for ELF data structures whose multi-byte fields can be
+/// converted between byte orders.
+pub(crate) trait ByteSwap: Pod {
+    /// Create a copy of `self` with the byte order of all multi-byte
+    /// fields reversed.
+    fn byte_swap(&self) -> Self;
+}
+
+impl ByteSwap for u16 {
+    fn byte_swap(&self) -> Self {
+        self.swap_bytes()
+    }
+}
+
+impl ByteSwap for u32 {
+    fn byte_swap(&self) -> Self {
+        self.swap_bytes()
+    }
+}
+
+impl ByteSwap for u64 {
+    fn byte_swap(&self) -> Self {
+        self.swap_bytes()
+    }
+}
 
 #[derive(Debug)]
 #[repr(C)]
@@ -34,25 +88,185 @@ pub(crate) struct Elf64_Ehdr {
 // SAFETY: `Elf64_Ehdr` is valid for any bit pattern.
 unsafe impl Pod for Elf64_Ehdr {}
 
+impl ByteSwap for Elf64_Ehdr {
+    fn byte_swap(&self) -> Self {
+        Self {
+            e_ident: self.e_ident,
+            e_type: self.e_type.swap_bytes(),
+            e_machine: self.e_machine.swap_bytes(),
+            e_version: self.e_version.swap_bytes(),
+            e_entry: self.e_entry.swap_bytes(),
+            e_phoff: self.e_phoff.swap_bytes(),
+            e_shoff: self.e_shoff.swap_bytes(),
+            e_flags: self.e_flags.swap_bytes(),
+            e_ehsize: self.e_ehsize.swap_bytes(),
+            e_phentsize: self.e_phentsize.swap_bytes(),
+            e_phnum: self.e_phnum.swap_bytes(),
+            e_shentsize: self.e_shentsize.swap_bytes(),
+            e_shnum: self.e_shnum.swap_bytes(),
+            e_shstrndx: self.e_shstrndx.swap_bytes(),
+        }
+    }
+}
+
+#[derive(Debug)]
+#[repr(C)]
+pub(crate) struct Elf32_Ehdr {
+    pub e_ident: [u8; EI_NIDENT],
+    pub e_type: Elf32_Half,
+    pub e_machine: Elf32_Half,
+    pub e_version: Elf32_Word,
+    pub e_entry: Elf32_Addr,
+    pub e_phoff: Elf32_Off,
+    pub e_shoff: Elf32_Off,
+    pub e_flags: Elf32_Word,
+    pub e_ehsize: Elf32_Half,
+    pub e_phentsize: Elf32_Half,
+    pub e_phnum: Elf32_Half,
+    pub e_shentsize: Elf32_Half,
+    pub e_shnum: Elf32_Half,
+    pub e_shstrndx: Elf32_Half,
+}
+
+// SAFETY: `Elf32_Ehdr` is valid for any bit pattern.
+unsafe impl Pod for Elf32_Ehdr {}
+
+impl ByteSwap for Elf32_Ehdr {
+    fn byte_swap(&self) -> Self {
+        Self {
+            e_ident: self.e_ident,
+            e_type: self.e_type.swap_bytes(),
+            e_machine: self.e_machine.swap_bytes(),
+            e_version: self.e_version.swap_bytes(),
+            e_entry: self.e_entry.swap_bytes(),
+            e_phoff: self.e_phoff.swap_bytes(),
+            e_shoff: self.e_shoff.swap_bytes(),
+            e_flags: self.e_flags.swap_bytes(),
+            e_ehsize: self.e_ehsize.swap_bytes(),
+            e_phentsize: self.e_phentsize.swap_bytes(),
+            e_phnum: self.e_phnum.swap_bytes(),
+            e_shentsize: self.e_shentsize.swap_bytes(),
+            e_shnum: self.e_shnum.swap_bytes(),
+            e_shstrndx: self.e_shstrndx.swap_bytes(),
+        }
+    }
+}
+
+impl From<&Elf32_Ehdr> for Elf64_Ehdr {
+    fn from(other: &Elf32_Ehdr) -> Self {
+        Self {
+            e_ident: other.e_ident,
+            e_type: other.e_type,
+            e_machine: other.e_machine,
+            e_version: other.e_version,
+            e_entry: other.e_entry.into(),
+            e_phoff: other.e_phoff.into(),
+            e_shoff: other.e_shoff.into(),
+            e_flags: other.e_flags,
+            e_ehsize: other.e_ehsize,
+            e_phentsize: other.e_phentsize,
+            e_phnum: other.e_phnum,
+            e_shentsize: other.e_shentsize,
+            e_shnum: other.e_shnum,
+            e_shstrndx: other.e_shstrndx,
+        }
+    }
+}
+
 pub(crate) const PT_LOAD: u32 = 1;
+pub(crate) const PT_NOTE: u32 = 4;
+pub(crate) const PT_TLS: u32 = 7;
 
+/// An ELF64 program header, describing a segment.
 #[derive(Debug)]
 #[repr(C)]
-pub(crate) struct Elf64_Phdr {
+pub struct Elf64_Phdr {
+    /// The kind of segment (`PT_*`).
     pub p_type: Elf64_Word,
+    /// Segment flags (`PF_*`), describing access permissions.
     pub p_flags: Elf64_Word,
-    pub p_offset: Elf64_Off,   /* Segment file offset */
-    pub p_vaddr: Elf64_Addr,   /* Segment virtual address */
-    pub p_paddr: Elf64_Addr,   /* Segment physical address */
-    pub p_filesz: Elf64_Xword, /* Segment size in file */
-    pub p_memsz: Elf64_Xword,  /* Segment size in memory */
-    pub p_align: Elf64_Xword,  /* Segment alignment, file & memory */
+    /// Segment file offset.
+    pub p_offset: Elf64_Off,
+    /// Segment virtual address.
+    pub p_vaddr: Elf64_Addr,
+    /// Segment physical address.
+    pub p_paddr: Elf64_Addr,
+    /// Segment size in file.
+    pub p_filesz: Elf64_Xword,
+    /// Segment size in memory.
+    pub p_memsz: Elf64_Xword,
+    /// Segment alignment, file & memory.
+    pub p_align: Elf64_Xword,
 }
 
 // SAFETY: `Elf64_Phdr` is valid for any bit pattern.
 unsafe impl Pod for Elf64_Phdr {}
 
+impl ByteSwap for Elf64_Phdr {
+    fn byte_swap(&self) -> Self {
+        Self {
+            p_type: self.p_type.swap_bytes(),
+            p_flags: self.p_flags.swap_bytes(),
+            p_offset: self.p_offset.swap_bytes(),
+            p_vaddr: self.p_vaddr.swap_bytes(),
+            p_paddr: self.p_paddr.swap_bytes(),
+            p_filesz: self.p_filesz.swap_bytes(),
+            p_memsz: self.p_memsz.swap_bytes(),
+            p_align: self.p_align.swap_bytes(),
+        }
+    }
+}
+
+/// An ELF32 program header, describing a segment.
+#[derive(Debug)]
+#[repr(C)]
+pub(crate) struct Elf32_Phdr {
+    pub p_type: Elf32_Word,
+    pub p_offset: Elf32_Off,
+    pub p_vaddr: Elf32_Addr,
+    pub p_paddr: Elf32_Addr,
+    pub p_filesz: Elf32_Word,
+    pub p_memsz: Elf32_Word,
+    pub p_flags: Elf32_Word,
+    pub p_align: Elf32_Word,
+}
+
+// SAFETY: `Elf32_Phdr` is valid for any bit pattern.
+unsafe impl Pod for Elf32_Phdr {}
+
+impl ByteSwap for Elf32_Phdr {
+    fn byte_swap(&self) -> Self {
+        Self {
+            p_type: self.p_type.swap_bytes(),
+            p_offset: self.p_offset.swap_bytes(),
+            p_vaddr: self.p_vaddr.swap_bytes(),
+            p_paddr: self.p_paddr.swap_bytes(),
+            p_filesz: self.p_filesz.swap_bytes(),
+            p_memsz: self.p_memsz.swap_bytes(),
+            p_flags: self.p_flags.swap_bytes(),
+            p_align: self.p_align.swap_bytes(),
+        }
+    }
+}
+
+impl From<&Elf32_Phdr> for Elf64_Phdr {
+    fn from(other: &Elf32_Phdr) -> Self {
+        Self {
+            p_type: other.p_type,
+            p_flags: other.p_flags,
+            p_offset: other.p_offset.into(),
+            p_vaddr: other.p_vaddr.into(),
+            p_paddr: other.p_paddr.into(),
+            p_filesz: other.p_filesz.into(),
+            p_memsz: other.p_memsz.into(),
+            p_align: other.p_align.into(),
+        }
+    }
+}
+
 pub(crate) const PF_X: Elf64_Word = 1;
+pub(crate) const PF_W: Elf64_Word = 2;
+pub(crate) const PF_R: Elf64_Word = 4;
 
 pub(crate) const PN_XNUM: u16 = 0xffff;
 
@@ -74,16 +288,110 @@ pub(crate) struct Elf64_Shdr {
 // SAFETY: `Elf64_Shdr` is valid for any bit pattern.
 unsafe impl Pod for Elf64_Shdr {}
 
+impl ByteSwap for Elf64_Shdr {
+    fn byte_swap(&self) -> Self {
+        Self {
+            sh_name: self.sh_name.swap_bytes(),
+            sh_type: self.sh_type.swap_bytes(),
+            sh_flags: self.sh_flags.swap_bytes(),
+            sh_addr: self.sh_addr.swap_bytes(),
+            sh_offset: self.sh_offset.swap_bytes(),
+            sh_size: self.sh_size.swap_bytes(),
+            sh_link: self.sh_link.swap_bytes(),
+            sh_info: self.sh_info.swap_bytes(),
+            sh_addralign: self.sh_addralign.swap_bytes(),
+            sh_entsize: self.sh_entsize.swap_bytes(),
+        }
+    }
+}
+
+#[derive(Debug)]
+#[repr(C)]
+pub(crate) struct Elf32_Shdr {
+    pub sh_name: Elf32_Word,
+    pub sh_type: Elf32_Word,
+    pub sh_flags: Elf32_Word,
+    pub sh_addr: Elf32_Addr,
+    pub sh_offset: Elf32_Off,
+    pub sh_size: Elf32_Word,
+    pub sh_link: Elf32_Word,
+    pub sh_info: Elf32_Word,
+    pub sh_addralign: Elf32_Word,
+    pub sh_entsize: Elf32_Word,
+}
+
+// SAFETY: `Elf32_Shdr` is valid for any bit pattern.
+unsafe impl Pod for Elf32_Shdr {}
+
+impl ByteSwap for Elf32_Shdr {
+    fn byte_swap(&self) -> Self {
+        Self {
+            sh_name: self.sh_name.swap_bytes(),
+            sh_type: self.sh_type.swap_bytes(),
+            sh_flags: self.sh_flags.swap_bytes(),
+            sh_addr: self.sh_addr.swap_bytes(),
+            sh_offset: self.sh_offset.swap_bytes(),
+            sh_size: self.sh_size.swap_bytes(),
+            sh_link: self.sh_link.swap_bytes(),
+            sh_info: self.sh_info.swap_bytes(),
+            sh_addralign: self.sh_addralign.swap_bytes(),
+            sh_entsize: self.sh_entsize.swap_bytes(),
+        }
+    }
+}
+
+impl From<&Elf32_Shdr> for Elf64_Shdr {
+    fn from(other: &Elf32_Shdr) -> Self {
+        Self {
+            sh_name: other.sh_name,
+            sh_type: other.sh_type,
+            sh_flags: other.sh_flags.into(),
+            sh_addr: other.sh_addr.into(),
+            sh_offset: other.sh_offset.into(),
+            sh_size: other.sh_size.into(),
+            sh_link: other.sh_link,
+            sh_info: other.sh_info,
+            sh_addralign: other.sh_addralign.into(),
+            sh_entsize: other.sh_entsize.into(),
+        }
+    }
+}
+
+pub(crate) const SHF_ALLOC: u64 = 0x2;
+pub(crate) const SHF_EXECINSTR: u64 = 0x4;
+pub(crate) const SHF_TLS: u64 = 0x400;
 pub(crate) const SHF_COMPRESSED: u64 = 0x800;
 
 pub(crate) const SHN_UNDEF: u16 = 0;
 pub(crate) const SHN_LORESERVE: u16 = 0xff00;
 pub(crate) const SHN_XINDEX: u16 = 0xffff;
 
+#[cfg(test)]
+pub(crate) const SHT_PROGBITS: Elf64_Word = 1;
+pub(crate) const SHT_SYMTAB: Elf64_Word = 2;
+#[cfg(test)]
+pub(crate) const SHT_STRTAB: Elf64_Word = 3;
+pub(crate) const SHT_RELA: Elf64_Word = 4;
+pub(crate) const SHT_DYNAMIC: Elf64_Word = 6;
 pub(crate) const SHT_NOTE: Elf64_Word = 7;
+pub(crate) const SHT_NOBITS: Elf64_Word = 8;
+pub(crate) const SHT_DYNSYM: Elf64_Word = 11;
+pub(crate) const SHT_GNU_HASH: Elf64_Word = 0x6ffffff6;
+// The symbol versioning section types share their values with the
+// `SHT_SUNW_verdef`, `SHT_SUNW_verneed`, and `SHT_SUNW_versym` types
+// they originate from.
+pub(crate) const SHT_GNU_VERDEF: Elf64_Word = 0x6ffffffd;
+pub(crate) const SHT_GNU_VERNEED: Elf64_Word = 0x6ffffffe;
+pub(crate) const SHT_GNU_VERSYM: Elf64_Word = 0x6fffffff;
+
+pub(crate) const STB_LOCAL: u8 = 0;
+pub(crate) const STB_GLOBAL: u8 = 1;
+pub(crate) const STB_WEAK: u8 = 2;
+pub(crate) const STB_GNU_UNIQUE: u8 = 10;
 
 pub(crate) const STT_OBJECT: u8 = 1;
 pub(crate) const STT_FUNC: u8 = 2;
+pub(crate) const STT_TLS: u8 = 6;
 pub(crate) const STT_GNU_IFUNC: u8 = 10;
 
 #[derive(Clone, Debug)]
@@ -104,6 +412,21 @@ impl Elf64_Sym {
         self.st_info & 0xf
     }
 
+    /// Extract the symbols binding, typically represented by a STB_*
+    /// constant.
+    #[inline]
+    pub fn binding(&self) -> u8 {
+        self.st_info >> 4
+    }
+
+    /// Check whether the symbol is a GNU indirect function, i.e., one
+    /// whose value is the address of a resolver function returning the
+    /// actual implementation at runtime.
+    #[inline]
+    pub fn is_ifunc(&self) -> bool {
+        self.type_() == STT_GNU_IFUNC
+    }
+
     /// Check whether the symbol's type matches that represented by the
     /// given [`SymType`].
     #[inline]
@@ -111,13 +434,24 @@ impl Elf64_Sym {
         let elf_ty = self.type_();
         let is_func = elf_ty == STT_FUNC || elf_ty == STT_GNU_IFUNC;
         let is_var = elf_ty == STT_OBJECT;
+        let is_tls = elf_ty == STT_TLS;
 
         match type_ {
-            SymType::Undefined => is_func || is_var,
+            SymType::Undefined => is_func || is_var || is_tls,
             SymType::Function => is_func,
             SymType::Variable => is_var,
+            SymType::Tls => is_tls,
         }
     }
+
+    /// Check whether the symbol's binding matches the given one, if
+    /// any.
+    #[inline]
+    pub fn matches_binding(&self, binding: Option<SymBinding>) -> bool {
+        binding
+            .map(|binding| SymBinding::try_from(self) == Ok(binding))
+            .unwrap_or(true)
+    }
 }
 
 impl TryFrom<&Elf64_Sym> for SymType {
@@ -127,6 +461,21 @@ impl TryFrom<&Elf64_Sym> for SymType {
         match other.type_() {
             STT_FUNC | STT_GNU_IFUNC => Ok(SymType::Function),
             STT_OBJECT => Ok(SymType::Variable),
+            STT_TLS => Ok(SymType::Tls),
+            _ => Err(()),
+        }
+    }
+}
+
+impl TryFrom<&Elf64_Sym> for SymBinding {
+    type Error = ();
+
+    fn try_from(other: &Elf64_Sym) -> Result<Self, Self::Error> {
+        match other.binding() {
+            STB_LOCAL => Ok(SymBinding::Local),
+            // `STB_GNU_UNIQUE` is a GNU extension to `STB_GLOBAL`.
+            STB_GLOBAL | STB_GNU_UNIQUE => Ok(SymBinding::Global),
+            STB_WEAK => Ok(SymBinding::Weak),
             _ => Err(()),
         }
     }
@@ -135,7 +484,299 @@ impl TryFrom<&Elf64_Sym> for SymType {
 // SAFETY: `Elf64_Sym` is valid for any bit pattern.
 unsafe impl Pod for Elf64_Sym {}
 
+impl ByteSwap for Elf64_Sym {
+    fn byte_swap(&self) -> Self {
+        Self {
+            st_name: self.st_name.swap_bytes(),
+            st_info: self.st_info,
+            st_other: self.st_other,
+            st_shndx: self.st_shndx.swap_bytes(),
+            st_value: self.st_value.swap_bytes(),
+            st_size: self.st_size.swap_bytes(),
+        }
+    }
+}
+
+#[derive(Debug)]
+#[repr(C)]
+pub(crate) struct Elf32_Sym {
+    pub st_name: Elf32_Word,
+    pub st_value: Elf32_Addr,
+    pub st_size: Elf32_Word,
+    pub st_info: u8,
+    pub st_other: u8,
+    pub st_shndx: Elf32_Half,
+}
+
+// SAFETY: `Elf32_Sym` is valid for any bit pattern.
+unsafe impl Pod for Elf32_Sym {}
+
+impl ByteSwap for Elf32_Sym {
+    fn byte_swap(&self) -> Self {
+        Self {
+            st_name: self.st_name.swap_bytes(),
+            st_value: self.st_value.swap_bytes(),
+            st_size: self.st_size.swap_bytes(),
+            st_info: self.st_info,
+            st_other: self.st_other,
+            st_shndx: self.st_shndx.swap_bytes(),
+        }
+    }
+}
+
+impl From<&Elf32_Sym> for Elf64_Sym {
+    fn from(other: &Elf32_Sym) -> Self {
+        Self {
+            st_name: other.st_name,
+            st_info: other.st_info,
+            st_other: other.st_other,
+            st_shndx: other.st_shndx,
+            st_value: other.st_value.into(),
+            st_size: other.st_size.into(),
+        }
+    }
+}
+
+pub(crate) const DT_NULL: i64 = 0;
+pub(crate) const DT_NEEDED: i64 = 1;
+pub(crate) const DT_SONAME: i64 = 14;
+pub(crate) const DT_RPATH: i64 = 15;
+pub(crate) const DT_RUNPATH: i64 = 29;
+pub(crate) const DT_VERSYM: i64 = 0x6ffffff0;
+pub(crate) const DT_VERDEF: i64 = 0x6ffffffc;
+pub(crate) const DT_VERDEFNUM: i64 = 0x6ffffffd;
+pub(crate) const DT_VERNEED: i64 = 0x6ffffffe;
+pub(crate) const DT_VERNEEDNUM: i64 = 0x6fffffff;
+
+/// An entry of the `.dynamic` section.
+#[derive(Debug)]
+#[repr(C)]
+pub(crate) struct Elf64_Dyn {
+    /// The kind of entry (`DT_*`).
+    pub d_tag: i64,
+    /// The entry's value; an integer, an address, or an offset,
+    /// depending on `d_tag`.
+    pub d_val: Elf64_Xword,
+}
+
+// SAFETY: `Elf64_Dyn` is valid for any bit pattern.
+unsafe impl Pod for Elf64_Dyn {}
+
+impl ByteSwap for Elf64_Dyn {
+    fn byte_swap(&self) -> Self {
+        Self {
+            d_tag: self.d_tag.swap_bytes(),
+            d_val: self.d_val.swap_bytes(),
+        }
+    }
+}
+
+/// An entry of the `.dynamic` section of an ELF32 file.
+#[derive(Debug)]
+#[repr(C)]
+pub(crate) struct Elf32_Dyn {
+    /// The kind of entry (`DT_*`).
+    pub d_tag: Elf32_Sword,
+    /// The entry's value; an integer, an address, or an offset,
+    /// depending on `d_tag`.
+    pub d_val: Elf32_Word,
+}
+
+// SAFETY: `Elf32_Dyn` is valid for any bit pattern.
+unsafe impl Pod for Elf32_Dyn {}
+
+impl ByteSwap for Elf32_Dyn {
+    fn byte_swap(&self) -> Self {
+        Self {
+            d_tag: self.d_tag.swap_bytes(),
+            d_val: self.d_val.swap_bytes(),
+        }
+    }
+}
+
+impl From<&Elf32_Dyn> for Elf64_Dyn {
+    fn from(other: &Elf32_Dyn) -> Self {
+        Self {
+            d_tag: other.d_tag.into(),
+            d_val: other.d_val.into(),
+        }
+    }
+}
+
+/// A relocation entry with explicit addend, as found in `.rela.*`
+/// sections.
+#[derive(Debug)]
+#[repr(C)]
+pub(crate) struct Elf64_Rela {
+    /// The location at which to apply the relocation.
+    pub r_offset: Elf64_Addr,
+    /// The index of the symbol the relocation refers to, along with
+    /// the relocation type.
+    pub r_info: Elf64_Xword,
+    /// The constant addend used to compute the relocated value.
+    pub r_addend: i64,
+}
+
+impl Elf64_Rela {
+    /// Retrieve the index of the symbol the relocation refers to.
+    #[inline]
+    pub fn sym(&self) -> u32 {
+        (self.r_info >> 32) as u32
+    }
+
+    /// Retrieve the relocation type.
+    #[inline]
+    pub fn type_(&self) -> u32 {
+        (self.r_info & 0xffffffff) as u32
+    }
+}
+
+// SAFETY: `Elf64_Rela` is valid for any bit pattern.
+unsafe impl Pod for Elf64_Rela {}
+
+impl ByteSwap for Elf64_Rela {
+    fn byte_swap(&self) -> Self {
+        Self {
+            r_offset: self.r_offset.swap_bytes(),
+            r_info: self.r_info.swap_bytes(),
+            r_addend: self.r_addend.swap_bytes(),
+        }
+    }
+}
+
+/// A relocation creating a PLT entry, i.e., setting a GOT entry to the
+/// address of the referenced symbol.
+pub(crate) const R_X86_64_JUMP_SLOT: u32 = 7;
+
+/// The version index of local symbols.
+pub(crate) const VER_NDX_LOCAL: Elf64_Half = 0;
+/// The version index of unversioned global symbols.
+pub(crate) const VER_NDX_GLOBAL: Elf64_Half = 1;
+/// The bit marking a symbol version as hidden, i.e., as not being the
+/// default version of the symbol.
+pub(crate) const VERSYM_HIDDEN: Elf64_Half = 0x8000;
+/// The mask extracting the version index from a `.gnu.version`
+/// entry.
+pub(crate) const VERSYM_VERSION: Elf64_Half = 0x7fff;
+/// The flag marking the version definition of the file itself.
+pub(crate) const VER_FLG_BASE: Elf64_Half = 0x1;
+
+/// A version definition, as found in the `.gnu.version_d` section.
+#[derive(Debug)]
+#[repr(C)]
+pub(crate) struct Elf64_Verdef {
+    pub vd_version: Elf64_Half,
+    pub vd_flags: Elf64_Half,
+    pub vd_ndx: Elf64_Half,
+    pub vd_cnt: Elf64_Half,
+    pub vd_hash: Elf64_Word,
+    /// The offset of the first `Elf64_Verdaux` entry, relative to this
+    /// entry.
+    pub vd_aux: Elf64_Word,
+    /// The offset of the next `Elf64_Verdef` entry, relative to this
+    /// entry, or zero for the last one.
+    pub vd_next: Elf64_Word,
+}
+
+// SAFETY: `Elf64_Verdef` is valid for any bit pattern.
+unsafe impl Pod for Elf64_Verdef {}
+
+impl ByteSwap for Elf64_Verdef {
+    fn byte_swap(&self) -> Self {
+        Self {
+            vd_version: self.vd_version.swap_bytes(),
+            vd_flags: self.vd_flags.swap_bytes(),
+            vd_ndx: self.vd_ndx.swap_bytes(),
+            vd_cnt: self.vd_cnt.swap_bytes(),
+            vd_hash: self.vd_hash.swap_bytes(),
+            vd_aux: self.vd_aux.swap_bytes(),
+            vd_next: self.vd_next.swap_bytes(),
+        }
+    }
+}
+
+/// An auxiliary version definition entry, carrying the version name.
+#[derive(Debug)]
+#[repr(C)]
+pub(crate) struct Elf64_Verdaux {
+    pub vda_name: Elf64_Word,
+    pub vda_next: Elf64_Word,
+}
+
+// SAFETY: `Elf64_Verdaux` is valid for any bit pattern.
+unsafe impl Pod for Elf64_Verdaux {}
+
+impl ByteSwap for Elf64_Verdaux {
+    fn byte_swap(&self) -> Self {
+        Self {
+            vda_name: self.vda_name.swap_bytes(),
+            vda_next: self.vda_next.swap_bytes(),
+        }
+    }
+}
+
+/// A version dependency, as found in the `.gnu.version_r` section.
+#[derive(Debug)]
+#[repr(C)]
+pub(crate) struct Elf64_Verneed {
+    pub vn_version: Elf64_Half,
+    pub vn_cnt: Elf64_Half,
+    pub vn_file: Elf64_Word,
+    /// The offset of the first `Elf64_Vernaux` entry, relative to this
+    /// entry.
+    pub vn_aux: Elf64_Word,
+    /// The offset of the next `Elf64_Verneed` entry, relative to this
+    /// entry, or zero for the last one.
+    pub vn_next: Elf64_Word,
+}
+
+// SAFETY: `Elf64_Verneed` is valid for any bit pattern.
+unsafe impl Pod for Elf64_Verneed {}
+
+impl ByteSwap for Elf64_Verneed {
+    fn byte_swap(&self) -> Self {
+        Self {
+            vn_version: self.vn_version.swap_bytes(),
+            vn_cnt: self.vn_cnt.swap_bytes(),
+            vn_file: self.vn_file.swap_bytes(),
+            vn_aux: self.vn_aux.swap_bytes(),
+            vn_next: self.vn_next.swap_bytes(),
+        }
+    }
+}
+
+/// An auxiliary version dependency entry, describing a single
+/// required version.
+#[derive(Debug)]
+#[repr(C)]
+pub(crate) struct Elf64_Vernaux {
+    pub vna_hash: Elf64_Word,
+    pub vna_flags: Elf64_Half,
+    /// The version index used in `.gnu.version` to refer to this
+    /// version.
+    pub vna_other: Elf64_Half,
+    pub vna_name: Elf64_Word,
+    pub vna_next: Elf64_Word,
+}
+
+// SAFETY: `Elf64_Vernaux` is valid for any bit pattern.
+unsafe impl Pod for Elf64_Vernaux {}
+
+impl ByteSwap for Elf64_Vernaux {
+    fn byte_swap(&self) -> Self {
+        Self {
+            vna_hash: self.vna_hash.swap_bytes(),
+            vna_flags: self.vna_flags.swap_bytes(),
+            vna_other: self.vna_other.swap_bytes(),
+            vna_name: self.vna_name.swap_bytes(),
+            vna_next: self.vna_next.swap_bytes(),
+        }
+    }
+}
+
 pub(crate) const NT_GNU_BUILD_ID: Elf64_Word = 3;
+/// A note describing the files backing the mappings in a core dump.
+pub(crate) const NT_FILE: Elf64_Word = 0x46494c45;
 
 #[derive(Debug)]
 #[repr(C)]
@@ -148,6 +789,16 @@ pub(crate) struct Elf64_Nhdr {
 // SAFETY: `Elf64_Nhdr` is valid for any bit pattern.
 unsafe impl Pod for Elf64_Nhdr {}
 
+impl ByteSwap for Elf64_Nhdr {
+    fn byte_swap(&self) -> Self {
+        Self {
+            n_namesz: self.n_namesz.swap_bytes(),
+            n_descsz: self.n_descsz.swap_bytes(),
+            n_type: self.n_type.swap_bytes(),
+        }
+    }
+}
+
 
 #[derive(Debug)]
 #[repr(C)]
@@ -166,6 +817,55 @@ pub(crate) struct Elf64_Chdr {
 // SAFETY: `Elf64_Chdr` is valid for any bit pattern.
 unsafe impl Pod for Elf64_Chdr {}
 
+impl ByteSwap for Elf64_Chdr {
+    fn byte_swap(&self) -> Self {
+        Self {
+            ch_type: self.ch_type.swap_bytes(),
+            ch_reserved: self.ch_reserved.swap_bytes(),
+            ch_size: self.ch_size.swap_bytes(),
+            ch_addralign: self.ch_addralign.swap_bytes(),
+        }
+    }
+}
+
+
+#[derive(Debug)]
+#[repr(C)]
+pub(crate) struct Elf32_Chdr {
+    /// Compression format.
+    ///
+    /// See `ELFCOMPRESS_*` constants for supported values.
+    pub ch_type: Elf32_Word,
+    /// Uncompressed data size.
+    pub ch_size: Elf32_Word,
+    /// Uncompressed data alignment.
+    pub ch_addralign: Elf32_Word,
+}
+
+// SAFETY: `Elf32_Chdr` is valid for any bit pattern.
+unsafe impl Pod for Elf32_Chdr {}
+
+impl ByteSwap for Elf32_Chdr {
+    fn byte_swap(&self) -> Self {
+        Self {
+            ch_type: self.ch_type.swap_bytes(),
+            ch_size: self.ch_size.swap_bytes(),
+            ch_addralign: self.ch_addralign.swap_bytes(),
+        }
+    }
+}
+
+impl From<&Elf32_Chdr> for Elf64_Chdr {
+    fn from(other: &Elf32_Chdr) -> Self {
+        Self {
+            ch_type: other.ch_type,
+            ch_reserved: 0,
+            ch_size: other.ch_size.into(),
+            ch_addralign: other.ch_addralign.into(),
+        }
+    }
+}
+
 
 /// zlib/deflate algorithm.
 pub(crate) const ELFCOMPRESS_ZLIB: u32 = 1;
@@ -11,6 +11,10 @@ use std::fmt::Result as FmtResult;
 use std::io;
 use std::mem::transmute;
 use std::ops::Deref;
+#[cfg(feature = "backtrace")]
+use std::sync::atomic::AtomicBool;
+#[cfg(feature = "backtrace")]
+use std::sync::atomic::Ordering;
 
 
 mod private {
@@ -96,6 +100,21 @@ impl IntoCowStr for String {
     }
 }
 
+/// Whether to capture backtraces irrespective of the environment's
+/// configuration.
+#[cfg(feature = "backtrace")]
+static FORCE_BACKTRACE_CAPTURE: AtomicBool = AtomicBool::new(false);
+
+/// Capture a backtrace, honoring the crate level configuration.
+#[cfg(feature = "backtrace")]
+fn capture_backtrace() -> Backtrace {
+    if FORCE_BACKTRACE_CAPTURE.load(Ordering::Relaxed) {
+        Backtrace::force_capture()
+    } else {
+        Backtrace::capture()
+    }
+}
+
 enum ErrorImpl {
     #[cfg(feature = "dwarf")]
     Dwarf {
@@ -163,8 +182,9 @@ impl ErrorImpl {
             Self::Dwarf { backtrace, .. } => Some(backtrace),
             Self::Io { backtrace, .. } => Some(backtrace),
             Self::Std { backtrace, .. } => Some(backtrace),
-            Self::ContextOwned { .. } => None,
-            Self::ContextStatic { .. } => None,
+            Self::ContextOwned { source, .. } | Self::ContextStatic { source, .. } => {
+                source.backtrace()
+            }
         }
     }
 
@@ -174,6 +194,19 @@ impl ErrorImpl {
         None
     }
 
+    /// Retrieve the terminal (i.e., non-context) error of the chain.
+    fn terminal(&self) -> &(dyn StdError + 'static) {
+        match self {
+            #[cfg(feature = "dwarf")]
+            Self::Dwarf { error, .. } => error,
+            Self::Io { error, .. } => error,
+            Self::Std { error, .. } => error.deref(),
+            Self::ContextOwned { source, .. } | Self::ContextStatic { source, .. } => {
+                source.terminal()
+            }
+        }
+    }
+
     #[cfg(test)]
     fn is_owned(&self) -> Option<bool> {
         match self {
@@ -324,6 +357,25 @@ pub enum ErrorKind {
     Other,
 }
 
+impl From<ErrorKind> for io::ErrorKind {
+    fn from(other: ErrorKind) -> Self {
+        match other {
+            ErrorKind::NotFound => io::ErrorKind::NotFound,
+            ErrorKind::PermissionDenied => io::ErrorKind::PermissionDenied,
+            ErrorKind::AlreadyExists => io::ErrorKind::AlreadyExists,
+            ErrorKind::WouldBlock => io::ErrorKind::WouldBlock,
+            ErrorKind::InvalidInput => io::ErrorKind::InvalidInput,
+            ErrorKind::InvalidData | ErrorKind::InvalidDwarf => io::ErrorKind::InvalidData,
+            ErrorKind::TimedOut => io::ErrorKind::TimedOut,
+            ErrorKind::WriteZero => io::ErrorKind::WriteZero,
+            ErrorKind::Unsupported => io::ErrorKind::Unsupported,
+            ErrorKind::UnexpectedEof => io::ErrorKind::UnexpectedEof,
+            ErrorKind::OutOfMemory => io::ErrorKind::OutOfMemory,
+            ErrorKind::Other => io::ErrorKind::Other,
+        }
+    }
+}
+
 impl ErrorKind {
     #[doc(hidden)]
     #[inline]
@@ -412,6 +464,9 @@ impl ErrorKind {
 /// - If you want only errors to have backtraces, set `RUST_LIB_BACKTRACE=1`
 /// - If you want only panics to have backtraces, set `RUST_BACKTRACE=1` and
 ///   `RUST_LIB_BACKTRACE=0`
+///
+/// Alternatively, capturing can be forced irrespective of the environment by
+/// means of [`Error::force_backtrace_capture`].
 // Representation is optimized for fast copying (a single machine word),
 // not so much for fast creation (as it is heap allocated). We generally
 // expect errors to be exceptional, though a lot of functionality is
@@ -448,7 +503,6 @@ impl Error {
         Self::with_io_error(io::ErrorKind::InvalidData, error)
     }
 
-    #[cfg(feature = "breakpad")]
     #[inline]
     pub(crate) fn with_invalid_input<E>(error: E) -> Self
     where
@@ -472,6 +526,112 @@ impl Error {
         self.error.kind()
     }
 
+    /// Force the capturing of backtraces for errors created from now on,
+    /// irrespective of the `RUST_BACKTRACE` and `RUST_LIB_BACKTRACE`
+    /// environment variables.
+    ///
+    /// This setting is process wide. When disabled (the default), the
+    /// environment variables decide whether a backtrace is captured.
+    /// The setting has no effect if the `backtrace` feature is disabled.
+    pub fn force_backtrace_capture(force: bool) {
+        #[cfg(feature = "backtrace")]
+        let () = FORCE_BACKTRACE_CAPTURE.store(force, Ordering::Relaxed);
+        #[cfg(not(feature = "backtrace"))]
+        let _force = force;
+    }
+
+    /// Retrieve the backtrace captured when the terminal error of the
+    /// chain was created, if any.
+    ///
+    /// Note that a backtrace may be present but without frames, if
+    /// backtrace capturing was disabled at the time. Use
+    /// [`Backtrace::status`] to check.
+    #[inline]
+    pub fn backtrace(&self) -> Option<&Backtrace> {
+        self.error.backtrace()
+    }
+
+    /// Retrieve the root cause of this `Error`, i.e., the deepest
+    /// error in the chain.
+    ///
+    /// ```
+    /// use std::fs::File;
+    /// # use blazesym::ErrorExt as _;
+    ///
+    /// let result = File::open("/does-not-exist").context("failed to open file");
+    /// let err = result.unwrap_err();
+    /// let root = err.root_cause();
+    /// assert!(root.to_string().starts_with("No such file or directory"));
+    /// ```
+    pub fn root_cause(&self) -> &(dyn StdError + 'static) {
+        let mut error = self.error.terminal();
+        while let Some(source) = error.source() {
+            error = source;
+        }
+        error
+    }
+
+    /// Produce a human readable report of this `Error`, containing its
+    /// kind and the chain of causes.
+    ///
+    /// In contrast to the `Debug` representation, the report is
+    /// deterministic: it never includes a backtrace and does not depend
+    /// on the `Debug` representation of any wrapped error (such as
+    /// [`std::io::Error`]), the format of which is not guaranteed to
+    /// be stable. It consists of the top-level message and the
+    /// [`ErrorKind`] in its `Debug` representation, followed by one
+    /// indented line per cause, if any:
+    /// ```text
+    /// Error: outer context
+    /// Kind: NotFound
+    ///
+    /// Caused by:
+    ///     No such file or directory (os error 2)
+    /// ```
+    ///
+    /// ```
+    /// use std::fs::File;
+    /// # use blazesym::ErrorExt as _;
+    ///
+    /// let result = File::open("/does-not-exist").context("failed to open file");
+    /// let err = result.unwrap_err();
+    /// assert!(err.report().starts_with("Error: failed to open file\nKind: NotFound\n"));
+    /// ```
+    pub fn report(&self) -> String {
+        let mut report = format!("Error: {self}\nKind: {:?}", self.kind());
+        let mut error = self.source();
+        if error.is_some() {
+            let () = report.push_str("\n\nCaused by:");
+        }
+        while let Some(err) = error {
+            let () = report.push_str(&format!("\n    {err}"));
+            error = err.source();
+        }
+        report
+    }
+
+    /// Attempt to downcast the terminal error of the chain (i.e., the
+    /// one not providing additional context) to a reference of the
+    /// given type.
+    ///
+    /// ```
+    /// use std::fs::File;
+    /// use std::io;
+    /// # use blazesym::ErrorExt as _;
+    ///
+    /// let result = File::open("/does-not-exist").context("failed to open file");
+    /// let err = result.unwrap_err();
+    /// let io_err = err.downcast_ref::<io::Error>().unwrap();
+    /// assert_eq!(io_err.kind(), io::ErrorKind::NotFound);
+    /// ```
+    #[inline]
+    pub fn downcast_ref<E>(&self) -> Option<&E>
+    where
+        E: StdError + 'static,
+    {
+        self.error.terminal().downcast_ref::<E>()
+    }
+
     /// Layer the provided context on top of this `Error`, creating a
     /// new one in the process.
     fn layer_context(self, context: Cow<'static, Str>) -> Self {
@@ -520,7 +680,7 @@ impl From<gimli::Error> for Error {
             error: Box::new(ErrorImpl::Dwarf {
                 error: other,
                 #[cfg(feature = "backtrace")]
-                backtrace: Backtrace::capture(),
+                backtrace: capture_backtrace(),
             }),
         }
     }
@@ -532,7 +692,7 @@ impl From<io::Error> for Error {
             error: Box::new(ErrorImpl::Io {
                 error: other,
                 #[cfg(feature = "backtrace")]
-                backtrace: Backtrace::capture(),
+                backtrace: capture_backtrace(),
             }),
         }
     }
@@ -548,12 +708,28 @@ impl From<Box<dyn StdError + Send + Sync + 'static>> for Error {
                 // because no trait exposes it. So the best we can do is capture
                 // a backtrace ourselves. Sigh.
                 #[cfg(feature = "backtrace")]
-                backtrace: Backtrace::capture(),
+                backtrace: capture_backtrace(),
             }),
         }
     }
 }
 
+impl From<Error> for io::Error {
+    /// Convert an [`Error`] into an [`io::Error`].
+    ///
+    /// The [`ErrorKind`] is mapped to its [`io::ErrorKind`] equivalent.
+    /// An [`Error`] directly wrapping an [`io::Error`] (without any
+    /// additional context) is unwrapped. In all other cases, the
+    /// [`Error`] is preserved as the inner error of the resulting
+    /// object.
+    fn from(other: Error) -> Self {
+        match *other.error {
+            ErrorImpl::Io { error, .. } => error,
+            _ => io::Error::new(other.kind().into(), other),
+        }
+    }
+}
+
 
 /// A trait providing ergonomic chaining capabilities to [`Error`].
 pub trait ErrorExt: private::Sealed {
@@ -739,6 +915,94 @@ mod tests {
         assert_eq!(err.kind(), ErrorKind::Other);
     }
 
+    /// Check that [`ErrorKind`] values round-trip through
+    /// [`io::Error`] conversions.
+    #[test]
+    fn io_error_kind_round_trip() {
+        let kinds = [
+            (io::ErrorKind::NotFound, ErrorKind::NotFound),
+            (io::ErrorKind::PermissionDenied, ErrorKind::PermissionDenied),
+            (io::ErrorKind::AlreadyExists, ErrorKind::AlreadyExists),
+            (io::ErrorKind::WouldBlock, ErrorKind::WouldBlock),
+            (io::ErrorKind::InvalidInput, ErrorKind::InvalidInput),
+            (io::ErrorKind::InvalidData, ErrorKind::InvalidData),
+            (io::ErrorKind::TimedOut, ErrorKind::TimedOut),
+            (io::ErrorKind::WriteZero, ErrorKind::WriteZero),
+            (io::ErrorKind::Unsupported, ErrorKind::Unsupported),
+            (io::ErrorKind::UnexpectedEof, ErrorKind::UnexpectedEof),
+            (io::ErrorKind::OutOfMemory, ErrorKind::OutOfMemory),
+            (io::ErrorKind::Other, ErrorKind::Other),
+        ];
+
+        for (io_kind, kind) in kinds {
+            assert_eq!(io::ErrorKind::from(kind), io_kind);
+
+            let err = Error::from(io::Error::new(io_kind, "some error"));
+            assert_eq!(err.kind(), kind);
+            let io_err = io::Error::from(err);
+            assert_eq!(io_err.kind(), io_kind);
+            assert_eq!(io_err.to_string(), "some error");
+
+            // Also check that the kind is preserved when context is
+            // involved.
+            let err = Error::from(io::Error::new(io_kind, "some error")).context("context");
+            let io_err = io::Error::from(err);
+            assert_eq!(io_err.kind(), io_kind);
+            assert_eq!(io_err.to_string(), "context");
+
+            let inner = io_err.get_ref().unwrap().downcast_ref::<Error>().unwrap();
+            assert_eq!(inner.kind(), kind);
+            assert_eq!(format!("{inner:#}"), "context: some error");
+        }
+
+        assert_eq!(
+            io::ErrorKind::from(ErrorKind::InvalidDwarf),
+            io::ErrorKind::InvalidData
+        );
+    }
+
+    /// Check that we can retrieve the root cause of an error and
+    /// downcast the terminal error.
+    #[test]
+    fn root_cause_and_downcasting() {
+        let err = io::Error::new(io::ErrorKind::InvalidData, "some invalid data");
+        let err = Error::from(err);
+        assert_eq!(err.root_cause().to_string(), "some invalid data");
+        assert_eq!(
+            err.downcast_ref::<io::Error>().unwrap().kind(),
+            io::ErrorKind::InvalidData
+        );
+
+        let err = err.context("inner context").context("outer context");
+        assert_eq!(err.root_cause().to_string(), "some invalid data");
+        assert_eq!(
+            err.downcast_ref::<io::Error>().unwrap().kind(),
+            io::ErrorKind::InvalidData
+        );
+
+        let inner = io::Error::new(io::ErrorKind::NotFound, "not found");
+        let outer = io::Error::new(io::ErrorKind::Other, inner);
+        let err = Box::new(outer) as Box<dyn StdError + Send + Sync + 'static>;
+        let err = Error::from(err).context("context");
+        assert_eq!(err.root_cause().to_string(), "not found");
+        assert_eq!(
+            err.downcast_ref::<io::Error>().unwrap().to_string(),
+            "not found"
+        );
+        assert!(err.downcast_ref::<Error>().is_none());
+    }
+
+    /// Append the formatted backtrace of `err` to `expected`, if one
+    /// was captured.
+    fn with_backtrace(err: &Error, expected: &str) -> String {
+        match err.backtrace() {
+            Some(backtrace) if backtrace.status() == BacktraceStatus::Captured => {
+                format!("{expected}\n\nStack backtrace:\n{backtrace}")
+            }
+            _ => expected.to_string(),
+        }
+    }
+
     /// Check that we can format errors as expected.
     #[test]
     fn error_formatting() {
@@ -773,7 +1037,7 @@ mod tests {
 
 Caused by:
     some invalid data"#;
-        assert_eq!(format!("{err:?}"), expected);
+        assert_eq!(format!("{err:?}"), with_backtrace(&err, expected));
         // Nope, not going to bother.
         assert_ne!(format!("{err:#?}"), "");
 
@@ -793,7 +1057,32 @@ Caused by:
 Caused by:
     inner context
     some invalid data"#;
-        assert_eq!(format!("{err:?}"), expected);
+        assert_eq!(format!("{err:?}"), with_backtrace(&err, expected));
         assert_ne!(format!("{err:#?}"), "");
     }
+
+    /// Check that error reports are formatted as expected.
+    #[test]
+    fn error_reporting() {
+        let err = io::Error::new(io::ErrorKind::InvalidData, "some invalid data");
+        let err = Error::from(err);
+        assert_eq!(err.report(), "Error: some invalid data\nKind: InvalidData");
+
+        let err = err.context("inner context");
+        let expected = r#"Error: inner context
+Kind: InvalidData
+
+Caused by:
+    some invalid data"#;
+        assert_eq!(err.report(), expected);
+
+        let err = err.context("outer context".to_string());
+        let expected = r#"Error: outer context
+Kind: InvalidData
+
+Caused by:
+    inner context
+    some invalid data"#;
+        assert_eq!(err.report(), expected);
+    }
 }
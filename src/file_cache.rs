@@ -1,10 +1,13 @@
+use std::cell::Cell;
 use std::fs::File;
 use std::marker::PhantomData;
+use std::os::unix::io::BorrowedFd;
 use std::path::Path;
 use std::path::PathBuf;
 
 use crate::insert_map::InsertMap;
 use crate::once::OnceCell;
+use crate::util::fstat;
 use crate::util::stat;
 use crate::ErrorExt as _;
 use crate::Result;
@@ -37,9 +40,56 @@ impl From<&libc::stat> for FileMeta {
 }
 
 
+/// Statistics about the usage of a cache.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CacheStats {
+    /// The number of entries currently present in the cache.
+    ///
+    /// Note that entries are never evicted and stale ones (for files
+    /// that changed since they were cached) are included in this
+    /// count.
+    pub entries: usize,
+    /// The number of lookups that could be satisfied by an existing
+    /// entry.
+    pub hits: u64,
+    /// The number of lookups that required the creation of a new
+    /// entry.
+    pub misses: u64,
+    /// An estimate of the memory used by cached data, in bytes.
+    ///
+    /// The estimate is based on the sizes of the files backing cache
+    /// entries and does not account for data structures derived from
+    /// them.
+    pub bytes_estimate: u64,
+    /// The struct is non-exhaustive and open to extension.
+    #[doc(hidden)]
+    pub _non_exhaustive: (),
+}
+
+impl CacheStats {
+    /// Accumulate the statistics of another cache into this object.
+    pub(crate) fn add(&mut self, other: &Self) {
+        let Self {
+            entries,
+            hits,
+            misses,
+            bytes_estimate,
+            _non_exhaustive: (),
+        } = other;
+
+        self.entries += entries;
+        self.hits += hits;
+        self.misses += misses;
+        self.bytes_estimate += bytes_estimate;
+    }
+}
+
+
 #[derive(Debug, Eq, Hash, PartialEq)]
 struct EntryMeta {
-    path: PathBuf,
+    /// The path to the file, if known. Entries for files without path
+    /// are identified by their meta data alone.
+    path: Option<PathBuf>,
     meta: Option<FileMeta>,
 }
 
@@ -48,10 +98,20 @@ impl EntryMeta {
     /// modification times and other meta data are effectively ignored.
     fn new(path: PathBuf, stat: Option<&libc::stat>) -> Self {
         Self {
-            path,
+            path: Some(path),
             meta: stat.map(FileMeta::from),
         }
     }
+
+    /// Create a new [`EntryMeta`] object for a file without path,
+    /// keyed by the device and inode (as well as size and
+    /// modification time) reported in `stat`.
+    fn without_path(stat: &libc::stat) -> Self {
+        Self {
+            path: None,
+            meta: Some(FileMeta::from(stat)),
+        }
+    }
 }
 
 
@@ -102,6 +162,9 @@ impl<T> Builder<T> {
         FileCache {
             cache: InsertMap::new(),
             auto_reload,
+            hits: Cell::new(0),
+            misses: Cell::new(0),
+            bytes: Cell::new(0),
         }
     }
 }
@@ -130,6 +193,12 @@ pub(crate) struct FileCache<T> {
     /// Whether or not to automatically reload files that were updated
     /// since the last open.
     auto_reload: bool,
+    /// The number of lookups satisfied by an existing entry.
+    hits: Cell<u64>,
+    /// The number of lookups that resulted in the creation of an entry.
+    misses: Cell<u64>,
+    /// The accumulated size of all files with an entry.
+    bytes: Cell<u64>,
 }
 
 impl<T> FileCache<T> {
@@ -149,19 +218,68 @@ impl<T> FileCache<T> {
         };
 
         let meta = EntryMeta::new(path.to_path_buf(), stat.as_ref());
-        let entry = self.cache.get_or_try_insert(meta, || {
+        let mut miss = false;
+        let result = self.cache.get_or_try_insert(meta, || {
+            miss = true;
             // We may end up associating this file with a potentially
             // outdated `stat` (which could have change), but the only
             // consequence is that we'd create a new entry again in the
             // future. On the bright side, we save one `stat` call.
             let file = File::open(path)
                 .with_context(|| format!("failed to open file {}", path.display()))?;
+            let size = match &stat {
+                Some(stat) => stat.st_size as u64,
+                None => file.metadata().map(|meta| meta.len()).unwrap_or(0),
+            };
+            let () = self.bytes.set(self.bytes.get() + size);
             let entry = Entry::new(file);
             Ok(entry)
-        })?;
+        });
+
+        let counter = if miss { &self.misses } else { &self.hits };
+        let () = counter.set(counter.get() + 1);
 
+        let entry = result?;
         Ok((&entry.file, &entry.value))
     }
+
+    /// Retrieve an entry for the file referenced by the given file
+    /// descriptor.
+    ///
+    /// Entries created this way are identified by the file's device
+    /// and inode number, as opposed to its path. A copy of `fd` is
+    /// stored inside the cache.
+    pub fn entry_fd(&self, fd: BorrowedFd<'_>) -> Result<(&File, &OnceCell<T>)> {
+        let stat = fstat(fd).context("failed to stat file descriptor")?;
+        let meta = EntryMeta::without_path(&stat);
+        let mut miss = false;
+        let result = self.cache.get_or_try_insert(meta, || {
+            miss = true;
+            let fd = fd
+                .try_clone_to_owned()
+                .context("failed to duplicate file descriptor")?;
+            let () = self.bytes.set(self.bytes.get() + stat.st_size as u64);
+            let entry = Entry::new(File::from(fd));
+            Ok(entry)
+        });
+
+        let counter = if miss { &self.misses } else { &self.hits };
+        let () = counter.set(counter.get() + 1);
+
+        let entry = result?;
+        Ok((&entry.file, &entry.value))
+    }
+
+    /// Retrieve usage statistics of the cache.
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            entries: self.cache.len(),
+            hits: self.hits.get(),
+            misses: self.misses.get(),
+            bytes_estimate: self.bytes.get(),
+            _non_exhaustive: (),
+        }
+    }
 }
 
 impl<T> Default for FileCache<T> {
@@ -177,6 +295,7 @@ mod tests {
 
     use std::io::Read as _;
     use std::io::Write as _;
+    use std::os::unix::io::AsFd as _;
     use std::thread::sleep;
     use std::time::Duration;
 
@@ -214,6 +333,62 @@ mod tests {
         }
     }
 
+    /// Check that cache statistics are tracked properly.
+    #[test]
+    fn stats_tracking() {
+        let cache = FileCache::<usize>::default();
+        let mut tmpfile = NamedTempFile::new().unwrap();
+        let () = tmpfile.write_all(b"foobar").unwrap();
+        assert_eq!(cache.stats(), CacheStats::default());
+
+        let _entry = cache.entry(tmpfile.path()).unwrap();
+        let _entry = cache.entry(tmpfile.path()).unwrap();
+        let _entry = cache.entry(tmpfile.path()).unwrap();
+
+        let stats = cache.stats();
+        assert_eq!(stats.entries, 1);
+        assert_eq!(stats.hits, 2);
+        assert_eq!(stats.misses, 1);
+        assert_eq!(stats.bytes_estimate, 6);
+
+        let mut total = CacheStats::default();
+        let () = total.add(&stats);
+        let () = total.add(&stats);
+        assert_eq!(total.entries, 2);
+        assert_eq!(total.hits, 4);
+        assert_eq!(total.misses, 2);
+        assert_eq!(total.bytes_estimate, 12);
+    }
+
+    /// Check that we can associate data with a file referenced by a
+    /// file descriptor.
+    #[test]
+    fn lookup_fd() {
+        let cache = FileCache::<usize>::default();
+        let tmpfile = NamedTempFile::new().unwrap();
+        let file1 = File::open(tmpfile.path()).unwrap();
+        let file2 = File::open(tmpfile.path()).unwrap();
+
+        {
+            let (_file, cell) = cache.entry_fd(file1.as_fd()).unwrap();
+            assert_eq!(cell.get(), None);
+
+            let () = cell.set(42).unwrap();
+        }
+
+        // A different file descriptor for the same file should map to
+        // the same entry.
+        {
+            let (_file, cell) = cache.entry_fd(file2.as_fd()).unwrap();
+            assert_eq!(cell.get(), Some(&42));
+        }
+
+        let stats = cache.stats();
+        assert_eq!(stats.entries, 1);
+        assert_eq!(stats.hits, 1);
+        assert_eq!(stats.misses, 1);
+    }
+
     /// Make sure that a changed file purges the cache entry.
     #[test]
     fn outdated() {
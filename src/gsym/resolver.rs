@@ -14,6 +14,7 @@ use crate::mmap::Mmap;
 use crate::symbolize::CodeInfo;
 use crate::symbolize::FindSymOpts;
 use crate::symbolize::InlinedFn;
+use crate::symbolize::Provenance;
 use crate::symbolize::Reason;
 use crate::symbolize::ResolvedSym;
 use crate::symbolize::SrcLang;
@@ -117,6 +118,7 @@ impl<'dat> GsymResolver<'dat> {
             file: Cow::Borrowed(OsStr::new(file)),
             line,
             column: None,
+            comp_dir: None,
             _non_exhaustive: (),
         };
         Ok(info)
@@ -198,6 +200,13 @@ impl Symbolize for GsymResolver<'_> {
                 lang,
                 code_info: None,
                 inlined: Box::new([]),
+                provenance: Provenance::Symbol,
+                is_synthetic: false,
+                raw_elf_sym: None,
+                compilation_unit: None,
+                version: None,
+                section: None,
+                linkage_name: None,
             };
             let () = self.fill_code_info(&mut sym, addr, opts, sym_addr, &info)?;
 
@@ -300,6 +309,9 @@ impl GsymResolver<'_> {
                     let inlined_fn = InlinedFn {
                         name: Cow::Borrowed(name),
                         code_info,
+                        addr: None,
+                        offset: None,
+                        origin_addr: None,
                         _non_exhaustive: (),
                     };
                     let () = inlined.push(inlined_fn);
@@ -372,7 +384,7 @@ mod tests {
         // `main` resides at address 0x2000000, and it's located at the given
         // line.
         let sym = resolver
-            .find_sym(0x2000000, &FindSymOpts::CodeInfoAndInlined)
+            .find_sym(0x2000000, &FindSymOpts::CODE_INFO_AND_INLINED)
             .unwrap()
             .unwrap();
         assert_eq!(sym.name, "main");
@@ -385,7 +397,7 @@ mod tests {
         // `factorial` resides at address 0x2000100, and it's located at the
         // given line.
         let sym = resolver
-            .find_sym(0x2000100, &FindSymOpts::CodeInfoAndInlined)
+            .find_sym(0x2000100, &FindSymOpts::CODE_INFO_AND_INLINED)
             .unwrap()
             .unwrap();
         assert_eq!(sym.name, "factorial");
@@ -400,7 +412,7 @@ mod tests {
         // padding bytes/dummy instructions and adjust some more.
         let addr = 0x200020a;
         let sym = resolver
-            .find_sym(addr, &FindSymOpts::CodeInfoAndInlined)
+            .find_sym(addr, &FindSymOpts::CODE_INFO_AND_INLINED)
             .unwrap()
             .unwrap();
         assert_eq!(sym.name, "factorial_inline_test");
@@ -423,7 +435,7 @@ mod tests {
         assert_eq!(frame.line, Some(23));
 
         let sym = resolver
-            .find_sym(addr, &FindSymOpts::CodeInfo)
+            .find_sym(addr, &FindSymOpts::CODE_INFO)
             .unwrap()
             .unwrap();
         assert_eq!(sym.name, "factorial_inline_test");
@@ -56,6 +56,15 @@ impl<K, V> InsertMap<K, V> {
             }
         }
     }
+
+    /// Retrieve the number of key-value pairs in the map.
+    pub(crate) fn len(&self) -> usize {
+        let _borrow = self.refcell.borrow_mut();
+        // SAFETY: We are sure to not borrow mutably twice because the `_borrow`
+        //         guard protects us.
+        let map = unsafe { self.map.as_ptr().as_ref() }.unwrap();
+        map.len()
+    }
 }
 
 impl<K, V> Default for InsertMap<K, V> {
@@ -94,6 +103,7 @@ mod tests {
 
         let s = map.get_or_try_insert(31, || Ok("31 wins")).unwrap();
         assert_eq!(s, &"31 wins");
+        assert_eq!(map.len(), 2);
     }
 
 
@@ -1,23 +1,46 @@
 #[cfg(feature = "breakpad")]
 use std::fs::File;
 use std::ops::Deref as _;
-#[cfg(feature = "breakpad")]
 use std::path::Path;
 
 #[cfg(feature = "breakpad")]
 use crate::breakpad::BreakpadResolver;
 use crate::elf::ElfResolverData;
 use crate::file_cache::FileCache;
+#[cfg(feature = "macho")]
+use crate::macho::MachOResolver;
+#[cfg(feature = "macho")]
+use crate::macho::MachOResolverData;
+#[cfg(feature = "pe")]
+use crate::pe::PeResolver;
+use crate::symbolize::demangle_into;
+use crate::Addr;
+#[cfg(any(
+    feature = "breakpad",
+    feature = "macho",
+    feature = "pe",
+    feature = "type-info"
+))]
+use crate::Error;
 use crate::Result;
 
 #[cfg(feature = "breakpad")]
 use super::source::Breakpad;
 use super::source::Elf;
+#[cfg(feature = "macho")]
+use super::source::MachO;
+#[cfg(feature = "pe")]
+use super::source::Pe;
 use super::source::Source;
+use super::DebugInfo;
 use super::FindAddrOpts;
 use super::Inspect;
+use super::LookupOpts;
 use super::SymInfo;
 use super::SymType;
+#[cfg(feature = "type-info")]
+use super::TypeInfo;
+use super::UnitInfo;
 
 
 /// An inspector of various "sources".
@@ -38,6 +61,10 @@ pub struct Inspector {
     #[cfg(feature = "breakpad")]
     breakpad_cache: FileCache<Box<BreakpadResolver>>,
     elf_cache: FileCache<ElfResolverData>,
+    #[cfg(feature = "macho")]
+    macho_cache: FileCache<MachOResolverData>,
+    #[cfg(feature = "pe")]
+    pe_cache: FileCache<PeResolver>,
 }
 
 impl Inspector {
@@ -48,6 +75,10 @@ impl Inspector {
             breakpad_cache: FileCache::builder().enable_auto_reload(true).build(),
             // TODO: Make auto reloading configurable by clients.
             elf_cache: FileCache::builder().enable_auto_reload(true).build(),
+            #[cfg(feature = "macho")]
+            macho_cache: FileCache::builder().enable_auto_reload(true).build(),
+            #[cfg(feature = "pe")]
+            pe_cache: FileCache::builder().enable_auto_reload(true).build(),
         }
     }
 
@@ -64,23 +95,78 @@ impl Inspector {
         Ok(resolver)
     }
 
+    #[cfg(feature = "macho")]
+    fn macho_resolver<'slf>(
+        &'slf self,
+        path: &Path,
+        arch: Option<&str>,
+        dsym: Option<&Path>,
+    ) -> Result<&'slf MachOResolver> {
+        self.macho_cache.macho_resolver(path, arch, dsym)
+    }
+
+    #[cfg(feature = "pe")]
+    fn pe_resolver<'slf>(&'slf self, path: &Path) -> Result<&'slf PeResolver> {
+        let (file, cell) = self.pe_cache.entry(path)?;
+        let resolver = cell.get_or_try_init(|| PeResolver::from_file(path, file, &[]))?;
+        Ok(resolver)
+    }
+
     /// Look up information (address etc.) about a list of symbols,
     /// given their names.
     ///
+    /// Results are reported grouped per input name, in the order of
+    /// `names`. All names are resolved as part of a single request,
+    /// which is considerably cheaper than looking them up individually
+    /// (e.g., all DWARF compilation units are only traversed once).
+    /// Names not matching any symbol result in an empty list, not in
+    /// an error. Duplicate names are looked up only once, but each
+    /// occurrence reports the same symbols.
+    ///
     /// # Notes
     /// - no symbol name demangling is performed currently
     /// - for the [`Breakpad`](Source::Breakpad) source:
     ///   - no variable support is present
     ///   - file offsets won't be reported
     ///   - addresses are reported as they appear in the symbol source
+    /// - for the [`MachO`](Source::MachO) source:
+    ///   - the symbol table is only consulted for names not found in DWARF
+    ///     debug information
+    ///   - no variable support is present
+    ///   - file offsets won't be reported
+    /// - for the [`Pe`](Source::Pe) source:
+    ///   - symbols are read from a matching PDB file located next to the PE
+    ///     file, if any, as well as from the export table
+    ///   - no variable support is present
+    ///   - addresses are relative virtual addresses
     pub fn lookup<'slf>(
         &'slf self,
         src: &Source,
         names: &[&str],
     ) -> Result<Vec<Vec<SymInfo<'slf>>>> {
+        self.lookup_with_opts(src, names, &LookupOpts::default())
+    }
+
+    /// Look up information (address etc.) about a list of symbols,
+    /// given their names, subject to additional constraints.
+    ///
+    /// This method behaves like [`Inspector::lookup`], except that only
+    /// symbols satisfying the criteria expressed by `opts` are
+    /// reported.
+    pub fn lookup_with_opts<'slf>(
+        &'slf self,
+        src: &Source,
+        names: &[&str],
+        opts: &LookupOpts,
+    ) -> Result<Vec<Vec<SymInfo<'slf>>>> {
+        let LookupOpts {
+            binding,
+            _non_exhaustive: (),
+        } = opts;
         let opts = FindAddrOpts {
             offset_in_file: true,
             sym_type: SymType::Undefined,
+            binding: *binding,
         };
 
         let resolver = match src {
@@ -100,20 +186,34 @@ impl Inspector {
                 let resolver = self.elf_cache.elf_resolver(path, *debug_syms)?;
                 resolver.deref() as &dyn Inspect
             }
+            #[cfg(feature = "macho")]
+            Source::MachO(MachO {
+                path,
+                arch,
+                dsym,
+                _non_exhaustive: (),
+            }) => {
+                let resolver = self.macho_resolver(path, arch.as_deref(), dsym.as_deref())?;
+                resolver as &dyn Inspect
+            }
+            #[cfg(feature = "pe")]
+            Source::Pe(Pe {
+                path,
+                _non_exhaustive: (),
+            }) => {
+                let resolver = self.pe_resolver(path)?;
+                resolver as &dyn Inspect
+            }
         };
 
-        let syms = names
-            .iter()
-            .map(|name| {
-                resolver.find_addr(name, &opts).map(|syms| {
-                    // This dance including reallocation of the vector
-                    // is very unfortunate, but it's unclear how else to
-                    // make the borrow checker accept this code (modulo
-                    // `transmute`).
-                    syms.into_iter().map(|sym| sym.to_owned()).collect()
-                })
-            })
-            .collect::<Result<Vec<_>>>()?;
+        let syms = resolver
+            .find_addrs(names, &opts)?
+            .into_iter()
+            // This dance including reallocation of the vectors is very
+            // unfortunate, but it's unclear how else to make the borrow
+            // checker accept this code (modulo `transmute`).
+            .map(|syms| syms.into_iter().map(|sym| sym.to_owned()).collect())
+            .collect();
 
         Ok(syms)
     }
@@ -154,6 +254,7 @@ impl Inspector {
                         // Breakpad logic doesn't support file offsets.
                         offset_in_file: false,
                         sym_type: SymType::Undefined,
+                        binding: None,
                     };
                     let resolver = slf.breakpad_resolver(path)?;
                     (resolver as &dyn Inspect, opts)
@@ -166,10 +267,39 @@ impl Inspector {
                     let opts = FindAddrOpts {
                         offset_in_file: true,
                         sym_type: SymType::Undefined,
+                        binding: None,
                     };
                     let resolver = slf.elf_cache.elf_resolver(path, *debug_syms)?;
                     (resolver.deref() as &dyn Inspect, opts)
                 }
+                #[cfg(feature = "macho")]
+                Source::MachO(MachO {
+                    path,
+                    arch,
+                    dsym,
+                    _non_exhaustive: (),
+                }) => {
+                    let opts = FindAddrOpts {
+                        offset_in_file: false,
+                        sym_type: SymType::Undefined,
+                        binding: None,
+                    };
+                    let resolver = slf.macho_resolver(path, arch.as_deref(), dsym.as_deref())?;
+                    (resolver as &dyn Inspect, opts)
+                }
+                #[cfg(feature = "pe")]
+                Source::Pe(Pe {
+                    path,
+                    _non_exhaustive: (),
+                }) => {
+                    let opts = FindAddrOpts {
+                        offset_in_file: true,
+                        sym_type: SymType::Undefined,
+                        binding: None,
+                    };
+                    let resolver = slf.pe_resolver(path)?;
+                    (resolver as &dyn Inspect, opts)
+                }
             };
 
             resolver.for_each(&opts, f)
@@ -177,6 +307,199 @@ impl Inspector {
 
         for_each_impl(self, src, &mut f)
     }
+
+    /// Perform an operation on each symbol in the source, providing
+    /// its demangled name.
+    ///
+    /// This method behaves like [`Inspector::for_each`], except that
+    /// the callback is additionally provided the demangled name of
+    /// each symbol, or `None` if the name could not be demangled (or
+    /// demangling support is disabled). A single buffer is reused for
+    /// demangling of all symbols, making this method more efficient
+    /// than demangling names as part of the callback, especially for
+    /// large symbol sources.
+    ///
+    /// Both Rust and C++ mangling schemes are supported.
+    pub fn for_each_demangled<F>(&self, src: &Source, mut f: F) -> Result<()>
+    where
+        F: FnMut(&SymInfo<'_>, Option<&str>),
+    {
+        let mut buf = String::new();
+        self.for_each(src, |sym| {
+            let demangled = demangle_into(&sym.name, &mut buf).then_some(buf.as_str());
+            f(sym, demangled)
+        })
+    }
+
+    /// Invoke `f` for each source file that contributed to the source.
+    ///
+    /// Source files are determined based on DWARF debug information:
+    /// the primary source file of each compilation unit as well as all
+    /// files referenced by the units' line programs are reported. Each
+    /// path is reported only once. Relative paths are interpreted
+    /// relative to the respective compilation directory, if known.
+    ///
+    /// For the [`Elf`](Source::Elf) source, no files are reported if
+    /// the file does not contain debug information or
+    /// [`debug_syms`][Elf::debug_syms] is disabled. The
+    /// [`Breakpad`](Source::Breakpad) source is not supported.
+    pub fn for_each_source_file<F>(&self, src: &Source, mut f: F) -> Result<()>
+    where
+        F: FnMut(&Path),
+    {
+        match src {
+            #[cfg(feature = "breakpad")]
+            Source::Breakpad(..) => Err(Error::with_unsupported(
+                "source file enumeration is not supported for Breakpad files",
+            )),
+            Source::Elf(Elf {
+                path,
+                debug_syms,
+                _non_exhaustive: (),
+            }) => {
+                let resolver = self.elf_cache.elf_resolver(path, *debug_syms)?;
+                resolver.for_each_source_file(&mut f)
+            }
+            #[cfg(feature = "macho")]
+            Source::MachO(MachO {
+                path,
+                arch,
+                dsym,
+                _non_exhaustive: (),
+            }) => {
+                let resolver = self.macho_resolver(path, arch.as_deref(), dsym.as_deref())?;
+                resolver.for_each_source_file(&mut f)
+            }
+            #[cfg(feature = "pe")]
+            Source::Pe(..) => Err(Error::with_unsupported(
+                "source file enumeration is not supported for PE files",
+            )),
+        }
+    }
+
+    /// Retrieve information about the DWARF compilation unit
+    /// containing the code at `addr`, such as the compiler that
+    /// produced it.
+    ///
+    /// `addr` is a virtual offset, i.e., an address as it appears in
+    /// the file, as opposed to one in the address space of a process.
+    ///
+    /// For the [`Elf`](Source::Elf) source, `None` is returned if the
+    /// file does not contain debug information or
+    /// [`debug_syms`][Elf::debug_syms] is disabled. The
+    /// [`Breakpad`](Source::Breakpad) source is not supported.
+    pub fn unit_for_addr(&self, src: &Source, addr: Addr) -> Result<Option<UnitInfo>> {
+        match src {
+            #[cfg(feature = "breakpad")]
+            Source::Breakpad(..) => Err(Error::with_unsupported(
+                "compilation unit lookup is not supported for Breakpad files",
+            )),
+            Source::Elf(Elf {
+                path,
+                debug_syms,
+                _non_exhaustive: (),
+            }) => {
+                let resolver = self.elf_cache.elf_resolver(path, *debug_syms)?;
+                resolver.unit_info(addr)
+            }
+            #[cfg(feature = "macho")]
+            Source::MachO(MachO {
+                path,
+                arch,
+                dsym,
+                _non_exhaustive: (),
+            }) => {
+                let resolver = self.macho_resolver(path, arch.as_deref(), dsym.as_deref())?;
+                resolver.unit_info(addr)
+            }
+            #[cfg(feature = "pe")]
+            Source::Pe(..) => Err(Error::with_unsupported(
+                "compilation unit lookup is not supported for PE files",
+            )),
+        }
+    }
+
+    /// Look up information about a type, given its name.
+    ///
+    /// Type information is retrieved from DWARF debug information and,
+    /// hence, only the [`Elf`](Source::Elf) source with
+    /// [`debug_syms`][Elf::debug_syms] enabled as well as the
+    /// `MachO` source are supported.
+    ///
+    /// If multiple types share the given name (e.g., because they are
+    /// defined in different compilation units), the first one
+    /// encountered is reported.
+    #[cfg(feature = "type-info")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "type-info")))]
+    pub fn type_info(&self, src: &Source, name: &str) -> Result<Option<TypeInfo>> {
+        match src {
+            #[cfg(feature = "breakpad")]
+            Source::Breakpad(..) => Err(Error::with_unsupported(
+                "Breakpad files do not contain type information",
+            )),
+            Source::Elf(Elf {
+                path,
+                debug_syms,
+                _non_exhaustive: (),
+            }) => {
+                let resolver = self.elf_cache.elf_resolver(path, *debug_syms)?;
+                let dwarf = resolver.dwarf_resolver().ok_or_else(|| {
+                    Error::with_unsupported("type information lookup requires debug symbols")
+                })?;
+                dwarf.type_info(name)
+            }
+            #[cfg(feature = "macho")]
+            Source::MachO(MachO {
+                path,
+                arch,
+                dsym,
+                _non_exhaustive: (),
+            }) => {
+                let resolver = self.macho_resolver(path, arch.as_deref(), dsym.as_deref())?;
+                resolver.type_info(name)
+            }
+            #[cfg(feature = "pe")]
+            Source::Pe(..) => Err(Error::with_unsupported(
+                "type information lookup is not supported for PE files",
+            )),
+        }
+    }
+
+    /// Probe the provided source for the kinds of symbols and debug
+    /// information it contains.
+    ///
+    /// The probe is cheap: it is mostly based on the presence of
+    /// certain sections and does not parse debug information in full.
+    /// The [`debug_syms`][Elf::debug_syms] property of the source is
+    /// ignored.
+    ///
+    /// Currently only the [`Elf`](Source::Elf) source is supported.
+    pub fn debug_info(&self, src: &Source) -> Result<DebugInfo> {
+        match src {
+            Source::Elf(Elf {
+                path,
+                debug_syms: _,
+                _non_exhaustive: (),
+            }) => {
+                let resolver = self.elf_cache.elf_resolver(path, false)?;
+                let mut info = resolver.parser().debug_info()?;
+                info.gsym = path.with_extension("gsym").exists();
+                Ok(info)
+            }
+            #[cfg(feature = "breakpad")]
+            Source::Breakpad(..) => Err(Error::with_unsupported(
+                "debug information probing is not supported for Breakpad files",
+            )),
+            #[cfg(feature = "macho")]
+            Source::MachO(..) => Err(Error::with_unsupported(
+                "debug information probing is not supported for Mach-O files",
+            )),
+            #[cfg(feature = "pe")]
+            Source::Pe(..) => Err(Error::with_unsupported(
+                "debug information probing is not supported for PE files",
+            )),
+        }
+    }
 }
 
 impl Default for Inspector {
@@ -190,8 +513,6 @@ impl Default for Inspector {
 mod tests {
     use super::*;
 
-    #[cfg(not(feature = "breakpad"))]
-    use std::path::Path;
     use std::rc::Rc;
 
     use crate::ErrorKind;
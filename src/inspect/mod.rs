@@ -17,11 +17,17 @@
 #[cfg_attr(not(feature = "dwarf"), allow(unused_variables))]
 mod inspector;
 mod source;
+cfg_type_info! {
+  mod type_info;
+}
 
 use std::borrow::Cow;
+use std::collections::HashMap;
 use std::fmt::Debug;
 use std::path::Path;
+use std::path::PathBuf;
 
+use crate::symbolize::SrcLang;
 use crate::Addr;
 use crate::Result;
 use crate::SymType;
@@ -31,7 +37,62 @@ cfg_breakpad! {
   pub use source::Breakpad;
 }
 pub use source::Elf;
+cfg_macho! {
+  pub use source::MachO;
+}
+cfg_pe! {
+  pub use source::Pe;
+}
 pub use source::Source;
+cfg_type_info! {
+  pub use type_info::EnumVariant;
+  pub use type_info::Field;
+  pub use type_info::TypeInfo;
+}
+
+
+/// The binding of a symbol, determining its visibility to the linker.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum SymBinding {
+    /// The symbol is not visible outside of the object file containing
+    /// its definition.
+    Local,
+    /// The symbol is visible to all object files being combined.
+    Global,
+    /// The symbol is global, but its definition may be overridden by a
+    /// global symbol of the same name.
+    Weak,
+}
+
+
+/// The version of a symbol, as recorded by means of ELF symbol
+/// versioning.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct SymVersion<'src> {
+    /// The name of the version; for example, `GLIBC_2.34`.
+    pub name: Cow<'src, str>,
+    /// Whether the version is hidden, i.e., not the default version of
+    /// the symbol.
+    ///
+    /// Hidden versions can only be referenced explicitly (e.g., as
+    /// `name@version`), whereas the default version is used when
+    /// referencing a symbol by name alone (and is commonly spelled
+    /// `name@@version`).
+    pub hidden: bool,
+}
+
+impl SymVersion<'_> {
+    /// Clone the object ensuring that references are converted to owned
+    /// objects.
+    #[inline]
+    pub fn to_owned(&self) -> SymVersion<'static> {
+        SymVersion {
+            name: Cow::Owned(self.name.to_string()),
+            hidden: self.hidden,
+        }
+    }
+}
 
 
 /// Information about a symbol.
@@ -45,6 +106,17 @@ pub struct SymInfo<'src> {
     pub size: usize,
     /// A function or a variable.
     pub sym_type: SymType,
+    /// The binding of the symbol, if known.
+    pub binding: Option<SymBinding>,
+    /// The version of the symbol, if it is versioned.
+    pub version: Option<SymVersion<'src>>,
+    /// Whether the symbol is a GNU indirect function (`STT_GNU_IFUNC`).
+    ///
+    /// The address of such a symbol is that of its resolver function,
+    /// which selects the actual implementation at runtime. It is not
+    /// the address of the implementation itself and hence, for
+    /// example, of limited use for attaching uprobes.
+    pub is_ifunc: bool,
     /// The offset in the object file.
     pub file_offset: Option<u64>,
     /// The file name of the shared object.
@@ -61,6 +133,9 @@ impl SymInfo<'_> {
             addr: self.addr,
             size: self.size,
             sym_type: self.sym_type,
+            binding: self.binding,
+            version: self.version.as_ref().map(SymVersion::to_owned),
+            is_ifunc: self.is_ifunc,
             file_offset: self.file_offset,
             obj_file_name: self
                 .obj_file_name
@@ -71,6 +146,98 @@ impl SymInfo<'_> {
 }
 
 
+/// The compression format used for debug information.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum DebugCompression {
+    /// zlib/deflate compression.
+    Zlib,
+    /// Zstandard compression.
+    Zstd,
+    /// A compression format not known to the library.
+    Unknown,
+}
+
+
+/// Information about the kinds of symbols and debug information
+/// available in a file.
+///
+/// The information is gathered cheaply, mostly based on the presence
+/// of sections, without parsing debug information in full.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct DebugInfo {
+    /// Whether the file contains a non-empty `.symtab` section.
+    pub symtab: bool,
+    /// Whether the file contains a non-empty `.dynsym` section.
+    pub dynsym: bool,
+    /// The DWARF version of the first compilation unit in the
+    /// `.debug_info` section, if DWARF debug information is present
+    /// and its version could be determined.
+    pub dwarf_version: Option<u16>,
+    /// The compression format used for the `.debug_info` section, if
+    /// it is compressed.
+    pub dwarf_compression: Option<DebugCompression>,
+    /// Whether the file contains a `.gnu_debuglink` section, referencing
+    /// a separate debug information file.
+    pub debug_link: bool,
+    /// Whether the file contains MiniDebugInfo, i.e., a
+    /// `.gnu_debugdata` section.
+    pub mini_debug_info: bool,
+    /// Whether a Gsym file accompanies the file, i.e., whether a file
+    /// with the same path but a `gsym` extension exists.
+    pub gsym: bool,
+    /// The struct is non-exhaustive and open to extension.
+    #[doc(hidden)]
+    pub _non_exhaustive: (),
+}
+
+
+/// Information about a DWARF compilation unit.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UnitInfo {
+    /// The path to the unit's primary source file, as recorded in its
+    /// `DW_AT_name` attribute and interpreted relative to its
+    /// compilation directory, if available.
+    pub path: Option<PathBuf>,
+    /// The identification of the compiler that produced the unit
+    /// (e.g., `GNU C17 12.2.0 -O2`), as recorded in its
+    /// `DW_AT_producer` attribute.
+    pub producer: Option<String>,
+    /// The source code language of the unit.
+    pub lang: SrcLang,
+    /// The raw `DW_LANG_*` value of the unit's `DW_AT_language`
+    /// attribute.
+    ///
+    /// Contrary to [`lang`][UnitInfo::lang], this value conveys the
+    /// exact language standard, such as C++17 as opposed to C++14.
+    pub language: Option<u16>,
+    /// The unit's compilation directory, as recorded in its
+    /// `DW_AT_comp_dir` attribute.
+    pub comp_dir: Option<PathBuf>,
+    /// The DWARF version of the unit.
+    pub version: u16,
+    /// The struct is non-exhaustive and open to extension.
+    #[doc(hidden)]
+    pub _non_exhaustive: (),
+}
+
+
+/// Options influencing symbol lookup via
+/// [`Inspector::lookup_with_opts`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LookupOpts {
+    /// Only report symbols with the given binding. `None` indicates
+    /// that symbols are of interest irrespective of their binding.
+    ///
+    /// Sources not recording symbol binding information (e.g.,
+    /// Breakpad files) report no symbols if a binding is requested.
+    pub binding: Option<SymBinding>,
+    /// The struct is non-exhaustive and open to extension.
+    #[doc(hidden)]
+    pub _non_exhaustive: (),
+}
+
+
 /// The context of an address finding request.
 ///
 /// This type passes additional parameters to resolvers.
@@ -83,6 +250,9 @@ pub(crate) struct FindAddrOpts {
     /// [`Undefined`][SymType::Undefined] indicates that all supported
     /// symbols are of interest.
     pub sym_type: SymType,
+    /// Return only symbols with the given binding. `None` indicates
+    /// that symbols are of interest irrespective of their binding.
+    pub binding: Option<SymBinding>,
 }
 
 
@@ -94,6 +264,49 @@ where
     /// Find information about a symbol given its name.
     fn find_addr(&self, name: &str, opts: &FindAddrOpts) -> Result<Vec<SymInfo<'_>>>;
 
+    /// Find information about multiple symbols given their names.
+    ///
+    /// Symbols are reported grouped by name, in the order of `names`.
+    /// Duplicate names are only looked up once and names not matching
+    /// any symbol produce an empty list. Implementations may override
+    /// this method to look up all names in a single pass over the
+    /// underlying data.
+    fn find_addrs(&self, names: &[&str], opts: &FindAddrOpts) -> Result<Vec<Vec<SymInfo<'_>>>> {
+        find_addrs_dedup(names, |_idx, name| self.find_addr(name, opts))
+    }
+
     /// Perform an operation on each symbol.
     fn for_each(&self, opts: &FindAddrOpts, f: &mut dyn FnMut(&SymInfo<'_>)) -> Result<()>;
 }
+
+
+/// Look up symbols for a list of names, invoking `find` only once for
+/// each distinct name.
+///
+/// `find` is provided the index of the first occurrence of a name
+/// in `names` along with the name itself. Results for duplicate names
+/// are copies of those reported for their first occurrence.
+pub(crate) fn find_addrs_dedup<'src, F>(
+    names: &[&str],
+    mut find: F,
+) -> Result<Vec<Vec<SymInfo<'src>>>>
+where
+    F: FnMut(usize, &str) -> Result<Vec<SymInfo<'src>>>,
+{
+    let mut firsts = HashMap::<&str, usize>::with_capacity(names.len());
+    let mut syms = Vec::with_capacity(names.len());
+    for (idx, name) in names.iter().enumerate() {
+        let found = match firsts.get(name) {
+            Some(first) => {
+                let found: &Vec<SymInfo<'src>> = &syms[*first];
+                found.clone()
+            }
+            None => {
+                let _prev = firsts.insert(name, idx);
+                find(idx, name)?
+            }
+        };
+        let () = syms.push(found);
+    }
+    Ok(syms)
+}
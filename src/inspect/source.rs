@@ -67,6 +67,81 @@ impl From<Elf> for Source {
 }
 
 
+cfg_macho! {
+/// A Mach-O file.
+///
+/// Both DWARF debug information, contained either in the file's own
+/// `__DWARF` segment or in the dSYM bundle provided via
+/// [`dsym`][MachO::dsym], and the file's symbol table are consulted.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MachO {
+    /// The path to the Mach-O file.
+    pub path: PathBuf,
+    /// The name of the architecture (e.g., `x86_64` or `arm64`) to
+    /// work with, in case the file is a universal ("fat") binary
+    /// containing more than one.
+    pub arch: Option<String>,
+    /// The path to a dSYM bundle (or the DWARF file contained in it)
+    /// providing debug information for the file.
+    pub dsym: Option<PathBuf>,
+    /// The struct is non-exhaustive and open to extension.
+    #[doc(hidden)]
+    pub _non_exhaustive: (),
+}
+
+impl MachO {
+    /// Create a new [`MachO`] object, referencing the provided path.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            arch: None,
+            dsym: None,
+            _non_exhaustive: (),
+        }
+    }
+}
+
+impl From<MachO> for Source {
+    fn from(macho: MachO) -> Self {
+        Source::MachO(macho)
+    }
+}
+}
+
+
+cfg_pe! {
+/// A PE file.
+///
+/// Symbols are read from the PDB file referenced by the PE file, if
+/// one matching it is found next to the file, and from the file's
+/// export table.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pe {
+    /// The path to the PE file.
+    pub path: PathBuf,
+    /// The struct is non-exhaustive and open to extension.
+    #[doc(hidden)]
+    pub _non_exhaustive: (),
+}
+
+impl Pe {
+    /// Create a new [`Pe`] object, referencing the provided path.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            _non_exhaustive: (),
+        }
+    }
+}
+
+impl From<Pe> for Source {
+    fn from(pe: Pe) -> Self {
+        Source::Pe(pe)
+    }
+}
+}
+
+
 /// The source to use for the inspection request.
 ///
 /// Objects of this type are used first and foremost with the
@@ -80,6 +155,14 @@ pub enum Source {
     Breakpad(Breakpad),
     /// The source is an ELF file.
     Elf(Elf),
+    /// The source is a Mach-O file.
+    #[cfg(feature = "macho")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "macho")))]
+    MachO(MachO),
+    /// The source is a PE file.
+    #[cfg(feature = "pe")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "pe")))]
+    Pe(Pe),
 }
 
 impl Source {
@@ -89,6 +172,10 @@ impl Source {
             #[cfg(feature = "breakpad")]
             Self::Breakpad(breakpad) => Some(&breakpad.path),
             Self::Elf(elf) => Some(&elf.path),
+            #[cfg(feature = "macho")]
+            Self::MachO(macho) => Some(&macho.path),
+            #[cfg(feature = "pe")]
+            Self::Pe(pe) => Some(&pe.path),
         }
     }
 }
@@ -0,0 +1,120 @@
+/// A member of a [`TypeInfo::Struct`] or [`TypeInfo::Union`].
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Field {
+    /// The name of the field, if any. Anonymous members (e.g., an
+    /// unnamed union embedded in a struct) do not have one.
+    pub name: Option<String>,
+    /// The offset of the field from the start of the containing type,
+    /// in bytes.
+    pub offset: usize,
+    /// The type of the field.
+    pub ty: TypeInfo,
+    /// The struct is non-exhaustive and open to extension.
+    #[doc(hidden)]
+    pub _non_exhaustive: (),
+}
+
+
+/// A variant of a [`TypeInfo::Enum`].
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct EnumVariant {
+    /// The name of the variant.
+    pub name: String,
+    /// The value of the variant.
+    ///
+    /// Values of enums with an unsigned underlying type that exceed
+    /// `i64::MAX` are reported in their two's complement
+    /// representation.
+    pub value: i64,
+    /// The struct is non-exhaustive and open to extension.
+    #[doc(hidden)]
+    pub _non_exhaustive: (),
+}
+
+
+/// Information about a type, as recorded in debug information.
+///
+/// Type qualifiers (`const`, `volatile`, ...) are transparently
+/// resolved to the type they refer to.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum TypeInfo {
+    /// A structure (or class) type.
+    ///
+    /// If an anonymous struct is (directly or indirectly) referenced by
+    /// one of its own fields, the inner occurrence is reported without
+    /// any fields.
+    Struct {
+        /// The name of the struct, if it is not anonymous.
+        name: Option<String>,
+        /// The size of the struct, in bytes.
+        size: usize,
+        /// The struct's fields, in declaration order.
+        fields: Vec<Field>,
+    },
+    /// A union type.
+    ///
+    /// Self-referential unions are treated the same way as structs.
+    Union {
+        /// The name of the union, if it is not anonymous.
+        name: Option<String>,
+        /// The size of the union, in bytes.
+        size: usize,
+        /// The union's fields, in declaration order.
+        fields: Vec<Field>,
+    },
+    /// An enumeration type.
+    Enum {
+        /// The name of the enum, if it is not anonymous.
+        name: Option<String>,
+        /// The size of the enum, in bytes.
+        size: usize,
+        /// The enum's variants, in declaration order.
+        variants: Vec<EnumVariant>,
+    },
+    /// A pointer (or reference) type.
+    ///
+    /// Named structs, classes, and unions being pointed to (directly or
+    /// through typedefs, qualifiers, and arrays) are reported as
+    /// [`TypeInfo::Opaque`] carrying just their name. Their definition
+    /// can be looked up separately.
+    Pointer {
+        /// The type being pointed to.
+        pointee: Box<TypeInfo>,
+    },
+    /// An array type.
+    ///
+    /// Multi-dimensional arrays are represented as arrays of arrays,
+    /// with the outermost dimension first.
+    Array {
+        /// The type of the array's elements.
+        elem: Box<TypeInfo>,
+        /// The number of elements in the array. Zero if the array is
+        /// of unknown or flexible size.
+        count: usize,
+    },
+    /// A primitive type, such as `int` or `double`.
+    ///
+    /// `void` is reported as a primitive of size zero.
+    Primitive {
+        /// The name of the type.
+        name: String,
+        /// The size of the type, in bytes.
+        size: usize,
+    },
+    /// A typedef, i.e., an alias for another type.
+    Typedef {
+        /// The name of the typedef.
+        name: String,
+        /// The type the typedef refers to.
+        ty: Box<TypeInfo>,
+    },
+    /// A type that could not be described in more detail, e.g.,
+    /// because only a declaration of it is available, because it is a
+    /// function type, or because the layout of one of its fields can't
+    /// be determined.
+    Opaque {
+        /// The name of the type, if any.
+        name: Option<String>,
+    },
+}
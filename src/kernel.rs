@@ -5,7 +5,10 @@ use std::path::Path;
 use std::rc::Rc;
 
 use crate::elf::ElfResolver;
+use crate::inspect::FindAddrOpts;
+use crate::inspect::Inspect;
 use crate::ksym::KSymResolver;
+use crate::log::warn;
 use crate::symbolize::FindSymOpts;
 use crate::symbolize::Reason;
 use crate::symbolize::ResolvedSym;
@@ -15,15 +18,58 @@ use crate::Error;
 use crate::Result;
 
 
+/// Symbols used as anchors for determining the KASLR offset, in order
+/// of preference.
+const KASLR_ANCHORS: [&str; 2] = ["_stext", "start_kernel"];
+
+
+/// Find the address of the symbol with the given name.
+fn find_anchor(resolver: &dyn Inspect, name: &str) -> Result<Option<Addr>> {
+    let opts = FindAddrOpts::default();
+    let syms = resolver.find_addr(name, &opts)?;
+    let addr = syms.iter().find(|sym| sym.name == name).map(|sym| sym.addr);
+    Ok(addr)
+}
+
+/// Determine the KASLR offset by comparing the addresses of one of the
+/// given anchor symbols in kallsyms and in the kernel image.
+fn find_kaslr_offset(
+    ksym_resolver: &KSymResolver,
+    elf_resolver: &ElfResolver,
+    anchors: &[&str],
+) -> Result<Option<u64>> {
+    for anchor in anchors {
+        let ksym_addr = find_anchor(ksym_resolver, anchor)?;
+        let elf_addr = find_anchor(elf_resolver, anchor)?;
+        if let (Some(ksym_addr), Some(elf_addr)) = (ksym_addr, elf_addr) {
+            let offset = ksym_addr.checked_sub(elf_addr).ok_or_else(|| {
+                Error::with_invalid_data(format!(
+                    "kallsyms address of `{anchor}` ({ksym_addr:#x}) is below kernel image address ({elf_addr:#x})"
+                ))
+            })?;
+            return Ok(Some(offset))
+        }
+    }
+    Ok(None)
+}
+
+
 pub(crate) struct KernelResolver {
     pub ksym_resolver: Option<Rc<KSymResolver>>,
     pub elf_resolver: Option<Rc<ElfResolver>>,
+    /// The KASLR offset to remove from addresses before looking them up
+    /// in `elf_resolver`.
+    ///
+    /// `None` if the offset could not be determined, in which case
+    /// `elf_resolver` is not used at all.
+    pub kaslr_offset: Option<u64>,
 }
 
 impl KernelResolver {
     pub fn new(
         ksym_resolver: Option<Rc<KSymResolver>>,
         elf_resolver: Option<Rc<ElfResolver>>,
+        kaslr_offset: Option<u64>,
     ) -> Result<KernelResolver> {
         if ksym_resolver.is_none() && elf_resolver.is_none() {
             return Err(Error::with_not_found(
@@ -31,22 +77,73 @@ impl KernelResolver {
             ))
         }
 
+        let kaslr_offset = match (kaslr_offset, &ksym_resolver, &elf_resolver) {
+            (Some(offset), _, _) => Some(offset),
+            (None, Some(ksym_resolver), Some(elf_resolver)) => {
+                let offset = find_kaslr_offset(ksym_resolver, elf_resolver, &KASLR_ANCHORS)?;
+                if offset.is_none() {
+                    warn!("failed to determine KASLR offset; using kallsyms only");
+                }
+                offset
+            }
+            // Without kallsyms there is nothing to derive the offset
+            // from. Assuming that there is none would result in bogus
+            // symbols on any randomized kernel.
+            (None, None, _) => {
+                warn!("KASLR offset is unknown; kernel image can't be used for symbolization");
+                None
+            }
+            (None, Some(_), None) => None,
+        };
+
         Ok(KernelResolver {
             ksym_resolver,
             elf_resolver,
+            kaslr_offset,
         })
     }
 }
 
 impl Symbolize for KernelResolver {
     fn find_sym(&self, addr: Addr, opts: &FindSymOpts) -> Result<Result<ResolvedSym<'_>, Reason>> {
-        // TODO: If an `ElfResolver` is available we probably should give
-        //       preference to it, if for no other reason than the fact that it
-        //       may report source code location information.
-        if let Some(ksym_resolver) = self.ksym_resolver.as_ref() {
-            ksym_resolver.find_sym(addr, opts)
-        } else {
-            self.elf_resolver.as_ref().unwrap().find_sym(addr, opts)
+        // We give preference to the kernel image, if available, because
+        // it may report source code location information. Kernel
+        // module symbols are only present in kallsyms, though.
+        // Without knowing the KASLR offset, the kernel image can't be
+        // used reliably.
+        if let (Some(elf_resolver), Some(kaslr_offset)) =
+            (self.elf_resolver.as_ref(), self.kaslr_offset)
+        {
+            let result = if let Some(addr) = addr.checked_sub(kaslr_offset) {
+                elf_resolver.find_sym(addr, opts)?
+            } else {
+                Err(Reason::UnknownAddr)
+            };
+
+            match result {
+                Ok(mut sym) => {
+                    // Report the symbol and its inlined functions at
+                    // addresses as they appear in kernel address space.
+                    sym.addr = sym.addr.wrapping_add(kaslr_offset);
+                    for inlined_fn in sym.inlined.iter_mut() {
+                        inlined_fn.addr =
+                            inlined_fn.addr.map(|addr| addr.wrapping_add(kaslr_offset));
+                        inlined_fn.origin_addr = inlined_fn
+                            .origin_addr
+                            .map(|addr| addr.wrapping_add(kaslr_offset));
+                    }
+                    return Ok(Ok(sym))
+                }
+                Err(reason) if self.ksym_resolver.is_none() => return Ok(Err(reason)),
+                Err(_reason) => (),
+            }
+        }
+
+        match &self.ksym_resolver {
+            Some(ksym_resolver) => ksym_resolver.find_sym(addr, opts),
+            // We only have a kernel image, but without knowing the
+            // KASLR offset we can't use it.
+            None => Ok(Err(Reason::Unsupported)),
         }
     }
 }
@@ -69,3 +166,181 @@ impl Debug for KernelResolver {
         )
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io::Write as _;
+
+    use tempfile::NamedTempFile;
+    use test_log::test;
+
+    use crate::ErrorKind;
+
+
+    /// Create a `KSymResolver` for a kallsyms file with the provided
+    /// contents.
+    fn ksym_resolver(kallsyms: &str) -> KSymResolver {
+        let mut file = NamedTempFile::new().unwrap();
+        let () = file.write_all(kallsyms.as_bytes()).unwrap();
+        KSymResolver::load_file_name(file.path().to_path_buf()).unwrap()
+    }
+
+    /// Create an `ElfResolver` for one of our test files.
+    fn elf_resolver() -> ElfResolver {
+        let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join("test-stable-addrs-no-dwarf.bin");
+        ElfResolver::open(path).unwrap()
+    }
+
+    /// Check that we can determine the KASLR offset based on an anchor
+    /// symbol.
+    #[test]
+    fn kaslr_offset_detection() {
+        let ksym = ksym_resolver("0000000012000100 T factorial\n");
+        let elf = elf_resolver();
+
+        let offset = find_kaslr_offset(&ksym, &elf, &["_stext", "factorial"])
+            .unwrap()
+            .unwrap();
+        assert_eq!(offset, 0x10000000);
+
+        let offset = find_kaslr_offset(&ksym, &elf, &["_stext"]).unwrap();
+        assert_eq!(offset, None);
+
+        let ksym = ksym_resolver("0000000000000100 T factorial\n");
+        let err = find_kaslr_offset(&ksym, &elf, &["factorial"]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+    }
+
+    /// Check that the KASLR offset is taken into account when
+    /// symbolizing using the kernel image.
+    #[test]
+    fn kaslr_offset_symbolization() {
+        let elf = Rc::new(elf_resolver());
+        let resolver = KernelResolver::new(None, Some(elf), Some(0x10000000)).unwrap();
+
+        let sym = resolver
+            .find_sym(0x12000100, &FindSymOpts::BASIC)
+            .unwrap()
+            .unwrap();
+        assert_eq!(sym.name, "factorial");
+        assert_eq!(sym.addr, 0x12000100);
+
+        let reason = resolver
+            .find_sym(0x100, &FindSymOpts::BASIC)
+            .unwrap()
+            .unwrap_err();
+        assert_eq!(reason, Reason::UnknownAddr);
+    }
+
+    /// Check that the KASLR offset is applied to the addresses of
+    /// inlined functions as well.
+    #[cfg(feature = "dwarf")]
+    #[test]
+    fn kaslr_offset_inlined_fns() {
+        let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join("test-inline-origin.bin");
+        let elf = Rc::new(ElfResolver::open(path).unwrap());
+        let (caller_addr, caller_size) = elf
+            .find_addr("caller", &FindAddrOpts::default())
+            .unwrap()
+            .into_iter()
+            .map(|sym| (sym.addr, sym.size))
+            .next()
+            .unwrap();
+
+        let offset = 0x10000000;
+        let unshifted = KernelResolver::new(None, Some(elf.clone()), Some(0)).unwrap();
+        let shifted = KernelResolver::new(None, Some(elf), Some(offset)).unwrap();
+        let opts = FindSymOpts::CODE_INFO_AND_INLINED
+            .with_inlined_fn_addrs(true)
+            .with_inlined_fn_origin_addrs(true);
+
+        let mut checked = false;
+        for addr in caller_addr..caller_addr + caller_size as Addr {
+            let expected = unshifted.find_sym(addr, &opts).unwrap().unwrap();
+            let sym = shifted.find_sym(addr + offset, &opts).unwrap().unwrap();
+            assert_eq!(sym.name, expected.name);
+            assert_eq!(sym.addr, expected.addr + offset);
+            assert_eq!(sym.inlined.len(), expected.inlined.len());
+
+            for (inlined_fn, expected) in sym.inlined.iter().zip(expected.inlined.iter()) {
+                assert_eq!(inlined_fn.name, expected.name);
+                assert_eq!(inlined_fn.addr, expected.addr.map(|addr| addr + offset));
+                assert_eq!(inlined_fn.offset, expected.offset);
+                assert_eq!(
+                    inlined_fn.origin_addr,
+                    expected.origin_addr.map(|addr| addr + offset)
+                );
+                checked |= inlined_fn.addr.is_some() && inlined_fn.origin_addr.is_some();
+            }
+        }
+        assert!(checked);
+    }
+
+    /// Check that we fall back to kallsyms for addresses not covered
+    /// by the kernel image.
+    #[test]
+    fn kallsyms_fallback() {
+        let ksym = Rc::new(ksym_resolver(
+            "0000000012000100 T factorial\nffffffffc0000000 t module_fn [mod]\n",
+        ));
+        let elf = Rc::new(elf_resolver());
+        let resolver = KernelResolver::new(Some(ksym), Some(elf), Some(0x10000000)).unwrap();
+
+        let sym = resolver
+            .find_sym(0x12000100, &FindSymOpts::BASIC)
+            .unwrap()
+            .unwrap();
+        assert_eq!(sym.name, "factorial");
+
+        let sym = resolver
+            .find_sym(0xffffffffc0000000, &FindSymOpts::BASIC)
+            .unwrap()
+            .unwrap();
+        assert_eq!(sym.name, "module_fn");
+    }
+
+    /// Check that the kernel image is not used if the KASLR offset
+    /// can't be determined.
+    #[test]
+    fn undetermined_kaslr_offset() {
+        let ksym = Rc::new(ksym_resolver("0000000012000100 T factorial\n"));
+        let elf = Rc::new(elf_resolver());
+        let resolver = KernelResolver::new(Some(ksym), Some(elf), None).unwrap();
+        assert_eq!(resolver.kaslr_offset, None);
+
+        let sym = resolver
+            .find_sym(0x12000100, &FindSymOpts::BASIC)
+            .unwrap()
+            .unwrap();
+        assert_eq!(sym.name, "factorial");
+
+        // The address of `factorial` in the kernel image must not be
+        // resolved with an assumed offset of zero.
+        let reason = resolver
+            .find_sym(0x2000100, &FindSymOpts::BASIC)
+            .unwrap()
+            .unwrap_err();
+        assert_eq!(reason, Reason::UnknownAddr);
+
+        // Without kallsyms the offset can't be derived and the kernel
+        // image should not be used.
+        let elf = Rc::new(elf_resolver());
+        let resolver = KernelResolver::new(None, Some(elf), None).unwrap();
+        assert_eq!(resolver.kaslr_offset, None);
+
+        for addr in [0x2000100, 0x12000100] {
+            let reason = resolver
+                .find_sym(addr, &FindSymOpts::BASIC)
+                .unwrap()
+                .unwrap_err();
+            assert_eq!(reason, Reason::Unsupported);
+        }
+    }
+}
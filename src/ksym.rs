@@ -3,6 +3,7 @@ use std::fmt::Debug;
 use std::fmt::Formatter;
 use std::fmt::Result as FmtResult;
 use std::fs::File;
+use std::io;
 use std::io::BufRead;
 use std::io::BufReader;
 use std::path::Path;
@@ -13,12 +14,14 @@ use crate::inspect::Inspect;
 use crate::inspect::SymInfo;
 use crate::once::OnceCell;
 use crate::symbolize::FindSymOpts;
+use crate::symbolize::Provenance;
 use crate::symbolize::Reason;
 use crate::symbolize::ResolvedSym;
 use crate::symbolize::SrcLang;
 use crate::symbolize::Symbolize;
 use crate::util::find_match_or_lower_bound_by_key;
 use crate::Addr;
+use crate::Error;
 use crate::Result;
 use crate::SymType;
 
@@ -45,6 +48,13 @@ impl<'ksym> From<&'ksym Ksym> for ResolvedSym<'ksym> {
             // kallsyms doesn't have source code location information.
             code_info: None,
             inlined: Box::new([]),
+            provenance: Provenance::Symbol,
+            is_synthetic: true,
+            raw_elf_sym: None,
+            compilation_unit: None,
+            version: None,
+            section: None,
+            linkage_name: None,
         }
     }
 }
@@ -57,6 +67,9 @@ impl<'ksym> From<&'ksym Ksym> for SymInfo<'ksym> {
             addr: *addr,
             size: 0,
             sym_type: SymType::Function,
+            binding: None,
+            version: None,
+            is_ifunc: false,
             file_offset: None,
             obj_file_name: None,
         }
@@ -81,6 +94,7 @@ impl KSymResolver {
         let mut reader = BufReader::new(f);
         let mut line = String::new();
         let mut syms = Vec::with_capacity(DFL_KSYM_CAP);
+        let mut hidden = false;
 
         loop {
             let sz = reader.read_line(&mut line)?;
@@ -94,6 +108,7 @@ impl KSymResolver {
             let (addr, _symbol, func) = (tokens[0], tokens[1], tokens[2]);
             if let Ok(addr) = Addr::from_str_radix(addr, 16) {
                 if addr == 0 {
+                    hidden = true;
                     line.truncate(0);
                     continue
                 }
@@ -104,6 +119,19 @@ impl KSymResolver {
             line.truncate(0);
         }
 
+        // If the kernel hides symbol addresses from us, all of them are
+        // reported as zero. Symbolizing with such data is pointless, so
+        // let the user know what is going on.
+        if syms.is_empty() && hidden {
+            return Err(Error::from(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                format!(
+                    "{} reports only zero symbol addresses; reading kernel addresses requires the CAP_SYSLOG capability and kernel.kptr_restrict to be less than 2",
+                    filename.display()
+                ),
+            )))
+        }
+
         syms.sort_by(|a, b| a.addr.cmp(&b.addr));
 
         let slf = Self {
@@ -157,7 +185,11 @@ impl Symbolize for KSymResolver {
 
 impl Inspect for KSymResolver {
     fn find_addr<'slf>(&'slf self, name: &str, opts: &FindAddrOpts) -> Result<Vec<SymInfo<'slf>>> {
-        if let SymType::Variable = opts.sym_type {
+        if let SymType::Variable | SymType::Tls = opts.sym_type {
+            return Ok(Vec::new())
+        }
+        // kallsyms doesn't carry symbol binding information.
+        if opts.binding.is_some() {
             return Ok(Vec::new())
         }
 
@@ -181,7 +213,10 @@ impl Inspect for KSymResolver {
     }
 
     fn for_each(&self, opts: &FindAddrOpts, f: &mut dyn FnMut(&SymInfo<'_>)) -> Result<()> {
-        if let SymType::Variable = opts.sym_type {
+        if let SymType::Variable | SymType::Tls = opts.sym_type {
+            return Ok(())
+        }
+        if opts.binding.is_some() {
             return Ok(())
         }
 
@@ -204,6 +239,9 @@ impl Debug for KSymResolver {
 mod tests {
     use super::*;
 
+    use std::io::Write as _;
+
+    use tempfile::NamedTempFile;
     use test_log::test;
 
     use crate::ErrorKind;
@@ -245,6 +283,7 @@ mod tests {
             let opts = FindAddrOpts {
                 offset_in_file: false,
                 sym_type: SymType::Function,
+                binding: None,
             };
             let found = resolver.find_addr(name, &opts).unwrap();
             assert!(
@@ -258,26 +297,26 @@ mod tests {
         let sym = &resolver.syms[resolver.syms.len() / 2];
         let addr = sym.addr;
         let found = resolver
-            .find_sym(addr, &FindSymOpts::Basic)
+            .find_sym(addr, &FindSymOpts::BASIC)
             .unwrap()
             .unwrap();
         ensure_addr_for_name(found.name, addr);
 
         // 0 is an invalid address.  We remove all symbols with 0 as
         // their address from the list.
-        assert!(resolver.find_sym(0, &FindSymOpts::Basic).unwrap().is_err());
+        assert!(resolver.find_sym(0, &FindSymOpts::BASIC).unwrap().is_err());
 
         // Find the address of the last symbol
         let sym = &resolver.syms.last().unwrap();
         let addr = sym.addr;
         let found = resolver
-            .find_sym(addr, &FindSymOpts::Basic)
+            .find_sym(addr, &FindSymOpts::BASIC)
             .unwrap()
             .unwrap();
         ensure_addr_for_name(found.name, addr);
 
         let found = resolver
-            .find_sym(addr + 1, &FindSymOpts::Basic)
+            .find_sym(addr + 1, &FindSymOpts::BASIC)
             .unwrap()
             .unwrap();
         // Should still find the previous symbol, which is the last one.
@@ -417,4 +456,16 @@ mod tests {
             .for_each(&opts, &mut |_sym| unreachable!())
             .unwrap();
     }
+
+    /// Check that we report a permission error if kallsyms only
+    /// contains zero addresses.
+    #[test]
+    fn ksym_resolver_hidden_addrs() {
+        let mut file = NamedTempFile::new().unwrap();
+        let () = writeln!(file, "0000000000000000 T _stext").unwrap();
+        let () = writeln!(file, "0000000000000000 T start_kernel").unwrap();
+
+        let err = KSymResolver::load_file_name(file.path().to_path_buf()).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::PermissionDenied);
+    }
 }
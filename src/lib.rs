@@ -15,6 +15,9 @@
 //! - [`inspect`] contains APIs for inspecting files such as ELF and Gsym to
 //!   lookup addresses to symbol names, for example
 //! - [`normalize`] exposes address normalization functionality
+//! - [`elf`] provides lower level access to ELF files
+//! - `dwarf` provides lower level access to DWARF debug information (requires
+//!   the `dwarf` feature)
 //!
 //! C API bindings are defined in a cross-cutting manner as part of the
 //! `blazesym-c` crate (note that Rust code should not have to consume
@@ -55,9 +58,11 @@ extern crate test;
 mod cfg;
 #[cfg(feature = "breakpad")]
 mod breakpad;
+mod coredump;
 #[cfg(feature = "dwarf")]
-mod dwarf;
-mod elf;
+#[cfg_attr(docsrs, doc(cfg(feature = "dwarf")))]
+pub mod dwarf;
+pub mod elf;
 mod error;
 mod file_cache;
 #[cfg(feature = "gsym")]
@@ -66,12 +71,19 @@ mod insert_map;
 pub mod inspect;
 mod kernel;
 mod ksym;
-mod maps;
+#[cfg(feature = "macho")]
+mod macho;
+pub mod maps;
 mod mmap;
 pub mod normalize;
 mod once;
+#[cfg(feature = "pe")]
+mod pe;
 mod pid;
+pub mod sym;
 pub mod symbolize;
+#[cfg(test)]
+mod testing;
 mod util;
 #[cfg(feature = "apk")]
 mod zip;
@@ -110,6 +122,12 @@ pub enum SymType {
     Function,
     /// The symbol is a variable.
     Variable,
+    /// The symbol is a thread-local variable.
+    ///
+    /// The address reported for such a symbol as part of symbol lookup
+    /// is its offset in the module's thread-local storage block, not a
+    /// virtual address.
+    Tls,
 }
 
 
@@ -119,6 +137,9 @@ pub mod helper {
 
     pub use crate::normalize::buildid::read_elf_build_id;
     pub use crate::normalize::buildid::read_elf_build_id_from_mmap;
+    cfg_apk! {
+        pub use crate::normalize::buildid::read_apk_build_ids;
+    }
 
     cfg_breakpad! {
         pub use crate::breakpad::BreakpadResolver;
@@ -210,7 +231,7 @@ mod tests {
 
         let resolver = helper::GsymResolver::open(test_gsym).unwrap();
         let sym = resolver
-            .find_sym(0x2000100, &FindSymOpts::Basic)
+            .find_sym(0x2000100, &FindSymOpts::BASIC)
             .unwrap()
             .unwrap();
         assert_eq!(sym.name, "factorial");
@@ -0,0 +1,8 @@
+//! Functionality for working with Mach-O files.
+
+mod parser;
+mod resolver;
+
+pub(crate) use parser::MachOParser;
+pub(crate) use resolver::MachOResolver;
+pub(crate) use resolver::MachOResolverData;
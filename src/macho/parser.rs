@@ -0,0 +1,432 @@
+use std::fs::File;
+use std::ops::Range;
+use std::path::Path;
+use std::path::PathBuf;
+
+use goblin::mach::constants::cputype::get_arch_name_from_types;
+use goblin::mach::constants::cputype::CpuSubType;
+use goblin::mach::constants::cputype::CpuType;
+use goblin::mach::constants::cputype::CPU_SUBTYPE_MASK;
+use goblin::mach::constants::S_ATTR_PURE_INSTRUCTIONS;
+use goblin::mach::constants::S_ATTR_SOME_INSTRUCTIONS;
+use goblin::mach::fat::FatArch;
+use goblin::mach::load_command::CommandVariant;
+use goblin::mach::symbols::N_EXT;
+use goblin::mach::symbols::N_SECT;
+use goblin::mach::symbols::N_WEAK_DEF;
+use goblin::mach::Mach;
+use goblin::mach::MachO;
+use goblin::mach::MultiArch;
+
+use crate::inspect::SymBinding;
+use crate::mmap::Mmap;
+use crate::Addr;
+use crate::Error;
+use crate::ErrorExt as _;
+use crate::Result;
+use crate::SymType;
+
+
+/// The name of the segment containing DWARF debug information.
+const DWARF_SEGMENT: &str = "__DWARF";
+
+
+/// A CPU type and sub-type pair, identifying an architecture.
+pub(crate) type Cpu = (CpuType, CpuSubType);
+
+
+/// Retrieve a human readable name for the architecture identified by
+/// `cpu`.
+fn arch_name((cputype, cpusubtype): Cpu) -> String {
+    match get_arch_name_from_types(cputype, cpusubtype) {
+        Some(name) => name.to_string(),
+        None => format!("{cputype:#x}/{cpusubtype:#x}"),
+    }
+}
+
+
+/// Select the slice of the universal binary `multi` for the
+/// architecture `cpu`.
+///
+/// If no architecture is provided, the binary has to contain a single
+/// slice only.
+fn select_arch(multi: &MultiArch<'_>, cpu: Option<Cpu>) -> Result<FatArch> {
+    let arches = multi.arches().map_err(Error::with_invalid_data)?;
+    let arch_cpu = |arch: &FatArch| (arch.cputype(), arch.cpusubtype() & !CPU_SUBTYPE_MASK);
+    let names = || {
+        arches
+            .iter()
+            .map(|arch| arch_name(arch_cpu(arch)))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    match cpu {
+        Some(cpu) => arches
+            .iter()
+            .find(|arch| arch_cpu(arch) == cpu)
+            .copied()
+            .ok_or_else(|| {
+                Error::with_not_found(format!(
+                    "universal Mach-O binary does not contain architecture `{}` (available: {})",
+                    arch_name(cpu),
+                    names()
+                ))
+            }),
+        None => match arches.as_slice() {
+            [arch] => Ok(*arch),
+            _ => Err(Error::with_invalid_input(format!(
+                "universal Mach-O binary contains multiple architectures ({}); one has to be selected",
+                names()
+            ))),
+        },
+    }
+}
+
+
+/// Convert a DWARF section name as used in ELF files (e.g.,
+/// `.debug_info`) into the corresponding Mach-O section name (e.g.,
+/// `__debug_info`).
+///
+/// Mach-O section names are limited to 16 bytes, meaning that longer
+/// names are truncated (`.debug_str_offsets` becomes
+/// `__debug_str_offs`).
+fn macho_section_name(name: &str) -> String {
+    let name = name.strip_prefix('.').unwrap_or(name);
+    let mut name = format!("__{name}");
+    let () = name.truncate(16);
+    name
+}
+
+
+/// Information about a section.
+#[derive(Debug)]
+pub(crate) struct Section {
+    /// The name of the section.
+    pub name: String,
+    /// The virtual address at which the section gets mapped.
+    pub addr: u64,
+    /// The size of the section in memory.
+    pub size: u64,
+    /// Whether the section contains instructions.
+    pub is_code: bool,
+}
+
+
+/// A symbol from the `nlist` symbol table of a Mach-O file.
+#[derive(Debug)]
+pub(crate) struct Symbol {
+    /// The name of the symbol, without the leading underscore that is
+    /// typically added by compilers.
+    pub name: String,
+    /// The address of the symbol.
+    pub addr: u64,
+    /// The extent of the symbol.
+    ///
+    /// The symbol table does not record sizes. Symbols are assumed to
+    /// extend up to the following symbol or the end of the section.
+    pub size: u64,
+    /// The type of the symbol.
+    pub sym_type: SymType,
+    /// The binding of the symbol.
+    pub binding: SymBinding,
+    /// The index of the section containing the symbol, as understood by
+    /// [`MachOParser::section`].
+    pub section: usize,
+}
+
+
+/// Information about a segment, as required for translating file
+/// offsets into virtual addresses.
+#[derive(Debug)]
+struct Segment {
+    /// The virtual address at which the segment gets mapped.
+    vmaddr: u64,
+    /// The file offset at which the segment's data starts.
+    fileoff: u64,
+    /// The size of the segment's data in the file.
+    filesize: u64,
+}
+
+
+/// A parser for Mach-O files.
+///
+/// Only the bits necessary for symbolization based on the `nlist`
+/// symbol table and for working with debug information contained in a
+/// `__DWARF` segment (as is the case for files part of dSYM bundles,
+/// for example) are supported. For universal binaries, a single
+/// architecture slice is worked with.
+#[derive(Debug)]
+pub(crate) struct MachOParser {
+    /// The architecture of the (selected slice of the) file.
+    cpu: Cpu,
+    /// The UUID of the file, if any.
+    uuid: Option<[u8; 16]>,
+    /// The offset of the selected slice inside of a universal binary,
+    /// or zero.
+    slice_offset: u64,
+    /// The segments of the file.
+    segments: Box<[Segment]>,
+    /// All sections of the file, in order.
+    sections: Box<[Section]>,
+    /// Symbols defined in the file, sorted by address.
+    symbols: Box<[Symbol]>,
+    /// The sections of the `__DWARF` segment, by name, along with the
+    /// file range of their data.
+    dwarf_sections: Box<[(String, Range<usize>)]>,
+    /// The memory mapped file.
+    mmap: Mmap,
+    /// The path to the Mach-O file being worked on.
+    path: PathBuf,
+}
+
+impl MachOParser {
+    /// Create a `MachOParser` for the provided file.
+    ///
+    /// `cpu` selects the architecture to work with. For universal
+    /// binaries it may only be omitted if they contain a single slice.
+    pub(crate) fn open_file(file: &File, path: &Path, cpu: Option<Cpu>) -> Result<Self> {
+        let mmap = Mmap::map(file)
+            .with_context(|| format!("failed to memory map Mach-O file `{}`", path.display()))?;
+        Self::from_mmap(mmap, path.to_path_buf(), cpu)
+            .with_context(|| format!("failed to parse Mach-O file `{}`", path.display()))
+    }
+
+    fn from_mmap(mmap: Mmap, path: PathBuf, cpu: Option<Cpu>) -> Result<Self> {
+        let (macho, slice_offset) = match Mach::parse(&mmap).map_err(Error::with_invalid_data)? {
+            Mach::Binary(macho) => {
+                let actual = (
+                    macho.header.cputype,
+                    macho.header.cpusubtype & !CPU_SUBTYPE_MASK,
+                );
+                match cpu {
+                    Some(cpu) if cpu != actual => {
+                        return Err(Error::with_not_found(format!(
+                            "Mach-O file is for architecture `{}`, not `{}`",
+                            arch_name(actual),
+                            arch_name(cpu)
+                        )))
+                    }
+                    _ => (),
+                }
+                (macho, 0)
+            }
+            Mach::Fat(multi) => {
+                let arch = select_arch(&multi, cpu)?;
+                let data = arch.slice(&mmap);
+                let macho = MachO::parse(data, 0).map_err(Error::with_invalid_data)?;
+                (macho, arch.offset)
+            }
+        };
+        let cpu = (
+            macho.header.cputype,
+            macho.header.cpusubtype & !CPU_SUBTYPE_MASK,
+        );
+
+        if macho.little_endian != cfg!(target_endian = "little") {
+            return Err(Error::with_unsupported(
+                "Mach-O files of foreign endianness are not supported",
+            ))
+        }
+
+        let uuid = macho
+            .load_commands
+            .iter()
+            .find_map(|command| match &command.command {
+                CommandVariant::Uuid(uuid) => Some(uuid.uuid),
+                _ => None,
+            });
+
+        let mut segments = Vec::with_capacity(macho.segments.len());
+        let mut sections = Vec::new();
+        let mut dwarf_sections = Vec::new();
+
+        for segment in macho.segments.iter() {
+            let () = segments.push(Segment {
+                vmaddr: segment.vmaddr,
+                fileoff: segment.fileoff,
+                filesize: segment.filesize,
+            });
+
+            let is_dwarf = segment.name().map_err(Error::with_invalid_data)? == DWARF_SEGMENT;
+            for (section, _data) in segment.sections().map_err(Error::with_invalid_data)? {
+                let name = section.name().map_err(Error::with_invalid_data)?;
+                let () = sections.push(Section {
+                    name: name.to_string(),
+                    addr: section.addr,
+                    size: section.size,
+                    is_code: section.flags & (S_ATTR_PURE_INSTRUCTIONS | S_ATTR_SOME_INSTRUCTIONS)
+                        != 0,
+                });
+
+                if !is_dwarf {
+                    continue
+                }
+
+                let start = section.offset as usize + slice_offset as usize;
+                let end = usize::try_from(section.size)
+                    .ok()
+                    .and_then(|size| start.checked_add(size))
+                    .filter(|end| *end <= mmap.len())
+                    .ok_or_else(|| {
+                        Error::with_invalid_data(format!(
+                            "data of Mach-O section `{name}` is out of bounds"
+                        ))
+                    })?;
+                let () = dwarf_sections.push((name.to_string(), start..end));
+            }
+        }
+
+        let symbols = Self::parse_symbols(&macho, &sections)?;
+
+        let slf = Self {
+            cpu,
+            uuid,
+            slice_offset: u64::from(slice_offset),
+            segments: segments.into_boxed_slice(),
+            sections: sections.into_boxed_slice(),
+            symbols,
+            dwarf_sections: dwarf_sections.into_boxed_slice(),
+            mmap,
+            path,
+        };
+        Ok(slf)
+    }
+
+    /// Read the symbols defined in sections of the file from its
+    /// `nlist` symbol table.
+    fn parse_symbols(macho: &MachO<'_>, sections: &[Section]) -> Result<Box<[Symbol]>> {
+        let mut symbols = Vec::new();
+        for result in macho.symbols() {
+            let (name, nlist) = result.map_err(Error::with_invalid_data)?;
+            if nlist.is_stab() || nlist.get_type() != N_SECT || name.is_empty() {
+                continue
+            }
+            // Section numbers are one-based.
+            let section = match nlist.n_sect.checked_sub(1) {
+                Some(idx) if idx < sections.len() => idx,
+                _ => continue,
+            };
+
+            let binding = if nlist.n_type & N_EXT == 0 {
+                SymBinding::Local
+            } else if nlist.n_desc & N_WEAK_DEF != 0 {
+                SymBinding::Weak
+            } else {
+                SymBinding::Global
+            };
+            let sym_type = if sections[section].is_code {
+                SymType::Function
+            } else {
+                SymType::Variable
+            };
+
+            let () = symbols.push(Symbol {
+                name: name.strip_prefix('_').unwrap_or(name).to_string(),
+                addr: nlist.n_value,
+                size: 0,
+                sym_type,
+                binding,
+                section,
+            });
+        }
+        let () = symbols.sort_by_key(|sym| sym.addr);
+
+        // Derive symbol extents from the addresses of subsequent
+        // symbols in the same section.
+        for idx in 0..symbols.len() {
+            let sym = &symbols[idx];
+            let section = &sections[sym.section];
+            let section_end = section.addr.saturating_add(section.size);
+            let end = symbols[idx + 1..]
+                .iter()
+                .map(|next| next.addr)
+                .find(|next| *next > sym.addr)
+                .unwrap_or(section_end)
+                .min(section_end);
+            symbols[idx].size = end.saturating_sub(symbols[idx].addr);
+        }
+        Ok(symbols.into_boxed_slice())
+    }
+
+    /// Retrieve the architecture of the file.
+    #[inline]
+    pub(crate) fn cpu(&self) -> Cpu {
+        self.cpu
+    }
+
+    /// Retrieve the UUID of the file, if present.
+    #[inline]
+    pub(crate) fn uuid(&self) -> Option<&[u8; 16]> {
+        self.uuid.as_ref()
+    }
+
+    /// Retrieve the section with the given index.
+    #[inline]
+    pub(crate) fn section(&self, idx: usize) -> Option<&Section> {
+        self.sections.get(idx)
+    }
+
+    /// Retrieve the symbols of the file, sorted by address.
+    #[inline]
+    pub(crate) fn symbols(&self) -> &[Symbol] {
+        &self.symbols
+    }
+
+    /// Find the symbol covering the provided address.
+    pub(crate) fn find_symbol(&self, addr: Addr) -> Option<&Symbol> {
+        let idx = self.symbols.partition_point(|sym| sym.addr <= addr);
+        let sym = &self.symbols[idx.checked_sub(1)?];
+        (addr - sym.addr < sym.size).then_some(sym)
+    }
+
+    /// Check whether the file contains DWARF debug information.
+    #[inline]
+    pub(crate) fn has_dwarf(&self) -> bool {
+        !self.dwarf_sections.is_empty()
+    }
+
+    /// Retrieve the data of the DWARF section with the given name.
+    ///
+    /// `name` is the section name as it is used in ELF files, e.g.,
+    /// `.debug_info`.
+    pub(crate) fn dwarf_section_data(&self, name: &str) -> Option<&[u8]> {
+        let name = macho_section_name(name);
+        self.dwarf_sections
+            .iter()
+            .find(|(section, _range)| *section == name)
+            .and_then(|(_section, range)| self.mmap.get(range.clone()))
+    }
+
+    /// Translate a file offset into a virtual address.
+    pub(crate) fn file_offset_to_virt_offset(&self, offset: u64) -> Option<Addr> {
+        // Segment offsets are relative to the start of the slice.
+        let offset = offset.checked_sub(self.slice_offset)?;
+        self.segments.iter().find_map(|segment| {
+            (segment.fileoff..segment.fileoff + segment.filesize)
+                .contains(&offset)
+                .then(|| (offset - segment.fileoff + segment.vmaddr) as Addr)
+        })
+    }
+
+    /// Retrieve the path to the file this object operates on.
+    #[inline]
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+
+    /// Check that we map DWARF section names as expected.
+    #[test]
+    fn section_name_mapping() {
+        assert_eq!(macho_section_name(".debug_info"), "__debug_info");
+        assert_eq!(macho_section_name(".debug_line_str"), "__debug_line_str");
+        assert_eq!(macho_section_name(".debug_str_offsets"), "__debug_str_offs");
+    }
+}
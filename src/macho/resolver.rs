@@ -0,0 +1,317 @@
+use std::borrow::Cow;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+use std::fs::read_dir;
+use std::fs::File;
+use std::mem::take;
+use std::path::Path;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use goblin::mach::constants::cputype::get_arch_from_flag;
+
+use crate::dwarf::DwarfResolver;
+use crate::file_cache::FileCache;
+use crate::insert_map::InsertMap;
+use crate::inspect::find_addrs_dedup;
+use crate::inspect::FindAddrOpts;
+use crate::inspect::Inspect;
+use crate::inspect::SymInfo;
+#[cfg(feature = "type-info")]
+use crate::inspect::TypeInfo;
+use crate::inspect::UnitInfo;
+use crate::symbolize::FindSymOpts;
+use crate::symbolize::Provenance;
+use crate::symbolize::Reason;
+use crate::symbolize::ResolvedSym;
+use crate::symbolize::SrcLang;
+use crate::symbolize::Symbolize;
+use crate::symbolize::TranslateFileOffset;
+use crate::Addr;
+use crate::Error;
+use crate::ErrorExt as _;
+use crate::Result;
+use crate::SymType;
+
+use super::parser::Symbol;
+use super::MachOParser;
+
+
+/// Mach-O resolvers created for a file, keyed by the architecture and
+/// dSYM they were created for.
+pub(crate) type MachOResolverData = InsertMap<(Option<String>, Option<PathBuf>), MachOResolver>;
+
+impl FileCache<MachOResolverData> {
+    /// Retrieve the Mach-O resolver for the file at `path`, creating it
+    /// if necessary.
+    pub(crate) fn macho_resolver<'slf>(
+        &'slf self,
+        path: &Path,
+        arch: Option<&str>,
+        dsym: Option<&Path>,
+    ) -> Result<&'slf MachOResolver> {
+        let (file, cell) = self.entry(path)?;
+        let resolvers = cell.get_or_init(InsertMap::new);
+        let key = (arch.map(str::to_string), dsym.map(Path::to_path_buf));
+        resolvers.get_or_try_insert(key, || MachOResolver::from_file(path, file, arch, dsym))
+    }
+}
+
+
+/// Find the file containing DWARF debug information in the dSYM
+/// bundle at `path`.
+///
+/// `path` may also refer to said file directly.
+fn dsym_dwarf_file(path: &Path) -> Result<PathBuf> {
+    if !path.is_dir() {
+        return Ok(path.to_path_buf())
+    }
+
+    let dir = path.join("Contents").join("Resources").join("DWARF");
+    let mut entries = read_dir(&dir)
+        .with_context(|| format!("failed to read dSYM directory `{}`", dir.display()))?;
+    match entries.next() {
+        Some(entry) => {
+            let entry = entry
+                .with_context(|| format!("failed to read dSYM directory `{}`", dir.display()))?;
+            Ok(entry.path())
+        }
+        None => Err(Error::with_not_found(format!(
+            "dSYM directory `{}` does not contain any files",
+            dir.display()
+        ))),
+    }
+}
+
+
+/// The symbol resolver for a single Mach-O file.
+///
+/// Symbolization is based on DWARF debug information, contained either
+/// in the file's own `__DWARF` segment or in an accompanying dSYM, if
+/// available. The `nlist` symbol table is used otherwise.
+pub(crate) struct MachOResolver {
+    /// The DWARF resolver used for symbolization, if debug information
+    /// is available.
+    dwarf: Option<DwarfResolver>,
+    /// The parser for the Mach-O file.
+    parser: Rc<MachOParser>,
+}
+
+impl MachOResolver {
+    /// Create a `MachOResolver` for the Mach-O file at `path`.
+    ///
+    /// `arch` is the name of the architecture (e.g., `arm64`) to work
+    /// with in the case of a universal binary. `dsym` is the path to a
+    /// dSYM bundle (or the DWARF file contained in it) to use for
+    /// debug information.
+    pub(crate) fn from_file(
+        path: &Path,
+        file: &File,
+        arch: Option<&str>,
+        dsym: Option<&Path>,
+    ) -> Result<Self> {
+        let cpu = match arch {
+            Some(arch) => Some(get_arch_from_flag(arch).ok_or_else(|| {
+                Error::with_invalid_input(format!("unknown Mach-O architecture `{arch}`"))
+            })?),
+            None => None,
+        };
+
+        let parser = Rc::new(MachOParser::open_file(file, path, cpu)?);
+        let dwarf = match dsym {
+            Some(dsym) => {
+                let dsym = dsym_dwarf_file(dsym)?;
+                let file = File::open(&dsym)
+                    .with_context(|| format!("failed to open dSYM file `{}`", dsym.display()))?;
+                let dsym_parser = MachOParser::open_file(&file, &dsym, Some(parser.cpu()))?;
+                match (parser.uuid(), dsym_parser.uuid()) {
+                    (Some(uuid), Some(dsym_uuid)) if uuid != dsym_uuid => {
+                        return Err(Error::with_invalid_input(format!(
+                            "dSYM file `{}` does not match Mach-O file `{}`",
+                            dsym.display(),
+                            path.display()
+                        )))
+                    }
+                    _ => (),
+                }
+                Some(DwarfResolver::from_macho(Rc::new(dsym_parser))?)
+            }
+            None if parser.has_dwarf() => Some(DwarfResolver::from_macho(parser.clone())?),
+            None => None,
+        };
+
+        let slf = Self { dwarf, parser };
+        Ok(slf)
+    }
+
+    /// Invoke `f` for each unique source file that contributed to the
+    /// binary.
+    pub(crate) fn for_each_source_file(&self, f: &mut dyn FnMut(&Path)) -> Result<()> {
+        match &self.dwarf {
+            Some(dwarf) => dwarf.for_each_source_file(f),
+            None => Ok(()),
+        }
+    }
+
+    /// Retrieve information about the compilation unit containing the
+    /// code at `addr`.
+    pub(crate) fn unit_info(&self, addr: Addr) -> Result<Option<UnitInfo>> {
+        match &self.dwarf {
+            Some(dwarf) => dwarf.unit_info(addr),
+            None => Ok(None),
+        }
+    }
+
+    /// Look up information about the type with the given name.
+    #[cfg(feature = "type-info")]
+    pub(crate) fn type_info(&self, name: &str) -> Result<Option<TypeInfo>> {
+        match &self.dwarf {
+            Some(dwarf) => dwarf.type_info(name),
+            None => Ok(None),
+        }
+    }
+
+    /// Look up symbols with the given name in the symbol table.
+    fn find_symbols(&self, name: &str, opts: &FindAddrOpts) -> Vec<SymInfo<'_>> {
+        self.parser
+            .symbols()
+            .iter()
+            .filter(|sym| sym.name == name && matches(sym, opts))
+            .map(sym_info)
+            .collect()
+    }
+}
+
+
+/// Check whether `sym` matches the type and binding constraints of
+/// `opts`.
+fn matches(sym: &Symbol, opts: &FindAddrOpts) -> bool {
+    let type_matches = match opts.sym_type {
+        SymType::Undefined => true,
+        sym_type => sym.sym_type == sym_type,
+    };
+    let binding_matches = match opts.binding {
+        Some(binding) => sym.binding == binding,
+        None => true,
+    };
+    type_matches && binding_matches
+}
+
+/// Convert a [`Symbol`] into a [`SymInfo`].
+fn sym_info(sym: &Symbol) -> SymInfo<'_> {
+    SymInfo {
+        name: Cow::Borrowed(&sym.name),
+        addr: sym.addr as Addr,
+        size: usize::try_from(sym.size).unwrap_or(usize::MAX),
+        sym_type: sym.sym_type,
+        binding: Some(sym.binding),
+        version: None,
+        is_ifunc: false,
+        file_offset: None,
+        obj_file_name: None,
+    }
+}
+
+impl Symbolize for MachOResolver {
+    #[cfg_attr(feature = "tracing", crate::log::instrument(fields(addr = format_args!("{addr:#x}"))))]
+    fn find_sym(&self, addr: Addr, opts: &FindSymOpts) -> Result<Result<ResolvedSym<'_>, Reason>> {
+        let reason = match &self.dwarf {
+            Some(dwarf) => match dwarf.find_sym(addr, opts)? {
+                Ok(sym) => return Ok(Ok(sym)),
+                Err(reason) => reason,
+            },
+            None => Reason::MissingSyms,
+        };
+
+        match self.parser.find_symbol(addr) {
+            Some(sym) if sym.sym_type == SymType::Function => {
+                let sym = ResolvedSym {
+                    name: &sym.name,
+                    addr: sym.addr as Addr,
+                    // The extent is merely derived from surrounding
+                    // symbols, so we don't report it as size.
+                    size: None,
+                    lang: SrcLang::Unknown,
+                    code_info: None,
+                    inlined: Box::new([]),
+                    provenance: Provenance::Symbol,
+                    is_synthetic: false,
+                    raw_elf_sym: None,
+                    compilation_unit: None,
+                    version: None,
+                    section: self
+                        .parser
+                        .section(sym.section)
+                        .map(|section| section.name.as_str()),
+                    linkage_name: None,
+                };
+                Ok(Ok(sym))
+            }
+            _ if !self.parser.symbols().is_empty() => Ok(Err(Reason::UnknownAddr)),
+            _ => Ok(Err(reason)),
+        }
+    }
+}
+
+impl TranslateFileOffset for MachOResolver {
+    fn file_offset_to_virt_offset(&self, file_offset: u64) -> Result<Option<Addr>> {
+        Ok(self.parser.file_offset_to_virt_offset(file_offset))
+    }
+}
+
+impl Inspect for MachOResolver {
+    fn find_addr<'slf>(&'slf self, name: &str, opts: &FindAddrOpts) -> Result<Vec<SymInfo<'slf>>> {
+        if let Some(dwarf) = &self.dwarf {
+            let syms = dwarf.find_addr(name, opts)?;
+            if !syms.is_empty() {
+                return Ok(syms)
+            }
+        }
+        Ok(self.find_symbols(name, opts))
+    }
+
+    fn find_addrs<'slf>(
+        &'slf self,
+        names: &[&str],
+        opts: &FindAddrOpts,
+    ) -> Result<Vec<Vec<SymInfo<'slf>>>> {
+        if let Some(dwarf) = &self.dwarf {
+            // DWARF lookup happens in a single pass over all units. We
+            // only fall back to the symbol table for names that it could
+            // not resolve.
+            let mut dwarf_syms = dwarf.find_addrs(names, opts)?;
+            return find_addrs_dedup(names, |idx, name| {
+                let syms = take(&mut dwarf_syms[idx]);
+                if !syms.is_empty() {
+                    Ok(syms)
+                } else {
+                    Ok(self.find_symbols(name, opts))
+                }
+            })
+        }
+
+        find_addrs_dedup(names, |_idx, name| Ok(self.find_symbols(name, opts)))
+    }
+
+    fn for_each(&self, opts: &FindAddrOpts, f: &mut dyn FnMut(&SymInfo<'_>)) -> Result<()> {
+        let symbols = self.parser.symbols();
+        match &self.dwarf {
+            // Prefer the symbol table, if present, as it is the
+            // canonical listing of a binary's symbols.
+            Some(dwarf) if symbols.is_empty() => dwarf.for_each(opts, f),
+            _ => {
+                for sym in symbols.iter().filter(|sym| matches(sym, opts)) {
+                    let () = f(&sym_info(sym));
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Debug for MachOResolver {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "Mach-O {}", self.parser.path().display())
+    }
+}
@@ -1,3 +1,22 @@
+//! Functionality for parsing `/proc/<pid>/maps` style files.
+//!
+//! Each line of such a file describes a memory mapping of a process
+//! and is represented by a [`MapsEntry`]. Entries can be parsed from
+//! the maps file of a running process ([`parse`]) or from any reader
+//! providing data in the same format ([`parse_file`]). Parsing happens
+//! lazily, one entry at a time, without reading the entire file into
+//! memory.
+//!
+//! The kind of entity backing a mapping is described by
+//! [`MapsEntry::path_name`]:
+//! - file backed mappings are represented by [`PathName::Path`], with
+//!   [`EntryPath::deleted`] indicating whether the file has since been deleted
+//! - special mappings such as `[stack]` or `[vdso]` are represented by
+//!   [`PathName::Component`]
+//! - anonymous mappings have no path name at all
+
+use std::borrow::Cow;
+use std::ffi::OsStr;
 use std::fmt::Debug;
 use std::fmt::Formatter;
 use std::fmt::Result as FmtResult;
@@ -7,8 +26,13 @@ use std::io::BufReader;
 use std::io::Error;
 use std::io::ErrorKind;
 use std::io::Read;
+use std::ops::BitOr;
+use std::ops::BitOrAssign;
 use std::ops::Range;
+use std::os::unix::ffi::OsStrExt as _;
+use std::path::Path;
 use std::path::PathBuf;
+use std::str;
 
 use crate::util;
 use crate::util::bytes_to_path;
@@ -22,6 +46,10 @@ use crate::Pid;
 use crate::Result;
 
 
+/// The suffix the kernel appends to paths of deleted files.
+const DELETED_SUFFIX: &[u8] = b" (deleted)";
+
+
 /// Path information about a process member.
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub struct EntryPath {
@@ -37,11 +65,63 @@ pub struct EntryPath {
     /// parsed. This path has been sanitized and no longer contains any
     /// `(deleted)` suffixes.
     pub symbolic_path: PathBuf,
+    /// Whether the file backing the entry has been deleted.
+    ///
+    /// The file may still be accessible via `maps_file`.
+    pub deleted: bool,
     /// The struct is non-exhaustive and open to extension.
     #[doc(hidden)]
     pub _non_exhaustive: (),
 }
 
+impl EntryPath {
+    /// Split the symbolic path into that of an archive (an APK or zip
+    /// file) and that of a member inside of it, if it refers to one.
+    ///
+    /// Android reports mappings of shared objects loaded directly from
+    /// an APK with paths such as
+    /// `/data/app/~~X/base.apk!/lib/arm64-v8a/libfoo.so`.
+    pub(crate) fn archive_member(&self) -> Option<(&Path, &Path)> {
+        let bytes = self.symbolic_path.as_os_str().as_bytes();
+        let idx = bytes.windows(2).position(|window| window == b"!/")?;
+        let archive = bytes_to_path(&bytes[..idx]);
+        let member = bytes_to_path(&bytes[idx + 2..]);
+        is_archive(archive).then_some((archive, member))
+    }
+
+    /// Retrieve the path of the archive backing the entry, if it is
+    /// backed by one.
+    pub(crate) fn archive_path(&self) -> Option<&Path> {
+        if is_archive(&self.symbolic_path) {
+            Some(&self.symbolic_path)
+        } else {
+            self.archive_member().map(|(archive, _member)| archive)
+        }
+    }
+
+    /// Check whether the entry is backed by a part of an ART boot image
+    /// (e.g., `boot.art` or `boot-framework.oat`).
+    pub(crate) fn is_art_boot_image(&self) -> bool {
+        let name = self
+            .symbolic_path
+            .file_name()
+            .map(OsStr::as_bytes)
+            .unwrap_or_default();
+        let ext = self.symbolic_path.extension().and_then(OsStr::to_str);
+        name.starts_with(b"boot") && matches!(ext, Some("art" | "oat" | "vdex"))
+    }
+}
+
+
+/// Check whether `path` refers to an archive, judging by its
+/// extension.
+fn is_archive(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(OsStr::to_str),
+        Some("apk" | "zip")
+    )
+}
+
 
 /// The "pathname" component in a proc maps entry. See `proc(5)` section
 /// `/proc/[pid]/maps`.
@@ -57,16 +137,18 @@ pub enum PathName {
 }
 
 impl PathName {
-    #[cfg(test)]
-    pub(crate) fn as_path(&self) -> Option<&EntryPath> {
+    /// Retrieve the [`EntryPath`] of a file backed member, if this is
+    /// one.
+    pub fn as_path(&self) -> Option<&EntryPath> {
         match self {
             Self::Path(path) => Some(path),
             _ => None,
         }
     }
 
-    #[cfg(test)]
-    pub(crate) fn as_component(&self) -> Option<&str> {
+    /// Retrieve the component of a non-file system backed member, if
+    /// this is one.
+    pub fn as_component(&self) -> Option<&str> {
         match self {
             Self::Component(comp) => Some(comp),
             _ => None,
@@ -75,13 +157,105 @@ impl PathName {
 }
 
 
-#[derive(Clone)]
-pub(crate) struct MapsEntry {
+/// The permissions of a memory mapping.
+///
+/// Individual permissions can be combined using `|`.
+#[derive(Clone, Copy, Default, Eq, Hash, PartialEq)]
+pub struct Perms(u8);
+
+impl Perms {
+    /// The mapping is readable.
+    pub const READ: Perms = Perms(0b1000);
+    /// The mapping is writable.
+    pub const WRITE: Perms = Perms(0b0100);
+    /// The mapping is executable.
+    pub const EXEC: Perms = Perms(0b0010);
+    /// The mapping is shared (as opposed to private, i.e.,
+    /// copy-on-write).
+    pub const SHARED: Perms = Perms(0b0001);
+
+    /// Create a `Perms` object without any permissions set.
+    #[inline]
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    /// Retrieve the raw bit representation of the permissions.
+    #[inline]
+    pub const fn bits(&self) -> u8 {
+        self.0
+    }
+
+    /// Check whether all permissions in `other` are set.
+    #[inline]
+    pub const fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Check whether any of the permissions in `other` is set.
+    #[inline]
+    pub const fn intersects(&self, other: Self) -> bool {
+        self.0 & other.0 != 0
+    }
+}
+
+impl BitOr for Perms {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, other: Self) -> Self::Output {
+        Self(self.0 | other.0)
+    }
+}
+
+impl BitOrAssign for Perms {
+    #[inline]
+    fn bitor_assign(&mut self, other: Self) {
+        self.0 |= other.0
+    }
+}
+
+impl Debug for Perms {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let flag = |perm, c| if self.contains(perm) { c } else { '-' };
+        let shared = if self.contains(Self::SHARED) {
+            's'
+        } else {
+            'p'
+        };
+        write!(
+            f,
+            "{}{}{}{shared}",
+            flag(Self::READ, 'r'),
+            flag(Self::WRITE, 'w'),
+            flag(Self::EXEC, 'x')
+        )
+    }
+}
+
+
+/// An entry in a proc maps file, describing a single memory mapping.
+#[derive(Clone, Eq, Hash, PartialEq)]
+pub struct MapsEntry {
     /// The virtual address range covered by this entry.
     pub range: Range<Addr>,
-    pub mode: u8,
+    /// The permissions of the mapping.
+    pub perms: Perms,
+    /// The offset of the mapping into the backing file.
     pub offset: u64,
+    /// The major and minor number of the device holding the backing
+    /// file.
+    pub dev: (u32, u32),
+    /// The inode of the backing file.
+    pub inode: u64,
+    /// The path name describing the entity backing the mapping, if
+    /// any.
+    ///
+    /// Anonymous mappings do not have a path name.
     pub path_name: Option<PathName>,
+    /// The struct is non-exhaustive and open to extension.
+    #[doc(hidden)]
+    pub _non_exhaustive: (),
 }
 
 impl AsRef<MapsEntry> for MapsEntry {
@@ -95,21 +269,53 @@ impl Debug for MapsEntry {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
         let Self {
             range,
-            mode,
+            perms,
             offset,
+            dev: (major, minor),
+            inode,
             path_name,
+            _non_exhaustive: (),
         } = self;
 
         f.debug_struct(stringify!(MapsEntry))
             .field(stringify!(range), &format_args!("{range:#x?}"))
-            .field(stringify!(mode), &format_args!("{mode:#06b}"))
+            .field(stringify!(perms), &perms)
             .field(stringify!(offset), &format_args!("{offset:#x}"))
+            .field(stringify!(dev), &format_args!("{major:02x}:{minor:02x}"))
+            .field(stringify!(inode), &inode)
             .field(stringify!(path_name), &path_name)
             .finish()
     }
 }
 
 
+/// Undo the escaping of newline characters the kernel performs on
+/// paths in proc maps.
+fn unescape_path(path: &[u8]) -> Cow<'_, [u8]> {
+    const ESCAPED_NEWLINE: &[u8] = b"\\012";
+
+    if !path
+        .windows(ESCAPED_NEWLINE.len())
+        .any(|window| window == ESCAPED_NEWLINE)
+    {
+        return Cow::Borrowed(path)
+    }
+
+    let mut unescaped = Vec::with_capacity(path.len());
+    let mut rest = path;
+    while !rest.is_empty() {
+        if rest.starts_with(ESCAPED_NEWLINE) {
+            let () = unescaped.push(b'\n');
+            rest = &rest[ESCAPED_NEWLINE.len()..];
+        } else {
+            let () = unescaped.push(rest[0]);
+            rest = &rest[1..];
+        }
+    }
+    Cow::Owned(unescaped)
+}
+
+
 /// Parse a line of a proc maps file.
 fn parse_maps_line<'line>(line: &'line [u8], pid: Pid) -> Result<MapsEntry> {
     let full_line = line;
@@ -128,6 +334,16 @@ fn parse_maps_line<'line>(line: &'line [u8], pid: Pid) -> Result<MapsEntry> {
         })
     };
 
+    let malformed = |component| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "encountered malformed {component} in proc maps line: {}",
+                String::from_utf8_lossy(full_line)
+            ),
+        )
+    };
+
     // Lines have the following format:
     // address           perms offset   dev   inode      pathname
     // 08048000-08049000 r-xp  00000000 03:00 8312       /opt/test
@@ -136,63 +352,53 @@ fn parse_maps_line<'line>(line: &'line [u8], pid: Pid) -> Result<MapsEntry> {
     // a7ed5000-a8008000 r-xp  00000000 03:00 4222       /lib/libc.so.6
     let (address_str, line) = split_once(line, "address range")?;
     // TODO: Use `<[u8]>::split_once` once stabilized.
-    let (loaded_str, end_str) = util::split_once(address_str, |b| *b == b'-').ok_or_else(|| {
-        Error::new(
-            ErrorKind::InvalidData,
-            format!(
-                "encountered malformed address range in proc maps line: {}",
-                String::from_utf8_lossy(full_line)
-            ),
-        )
-    })?;
-    let loaded_addr = from_radix_16(loaded_str).ok_or_else(|| {
-        Error::new(
-            ErrorKind::InvalidData,
-            format!(
-                "encountered malformed start address in proc maps line: {}",
-                String::from_utf8_lossy(full_line)
-            ),
-        )
-    })?;
-    let end_addr = from_radix_16(end_str).ok_or_else(|| {
-        Error::new(
-            ErrorKind::InvalidData,
-            format!(
-                "encountered malformed end address in proc maps line: {}",
-                String::from_utf8_lossy(full_line)
-            ),
-        )
-    })?;
+    let (loaded_str, end_str) =
+        util::split_once(address_str, |b| *b == b'-').ok_or_else(|| malformed("address range"))?;
+    let loaded_addr = from_radix_16(loaded_str).ok_or_else(|| malformed("start address"))?;
+    let end_addr = from_radix_16(end_str).ok_or_else(|| malformed("end address"))?;
 
-    let (mode_str, line) = split_once(line, "permissions component")?;
-    let mode = mode_str
+    let (perms_str, line) = split_once(line, "permissions component")?;
+    let perms = perms_str
         .iter()
-        .fold(0, |mode, b| (mode << 1) | u8::from(*b != b'-'));
+        .zip([Perms::READ, Perms::WRITE, Perms::EXEC, Perms::SHARED])
+        .fold(Perms::empty(), |perms, (b, perm)| {
+            if matches!(b, b'-' | b'p') {
+                perms
+            } else {
+                perms | perm
+            }
+        });
 
     let (offset_str, line) = split_once(line, "offset component")?;
-    let offset = from_radix_16(offset_str).ok_or_else(|| {
-        Error::new(
-            ErrorKind::InvalidData,
-            format!(
-                "encountered malformed offset component in proc maps line: {}",
-                String::from_utf8_lossy(full_line)
-            ),
-        )
-    })?;
+    let offset = from_radix_16(offset_str).ok_or_else(|| malformed("offset component"))?;
+
+    let (dev_str, line) = split_once(line, "device component")?;
+    let dev = util::split_once(dev_str, |b| *b == b':')
+        .and_then(|(major, minor)| {
+            let major = u32::try_from(from_radix_16(major)?).ok()?;
+            let minor = u32::try_from(from_radix_16(minor)?).ok()?;
+            Some((major, minor))
+        })
+        .ok_or_else(|| malformed("device component"))?;
 
-    let (_dev, line) = split_once(line, "device component")?;
     // Note that by design, a path may not be present and so we may not be able
     // to successfully split.
-    let path_str = split_once_opt(line)
-        .map(|(_inode, line)| trim_ascii(line))
-        .unwrap_or(b"");
+    let (inode_str, path_str) = split_once_opt(line)
+        .map(|(inode, line)| (inode, trim_ascii(line)))
+        .unwrap_or((trim_ascii(line), b""));
+    let inode = str::from_utf8(inode_str)
+        .ok()
+        .and_then(|inode| inode.parse::<u64>().ok())
+        .ok_or_else(|| malformed("inode component"))?;
 
     let path_name = match path_str {
         [] => None,
         [b'/', ..] => {
-            let symbolic_path =
-                bytes_to_path(path_str.strip_suffix(b" (deleted)").unwrap_or(path_str))
-                    .to_path_buf();
+            let (path_str, deleted) = match path_str.strip_suffix(DELETED_SUFFIX) {
+                Some(path_str) => (path_str, true),
+                None => (path_str, false),
+            };
+            let symbolic_path = bytes_to_path(&unescape_path(path_str)).to_path_buf();
             // TODO: May have to resolve the symbolic link in case of
             //       `Pid::Slf` here for remote symbolization use cases.
             let maps_file = PathBuf::from(format!(
@@ -201,6 +407,7 @@ fn parse_maps_line<'line>(line: &'line [u8], pid: Pid) -> Result<MapsEntry> {
             Some(PathName::Path(EntryPath {
                 maps_file,
                 symbolic_path,
+                deleted,
                 _non_exhaustive: (),
             }))
         }
@@ -215,9 +422,12 @@ fn parse_maps_line<'line>(line: &'line [u8], pid: Pid) -> Result<MapsEntry> {
 
     let entry = MapsEntry {
         range: (loaded_addr..end_addr),
-        mode,
+        perms,
         offset,
+        dev,
+        inode,
         path_name,
+        _non_exhaustive: (),
     };
     Ok(entry)
 }
@@ -245,7 +455,7 @@ where
                 Ok(_) => {
                     // There shouldn't be any empty lines, but we'd just ignore them. We
                     // need to trim anyway.
-                    if !self.line.is_empty() {
+                    if !trim_ascii(&self.line).is_empty() {
                         let result = parse_maps_line(&self.line, self.pid);
                         break Some(result)
                     }
@@ -257,7 +467,12 @@ where
 
 
 /// Parse a proc maps file from the provided reader.
-pub(crate) fn parse_file<R>(reader: R, pid: Pid) -> impl Iterator<Item = Result<MapsEntry>>
+///
+/// `pid` is the ID of the process the maps file belongs to. It is used
+/// for constructing [`EntryPath::maps_file`] paths.
+///
+/// Entries are parsed lazily as the returned iterator is advanced.
+pub fn parse_file<R>(reader: R, pid: Pid) -> impl Iterator<Item = Result<MapsEntry>>
 where
     R: Read,
 {
@@ -271,7 +486,9 @@ where
 }
 
 /// Parse the maps file for the process with the given PID.
-pub(crate) fn parse(pid: Pid) -> Result<impl Iterator<Item = Result<MapsEntry>>> {
+///
+/// Entries are parsed lazily as the returned iterator is advanced.
+pub fn parse(pid: Pid) -> Result<impl Iterator<Item = Result<MapsEntry>>> {
     let path = format!("/proc/{pid}/maps");
     let file =
         File::open(&path).with_context(|| format!("failed to open proc maps file {path}"))?;
@@ -279,17 +496,33 @@ pub(crate) fn parse(pid: Pid) -> Result<impl Iterator<Item = Result<MapsEntry>>>
     Ok(iter)
 }
 
+/// Find the entry covering `addr` in a list of entries sorted by
+/// address, as they are reported in a proc maps file.
+pub fn find_entry<M>(entries: &[M], addr: Addr) -> Option<&M>
+where
+    M: AsRef<MapsEntry>,
+{
+    let idx = entries.partition_point(|entry| entry.as_ref().range.end <= addr);
+    entries
+        .get(idx)
+        .filter(|entry| entry.as_ref().range.contains(&addr))
+}
+
 /// A helper function checking whether a `MapsEntry` has relevance to
 /// symbolization efforts.
 pub(crate) fn filter_relevant(entry: &MapsEntry) -> bool {
     // Only readable (r---) or executable (--x-) entries are of relevance.
-    if (entry.mode & 0b1010) == 0 {
+    if !entry.perms.intersects(Perms::READ | Perms::EXEC) {
         return false
     }
 
     match entry.path_name {
         Some(PathName::Path(..)) => true,
-        Some(PathName::Component(..)) => false,
+        // The vDSO and `vsyscall` page contain code, while other
+        // components (such as `[stack]` or `[heap]`) do not.
+        Some(PathName::Component(ref component)) => {
+            component == "[vdso]" || component == "[vsyscall]"
+        }
         None => true,
     }
 }
@@ -400,7 +633,9 @@ ffffffffff600000-ffffffffff601000 --xp 00000000 00:00 0                  [vsysca
         let entry = parse_maps_line(lines.lines().nth(6).unwrap().as_bytes(), Pid::Slf).unwrap();
         assert_eq!(entry.range.start, 0x55f4a95cb000);
         assert_eq!(entry.range.end, 0x55f4a95cf000);
-        assert_eq!(entry.mode, 0b1011);
+        assert_eq!(entry.perms, Perms::READ | Perms::EXEC);
+        assert_eq!(entry.dev, (0, 0x20));
+        assert_eq!(entry.inode, 41445);
         assert_eq!(
             entry
                 .path_name
@@ -416,7 +651,7 @@ ffffffffff600000-ffffffffff601000 --xp 00000000 00:00 0                  [vsysca
         let entry = parse_maps_line(lines.lines().nth(10).unwrap().as_bytes(), Pid::Slf).unwrap();
         assert_eq!(entry.range.start, 0x55f4aa379000);
         assert_eq!(entry.range.end, 0x55f4aa39a000);
-        assert_eq!(entry.mode, 0b1101);
+        assert_eq!(entry.perms, Perms::READ | Perms::WRITE);
         assert_eq!(
             entry.path_name.as_ref().unwrap().as_component().unwrap(),
             "[heap]",
@@ -424,17 +659,17 @@ ffffffffff600000-ffffffffff601000 --xp 00000000 00:00 0                  [vsysca
         assert_eq!(entry.path_name.as_ref().unwrap().as_path(), None);
 
         let entry = parse_maps_line(lines.lines().nth(12).unwrap().as_bytes(), Pid::Slf).unwrap();
-        assert_eq!(entry.mode, 0b1001);
+        assert_eq!(entry.perms, Perms::READ);
+        let path = entry.path_name.as_ref().unwrap().as_path().unwrap();
         assert_eq!(
-            entry
-                .path_name
-                .as_ref()
-                .unwrap()
-                .as_path()
-                .unwrap()
-                .maps_file,
+            path.maps_file,
             Path::new("/proc/self/map_files/7f2321e00000-7f2321e37000")
         );
+        assert_eq!(
+            path.symbolic_path,
+            Path::new("/usr/lib64/libgnutls.so.30.34.1")
+        );
+        assert!(path.deleted);
 
         let entry = parse_maps_line(lines.lines().nth(23).unwrap().as_bytes(), Pid::Slf).unwrap();
         assert_eq!(entry.range.start, 0x7fa7bb5fa000);
@@ -442,6 +677,85 @@ ffffffffff600000-ffffffffff601000 --xp 00000000 00:00 0                  [vsysca
         assert_eq!(entry.path_name, None);
     }
 
+    /// Check that we can parse proc maps lines with unusual but valid
+    /// contents.
+    #[test]
+    fn unusual_map_line_parsing() {
+        let lines = r#"7f8b34a00000-7f8b34a28000 r--p 00000000 0000000000000000000000000000000000000000fd:0000000000000001 1311204 /usr/lib/x86_64-linux-gnu/libc.so.6
+7f8b34c00000-7f8b34c02000 r-xp 00001000 fd:01 2359602                    /home/user/My Projects/lib with spaces.so
+7f8b34e00000-7f8b34e02000 r-xp 00001000 fd:01 2359603                    /tmp/new\012line.so (deleted)
+7f8b35000000-7f8b35001000 rw-s 00000000 00:01 1034                       /memfd:shm (deleted)
+7ffd03212000-7ffd03234000 rw-p 00000000 00:00 0                          [stack]
+7ffd03300000-7ffd03301000 rw-p 00000000 00:00 0
+"#;
+
+        let entries = parse_file(lines.as_bytes(), Pid::Slf)
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(entries.len(), 6);
+
+        assert_eq!(entries[0].dev, (0xfd, 0x1));
+        assert_eq!(entries[0].inode, 1311204);
+        let path = entries[0].path_name.as_ref().unwrap().as_path().unwrap();
+        assert_eq!(
+            path.symbolic_path,
+            Path::new("/usr/lib/x86_64-linux-gnu/libc.so.6")
+        );
+
+        let path = entries[1].path_name.as_ref().unwrap().as_path().unwrap();
+        assert_eq!(
+            path.symbolic_path,
+            Path::new("/home/user/My Projects/lib with spaces.so")
+        );
+        assert!(!path.deleted);
+
+        let path = entries[2].path_name.as_ref().unwrap().as_path().unwrap();
+        assert_eq!(path.symbolic_path, Path::new("/tmp/new\nline.so"));
+        assert!(path.deleted);
+
+        assert_eq!(entries[3].perms, Perms::READ | Perms::WRITE | Perms::SHARED);
+        let path = entries[3].path_name.as_ref().unwrap().as_path().unwrap();
+        assert_eq!(path.symbolic_path, Path::new("/memfd:shm"));
+        assert!(path.deleted);
+
+        assert_eq!(
+            entries[4].path_name.as_ref().unwrap().as_component(),
+            Some("[stack]")
+        );
+        assert_eq!(entries[5].path_name, None);
+        assert_eq!(entries[5].inode, 0);
+    }
+
+    /// Check that we can find the entry covering an address.
+    #[test]
+    fn entry_lookup() {
+        let lines = r#"00400000-00401000 r--p 00000000 00:29 47459                              /tmp/test/test
+00401000-00402000 r-xp 00001000 00:29 47459                              /tmp/test/test
+00404000-00405000 rw-p 00003000 00:29 47459                              /tmp/test/test
+"#;
+        let entries = parse_file(lines.as_bytes(), Pid::Slf)
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(find_entry(&entries, 0x3fffff), None);
+        assert_eq!(find_entry(&entries, 0x400000), Some(&entries[0]));
+        assert_eq!(find_entry(&entries, 0x401fff), Some(&entries[1]));
+        assert_eq!(find_entry(&entries, 0x402000), None);
+        assert_eq!(find_entry(&entries, 0x404800), Some(&entries[2]));
+        assert_eq!(find_entry(&entries, 0x405000), None);
+    }
+
+    /// Check that `Perms` objects are represented as in proc maps.
+    #[test]
+    fn perms_debug_repr() {
+        assert_eq!(format!("{:?}", Perms::empty()), "---p");
+        assert_eq!(format!("{:?}", Perms::READ | Perms::EXEC), "r-xp");
+        assert_eq!(
+            format!("{:?}", Perms::READ | Perms::WRITE | Perms::SHARED),
+            "rw-s"
+        );
+    }
+
     /// Check that we error out as expected on malformed proc maps lines.
     #[test]
     fn malformed_proc_maps_lines() {
@@ -457,6 +771,10 @@ ffffffffff600000-ffffffffff601000 --xp 00000000 00:00 0                  [vsysca
             b"7fa7bb75a000-7fa7bb75c000 r--p".as_slice(),
             b"7fa7bb75a000-7fa7bb75c000 r--p 00000000".as_slice(),
             b"7fa7bb75a000-7fa7bb75c000 r--p 000zz000 00:20".as_slice(),
+            b"7fa7bb75a000-7fa7bb75c000 r--p 00000000 0020 0".as_slice(),
+            b"7fa7bb75a000-7fa7bb75c000 r--p 00000000 00:20 1x".as_slice(),
+            b"7fa7bb75a000-7fa7bb75c000 r--p 00000000 100000000:20 0".as_slice(),
+            b"fffffffffffffffff-7fa7bb75c000 r--p 00000000 00:20 0".as_slice(),
         ];
 
         let () = lines.iter().for_each(|line| {
@@ -464,6 +782,52 @@ ffffffffff600000-ffffffffff601000 --xp 00000000 00:00 0                  [vsysca
         });
     }
 
+    /// Check that we correctly classify paths of archive members and
+    /// ART boot images.
+    #[test]
+    fn entry_path_classification() {
+        let entry_path = |path: &str| EntryPath {
+            maps_file: PathBuf::new(),
+            symbolic_path: PathBuf::from(path),
+            deleted: false,
+            _non_exhaustive: (),
+        };
+
+        let path = entry_path("/data/app/~~X/base.apk!/lib/arm64-v8a/libfoo.so");
+        assert_eq!(
+            path.archive_member(),
+            Some((
+                Path::new("/data/app/~~X/base.apk"),
+                Path::new("lib/arm64-v8a/libfoo.so")
+            ))
+        );
+        assert_eq!(
+            path.archive_path(),
+            Some(Path::new("/data/app/~~X/base.apk"))
+        );
+        assert!(!path.is_art_boot_image());
+
+        let path = entry_path("/data/app/base.apk");
+        assert_eq!(path.archive_member(), None);
+        assert_eq!(path.archive_path(), Some(Path::new("/data/app/base.apk")));
+
+        let path = entry_path("/tmp/not-an-archive!/libfoo.so");
+        assert_eq!(path.archive_member(), None);
+        assert_eq!(path.archive_path(), None);
+
+        for path in [
+            "/system/framework/arm64/boot.art",
+            "/system/framework/arm64/boot-framework.oat",
+            "/apex/com.android.art/javalib/arm64/boot.vdex",
+        ] {
+            assert!(entry_path(path).is_art_boot_image(), "{path}");
+        }
+
+        for path in ["/system/lib64/libart.so", "/data/dalvik-cache/base.art"] {
+            assert!(!entry_path(path).is_art_boot_image(), "{path}");
+        }
+    }
+
     /// Benchmark the parsing of a large /proc/[pid]/maps file.
     #[cfg(feature = "nightly")]
     #[bench]
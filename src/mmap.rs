@@ -5,6 +5,7 @@ use std::ops::Range;
 use std::os::unix::io::AsRawFd;
 use std::path::Path;
 use std::ptr::null_mut;
+use std::ptr::NonNull;
 use std::rc::Rc;
 use std::slice;
 
@@ -51,14 +52,7 @@ impl Builder {
         // The kernel does not allow mmap'ing a region of size 0. We
         // want to enable this case transparently, though.
         let mmap = if len == 0 {
-            let mapping = Mapping {
-                ptr: null_mut(),
-                len: 0,
-            };
-            Mmap {
-                mapping: Rc::new(mapping),
-                view: 0..1,
-            }
+            Mmap::empty()
         } else {
             let offset = 0;
 
@@ -86,6 +80,49 @@ impl Builder {
         };
         Ok(mmap)
     }
+
+    /// Create an anonymous memory mapping containing a copy of the
+    /// provided data.
+    pub fn map_data(self, data: &[u8]) -> Result<Mmap> {
+        self.map_anon(data.len(), |buf| {
+            let () = buf.copy_from_slice(data);
+            Ok(())
+        })
+    }
+
+    /// Create an anonymous memory mapping of `len` bytes, with contents
+    /// initialized by the provided function.
+    ///
+    /// Contrary to heap allocations, anonymous mappings are returned to
+    /// the operating system in their entirety once dropped.
+    pub fn map_anon<F>(self, len: usize, init: F) -> Result<Mmap>
+    where
+        F: FnOnce(&mut [u8]) -> Result<()>,
+    {
+        if len == 0 {
+            return Ok(Mmap::empty())
+        }
+
+        let mapping = Mapping::anon(len)?;
+        let ptr = mapping.ptr;
+        // SAFETY: We just mapped a writable region of `len` bytes at
+        //         `ptr`, which no one else has access to.
+        let buf = unsafe { slice::from_raw_parts_mut(ptr.cast::<u8>(), len) };
+        let () = init(buf)?;
+
+        // SAFETY: `mprotect` with the provided arguments is always safe
+        //         to call.
+        let rc = unsafe { libc::mprotect(ptr, len, self.protection) };
+        if rc != 0 {
+            return Err(Error::from(io::Error::last_os_error()))
+        }
+
+        let mmap = Mmap {
+            mapping: Rc::new(mapping),
+            view: 0..len as u64,
+        };
+        Ok(mmap)
+    }
 }
 
 
@@ -95,6 +132,28 @@ pub(crate) struct Mapping {
     len: usize,
 }
 
+impl Mapping {
+    /// Create a readable and writable anonymous mapping of `len` bytes.
+    fn anon(len: usize) -> Result<Self> {
+        // SAFETY: `mmap` with the provided arguments is always safe to call.
+        let ptr = unsafe {
+            libc::mmap(
+                null_mut(),
+                len,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_PRIVATE | libc::MAP_ANONYMOUS,
+                -1,
+                0,
+            )
+        };
+
+        if ptr == libc::MAP_FAILED {
+            return Err(Error::from(io::Error::last_os_error()))
+        }
+        Ok(Self { ptr, len })
+    }
+}
+
 impl Deref for Mapping {
     type Target = [u8];
 
@@ -136,6 +195,19 @@ impl Mmap {
         Builder::new()
     }
 
+    /// Create an empty `Mmap` object, not backed by any actual memory
+    /// mapping.
+    fn empty() -> Self {
+        let mapping = Mapping {
+            ptr: null_mut(),
+            len: 0,
+        };
+        Self {
+            mapping: Rc::new(mapping),
+            view: 0..1,
+        }
+    }
+
     /// Map the provided file into memory, in its entirety.
     pub(crate) fn map(file: &File) -> Result<Self> {
         Self::builder().map(file)
@@ -156,6 +228,55 @@ impl Mmap {
     }
 }
 
+/// A writable anonymous memory mapping that is populated
+/// incrementally by its user.
+///
+/// Contrary to [`Mmap`], this type does not provide access to the
+/// mapped memory as a whole, as parts of it may be written to while
+/// others are referenced. Users are responsible for upholding Rust's
+/// aliasing rules themselves.
+#[derive(Debug)]
+pub(crate) struct MmapMut {
+    /// The actual memory mapping; `None` if empty.
+    mapping: Option<Mapping>,
+    /// The length of the mapping, in bytes.
+    len: usize,
+}
+
+impl MmapMut {
+    /// Create an anonymous, zero initialized mapping of `len` bytes.
+    ///
+    /// Memory is only committed once written to.
+    pub(crate) fn anon(len: usize) -> Result<Self> {
+        // The kernel does not allow mmap'ing a region of size 0.
+        let mapping = if len == 0 {
+            None
+        } else {
+            Some(Mapping::anon(len)?)
+        };
+        Ok(Self { mapping, len })
+    }
+
+    /// Retrieve the length of the mapping, in bytes.
+    #[inline]
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Retrieve a pointer to the beginning of the mapping.
+    ///
+    /// The pointer is dangling (but suitably aligned for any ELF data
+    /// structure) for empty mappings.
+    #[inline]
+    pub(crate) fn as_mut_ptr(&self) -> *mut u8 {
+        match &self.mapping {
+            Some(mapping) => mapping.ptr.cast(),
+            None => NonNull::<u64>::dangling().as_ptr().cast(),
+        }
+    }
+}
+
+
 impl Deref for Mmap {
     type Target = [u8];
 
@@ -213,6 +334,37 @@ mod tests {
         );
     }
 
+    /// Check that we can create an anonymous mapping from some data.
+    #[test]
+    fn mmap_data() {
+        let mmap = Mmap::builder().map_data(b"").unwrap();
+        assert_eq!(mmap.deref(), &[]);
+
+        let mmap = Mmap::builder().map_data(b"abcdefghijklm").unwrap();
+        assert_eq!(mmap.deref(), b"abcdefghijklm");
+
+        let mmap = mmap.constrain(2..5).unwrap();
+        assert_eq!(mmap.deref(), b"cde");
+    }
+
+    /// Check that we can create an anonymous mapping initialized by a
+    /// user provided function.
+    #[test]
+    fn mmap_anon() {
+        let mmap = Mmap::builder()
+            .map_anon(4, |buf| {
+                let () = buf.copy_from_slice(b"wxyz");
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(mmap.deref(), b"wxyz");
+
+        let err = Mmap::builder()
+            .map_anon(4, |_buf| Err(Error::with_invalid_data("init failed")))
+            .unwrap_err();
+        assert_eq!(err.to_string(), "init failed");
+    }
+
     /// Check that we can properly restrict the view of a `Mmap`.
     #[test]
     fn view_constraining() {
@@ -1,5 +1,7 @@
 use std::borrow::Cow;
 use std::path::Path;
+#[cfg(feature = "apk")]
+use std::path::PathBuf;
 
 use crate::elf;
 use crate::elf::types::Elf64_Nhdr;
@@ -7,7 +9,11 @@ use crate::elf::ElfParser;
 use crate::file_cache::FileCache;
 use crate::log::warn;
 use crate::util::ReadRaw as _;
+#[cfg(feature = "apk")]
+use crate::zip;
 use crate::Error;
+#[cfg(feature = "apk")]
+use crate::ErrorExt as _;
 use crate::IntoError as _;
 use crate::Mmap;
 use crate::Result;
@@ -88,7 +94,7 @@ fn read_build_id_from_section_name(parser: &ElfParser) -> Result<Option<BuildId<
 }
 
 
-fn read_build_id_impl(parser: &ElfParser) -> Result<Option<BuildId>> {
+pub(crate) fn read_build_id_impl(parser: &ElfParser) -> Result<Option<BuildId>> {
     if let Some(build_id) = read_build_id_from_section_name(parser)? {
         Ok(Some(build_id))
     } else if let Some(build_id) = read_build_id_from_notes(parser)? {
@@ -220,6 +226,55 @@ pub fn read_elf_build_id_from_mmap(mmap: &Mmap) -> Result<Option<BuildId<'static
     Ok(buildid)
 }
 
+/// Read the build IDs of all ELF files contained in the APK (or zip
+/// archive) at the given path.
+///
+/// For each ELF member of the archive, its path inside the archive is
+/// reported along with its build ID, if any. Members are inspected in
+/// place, i.e., without extracting them. Compressed members can't be
+/// inspected this way and are skipped, as are members that are not ELF
+/// files.
+#[cfg(feature = "apk")]
+pub fn read_apk_build_ids<P>(path: &P) -> Result<Vec<(PathBuf, Option<BuildId<'static>>)>>
+where
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    let mmap = Mmap::builder().open(path)?;
+    let archive = zip::Archive::with_mmap(mmap)?;
+
+    let mut build_ids = Vec::new();
+    for entry in archive.entries() {
+        let entry = entry?;
+        if entry.is_compressed() || !entry.data.starts_with(b"\x7fELF") {
+            continue
+        }
+
+        let bounds = entry.data_offset..entry.data_offset + entry.data.len() as u64;
+        let mmap = archive
+            .mmap()
+            .constrain(bounds.clone())
+            .ok_or_invalid_data(|| {
+                format!(
+                    "invalid APK entry data bounds ({bounds:?}) in {}",
+                    path.display()
+                )
+            })?;
+        let parser = ElfParser::from_mmap(mmap, entry.path);
+        let build_id = read_build_id_impl(&parser)
+            .with_context(|| {
+                format!(
+                    "failed to read build ID of APK entry {} in {}",
+                    entry.path.display(),
+                    path.display()
+                )
+            })?
+            .map(|build_id| Cow::Owned(build_id.to_vec()));
+        let () = build_ids.push((entry.path.to_path_buf(), build_id));
+    }
+    Ok(build_ids)
+}
+
 
 #[cfg(test)]
 mod tests {
@@ -229,6 +284,9 @@ mod tests {
 
     use test_log::test;
 
+    #[cfg(feature = "apk")]
+    use crate::ErrorKind;
+
 
     /// Check that we can read a binary's build ID based on the ELF section name
     /// as well as ELF section type.
@@ -278,4 +336,44 @@ mod tests {
         let build_id = read_elf_build_id(&elf).unwrap();
         assert_eq!(build_id, None);
     }
+
+    /// Check that we can read the build IDs of ELF files inside an
+    /// APK.
+    #[cfg(feature = "apk")]
+    #[test]
+    fn apk_build_id_reading() {
+        let data_dir = Path::new(&env!("CARGO_MANIFEST_DIR")).join("data");
+        let expected = |name: &str| read_elf_build_id(&data_dir.join(name)).unwrap();
+
+        let build_ids = read_apk_build_ids(&data_dir.join("test.zip")).unwrap();
+        assert_eq!(
+            build_ids,
+            vec![
+                (
+                    PathBuf::from("test-stable-addrs-stripped-elf-with-dwarf.bin"),
+                    expected("test-stable-addrs-stripped-elf-with-dwarf.bin"),
+                ),
+                (PathBuf::from("zip-dir/test-no-debug.bin"), None),
+                (PathBuf::from("libtest-so.so"), expected("libtest-so.so")),
+                (
+                    PathBuf::from("libtest-so-no-separate-code.so"),
+                    expected("libtest-so-no-separate-code.so"),
+                ),
+            ]
+        );
+        assert!(build_ids[2].1.is_some());
+
+        let build_ids = read_apk_build_ids(&data_dir.join("test-zip64-prefixed.zip")).unwrap();
+        assert_eq!(
+            build_ids,
+            vec![
+                (PathBuf::from("zip-dir/test-no-debug.bin"), None),
+                (PathBuf::from("libtest-so.so"), expected("libtest-so.so")),
+            ]
+        );
+
+        // Files that are not archives are reported as errors.
+        let err = read_apk_build_ids(&data_dir.join("libtest-so.so")).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+    }
 }
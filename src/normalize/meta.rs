@@ -1,3 +1,6 @@
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
 use std::path::PathBuf;
 
 use super::buildid::BuildId;
@@ -56,6 +59,46 @@ pub struct Elf<'src> {
     pub path: PathBuf,
     /// The ELF file's build ID, if available.
     pub build_id: Option<BuildId<'src>>,
+    /// The shared object name, as recorded in the `DT_SONAME` entry of
+    /// the ELF file's `.dynamic` section, if requested and available.
+    ///
+    /// Reading of the shared object name can be enabled using
+    /// [`Builder::enable_sonames`][crate::normalize::Builder::enable_sonames].
+    pub soname: Option<String>,
+    /// Whether the ELF file is the process' main executable.
+    ///
+    /// This information is only available when normalizing addresses
+    /// of a live process, where the executable is known via
+    /// `/proc/<pid>/exe`. It is `false` otherwise.
+    pub is_main_exe: bool,
+    /// The struct is non-exhaustive and open to extension.
+    #[doc(hidden)]
+    pub _non_exhaustive: (),
+}
+
+
+/// Meta information about the virtual dynamic shared object (vDSO)
+/// provided by the kernel.
+///
+/// This type is used in the [`UserMeta::Vdso`] variant.
+///
+/// The corresponding offset is relative to the start of the vDSO
+/// image, which is mapped in its entirety.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Vdso {
+    /// The struct is non-exhaustive and open to extension.
+    #[doc(hidden)]
+    pub _non_exhaustive: (),
+}
+
+
+/// Meta information about the legacy `vsyscall` page.
+///
+/// This type is used in the [`UserMeta::Vsyscall`] variant.
+///
+/// The corresponding offset is relative to the start of the page.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Vsyscall {
     /// The struct is non-exhaustive and open to extension.
     #[doc(hidden)]
     pub _non_exhaustive: (),
@@ -108,6 +151,10 @@ pub enum UserMeta<'src> {
     Elf(Elf<'src>),
     /// The address' origin is unknown.
     Unknown(Unknown),
+    /// The address belongs to the vDSO.
+    Vdso(Vdso),
+    /// The address belongs to the `vsyscall` page.
+    Vsyscall(Vsyscall),
 }
 
 impl<'src> UserMeta<'src> {
@@ -134,6 +181,56 @@ impl<'src> UserMeta<'src> {
             _ => None,
         }
     }
+
+    /// Retrieve the [`Vdso`] of this enum, if this variant is active.
+    pub fn vdso(&self) -> Option<&Vdso> {
+        match self {
+            Self::Vdso(vdso) => Some(vdso),
+            _ => None,
+        }
+    }
+
+    /// Retrieve the [`Vsyscall`] of this enum, if this variant is active.
+    pub fn vsyscall(&self) -> Option<&Vsyscall> {
+        match self {
+            Self::Vsyscall(vsyscall) => Some(vsyscall),
+            _ => None,
+        }
+    }
+}
+
+impl Display for UserMeta<'_> {
+    /// Format a short description of the entity that an address
+    /// belongs to, e.g., `library /usr/lib/libc.so.6 (build ID: ...)`.
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::Apk(Apk { path, .. }) => write!(f, "APK {}", path.display()),
+            Self::Elf(Elf {
+                path,
+                build_id,
+                is_main_exe,
+                ..
+            }) => {
+                let kind = if *is_main_exe {
+                    "executable"
+                } else {
+                    "library"
+                };
+                let () = write!(f, "{kind} {}", path.display())?;
+                if let Some(build_id) = build_id {
+                    let () = f.write_str(" (build ID: ")?;
+                    for byte in build_id.iter() {
+                        let () = write!(f, "{byte:02x}")?;
+                    }
+                    let () = f.write_str(")")?;
+                }
+                Ok(())
+            }
+            Self::Unknown(Unknown { .. }) => f.write_str("<unknown>"),
+            Self::Vdso(Vdso { .. }) => f.write_str("[vdso]"),
+            Self::Vsyscall(Vsyscall { .. }) => f.write_str("[vsyscall]"),
+        }
+    }
 }
 
 
@@ -157,6 +254,8 @@ mod tests {
         let meta = UserMeta::Elf(Elf {
             path: PathBuf::from("/tmp/executable.bin"),
             build_id: None,
+            soname: None,
+            is_main_exe: true,
             _non_exhaustive: (),
         });
         assert!(meta.apk().is_none());
@@ -170,5 +269,55 @@ mod tests {
         assert!(meta.apk().is_none());
         assert!(meta.elf().is_none());
         assert!(meta.unknown().is_some());
+        assert!(meta.vdso().is_none());
+
+        let meta = UserMeta::Vdso(Vdso {
+            _non_exhaustive: (),
+        });
+        assert!(meta.unknown().is_none());
+        assert!(meta.vdso().is_some());
+        assert!(meta.vsyscall().is_none());
+
+        let meta = UserMeta::Vsyscall(Vsyscall {
+            _non_exhaustive: (),
+        });
+        assert!(meta.vdso().is_none());
+        assert!(meta.vsyscall().is_some());
+    }
+
+    /// Exercise the `Display` representation of [`UserMeta`].
+    #[test]
+    fn user_meta_display() {
+        let meta = UserMeta::Apk(Apk {
+            path: PathBuf::from("/tmp/archive.apk"),
+            _non_exhaustive: (),
+        });
+        assert_eq!(meta.to_string(), "APK /tmp/archive.apk");
+
+        let mut elf = Elf {
+            path: PathBuf::from("/tmp/libfoo.so"),
+            build_id: Some(BuildId::Borrowed(&[0xde, 0xad, 0x0b, 0xef])),
+            soname: None,
+            is_main_exe: false,
+            _non_exhaustive: (),
+        };
+        assert_eq!(
+            UserMeta::Elf(elf.clone()).to_string(),
+            "library /tmp/libfoo.so (build ID: dead0bef)"
+        );
+        elf.build_id = None;
+        elf.is_main_exe = true;
+        assert_eq!(UserMeta::Elf(elf).to_string(), "executable /tmp/libfoo.so");
+
+        let meta = UserMeta::Unknown(Unknown {
+            reason: Reason::Unsupported,
+            _non_exhaustive: (),
+        });
+        assert_eq!(meta.to_string(), "<unknown>");
+
+        let meta = UserMeta::Vdso(Vdso {
+            _non_exhaustive: (),
+        });
+        assert_eq!(meta.to_string(), "[vdso]");
     }
 }
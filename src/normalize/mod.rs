@@ -40,6 +40,7 @@
 
 pub(crate) mod buildid;
 mod meta;
+mod module;
 mod normalizer;
 mod user;
 
@@ -51,6 +52,10 @@ pub use meta::Apk;
 pub use meta::Elf;
 pub use meta::Unknown;
 pub use meta::UserMeta;
+pub use meta::Vdso;
+pub use meta::Vsyscall;
+pub use module::Module;
+pub use module::ModuleKind;
 pub use normalizer::Builder;
 pub use normalizer::Normalizer;
 // For reasons unknown, we need to `pub use` this type here or the documentation
@@ -79,6 +84,10 @@ pub enum Reason {
     MissingComponent,
     /// The address belonged to an entity that is currently unsupported.
     Unsupported,
+    /// The address belongs to an Android Runtime (ART) boot image
+    /// (e.g., `boot.art` or `boot.oat`), which contains precompiled Java
+    /// code and is not an ordinary ELF binary.
+    ArtBootImage,
 }
 
 impl Display for Reason {
@@ -87,6 +96,7 @@ impl Display for Reason {
             Self::Unmapped => "absolute address not found in virtual memory map of process",
             Self::MissingComponent => "proc maps entry has no component",
             Self::Unsupported => "address belongs to unsupprted entity",
+            Self::ArtBootImage => "address belongs to ART boot image",
         };
 
         f.write_str(s)
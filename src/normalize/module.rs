@@ -0,0 +1,304 @@
+use std::ops::Range;
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::maps::EntryPath;
+use crate::maps::MapsEntry;
+use crate::maps::PathName;
+use crate::maps::Perms;
+use crate::Addr;
+use crate::Result;
+
+use super::buildid::BuildId;
+use super::buildid::BuildIdReader;
+use super::user::entry_paths;
+use super::user::root_path;
+
+
+/// The kind of entity a [`Module`] represents.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum ModuleKind {
+    /// An ELF file (executable or shared object).
+    Elf,
+    /// An APK, with one or more ELF members mapped directly from it.
+    Apk,
+    /// The virtual dynamic shared object provided by the kernel.
+    Vdso,
+    /// A part of an Android Runtime (ART) boot image, such as
+    /// `boot.art` or `boot.oat`.
+    ArtBootImage,
+}
+
+
+/// A module loaded into a process.
+///
+/// A module comprises all consecutive memory mappings of a file,
+/// starting with the one of the file's beginning.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Module<'src> {
+    /// The virtual address range covered by the module's mappings.
+    ///
+    /// The range starts at the module's base address. It may include
+    /// gaps as well as anonymous mappings (such as those for `.bss`
+    /// data) interspersed with the module's mappings.
+    pub range: Range<Addr>,
+    /// The canonical absolute path to the file backing the module.
+    ///
+    /// The path is `None` for modules not backed by a file, such as
+    /// the vDSO.
+    pub path: Option<PathBuf>,
+    /// The build ID of the module, if requested and available.
+    ///
+    /// Build IDs are only read for ELF files.
+    pub build_id: Option<BuildId<'src>>,
+    /// Whether the module is the process' main executable.
+    pub is_main_exe: bool,
+    /// The kind of module.
+    pub kind: ModuleKind,
+    /// The struct is non-exhaustive and open to extension.
+    #[doc(hidden)]
+    pub _non_exhaustive: (),
+}
+
+
+/// A module in the process of being assembled from proc maps entries.
+struct PartialModule {
+    range: Range<Addr>,
+    entry_path: EntryPath,
+    offset: u64,
+    exec: bool,
+}
+
+impl PartialModule {
+    fn new(entry: &MapsEntry, entry_path: &EntryPath) -> Self {
+        Self {
+            range: entry.range.clone(),
+            entry_path: entry_path.clone(),
+            offset: entry.offset,
+            exec: entry.perms.contains(Perms::EXEC),
+        }
+    }
+
+    /// Check whether `entry` is a continuation of this module.
+    fn continued_by(&self, entry: &MapsEntry, entry_path: &EntryPath) -> bool {
+        // A mapping of a file's beginning always starts a new module, as
+        // the same file may be loaded multiple times (e.g., into
+        // different `dlmopen(3)` namespaces).
+        entry_path.symbolic_path == self.entry_path.symbolic_path
+            && entry.offset != 0
+            && entry.offset > self.offset
+    }
+
+    fn extend(&mut self, entry: &MapsEntry) {
+        self.range.end = entry.range.end;
+        self.offset = entry.offset;
+        self.exec |= entry.perms.contains(Perms::EXEC);
+    }
+
+    /// Convert the partial module into a [`Module`], if it actually
+    /// contains code.
+    fn finish<'src>(
+        self,
+        exe: Option<&Path>,
+        proc_root: Option<&Path>,
+        build_id_reader: &dyn BuildIdReader<'src>,
+    ) -> Result<Option<Module<'src>>> {
+        // Files that are not mapped executable (e.g., locale archives
+        // or fonts) are not considered to be modules.
+        if !self.exec {
+            return Ok(None)
+        }
+
+        let Self {
+            range, entry_path, ..
+        } = self;
+        let (kind, path, build_id) = if entry_path.is_art_boot_image() {
+            let (path, _file) = entry_paths(&entry_path, proc_root)?;
+            (ModuleKind::ArtBootImage, path, None)
+        } else if let Some(archive) = entry_path.archive_path() {
+            // Members mapped directly from an archive (e.g.,
+            // `base.apk!/lib/arm64-v8a/libfoo.so`) are reported as
+            // part of the archive.
+            let path = root_path(archive, proc_root)?;
+            (ModuleKind::Apk, path, None)
+        } else {
+            let (path, file) = entry_paths(&entry_path, proc_root)?;
+            let build_id = build_id_reader.read_build_id(&file)?;
+            (ModuleKind::Elf, path, build_id)
+        };
+
+        let module = Module {
+            range,
+            path: Some(path.into_owned()),
+            build_id,
+            is_main_exe: exe == Some(entry_path.symbolic_path.as_path()),
+            kind,
+            _non_exhaustive: (),
+        };
+        Ok(Some(module))
+    }
+}
+
+
+/// Assemble the modules described by the proc maps `entries`.
+///
+/// `exe` is the path of the process' main executable, if known.
+pub(super) fn modules_from_entries<'src, E, M>(
+    entries: E,
+    exe: Option<&Path>,
+    proc_root: Option<&Path>,
+    build_id_reader: &dyn BuildIdReader<'src>,
+) -> Result<Vec<Module<'src>>>
+where
+    E: Iterator<Item = Result<M>>,
+    M: AsRef<MapsEntry>,
+{
+    let mut modules = Vec::new();
+    let mut current = None::<PartialModule>;
+
+    for entry in entries {
+        let entry = entry?;
+        let entry = entry.as_ref();
+
+        match &entry.path_name {
+            Some(PathName::Path(entry_path)) => match &mut current {
+                Some(module) if module.continued_by(entry, entry_path) => {
+                    let () = module.extend(entry);
+                }
+                _ => {
+                    let module = current.replace(PartialModule::new(entry, entry_path));
+                    if let Some(module) = module {
+                        let () = modules.extend(module.finish(exe, proc_root, build_id_reader)?);
+                    }
+                }
+            },
+            Some(PathName::Component(component)) => {
+                if let Some(module) = current.take() {
+                    let () = modules.extend(module.finish(exe, proc_root, build_id_reader)?);
+                }
+
+                if component == "[vdso]" {
+                    let module = Module {
+                        range: entry.range.clone(),
+                        path: None,
+                        build_id: None,
+                        is_main_exe: false,
+                        kind: ModuleKind::Vdso,
+                        _non_exhaustive: (),
+                    };
+                    let () = modules.push(module);
+                }
+            }
+            // Anonymous mappings are commonly used for a module's
+            // zero initialized data and don't end it.
+            None => (),
+        }
+    }
+
+    if let Some(module) = current {
+        let () = modules.extend(module.finish(exe, proc_root, build_id_reader)?);
+    }
+    Ok(modules)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::maps;
+    use crate::normalize::buildid::NoBuildIdReader;
+    use crate::Pid;
+
+
+    /// Check that we assemble modules from proc maps entries as
+    /// expected.
+    #[test]
+    fn module_assembly() {
+        let maps = r#"55d3195b7000-55d3195b9000 r--p 00000000 00:12 2015701                    /bin/cat
+55d3195b9000-55d3195be000 r-xp 00002000 00:12 2015701                    /bin/cat
+55d3195be000-55d3195c1000 r--p 00007000 00:12 2015701                    /bin/cat
+55d31b4dc000-55d31b4fd000 rw-p 00000000 00:00 0                          [heap]
+7fd5b9c5f000-7fd5ba034000 r--p 00000000 00:12 7689533                    /usr/lib/locale/locale-archive
+7fd5ba037000-7fd5ba059000 r--p 00000000 00:12 2088876                    /lib64/libc.so.6
+7fd5ba059000-7fd5ba1a8000 r-xp 00022000 00:12 2088876                    /lib64/libc.so.6
+7fd5ba1a8000-7fd5ba1fa000 r--p 00171000 00:12 2088876                    /lib64/libc.so.6
+7fd5ba200000-7fd5ba208000 rw-p 00000000 00:00 0
+7fd5ba208000-7fd5ba20a000 rw-p 001c7000 00:12 2088876                    /lib64/libc.so.6
+7fd5ba210000-7fd5ba211000 r--p 00000000 00:12 2088876                    /lib64/libc.so.6
+7fd5ba211000-7fd5ba212000 r-xp 00022000 00:12 2088876                    /lib64/libc.so.6
+7fd5ba212000-7fd5ba214000 r-xp 00030000 00:12 2088870                    /data/app/base.apk
+7fd5ba220000-7fd5ba224000 r-xp 00042000 00:12 2088871                    /data/app/other.apk!/lib/arm64-v8a/libfoo.so
+7fd5ba230000-7fd5ba238000 r--p 00000000 00:12 2088872                    /system/framework/arm64/boot.art
+7fd5ba238000-7fd5ba240000 r-xp 00004000 00:12 2088873                    /system/framework/arm64/boot.oat
+7ffe103f6000-7ffe103fa000 r--p 00000000 00:00 0                          [vvar]
+7ffe103fa000-7ffe103fc000 r-xp 00000000 00:00 0                          [vdso]
+"#;
+
+        let entries = maps::parse_file(maps.as_bytes(), Pid::Slf);
+        let modules =
+            modules_from_entries(entries, Some(Path::new("/bin/cat")), None, &NoBuildIdReader)
+                .unwrap();
+
+        let summary = modules
+            .iter()
+            .map(|module| {
+                (
+                    module.range.clone(),
+                    module.path.as_deref().and_then(Path::to_str),
+                    module.is_main_exe,
+                    module.kind,
+                )
+            })
+            .collect::<Vec<_>>();
+        let expected = vec![
+            (
+                0x55d3195b7000..0x55d3195c1000,
+                Some("/bin/cat"),
+                true,
+                ModuleKind::Elf,
+            ),
+            (
+                0x7fd5ba037000..0x7fd5ba20a000,
+                Some("/lib64/libc.so.6"),
+                false,
+                ModuleKind::Elf,
+            ),
+            // The second instance of `libc.so.6` is reported as a
+            // module of its own.
+            (
+                0x7fd5ba210000..0x7fd5ba212000,
+                Some("/lib64/libc.so.6"),
+                false,
+                ModuleKind::Elf,
+            ),
+            (
+                0x7fd5ba212000..0x7fd5ba214000,
+                Some("/data/app/base.apk"),
+                false,
+                ModuleKind::Apk,
+            ),
+            (
+                0x7fd5ba220000..0x7fd5ba224000,
+                Some("/data/app/other.apk"),
+                false,
+                ModuleKind::Apk,
+            ),
+            (
+                0x7fd5ba238000..0x7fd5ba240000,
+                Some("/system/framework/arm64/boot.oat"),
+                false,
+                ModuleKind::ArtBootImage,
+            ),
+            (
+                0x7ffe103fa000..0x7ffe103fc000,
+                None,
+                false,
+                ModuleKind::Vdso,
+            ),
+        ];
+        assert_eq!(summary, expected);
+        assert!(modules.iter().all(|module| module.build_id.is_none()));
+    }
+}
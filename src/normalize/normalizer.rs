@@ -1,3 +1,11 @@
+use std::ffi::OsStr;
+use std::fs::read_link;
+use std::fs::File;
+use std::os::unix::ffi::OsStrExt as _;
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::elf::ElfParser;
 use crate::file_cache::FileCache;
 use crate::insert_map::InsertMap;
 use crate::maps;
@@ -5,15 +13,38 @@ use crate::normalize::buildid::BuildIdReader;
 use crate::normalize::buildid::CachingBuildIdReader;
 use crate::util;
 use crate::Addr;
+use crate::ErrorExt as _;
 use crate::Pid;
 use crate::Result;
 
 use super::buildid::BuildId;
 use super::buildid::DefaultBuildIdReader;
 use super::buildid::NoBuildIdReader;
+use super::meta::Elf;
+use super::meta::Unknown;
+use super::meta::UserMeta;
+use super::module::modules_from_entries;
+use super::module::Module;
 use super::user;
 use super::user::normalize_sorted_user_addrs_with_entries;
 use super::user::UserOutput;
+use super::Reason;
+
+
+/// Retrieve the path of the main executable of the process with the
+/// given PID, if it can be determined.
+fn read_exe(pid: Pid) -> Option<PathBuf> {
+    // Kernel threads, for example, don't have an executable, in
+    // which case reading the link fails.
+    read_link(format!("/proc/{pid}/exe")).ok().map(|exe| {
+        // The kernel marks deleted executables the same way it does
+        // in proc maps, from which the suffix got stripped.
+        match exe.as_os_str().as_bytes().strip_suffix(b" (deleted)") {
+            Some(exe) => PathBuf::from(OsStr::from_bytes(exe)),
+            None => exe,
+        }
+    })
+}
 
 
 /// A type capturing normalized outputs along with captured meta data.
@@ -60,6 +91,12 @@ pub struct Builder {
     /// Whether or not to cache build IDs. This flag only has an effect
     /// if build ID reading is enabled in the first place.
     cache_build_ids: bool,
+    /// Whether to read and report shared object names as part of the
+    /// normalization process.
+    sonames: bool,
+    /// The directory below which to look up files referenced by proc
+    /// maps entries.
+    proc_root: Option<PathBuf>,
 }
 
 impl Builder {
@@ -85,18 +122,58 @@ impl Builder {
         self
     }
 
+    /// Enable/disable the reading of shared object names.
+    ///
+    /// If enabled, the name recorded in the `DT_SONAME` entry of an
+    /// ELF file's `.dynamic` section is reported as part of the
+    /// [`Elf`][crate::normalize::Elf] meta information. This name
+    /// (e.g., `libc.so.6`) is independent of the path at which the
+    /// file is located and may be more suitable for compact reporting.
+    pub fn enable_sonames(mut self, enable: bool) -> Builder {
+        self.sonames = enable;
+        self
+    }
+
+    /// Set the directory below which to look up files referenced by
+    /// `/proc/<pid>/maps` entries.
+    ///
+    /// Paths in a process' maps file are relative to the process' root
+    /// directory. When normalizing addresses of a containerized process
+    /// from outside of the container, that root is typically not the
+    /// root of the normalizing process. If a proc root is set, paths
+    /// are interpreted relative to it instead. E.g., with a root of
+    /// `/var/lib/containers/abc/rootfs`, a maps entry for
+    /// `/lib/libc.so.6` is resolved to
+    /// `/var/lib/containers/abc/rootfs/lib/libc.so.6`.
+    ///
+    /// Symbolic links encountered while resolving a path are
+    /// interpreted relative to the root, i.e., they cannot escape it.
+    /// The resolved paths are the ones reported as part of the
+    /// normalization output.
+    pub fn set_proc_root<P>(mut self, root: P) -> Builder
+    where
+        P: Into<PathBuf>,
+    {
+        self.proc_root = Some(root.into());
+        self
+    }
+
     /// Create the [`Normalizer`] object.
     pub fn build(self) -> Normalizer {
         let Builder {
             cache_maps,
             build_ids,
             cache_build_ids,
+            sonames,
+            proc_root,
         } = self;
 
         Normalizer {
             cache_maps,
             build_ids,
             cache_build_ids,
+            sonames,
+            proc_root,
             cached_entries: InsertMap::new(),
             cached_build_ids: FileCache::default(),
         }
@@ -109,6 +186,8 @@ impl Default for Builder {
             cache_maps: false,
             build_ids: true,
             cache_build_ids: false,
+            sonames: false,
+            proc_root: None,
         }
     }
 }
@@ -143,6 +222,11 @@ pub struct Normalizer {
     /// Whether or not to cache build IDs. This flag only has an effect
     /// if build ID reading is enabled in the first place.
     cache_build_ids: bool,
+    /// Whether to read and report shared object names.
+    sonames: bool,
+    /// The directory below which to look up files referenced by proc
+    /// maps entries, if any.
+    proc_root: Option<PathBuf>,
     /// If `cache_maps` is `true`, the cached parsed
     /// [`MapsEntry`][maps::MapsEntry] objects.
     cached_entries: InsertMap<Pid, Box<[maps::MapsEntry]>>,
@@ -167,26 +251,43 @@ impl Normalizer {
         Builder::default()
     }
 
-    fn normalize_user_addrs_impl<A, E, M>(&self, addrs: A, entries: E) -> Result<UserOutput<'_>>
+    /// Retrieve the build ID reader to use as per our configuration.
+    ///
+    /// `caching_reader` provides storage for a caching reader, should
+    /// one be required.
+    fn build_id_reader<'slf, 'rdr>(
+        &'slf self,
+        caching_reader: &'rdr mut Option<CachingBuildIdReader<'slf>>,
+    ) -> &'rdr dyn BuildIdReader<'slf> {
+        if self.build_ids {
+            if self.cache_build_ids {
+                caching_reader.insert(CachingBuildIdReader::new(&self.cached_build_ids))
+            } else {
+                &DefaultBuildIdReader
+            }
+        } else {
+            &NoBuildIdReader
+        }
+    }
+
+    fn normalize_user_addrs_impl<A, E, M>(
+        &self,
+        addrs: A,
+        entries: E,
+        proc_root: Option<&Path>,
+        exe: Option<&Path>,
+    ) -> Result<UserOutput<'_>>
     where
         A: ExactSizeIterator<Item = Addr> + Clone,
         E: Iterator<Item = Result<M>>,
         M: AsRef<maps::MapsEntry>,
     {
-        let caching_reader;
+        let mut caching_reader = None;
         let addrs_cnt = addrs.len();
-        let reader = if self.build_ids {
-            if self.cache_build_ids {
-                caching_reader = CachingBuildIdReader::new(&self.cached_build_ids);
-                &caching_reader as &dyn BuildIdReader
-            } else {
-                &DefaultBuildIdReader as &dyn BuildIdReader
-            }
-        } else {
-            &NoBuildIdReader as &dyn BuildIdReader
-        };
+        let reader = self.build_id_reader(&mut caching_reader);
 
-        let mut handler = user::NormalizationHandler::new(reader, addrs_cnt);
+        let mut handler =
+            user::NormalizationHandler::new(reader, proc_root, exe, self.sonames, addrs_cnt);
         let () = normalize_sorted_user_addrs_with_entries(addrs, entries, &mut handler)?;
         debug_assert_eq!(handler.normalized.outputs.len(), addrs_cnt);
         Ok(handler.normalized)
@@ -196,9 +297,15 @@ impl Normalizer {
     where
         A: ExactSizeIterator<Item = Addr> + Clone,
     {
+        let exe = read_exe(pid);
         if !self.cache_maps {
             let entries = maps::parse_filtered(pid)?;
-            self.normalize_user_addrs_impl(addrs, entries)
+            self.normalize_user_addrs_impl(
+                addrs,
+                entries,
+                self.proc_root.as_deref(),
+                exe.as_deref(),
+            )
         } else {
             let parsed = self.cached_entries.get_or_try_insert(pid, || {
                 // If we use the cached maps entries but don't have anything
@@ -211,7 +318,12 @@ impl Normalizer {
             })?;
 
             let entries = parsed.iter().map(Ok);
-            self.normalize_user_addrs_impl(addrs, entries)
+            self.normalize_user_addrs_impl(
+                addrs,
+                entries,
+                self.proc_root.as_deref(),
+                exe.as_deref(),
+            )
         }
     }
 
@@ -260,6 +372,138 @@ impl Normalizer {
             |sorted_addrs| self.normalize_user_addrs_iter(sorted_addrs, pid),
         )
     }
+
+    /// Normalize addresses using a captured proc maps file.
+    ///
+    /// Contrary to [`Normalizer::normalize_user_addrs`], this method
+    /// does not require a live process. Instead, memory mappings are
+    /// read from the file at `maps`, which is expected to be in the
+    /// format of `/proc/<pid>/maps`. That makes it possible to
+    /// normalize addresses captured alongside such a dump at a later
+    /// point in time.
+    ///
+    /// Files referenced by the maps entries are looked up below the
+    /// root set via [`Builder::set_proc_root`], if any, and at their
+    /// recorded paths otherwise. The caching of maps entries does
+    /// not apply.
+    ///
+    /// The provided `addrs` array does not have to be sorted.
+    #[cfg_attr(feature = "tracing", crate::log::instrument(skip(self)))]
+    pub fn normalize_user_addrs_with_maps_file(
+        &self,
+        maps: &Path,
+        addrs: &[Addr],
+    ) -> Result<UserOutput<'_>> {
+        // A captured maps file may reference files that are no longer
+        // accessible via `/proc/<pid>/map_files/`, so we always go
+        // through the symbolic paths.
+        let proc_root = self.proc_root.as_deref().unwrap_or_else(|| Path::new("/"));
+
+        util::with_ordered_elems(
+            addrs,
+            |normalized: &mut UserOutput| normalized.outputs.as_mut_slice(),
+            |sorted_addrs| {
+                let file = File::open(maps)
+                    .with_context(|| format!("failed to open proc maps file {}", maps.display()))?;
+                let entries = maps::parse_file(file, Pid::Slf)
+                    .filter(|result| result.as_ref().map(maps::filter_relevant).unwrap_or(true));
+                self.normalize_user_addrs_impl(sorted_addrs, entries, Some(proc_root), None)
+            },
+        )
+    }
+
+    /// Normalize addresses belonging to a single ELF file loaded at a
+    /// known address.
+    ///
+    /// Contrary to [`Normalizer::normalize_user_addrs`], this method
+    /// does not consult any memory mappings. Instead, the caller
+    /// provides the `path` to the ELF file along with `base_addr`,
+    /// the address at which it got loaded (i.e., the start address of
+    /// its first mapping, as reported in `/proc/<pid>/maps`). Each
+    /// address in `addrs` is converted to a file offset relative to
+    /// that base.
+    ///
+    /// All successfully normalized addresses reference the same
+    /// [`Elf`][crate::normalize::Elf] meta entry. Addresses not
+    /// falling into any loadable segment of the file are reported as
+    /// [`Unknown`][crate::normalize::Unknown] with
+    /// [`Reason::Unmapped`].
+    ///
+    /// The provided `addrs` array does not have to be sorted.
+    /// Normalized outputs are reported in the exact same order (and in
+    /// equal amount) in which the non-normalized ones were provided.
+    #[cfg_attr(feature = "tracing", crate::log::instrument(skip(self)))]
+    pub fn normalize_addrs_for_module(
+        &self,
+        path: &Path,
+        base_addr: Addr,
+        addrs: &[Addr],
+    ) -> Result<UserOutput<'_>> {
+        let parser = ElfParser::open(path)?;
+        let load_bias = parser.compute_load_bias(base_addr)?;
+
+        let mut caching_reader = None;
+        let reader = self.build_id_reader(&mut caching_reader);
+        let soname = if self.sonames { parser.soname()? } else { None };
+        let elf = Elf {
+            path: path.to_path_buf(),
+            build_id: reader.read_build_id(path)?,
+            soname,
+            is_main_exe: false,
+            _non_exhaustive: (),
+        };
+
+        let mut normalized = UserOutput {
+            outputs: Vec::with_capacity(addrs.len()),
+            meta: vec![UserMeta::Elf(elf)],
+        };
+        let mut unknown_idx = None;
+
+        for addr in addrs {
+            let file_offset = match Addr::try_from(i128::from(*addr) - i128::from(load_bias)) {
+                Ok(vaddr) => parser
+                    .segment_for_addr(vaddr)?
+                    .map(|phdr| vaddr - phdr.p_vaddr + phdr.p_offset),
+                Err(_) => None,
+            };
+
+            let output = if let Some(file_offset) = file_offset {
+                (file_offset, 0)
+            } else {
+                let idx = *unknown_idx.get_or_insert_with(|| {
+                    let () = normalized.meta.push(Unknown::new(Reason::Unmapped).into());
+                    normalized.meta.len() - 1
+                });
+                (*addr, idx)
+            };
+            let () = normalized.outputs.push(output);
+        }
+        Ok(normalized)
+    }
+
+    /// Retrieve the modules loaded into a process.
+    ///
+    /// A module is an ELF file (the main executable or a shared
+    /// object), an APK with ELF members mapped from it, or the vDSO.
+    /// All consecutive mappings of a file are merged into a single
+    /// [`Module`], anchored at the mapping of the file's beginning.
+    /// Files mapped multiple times (e.g., into different `dlmopen(3)`
+    /// namespaces) are reported as separate modules. Files without any
+    /// executable mapping are not considered modules.
+    ///
+    /// Build IDs are read according to the [`Builder`]
+    /// configuration and files are looked up below the proc root, if
+    /// one is set. The caching of maps entries does not apply.
+    ///
+    /// Modules are reported in ascending order of their addresses.
+    #[cfg_attr(feature = "tracing", crate::log::instrument(skip(self)))]
+    pub fn modules(&self, pid: Pid) -> Result<Vec<Module<'_>>> {
+        let exe = read_exe(pid);
+        let mut caching_reader = None;
+        let reader = self.build_id_reader(&mut caching_reader);
+        let entries = maps::parse(pid)?;
+        modules_from_entries(entries, exe.as_deref(), self.proc_root.as_deref(), reader)
+    }
 }
 
 
@@ -267,20 +511,19 @@ impl Normalizer {
 mod tests {
     use super::*;
 
+    use std::env;
+    use std::fs::write;
     use std::mem::transmute;
-    use std::path::Path;
+
+    use tempfile::tempdir;
 
     use test_log::test;
 
-    use crate::elf::ElfParser;
     use crate::inspect::FindAddrOpts;
     use crate::mmap::Mmap;
     use crate::normalize::buildid::read_elf_build_id;
     use crate::normalize::Apk;
-    use crate::normalize::Elf;
-    use crate::normalize::Reason;
-    use crate::normalize::Unknown;
-    use crate::normalize::UserMeta;
+    use crate::normalize::ModuleKind;
     use crate::symbolize;
     use crate::symbolize::Symbolizer;
     use crate::zip;
@@ -360,6 +603,11 @@ mod tests {
                 .unwrap()
                 .to_string_lossy()
                 .contains("libc.so"));
+            assert!(!meta[errno_meta_idx].elf().unwrap().is_main_exe);
+
+            // Our own functions reside in the main executable.
+            let exe_meta_idx = outputs[4].1;
+            assert!(meta[exe_meta_idx].elf().unwrap().is_main_exe);
         }
 
         let normalizer = Normalizer::new();
@@ -413,7 +661,9 @@ mod tests {
             .join("libtest-so.so");
         let expected_elf = Elf {
             build_id: Some(read_elf_build_id(&so_path).unwrap().unwrap()),
+            soname: None,
             path: so_path,
+            is_main_exe: false,
             _non_exhaustive: (),
         };
         assert_eq!(meta, &UserMeta::Elf(expected_elf));
@@ -448,6 +698,7 @@ mod tests {
             let opts = FindAddrOpts {
                 sym_type: SymType::Function,
                 offset_in_file: true,
+                binding: None,
             };
             let syms = elf_parser.find_addr("the_answer", &opts).unwrap();
             // There is only one symbol with this address in there.
@@ -504,4 +755,128 @@ mod tests {
         test("libtest-so.so");
         test("libtest-so-no-separate-code.so");
     }
+
+    /// Check that we can normalize addresses using a captured proc
+    /// maps file.
+    #[test]
+    fn user_address_normalization_maps_file() {
+        let so = Path::new(&env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join("libtest-so.so");
+        let dir = tempdir().unwrap();
+        let maps = dir.path().join("maps");
+        let contents = format!(
+            r#"7f0000000000-7f0000001000 r-xp 00000000 00:12 1234                       {so}
+7f0000002000-7f0000003000 rw-p 00000000 00:00 0                          [heap]
+7f0000003000-7f0000004000 r-xp 00001000 00:12 1234                       {so}
+"#,
+            so = so.display()
+        );
+        let () = write(&maps, contents).unwrap();
+
+        let build_id = read_elf_build_id(&so).unwrap();
+        let addrs = [0x7f0000003010, 0x7f0000000100, 0x7f0000002010];
+        let normalizer = Normalizer::new();
+        let normalized = normalizer
+            .normalize_user_addrs_with_maps_file(&maps, addrs.as_slice())
+            .unwrap();
+        assert_eq!(normalized.outputs.len(), 3);
+        assert_eq!(normalized.outputs[0].0, 0x1010);
+        assert_eq!(normalized.outputs[1].0, 0x100);
+        assert_eq!(normalized.outputs[0].1, normalized.outputs[1].1);
+
+        let elf = normalized.meta[normalized.outputs[0].1].elf().unwrap();
+        assert_eq!(elf.path, so);
+        assert_eq!(elf.build_id, build_id);
+        assert_eq!(
+            normalized.meta[normalized.outputs[2].1],
+            Unknown::new(Reason::Unmapped).into()
+        );
+
+        // Referenced files are looked up below the proc root, if set.
+        let root = tempdir().unwrap();
+        let normalizer = Normalizer::builder().set_proc_root(root.path()).build();
+        let err = normalizer
+            .normalize_user_addrs_with_maps_file(&maps, addrs.as_slice())
+            .unwrap_err();
+        assert!(
+            err.to_string().contains(&root.path().display().to_string()),
+            "{err}"
+        );
+    }
+
+    /// Check that we can normalize addresses of an ELF file loaded at a
+    /// known base address.
+    #[test]
+    fn module_address_normalization() {
+        let so = Path::new(&env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join("libtest-so.so");
+        let parser = ElfParser::open(&so).unwrap();
+        let opts = FindAddrOpts {
+            offset_in_file: true,
+            ..Default::default()
+        };
+        let syms = parser.find_addr("the_answer", &opts).unwrap();
+        let the_answer = syms.first().unwrap();
+
+        let base = 0x7f0000000000;
+        let load_bias = parser.compute_load_bias(base).unwrap();
+        let addr = (the_answer.addr as i64 + load_bias) as Addr;
+        let addrs = [addr + 2, base - 1, addr];
+        let normalizer = Normalizer::new();
+        let normalized = normalizer
+            .normalize_addrs_for_module(&so, base, addrs.as_slice())
+            .unwrap();
+        assert_eq!(normalized.outputs.len(), 3);
+        assert_eq!(normalized.meta.len(), 2);
+
+        let file_offset = the_answer.file_offset.unwrap();
+        assert_eq!(normalized.outputs[0], (file_offset + 2, 0));
+        assert_eq!(normalized.outputs[2], (file_offset, 0));
+        let elf = normalized.meta[0].elf().unwrap();
+        assert_eq!(elf.path, so);
+        assert_eq!(elf.build_id, read_elf_build_id(&so).unwrap());
+
+        assert_eq!(normalized.outputs[1].0, base - 1);
+        assert_eq!(
+            normalized.meta[normalized.outputs[1].1],
+            Unknown::new(Reason::Unmapped).into()
+        );
+
+        let src = symbolize::Source::Elf(symbolize::Elf::new(&so));
+        let symbolizer = Symbolizer::new();
+        let sym = symbolizer
+            .symbolize_single(&src, symbolize::Input::FileOffset(normalized.outputs[0].0))
+            .unwrap()
+            .into_sym()
+            .unwrap();
+        assert_eq!(sym.name, "the_answer");
+        assert_eq!(sym.offset, 2);
+    }
+
+    /// Check that we can enumerate the modules loaded into our own
+    /// process.
+    #[test]
+    fn module_enumeration() {
+        let normalizer = Normalizer::new();
+        let modules = normalizer.modules(Pid::Slf).unwrap();
+
+        let exe = env::current_exe().unwrap();
+        let main = modules.iter().find(|module| module.is_main_exe).unwrap();
+        assert_eq!(main.path.as_deref(), Some(exe.as_path()));
+        assert_eq!(main.kind, ModuleKind::Elf);
+        assert_eq!(main.build_id, read_elf_build_id(&exe).unwrap());
+        assert_eq!(
+            modules.iter().filter(|module| module.is_main_exe).count(),
+            1
+        );
+
+        let addr = module_enumeration as fn() as Addr;
+        assert!(main.range.contains(&addr));
+        assert!(modules.iter().any(|module| module.kind == ModuleKind::Vdso));
+        assert!(modules
+            .windows(2)
+            .all(|modules| modules[0].range.end <= modules[1].range.start));
+    }
 }
@@ -1,16 +1,20 @@
+use std::borrow::Cow;
 use std::collections::hash_map::Entry;
 use std::collections::HashMap;
-use std::ffi::OsStr;
 use std::io::Error;
 use std::io::ErrorKind;
 use std::path::Path;
 use std::path::PathBuf;
 
+use crate::elf::ElfParser;
 use crate::maps;
 use crate::maps::EntryPath;
 use crate::maps::MapsEntry;
 use crate::maps::PathName;
+use crate::symbolize;
+use crate::util::resolve_in_root;
 use crate::Addr;
+use crate::ErrorExt as _;
 use crate::Result;
 
 use super::buildid::BuildIdReader;
@@ -18,18 +22,71 @@ use super::meta::Apk;
 use super::meta::Elf;
 use super::meta::Unknown;
 use super::meta::UserMeta;
+use super::meta::Vdso;
+use super::meta::Vsyscall;
 use super::normalizer::Output;
 use super::Reason;
 
 
+/// Resolve `path` below `proc_root`, if set.
+pub(super) fn root_path<'path>(
+    path: &'path Path,
+    proc_root: Option<&Path>,
+) -> Result<Cow<'path, Path>> {
+    if let Some(root) = proc_root {
+        let path = resolve_in_root(root, path).with_context(|| {
+            format!(
+                "failed to resolve `{}` below `{}`",
+                path.display(),
+                root.display()
+            )
+        })?;
+        Ok(Cow::Owned(path))
+    } else {
+        Ok(Cow::Borrowed(path))
+    }
+}
+
+
+/// Retrieve the path to report for and the path to access the file
+/// backing a proc maps entry.
+///
+/// If `proc_root` is set, both refer to the file below it.
+pub(super) fn entry_paths<'path>(
+    entry_path: &'path EntryPath,
+    proc_root: Option<&Path>,
+) -> Result<(Cow<'path, Path>, Cow<'path, Path>)> {
+    if proc_root.is_some() {
+        let path = root_path(&entry_path.symbolic_path, proc_root)?;
+        Ok((path.clone(), path))
+    } else {
+        Ok((
+            Cow::Borrowed(&entry_path.symbolic_path),
+            Cow::Borrowed(&entry_path.maps_file),
+        ))
+    }
+}
+
+
 /// Make a [`UserMeta::Elf`] variant.
 fn make_elf_meta<'src>(
     entry_path: &EntryPath,
+    proc_root: Option<&Path>,
+    exe: Option<&Path>,
     build_id_reader: &dyn BuildIdReader<'src>,
+    sonames: bool,
 ) -> Result<UserMeta<'src>> {
+    let (path, file) = entry_paths(entry_path, proc_root)?;
+    let soname = if sonames {
+        ElfParser::open(&file)?.soname()?
+    } else {
+        None
+    };
     let elf = Elf {
-        path: entry_path.symbolic_path.to_path_buf(),
-        build_id: build_id_reader.read_build_id(&entry_path.maps_file)?,
+        build_id: build_id_reader.read_build_id(&file)?,
+        soname,
+        path: path.into_owned(),
+        is_main_exe: exe == Some(entry_path.symbolic_path.as_path()),
         _non_exhaustive: (),
     };
     let meta = UserMeta::Elf(elf);
@@ -37,10 +94,11 @@ fn make_elf_meta<'src>(
 }
 
 
-/// Make a [`UserMeta::Apk`] variant.
-fn make_apk_meta(entry_path: &EntryPath) -> Result<UserMeta<'static>> {
+/// Make a [`UserMeta::Apk`] variant for the archive at `archive`.
+fn make_apk_meta(archive: &Path, proc_root: Option<&Path>) -> Result<UserMeta<'static>> {
+    let path = root_path(archive, proc_root)?;
     let apk = Apk {
-        path: entry_path.symbolic_path.to_path_buf(),
+        path: path.into_owned(),
         _non_exhaustive: (),
     };
     let meta = UserMeta::Apk(apk);
@@ -109,6 +167,71 @@ impl<'src> UserOutput<'src> {
         let () = self.outputs.push((file_offset, meta_idx));
         Ok(())
     }
+
+    /// Group the normalized file offsets by the component they belong
+    /// to, creating the corresponding symbolization source for each.
+    ///
+    /// Each group consists of a [`symbolize::Source`], the file offsets
+    /// belonging to it, in the order in which they appear in
+    /// [`Self::outputs`], and the indices of these offsets in
+    /// [`Self::outputs`] (and, hence, in the input address list). The
+    /// offsets are meant to be symbolized using the
+    /// [`Input::FileOffset`][symbolize::Input::FileOffset] variant, with
+    /// the indices allowing for mapping the results back to the input:
+    /// ```no_run
+    /// # use blazesym::normalize::Normalizer;
+    /// # use blazesym::symbolize::Input;
+    /// # use blazesym::symbolize::Symbolizer;
+    /// # use blazesym::Addr;
+    /// # use blazesym::Pid;
+    /// let normalizer = Normalizer::new();
+    /// let addrs = [libc::fopen as Addr];
+    /// let normalized = normalizer.normalize_user_addrs(Pid::Slf, &addrs).unwrap();
+    ///
+    /// let symbolizer = Symbolizer::new();
+    /// let mut syms = vec![None; addrs.len()];
+    /// for (src, offsets, idxs) in normalized.into_symbolizer_groups() {
+    ///     let results = symbolizer
+    ///         .symbolize(&src, Input::FileOffset(&offsets))
+    ///         .unwrap();
+    ///     for (idx, result) in idxs.into_iter().zip(results) {
+    ///         syms[idx] = Some(result);
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// Addresses that could not be normalized (i.e., those referencing
+    /// an [`Unknown`] meta entry) are not reported. Neither are addresses
+    /// inside of APKs, if the `apk` feature is not enabled.
+    pub fn into_symbolizer_groups(self) -> Vec<(symbolize::Source<'static>, Vec<u64>, Vec<usize>)> {
+        let Self { outputs, meta } = self;
+
+        // A mapping from meta index to index into `groups`.
+        let mut group_idxs = vec![None::<usize>; meta.len()];
+        let mut groups = Vec::<(symbolize::Source<'static>, Vec<u64>, Vec<usize>)>::new();
+
+        for (idx, (offset, meta_idx)) in outputs.into_iter().enumerate() {
+            let group_idx = if let Some(group_idx) = group_idxs[meta_idx] {
+                group_idx
+            } else {
+                let src = match &meta[meta_idx] {
+                    #[cfg(feature = "apk")]
+                    UserMeta::Apk(apk) => symbolize::Source::from(symbolize::Apk::new(&apk.path)),
+                    UserMeta::Elf(elf) => symbolize::Source::from(symbolize::Elf::new(&elf.path)),
+                    _ => continue,
+                };
+                let group_idx = groups.len();
+                let () = groups.push((src, Vec::new(), Vec::new()));
+                group_idxs[meta_idx] = Some(group_idx);
+                group_idx
+            };
+
+            let (_src, offsets, idxs) = &mut groups[group_idx];
+            let () = offsets.push(offset);
+            let () = idxs.push(idx);
+        }
+        groups
+    }
 }
 
 
@@ -126,6 +249,13 @@ pub(super) struct NormalizationHandler<'reader, 'src> {
     pub normalized: UserOutput<'src>,
     /// The build ID reader to use.
     build_id_reader: &'reader dyn BuildIdReader<'src>,
+    /// The directory below which to look up files referenced by proc
+    /// maps entries, if any.
+    proc_root: Option<&'reader Path>,
+    /// The path of the process' main executable, if known.
+    exe: Option<&'reader Path>,
+    /// Whether to read shared object names of ELF files.
+    sonames: bool,
     /// Lookup table from path (as used in each proc maps entry) to index into
     /// `output.meta`.
     meta_lookup: HashMap<PathBuf, usize>,
@@ -136,13 +266,22 @@ pub(super) struct NormalizationHandler<'reader, 'src> {
 
 impl<'reader, 'src> NormalizationHandler<'reader, 'src> {
     /// Instantiate a new `NormalizationHandler` object.
-    pub fn new(reader: &'reader dyn BuildIdReader<'src>, addr_cnt: usize) -> Self {
+    pub fn new(
+        reader: &'reader dyn BuildIdReader<'src>,
+        proc_root: Option<&'reader Path>,
+        exe: Option<&'reader Path>,
+        sonames: bool,
+        addr_cnt: usize,
+    ) -> Self {
         Self {
             normalized: UserOutput {
                 outputs: Vec::with_capacity(addr_cnt),
                 meta: Vec::new(),
             },
             build_id_reader: reader,
+            proc_root,
+            exe,
+            sonames,
             meta_lookup: HashMap::new(),
             unknown_cache: HashMap::new(),
         }
@@ -161,27 +300,67 @@ impl Handler<Reason> for NormalizationHandler<'_, '_> {
     fn handle_entry_addr(&mut self, addr: Addr, entry: &MapsEntry) -> Result<()> {
         match &entry.path_name {
             Some(PathName::Path(entry_path)) => {
+                if entry_path.is_art_boot_image() {
+                    return self.handle_unknown_addr(addr, Reason::ArtBootImage)
+                }
+
                 let file_off = addr - entry.range.start + entry.offset;
-                let ext = entry_path
-                    .symbolic_path
-                    .extension()
-                    .unwrap_or_else(|| OsStr::new(""));
-                match ext.to_str() {
-                    Some("apk") | Some("zip") => self.normalized.add_normalized_offset(
+                // Mappings of archive members (e.g.,
+                // `base.apk!/lib/arm64-v8a/libfoo.so`) have their
+                // offset reported relative to the archive itself, so
+                // we normalize them just like any other APK address.
+                match entry_path.archive_path() {
+                    Some(archive) => self.normalized.add_normalized_offset(
                         file_off,
-                        &entry_path.symbolic_path,
+                        archive,
                         &mut self.meta_lookup,
-                        || make_apk_meta(entry_path),
+                        || make_apk_meta(archive, self.proc_root),
                     ),
-                    _ => self.normalized.add_normalized_offset(
+                    None => self.normalized.add_normalized_offset(
                         file_off,
                         &entry_path.symbolic_path,
                         &mut self.meta_lookup,
-                        || make_elf_meta(entry_path, self.build_id_reader),
+                        || {
+                            make_elf_meta(
+                                entry_path,
+                                self.proc_root,
+                                self.exe,
+                                self.build_id_reader,
+                                self.sonames,
+                            )
+                        },
                     ),
                 }
             }
-            Some(PathName::Component(..)) => self.handle_unknown_addr(addr, Reason::Unsupported),
+            Some(PathName::Component(component)) => {
+                let offset = addr - entry.range.start + entry.offset;
+                // Components are keyed by their name. Because paths
+                // reported in proc maps are always absolute, they
+                // can't clash with those of actual files.
+                match component.as_str() {
+                    "[vdso]" => self.normalized.add_normalized_offset(
+                        offset,
+                        Path::new(component),
+                        &mut self.meta_lookup,
+                        || {
+                            Ok(UserMeta::Vdso(Vdso {
+                                _non_exhaustive: (),
+                            }))
+                        },
+                    ),
+                    "[vsyscall]" => self.normalized.add_normalized_offset(
+                        offset,
+                        Path::new(component),
+                        &mut self.meta_lookup,
+                        || {
+                            Ok(UserMeta::Vsyscall(Vsyscall {
+                                _non_exhaustive: (),
+                            }))
+                        },
+                    ),
+                    _ => self.handle_unknown_addr(addr, Reason::Unsupported),
+                }
+            }
             // We could still normalize the address and report it, but without a
             // path nobody could really do anything with it.
             None => self.handle_unknown_addr(addr, Reason::MissingComponent),
@@ -256,8 +435,16 @@ where
 mod tests {
     use super::*;
 
+    use std::env;
+    use std::fs::copy;
+    use std::fs::create_dir_all;
+    use std::os::unix::fs::symlink;
+
+    use tempfile::tempdir;
     use test_log::test;
 
+    use crate::normalize::buildid::read_elf_build_id;
+    use crate::normalize::buildid::DefaultBuildIdReader;
     use crate::normalize::buildid::NoBuildIdReader;
     use crate::Pid;
 
@@ -298,7 +485,7 @@ mod tests {
             let entries = maps::parse_file(maps.as_bytes(), pid)
                 .filter(|result| result.as_ref().map(maps::filter_relevant).unwrap_or(true));
             let reader = NoBuildIdReader;
-            let mut handler = NormalizationHandler::new(&reader, addrs.len());
+            let mut handler = NormalizationHandler::new(&reader, None, None, false, addrs.len());
             let () = normalize_sorted_user_addrs_with_entries(
                 addrs.as_slice().iter().copied(),
                 entries,
@@ -323,4 +510,142 @@ mod tests {
         test(0x7fffffff1001, Reason::Unmapped);
         test(0x7fffffffffff, Reason::Unmapped);
     }
+
+    /// Check that we classify addresses as belonging to the main
+    /// executable, a shared object, the vDSO, or the `vsyscall` page.
+    #[test]
+    fn user_address_normalization_classification() {
+        let maps = r#"55d3195b9000-55d3195be000 r-xp 00002000 00:12 2015701                    /bin/cat (deleted)
+7fd5ba059000-7fd5ba1a8000 r-xp 00022000 00:12 2088876                    /lib64/libc.so.6
+7ffe102a2000-7ffe102c4000 rw-p 00000000 00:00 0                          [stack]
+7ffe103f6000-7ffe103fa000 r--p 00000000 00:00 0                          [vvar]
+7ffe103fa000-7ffe103fc000 r-xp 00000000 00:00 0                          [vdso]
+ffffffffff600000-ffffffffff601000 --xp 00000000 00:00 0                  [vsyscall]
+"#;
+        let addrs = [
+            0x55d3195b9010,
+            0x7fd5ba059020,
+            0x7ffe102a2000,
+            0x7ffe103fa030,
+            0x7ffe103fa040,
+            0xffffffffff600400,
+        ];
+        let entries = maps::parse_file(maps.as_bytes(), Pid::Slf)
+            .filter(|result| result.as_ref().map(maps::filter_relevant).unwrap_or(true));
+        let reader = NoBuildIdReader;
+        let exe = Path::new("/bin/cat");
+        let mut handler = NormalizationHandler::new(&reader, None, Some(exe), false, addrs.len());
+        let () = normalize_sorted_user_addrs_with_entries(
+            addrs.as_slice().iter().copied(),
+            entries,
+            &mut handler,
+        )
+        .unwrap();
+
+        let normalized = handler.normalized;
+        assert_eq!(
+            normalized.outputs,
+            vec![
+                (0x2010, 0),
+                (0x22020, 1),
+                (0x7ffe102a2000, 2),
+                (0x30, 3),
+                (0x40, 3),
+                (0x400, 4)
+            ]
+        );
+        assert_eq!(normalized.meta.len(), 5);
+
+        let elf = normalized.meta[0].elf().unwrap();
+        assert_eq!(elf.path, Path::new("/bin/cat"));
+        assert!(elf.is_main_exe);
+        let elf = normalized.meta[1].elf().unwrap();
+        assert_eq!(elf.path, Path::new("/lib64/libc.so.6"));
+        assert!(!elf.is_main_exe);
+        assert_eq!(normalized.meta[2], Unknown::new(Reason::Unmapped).into());
+        assert!(normalized.meta[3].vdso().is_some());
+        assert!(normalized.meta[4].vsyscall().is_some());
+    }
+
+    /// Check that files referenced by proc maps entries are looked up
+    /// below the proc root, if one is set.
+    #[test]
+    fn user_address_normalization_proc_root() {
+        let so = Path::new(&env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join("libtest-so.so");
+        let root = tempdir().unwrap();
+        let lib_dir = root.path().join("usr").join("lib");
+        let () = create_dir_all(&lib_dir).unwrap();
+        let _size = copy(&so, lib_dir.join("libtest-so.so")).unwrap();
+        // Absolute symbolic links, as commonly found in container
+        // images, are interpreted relative to the root.
+        let () = symlink("/usr/lib", root.path().join("lib")).unwrap();
+
+        let maps = r#"7f0000000000-7f0000001000 r-xp 00000000 00:12 1234                       /lib/libtest-so.so
+7f0000001000-7f0000002000 r-xp 00000000 00:12 1235                       /usr/lib/libtest-so.so
+"#;
+        let addrs = [0x7f0000000100, 0x7f0000000200, 0x7f0000001100];
+        let entries = maps::parse_file(maps.as_bytes(), Pid::Slf);
+        let reader = DefaultBuildIdReader;
+        let mut handler =
+            NormalizationHandler::new(&reader, Some(root.path()), None, false, addrs.len());
+        let () = normalize_sorted_user_addrs_with_entries(
+            addrs.as_slice().iter().copied(),
+            entries,
+            &mut handler,
+        )
+        .unwrap();
+
+        let normalized = handler.normalized;
+        assert_eq!(normalized.outputs, vec![(0x100, 0), (0x200, 0), (0x100, 1)]);
+        assert_eq!(normalized.meta.len(), 2);
+
+        let build_id = read_elf_build_id(&so).unwrap();
+        assert!(build_id.is_some());
+        for meta in normalized.meta {
+            let elf = match meta {
+                UserMeta::Elf(elf) => elf,
+                _ => panic!("encountered unexpected meta: {meta:?}"),
+            };
+            assert_eq!(elf.path, lib_dir.join("libtest-so.so"));
+            assert_eq!(elf.build_id, build_id);
+        }
+    }
+
+    /// Check that we report shared object names if asked to.
+    #[test]
+    fn user_address_normalization_sonames() {
+        let so = Path::new(&env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join("libtest-so-soname.so");
+        let maps = format!(
+            "7f0000000000-7f0000001000 r-xp 00000000 00:12 1234                       {}\n",
+            so.display()
+        );
+
+        for (sonames, expected) in [(false, None), (true, Some("libtest-so.so.1"))] {
+            let addrs = [0x7f0000000100];
+            let entries = maps::parse_file(maps.as_bytes(), Pid::Slf);
+            let reader = NoBuildIdReader;
+            // The mapping is fake and so we can't access the file via
+            // `/proc/self/map_files/`. Using `/` as proc root makes us
+            // use the symbolic path instead.
+            let root = Path::new("/");
+            let mut handler =
+                NormalizationHandler::new(&reader, Some(root), None, sonames, addrs.len());
+            let () = normalize_sorted_user_addrs_with_entries(
+                addrs.as_slice().iter().copied(),
+                entries,
+                &mut handler,
+            )
+            .unwrap();
+
+            let normalized = handler.normalized;
+            assert_eq!(normalized.outputs, vec![(0x100, 0)]);
+            let elf = normalized.meta[0].elf().unwrap();
+            assert_eq!(elf.path, so);
+            assert_eq!(elf.soname.as_deref(), expected);
+        }
+    }
 }
@@ -0,0 +1,24 @@
+//! Functionality for working with PE files and their accompanying PDB
+//! debug information.
+
+mod parser;
+mod pdb;
+mod resolver;
+
+use std::fs::File;
+use std::io::Read as _;
+use std::path::Path;
+
+pub(crate) use parser::PeParser;
+pub(crate) use resolver::PeResolver;
+
+
+/// Check whether the file at `path` appears to be a PE file, based on
+/// its (DOS header) magic.
+pub(crate) fn is_pe_file(path: &Path) -> bool {
+    let mut magic = [0; 2];
+    File::open(path)
+        .and_then(|mut file| file.read_exact(&mut magic))
+        .map(|()| &magic == b"MZ")
+        .unwrap_or(false)
+}
@@ -0,0 +1,233 @@
+use std::fs::File;
+use std::path::Path;
+use std::path::PathBuf;
+
+use goblin::pe::PE;
+
+use crate::mmap::Mmap;
+use crate::Addr;
+use crate::Error;
+use crate::ErrorExt as _;
+use crate::Result;
+
+
+/// Information about a section, as required for translating file
+/// offsets and for attributing addresses to sections.
+#[derive(Debug)]
+pub(crate) struct Section {
+    /// The name of the section.
+    pub name: String,
+    /// The address of the section, relative to the image base.
+    pub virtual_address: u32,
+    /// The size of the section when loaded into memory.
+    pub virtual_size: u32,
+    /// The file offset at which the section's data starts.
+    pub pointer_to_raw_data: u32,
+    /// The size of the section's data in the file.
+    pub size_of_raw_data: u32,
+}
+
+impl Section {
+    /// Check whether the section covers the provided relative virtual
+    /// address.
+    #[inline]
+    pub fn contains(&self, rva: u32) -> bool {
+        // The size of the raw data is rounded up to the file alignment
+        // and may exceed the virtual size. The excess is still mapped.
+        let size = self.virtual_size.max(self.size_of_raw_data);
+        (self.virtual_address..self.virtual_address.saturating_add(size)).contains(&rva)
+    }
+}
+
+
+/// The CodeView (`RSDS`) record referencing the PDB file containing
+/// debug information for a PE file.
+#[derive(Debug)]
+pub(crate) struct CodeView {
+    /// The path to the PDB file, as recorded at link time.
+    pub path: String,
+    /// The GUID identifying the PDB file, in its on-disk (i.e., mixed
+    /// endian) representation.
+    pub guid: [u8; 16],
+    /// The "age" of the PDB file.
+    pub age: u32,
+}
+
+impl CodeView {
+    /// Retrieve the file name component of the PDB path.
+    ///
+    /// The path was recorded on the build system, which typically is
+    /// Windows, so both kinds of separators are honored.
+    pub fn file_name(&self) -> &str {
+        self.path.rsplit(['\\', '/']).next().unwrap_or(&self.path)
+    }
+}
+
+
+/// A parser for PE files.
+///
+/// Only the bits necessary for symbolization based on the export table
+/// and for locating the corresponding PDB file are supported.
+#[derive(Debug)]
+pub(crate) struct PeParser {
+    /// The sections of the file.
+    sections: Box<[Section]>,
+    /// The named exports of the file, as relative virtual address and
+    /// name, sorted by address.
+    exports: Box<[(u32, String)]>,
+    /// The CodeView record of the file, if any.
+    codeview: Option<CodeView>,
+    /// The path to the PE file being worked on.
+    path: PathBuf,
+}
+
+impl PeParser {
+    /// Create a `PeParser` for the provided file.
+    pub(crate) fn open_file(file: &File, path: &Path) -> Result<Self> {
+        let mmap = Mmap::map(file)
+            .with_context(|| format!("failed to memory map PE file `{}`", path.display()))?;
+        Self::from_bytes(&mmap, path.to_path_buf())
+            .with_context(|| format!("failed to parse PE file `{}`", path.display()))
+    }
+
+    fn from_bytes(data: &[u8], path: PathBuf) -> Result<Self> {
+        let pe = PE::parse(data).map_err(Error::with_invalid_data)?;
+
+        let sections = pe
+            .sections
+            .iter()
+            .map(|section| {
+                let name = match &section.real_name {
+                    Some(name) => name.clone(),
+                    None => section
+                        .name()
+                        .map_err(Error::with_invalid_data)?
+                        .to_string(),
+                };
+                Ok(Section {
+                    name,
+                    virtual_address: section.virtual_address,
+                    virtual_size: section.virtual_size,
+                    pointer_to_raw_data: section.pointer_to_raw_data,
+                    size_of_raw_data: section.size_of_raw_data,
+                })
+            })
+            .collect::<Result<Box<[_]>>>()?;
+
+        let mut exports = pe
+            .exports
+            .iter()
+            // Forwarded exports don't refer to code in this file.
+            .filter(|export| export.reexport.is_none())
+            .filter_map(|export| {
+                let name = export.name?;
+                let rva = u32::try_from(export.rva).ok()?;
+                Some((rva, name.to_string()))
+            })
+            .collect::<Vec<_>>();
+        let () = exports.sort();
+
+        let codeview = pe
+            .debug_data
+            .and_then(|debug_data| debug_data.codeview_pdb70_debug_info)
+            .map(|info| {
+                let filename = info.filename;
+                let len = filename
+                    .iter()
+                    .position(|b| *b == b'\0')
+                    .unwrap_or(filename.len());
+                CodeView {
+                    path: String::from_utf8_lossy(&filename[..len]).into_owned(),
+                    guid: info.signature,
+                    age: info.age,
+                }
+            });
+
+        let slf = Self {
+            sections,
+            exports: exports.into_boxed_slice(),
+            codeview,
+            path,
+        };
+        Ok(slf)
+    }
+
+    /// Retrieve the sections of the file.
+    #[inline]
+    pub(crate) fn sections(&self) -> &[Section] {
+        &self.sections
+    }
+
+    /// Find the section covering the provided relative virtual address.
+    pub(crate) fn find_section(&self, rva: u32) -> Option<&Section> {
+        self.sections.iter().find(|section| section.contains(rva))
+    }
+
+    /// Retrieve the named exports of the file, sorted by address.
+    #[inline]
+    pub(crate) fn exports(&self) -> &[(u32, String)] {
+        &self.exports
+    }
+
+    /// Retrieve the CodeView record of the file, if present.
+    #[inline]
+    pub(crate) fn codeview(&self) -> Option<&CodeView> {
+        self.codeview.as_ref()
+    }
+
+    /// Translate a file offset into a relative virtual address.
+    pub(crate) fn file_offset_to_virt_offset(&self, offset: u64) -> Option<Addr> {
+        let section = self.sections.iter().find(|section| {
+            let start = u64::from(section.pointer_to_raw_data);
+            let end = start + u64::from(section.size_of_raw_data);
+            (start..end).contains(&offset)
+        });
+
+        match section {
+            Some(section) => Some(
+                (offset - u64::from(section.pointer_to_raw_data)
+                    + u64::from(section.virtual_address)) as Addr,
+            ),
+            // Headers are mapped at the start of the image and are not
+            // covered by any section.
+            None => self
+                .sections
+                .iter()
+                .map(|section| u64::from(section.pointer_to_raw_data))
+                .filter(|start| *start != 0)
+                .min()
+                .filter(|start| offset < *start)
+                .map(|_| offset as Addr),
+        }
+    }
+
+    /// Retrieve the path to the file this object operates on.
+    #[inline]
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+
+    /// Check that we extract the file name from PDB paths as expected.
+    #[test]
+    fn pdb_file_name() {
+        let codeview = |path: &str| CodeView {
+            path: path.to_string(),
+            guid: [0; 16],
+            age: 1,
+        };
+
+        assert_eq!(
+            codeview(r"C:\build\out\kernel32.pdb").file_name(),
+            "kernel32.pdb"
+        );
+        assert_eq!(codeview("/tmp/build/foo.pdb").file_name(), "foo.pdb");
+        assert_eq!(codeview("foo.pdb").file_name(), "foo.pdb");
+    }
+}
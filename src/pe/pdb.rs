@@ -0,0 +1,244 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::Path;
+
+use pdb::FallibleIterator as _;
+use pdb::PdbInternalSectionOffset;
+use pdb::SymbolData;
+use pdb::PDB;
+
+use crate::Error;
+use crate::ErrorExt as _;
+use crate::Result;
+
+use super::parser::CodeView;
+use super::parser::Section;
+
+
+/// A line table entry of a function.
+#[derive(Debug)]
+pub(crate) struct Line {
+    /// The relative virtual address at which the entry starts.
+    pub rva: u32,
+    /// The index of the source file, as understood by
+    /// [`PdbData::file`].
+    pub file: u32,
+    /// The (1-based) line number.
+    pub line: u32,
+    /// The (1-based) column number, if known.
+    pub column: Option<u16>,
+}
+
+
+/// A function (procedure) described by a PDB file.
+#[derive(Debug)]
+pub(crate) struct Function {
+    /// The relative virtual address at which the function starts.
+    pub rva: u32,
+    /// The size of the function's code, in bytes.
+    pub size: u32,
+    /// The function's name.
+    pub name: String,
+    /// The line table entries of the function, sorted by address.
+    pub lines: Box<[Line]>,
+}
+
+impl Function {
+    /// Find the line table entry covering the provided relative virtual
+    /// address.
+    pub fn find_line(&self, rva: u32) -> Option<&Line> {
+        let idx = self.lines.partition_point(|line| line.rva <= rva);
+        idx.checked_sub(1).map(|idx| &self.lines[idx])
+    }
+}
+
+
+/// Symbol and line information read from a PDB file.
+#[derive(Debug)]
+pub(crate) struct PdbData {
+    /// Functions, sorted by address.
+    functions: Box<[Function]>,
+    /// Code related public symbols as relative virtual address and
+    /// (usually mangled) name, sorted by address.
+    publics: Box<[(u32, String)]>,
+    /// Source file paths, as recorded on the build system.
+    files: Box<[String]>,
+}
+
+impl PdbData {
+    /// Read the PDB file at `path`.
+    ///
+    /// `None` is returned if the file does not match the PE file
+    /// described by `codeview`. `sections` are the sections of said PE
+    /// file.
+    pub(crate) fn open(
+        path: &Path,
+        codeview: &CodeView,
+        sections: &[Section],
+    ) -> Result<Option<Self>> {
+        let file = File::open(path)
+            .with_context(|| format!("failed to open PDB file `{}`", path.display()))?;
+        Self::from_file(file, codeview, sections)
+            .with_context(|| format!("failed to parse PDB file `{}`", path.display()))
+    }
+
+    fn from_file(file: File, codeview: &CodeView, sections: &[Section]) -> Result<Option<Self>> {
+        let mut pdb = PDB::open(file).map_err(Error::with_invalid_data)?;
+        let info = pdb.pdb_information().map_err(Error::with_invalid_data)?;
+        let dbi = pdb.debug_information().map_err(Error::with_invalid_data)?;
+        // The age recorded in the DBI stream is the one matching the
+        // image, if present.
+        let age = dbi.age().unwrap_or(info.age);
+        if info.guid.to_bytes_le() != codeview.guid || age < codeview.age {
+            return Ok(None)
+        }
+
+        let address_map = pdb.address_map().map_err(Error::with_invalid_data)?;
+        let has_sections = pdb
+            .sections()
+            .map_err(Error::with_invalid_data)?
+            .map(|sections| !sections.is_empty())
+            .unwrap_or(false);
+        let to_rva = |offset: PdbInternalSectionOffset| {
+            if has_sections {
+                offset.to_rva(&address_map).map(|rva| rva.0)
+            } else {
+                // Without section headers in the PDB we fall back to
+                // those of the PE file itself.
+                let idx = usize::from(offset.section).checked_sub(1)?;
+                let section = sections.get(idx)?;
+                section.virtual_address.checked_add(offset.offset)
+            }
+        };
+
+        // The string table is absent if no module carries line
+        // information.
+        let strings = pdb.string_table().ok();
+        let mut file_idxs = HashMap::<String, u32>::new();
+        let mut files = Vec::new();
+        let mut functions = Vec::new();
+
+        let mut modules = dbi.modules().map_err(Error::with_invalid_data)?;
+        while let Some(module) = modules.next().map_err(Error::with_invalid_data)? {
+            let info = match pdb.module_info(&module).map_err(Error::with_invalid_data)? {
+                Some(info) => info,
+                None => continue,
+            };
+            let program = info.line_program().map_err(Error::with_invalid_data)?;
+            let mut symbols = info.symbols().map_err(Error::with_invalid_data)?;
+
+            while let Some(symbol) = symbols.next().map_err(Error::with_invalid_data)? {
+                // Symbols of kinds not understood by the parser are
+                // irrelevant to us.
+                let proc = match symbol.parse() {
+                    Ok(SymbolData::Procedure(proc)) => proc,
+                    _ => continue,
+                };
+                let rva = match to_rva(proc.offset) {
+                    Some(rva) => rva,
+                    None => continue,
+                };
+
+                let mut lines = Vec::new();
+                let mut line_iter = program.lines_for_symbol(proc.offset);
+                while let Some(line) = line_iter.next().map_err(Error::with_invalid_data)? {
+                    let rva = match to_rva(line.offset) {
+                        Some(rva) => rva,
+                        None => continue,
+                    };
+                    let file_info = program
+                        .get_file_info(line.file_index)
+                        .map_err(Error::with_invalid_data)?;
+                    let name = match &strings {
+                        Some(strings) => file_info
+                            .name
+                            .to_string_lossy(strings)
+                            .map_err(Error::with_invalid_data)?
+                            .into_owned(),
+                        None => continue,
+                    };
+                    let file = match file_idxs.get(&name) {
+                        Some(idx) => *idx,
+                        None => {
+                            let idx = files.len() as u32;
+                            let () = files.push(name.clone());
+                            let _prev = file_idxs.insert(name, idx);
+                            idx
+                        }
+                    };
+
+                    let () = lines.push(Line {
+                        rva,
+                        file,
+                        line: line.line_start,
+                        column: line
+                            .column_start
+                            .filter(|column| *column != 0)
+                            .and_then(|column| u16::try_from(column).ok()),
+                    });
+                }
+                let () = lines.sort_by_key(|line| line.rva);
+
+                let () = functions.push(Function {
+                    rva,
+                    size: proc.len,
+                    name: proc.name.to_string().into_owned(),
+                    lines: lines.into_boxed_slice(),
+                });
+            }
+        }
+        let () = functions.sort_by_key(|function| function.rva);
+
+        let mut publics = Vec::new();
+        // Not every PDB file contains global symbols.
+        if let Ok(globals) = pdb.global_symbols() {
+            let mut symbols = globals.iter();
+            while let Some(symbol) = symbols.next().map_err(Error::with_invalid_data)? {
+                match symbol.parse() {
+                    Ok(SymbolData::Public(public)) if public.code || public.function => {
+                        if let Some(rva) = to_rva(public.offset) {
+                            let () = publics.push((rva, public.name.to_string().into_owned()));
+                        }
+                    }
+                    _ => (),
+                }
+            }
+        }
+        let () = publics.sort();
+
+        let slf = Self {
+            functions: functions.into_boxed_slice(),
+            publics: publics.into_boxed_slice(),
+            files: files.into_boxed_slice(),
+        };
+        Ok(Some(slf))
+    }
+
+    /// Find the function covering the provided relative virtual
+    /// address.
+    pub(crate) fn find_function(&self, rva: u32) -> Option<&Function> {
+        let idx = self
+            .functions
+            .partition_point(|function| function.rva <= rva);
+        let function = &self.functions[idx.checked_sub(1)?];
+        (rva - function.rva < function.size).then_some(function)
+    }
+
+    /// Retrieve all functions, sorted by address.
+    #[inline]
+    pub(crate) fn functions(&self) -> &[Function] {
+        &self.functions
+    }
+
+    /// Retrieve code related public symbols, sorted by address.
+    #[inline]
+    pub(crate) fn publics(&self) -> &[(u32, String)] {
+        &self.publics
+    }
+
+    /// Retrieve the path of the source file with the given index.
+    #[inline]
+    pub(crate) fn file(&self, idx: u32) -> Option<&str> {
+        self.files.get(idx as usize).map(String::as_str)
+    }
+}
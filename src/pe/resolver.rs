@@ -0,0 +1,409 @@
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::ffi::OsStr;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+use std::fs::File;
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::inspect::FindAddrOpts;
+use crate::inspect::Inspect;
+use crate::inspect::SymInfo;
+use crate::log;
+use crate::symbolize::CodeInfo;
+use crate::symbolize::FindSymOpts;
+use crate::symbolize::Provenance;
+use crate::symbolize::Reason;
+use crate::symbolize::ResolvedSym;
+use crate::symbolize::SrcLang;
+use crate::symbolize::Symbolize;
+use crate::symbolize::TranslateFileOffset;
+use crate::Addr;
+use crate::Error;
+use crate::Result;
+use crate::SymType;
+
+use super::parser::Section;
+use super::pdb::Line;
+use super::pdb::PdbData;
+use super::PeParser;
+
+
+/// Split a source file path as recorded in a PDB file into directory
+/// and file name.
+///
+/// Paths typically stem from Windows systems and use backslashes as
+/// separators.
+fn split_path(path: &str) -> (Option<&Path>, &OsStr) {
+    match path.rfind(['\\', '/']) {
+        Some(idx) if idx > 0 => (Some(Path::new(&path[..idx])), OsStr::new(&path[idx + 1..])),
+        Some(idx) => (Some(Path::new(&path[..=idx])), OsStr::new(&path[idx + 1..])),
+        None => (None, OsStr::new(path)),
+    }
+}
+
+
+/// Find the symbol in `syms` (sorted by address) closest preceding
+/// `rva` inside `section`, along with the extent it is assumed to
+/// have.
+///
+/// Public symbols and exports carry no size information. They are
+/// assumed to extend up to the following symbol or the end of the
+/// section.
+fn find_nearest<'syms>(
+    syms: &'syms [(u32, String)],
+    rva: u32,
+    section: &Section,
+) -> Option<(&'syms (u32, String), u32)> {
+    let idx = syms.partition_point(|(addr, _name)| *addr <= rva);
+    let idx = idx.checked_sub(1)?;
+    let sym = &syms[idx];
+    if !section.contains(sym.0) {
+        return None
+    }
+    Some((sym, sym_extent(syms, idx, section)))
+}
+
+/// Determine the extent of the symbol at index `idx` of `syms`, which
+/// is located in `section`.
+fn sym_extent(syms: &[(u32, String)], idx: usize, section: &Section) -> u32 {
+    let (addr, _name) = &syms[idx];
+    let section_end = section
+        .virtual_address
+        .saturating_add(section.virtual_size.max(section.size_of_raw_data));
+    let end = syms[idx + 1..]
+        .iter()
+        .map(|(next, _name)| *next)
+        .find(|next| next > addr)
+        .unwrap_or(section_end)
+        .min(section_end);
+    end.saturating_sub(*addr)
+}
+
+
+/// The symbol resolver for a single PE file.
+///
+/// Symbolization is based on the PDB file referenced by the PE file,
+/// if it can be found, and falls back to the file's export table
+/// otherwise. Addresses are relative virtual addresses, i.e., relative
+/// to the image base.
+pub(crate) struct PeResolver {
+    /// The parser for the PE file.
+    parser: PeParser,
+    /// Data from the matching PDB file, if any was found.
+    pdb: Option<PdbData>,
+}
+
+impl PeResolver {
+    /// Create a `PeResolver` for the provided PE file.
+    ///
+    /// The PDB file referenced by the PE file is looked for next to it
+    /// first and in `pdb_dirs` after that.
+    pub(crate) fn from_file(path: &Path, file: &File, pdb_dirs: &[PathBuf]) -> Result<Self> {
+        let parser = PeParser::open_file(file, path)?;
+        let pdb = Self::find_pdb(&parser, pdb_dirs);
+        let slf = Self { parser, pdb };
+        Ok(slf)
+    }
+
+    /// Locate and read the PDB file matching the PE file represented
+    /// by `parser`.
+    ///
+    /// Failure to read a PDB file is not fatal, as we can still fall
+    /// back to using the export table.
+    fn find_pdb(parser: &PeParser, pdb_dirs: &[PathBuf]) -> Option<PdbData> {
+        let codeview = parser.codeview()?;
+        let name = codeview.file_name();
+        // The path may be a symbolic link (e.g., a
+        // `/proc/<pid>/map_files/` entry), in which case the PDB file is
+        // expected next to its target.
+        let path = parser
+            .path()
+            .canonicalize()
+            .unwrap_or_else(|_err| parser.path().to_path_buf());
+        let dirs = path
+            .parent()
+            .into_iter()
+            .chain(pdb_dirs.iter().map(PathBuf::as_path));
+
+        for dir in dirs {
+            let path = dir.join(name);
+            if !path.is_file() {
+                continue
+            }
+
+            match PdbData::open(&path, codeview, parser.sections()) {
+                Ok(Some(pdb)) => return Some(pdb),
+                Ok(None) => log::debug!(
+                    "PDB file `{}` does not match `{}`",
+                    path.display(),
+                    parser.path().display()
+                ),
+                Err(err) => log::warn!("{err:#}"),
+            }
+        }
+        None
+    }
+
+    fn fill_code_info<'slf>(
+        &'slf self,
+        sym: &mut ResolvedSym<'slf>,
+        pdb: &'slf PdbData,
+        line: Option<&'slf Line>,
+        opts: &FindSymOpts,
+    ) {
+        if !opts.code_info() {
+            return
+        }
+
+        let line = match line {
+            Some(line) => line,
+            None => return,
+        };
+        let path = match pdb.file(line.file) {
+            Some(path) => path,
+            None => return,
+        };
+        let (dir, file) = split_path(path);
+
+        sym.code_info = Some(CodeInfo {
+            dir: dir.map(Cow::Borrowed),
+            file: Cow::Borrowed(file),
+            line: Some(line.line),
+            column: line.column,
+            comp_dir: None,
+            _non_exhaustive: (),
+        });
+    }
+
+    /// Translate a relative virtual address into a file offset.
+    fn file_offset(&self, rva: u32) -> Option<u64> {
+        let section = self.parser.find_section(rva)?;
+        let offset = rva - section.virtual_address;
+        (offset < section.size_of_raw_data)
+            .then(|| u64::from(section.pointer_to_raw_data) + u64::from(offset))
+    }
+
+    /// Invoke `f` for each function symbol, with duplicates reported by
+    /// multiple sources (PDB functions, PDB public symbols, and
+    /// exports) removed.
+    fn for_each_sym<'slf>(&'slf self, f: &mut dyn FnMut(&'slf str, u32, u32)) {
+        let mut seen = HashSet::<(u32, &str)>::new();
+
+        if let Some(pdb) = &self.pdb {
+            for function in pdb.functions() {
+                if seen.insert((function.rva, &function.name)) {
+                    let () = f(&function.name, function.rva, function.size);
+                }
+            }
+        }
+
+        let tables = self
+            .pdb
+            .as_ref()
+            .map(PdbData::publics)
+            .into_iter()
+            .chain([self.parser.exports()]);
+        for syms in tables {
+            for (idx, (rva, name)) in syms.iter().enumerate() {
+                if !seen.insert((*rva, name)) {
+                    continue
+                }
+                let size = match self.parser.find_section(*rva) {
+                    Some(section) => sym_extent(syms, idx, section),
+                    None => 0,
+                };
+                let () = f(name, *rva, size);
+            }
+        }
+    }
+
+    fn make_sym_info<'slf>(
+        &'slf self,
+        name: &'slf str,
+        rva: u32,
+        size: u32,
+        opts: &FindAddrOpts,
+    ) -> SymInfo<'slf> {
+        SymInfo {
+            name: Cow::Borrowed(name),
+            addr: Addr::from(rva),
+            size: size as usize,
+            sym_type: SymType::Function,
+            binding: None,
+            version: None,
+            is_ifunc: false,
+            file_offset: opts.offset_in_file.then(|| self.file_offset(rva)).flatten(),
+            obj_file_name: None,
+        }
+    }
+}
+
+impl Symbolize for PeResolver {
+    #[cfg_attr(feature = "tracing", crate::log::instrument(fields(addr = format_args!("{addr:#x}"))))]
+    fn find_sym(&self, addr: Addr, opts: &FindSymOpts) -> Result<Result<ResolvedSym<'_>, Reason>> {
+        let rva = match u32::try_from(addr) {
+            Ok(rva) => rva,
+            Err(_) => return Ok(Err(Reason::UnknownAddr)),
+        };
+        let section = self.parser.find_section(rva);
+
+        let make_sym = |name, addr, size| ResolvedSym {
+            name,
+            addr: Addr::from(addr),
+            size,
+            lang: SrcLang::Unknown,
+            code_info: None,
+            inlined: Box::new([]),
+            provenance: Provenance::Symbol,
+            is_synthetic: false,
+            raw_elf_sym: None,
+            compilation_unit: None,
+            version: None,
+            section: section.map(|section| section.name.as_str()),
+            linkage_name: None,
+        };
+
+        if let Some(pdb) = &self.pdb {
+            if let Some(function) = pdb.find_function(rva) {
+                let mut sym = make_sym(&function.name, function.rva, Some(function.size as usize));
+                let () = self.fill_code_info(&mut sym, pdb, function.find_line(rva), opts);
+                return Ok(Ok(sym))
+            }
+        }
+
+        if let Some(section) = section {
+            let publics = self.pdb.as_ref().map(PdbData::publics).unwrap_or(&[]);
+            for syms in [publics, self.parser.exports()] {
+                if let Some(((addr, name), _extent)) = find_nearest(syms, rva, section) {
+                    // The extent is merely derived from surrounding
+                    // symbols, so we don't report it as size.
+                    return Ok(Ok(make_sym(name, *addr, None)))
+                }
+            }
+        }
+
+        let has_syms = !self.parser.exports().is_empty()
+            || self
+                .pdb
+                .as_ref()
+                .map(|pdb| !pdb.functions().is_empty() || !pdb.publics().is_empty())
+                .unwrap_or(false);
+        let reason = if has_syms {
+            Reason::UnknownAddr
+        } else {
+            Reason::MissingSyms
+        };
+        Ok(Err(reason))
+    }
+}
+
+impl TranslateFileOffset for PeResolver {
+    fn file_offset_to_virt_offset(&self, file_offset: u64) -> Result<Option<Addr>> {
+        Ok(self.parser.file_offset_to_virt_offset(file_offset))
+    }
+}
+
+impl Inspect for PeResolver {
+    fn find_addr<'slf>(&'slf self, name: &str, opts: &FindAddrOpts) -> Result<Vec<SymInfo<'slf>>> {
+        if let SymType::Variable | SymType::Tls = opts.sym_type {
+            return Err(Error::with_unsupported(
+                "PE logic does not currently support variable lookup",
+            ))
+        }
+
+        // PE files don't carry symbol binding information.
+        if opts.binding.is_some() {
+            return Ok(Vec::new())
+        }
+
+        let mut syms = Vec::new();
+        let () = self.for_each_sym(&mut |sym_name, rva, size| {
+            if sym_name == name {
+                let () = syms.push(self.make_sym_info(sym_name, rva, size, opts));
+            }
+        });
+        Ok(syms)
+    }
+
+    fn for_each(&self, opts: &FindAddrOpts, f: &mut dyn FnMut(&SymInfo<'_>)) -> Result<()> {
+        if let SymType::Variable | SymType::Tls = opts.sym_type {
+            return Err(Error::with_unsupported(
+                "PE logic does not currently support variable iteration",
+            ))
+        }
+
+        if opts.binding.is_some() {
+            return Ok(())
+        }
+
+        let () = self.for_each_sym(&mut |name, rva, size| {
+            let sym = self.make_sym_info(name, rva, size, opts);
+            let () = f(&sym);
+        });
+        Ok(())
+    }
+}
+
+impl Debug for PeResolver {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "PE {}", self.parser.path().display())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use test_log::test;
+
+
+    /// Check that we split Windows and Unix style source paths as
+    /// expected.
+    #[test]
+    fn source_path_splitting() {
+        assert_eq!(
+            split_path(r"C:\src\project\main.c"),
+            (Some(Path::new(r"C:\src\project")), OsStr::new("main.c"))
+        );
+        assert_eq!(
+            split_path("/src/main.c"),
+            (Some(Path::new("/src")), OsStr::new("main.c"))
+        );
+        assert_eq!(
+            split_path(r"\main.c"),
+            (Some(Path::new(r"\")), OsStr::new("main.c"))
+        );
+        assert_eq!(split_path("main.c"), (None, OsStr::new("main.c")));
+    }
+
+    /// Check that we determine the extent of symbols without size
+    /// information as expected.
+    #[test]
+    fn sym_extent_calculation() {
+        let section = Section {
+            name: ".text".to_string(),
+            virtual_address: 0x1000,
+            virtual_size: 0x100,
+            pointer_to_raw_data: 0x400,
+            size_of_raw_data: 0x200,
+        };
+        let syms = [
+            (0x1000, "a".to_string()),
+            (0x1000, "alias".to_string()),
+            (0x1010, "b".to_string()),
+            (0x3000, "c".to_string()),
+        ];
+        assert_eq!(sym_extent(&syms, 0, &section), 0x10);
+        assert_eq!(sym_extent(&syms, 1, &section), 0x10);
+        assert_eq!(sym_extent(&syms, 2, &section), 0x1f0);
+
+        let ((addr, name), extent) = find_nearest(&syms, 0x1020, &section).unwrap();
+        assert_eq!((*addr, name.as_str(), extent), (0x1010, "b", 0x1f0));
+        assert_eq!(find_nearest(&syms, 0xfff, &section), None);
+        assert_eq!(find_nearest(&syms, 0x3001, &section), None);
+    }
+}
@@ -0,0 +1,301 @@
+//! A unified representation of symbols.
+//!
+//! Inspection (via [`Inspector`][crate::inspect::Inspector]) and
+//! symbolization (via [`Symbolizer`][crate::symbolize::Symbolizer])
+//! report symbols using different types, tailored to their respective
+//! use cases. The [`Sym`] type provided by this module captures the
+//! core properties of a symbol that both can produce, making it
+//! possible to merge the outputs of the two.
+//!
+//! ```no_run
+//! use blazesym::inspect;
+//! use blazesym::inspect::Inspector;
+//! use blazesym::sym;
+//! use blazesym::symbolize;
+//! use blazesym::symbolize::Symbolizer;
+//!
+//! let path = "/usr/lib/libc.so.6";
+//! let inspector = Inspector::new();
+//! let src = inspect::Source::Elf(inspect::Elf::new(path));
+//! let info = inspector.lookup(&src, &["fopen"]).unwrap()[0][0].clone();
+//!
+//! let symbolizer = Symbolizer::new();
+//! let src = symbolize::Source::Elf(symbolize::Elf::new(path));
+//! let input = symbolize::Input::VirtOffset(info.addr);
+//! let symbolized = symbolizer.symbolize_single(&src, input).unwrap();
+//! let symbolized = symbolized.into_sym().unwrap();
+//!
+//! let sym = sym::Sym::from(symbolized).merge(sym::Sym::from(info));
+//! ```
+
+use std::borrow::Cow;
+use std::path::Path;
+
+use crate::inspect::SymInfo;
+use crate::symbolize;
+use crate::symbolize::CodeInfo;
+use crate::symbolize::InlinedFn;
+use crate::Addr;
+use crate::BuildId;
+use crate::Error;
+use crate::SymType;
+
+
+/// The core properties of a symbol, as reported by both inspection and
+/// symbolization APIs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sym<'src> {
+    /// The name of the symbol.
+    pub name: Cow<'src, str>,
+    /// The address at which the symbol is located (i.e., its "start").
+    pub addr: Addr,
+    /// The symbol's size, if known.
+    pub size: Option<usize>,
+    /// The type of the symbol, if known.
+    pub sym_type: SymType,
+    /// The path to the module (e.g., a shared object) containing the
+    /// symbol, if known.
+    pub module: Option<Cow<'src, Path>>,
+    /// The build ID of the module containing the symbol, if known.
+    pub build_id: Option<BuildId<'src>>,
+    /// The struct is non-exhaustive and open to extension.
+    #[doc(hidden)]
+    pub _non_exhaustive: (),
+}
+
+impl<'src> Sym<'src> {
+    /// Merge `self` with `other`, which is assumed to describe the same
+    /// symbol.
+    ///
+    /// Properties present on `self` take precedence; unknown ones are
+    /// filled in from `other`.
+    pub fn merge(self, other: Sym<'src>) -> Sym<'src> {
+        let sym_type = if self.sym_type == SymType::Undefined {
+            other.sym_type
+        } else {
+            self.sym_type
+        };
+
+        Sym {
+            name: self.name,
+            addr: self.addr,
+            size: self.size.or(other.size),
+            sym_type,
+            module: self.module.or(other.module),
+            build_id: self.build_id.or(other.build_id),
+            _non_exhaustive: (),
+        }
+    }
+
+    /// Clone the object ensuring that references are converted to owned
+    /// objects.
+    pub fn to_owned(&self) -> Sym<'static> {
+        Sym {
+            name: Cow::Owned(self.name.to_string()),
+            addr: self.addr,
+            size: self.size,
+            sym_type: self.sym_type,
+            module: self
+                .module
+                .as_deref()
+                .map(|path| Cow::Owned(path.to_path_buf())),
+            build_id: self
+                .build_id
+                .as_deref()
+                .map(|build_id| Cow::Owned(build_id.to_vec())),
+            _non_exhaustive: (),
+        }
+    }
+}
+
+impl<'src> From<SymInfo<'src>> for Sym<'src> {
+    fn from(other: SymInfo<'src>) -> Self {
+        let SymInfo {
+            name,
+            addr,
+            size,
+            sym_type,
+            binding: _,
+            version: _,
+            is_ifunc: _,
+            file_offset: _,
+            obj_file_name,
+        } = other;
+
+        Self {
+            name,
+            addr,
+            size: Some(size),
+            sym_type,
+            module: obj_file_name,
+            build_id: None,
+            _non_exhaustive: (),
+        }
+    }
+}
+
+impl<'src> From<symbolize::Sym<'src>> for Sym<'src> {
+    fn from(other: symbolize::Sym<'src>) -> Self {
+        CodeSym::from(other).sym
+    }
+}
+
+impl<'src> TryFrom<Sym<'src>> for SymInfo<'src> {
+    type Error = Error;
+
+    /// Convert a [`Sym`] into a [`SymInfo`].
+    ///
+    /// The conversion fails if the symbol's size is not known.
+    fn try_from(other: Sym<'src>) -> Result<Self, Self::Error> {
+        let Sym {
+            name,
+            addr,
+            size,
+            sym_type,
+            module,
+            build_id: _,
+            _non_exhaustive: (),
+        } = other;
+
+        let size = size.ok_or_else(|| {
+            Error::with_invalid_input(format!("size of symbol `{name}` is not known"))
+        })?;
+
+        let info = SymInfo {
+            name,
+            addr,
+            size,
+            sym_type,
+            binding: None,
+            version: None,
+            is_ifunc: false,
+            file_offset: None,
+            obj_file_name: module,
+        };
+        Ok(info)
+    }
+}
+
+impl<'src> TryFrom<symbolize::Sym<'src>> for SymInfo<'src> {
+    type Error = Error;
+
+    /// Convert a [`symbolize::Sym`] into a [`SymInfo`].
+    ///
+    /// The conversion fails if the symbol's size is not known.
+    fn try_from(other: symbolize::Sym<'src>) -> Result<Self, Self::Error> {
+        SymInfo::try_from(Sym::from(other))
+    }
+}
+
+
+/// A [`Sym`] extended with source code information, as reported by
+/// symbolization.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CodeSym<'src> {
+    /// The core properties of the symbol.
+    pub sym: Sym<'src>,
+    /// Source code location information for the symbol.
+    pub code_info: Option<CodeInfo<'src>>,
+    /// Inlined function information, if available.
+    ///
+    /// Please refer to [`symbolize::Sym::inlined`] for details.
+    pub inlined: Box<[InlinedFn<'src>]>,
+    /// The struct is non-exhaustive and open to extension.
+    #[doc(hidden)]
+    pub _non_exhaustive: (),
+}
+
+impl<'src> From<symbolize::Sym<'src>> for CodeSym<'src> {
+    fn from(other: symbolize::Sym<'src>) -> Self {
+        let symbolize::Sym {
+            name,
+            addr,
+            size,
+            code_info,
+            inlined,
+            ..
+        } = other;
+
+        Self {
+            sym: Sym {
+                name,
+                addr,
+                size,
+                // Symbolization does not report symbol types.
+                sym_type: SymType::Undefined,
+                module: None,
+                build_id: None,
+                _non_exhaustive: (),
+            },
+            code_info,
+            inlined,
+            _non_exhaustive: (),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::path::PathBuf;
+
+
+    /// Check that we can convert between [`SymInfo`] and [`Sym`].
+    #[test]
+    fn sym_info_conversion() {
+        let info = SymInfo {
+            name: Cow::Borrowed("foo"),
+            addr: 0x1000,
+            size: 0x42,
+            sym_type: SymType::Function,
+            binding: None,
+            version: None,
+            is_ifunc: false,
+            file_offset: None,
+            obj_file_name: Some(Cow::Owned(PathBuf::from("/tmp/libfoo.so"))),
+        };
+        let sym = Sym::from(info.clone());
+        assert_eq!(sym.name, "foo");
+        assert_eq!(sym.size, Some(0x42));
+        assert_eq!(sym.sym_type, SymType::Function);
+        assert_eq!(sym.module.as_deref(), Some(Path::new("/tmp/libfoo.so")));
+        assert_eq!(SymInfo::try_from(sym.clone()).unwrap(), info);
+
+        let sym = Sym { size: None, ..sym };
+        let err = SymInfo::try_from(sym).unwrap_err();
+        assert!(err.to_string().contains("size of symbol `foo`"), "{err}");
+    }
+
+    /// Check that merging [`Sym`] objects fills in unknown properties.
+    #[test]
+    fn sym_merging() {
+        let sym1 = Sym {
+            name: Cow::Borrowed("foo"),
+            addr: 0x1000,
+            size: None,
+            sym_type: SymType::Undefined,
+            module: None,
+            build_id: Some(Cow::Borrowed(&[0x01, 0x02])),
+            _non_exhaustive: (),
+        };
+        let sym2 = Sym {
+            name: Cow::Borrowed("bar"),
+            addr: 0x2000,
+            size: Some(0x10),
+            sym_type: SymType::Function,
+            module: Some(Cow::Borrowed(Path::new("/tmp/libfoo.so"))),
+            build_id: Some(Cow::Borrowed(&[0x03])),
+            _non_exhaustive: (),
+        };
+
+        let merged = sym1.merge(sym2).to_owned();
+        assert_eq!(merged.name, "foo");
+        assert_eq!(merged.addr, 0x1000);
+        assert_eq!(merged.size, Some(0x10));
+        assert_eq!(merged.sym_type, SymType::Function);
+        assert_eq!(merged.module.as_deref(), Some(Path::new("/tmp/libfoo.so")));
+        assert_eq!(merged.build_id.as_deref(), Some([0x01, 0x02].as_slice()));
+    }
+}
@@ -0,0 +1,139 @@
+/// A module for working with textual kernel trace (`ftrace`) output.
+use std::fmt::Write as _;
+use std::ops::Range;
+
+use crate::Addr;
+
+use super::Symbolized;
+
+
+/// The number of hex digits making up a kernel address as printed by
+/// `ftrace`.
+const ADDR_DIGITS: usize = 16;
+
+
+/// Find all kernel addresses in a line of `ftrace` output.
+///
+/// Addresses are expected to be printed as exactly 16 hexadecimal
+/// digits, optionally prefixed with `0x`, and not be part of a larger
+/// word. The byte range of each address (including any prefix) is
+/// reported alongside it.
+pub(crate) fn find_addrs(line: &str) -> Vec<(Range<usize>, Addr)> {
+    let bytes = line.as_bytes();
+    let is_word = |b: u8| b.is_ascii_alphanumeric() || b == b'_';
+
+    let mut addrs = Vec::new();
+    let mut idx = 0;
+    while idx < bytes.len() {
+        if !is_word(bytes[idx]) {
+            idx += 1;
+            continue
+        }
+
+        let start = idx;
+        while idx < bytes.len() && is_word(bytes[idx]) {
+            idx += 1;
+        }
+
+        let word = &line[start..idx];
+        let digits = word
+            .strip_prefix("0x")
+            .or_else(|| word.strip_prefix("0X"))
+            .unwrap_or(word);
+        if digits.len() == ADDR_DIGITS {
+            if let Ok(addr) = Addr::from_str_radix(digits, 16) {
+                let () = addrs.push((start..idx, addr));
+            }
+        }
+    }
+    addrs
+}
+
+
+/// Replace the addresses at the provided ranges in `line` with their
+/// symbolized representation.
+///
+/// Addresses that could not be symbolized are left untouched.
+pub(crate) fn replace_addrs<'sym, 'src, I>(line: &str, addrs: I) -> String
+where
+    'src: 'sym,
+    I: IntoIterator<Item = (Range<usize>, &'sym Symbolized<'src>)>,
+{
+    let mut result = String::with_capacity(line.len());
+    let mut last = 0;
+    for (range, symbolized) in addrs {
+        let () = result.push_str(&line[last..range.start]);
+        match symbolized {
+            Symbolized::Sym(sym) => {
+                let _result = write!(&mut result, "{}+{:#x}", sym.name, sym.offset);
+            }
+            Symbolized::Unknown(..) => {
+                let () = result.push_str(&line[range.clone()]);
+            }
+        }
+        last = range.end;
+    }
+    let () = result.push_str(&line[last..]);
+    result
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::borrow::Cow;
+
+    use crate::symbolize::Provenance;
+    use crate::symbolize::Reason;
+    use crate::symbolize::Sym;
+
+
+    /// Check that we can find kernel addresses in lines of `ftrace`
+    /// output.
+    #[test]
+    fn addr_finding() {
+        let line = " => ffffffffc0abcd00 (0xffffffff81000010) deadbeef 12345678ffffffff1";
+        let addrs = find_addrs(line);
+        assert_eq!(
+            addrs,
+            vec![(4..20, 0xffffffffc0abcd00), (22..40, 0xffffffff81000010)]
+        );
+        assert_eq!(&line[addrs[1].0.clone()], "0xffffffff81000010");
+
+        assert_eq!(find_addrs(""), Vec::new());
+        assert_eq!(find_addrs("zzzzzzzzzzzzzzzz"), Vec::new());
+    }
+
+    /// Check that addresses are replaced as expected.
+    #[test]
+    fn addr_replacement() {
+        let sym = Symbolized::Sym(Sym {
+            name: Cow::Borrowed("do_sys_open"),
+            addr: 0xffffffffc0abccf0,
+            offset: 0x10,
+            size: None,
+            code_info: None,
+            inlined: Box::new([]),
+            provenance: Provenance::Symbol,
+            is_synthetic: false,
+            raw_elf_sym: None,
+            compilation_unit: None,
+            section: None,
+            linkage_name: None,
+            _non_exhaustive: (),
+        });
+        let unknown = Symbolized::Unknown(Reason::UnknownAddr);
+
+        let line = " => ffffffffc0abcd00 <- ffffffff81000010";
+        let addrs = find_addrs(line);
+        let line = replace_addrs(
+            line,
+            addrs
+                .into_iter()
+                .map(|(range, _addr)| range)
+                .zip([&sym, &unknown]),
+        );
+        assert_eq!(line, " => do_sys_open+0x10 <- ffffffff81000010");
+    }
+}
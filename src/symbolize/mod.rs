@@ -91,8 +91,11 @@
 //! [`gsym-in-apk`](https://github.com/libbpf/blazesym/blob/main/examples/gsym-in-apk)
 //! example, which illustrates the basic workflow.
 
+mod ktrace;
 mod perf_map;
 mod source;
+mod src_context;
+mod symbol_map;
 mod symbolizer;
 
 use std::borrow::Cow;
@@ -101,14 +104,22 @@ use std::fmt::Debug;
 use std::fmt::Display;
 use std::fmt::Formatter;
 use std::fmt::Result as FmtResult;
+use std::ops::Range;
 use std::path::Path;
+use std::path::PathBuf;
 
 cfg_apk! {
     pub use source::Apk;
+    pub use source::Zip;
 }
 cfg_breakpad! {
     pub use source::Breakpad;
 }
+pub use source::Composite;
+pub use source::CoreDump;
+cfg_dwarf! {
+    pub use source::Dwarf;
+}
 pub use source::Elf;
 cfg_gsym! {
     pub use source::Gsym;
@@ -116,8 +127,18 @@ cfg_gsym! {
     pub use source::GsymFile;
 }
 pub use source::Kernel;
+pub use source::Ktrace;
+cfg_macho! {
+    pub use source::MachO;
+}
+cfg_pe! {
+    pub use source::Pe;
+}
 pub use source::Process;
 pub use source::Source;
+pub use source::SymbolMap;
+pub use src_context::SourceContext;
+pub use src_context::SourceLines;
 
 cfg_apk! {
     pub use symbolizer::ApkDispatch;
@@ -128,46 +149,285 @@ pub use symbolizer::ProcessDispatch;
 pub use symbolizer::ProcessMemberInfo;
 pub use symbolizer::Symbolizer;
 
+pub(crate) use symbolizer::demangle_into;
+
 // Strictly speaking these types are applicable to the entire crate, but right
 // now they are only used as part of the symbolization APIs, so we re-export
 // them through this module only.
+pub use crate::file_cache::CacheStats;
 pub use crate::maps::EntryPath as ProcessMemberPath;
 pub use crate::maps::PathName as ProcessMemberType;
 
+use crate::inspect::SymVersion;
 use crate::normalize;
 use crate::Addr;
 use crate::Result;
 
 
 /// Options determining what data about a symbol to look up.
-#[derive(Debug)]
-#[non_exhaustive]
-pub enum FindSymOpts {
+#[derive(Clone, Debug)]
+pub struct FindSymOpts {
+    /// Whether to look up source code location information.
+    code_info: bool,
+    /// Whether to look up inlined function information.
+    inlined_fns: bool,
+    /// Whether to report addresses for inlined functions.
+    inlined_fn_addrs: bool,
+    /// Whether to report addresses of the out-of-line definitions of
+    /// inlined functions.
+    inlined_fn_origin_addrs: bool,
+    /// Whether to fall back to reporting source code location
+    /// information for addresses not covered by any symbol.
+    line_table_fallback: bool,
+    /// Whether to fall back to synthesizing symbols from call frame
+    /// information for addresses not covered by any symbol.
+    eh_frame_fallback: bool,
+    /// Whether to synthesize symbols for addresses inside PLT stubs.
+    plt_syms: bool,
+    /// Whether to fall back to reporting the nearest preceding symbol
+    /// for addresses not covered by any symbol.
+    nearest_preceding: bool,
+    /// Whether to report the raw ELF symbol table values of symbols.
+    raw_elf_syms: bool,
+    /// Whether to report compilation directories as part of source
+    /// code location information.
+    comp_dirs: bool,
+    /// Whether to report ELF symbol versions.
+    sym_versions: bool,
+}
+
+impl FindSymOpts {
     /// Only look up the "basic" symbol data (name, address, size, ...), without
     /// source code location and inlined function information.
-    Basic,
+    pub const BASIC: Self = Self {
+        code_info: false,
+        inlined_fns: false,
+        inlined_fn_addrs: false,
+        inlined_fn_origin_addrs: false,
+        line_table_fallback: false,
+        eh_frame_fallback: false,
+        plt_syms: false,
+        nearest_preceding: false,
+        raw_elf_syms: false,
+        comp_dirs: false,
+        sym_versions: false,
+    };
     /// Look up symbol data and source code location information.
-    CodeInfo,
+    pub const CODE_INFO: Self = Self {
+        code_info: true,
+        inlined_fns: false,
+        inlined_fn_addrs: false,
+        inlined_fn_origin_addrs: false,
+        line_table_fallback: false,
+        eh_frame_fallback: false,
+        plt_syms: false,
+        nearest_preceding: false,
+        raw_elf_syms: false,
+        comp_dirs: false,
+        sym_versions: false,
+    };
     /// Look up symbol data, source code location information, and inlined
     /// function information.
-    CodeInfoAndInlined,
-}
+    pub const CODE_INFO_AND_INLINED: Self = Self {
+        code_info: true,
+        inlined_fns: true,
+        inlined_fn_addrs: false,
+        inlined_fn_origin_addrs: false,
+        line_table_fallback: false,
+        eh_frame_fallback: false,
+        plt_syms: false,
+        nearest_preceding: false,
+        raw_elf_syms: false,
+        comp_dirs: false,
+        sym_versions: false,
+    };
 
-impl FindSymOpts {
+    /// Set whether source code location information should be reported
+    /// for addresses that are not covered by any symbol.
+    ///
+    /// In such a case, a [`ResolvedSym`] named after the closest
+    /// preceding `DW_TAG_label` entry outside of any function, if any,
+    /// is reported with [`Provenance::Label`]. Otherwise the symbol has
+    /// an empty name and [`Provenance::LineTable`]. This option only has
+    /// an effect if source code location information is looked up in
+    /// the first place.
     #[inline]
-    pub(crate) fn code_info(&self) -> bool {
-        match self {
-            Self::Basic => false,
-            Self::CodeInfo | Self::CodeInfoAndInlined => true,
-        }
+    pub fn with_line_table_fallback(mut self, enable: bool) -> Self {
+        self.line_table_fallback = enable;
+        self
     }
 
+    /// Set whether symbols should be synthesized from `.eh_frame` call
+    /// frame information for addresses not covered by any symbol.
+    ///
+    /// Such symbols are named after their start address (e.g.,
+    /// `fn_0x401230`) and are reported with
+    /// [`Provenance::EhFrame`].
     #[inline]
-    pub(crate) fn inlined_fns(&self) -> bool {
-        match self {
-            Self::Basic | Self::CodeInfo => false,
-            Self::CodeInfoAndInlined => true,
-        }
+    pub fn with_eh_frame_fallback(mut self, enable: bool) -> Self {
+        self.eh_frame_fallback = enable;
+        self
+    }
+
+    /// Set whether symbols should be synthesized for addresses inside
+    /// ELF procedure linkage table (PLT) stubs.
+    ///
+    /// PLT stubs are not covered by any proper symbol, but addresses
+    /// inside them may still be attributed to a preceding symbol of
+    /// unknown size. If enabled, stubs take precedence and are reported
+    /// as symbols named after the imported function (e.g.,
+    /// `memcpy@plt`), with [`Provenance::Plt`].
+    #[inline]
+    pub fn with_plt_syms(mut self, enable: bool) -> Self {
+        self.plt_syms = enable;
+        self
+    }
+
+    /// Set whether the nearest symbol preceding an address should be
+    /// reported if no symbol covers it.
+    ///
+    /// Symbols with a size of zero are always considered to cover all
+    /// addresses up to the next symbol. If enabled, addresses past the
+    /// end of a sized symbol are attributed to the closest symbol
+    /// starting before them as well, similar to what tools such as
+    /// `nm(1)` or `perf` do. Only ELF symbol tables are affected.
+    #[inline]
+    pub fn with_nearest_preceding(mut self, enable: bool) -> Self {
+        self.nearest_preceding = enable;
+        self
+    }
+
+    /// Set whether the raw ELF symbol table values of symbols should
+    /// be reported (see [`ResolvedSym::raw_elf_sym`]).
+    ///
+    /// For symbols resolved using DWARF debug information, this
+    /// incurs an additional ELF symbol table lookup.
+    #[inline]
+    pub fn with_raw_elf_syms(mut self, enable: bool) -> Self {
+        self.raw_elf_syms = enable;
+        self
+    }
+
+    /// Set whether the compilation directory of the compilation unit
+    /// should be reported as part of source code location information
+    /// (see [`CodeInfo::comp_dir`]).
+    ///
+    /// This option only has an effect if source code location
+    /// information is looked up in the first place.
+    #[inline]
+    pub fn with_comp_dirs(mut self, enable: bool) -> Self {
+        self.comp_dirs = enable;
+        self
+    }
+
+    /// Set whether the version of versioned ELF symbols should be
+    /// reported (see [`ResolvedSym::version`]).
+    ///
+    /// For symbols resolved using DWARF debug information, the version
+    /// is taken from the ELF symbol of the same name covering the
+    /// address, if any.
+    #[inline]
+    pub fn with_sym_versions(mut self, enable: bool) -> Self {
+        self.sym_versions = enable;
+        self
+    }
+
+    /// Set whether the address and offset of each inlined function
+    /// should be reported (see [`InlinedFn::addr`] and
+    /// [`InlinedFn::offset`]).
+    ///
+    /// This option only has an effect if inlined function information
+    /// is looked up in the first place.
+    #[inline]
+    pub fn with_inlined_fn_addrs(mut self, enable: bool) -> Self {
+        self.inlined_fn_addrs = enable;
+        self
+    }
+
+    /// Set whether the address of the out-of-line definition of each
+    /// inlined function should be reported (see
+    /// [`InlinedFn::origin_addr`]).
+    ///
+    /// Looking up these addresses requires additional work and is
+    /// only supported for DWARF debug information. This option only
+    /// has an effect if inlined function information is looked up in
+    /// the first place.
+    #[inline]
+    pub fn with_inlined_fn_origin_addrs(mut self, enable: bool) -> Self {
+        self.inlined_fn_origin_addrs = enable;
+        self
+    }
+
+    /// Check whether source code location information should be looked
+    /// up.
+    #[inline]
+    pub fn code_info(&self) -> bool {
+        self.code_info
+    }
+
+    /// Check whether inlined function information should be looked up.
+    #[inline]
+    pub fn inlined_fns(&self) -> bool {
+        self.code_info && self.inlined_fns
+    }
+
+    /// Check whether addresses of inlined functions should be reported.
+    #[inline]
+    pub fn inlined_fn_addrs(&self) -> bool {
+        self.inlined_fns() && self.inlined_fn_addrs
+    }
+
+    /// Check whether addresses of the out-of-line definitions of
+    /// inlined functions should be reported.
+    #[inline]
+    pub fn inlined_fn_origin_addrs(&self) -> bool {
+        self.inlined_fns() && self.inlined_fn_origin_addrs
+    }
+
+    /// Check whether source code location information should be reported
+    /// for addresses not covered by any symbol.
+    #[inline]
+    pub fn line_table_fallback(&self) -> bool {
+        self.code_info && self.line_table_fallback
+    }
+
+    /// Check whether symbols should be synthesized from call frame
+    /// information for addresses not covered by any symbol.
+    #[inline]
+    pub fn eh_frame_fallback(&self) -> bool {
+        self.eh_frame_fallback
+    }
+
+    /// Check whether symbols should be synthesized for PLT stubs.
+    #[inline]
+    pub fn plt_syms(&self) -> bool {
+        self.plt_syms
+    }
+
+    /// Check whether the nearest preceding symbol should be reported
+    /// for addresses not covered by any symbol.
+    #[inline]
+    pub fn nearest_preceding(&self) -> bool {
+        self.nearest_preceding
+    }
+
+    /// Check whether the raw ELF symbol table values of symbols should
+    /// be reported.
+    #[inline]
+    pub fn raw_elf_syms(&self) -> bool {
+        self.raw_elf_syms
+    }
+
+    /// Check whether compilation directories should be reported.
+    #[inline]
+    pub fn comp_dirs(&self) -> bool {
+        self.code_info && self.comp_dirs
+    }
+
+    /// Check whether ELF symbol versions should be reported.
+    #[inline]
+    pub fn sym_versions(&self) -> bool {
+        self.sym_versions
     }
 }
 
@@ -207,6 +467,18 @@ impl<T> Input<T> {
             Self::AbsAddr(x) | Self::VirtOffset(x) | Self::FileOffset(x) => x,
         }
     }
+
+    /// Map the inner payload, retaining the input type.
+    pub(crate) fn map<U, F>(self, f: F) -> Input<U>
+    where
+        F: FnOnce(T) -> U,
+    {
+        match self {
+            Self::AbsAddr(x) => Input::AbsAddr(f(x)),
+            Self::VirtOffset(x) => Input::VirtOffset(f(x)),
+            Self::FileOffset(x) => Input::FileOffset(f(x)),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -242,6 +514,15 @@ pub struct CodeInfo<'src> {
     /// The column number of the symbolized instruction in the source
     /// code.
     pub column: Option<u16>,
+    /// The compilation directory (`DW_AT_comp_dir`) of the compilation
+    /// unit the location information stems from.
+    ///
+    /// If [`dir`][Self::dir] (or [`file`][Self::file], in its absence)
+    /// is relative, it is typically relative to this directory.
+    ///
+    /// This member is only populated if requested (see
+    /// [`Builder::enable_comp_dirs`]) and available.
+    pub comp_dir: Option<Cow<'src, Path>>,
     /// The struct is non-exhaustive and open to extension.
     #[doc(hidden)]
     pub _non_exhaustive: (),
@@ -269,11 +550,29 @@ impl CodeInfo<'_> {
             file: Cow::Owned(self.file.to_os_string()),
             line: self.line,
             column: self.column,
+            comp_dir: self
+                .comp_dir
+                .as_ref()
+                .map(|dir| Cow::Owned(dir.to_path_buf())),
             _non_exhaustive: (),
         }
     }
 }
 
+impl Display for CodeInfo<'_> {
+    /// Format the source code location as `<path>[:<line>[:<column>]]`.
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let path = self.to_path();
+        let path = path.display();
+
+        match (self.line, self.column) {
+            (Some(line), Some(col)) => write!(f, "{path}:{line}:{col}"),
+            (Some(line), None) => write!(f, "{path}:{line}"),
+            (None, _) => write!(f, "{path}"),
+        }
+    }
+}
+
 
 /// A type representing an inlined function.
 #[derive(Clone, Debug, PartialEq)]
@@ -282,11 +581,44 @@ pub struct InlinedFn<'src> {
     pub name: Cow<'src, str>,
     /// Source code location information for the call to the function.
     pub code_info: Option<CodeInfo<'src>>,
+    /// The address at which the inlined function's code containing the
+    /// symbolized address starts, if requested and available.
+    ///
+    /// Similar to [`Sym::addr`], this is a "normalized" address.
+    pub addr: Option<Addr>,
+    /// The byte offset of the symbolized address from `addr`, if
+    /// requested and available.
+    ///
+    /// This member mirrors the semantics of [`Sym::offset`], but is
+    /// relative to the inlined function's `addr` and not that of the
+    /// enclosing symbol. [`Sym::offset`] does not apply to inlined
+    /// functions.
+    pub offset: Option<usize>,
+    /// The address of the out-of-line definition of the inlined
+    /// function, if requested and available.
+    ///
+    /// Inlined functions may additionally be emitted as regular
+    /// functions, e.g., because they are called from elsewhere or
+    /// because their address is taken. This address allows for
+    /// relating inlined code to such a definition. Similar to
+    /// [`Sym::addr`], this is a "normalized" address.
+    pub origin_addr: Option<Addr>,
     /// The struct is non-exhaustive and open to extension.
     #[doc(hidden)]
     pub _non_exhaustive: (),
 }
 
+impl Display for InlinedFn<'_> {
+    /// Format the inlined function as `<name>[ @ <code-info>] [inlined]`.
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let () = f.write_str(&self.name)?;
+        if let Some(code_info) = &self.code_info {
+            let () = write!(f, " @ {code_info}")?;
+        }
+        f.write_str(" [inlined]")
+    }
+}
+
 
 /// The source code language from which a symbol originates.
 #[derive(Clone, Copy, Default, Debug, PartialEq)]
@@ -299,6 +631,67 @@ pub enum SrcLang {
     Cpp,
     /// The language is Rust.
     Rust,
+    /// The language is C.
+    C,
+    /// The language is Go.
+    Go,
+    /// The language is Swift.
+    Swift,
+    /// The language is Zig.
+    Zig,
+}
+
+
+/// The origin of a symbol.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum Provenance {
+    /// The symbol was found in the symbolization source's symbol
+    /// tables or debug information.
+    #[default]
+    Symbol,
+    /// The symbol was synthesized from call frame information in the
+    /// `.eh_frame` section.
+    ///
+    /// Such a symbol has correct start address and size, but its name
+    /// is merely derived from its address.
+    EhFrame,
+    /// The symbol was synthesized for a stub in the procedure linkage
+    /// table (PLT).
+    ///
+    /// Such a symbol is named after the imported function the stub
+    /// calls, suffixed with `@plt` (e.g., `memcpy@plt`).
+    Plt,
+    /// The symbol was synthesized from line table information for an
+    /// address not covered by any symbol.
+    ///
+    /// Such a symbol has an empty name and an unknown size.
+    LineTable,
+    /// The symbol was synthesized from a label (e.g., a DWARF
+    /// `DW_TAG_label` entry) preceding an address not covered by any
+    /// function or symbol.
+    ///
+    /// Such a symbol is named after the label, starts at its address,
+    /// and has an unknown size.
+    Label,
+}
+
+
+/// The raw values of an ELF symbol table entry.
+///
+/// These values are reported as they are stored in the symbol table,
+/// without any adjustments applied. For example, `st_value` of a
+/// thread-local symbol is an offset into the TLS initialization image
+/// and that of an ARM Thumb function has its lowest bit set.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct RawElfSym {
+    /// The symbol's `st_value`.
+    pub st_value: u64,
+    /// The symbol's `st_size`.
+    pub st_size: u64,
+    /// The struct is non-exhaustive and open to extension.
+    #[doc(hidden)]
+    pub _non_exhaustive: (),
 }
 
 
@@ -317,6 +710,32 @@ pub struct ResolvedSym<'src> {
     pub code_info: Option<CodeInfo<'src>>,
     /// Inlined function information.
     pub inlined: Box<[InlinedFn<'src>]>,
+    /// The origin of the symbol.
+    pub provenance: Provenance,
+    /// Whether the symbol was derived from a symbol table or similar
+    /// source (e.g., the ELF `.symtab`), as opposed to a function
+    /// described by debug information (e.g., a DWARF
+    /// `DW_TAG_subprogram`).
+    pub is_synthetic: bool,
+    /// The raw ELF symbol table values of the symbol covering the
+    /// address, if requested and available.
+    ///
+    /// For symbols resolved using DWARF debug information, this is
+    /// the ELF symbol covering the same address, if any. Its values
+    /// may differ from `addr` and `size`.
+    pub raw_elf_sym: Option<RawElfSym>,
+    /// The path to the primary source file of the compilation unit
+    /// containing the symbol, if available.
+    pub compilation_unit: Option<PathBuf>,
+    /// The ELF symbol version of the symbol, if requested and the
+    /// symbol is versioned.
+    pub version: Option<SymVersion<'src>>,
+    /// The name of the ELF section containing the symbol, if
+    /// available.
+    pub section: Option<&'src str>,
+    /// The symbol's linkage (i.e., mangled) name, if reported
+    /// separately by the symbolization source.
+    pub linkage_name: Option<&'src str>,
 }
 
 
@@ -340,6 +759,12 @@ pub struct Sym<'src> {
     /// already normalized, such as when normalizing an address in a process
     /// context (which may have been relocated and/or have layout randomizations
     /// applied).
+    ///
+    /// The offset is always relative to `addr` as reported, irrespective
+    /// of the symbolization source used. That is, `addr + offset` equals
+    /// the normalized address that got symbolized. It does not apply to
+    /// the functions in [`inlined`][Sym::inlined], which carry their own
+    /// offsets.
     pub offset: usize,
     /// The symbol's size, if available.
     pub size: Option<usize>,
@@ -357,11 +782,201 @@ pub struct Sym<'src> {
     /// contains an inlined call to `h`, the symbols will be reported in the
     /// order `f`, `g`, `h`.
     pub inlined: Box<[InlinedFn<'src>]>,
+    /// The origin of the symbol.
+    pub provenance: Provenance,
+    /// Whether the symbol was derived from a symbol table rather than
+    /// from a function described by debug information.
+    ///
+    /// When symbolizing using DWARF, addresses not covered by any
+    /// DWARF function are attributed to the corresponding ELF symbol,
+    /// if any. Such symbols are flagged as synthetic. Their name, start
+    /// address, and size may differ from what debug information would
+    /// report and no inlined function information is available for
+    /// them.
+    pub is_synthetic: bool,
+    /// The raw ELF symbol table values (`st_value` and `st_size`) of
+    /// the symbol covering the address, if requested and available.
+    ///
+    /// Please refer to [`ResolvedSym::raw_elf_sym`] for details.
+    pub raw_elf_sym: Option<RawElfSym>,
+    /// The path to the primary source file (e.g., a `.c` or `.rs`
+    /// file) of the compilation unit that produced the symbol, if
+    /// available.
+    ///
+    /// The path is derived from the compilation unit's name and
+    /// compilation directory, as recorded in DWARF debug information.
+    /// Contrary to [`code_info`][Sym::code_info], which describes the
+    /// source code location of the symbolized address and may refer to
+    /// a header file, for example, it identifies the translation unit
+    /// the code was compiled from.
+    pub compilation_unit: Option<PathBuf>,
+    /// The name of the section (e.g., `.text`) containing the symbol,
+    /// if available.
+    ///
+    /// The section is determined by looking up the symbol's address
+    /// in the section header table of the ELF file it was resolved
+    /// from. As such, it is only reported for ELF based sources (which
+    /// includes DWARF). It can be used, for example, to tell apart
+    /// regular code from code in dedicated sections, such as
+    /// `.init` or `.text.unlikely`.
+    pub section: Option<String>,
+    /// The linkage name of the symbol, if available.
+    ///
+    /// The linkage name is the (potentially mangled) name of the
+    /// symbol as used by the linker. Contrary to
+    /// [`name`][Sym::name], it is never demangled, making it suitable
+    /// for setting breakpoints programmatically, for example. It is
+    /// taken from `DW_AT_linkage_name` when symbolizing using DWARF
+    /// and otherwise reported for names that appear to be mangled.
+    pub linkage_name: Option<Cow<'src, str>>,
     /// The struct is non-exhaustive and open to extension.
     #[doc(hidden)]
     pub _non_exhaustive: (),
 }
 
+impl Sym<'_> {
+    /// Retrieve the address range covered by the symbol, if its size is
+    /// known.
+    ///
+    /// The range starts at [`Sym::addr`] and ends (exclusively) at
+    /// `addr + size`.
+    #[inline]
+    pub fn addr_range(&self) -> Option<Range<Addr>> {
+        self.size
+            .map(|size| self.addr..self.addr.saturating_add(size as Addr))
+    }
+
+    /// Check whether the provided address falls into the symbol's
+    /// address range.
+    ///
+    /// `None` is returned if the symbol's size is not known.
+    #[inline]
+    pub fn contains(&self, addr: Addr) -> Option<bool> {
+        self.addr_range().map(|range| range.contains(&addr))
+    }
+
+    /// Retrieve an object formatting the symbol according to `format`.
+    ///
+    /// The symbol is formatted as
+    /// `<name> @ <addr>+<offset>[ <code-info>]`, where `<code-info>` is
+    /// formatted as per [`CodeInfo`]'s [`Display`] implementation. A
+    /// symbol without name (as may be reported when falling back to
+    /// line tables) is formatted as `<unknown>[ @ <code-info>]`. If
+    /// enabled, each inlined function is formatted on a separate
+    /// line, as per [`InlinedFn`]'s [`Display`] implementation.
+    ///
+    /// ```
+    /// # use blazesym::symbolize::Sym;
+    /// # use blazesym::symbolize::SymFormat;
+    /// # fn print(sym: &Sym) {
+    /// let format = SymFormat {
+    ///     addr_width: 8,
+    ///     inlined_fns: false,
+    ///     ..Default::default()
+    /// };
+    /// println!("{}", sym.display(&format));
+    /// # }
+    /// ```
+    pub fn display<'slf>(&'slf self, format: &'slf SymFormat) -> impl Display + 'slf {
+        SymDisplay { sym: self, format }
+    }
+}
+
+impl Display for Sym<'_> {
+    /// Format the symbol using the default [`SymFormat`].
+    ///
+    /// Please refer to [`Sym::display`] for details.
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        SymDisplay {
+            sym: self,
+            format: &SymFormat::default(),
+        }
+        .fmt(f)
+    }
+}
+
+
+/// Options controlling the formatting of a [`Sym`], as performed by
+/// [`Sym::display`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SymFormat {
+    /// The minimum number of hexadecimal digits to format the symbol's
+    /// address with. Addresses are zero-padded as necessary.
+    ///
+    /// Defaults to `0`.
+    pub addr_width: usize,
+    /// Whether to report inlined functions, each on a separate line.
+    ///
+    /// Defaults to `true`.
+    pub inlined_fns: bool,
+    /// The number of spaces to indent lines reporting inlined
+    /// functions by.
+    ///
+    /// Defaults to `2`.
+    pub inlined_indent: usize,
+    /// The struct is non-exhaustive and open to extension.
+    #[doc(hidden)]
+    pub _non_exhaustive: (),
+}
+
+impl Default for SymFormat {
+    fn default() -> Self {
+        Self {
+            addr_width: 0,
+            inlined_fns: true,
+            inlined_indent: 2,
+            _non_exhaustive: (),
+        }
+    }
+}
+
+
+/// A helper for formatting a [`Sym`] according to a [`SymFormat`].
+struct SymDisplay<'sym> {
+    sym: &'sym Sym<'sym>,
+    format: &'sym SymFormat,
+}
+
+impl Display for SymDisplay<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let Self { sym, format } = self;
+
+        // An empty name indicates that no symbol covers the address,
+        // but we may still know the source code location it belongs
+        // to.
+        if sym.name.is_empty() {
+            let () = f.write_str("<unknown>")?;
+            if let Some(code_info) = &sym.code_info {
+                let () = write!(f, " @ {code_info}")?;
+            }
+        } else {
+            let () = write!(
+                f,
+                "{name} @ 0x{addr:0width$x}+{offset:#x}",
+                name = sym.name,
+                addr = sym.addr,
+                offset = sym.offset,
+                width = format.addr_width,
+            )?;
+            if let Some(code_info) = &sym.code_info {
+                let () = write!(f, " {code_info}")?;
+            }
+        }
+
+        if format.inlined_fns {
+            for inlined in sym.inlined.iter() {
+                let () = write!(
+                    f,
+                    "\n{:indent$}{inlined}",
+                    "",
+                    indent = format.inlined_indent
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
 
 /// The reason why symbolization failed.
 ///
@@ -388,6 +1003,24 @@ pub enum Reason {
     Unsupported,
     /// The address could not be found in the symbolization source.
     UnknownAddr,
+    /// The address lies before the first symbol of the symbolization
+    /// source.
+    BeforeFirstSym,
+    /// The address lies in a gap between symbols of the symbolization
+    /// source.
+    BetweenSyms,
+    /// The address lies past the last symbol of the symbolization
+    /// source.
+    AfterLastSym,
+    /// The file backing the address could not be found.
+    MissingFile,
+    /// The file backing the address does not have the expected build
+    /// ID.
+    BuildIdMismatch,
+    /// The address belongs to an Android Runtime (ART) boot image
+    /// (e.g., `boot.art` or `boot.oat`), which contains precompiled Java
+    /// code and is not an ordinary ELF binary.
+    ArtBootImage,
 }
 
 impl Display for Reason {
@@ -399,12 +1032,31 @@ impl Display for Reason {
             Self::MissingSyms => "symbolization source has no or no relevant symbols",
             Self::Unsupported => "address belongs to unsupprted entity",
             Self::UnknownAddr => "address not found in symbolization source",
+            Self::BeforeFirstSym => "address lies before first symbol in symbolization source",
+            Self::BetweenSyms => "address lies between symbols in symbolization source",
+            Self::AfterLastSym => "address lies past last symbol in symbolization source",
+            Self::MissingFile => "file backing the address could not be found",
+            Self::BuildIdMismatch => "file backing the address has mismatching build ID",
+            Self::ArtBootImage => "address belongs to ART boot image",
         };
 
         f.write_str(s)
     }
 }
 
+impl Reason {
+    /// Determine the reason for `addr` not being covered by any
+    /// symbol, given the range `[start, end)` spanned by all symbols.
+    pub(crate) fn unknown_addr(addr: Addr, bounds: Option<(Addr, Addr)>) -> Self {
+        match bounds {
+            Some((start, _end)) if addr < start => Self::BeforeFirstSym,
+            Some((_start, end)) if addr >= end => Self::AfterLastSym,
+            Some(..) => Self::BetweenSyms,
+            None => Self::UnknownAddr,
+        }
+    }
+}
+
 impl From<normalize::Reason> for Reason {
     #[inline]
     fn from(reason: normalize::Reason) -> Self {
@@ -412,6 +1064,7 @@ impl From<normalize::Reason> for Reason {
             normalize::Reason::Unmapped => Self::Unmapped,
             normalize::Reason::MissingComponent => Self::MissingComponent,
             normalize::Reason::Unsupported => Self::Unsupported,
+            normalize::Reason::ArtBootImage => Self::ArtBootImage,
         }
     }
 }
@@ -420,6 +1073,9 @@ impl From<normalize::Reason> for Reason {
 /// An enumeration used as reporting vehicle for address symbolization.
 // We keep this enum as exhaustive because additions to it, should they occur,
 // are expected to be backwards-compatibility breaking.
+// The size difference between variants is accepted, as the vast
+// majority of objects are expected to be `Sym` ones in practice.
+#[allow(clippy::large_enum_variant)]
 #[derive(Clone, Debug, PartialEq)]
 pub enum Symbolized<'src> {
     /// The input address was symbolized as the provided symbol.
@@ -524,6 +1180,7 @@ mod tests {
             file: Cow::Borrowed(OsStr::new("test.c")),
             line: Some(1337),
             column: None,
+            comp_dir: None,
             _non_exhaustive: (),
         };
 
@@ -536,8 +1193,17 @@ mod tests {
             inlined: Box::new([InlinedFn {
                 name: Cow::Borrowed("inlined_test"),
                 code_info: Some(code_info.clone()),
+                addr: None,
+                offset: None,
+                origin_addr: None,
                 _non_exhaustive: (),
             }]),
+            provenance: Provenance::Symbol,
+            is_synthetic: false,
+            raw_elf_sym: None,
+            compilation_unit: None,
+            section: None,
+            linkage_name: None,
             _non_exhaustive: (),
         };
         assert_ne!(format!("{sym:?}"), "");
@@ -553,6 +1219,70 @@ mod tests {
             Reason::MissingSyms.to_string(),
             "symbolization source has no or no relevant symbols"
         );
+
+        let mut code_info = CodeInfo {
+            dir: Some(Cow::Borrowed(Path::new("/tmp/some-dir"))),
+            file: Cow::Borrowed(OsStr::new("test.c")),
+            line: Some(1337),
+            column: Some(5),
+            comp_dir: None,
+            _non_exhaustive: (),
+        };
+        assert_eq!(code_info.to_string(), "/tmp/some-dir/test.c:1337:5");
+        code_info.column = None;
+        assert_eq!(code_info.to_string(), "/tmp/some-dir/test.c:1337");
+        code_info.line = None;
+        assert_eq!(code_info.to_string(), "/tmp/some-dir/test.c");
+        code_info.line = Some(42);
+
+        let inlined = InlinedFn {
+            name: Cow::Borrowed("inlined_test"),
+            code_info: Some(code_info.clone()),
+            addr: None,
+            offset: None,
+            origin_addr: None,
+            _non_exhaustive: (),
+        };
+        assert_eq!(
+            inlined.to_string(),
+            "inlined_test @ /tmp/some-dir/test.c:42 [inlined]"
+        );
+
+        let mut sym = Sym {
+            name: Cow::Borrowed("test"),
+            addr: 0x1337,
+            offset: 0x2a,
+            size: None,
+            code_info: Some(code_info.clone()),
+            inlined: Box::new([inlined]),
+            provenance: Provenance::Symbol,
+            is_synthetic: false,
+            raw_elf_sym: None,
+            compilation_unit: None,
+            section: None,
+            linkage_name: None,
+            _non_exhaustive: (),
+        };
+        assert_eq!(
+            sym.to_string(),
+            "test @ 0x1337+0x2a /tmp/some-dir/test.c:42\n  inlined_test @ /tmp/some-dir/test.c:42 [inlined]"
+        );
+
+        let format = SymFormat {
+            addr_width: 8,
+            inlined_fns: false,
+            ..Default::default()
+        };
+        assert_eq!(
+            sym.display(&format).to_string(),
+            "test @ 0x00001337+0x2a /tmp/some-dir/test.c:42"
+        );
+
+        sym.name = Cow::Borrowed("");
+        assert_eq!(
+            sym.display(&format).to_string(),
+            "<unknown> @ /tmp/some-dir/test.c:42"
+        );
     }
 
     /// Check that we can convert `normalize::Reason` objects into
@@ -570,6 +1300,39 @@ mod tests {
         );
     }
 
+    /// Check that `Sym::addr_range` and `Sym::contains` work as
+    /// expected.
+    #[test]
+    fn sym_addr_range() {
+        let mut sym = Sym {
+            name: Cow::Borrowed("test"),
+            addr: 0x1000,
+            offset: 0,
+            size: None,
+            code_info: None,
+            inlined: Box::new([]),
+            provenance: Provenance::Symbol,
+            is_synthetic: false,
+            raw_elf_sym: None,
+            compilation_unit: None,
+            section: None,
+            linkage_name: None,
+            _non_exhaustive: (),
+        };
+        assert_eq!(sym.addr_range(), None);
+        assert_eq!(sym.contains(0x1000), None);
+
+        sym.size = Some(0x10);
+        assert_eq!(sym.addr_range(), Some(0x1000..0x1010));
+        assert_eq!(sym.contains(0xfff), Some(false));
+        assert_eq!(sym.contains(0x1000), Some(true));
+        assert_eq!(sym.contains(0x100f), Some(true));
+        assert_eq!(sym.contains(0x1010), Some(false));
+
+        sym.size = Some(0);
+        assert_eq!(sym.contains(0x1000), Some(false));
+    }
+
     /// Test the `Symbolized::*_sym()` conversion methods for the `Unknown`
     /// variant.
     #[test]
@@ -13,6 +13,7 @@ use std::str;
 
 use crate::mmap::Mmap;
 use crate::symbolize::FindSymOpts;
+use crate::symbolize::Provenance;
 use crate::symbolize::Reason;
 use crate::symbolize::ResolvedSym;
 use crate::symbolize::SrcLang;
@@ -175,6 +176,13 @@ impl Symbolize for PerfMap {
                             lang: SrcLang::Unknown,
                             code_info: None,
                             inlined: Box::new([]),
+                            provenance: Provenance::Symbol,
+                            is_synthetic: true,
+                            raw_elf_sym: None,
+                            compilation_unit: None,
+                            version: None,
+                            section: None,
+                            linkage_name: None,
                         };
                         return Ok(Ok(sym))
                     }
@@ -304,7 +312,7 @@ mod tests {
 
         for offset in 0..0xb {
             let sym = perf_map
-                .find_sym(0x7fbf1fc2144c + offset, &FindSymOpts::Basic)
+                .find_sym(0x7fbf1fc2144c + offset, &FindSymOpts::BASIC)
                 .unwrap()
                 .unwrap();
             assert_eq!(sym.name, "py::<module>:<frozen posixpath>");
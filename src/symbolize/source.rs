@@ -2,8 +2,12 @@ use std::cmp::min;
 use std::fmt::Debug;
 use std::fmt::Formatter;
 use std::fmt::Result as FmtResult;
+use std::io::Read;
+use std::os::unix::io::OwnedFd;
 use std::path::PathBuf;
+use std::sync::Arc;
 
+use crate::Addr;
 use crate::Pid;
 
 #[cfg(doc)]
@@ -13,6 +17,11 @@ use super::Symbolizer;
 cfg_apk! {
 /// A single APK file.
 ///
+/// File offsets are interpreted relative to the start of the archive.
+/// Offsets into compressed members are reported as
+/// [`Reason::Unsupported`][crate::symbolize::Reason::Unsupported], as
+/// they have no meaningful relation to the member's contents.
+///
 /// This type is used in the [`Source::Apk`] variant.
 #[derive(Clone)]
 pub struct Apk {
@@ -62,6 +71,74 @@ impl Debug for Apk {
         f.debug_tuple(stringify!(Apk)).field(path).finish()
     }
 }
+
+
+/// A single ELF file stored as a member of a zip archive (such as an
+/// APK).
+///
+/// Contrary to [`Apk`], which interprets file offsets relative to the
+/// start of the archive, this source works on the ELF member directly:
+/// virtual offsets and file offsets are both relative to the member,
+/// just as they would be for an [`Elf`] source referencing the
+/// extracted file.
+///
+/// This type is used in the [`Source::Zip`] variant.
+#[derive(Clone)]
+pub struct Zip {
+    /// The path to the zip archive.
+    pub path: PathBuf,
+    /// The path of the ELF member inside the archive.
+    pub member: String,
+    /// Whether or not to consult debug symbols to satisfy the request
+    /// (if present).
+    ///
+    /// On top of this runtime configuration, the crate needs to be
+    /// built with the `dwarf` feature to actually consult debug
+    /// symbols. If neither is satisfied, ELF symbols will be used.
+    pub debug_syms: bool,
+    /// The struct is non-exhaustive and open to extension.
+    #[doc(hidden)]
+    pub _non_exhaustive: (),
+}
+
+impl Zip {
+    /// Create a new [`Zip`] object, referencing the member `member` of
+    /// the archive at `path`.
+    ///
+    /// `debug_syms` defaults to `true` when using this constructor.
+    #[inline]
+    pub fn new(path: impl Into<PathBuf>, member: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            member: member.into(),
+            debug_syms: true,
+            _non_exhaustive: (),
+        }
+    }
+}
+
+impl From<Zip> for Source<'static> {
+    #[inline]
+    fn from(zip: Zip) -> Self {
+        Source::Zip(zip)
+    }
+}
+
+impl Debug for Zip {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let Self {
+            path,
+            member,
+            debug_syms: _,
+            _non_exhaustive: (),
+        } = self;
+
+        f.debug_tuple(stringify!(Zip))
+            .field(path)
+            .field(member)
+            .finish()
+    }
+}
 }
 
 
@@ -115,7 +192,18 @@ impl Debug for Breakpad {
 #[derive(Clone)]
 pub struct Elf {
     /// The path to an ELF file.
+    ///
+    /// If [`fd`][Elf::fd] is set, this path is not accessed and only
+    /// serves as a label for the file, e.g., in error messages.
     pub path: PathBuf,
+    /// An open file descriptor referencing the ELF file.
+    ///
+    /// If set, the file is accessed through this file descriptor
+    /// instead of by path. This is useful in sandboxed contexts, in
+    /// which files cannot be opened directly. Symbolization data for
+    /// such files are cached based on the device and inode number of
+    /// the file.
+    pub fd: Option<Arc<OwnedFd>>,
     /// Whether or not to consult debug symbols to satisfy the request
     /// (if present).
     ///
@@ -123,6 +211,15 @@ pub struct Elf {
     /// built with the `dwarf` feature to actually consult debug
     /// symbols. If neither is satisfied, ELF symbols will be used.
     pub debug_syms: bool,
+    /// The address at which the ELF file is loaded, i.e., the
+    /// difference between absolute addresses and the corresponding
+    /// virtual addresses in the ELF file.
+    ///
+    /// If set, [`Input::AbsAddr`][crate::symbolize::Input::AbsAddr]
+    /// inputs are supported and are translated by subtracting this
+    /// value. Without it, only inputs relative to the ELF file can be
+    /// symbolized.
+    pub base_addr: Option<Addr>,
     /// The struct is non-exhaustive and open to extension.
     #[doc(hidden)]
     pub _non_exhaustive: (),
@@ -136,7 +233,41 @@ impl Elf {
     pub fn new(path: impl Into<PathBuf>) -> Self {
         Self {
             path: path.into(),
+            fd: None,
+            debug_syms: true,
+            base_addr: None,
+            _non_exhaustive: (),
+        }
+    }
+
+    /// Create a new [`Elf`] object, referencing the provided path and
+    /// loaded at `base_addr`.
+    ///
+    /// This constructor is useful for symbolizing absolute addresses
+    /// of a position-independent ELF file whose load address is known
+    /// through other means than a live process (e.g., a crash report).
+    /// `debug_syms` defaults to `true` when using this constructor.
+    #[inline]
+    pub fn with_base(path: impl Into<PathBuf>, base_addr: Addr) -> Self {
+        Self {
+            base_addr: Some(base_addr),
+            ..Self::new(path)
+        }
+    }
+
+    /// Create a new [`Elf`] object, referencing the ELF file backing
+    /// the provided file descriptor.
+    ///
+    /// `label` is used in place of the file's path, e.g., when
+    /// reporting errors. `debug_syms` defaults to `true` when using
+    /// this constructor.
+    #[inline]
+    pub fn from_fd(fd: OwnedFd, label: impl Into<PathBuf>) -> Self {
+        Self {
+            path: label.into(),
+            fd: Some(Arc::new(fd)),
             debug_syms: true,
+            base_addr: None,
             _non_exhaustive: (),
         }
     }
@@ -153,7 +284,9 @@ impl Debug for Elf {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
         let Self {
             path,
+            fd: _,
             debug_syms: _,
+            base_addr: _,
             _non_exhaustive: (),
         } = self;
 
@@ -162,6 +295,56 @@ impl Debug for Elf {
 }
 
 
+cfg_dwarf! {
+/// A standalone file containing DWARF debug information.
+///
+/// Such files are commonly produced by `objcopy --only-keep-debug` and
+/// use the ELF container format, but they are not actually
+/// executable. Contrary to [`Elf`], symbolization is based
+/// exclusively on the DWARF information, i.e., ELF symbols present in
+/// the file are not consulted.
+///
+/// This type is used in the [`Source::Dwarf`] variant.
+#[derive(Clone)]
+pub struct Dwarf {
+    /// The path to the file containing DWARF debug information.
+    pub path: PathBuf,
+    /// The struct is non-exhaustive and open to extension.
+    #[doc(hidden)]
+    pub _non_exhaustive: (),
+}
+
+impl Dwarf {
+    /// Create a new [`Dwarf`] object, referencing the provided path.
+    #[inline]
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            _non_exhaustive: (),
+        }
+    }
+}
+
+impl From<Dwarf> for Source<'static> {
+    #[inline]
+    fn from(dwarf: Dwarf) -> Self {
+        Source::Dwarf(dwarf)
+    }
+}
+
+impl Debug for Dwarf {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let Self {
+            path,
+            _non_exhaustive: (),
+        } = self;
+
+        f.debug_tuple(stringify!(Dwarf)).field(path).finish()
+    }
+}
+}
+
+
 /// Linux Kernel's binary image and a copy of `/proc/kallsyms`.
 ///
 /// This type is used in the [`Source::Kernel`] variant.
@@ -189,6 +372,15 @@ pub struct Kernel {
     /// built with the `dwarf` feature to actually consult debug
     /// symbols. If neither is satisfied, ELF symbols will be used.
     pub debug_syms: bool,
+    /// The kernel address space layout randomization (KASLR) offset to
+    /// apply to addresses before looking them up in the kernel image.
+    ///
+    /// If `None`, the offset is derived by comparing the address of an
+    /// anchor symbol (e.g., `_stext`) in kallsyms with that in the
+    /// kernel image. Setting it explicitly is mostly useful for offline
+    /// symbolization, where a matching kallsyms copy is not available.
+    /// If it can't be derived, the kernel image is not used.
+    pub kaslr_offset: Option<u64>,
     /// The struct is non-exhaustive and open to extension.
     #[doc(hidden)]
     pub _non_exhaustive: (),
@@ -200,6 +392,7 @@ impl Default for Kernel {
             kallsyms: None,
             kernel_image: None,
             debug_syms: true,
+            kaslr_offset: None,
             _non_exhaustive: (),
         }
     }
@@ -296,6 +489,80 @@ impl From<Process> for Source<'static> {
 }
 
 
+/// An ELF core dump file.
+///
+/// This type is used in the [`Source::CoreDump`] variant.
+///
+/// The corresponding addresses supplied to [`Symbolizer::symbolize`] are
+/// expected to be absolute addresses
+/// ([`Input::AbsAddr`][crate::symbolize::Input::AbsAddr]) as valid within the
+/// process whose memory image got captured in the core dump. The files
+/// backing the process' memory mappings are looked up on the local file
+/// system.
+///
+/// Only 64 bit core dumps of host byte order are supported.
+#[derive(Clone)]
+pub struct CoreDump {
+    /// The path to the core dump file.
+    pub path: PathBuf,
+    /// Whether or not to consult debug symbols to satisfy the request
+    /// (if present).
+    ///
+    /// On top of this runtime configuration, the crate needs to be
+    /// built with the `dwarf` feature to actually consult debug
+    /// symbols. If neither is satisfied, ELF symbols will be used.
+    pub debug_syms: bool,
+    /// A directory to prefix paths of files backing memory mappings
+    /// with.
+    ///
+    /// This is mostly useful for analyzing core dumps captured on a
+    /// different system. If a file is not present below this
+    /// directory, the original path is used. If not set, the sysroot
+    /// configured via
+    /// [`Builder::set_sysroot`][crate::symbolize::Builder::set_sysroot]
+    /// is used, if any.
+    pub sysroot: Option<PathBuf>,
+    /// The struct is non-exhaustive and open to extension.
+    #[doc(hidden)]
+    pub _non_exhaustive: (),
+}
+
+impl CoreDump {
+    /// Create a new [`CoreDump`] object, referencing the provided path.
+    ///
+    /// `debug_syms` defaults to `true` when using this constructor.
+    #[inline]
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            debug_syms: true,
+            sysroot: None,
+            _non_exhaustive: (),
+        }
+    }
+}
+
+impl Debug for CoreDump {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let Self {
+            path,
+            debug_syms: _,
+            sysroot: _,
+            _non_exhaustive: (),
+        } = self;
+
+        f.debug_tuple(stringify!(CoreDump)).field(path).finish()
+    }
+}
+
+impl From<CoreDump> for Source<'static> {
+    #[inline]
+    fn from(core: CoreDump) -> Self {
+        Source::CoreDump(core)
+    }
+}
+
+
 cfg_gsym! {
 /// Enumeration of supported Gsym sources.
 ///
@@ -408,6 +675,255 @@ impl From<GsymFile> for Source<'static> {
 }
 
 
+cfg_macho! {
+/// A single Mach-O file.
+///
+/// Symbolization is based on DWARF debug information, contained either
+/// in the file's own `__DWARF` segment or in the dSYM bundle provided
+/// via [`dsym`][MachO::dsym]. The file's symbol table is used for
+/// addresses not covered by it.
+///
+/// This type is used in the [`Source::MachO`] variant.
+#[derive(Clone)]
+pub struct MachO {
+    /// The path to a Mach-O file.
+    pub path: PathBuf,
+    /// The name of the architecture (e.g., `x86_64` or `arm64`) to
+    /// work with, in case the file is a universal ("fat") binary
+    /// containing more than one.
+    pub arch: Option<String>,
+    /// The path to a dSYM bundle (or the DWARF file contained in it)
+    /// providing debug information for the file.
+    pub dsym: Option<PathBuf>,
+    /// The struct is non-exhaustive and open to extension.
+    #[doc(hidden)]
+    pub _non_exhaustive: (),
+}
+
+impl MachO {
+    /// Create a new [`MachO`] object, referencing the provided path.
+    #[inline]
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            arch: None,
+            dsym: None,
+            _non_exhaustive: (),
+        }
+    }
+}
+
+impl From<MachO> for Source<'static> {
+    #[inline]
+    fn from(macho: MachO) -> Self {
+        Source::MachO(macho)
+    }
+}
+
+impl Debug for MachO {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let Self {
+            path,
+            arch: _,
+            dsym: _,
+            _non_exhaustive: (),
+        } = self;
+
+        f.debug_tuple(stringify!(MachO)).field(path).finish()
+    }
+}
+}
+
+
+cfg_pe! {
+/// A single PE file (e.g., a `.dll` or `.exe`).
+///
+/// Symbolization is based on the PDB file referenced by the PE file,
+/// if one matching it is found next to the file or in one of the
+/// directories configured using
+/// [`Builder::set_pdb_dirs`][crate::symbolize::Builder::set_pdb_dirs].
+/// Otherwise, the file's export table is used.
+///
+/// This type is used in the [`Source::Pe`] variant.
+#[derive(Clone)]
+pub struct Pe {
+    /// The path to a PE file.
+    pub path: PathBuf,
+    /// The struct is non-exhaustive and open to extension.
+    #[doc(hidden)]
+    pub _non_exhaustive: (),
+}
+
+impl Pe {
+    /// Create a new [`Pe`] object, referencing the provided path.
+    #[inline]
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            _non_exhaustive: (),
+        }
+    }
+}
+
+impl From<Pe> for Source<'static> {
+    #[inline]
+    fn from(pe: Pe) -> Self {
+        Source::Pe(pe)
+    }
+}
+
+impl Debug for Pe {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let Self {
+            path,
+            _non_exhaustive: (),
+        } = self;
+
+        f.debug_tuple(stringify!(Pe)).field(path).finish()
+    }
+}
+}
+
+
+/// A flat symbol map file.
+///
+/// A symbol map is a simple text file containing one symbol per line,
+/// in the form `addr,size,name`. Addresses and sizes are hexadecimal
+/// numbers, optionally prefixed with `0x`. The name spans the remainder
+/// of the line and may contain commas. An optional header line
+/// `addr,size,name` is ignored.
+///
+/// Symbolization via symbol maps does not provide source code
+/// information.
+///
+/// This type is used in the [`Source::SymbolMap`] variant.
+#[derive(Clone)]
+pub struct SymbolMap {
+    /// The path to the symbol map file.
+    pub path: PathBuf,
+    /// The struct is non-exhaustive and open to extension.
+    #[doc(hidden)]
+    pub _non_exhaustive: (),
+}
+
+impl SymbolMap {
+    /// Create a new [`SymbolMap`] object, referencing the provided path.
+    #[inline]
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            _non_exhaustive: (),
+        }
+    }
+}
+
+impl From<SymbolMap> for Source<'static> {
+    #[inline]
+    fn from(symbol_map: SymbolMap) -> Self {
+        Source::SymbolMap(symbol_map)
+    }
+}
+
+impl Debug for SymbolMap {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let Self {
+            path,
+            _non_exhaustive: (),
+        } = self;
+
+        f.debug_tuple(stringify!(SymbolMap)).field(path).finish()
+    }
+}
+
+
+/// An ordered list of sources to consult for symbolization.
+///
+/// Each address is symbolized using the first source that is able to
+/// resolve it. This makes it possible to symbolize heterogeneous
+/// stacks, e.g., ones containing kernel addresses as well as addresses
+/// in JIT compiled code and regular ELF binaries, with a single
+/// symbolization request.
+///
+/// Sources that do not support the provided input type (e.g., because
+/// they only work with virtual offsets and absolute addresses were
+/// provided) are skipped.
+///
+/// This type is used in the [`Source::Composite`] variant.
+#[derive(Clone)]
+pub struct Composite<'dat> {
+    /// The sources to consult, in order.
+    pub sources: Vec<Source<'dat>>,
+    /// The struct is non-exhaustive and open to extension.
+    #[doc(hidden)]
+    pub _non_exhaustive: (),
+}
+
+impl<'dat> Composite<'dat> {
+    /// Create a new [`Composite`] object from the provided sources.
+    #[inline]
+    pub fn new(sources: impl IntoIterator<Item = Source<'dat>>) -> Self {
+        Self {
+            sources: sources.into_iter().collect(),
+            _non_exhaustive: (),
+        }
+    }
+}
+
+impl<'dat> From<Composite<'dat>> for Source<'dat> {
+    #[inline]
+    fn from(composite: Composite<'dat>) -> Self {
+        Source::Composite(composite)
+    }
+}
+
+impl Debug for Composite<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let Self {
+            sources,
+            _non_exhaustive: (),
+        } = self;
+
+        f.debug_tuple(stringify!(Composite)).field(sources).finish()
+    }
+}
+
+
+/// Textual kernel trace output, as produced by `ftrace`.
+///
+/// Kernel addresses contained in the text are symbolized in-line. This
+/// type is used with the [`Symbolizer::symbolize_ktrace`] method.
+pub struct Ktrace {
+    /// The reader providing the trace output.
+    pub input: Box<dyn Read>,
+    /// The struct is non-exhaustive and open to extension.
+    #[doc(hidden)]
+    pub _non_exhaustive: (),
+}
+
+impl Ktrace {
+    /// Create a new [`Ktrace`] object, reading trace output from
+    /// `input`.
+    #[inline]
+    pub fn new(input: impl Read + 'static) -> Self {
+        Self {
+            input: Box::new(input),
+            _non_exhaustive: (),
+        }
+    }
+}
+
+impl Debug for Ktrace {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let Self {
+            input: _,
+            _non_exhaustive: (),
+        } = self;
+
+        f.debug_struct(stringify!(Ktrace)).finish()
+    }
+}
+
+
 /// The description of a source of symbols and debug information that the
 /// library will consult to satisfy an address symbolization request.
 ///
@@ -424,16 +940,38 @@ pub enum Source<'dat> {
     #[cfg(feature = "breakpad")]
     #[cfg_attr(docsrs, doc(cfg(feature = "breakpad")))]
     Breakpad(Breakpad),
+    /// A standalone file containing DWARF debug information.
+    #[cfg(feature = "dwarf")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "dwarf")))]
+    Dwarf(Dwarf),
     /// A single ELF file.
     Elf(Elf),
     /// Information about the Linux kernel.
     Kernel(Kernel),
     /// Information about a process.
     Process(Process),
+    /// An ELF core dump.
+    CoreDump(CoreDump),
     /// A Gsym file.
     #[cfg(feature = "gsym")]
     #[cfg_attr(docsrs, doc(cfg(feature = "gsym")))]
     Gsym(Gsym<'dat>),
+    /// A single Mach-O file.
+    #[cfg(feature = "macho")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "macho")))]
+    MachO(MachO),
+    /// A single PE file.
+    #[cfg(feature = "pe")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "pe")))]
+    Pe(Pe),
+    /// A flat symbol map file.
+    SymbolMap(SymbolMap),
+    /// An ordered list of sources, consulted one after the other.
+    Composite(Composite<'dat>),
+    /// An ELF file stored inside a zip archive.
+    #[cfg(feature = "apk")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "apk")))]
+    Zip(Zip),
     #[doc(hidden)]
     Phantom(&'dat ()),
 }
@@ -445,11 +983,22 @@ impl Debug for Source<'_> {
             Self::Apk(apk) => Debug::fmt(apk, f),
             #[cfg(feature = "breakpad")]
             Self::Breakpad(breakpad) => Debug::fmt(breakpad, f),
+            #[cfg(feature = "dwarf")]
+            Self::Dwarf(dwarf) => Debug::fmt(dwarf, f),
             Self::Elf(elf) => Debug::fmt(elf, f),
             Self::Kernel(kernel) => Debug::fmt(kernel, f),
             Self::Process(process) => Debug::fmt(process, f),
+            Self::CoreDump(core) => Debug::fmt(core, f),
             #[cfg(feature = "gsym")]
             Self::Gsym(gsym) => Debug::fmt(gsym, f),
+            #[cfg(feature = "macho")]
+            Self::MachO(macho) => Debug::fmt(macho, f),
+            #[cfg(feature = "pe")]
+            Self::Pe(pe) => Debug::fmt(pe, f),
+            Self::SymbolMap(symbol_map) => Debug::fmt(symbol_map, f),
+            Self::Composite(composite) => Debug::fmt(composite, f),
+            #[cfg(feature = "apk")]
+            Self::Zip(zip) => Debug::fmt(zip, f),
             Self::Phantom(()) => unreachable!(),
         }
     }
@@ -466,6 +1015,11 @@ mod tests {
     fn debug_repr() {
         let apk = Apk::new("/a-path/with/components.apk");
         assert_eq!(format!("{apk:?}"), "Apk(\"/a-path/with/components.apk\")");
+        let zip = Zip::new("/a-path/with/components.apk", "lib/libfoo.so");
+        assert_eq!(
+            format!("{zip:?}"),
+            "Zip(\"/a-path/with/components.apk\", \"lib/libfoo.so\")"
+        );
         let src = Source::from(apk);
         assert_eq!(format!("{src:?}"), "Apk(\"/a-path/with/components.apk\")");
 
@@ -485,6 +1039,17 @@ mod tests {
         let src = Source::from(elf);
         assert_eq!(format!("{src:?}"), "Elf(\"/a-path/with/components.elf\")");
 
+        let dwarf = Dwarf::new("/a-path/with/components.debug");
+        assert_eq!(
+            format!("{dwarf:?}"),
+            "Dwarf(\"/a-path/with/components.debug\")"
+        );
+        let src = Source::from(dwarf);
+        assert_eq!(
+            format!("{src:?}"),
+            "Dwarf(\"/a-path/with/components.debug\")"
+        );
+
         let gsym_data = GsymData::new(b"12345");
         assert_eq!(format!("{gsym_data:?}"), "GsymData([49, 50, 51, 52, 53])");
         let gsym = Gsym::Data(gsym_data.clone());
@@ -499,6 +1064,47 @@ mod tests {
         let src = Source::from(Gsym::Data(gsym_data));
         assert_eq!(format!("{src:?}"), "GsymData([49, 50, 51, 52, 53])");
 
+        let macho = MachO::new("/a-path/with/components.dSYM");
+        assert_eq!(
+            format!("{macho:?}"),
+            "MachO(\"/a-path/with/components.dSYM\")"
+        );
+        let src = Source::from(macho);
+        assert_eq!(
+            format!("{src:?}"),
+            "MachO(\"/a-path/with/components.dSYM\")"
+        );
+
+        let pe = Pe::new("/a-path/kernel32.dll");
+        assert_eq!(format!("{pe:?}"), "Pe(\"/a-path/kernel32.dll\")");
+        let src = Source::from(pe);
+        assert_eq!(format!("{src:?}"), "Pe(\"/a-path/kernel32.dll\")");
+
+        let ktrace = Ktrace::new([].as_slice());
+        assert_eq!(format!("{ktrace:?}"), "Ktrace");
+
+        let symbol_map = SymbolMap::new("/a-path/symbols.csv");
+        assert_eq!(
+            format!("{symbol_map:?}"),
+            "SymbolMap(\"/a-path/symbols.csv\")"
+        );
+        let src = Source::from(symbol_map);
+        assert_eq!(format!("{src:?}"), "SymbolMap(\"/a-path/symbols.csv\")");
+
+        let composite = Composite::new([
+            Source::from(Elf::new("/a-path/elf")),
+            Source::from(SymbolMap::new("/a-path/symbols.csv")),
+        ]);
+        assert_eq!(
+            format!("{composite:?}"),
+            "Composite([Elf(\"/a-path/elf\"), SymbolMap(\"/a-path/symbols.csv\")])"
+        );
+        let src = Source::from(composite);
+        assert_eq!(
+            format!("{src:?}"),
+            "Composite([Elf(\"/a-path/elf\"), SymbolMap(\"/a-path/symbols.csv\")])"
+        );
+
         let kernel = Kernel::default();
         assert_ne!(format!("{kernel:?}"), "");
         let src = Source::from(kernel);
@@ -510,5 +1116,10 @@ mod tests {
         assert_eq!(format!("{process:?}"), "Process(1234)");
         let src = Source::from(process);
         assert_eq!(format!("{src:?}"), "Process(1234)");
+
+        let core = CoreDump::new("/a-path/core");
+        assert_eq!(format!("{core:?}"), "CoreDump(\"/a-path/core\")");
+        let src = Source::from(core);
+        assert_eq!(format!("{src:?}"), "CoreDump(\"/a-path/core\")");
     }
 }
@@ -0,0 +1,331 @@
+//! Functionality for retrieving the source code corresponding to
+//! symbolization results.
+//!
+//! Source code location information, as reported in a [`CodeInfo`],
+//! refers to paths on the system the binary was built on. Furthermore,
+//! it is read from the binary being symbolized and, hence, has to be
+//! considered untrusted. As such, source files are only ever read from
+//! directories explicitly configured by the user.
+
+use std::fs::File;
+use std::io::BufRead as _;
+use std::io::BufReader;
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::ErrorExt as _;
+use crate::Result;
+
+use super::CodeInfo;
+use super::Sym;
+
+
+/// A range of lines read from a source file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SourceLines {
+    /// The path of the file the lines were read from, after
+    /// application of source path mappings.
+    pub path: PathBuf,
+    /// The (1-based) number of the line of interest, i.e., the one
+    /// referenced by the [`CodeInfo`] object.
+    pub line: u32,
+    /// The (1-based) number of the first line in `lines`.
+    pub first_line: u32,
+    /// The lines read, without line terminators.
+    ///
+    /// Lines that are not valid UTF-8 are converted lossily.
+    pub lines: Vec<String>,
+    /// The struct is non-exhaustive and open to extension.
+    #[doc(hidden)]
+    pub _non_exhaustive: (),
+}
+
+impl SourceLines {
+    /// Retrieve the text of the line of interest.
+    #[inline]
+    pub fn text(&self) -> &str {
+        // SANITY: We never create an object without the line of
+        //         interest being part of `lines`.
+        &self.lines[(self.line - self.first_line) as usize]
+    }
+}
+
+
+/// Lexically resolve `path`, making sure that it never refers to
+/// anything above the directory it is relative to.
+fn resolve_relative(path: &Path) -> Option<PathBuf> {
+    let mut resolved = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(component) => resolved.push(component),
+            Component::CurDir => (),
+            Component::ParentDir => {
+                if !resolved.pop() {
+                    return None
+                }
+            }
+            Component::RootDir | Component::Prefix(..) => return None,
+        }
+    }
+    Some(resolved)
+}
+
+
+/// A reader of source code for [`CodeInfo`] objects.
+///
+/// Source files are located by means of prefix based path mappings,
+/// which map a directory on the build system (as recorded in debug
+/// information) to one on the local system (e.g., a checkout of the
+/// source code). Only files inside of a mapping's target directory are
+/// ever read: paths not covered by any mapping, as well as paths that
+/// would escape the target directory (by means of `..` components or
+/// symbolic links), are ignored.
+///
+/// ```no_run
+/// # use blazesym::symbolize::Sym;
+/// # use blazesym::symbolize::SourceContext;
+/// # fn print(sym: &Sym) {
+/// let mut ctx = SourceContext::new();
+/// let () = ctx.set_source_map("/build/project", "/home/user/project");
+/// if let Some(lines) = ctx.sym_lines(sym, 2).unwrap() {
+///     println!("{}", lines.text());
+/// }
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct SourceContext {
+    /// Source path prefix mappings, from build system to local system.
+    maps: Vec<(PathBuf, PathBuf)>,
+}
+
+impl SourceContext {
+    /// Create a new `SourceContext` without any path mappings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Map source file paths starting with `from` to paths below `to`.
+    ///
+    /// If multiple mappings apply to a path, the one with the longest
+    /// `from` prefix is used. Setting a mapping for a prefix that is
+    /// already mapped replaces the existing one. To read source files
+    /// at the location recorded in debug information, map a directory
+    /// to itself.
+    pub fn set_source_map<F, T>(&mut self, from: F, to: T)
+    where
+        F: Into<PathBuf>,
+        T: Into<PathBuf>,
+    {
+        let from = from.into();
+        let to = to.into();
+
+        if let Some(map) = self.maps.iter_mut().find(|(prefix, _)| *prefix == from) {
+            map.1 = to;
+        } else {
+            let () = self.maps.push((from, to));
+        }
+    }
+
+    /// Map the provided source file path to a path on the local
+    /// system, making sure that it stays within the target directory.
+    fn map_path(&self, path: &Path) -> Option<PathBuf> {
+        let (from, to) = self
+            .maps
+            .iter()
+            .filter(|(from, _)| path.starts_with(from))
+            .max_by_key(|(from, _)| from.components().count())?;
+        // SANITY: We only consider mappings whose prefix `path` starts
+        //         with.
+        let rest = path.strip_prefix(from).unwrap();
+        let rest = resolve_relative(rest)?;
+
+        // Symbolic links may point outside of the target directory, so
+        // check the fully resolved path as well.
+        let root = to.canonicalize().ok()?;
+        let path = root.join(rest).canonicalize().ok()?;
+        path.starts_with(&root).then_some(path)
+    }
+
+    /// Read the source line referenced by `code_info`, along with up
+    /// to `context` lines before and after it.
+    ///
+    /// Relative source paths are interpreted relative to the
+    /// compilation directory, if reported (see
+    /// [`Builder::enable_comp_dirs`][super::Builder::enable_comp_dirs]).
+    ///
+    /// `None` is returned if `code_info` does not contain a line
+    /// number, if the source file is not covered by any mapping or
+    /// could not be found or opened, or if the line is past the end of
+    /// the file.
+    pub fn code_info_lines(
+        &self,
+        code_info: &CodeInfo<'_>,
+        context: usize,
+    ) -> Result<Option<SourceLines>> {
+        let line = match code_info.line {
+            Some(line) if line > 0 => line,
+            _ => return Ok(None),
+        };
+
+        let path = code_info.to_path();
+        let path = match &code_info.comp_dir {
+            Some(comp_dir) if path.is_relative() => comp_dir.join(path),
+            _ => path.into_owned(),
+        };
+        let path = match self.map_path(&path) {
+            Some(path) => path,
+            None => return Ok(None),
+        };
+        if !path.is_file() {
+            return Ok(None)
+        }
+        let file = match File::open(&path) {
+            Ok(file) => file,
+            Err(_) => return Ok(None),
+        };
+
+        let context = u32::try_from(context).unwrap_or(u32::MAX);
+        let first_line = line.saturating_sub(context).max(1);
+        let last_line = line.saturating_add(context);
+
+        let mut reader = BufReader::new(file);
+        let mut lines = Vec::new();
+        let mut buf = Vec::new();
+        for nr in 1..=last_line {
+            let () = buf.clear();
+            let count = reader
+                .read_until(b'\n', &mut buf)
+                .with_context(|| format!("failed to read source file `{}`", path.display()))?;
+            if count == 0 {
+                break
+            }
+            if nr >= first_line {
+                let text = buf.strip_suffix(b"\n").unwrap_or(&buf);
+                let text = text.strip_suffix(b"\r").unwrap_or(text);
+                let () = lines.push(String::from_utf8_lossy(text).into_owned());
+            }
+        }
+
+        if lines.len() <= (line - first_line) as usize {
+            return Ok(None)
+        }
+
+        let lines = SourceLines {
+            path,
+            line,
+            first_line,
+            lines,
+            _non_exhaustive: (),
+        };
+        Ok(Some(lines))
+    }
+
+    /// Read the source line corresponding to the symbolized address,
+    /// along with up to `context` lines before and after it.
+    ///
+    /// This is a convenience wrapper around
+    /// [`SourceContext::code_info_lines`] for [`Sym::code_info`].
+    pub fn sym_lines(&self, sym: &Sym<'_>, context: usize) -> Result<Option<SourceLines>> {
+        match &sym.code_info {
+            Some(code_info) => self.code_info_lines(code_info, context),
+            None => Ok(None),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::borrow::Cow;
+    use std::ffi::OsStr;
+    use std::fs::create_dir_all;
+    use std::fs::write;
+    use std::os::unix::fs::symlink;
+
+    use tempfile::tempdir;
+
+    use test_log::test;
+
+
+    fn code_info<'p>(dir: &'p str, file: &'p str, line: u32) -> CodeInfo<'p> {
+        CodeInfo {
+            dir: Some(Cow::Borrowed(Path::new(dir))),
+            file: Cow::Borrowed(OsStr::new(file)),
+            line: Some(line),
+            column: None,
+            comp_dir: None,
+            _non_exhaustive: (),
+        }
+    }
+
+
+    /// Check that we can read source lines through a path mapping.
+    #[test]
+    fn source_line_reading() {
+        let dir = tempdir().unwrap();
+        let root = dir.path().join("checkout");
+        let () = create_dir_all(root.join("src")).unwrap();
+        let () = write(root.join("src/main.c"), "one\ntwo\r\nthree\nfour\n").unwrap();
+
+        let mut ctx = SourceContext::new();
+        let info = code_info("/build/project/src", "main.c", 2);
+        assert_eq!(ctx.code_info_lines(&info, 0).unwrap(), None);
+
+        let () = ctx.set_source_map("/build", "/does-not-exist");
+        let () = ctx.set_source_map("/build/project", &root);
+        let lines = ctx.code_info_lines(&info, 0).unwrap().unwrap();
+        assert_eq!(lines.path, root.canonicalize().unwrap().join("src/main.c"));
+        assert_eq!(lines.text(), "two");
+        assert_eq!(lines.first_line, 2);
+
+        let lines = ctx.code_info_lines(&info, 5).unwrap().unwrap();
+        assert_eq!(lines.first_line, 1);
+        assert_eq!(lines.lines, vec!["one", "two", "three", "four"]);
+        assert_eq!(lines.text(), "two");
+
+        let info = code_info("/build/project/src", "main.c", 4);
+        let lines = ctx.code_info_lines(&info, 1).unwrap().unwrap();
+        assert_eq!(lines.first_line, 3);
+        assert_eq!(lines.lines, vec!["three", "four"]);
+
+        let info = code_info("/build/project/src", "main.c", 5);
+        assert_eq!(ctx.code_info_lines(&info, 1).unwrap(), None);
+
+        let info = code_info("/build/project/src", "missing.c", 1);
+        assert_eq!(ctx.code_info_lines(&info, 1).unwrap(), None);
+    }
+
+    /// Make sure that source paths can't escape the configured
+    /// directories.
+    #[test]
+    fn source_path_traversal() {
+        let dir = tempdir().unwrap();
+        let root = dir.path().join("checkout");
+        let () = create_dir_all(&root).unwrap();
+        let () = write(dir.path().join("secret"), "secret\n").unwrap();
+        let () = write(root.join("main.c"), "main\n").unwrap();
+        let () = symlink(dir.path().join("secret"), root.join("link.c")).unwrap();
+
+        let mut ctx = SourceContext::new();
+        let () = ctx.set_source_map("/build", &root);
+
+        let info = code_info("/build/src/..", "main.c", 1);
+        let lines = ctx.code_info_lines(&info, 0).unwrap().unwrap();
+        assert_eq!(lines.text(), "main");
+
+        for (dir, file) in [
+            ("/build/..", "secret"),
+            ("/build/src/../..", "secret"),
+            ("/build", "../secret"),
+            ("/build", "/etc/passwd"),
+            ("/build", "link.c"),
+            ("/build", "."),
+        ] {
+            let info = code_info(dir, file, 1);
+            assert_eq!(ctx.code_info_lines(&info, 0).unwrap(), None, "{dir}/{file}");
+        }
+    }
+}
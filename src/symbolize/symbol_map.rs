@@ -0,0 +1,220 @@
+/// A module for working with flat symbol map files.
+///
+/// A symbol map is a simple comma separated table of `addr,size,name`
+/// triplets, one symbol per line.
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+use std::fs::File;
+use std::mem::transmute;
+use std::ops::Deref as _;
+use std::path::Path;
+use std::str;
+
+use crate::mmap::Mmap;
+use crate::symbolize::FindSymOpts;
+use crate::symbolize::Provenance;
+use crate::symbolize::Reason;
+use crate::symbolize::ResolvedSym;
+use crate::symbolize::SrcLang;
+use crate::symbolize::Symbolize;
+use crate::util::find_match_or_lower_bound_by_key;
+use crate::Addr;
+use crate::Error;
+use crate::ErrorExt as _;
+use crate::Result;
+
+
+/// The (optional) header line of a symbol map.
+const HEADER: &[u8] = b"addr,size,name";
+
+
+#[derive(Debug, Eq, PartialEq)]
+struct Function<'mmap> {
+    /// The name of the function.
+    name: &'mmap str,
+    /// The function's start address.
+    addr: Addr,
+    /// The size of the function.
+    size: usize,
+}
+
+
+/// Parse a hexadecimal number, with an optional `0x` prefix.
+fn parse_hex(component: &[u8]) -> Option<u64> {
+    let s = str::from_utf8(component).ok()?.trim();
+    let s = s
+        .strip_prefix("0x")
+        .or_else(|| s.strip_prefix("0X"))
+        .unwrap_or(s);
+    u64::from_str_radix(s, 16).ok()
+}
+
+
+/// Parse a line of a symbol map file.
+fn parse_symbol_map_line(line: &[u8]) -> Result<Function<'_>> {
+    let malformed = |component| {
+        Error::with_invalid_data(format!(
+            "encountered malformed {component} in symbol map line: {}",
+            String::from_utf8_lossy(line)
+        ))
+    };
+
+    // Lines have the following format:
+    // > ADDR,SIZE,symbolname
+    //
+    // ADDR and SIZE are hex numbers, optionally prefixed with 0x.
+    // symbolname is the rest of the line, so it could contain commas.
+    let mut split = line.splitn(3, |&b| b == b',');
+    let addr = split
+        .next()
+        .and_then(parse_hex)
+        .ok_or_else(|| malformed("start address"))?;
+    let size = split
+        .next()
+        .and_then(parse_hex)
+        .and_then(|size| usize::try_from(size).ok())
+        .ok_or_else(|| malformed("size component"))?;
+    let name = split
+        .next()
+        .and_then(|name| str::from_utf8(name).ok())
+        .ok_or_else(|| malformed("symbol component"))?;
+
+    let function = Function { name, addr, size };
+    Ok(function)
+}
+
+
+fn parse_symbol_map(data: &[u8]) -> Result<Vec<Function<'_>>> {
+    let mut lines = data
+        .split(|&b| b == b'\n' || b == b'\r')
+        .filter(|line| !line.is_empty())
+        .peekable();
+
+    if lines.peek() == Some(&HEADER) {
+        let _header = lines.next();
+    }
+
+    let mut functions = lines
+        .map(parse_symbol_map_line)
+        .collect::<Result<Vec<_>>>()?;
+    let () = functions.sort_by_key(|x| (x.addr, x.size));
+    Ok(functions)
+}
+
+
+pub(crate) struct SymbolMapResolver {
+    /// All functions found in the symbol map, ordered by start address.
+    // SAFETY: We must not hand out references with a 'static lifetime to
+    //         this member. Rather, they should never outlive `self`.
+    //         Furthermore, this member has to be listed before `_mmap`
+    //         to make sure we never end up with a dangling reference.
+    functions: Vec<Function<'static>>,
+    /// The memory mapped file.
+    _mmap: Mmap,
+}
+
+impl SymbolMapResolver {
+    /// Load a [`SymbolMapResolver`] from the provided file.
+    pub fn from_file(path: &Path, file: &File) -> Result<Self> {
+        let mmap = Mmap::map(file)
+            .with_context(|| format!("failed to mmap symbol map `{}`", path.display()))?;
+        // We transmute the mmap's lifetime to static here as that is a
+        // necessity for self-referentiality.
+        // SAFETY: We never hand out any 'static references later on.
+        let data = unsafe { transmute::<&[u8], &'static [u8]>(mmap.deref()) };
+        let functions = parse_symbol_map(data)
+            .with_context(|| format!("failed to parse symbol map `{}`", path.display()))?;
+
+        let slf = Self {
+            functions,
+            _mmap: mmap,
+        };
+        Ok(slf)
+    }
+}
+
+impl Symbolize for SymbolMapResolver {
+    fn find_sym(&self, addr: Addr, _opts: &FindSymOpts) -> Result<Result<ResolvedSym<'_>, Reason>> {
+        let result = find_match_or_lower_bound_by_key(&self.functions, addr, |l| l.addr);
+        match result {
+            Some(idx) => {
+                for function in &self.functions[idx..] {
+                    if function.addr > addr {
+                        break
+                    }
+
+                    if (function.addr == addr && function.size == 0)
+                        || (function.addr <= addr && addr < function.addr + function.size as Addr)
+                    {
+                        let Function { name, addr, size } = function;
+                        let sym = ResolvedSym {
+                            name,
+                            addr: *addr,
+                            size: Some(*size),
+                            lang: SrcLang::Unknown,
+                            code_info: None,
+                            inlined: Box::new([]),
+                            provenance: Provenance::Symbol,
+                            is_synthetic: true,
+                            raw_elf_sym: None,
+                            compilation_unit: None,
+                            version: None,
+                            section: None,
+                            linkage_name: None,
+                        };
+                        return Ok(Ok(sym))
+                    }
+                }
+                Ok(Err(Reason::UnknownAddr))
+            }
+            None => Ok(Err(Reason::UnknownAddr)),
+        }
+    }
+}
+
+impl Debug for SymbolMapResolver {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.debug_struct("SymbolMapResolver").finish()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+
+    /// Check that we can parse a symbol map.
+    #[test]
+    fn symbol_map_parsing() {
+        let data = b"addr,size,name\n0x2000,0x10,bar\n1000,20,foo<a, b>\n\n";
+        let functions = parse_symbol_map(data).unwrap();
+        let expected = vec![
+            Function {
+                name: "foo<a, b>",
+                addr: 0x1000,
+                size: 0x20,
+            },
+            Function {
+                name: "bar",
+                addr: 0x2000,
+                size: 0x10,
+            },
+        ];
+        assert_eq!(functions, expected);
+    }
+
+    /// Make sure that we report errors on malformed lines.
+    #[test]
+    fn malformed_lines() {
+        let err = parse_symbol_map(b"xyz,10,foo").unwrap_err();
+        assert!(err.to_string().contains("start address"), "{err}");
+
+        let err = parse_symbol_map(b"1000,foo").unwrap_err();
+        assert!(err.to_string().contains("size component"), "{err}");
+
+        let err = parse_symbol_map(b"1000,10").unwrap_err();
+        assert!(err.to_string().contains("symbol component"), "{err}");
+    }
+}
@@ -1,39 +1,60 @@
 use std::borrow::Cow;
+use std::collections::HashMap;
 use std::ffi::OsStr;
 use std::fmt::Debug;
+#[cfg(feature = "demangle")]
+use std::fmt::Write as _;
 use std::fs::File;
+use std::io::Read as _;
 use std::mem::take;
 use std::ops::Deref as _;
 use std::ops::Range;
+use std::os::unix::io::AsFd as _;
+use std::os::unix::io::OwnedFd;
 use std::path::Path;
 use std::path::PathBuf;
 use std::rc::Rc;
 
 #[cfg(feature = "breakpad")]
 use crate::breakpad::BreakpadResolver;
+use crate::coredump;
+#[cfg(feature = "dwarf")]
+use crate::dwarf::DwarfResolver;
 use crate::elf::ElfParser;
 use crate::elf::ElfResolver;
 use crate::elf::ElfResolverData;
+use crate::file_cache::CacheStats;
 use crate::file_cache::FileCache;
 #[cfg(feature = "gsym")]
 use crate::gsym::GsymResolver;
 use crate::insert_map::InsertMap;
+use crate::inspect::SymVersion;
 use crate::kernel::KernelResolver;
 use crate::ksym::KSymResolver;
 use crate::ksym::KALLSYMS;
 use crate::log;
+#[cfg(feature = "macho")]
+use crate::macho::MachOResolver;
+#[cfg(feature = "macho")]
+use crate::macho::MachOResolverData;
 use crate::maps;
 use crate::maps::EntryPath;
 use crate::maps::MapsEntry;
 use crate::maps::PathName;
 use crate::mmap::Mmap;
 use crate::normalize;
+use crate::normalize::buildid::read_elf_build_id;
 use crate::normalize::normalize_sorted_user_addrs_with_entries;
 use crate::normalize::Handler as _;
+#[cfg(feature = "pe")]
+use crate::pe::is_pe_file;
+#[cfg(feature = "pe")]
+use crate::pe::PeResolver;
 use crate::symbolize::InlinedFn;
 use crate::symbolize::Resolve;
 use crate::symbolize::TranslateFileOffset;
 use crate::util;
+use crate::util::resolve_in_root;
 use crate::util::uname_release;
 use crate::util::Dbg;
 #[cfg(feature = "apk")]
@@ -46,11 +67,16 @@ use crate::IntoError as _;
 use crate::Pid;
 use crate::Result;
 
+use super::ktrace;
 use super::perf_map::PerfMap;
 #[cfg(feature = "apk")]
 use super::source::Apk;
 #[cfg(feature = "breakpad")]
 use super::source::Breakpad;
+use super::source::Composite;
+use super::source::CoreDump;
+#[cfg(feature = "dwarf")]
+use super::source::Dwarf;
 use super::source::Elf;
 #[cfg(feature = "gsym")]
 use super::source::Gsym;
@@ -59,8 +85,17 @@ use super::source::GsymData;
 #[cfg(feature = "gsym")]
 use super::source::GsymFile;
 use super::source::Kernel;
+use super::source::Ktrace;
+#[cfg(feature = "macho")]
+use super::source::MachO;
+#[cfg(feature = "pe")]
+use super::source::Pe;
 use super::source::Process;
 use super::source::Source;
+use super::source::SymbolMap;
+#[cfg(feature = "apk")]
+use super::source::Zip;
+use super::symbol_map::SymbolMapResolver;
 use super::FindSymOpts;
 use super::Input;
 use super::Reason;
@@ -94,6 +129,37 @@ fn create_apk_elf_path(apk: &Path, elf: &Path) -> Result<PathBuf> {
 }
 
 
+/// Map `path` into `sysroot`, if the file it refers to exists below it.
+fn sysroot_path(sysroot: &Path, path: &Path) -> Option<PathBuf> {
+    match resolve_in_root(sysroot, path) {
+        Ok(path) if path.exists() => Some(path),
+        Ok(_) => None,
+        Err(err) => {
+            log::debug!(
+                "failed to resolve {} in sysroot {}: {err}",
+                path.display(),
+                sysroot.display()
+            );
+            None
+        }
+    }
+}
+
+
+/// Check whether `name` appears to be a mangled symbol name, as
+/// produced by the Itanium C++ ABI or one of the Rust mangling
+/// schemes.
+fn is_mangled(name: &str) -> bool {
+    // Mach-O symbol names carry an additional leading underscore.
+    // Mangled names continue with an upper case tag or a length.
+    ["_Z", "__Z", "_R", "__R"].iter().any(|prefix| {
+        name.strip_prefix(prefix)
+            .and_then(|rest| rest.chars().next())
+            .map(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+            .unwrap_or(false)
+    })
+}
+
 /// Demangle a symbol name using the demangling scheme for the given language.
 #[cfg(feature = "demangle")]
 fn maybe_demangle(name: Cow<'_, str>, language: SrcLang) -> Cow<'_, str> {
@@ -105,6 +171,9 @@ fn maybe_demangle(name: Cow<'_, str>, language: SrcLang) -> Cow<'_, str> {
         SrcLang::Cpp => cpp_demangle::Symbol::new(name.as_ref())
             .ok()
             .and_then(|x| x.demangle(&Default::default()).ok().map(Cow::Owned)),
+        // We don't support demangling of Swift symbols and the remaining
+        // languages don't use mangling schemes that we could reverse.
+        SrcLang::C | SrcLang::Go | SrcLang::Swift | SrcLang::Zig => None,
         SrcLang::Unknown => rustc_demangle::try_demangle(name.as_ref())
             .map(|x| Cow::Owned(format!("{x:#}")))
             .ok()
@@ -123,6 +192,94 @@ fn maybe_demangle(name: Cow<'_, str>, _language: SrcLang) -> Cow<'_, str> {
     name
 }
 
+/// Demangle a symbol name of unknown language into `buf`.
+///
+/// `buf` is cleared before use. Returns `true` if the name could be
+/// demangled, in which case `buf` contains the demangled name.
+#[cfg(feature = "demangle")]
+pub(crate) fn demangle_into(name: &str, buf: &mut String) -> bool {
+    let () = buf.clear();
+
+    if let Ok(demangled) = rustc_demangle::try_demangle(name) {
+        if write!(buf, "{demangled:#}").is_ok() {
+            return true
+        }
+        let () = buf.clear();
+    }
+
+    if let Ok(sym) = cpp_demangle::Symbol::new(name) {
+        if sym.structured_demangle(buf, &Default::default()).is_ok() {
+            return true
+        }
+        let () = buf.clear();
+    }
+    false
+}
+
+#[cfg(not(feature = "demangle"))]
+pub(crate) fn demangle_into(_name: &str, _buf: &mut String) -> bool {
+    // Demangling is disabled.
+    false
+}
+
+
+/// The default maximum number of inlined functions reported per
+/// symbol.
+const DEFAULT_MAX_INLINED_DEPTH: usize = 64;
+
+/// The name of the synthetic inlined function marking a truncated
+/// inline stack.
+const TRUNCATED_INLINED_FN: &str = "<truncated>";
+
+
+/// Truncate an inline stack to at most `max_depth` inlined functions.
+///
+/// Excess frames are replaced with a single synthetic one named
+/// `<truncated>`, which carries the source code location of the
+/// innermost inlined function.
+fn truncate_inlined(inlined: Box<[InlinedFn<'_>]>, max_depth: usize) -> Box<[InlinedFn<'_>]> {
+    if inlined.len() <= max_depth {
+        return inlined
+    }
+
+    let mut inlined = Vec::from(inlined);
+    // SANITY: The stack contains more than `max_depth` frames and so
+    //         it can't be empty.
+    let innermost = inlined.pop().unwrap();
+    let () = inlined.truncate(max_depth);
+    // Each frame carries the location at which the next one got
+    // inlined, with the innermost one carrying the actual location of
+    // the address in question. We preserve this property by handing
+    // the innermost location to the truncation marker.
+    let truncated = InlinedFn {
+        name: Cow::Borrowed(TRUNCATED_INLINED_FN),
+        code_info: innermost.code_info,
+        addr: None,
+        offset: None,
+        origin_addr: None,
+        _non_exhaustive: (),
+    };
+    let () = inlined.push(truncated);
+    inlined.into_boxed_slice()
+}
+
+
+/// Append the provided symbol version, if any, to a symbol name.
+///
+/// The default version is separated by `@@`, hidden ones by `@`.
+fn append_version<'sym>(name: Cow<'sym, str>, version: Option<SymVersion<'_>>) -> Cow<'sym, str> {
+    match version {
+        Some(SymVersion {
+            name: version,
+            hidden,
+        }) => {
+            let sep = if hidden { "@" } else { "@@" };
+            Cow::Owned(format!("{name}{sep}{version}"))
+        }
+        None => name,
+    }
+}
+
 
 /// Information about a member inside an APK.
 ///
@@ -211,6 +368,32 @@ pub struct Builder {
     code_info: bool,
     /// Whether to report inlined functions as part of symbolization.
     inlined_fns: bool,
+    /// Whether to report addresses of inlined functions.
+    inlined_fn_addrs: bool,
+    /// Whether to report addresses of the out-of-line definitions of
+    /// inlined functions.
+    inlined_fn_origin_addrs: bool,
+    /// The maximum number of inlined functions to report per symbol.
+    max_inlined_depth: usize,
+    /// Whether to report source code location information for addresses
+    /// not covered by any symbol.
+    line_table_fallback: bool,
+    /// Whether to synthesize symbols from `.eh_frame` call frame
+    /// information for addresses not covered by any symbol.
+    #[cfg(feature = "dwarf")]
+    eh_frame_fallback: bool,
+    /// Whether to synthesize symbols for ELF PLT stubs.
+    plt_syms: bool,
+    /// Whether to report the nearest preceding symbol for addresses not
+    /// covered by any symbol.
+    nearest_preceding: bool,
+    /// Whether to report the raw ELF symbol table values of symbols.
+    raw_elf_syms: bool,
+    /// Whether to report compilation directories as part of source
+    /// code location information.
+    comp_dirs: bool,
+    /// Whether to append ELF symbol versions to symbol names.
+    sym_versions: bool,
     /// Whether or not to transparently demangle symbols.
     ///
     /// Demangling happens on a best-effort basis. Currently supported
@@ -224,6 +407,20 @@ pub struct Builder {
     /// The "dispatch" function to use when symbolizing addresses
     /// mapping to members of a process.
     process_dispatch: Option<Dbg<Box<dyn ProcessDispatch>>>,
+    /// The directory below which to look for files before falling back
+    /// to their original paths.
+    sysroot: Option<PathBuf>,
+    /// The directory in which to cache data derived from DWARF debug
+    /// information.
+    #[cfg(feature = "dwarf")]
+    cache_dir: Option<PathBuf>,
+    /// Whether to report DWARF data that can't be interpreted as an
+    /// error.
+    #[cfg(feature = "dwarf")]
+    strict_dwarf: bool,
+    /// Additional directories in which to look for PDB files.
+    #[cfg(feature = "pe")]
+    pdb_dirs: Vec<PathBuf>,
 }
 
 impl Builder {
@@ -249,6 +446,156 @@ impl Builder {
         self
     }
 
+    /// Enable/disable reporting of addresses of inlined functions (see
+    /// [`InlinedFn::addr`] and [`InlinedFn::offset`]).
+    ///
+    /// This option only has an effect if `inlined_fns` is `true`.
+    pub fn enable_inlined_fn_addrs(mut self, enable: bool) -> Self {
+        self.inlined_fn_addrs = enable;
+        self
+    }
+
+    /// Enable/disable reporting of addresses of the out-of-line
+    /// definitions of inlined functions (see
+    /// [`InlinedFn::origin_addr`]).
+    ///
+    /// This option only has an effect if `inlined_fns` is `true`.
+    pub fn enable_inlined_fn_origin_addrs(mut self, enable: bool) -> Self {
+        self.inlined_fn_origin_addrs = enable;
+        self
+    }
+
+    /// Set the maximum number of inlined functions to report for a
+    /// single symbol.
+    ///
+    /// Heavily inlined code (or malformed debug information) can
+    /// result in inline stacks hundreds of frames deep. If the inline
+    /// stack of a symbol exceeds this limit, only the `max_depth`
+    /// outermost inlined functions are reported, followed by a
+    /// synthetic one named `<truncated>` that carries the source code
+    /// location of the address in question.
+    ///
+    /// Defaults to `64`.
+    pub fn set_max_inlined_depth(mut self, max_depth: usize) -> Self {
+        self.max_inlined_depth = max_depth;
+        self
+    }
+
+    /// Enable/disable reporting of source code location information
+    /// for addresses not covered by any symbol.
+    ///
+    /// If enabled, addresses for which no symbol is found but that are
+    /// covered by DWARF line information are reported as a [`Sym`]
+    /// with populated [`code_info`][Sym::code_info]. The symbol is
+    /// named after the closest preceding `DW_TAG_label` entry, if
+    /// any. Otherwise it has an empty name and
+    /// [`Provenance::LineTable`][crate::symbolize::Provenance::LineTable].
+    ///
+    /// This option only has an effect if `code_info` is `true`.
+    pub fn enable_line_table_fallback(mut self, enable: bool) -> Self {
+        self.line_table_fallback = enable;
+        self
+    }
+
+    /// Enable/disable synthesis of symbols from `.eh_frame` call frame
+    /// information for addresses in ELF files not covered by any
+    /// symbol.
+    ///
+    /// Even fully stripped binaries typically contain call frame
+    /// information describing the boundaries of each function. If
+    /// enabled, addresses for which no other symbol is found but that
+    /// fall into one of these functions are reported as a [`Sym`]
+    /// named after the function's start address (e.g.,
+    /// `fn_0x401230`), with [`Sym::provenance`] set to
+    /// [`Provenance::EhFrame`][crate::symbolize::Provenance::EhFrame].
+    ///
+    /// This option is disabled by default, because such synthesized
+    /// names may be confusing to consumers.
+    #[cfg(feature = "dwarf")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "dwarf")))]
+    pub fn enable_eh_frame_fallback(mut self, enable: bool) -> Self {
+        self.eh_frame_fallback = enable;
+        self
+    }
+
+    /// Enable/disable synthesis of symbols for addresses inside of
+    /// procedure linkage table (PLT) stubs of ELF files.
+    ///
+    /// Calls to functions imported from shared objects go through such
+    /// stubs, which are not covered by any symbol. Addresses inside
+    /// them are hence either reported as unknown or attributed to a
+    /// preceding symbol of unknown size, such as `_init`. If enabled,
+    /// addresses inside them are reported as a [`Sym`] named after the
+    /// called function with a `@plt` suffix (e.g., `memcpy@plt`), with
+    /// [`Sym::provenance`] set to
+    /// [`Provenance::Plt`][crate::symbolize::Provenance::Plt]. Only
+    /// x86-64 PLT layouts are currently understood.
+    ///
+    /// This option is disabled by default.
+    pub fn enable_plt_syms(mut self, enable: bool) -> Self {
+        self.plt_syms = enable;
+        self
+    }
+
+    /// Enable/disable reporting of the nearest preceding symbol for
+    /// addresses in ELF files that are not covered by any symbol.
+    ///
+    /// By default, addresses past the end of a symbol (as per its
+    /// declared size) are reported as unknown, unless another symbol
+    /// covers them. If enabled, they are attributed to the closest
+    /// symbol starting before them instead, with [`Sym::offset`]
+    /// being the distance from its start, similar to how `nm(1)` or
+    /// `perf` behave. Kernel symbols are always resolved this way, as
+    /// `kallsyms` carries no size information.
+    ///
+    /// This option is disabled by default.
+    pub fn enable_nearest_preceding(mut self, enable: bool) -> Self {
+        self.nearest_preceding = enable;
+        self
+    }
+
+    /// Enable/disable reporting of the raw ELF symbol table values
+    /// (`st_value` and `st_size`) of symbols (see
+    /// [`Sym::raw_elf_sym`]).
+    ///
+    /// These values are reported as stored in the ELF symbol table,
+    /// irrespective of whether the symbol itself got resolved using
+    /// the ELF symbol table or DWARF debug information. Hence, they
+    /// may differ from [`Sym::addr`] and [`Sym::size`].
+    pub fn enable_raw_elf_syms(mut self, enable: bool) -> Self {
+        self.raw_elf_syms = enable;
+        self
+    }
+
+    /// Enable/disable reporting of the compilation directory
+    /// (`DW_AT_comp_dir`) of the compilation unit that source code
+    /// location information stems from (see
+    /// [`CodeInfo::comp_dir`][crate::symbolize::CodeInfo::comp_dir]).
+    ///
+    /// This information can be used to reconstruct absolute source
+    /// file paths, when
+    /// [`CodeInfo::dir`][crate::symbolize::CodeInfo::dir] is relative.
+    /// It is only available for symbolization based on DWARF debug
+    /// information.
+    ///
+    /// This option only has an effect if `code_info` is `true`.
+    pub fn enable_comp_dirs(mut self, enable: bool) -> Self {
+        self.comp_dirs = enable;
+        self
+    }
+
+    /// Enable/disable reporting of ELF symbol versions.
+    ///
+    /// If enabled, the version of versioned symbols is appended to
+    /// [`Sym::name`], using the same notation as the GNU toolchain:
+    /// the default version of a symbol is separated by `@@` (e.g.,
+    /// `pthread_create@@GLIBC_2.34`), while hidden versions are
+    /// separated by a single `@` (e.g., `pthread_create@GLIBC_2.2.5`).
+    pub fn enable_sym_versions(mut self, enable: bool) -> Self {
+        self.sym_versions = enable;
+        self
+    }
+
     /// Enable/disable transparent demangling of symbol names.
     ///
     /// Demangling happens on a best-effort basis. Currently supported languages
@@ -281,16 +628,122 @@ impl Builder {
         self
     }
 
+    /// Set the sysroot to use for locating files.
+    ///
+    /// When symbolizing data captured on a different system (e.g.,
+    /// one of a different architecture), the files referenced may not
+    /// be present at their original paths, but below a directory
+    /// mirroring the target's file system. If a sysroot is set, paths
+    /// of files mapped into a process, of files referenced by a core
+    /// dump, as well as of files provided via the [`Elf`] and
+    /// [`Apk`][crate::symbolize::Apk] sources, are first looked up
+    /// below it, before falling back to the original path.
+    ///
+    /// Symbolic links encountered while resolving a path are
+    /// interpreted relative to the sysroot, i.e., they cannot escape
+    /// it.
+    pub fn set_sysroot<P>(mut self, sysroot: P) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        self.sysroot = Some(sysroot.into());
+        self
+    }
+
+    /// Set the directory in which to cache data derived from DWARF
+    /// debug information.
+    ///
+    /// Before symbolizing using DWARF debug information, all
+    /// compilation units are scanned to build an index of their
+    /// address ranges, which can take seconds for large binaries. If a
+    /// cache directory is set, this index is stored in it and reused
+    /// on subsequent runs. Cache entries are keyed by the file's build
+    /// ID and size or, in the absence of a build ID, by its device,
+    /// inode, size, and modification time. Entries unused for 30 days
+    /// are evicted, as are the least recently used ones once all
+    /// entries exceed 256 MiB in total. The directory is created if it
+    /// does not exist.
+    ///
+    /// Caching happens on a best-effort basis: failure to read or
+    /// write a cache entry does not cause symbolization to fail.
+    #[cfg(feature = "dwarf")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "dwarf")))]
+    pub fn set_cache_dir<P>(mut self, cache_dir: P) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        self.cache_dir = Some(cache_dir.into());
+        self
+    }
+
+    /// Enable/disable strict interpretation of DWARF debug
+    /// information.
+    ///
+    /// By default, DWARF data that can't be interpreted is ignored on
+    /// a best-effort basis, possibly leading to partial results. When
+    /// enabled, symbolization fails with an error of kind
+    /// [`ErrorKind::InvalidDwarf`][crate::ErrorKind::InvalidDwarf]
+    /// instead. Please refer to
+    /// [`dwarf::Builder::set_strict`][crate::dwarf::Builder::set_strict]
+    /// for details.
+    ///
+    /// The DWARF cache (see [`Builder::set_cache_dir`]) is not used in
+    /// strict mode.
+    #[cfg(feature = "dwarf")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "dwarf")))]
+    pub fn enable_strict_dwarf(mut self, enable: bool) -> Self {
+        self.strict_dwarf = enable;
+        self
+    }
+
+    /// Set the directories in which to look for PDB files containing
+    /// debug information for PE files.
+    ///
+    /// The PDB file referenced by a PE file is always looked for in
+    /// the directory containing the PE file first. The directories
+    /// provided here are consulted afterwards, in order. A PDB file is
+    /// only used if it matches the PE file (as per the GUID and age
+    /// recorded in it). If no matching PDB file is found, symbolization
+    /// is based on the PE file's export table.
+    #[cfg(feature = "pe")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "pe")))]
+    pub fn set_pdb_dirs<D, P>(mut self, dirs: D) -> Self
+    where
+        D: IntoIterator<Item = P>,
+        P: Into<PathBuf>,
+    {
+        self.pdb_dirs = dirs.into_iter().map(Into::into).collect();
+        self
+    }
+
     /// Create the [`Symbolizer`] object.
     pub fn build(self) -> Symbolizer {
         let Self {
             auto_reload,
             code_info,
             inlined_fns,
+            inlined_fn_addrs,
+            inlined_fn_origin_addrs,
+            max_inlined_depth,
+            line_table_fallback,
+            #[cfg(feature = "dwarf")]
+            eh_frame_fallback,
+            plt_syms,
+            nearest_preceding,
+            raw_elf_syms,
+            comp_dirs,
+            sym_versions,
             demangle,
             #[cfg(feature = "apk")]
             apk_dispatch,
             process_dispatch,
+            sysroot,
+            #[cfg(feature = "dwarf")]
+            cache_dir,
+            #[cfg(feature = "dwarf")]
+            strict_dwarf,
+            #[cfg(feature = "pe")]
+            pdb_dirs,
         } = self;
 
         let find_sym_opts = match (code_info, inlined_fns) {
@@ -300,28 +753,53 @@ impl Builder {
                         "inlined function reporting asked for but more general code information inquiry is disabled; flag is being ignored"
                     );
                 }
-                FindSymOpts::Basic
+                FindSymOpts::BASIC
             }
-            (true, false) => FindSymOpts::CodeInfo,
-            (true, true) => FindSymOpts::CodeInfoAndInlined,
-        };
+            (true, false) => FindSymOpts::CODE_INFO,
+            (true, true) => FindSymOpts::CODE_INFO_AND_INLINED,
+        }
+        .with_inlined_fn_addrs(inlined_fn_addrs)
+        .with_inlined_fn_origin_addrs(inlined_fn_origin_addrs)
+        .with_line_table_fallback(line_table_fallback)
+        .with_plt_syms(plt_syms)
+        .with_nearest_preceding(nearest_preceding)
+        .with_raw_elf_syms(raw_elf_syms)
+        .with_comp_dirs(comp_dirs)
+        .with_sym_versions(sym_versions);
+        #[cfg(feature = "dwarf")]
+        let find_sym_opts = find_sym_opts.with_eh_frame_fallback(eh_frame_fallback);
 
         Symbolizer {
             #[cfg(feature = "apk")]
             apk_cache: FileCache::builder().enable_auto_reload(auto_reload).build(),
             #[cfg(feature = "breakpad")]
             breakpad_cache: FileCache::builder().enable_auto_reload(auto_reload).build(),
+            #[cfg(feature = "dwarf")]
+            dwarf_cache: FileCache::builder().enable_auto_reload(auto_reload).build(),
             elf_cache: FileCache::builder().enable_auto_reload(auto_reload).build(),
             #[cfg(feature = "gsym")]
             gsym_cache: FileCache::builder().enable_auto_reload(auto_reload).build(),
             ksym_cache: FileCache::builder().enable_auto_reload(auto_reload).build(),
+            #[cfg(feature = "macho")]
+            macho_cache: FileCache::builder().enable_auto_reload(auto_reload).build(),
+            #[cfg(feature = "pe")]
+            pe_cache: FileCache::builder().enable_auto_reload(auto_reload).build(),
             perf_map_cache: FileCache::builder().enable_auto_reload(auto_reload).build(),
             process_cache: InsertMap::new(),
+            symbol_map_cache: FileCache::builder().enable_auto_reload(auto_reload).build(),
             find_sym_opts,
+            max_inlined_depth,
             demangle,
             #[cfg(feature = "apk")]
             apk_dispatch,
             process_dispatch,
+            sysroot,
+            #[cfg(feature = "dwarf")]
+            cache_dir,
+            #[cfg(feature = "dwarf")]
+            strict_dwarf,
+            #[cfg(feature = "pe")]
+            pdb_dirs,
         }
     }
 }
@@ -332,10 +810,28 @@ impl Default for Builder {
             auto_reload: true,
             code_info: true,
             inlined_fns: true,
+            inlined_fn_addrs: false,
+            inlined_fn_origin_addrs: false,
+            max_inlined_depth: DEFAULT_MAX_INLINED_DEPTH,
+            line_table_fallback: false,
+            #[cfg(feature = "dwarf")]
+            eh_frame_fallback: false,
+            plt_syms: false,
+            nearest_preceding: false,
+            raw_elf_syms: false,
+            comp_dirs: false,
+            sym_versions: false,
             demangle: true,
             #[cfg(feature = "apk")]
             apk_dispatch: None,
             process_dispatch: None,
+            sysroot: None,
+            #[cfg(feature = "dwarf")]
+            cache_dir: None,
+            #[cfg(feature = "dwarf")]
+            strict_dwarf: false,
+            #[cfg(feature = "pe")]
+            pdb_dirs: Vec::new(),
         }
     }
 }
@@ -361,6 +857,235 @@ enum Resolver<'tmp, 'slf> {
 }
 
 
+/// A resolver combining the ELF symbols of one file with the DWARF
+/// debug information of another, separate, one.
+#[cfg(feature = "dwarf")]
+#[derive(Debug)]
+struct SplitDebugResolver<'res> {
+    /// The resolver for the "primary" ELF file, i.e., the one
+    /// describing the address layout.
+    elf: &'res ElfResolver,
+    /// The resolver for the file containing the DWARF debug
+    /// information.
+    dwarf: &'res DwarfResolver,
+}
+
+#[cfg(feature = "dwarf")]
+impl Symbolize for SplitDebugResolver<'_> {
+    fn find_sym(&self, addr: Addr, opts: &FindSymOpts) -> Result<Result<ResolvedSym<'_>, Reason>> {
+        if let Ok(sym) = self.dwarf.find_sym(addr, opts)? {
+            return Ok(Ok(sym))
+        }
+        self.elf.find_sym(addr, opts)
+    }
+}
+
+
+/// A handler for symbolizing addresses belonging to proc maps style
+/// entries.
+struct SymbolizeHandler<'sym> {
+    /// The "outer" `Symbolizer` instance.
+    symbolizer: &'sym Symbolizer,
+    /// The PID of the process in which we symbolize.
+    pid: Pid,
+    /// Whether or not to consult debug symbols to satisfy the request
+    /// (if present).
+    debug_syms: bool,
+    /// Whether or not to consult the process' perf map (if any) to
+    /// satisfy the request.
+    perf_map: bool,
+    /// Whether to work with `/proc/<pid>/map_files/` entries or with
+    /// symbolic paths mentioned in `/proc/<pid>/maps` instead.
+    map_files: bool,
+    /// The sysroot below which to look for files first, if any.
+    sysroot: Option<&'sym Path>,
+    /// Files that are known to be unusable for symbolization, along
+    /// with the reason why.
+    unavailable: HashMap<PathBuf, Reason>,
+    /// Symbols representing the symbolized addresses.
+    all_symbols: Vec<Symbolized<'sym>>,
+}
+
+impl SymbolizeHandler<'_> {
+    /// Determine the path of the file to use for the provided entry.
+    fn entry_path<'path>(&self, entry_path: &'path EntryPath) -> Cow<'path, Path> {
+        if let Some(path) = self
+            .sysroot
+            .and_then(|sysroot| sysroot_path(sysroot, &entry_path.symbolic_path))
+        {
+            return Cow::Owned(path)
+        }
+
+        if self.map_files {
+            Cow::Borrowed(&entry_path.maps_file)
+        } else {
+            Cow::Borrowed(&entry_path.symbolic_path)
+        }
+    }
+
+    /// Symbolize an address in a mapping backed by the archive at
+    /// `archive`, which may be a member of it (e.g.,
+    /// `base.apk!/lib/arm64-v8a/libfoo.so`).
+    #[cfg(feature = "apk")]
+    fn handle_apk_addr(
+        &mut self,
+        addr: Addr,
+        file_off: u64,
+        entry_path: &EntryPath,
+        archive: &Path,
+    ) -> Result<()> {
+        let apk_path = if let Some(path) = self
+            .sysroot
+            .and_then(|sysroot| sysroot_path(sysroot, archive))
+        {
+            Cow::Owned(path)
+        } else if self.map_files {
+            // The map file refers to the archive itself, not just the
+            // member.
+            Cow::Borrowed(entry_path.maps_file.as_path())
+        } else {
+            Cow::Borrowed(archive)
+        };
+
+        match self
+            .symbolizer
+            .apk_resolver(&apk_path, file_off, self.debug_syms)?
+        {
+            Ok((elf_resolver, elf_addr)) => {
+                let symbol = self.symbolizer.symbolize_with_resolver(
+                    elf_addr,
+                    &Resolver::Cached(elf_resolver.as_symbolize()),
+                )?;
+                let () = self.all_symbols.push(symbol);
+                Ok(())
+            }
+            Err(reason) => self.handle_unknown_addr(addr, reason),
+        }
+    }
+
+    fn handle_elf_addr(&mut self, addr: Addr, file_off: u64, entry_path: &EntryPath) -> Result<()> {
+        let path = self.entry_path(entry_path);
+        let resolver = self.symbolizer.elf_resolver(&path, self.debug_syms)?;
+
+        match resolver.file_offset_to_virt_offset(file_off)? {
+            Some(addr) => {
+                let symbol = self
+                    .symbolizer
+                    .symbolize_with_resolver(addr, &Resolver::Cached(resolver.deref()))?;
+                let () = self.all_symbols.push(symbol);
+                Ok(())
+            }
+            None => self.handle_unknown_addr(addr, Reason::InvalidFileOffset),
+        }
+    }
+
+    #[cfg(feature = "pe")]
+    fn handle_pe_addr(&mut self, addr: Addr, file_off: u64, path: &Path) -> Result<()> {
+        let resolver = self.symbolizer.pe_resolver(path)?;
+
+        match resolver.file_offset_to_virt_offset(file_off)? {
+            Some(addr) => {
+                let symbol = self
+                    .symbolizer
+                    .symbolize_with_resolver(addr, &Resolver::Cached(resolver))?;
+                let () = self.all_symbols.push(symbol);
+                Ok(())
+            }
+            None => self.handle_unknown_addr(addr, Reason::InvalidFileOffset),
+        }
+    }
+
+    fn handle_perf_map_addr(&mut self, addr: Addr) -> Result<()> {
+        if let Some(perf_map) = self.symbolizer.perf_map(self.pid)? {
+            let symbolized = self
+                .symbolizer
+                .symbolize_with_resolver(addr, &Resolver::Cached(perf_map))?;
+            let () = self.all_symbols.push(symbolized);
+            Ok(())
+        } else {
+            self.handle_unknown_addr(addr, Reason::UnknownAddr)
+        }
+    }
+}
+
+impl normalize::Handler<Reason> for SymbolizeHandler<'_> {
+    #[cfg_attr(feature = "tracing", crate::log::instrument(skip_all, fields(addr = format_args!("{_addr:#x}"))))]
+    fn handle_unknown_addr(&mut self, _addr: Addr, reason: Reason) -> Result<()> {
+        let () = self.all_symbols.push(Symbolized::Unknown(reason));
+        Ok(())
+    }
+
+    fn handle_entry_addr(&mut self, addr: Addr, entry: &MapsEntry) -> Result<()> {
+        if let Some(path_name) = &entry.path_name {
+            if let Some(resolver) = self
+                .symbolizer
+                .process_dispatch_resolver(entry.range.clone(), path_name)?
+            {
+                let file_off = addr - entry.range.start + entry.offset;
+                let result = match resolver.file_offset_to_virt_offset(file_off)? {
+                    Some(addr) => {
+                        let symbol = self.symbolizer.symbolize_with_resolver(
+                            addr,
+                            &Resolver::Cached(resolver.as_symbolize()),
+                        )?;
+                        let () = self.all_symbols.push(symbol);
+                        Ok(())
+                    }
+                    None => self.handle_unknown_addr(addr, Reason::InvalidFileOffset),
+                };
+                return result
+            }
+
+            // If there is no process dispatcher installed or it did
+            // not return a resolver for the entry, we use our
+            // default handling scheme.
+        }
+
+        match &entry.path_name {
+            Some(PathName::Path(entry_path)) => {
+                if let Some(reason) = self.unavailable.get(&entry_path.maps_file) {
+                    return self.handle_unknown_addr(addr, reason.clone())
+                }
+
+                if entry_path.is_art_boot_image() {
+                    return self.handle_unknown_addr(addr, Reason::ArtBootImage)
+                }
+
+                let file_off = addr - entry.range.start + entry.offset;
+                match entry_path.archive_path() {
+                    #[cfg(feature = "apk")]
+                    Some(archive) => self.handle_apk_addr(addr, file_off, entry_path, archive),
+                    _ => {
+                        let result = self.handle_elf_addr(addr, file_off, entry_path);
+                        // Wine & Proton processes map PE files, which we
+                        // tell apart from ELF ones by their magic. To keep
+                        // the common case fast we only check once the file
+                        // failed to parse as ELF.
+                        #[cfg(feature = "pe")]
+                        if result.is_err() {
+                            let path = self.entry_path(entry_path);
+                            if is_pe_file(&path) {
+                                return self.handle_pe_addr(addr, file_off, &path)
+                            }
+                        }
+                        result
+                    }
+                }
+            }
+            Some(PathName::Component(..)) => self.handle_unknown_addr(addr, Reason::Unsupported),
+            // If there is no path associated with this entry, we don't
+            // really have any idea what the address may belong to. But
+            // there is a chance that the address is part of the perf
+            // map, so check that.
+            // TODO: It's not entirely clear if a perf map could also
+            //       cover addresses belonging to entries with a path.
+            None if self.perf_map => self.handle_perf_map_addr(addr),
+            None => self.handle_unknown_addr(addr, Reason::UnknownAddr),
+        }
+    }
+}
+
+
 /// Symbolizer provides an interface to symbolize addresses.
 ///
 /// An instance of this type is the unit at which symbolization inputs are
@@ -382,17 +1107,32 @@ pub struct Symbolizer {
     apk_cache: FileCache<(zip::Archive, InsertMap<Range<u64>, Box<dyn Resolve>>)>,
     #[cfg(feature = "breakpad")]
     breakpad_cache: FileCache<BreakpadResolver>,
+    #[cfg(feature = "dwarf")]
+    dwarf_cache: FileCache<DwarfResolver>,
     elf_cache: FileCache<ElfResolverData>,
     #[cfg(feature = "gsym")]
     gsym_cache: FileCache<GsymResolver<'static>>,
     ksym_cache: FileCache<Rc<KSymResolver>>,
+    #[cfg(feature = "macho")]
+    macho_cache: FileCache<MachOResolverData>,
+    #[cfg(feature = "pe")]
+    pe_cache: FileCache<PeResolver>,
     perf_map_cache: FileCache<PerfMap>,
     process_cache: InsertMap<PathName, Option<Box<dyn Resolve>>>,
+    symbol_map_cache: FileCache<SymbolMapResolver>,
     find_sym_opts: FindSymOpts,
+    max_inlined_depth: usize,
     demangle: bool,
     #[cfg(feature = "apk")]
     apk_dispatch: Option<Dbg<Box<dyn ApkDispatch>>>,
     process_dispatch: Option<Dbg<Box<dyn ProcessDispatch>>>,
+    sysroot: Option<PathBuf>,
+    #[cfg(feature = "dwarf")]
+    cache_dir: Option<PathBuf>,
+    #[cfg(feature = "dwarf")]
+    strict_dwarf: bool,
+    #[cfg(feature = "pe")]
+    pdb_dirs: Vec<PathBuf>,
 }
 
 impl Symbolizer {
@@ -407,6 +1147,44 @@ impl Symbolizer {
         Builder::default()
     }
 
+    /// Retrieve statistics about the usage of the caches maintained
+    /// internally, accumulated over all file based symbolization
+    /// sources.
+    ///
+    /// These statistics can help with judging the effectiveness of
+    /// caching, e.g., to decide when to create a new `Symbolizer`
+    /// instance.
+    pub fn cache_stats(&self) -> CacheStats {
+        let mut stats = CacheStats::default();
+        #[cfg(feature = "apk")]
+        let () = stats.add(&self.apk_cache.stats());
+        #[cfg(feature = "breakpad")]
+        let () = stats.add(&self.breakpad_cache.stats());
+        #[cfg(feature = "dwarf")]
+        let () = stats.add(&self.dwarf_cache.stats());
+        let () = stats.add(&self.elf_cache.stats());
+        #[cfg(feature = "gsym")]
+        let () = stats.add(&self.gsym_cache.stats());
+        let () = stats.add(&self.ksym_cache.stats());
+        #[cfg(feature = "macho")]
+        let () = stats.add(&self.macho_cache.stats());
+        #[cfg(feature = "pe")]
+        let () = stats.add(&self.pe_cache.stats());
+        let () = stats.add(&self.perf_map_cache.stats());
+        let () = stats.add(&self.symbol_map_cache.stats());
+        stats
+    }
+
+    /// Map `path` into the configured sysroot, if any and if the file
+    /// exists below it.
+    fn sysroot_path<'path>(&self, path: &'path Path) -> Cow<'path, Path> {
+        self.sysroot
+            .as_deref()
+            .and_then(|sysroot| sysroot_path(sysroot, path))
+            .map(Cow::Owned)
+            .unwrap_or(Cow::Borrowed(path))
+    }
+
     /// Demangle the provided symbol if asked for and possible.
     fn maybe_demangle<'sym>(&self, symbol: Cow<'sym, str>, language: SrcLang) -> Cow<'sym, str> {
         if self.demangle {
@@ -423,7 +1201,7 @@ impl Symbolizer {
         addr: Addr,
         resolver: &Resolver<'_, 'slf>,
     ) -> Result<Symbolized<'slf>> {
-        let (sym_name, sym_addr, sym_size, code_info, inlined) = match resolver {
+        let mut sym = match resolver {
             Resolver::Uncached(resolver) => match resolver.find_sym(addr, &self.find_sym_opts)? {
                 Ok(sym) => {
                     let ResolvedSym {
@@ -433,10 +1211,18 @@ impl Symbolizer {
                         lang,
                         code_info,
                         inlined,
+                        provenance,
+                        is_synthetic,
+                        raw_elf_sym,
+                        compilation_unit,
+                        version,
+                        section,
+                        linkage_name,
                     } = sym;
 
-                    let name =
-                        Cow::Owned(self.maybe_demangle(Cow::Borrowed(name), lang).into_owned());
+                    let linkage_name = linkage_name.or_else(|| is_mangled(name).then_some(name));
+                    let name = self.maybe_demangle(Cow::Borrowed(name), lang);
+                    let name = Cow::Owned(append_version(name, version).into_owned());
                     let code_info = code_info.map(|info| info.to_owned());
                     let inlined = Vec::from(inlined)
                         .into_iter()
@@ -444,18 +1230,38 @@ impl Symbolizer {
                             let InlinedFn {
                                 name,
                                 code_info,
+                                addr,
+                                offset,
+                                origin_addr,
                                 _non_exhaustive: (),
                             } = inlined_fn;
                             InlinedFn {
                                 name: Cow::Owned(self.maybe_demangle(name, lang).into_owned()),
                                 code_info: code_info.map(|info| info.to_owned()),
+                                addr,
+                                offset,
+                                origin_addr,
                                 _non_exhaustive: (),
                             }
                         })
                         .collect::<Vec<_>>()
                         .into_boxed_slice();
 
-                    (name, addr, size, code_info, inlined)
+                    Sym {
+                        name,
+                        addr,
+                        offset: 0,
+                        size,
+                        code_info,
+                        inlined,
+                        provenance,
+                        is_synthetic,
+                        raw_elf_sym,
+                        compilation_unit,
+                        section: section.map(str::to_string),
+                        linkage_name: linkage_name.map(|name| Cow::Owned(name.to_string())),
+                        _non_exhaustive: (),
+                    }
                 }
                 Err(reason) => return Ok(Symbolized::Unknown(reason)),
             },
@@ -468,28 +1274,54 @@ impl Symbolizer {
                         lang,
                         code_info,
                         mut inlined,
+                        provenance,
+                        is_synthetic,
+                        raw_elf_sym,
+                        compilation_unit,
+                        version,
+                        section,
+                        linkage_name,
                     } = sym;
 
+                    let linkage_name = linkage_name.or_else(|| is_mangled(name).then_some(name));
                     let name = self.maybe_demangle(Cow::Borrowed(name), lang);
+                    let name = append_version(name, version);
                     let () = inlined.iter_mut().for_each(|inlined_fn| {
                         let name = take(&mut inlined_fn.name);
                         inlined_fn.name = self.maybe_demangle(name, lang);
                     });
-                    (name, addr, size, code_info, inlined)
+
+                    Sym {
+                        name,
+                        addr,
+                        offset: 0,
+                        size,
+                        code_info,
+                        inlined,
+                        provenance,
+                        is_synthetic,
+                        raw_elf_sym,
+                        compilation_unit,
+                        section: section.map(str::to_string),
+                        linkage_name: linkage_name.map(Cow::Borrowed),
+                        _non_exhaustive: (),
+                    }
                 }
                 Err(reason) => return Ok(Symbolized::Unknown(reason)),
             },
         };
 
-        let sym = Sym {
-            name: sym_name,
-            addr: sym_addr,
-            offset: (addr - sym_addr) as usize,
-            size: sym_size,
-            code_info,
-            inlined,
-            _non_exhaustive: (),
-        };
+        // All resolvers are expected to report symbols starting at or
+        // before the address in question, so that `addr + offset`
+        // always round trips to the input address.
+        debug_assert!(
+            sym.addr <= addr,
+            "symbol `{}` at {:#x} starts after address {addr:#x}",
+            sym.name,
+            sym.addr
+        );
+        sym.offset = addr.saturating_sub(sym.addr) as usize;
+        sym.inlined = truncate_inlined(sym.inlined, self.max_inlined_depth);
         Ok(Symbolized::Sym(sym))
     }
 
@@ -518,6 +1350,73 @@ impl Symbolizer {
         Ok(resolver)
     }
 
+    /// Retrieve the bounds of the data of `apk_entry` inside the
+    /// archive.
+    ///
+    /// The bounds reflect the member's actual extent in the archive,
+    /// i.e., its compressed size if it is compressed.
+    #[cfg(feature = "apk")]
+    fn apk_entry_bounds(apk_entry: &zip::Entry<'_>) -> Range<u64> {
+        apk_entry.data_offset..apk_entry.data_offset + apk_entry.data.len() as u64
+    }
+
+    /// Retrieve the resolver for the member `apk_entry` of `apk`,
+    /// creating it if necessary.
+    #[cfg(feature = "apk")]
+    fn apk_member_resolver<'slf>(
+        &'slf self,
+        apk: &zip::Archive,
+        apk_path: &Path,
+        apk_entry: &zip::Entry<'_>,
+        debug_syms: bool,
+        resolver_map: &'slf InsertMap<Range<u64>, Box<dyn Resolve>>,
+    ) -> Result<&'slf dyn Resolve> {
+        let bounds = Self::apk_entry_bounds(apk_entry);
+        let resolver = resolver_map.get_or_try_insert(bounds.clone(), || {
+            let mmap = if apk_entry.is_compressed() {
+                // Compressed members can't be memory mapped
+                // directly. Decompress them into an anonymous
+                // mapping instead.
+                let data = apk_entry.uncompressed_data().with_context(|| {
+                    format!(
+                        "failed to decompress APK entry {} in {}",
+                        apk_entry.path.display(),
+                        apk_path.display()
+                    )
+                })?;
+                Mmap::builder().map_data(&data)?
+            } else {
+                apk.mmap()
+                    .constrain(bounds.clone())
+                    .ok_or_invalid_input(|| {
+                        format!(
+                            "invalid APK entry data bounds ({bounds:?}) in {}",
+                            apk_path.display()
+                        )
+                    })?
+            };
+            let info = ApkMemberInfo {
+                apk_path,
+                member_path: apk_entry.path,
+                member_mmap: mmap,
+                _non_exhaustive: (),
+            };
+
+            let resolver = if let Some(Dbg(apk_dispatch)) = &self.apk_dispatch {
+                if let Some(resolver) = (apk_dispatch)(info.clone())? {
+                    resolver
+                } else {
+                    default_apk_dispatcher(info, debug_syms)?
+                }
+            } else {
+                default_apk_dispatcher(info, debug_syms)?
+            };
+
+            Ok(resolver)
+        })?;
+        Ok(resolver.deref())
+    }
+
     #[cfg(feature = "apk")]
     fn create_apk_resolver<'slf>(
         &'slf self,
@@ -526,52 +1425,30 @@ impl Symbolizer {
         file_off: u64,
         debug_syms: bool,
         resolver_map: &'slf InsertMap<Range<u64>, Box<dyn Resolve>>,
-    ) -> Result<Option<(&'slf dyn Resolve, Addr)>> {
+    ) -> Result<Result<(&'slf dyn Resolve, Addr), Reason>> {
         // Find the APK entry covering the calculated file offset.
         for apk_entry in apk.entries() {
             let apk_entry = apk_entry?;
-            let bounds = apk_entry.data_offset..apk_entry.data_offset + apk_entry.data.len() as u64;
-
-            if bounds.contains(&file_off) {
-                let resolver = resolver_map.get_or_try_insert(bounds.clone(), || {
-                    let mmap = apk
-                        .mmap()
-                        .constrain(bounds.clone())
-                        .ok_or_invalid_input(|| {
-                            format!(
-                                "invalid APK entry data bounds ({bounds:?}) in {}",
-                                apk_path.display()
-                            )
-                        })?;
-                    let info = ApkMemberInfo {
-                        apk_path,
-                        member_path: apk_entry.path,
-                        member_mmap: mmap,
-                        _non_exhaustive: (),
-                    };
-
-                    let resolver = if let Some(Dbg(apk_dispatch)) = &self.apk_dispatch {
-                        if let Some(resolver) = (apk_dispatch)(info.clone())? {
-                            resolver
-                        } else {
-                            default_apk_dispatcher(info, debug_syms)?
-                        }
-                    } else {
-                        default_apk_dispatcher(info, debug_syms)?
-                    };
-
-                    Ok(resolver)
-                })?;
+            if Self::apk_entry_bounds(&apk_entry).contains(&file_off) {
+                // An offset into the compressed data of a member has no
+                // meaningful relation to an offset into the ELF file
+                // it contains. Such members can only be symbolized
+                // through a `Zip` source.
+                if apk_entry.is_compressed() {
+                    return Ok(Err(Reason::Unsupported))
+                }
 
+                let resolver =
+                    self.apk_member_resolver(apk, apk_path, &apk_entry, debug_syms, resolver_map)?;
                 let elf_off = file_off - apk_entry.data_offset;
                 if let Some(addr) = resolver.file_offset_to_virt_offset(elf_off)? {
-                    return Ok(Some((resolver.deref(), addr)))
+                    return Ok(Ok((resolver, addr)))
                 }
                 break
             }
         }
 
-        Ok(None)
+        Ok(Err(Reason::InvalidFileOffset))
     }
 
     #[cfg(feature = "apk")]
@@ -580,7 +1457,7 @@ impl Symbolizer {
         path: &Path,
         file_off: u64,
         debug_syms: bool,
-    ) -> Result<Option<(&'slf dyn Resolve, Addr)>> {
+    ) -> Result<Result<(&'slf dyn Resolve, Addr), Reason>> {
         let (file, cell) = self.apk_cache.entry(path)?;
         let (apk, resolvers) = cell.get_or_try_init(|| {
             let apk = zip::Archive::with_mmap(Mmap::builder().map(file)?)?;
@@ -592,17 +1469,113 @@ impl Symbolizer {
         result
     }
 
-    #[cfg(feature = "breakpad")]
-    fn create_breakpad_resolver(&self, path: &Path, file: &File) -> Result<BreakpadResolver> {
-        let resolver = BreakpadResolver::from_file(path.to_path_buf(), file)?;
-        Ok(resolver)
-    }
-
-    #[cfg(feature = "breakpad")]
-    fn breakpad_resolver<'slf>(&'slf self, path: &Path) -> Result<&'slf BreakpadResolver> {
-        let (file, cell) = self.breakpad_cache.entry(path)?;
-        let resolver = cell.get_or_try_init(|| self.create_breakpad_resolver(path, file))?;
-        Ok(resolver)
+    /// Retrieve the resolver for the ELF member `member` of the zip
+    /// archive at `path`.
+    #[cfg(feature = "apk")]
+    fn zip_member_resolver<'slf>(
+        &'slf self,
+        path: &Path,
+        member: &str,
+        debug_syms: bool,
+    ) -> Result<&'slf dyn Resolve> {
+        let (file, cell) = self.apk_cache.entry(path)?;
+        let (apk, resolvers) = cell.get_or_try_init(|| {
+            let apk = zip::Archive::with_mmap(Mmap::builder().map(file)?)?;
+            let resolvers = InsertMap::new();
+            Result::<_, Error>::Ok((apk, resolvers))
+        })?;
+
+        for apk_entry in apk.entries() {
+            let apk_entry = apk_entry?;
+            if apk_entry.path == Path::new(member) {
+                return self.apk_member_resolver(apk, path, &apk_entry, debug_syms, resolvers)
+            }
+        }
+
+        Err(Error::with_not_found(format!(
+            "member `{member}` not found in `{}`",
+            path.display()
+        )))
+    }
+
+    #[cfg(feature = "breakpad")]
+    fn create_breakpad_resolver(&self, path: &Path, file: &File) -> Result<BreakpadResolver> {
+        let resolver = BreakpadResolver::from_file(path.to_path_buf(), file)?;
+        Ok(resolver)
+    }
+
+    #[cfg(feature = "breakpad")]
+    fn breakpad_resolver<'slf>(&'slf self, path: &Path) -> Result<&'slf BreakpadResolver> {
+        let (file, cell) = self.breakpad_cache.entry(path)?;
+        let resolver = cell.get_or_try_init(|| self.create_breakpad_resolver(path, file))?;
+        Ok(resolver)
+    }
+
+    #[cfg(feature = "dwarf")]
+    fn dwarf_resolver<'slf>(&'slf self, path: &Path) -> Result<&'slf DwarfResolver> {
+        let (file, cell) = self.dwarf_cache.entry(path)?;
+        let resolver = cell.get_or_try_init(|| {
+            let parser = ElfParser::open_file(file, path)?;
+            DwarfResolver::from_debug_file(
+                Rc::new(parser),
+                self.cache_dir.as_deref(),
+                self.strict_dwarf,
+            )
+        })?;
+        Ok(resolver)
+    }
+
+    fn elf_resolver<'slf>(
+        &'slf self,
+        path: &Path,
+        debug_syms: bool,
+    ) -> Result<&'slf Rc<ElfResolver>> {
+        #[cfg(feature = "dwarf")]
+        let (cache_dir, strict_dwarf) = (self.cache_dir.as_deref(), self.strict_dwarf);
+        #[cfg(not(feature = "dwarf"))]
+        let (cache_dir, strict_dwarf) = (None, false);
+        self.elf_cache
+            .elf_resolver_with_cache(path, debug_syms, cache_dir, strict_dwarf)
+    }
+
+    /// Retrieve the ELF resolver for an [`Elf`] source, accessing the
+    /// file via `fd`, if provided, and via `path` otherwise.
+    fn elf_src_resolver<'slf>(
+        &'slf self,
+        path: &Path,
+        fd: Option<&OwnedFd>,
+        debug_syms: bool,
+    ) -> Result<&'slf Rc<ElfResolver>> {
+        if let Some(fd) = fd {
+            #[cfg(feature = "dwarf")]
+            let (cache_dir, strict_dwarf) = (self.cache_dir.as_deref(), self.strict_dwarf);
+            #[cfg(not(feature = "dwarf"))]
+            let (cache_dir, strict_dwarf) = (None, false);
+            self.elf_cache
+                .elf_resolver_for_fd(fd.as_fd(), path, debug_syms, cache_dir, strict_dwarf)
+                .with_context(|| format!("failed to symbolize {}", path.display()))
+        } else {
+            let path = self.sysroot_path(path);
+            self.elf_resolver(&path, debug_syms)
+        }
+    }
+
+    #[cfg(feature = "macho")]
+    fn macho_resolver<'slf>(
+        &'slf self,
+        path: &Path,
+        arch: Option<&str>,
+        dsym: Option<&Path>,
+    ) -> Result<&'slf MachOResolver> {
+        self.macho_cache.macho_resolver(path, arch, dsym)
+    }
+
+    #[cfg(feature = "pe")]
+    fn pe_resolver<'slf>(&'slf self, path: &Path) -> Result<&'slf PeResolver> {
+        let (file, cell) = self.pe_cache.entry(path)?;
+        let resolver =
+            cell.get_or_try_init(|| PeResolver::from_file(path, file, &self.pdb_dirs))?;
+        Ok(resolver)
     }
 
     fn create_perf_map(&self, path: &Path, file: &File) -> Result<PerfMap> {
@@ -610,6 +1583,12 @@ impl Symbolizer {
         Ok(perf_map)
     }
 
+    fn symbol_map_resolver<'slf>(&'slf self, path: &Path) -> Result<&'slf SymbolMapResolver> {
+        let (file, cell) = self.symbol_map_cache.entry(path)?;
+        let resolver = cell.get_or_try_init(|| SymbolMapResolver::from_file(path, file))?;
+        Ok(resolver)
+    }
+
     fn perf_map(&self, pid: Pid) -> Result<Option<&PerfMap>> {
         let path = PerfMap::path(pid);
 
@@ -655,169 +1634,105 @@ impl Symbolizer {
         perf_map: bool,
         map_files: bool,
     ) -> Result<Vec<Symbolized>> {
-        struct SymbolizeHandler<'sym> {
-            /// The "outer" `Symbolizer` instance.
-            symbolizer: &'sym Symbolizer,
-            /// The PID of the process in which we symbolize.
-            pid: Pid,
-            /// Whether or not to consult debug symbols to satisfy the request
-            /// (if present).
-            debug_syms: bool,
-            /// Whether or not to consult the process' perf map (if any) to
-            /// satisfy the request.
-            perf_map: bool,
-            /// Whether to work with `/proc/<pid>/map_files/` entries or with
-            /// symbolic paths mentioned in `/proc/<pid>/maps` instead.
-            map_files: bool,
-            /// Symbols representing the symbolized addresses.
-            all_symbols: Vec<Symbolized<'sym>>,
-        }
-
-        impl SymbolizeHandler<'_> {
-            #[cfg(feature = "apk")]
-            fn handle_apk_addr(
-                &mut self,
-                addr: Addr,
-                file_off: u64,
-                entry_path: &EntryPath,
-            ) -> Result<()> {
-                let apk_path = if self.map_files {
-                    &entry_path.maps_file
-                } else {
-                    &entry_path.symbolic_path
-                };
-
-                match self
-                    .symbolizer
-                    .apk_resolver(apk_path, file_off, self.debug_syms)?
-                {
-                    Some((elf_resolver, elf_addr)) => {
-                        let symbol = self.symbolizer.symbolize_with_resolver(
-                            elf_addr,
-                            &Resolver::Cached(elf_resolver.as_symbolize()),
-                        )?;
-                        let () = self.all_symbols.push(symbol);
-                        Ok(())
-                    }
-                    None => self.handle_unknown_addr(addr, Reason::InvalidFileOffset),
-                }
-            }
-
-            fn handle_elf_addr(
-                &mut self,
-                addr: Addr,
-                file_off: u64,
-                entry_path: &EntryPath,
-            ) -> Result<()> {
-                let path = if self.map_files {
-                    &entry_path.maps_file
-                } else {
-                    &entry_path.symbolic_path
-                };
-
-                let resolver = self
-                    .symbolizer
-                    .elf_cache
-                    .elf_resolver(path, self.debug_syms)?;
-
-                match resolver.file_offset_to_virt_offset(file_off)? {
-                    Some(addr) => {
-                        let symbol = self
-                            .symbolizer
-                            .symbolize_with_resolver(addr, &Resolver::Cached(resolver.deref()))?;
-                        let () = self.all_symbols.push(symbol);
-                        Ok(())
-                    }
-                    None => self.handle_unknown_addr(addr, Reason::InvalidFileOffset),
-                }
-            }
-
-            fn handle_perf_map_addr(&mut self, addr: Addr) -> Result<()> {
-                if let Some(perf_map) = self.symbolizer.perf_map(self.pid)? {
-                    let symbolized = self
-                        .symbolizer
-                        .symbolize_with_resolver(addr, &Resolver::Cached(perf_map))?;
-                    let () = self.all_symbols.push(symbolized);
-                    Ok(())
-                } else {
-                    self.handle_unknown_addr(addr, Reason::UnknownAddr)
-                }
-            }
-        }
-
-        impl normalize::Handler<Reason> for SymbolizeHandler<'_> {
-            #[cfg_attr(feature = "tracing", crate::log::instrument(skip_all, fields(addr = format_args!("{_addr:#x}"))))]
-            fn handle_unknown_addr(&mut self, _addr: Addr, reason: Reason) -> Result<()> {
-                let () = self.all_symbols.push(Symbolized::Unknown(reason));
-                Ok(())
-            }
+        let entries = maps::parse(pid)?;
+        let handler = SymbolizeHandler {
+            symbolizer: self,
+            pid,
+            debug_syms,
+            perf_map,
+            map_files,
+            sysroot: self.sysroot.as_deref(),
+            unavailable: HashMap::new(),
+            all_symbols: Vec::with_capacity(addrs.len()),
+        };
+        self.symbolize_entry_addrs(addrs, entries, handler)
+    }
 
-            fn handle_entry_addr(&mut self, addr: Addr, entry: &MapsEntry) -> Result<()> {
-                if let Some(path_name) = &entry.path_name {
-                    if let Some(resolver) = self
-                        .symbolizer
-                        .process_dispatch_resolver(entry.range.clone(), path_name)?
-                    {
-                        let file_off = addr - entry.range.start + entry.offset;
-                        let result = match resolver.file_offset_to_virt_offset(file_off)? {
-                            Some(addr) => {
-                                let symbol = self.symbolizer.symbolize_with_resolver(
-                                    addr,
-                                    &Resolver::Cached(resolver.as_symbolize()),
-                                )?;
-                                let () = self.all_symbols.push(symbol);
-                                Ok(())
+    /// Symbolize the given list of user space addresses in the process
+    /// captured by the provided core dump.
+    fn symbolize_core_addrs(
+        &self,
+        addrs: &[Addr],
+        path: &Path,
+        debug_syms: bool,
+        sysroot: Option<&Path>,
+    ) -> Result<Vec<Symbolized<'_>>> {
+        let sysroot = sysroot.or(self.sysroot.as_deref());
+        let parser = ElfParser::open(path)?;
+        let mappings = coredump::parse(&parser)?;
+        let mut unavailable = HashMap::new();
+        let mut entries = Vec::with_capacity(mappings.len());
+
+        for coredump::CoreMapping {
+            mut entry,
+            build_id,
+        } in mappings
+        {
+            if let Some(PathName::Path(entry_path)) = &mut entry.path_name {
+                // Members of archives are backed by the archive itself.
+                let archive = entry_path.archive_path();
+                let path = archive.unwrap_or(&entry_path.symbolic_path);
+                let file = sysroot
+                    .and_then(|sysroot| sysroot_path(sysroot, path))
+                    .unwrap_or_else(|| path.to_path_buf());
+
+                if !unavailable.contains_key(&file) {
+                    let is_elf = archive.is_none();
+                    if !file.exists() {
+                        let _prev = unavailable.insert(file.clone(), Reason::MissingFile);
+                    } else if let (Some(build_id), true) = (build_id, is_elf) {
+                        // Make sure that the file we found is the one
+                        // that was mapped at the time the core dump was
+                        // created.
+                        match read_elf_build_id(&file) {
+                            Ok(id) if id.as_deref() == Some(build_id.as_slice()) => (),
+                            Ok(_) => {
+                                let _prev =
+                                    unavailable.insert(file.clone(), Reason::BuildIdMismatch);
                             }
-                            None => self.handle_unknown_addr(addr, Reason::InvalidFileOffset),
-                        };
-                        return result
-                    }
-
-                    // If there is no process dispatcher installed or it did
-                    // not return a resolver for the entry, we use our
-                    // default handling scheme.
-                }
-
-                match &entry.path_name {
-                    Some(PathName::Path(entry_path)) => {
-                        let file_off = addr - entry.range.start + entry.offset;
-                        let ext = entry_path
-                            .symbolic_path
-                            .extension()
-                            .unwrap_or_else(|| OsStr::new(""));
-                        match ext.to_str() {
-                            #[cfg(feature = "apk")]
-                            Some("apk") | Some("zip") => {
-                                self.handle_apk_addr(addr, file_off, entry_path)
+                            // A file we can't read the build ID from
+                            // should not prevent symbolization of
+                            // addresses in other files.
+                            Err(err) => {
+                                log::debug!(
+                                    "failed to read build ID of `{}`: {err}",
+                                    file.display()
+                                );
+                                let _prev = unavailable.insert(file.clone(), Reason::Unsupported);
                             }
-                            _ => self.handle_elf_addr(addr, file_off, entry_path),
                         }
                     }
-                    Some(PathName::Component(..)) => {
-                        self.handle_unknown_addr(addr, Reason::Unsupported)
-                    }
-                    // If there is no path associated with this entry, we don't
-                    // really have any idea what the address may belong to. But
-                    // there is a chance that the address is part of the perf
-                    // map, so check that.
-                    // TODO: It's not entirely clear if a perf map could also
-                    //       cover addresses belonging to entries with a path.
-                    None if self.perf_map => self.handle_perf_map_addr(addr),
-                    None => self.handle_unknown_addr(addr, Reason::UnknownAddr),
                 }
+                entry_path.maps_file = file;
             }
+            let () = entries.push(Ok(entry));
         }
 
-        let entries = maps::parse(pid)?;
-        let mut handler = SymbolizeHandler {
+        let handler = SymbolizeHandler {
             symbolizer: self,
-            pid,
+            pid: Pid::Slf,
             debug_syms,
-            perf_map,
-            map_files,
+            perf_map: false,
+            map_files: true,
+            // Paths have already been mapped into the sysroot above.
+            sysroot: None,
+            unavailable,
             all_symbols: Vec::with_capacity(addrs.len()),
         };
+        self.symbolize_entry_addrs(addrs, entries.into_iter(), handler)
+    }
 
+    /// Symbolize the given list of addresses, using the provided
+    /// proc maps style entries and handler.
+    fn symbolize_entry_addrs<'slf, E>(
+        &'slf self,
+        addrs: &[Addr],
+        entries: E,
+        mut handler: SymbolizeHandler<'slf>,
+    ) -> Result<Vec<Symbolized<'slf>>>
+    where
+        E: Iterator<Item = Result<MapsEntry>>,
+    {
         let handler = util::with_ordered_elems(
             addrs,
             |handler: &mut SymbolizeHandler<'_>| handler.all_symbols.as_mut_slice(),
@@ -848,6 +1763,7 @@ impl Symbolizer {
             kallsyms,
             kernel_image,
             debug_syms,
+            kaslr_offset,
             _non_exhaustive: (),
         } = src;
 
@@ -859,6 +1775,19 @@ impl Symbolizer {
             let result = self.ksym_resolver(kallsyms);
             match result {
                 Ok(resolver) => Some(resolver),
+                // Without readable kallsyms we would not be able to
+                // infer the KASLR offset and could not make use of the
+                // explicitly provided kernel image at all. An
+                // auto-discovered image is simply left unused.
+                Err(err)
+                    if err.kind() == ErrorKind::PermissionDenied
+                        && kernel_image.is_some()
+                        && kaslr_offset.is_none() =>
+                {
+                    return Err(err).context(
+                        "failed to determine KASLR offset; consider providing it explicitly",
+                    )
+                }
                 Err(err) => {
                     log::warn!(
                         "failed to load kallsyms from {}: {err}; ignoring...",
@@ -870,7 +1799,7 @@ impl Symbolizer {
         };
 
         let elf_resolver = if let Some(image) = kernel_image {
-            let resolver = self.elf_cache.elf_resolver(image, *debug_syms)?;
+            let resolver = self.elf_resolver(image, *debug_syms)?;
             Some(resolver)
         } else {
             let release = uname_release()?.to_str().unwrap().to_string();
@@ -882,7 +1811,7 @@ impl Symbolizer {
             });
 
             if let Some(image) = kernel_image {
-                let result = self.elf_cache.elf_resolver(&image, *debug_syms);
+                let result = self.elf_resolver(&image, *debug_syms);
                 match result {
                     Ok(resolver) => Some(resolver),
                     Err(err) => {
@@ -898,7 +1827,7 @@ impl Symbolizer {
             }
         };
 
-        KernelResolver::new(ksym_resolver.cloned(), elf_resolver.cloned())
+        KernelResolver::new(ksym_resolver.cloned(), elf_resolver.cloned(), *kaslr_offset)
     }
 
     /// Symbolize a list of addresses.
@@ -943,30 +1872,33 @@ impl Symbolizer {
                 path,
                 debug_syms,
                 _non_exhaustive: (),
-            }) => match input {
-                Input::VirtOffset(..) => {
-                    return Err(Error::with_unsupported(
-                        "APK symbolization does not support virtual offset inputs",
-                    ))
-                }
-                Input::AbsAddr(..) => {
-                    return Err(Error::with_unsupported(
-                        "APK symbolization does not support absolute address inputs",
-                    ))
+            }) => {
+                let path = self.sysroot_path(path);
+                match input {
+                    Input::VirtOffset(..) => {
+                        return Err(Error::with_unsupported(
+                            "APK symbolization does not support virtual offset inputs",
+                        ))
+                    }
+                    Input::AbsAddr(..) => {
+                        return Err(Error::with_unsupported(
+                            "APK symbolization does not support absolute address inputs",
+                        ))
+                    }
+                    Input::FileOffset(offsets) => offsets
+                        .iter()
+                        .map(
+                            |offset| match self.apk_resolver(&path, *offset, *debug_syms)? {
+                                Ok((elf_resolver, elf_addr)) => self.symbolize_with_resolver(
+                                    elf_addr,
+                                    &Resolver::Cached(elf_resolver.as_symbolize()),
+                                ),
+                                Err(reason) => Ok(Symbolized::Unknown(reason)),
+                            },
+                        )
+                        .collect(),
                 }
-                Input::FileOffset(offsets) => offsets
-                    .iter()
-                    .map(
-                        |offset| match self.apk_resolver(path, *offset, *debug_syms)? {
-                            Some((elf_resolver, elf_addr)) => self.symbolize_with_resolver(
-                                elf_addr,
-                                &Resolver::Cached(elf_resolver.as_symbolize()),
-                            ),
-                            None => Ok(Symbolized::Unknown(Reason::InvalidFileOffset)),
-                        },
-                    )
-                    .collect(),
-            },
+            }
             #[cfg(feature = "breakpad")]
             Source::Breakpad(Breakpad {
                 path,
@@ -990,12 +1922,33 @@ impl Symbolizer {
                 let symbols = self.symbolize_addrs(addrs, &Resolver::Cached(resolver))?;
                 Ok(symbols)
             }
+            #[cfg(feature = "dwarf")]
+            Source::Dwarf(Dwarf {
+                path,
+                _non_exhaustive: (),
+            }) => {
+                let path = self.sysroot_path(path);
+                let resolver = self.dwarf_resolver(&path)?;
+                match input {
+                    Input::VirtOffset(addrs) => {
+                        self.symbolize_addrs(addrs, &Resolver::Cached(resolver))
+                    }
+                    Input::AbsAddr(..) => Err(Error::with_unsupported(
+                        "DWARF symbolization does not support absolute address inputs",
+                    )),
+                    Input::FileOffset(..) => Err(Error::with_unsupported(
+                        "DWARF symbolization does not support file offset inputs",
+                    )),
+                }
+            }
             Source::Elf(Elf {
                 path,
+                fd,
                 debug_syms,
+                base_addr,
                 _non_exhaustive: (),
             }) => {
-                let resolver = self.elf_cache.elf_resolver(path, *debug_syms)?;
+                let resolver = self.elf_src_resolver(path, fd.as_deref(), *debug_syms)?;
                 match input {
                     Input::VirtOffset(addrs) => addrs
                         .iter()
@@ -1003,11 +1956,23 @@ impl Symbolizer {
                             self.symbolize_with_resolver(*addr, &Resolver::Cached(resolver.deref()))
                         })
                         .collect(),
-                    Input::AbsAddr(..) => {
-                        return Err(Error::with_unsupported(
-                            "ELF symbolization does not support absolute address inputs",
-                        ))
-                    }
+                    Input::AbsAddr(addrs) => match base_addr {
+                        Some(base_addr) => addrs
+                            .iter()
+                            .map(|addr| match addr.checked_sub(*base_addr) {
+                                Some(addr) => self.symbolize_with_resolver(
+                                    addr,
+                                    &Resolver::Cached(resolver.deref()),
+                                ),
+                                None => Ok(Symbolized::Unknown(Reason::UnknownAddr)),
+                            })
+                            .collect(),
+                        None => {
+                            return Err(Error::with_unsupported(
+                                "ELF symbolization does not support absolute address inputs without a base address",
+                            ))
+                        }
+                    },
                     Input::FileOffset(offsets) => offsets
                         .iter()
                         .map(
@@ -1064,6 +2029,28 @@ impl Symbolizer {
 
                 self.symbolize_user_addrs(addrs, *pid, *debug_syms, *perf_map, *map_files)
             }
+            Source::CoreDump(CoreDump {
+                path,
+                debug_syms,
+                sysroot,
+                _non_exhaustive: (),
+            }) => {
+                let addrs = match input {
+                    Input::AbsAddr(addrs) => addrs,
+                    Input::VirtOffset(..) => {
+                        return Err(Error::with_unsupported(
+                            "core dump symbolization does not support virtual offset inputs",
+                        ))
+                    }
+                    Input::FileOffset(..) => {
+                        return Err(Error::with_unsupported(
+                            "core dump symbolization does not support file offset inputs",
+                        ))
+                    }
+                };
+
+                self.symbolize_core_addrs(addrs, path, *debug_syms, sysroot.as_deref())
+            }
             #[cfg(feature = "gsym")]
             Source::Gsym(Gsym::Data(GsymData {
                 data,
@@ -1106,12 +2093,360 @@ impl Symbolizer {
                     }
                 };
 
-                let resolver = self.gsym_resolver(path)?;
-                let symbols = self.symbolize_addrs(addrs, &Resolver::Cached(resolver))?;
-                Ok(symbols)
+                let resolver = self.gsym_resolver(path)?;
+                let symbols = self.symbolize_addrs(addrs, &Resolver::Cached(resolver))?;
+                Ok(symbols)
+            }
+            #[cfg(feature = "macho")]
+            Source::MachO(MachO {
+                path,
+                arch,
+                dsym,
+                _non_exhaustive: (),
+            }) => {
+                let resolver = self.macho_resolver(path, arch.as_deref(), dsym.as_deref())?;
+                match input {
+                    Input::VirtOffset(addrs) => {
+                        self.symbolize_addrs(addrs, &Resolver::Cached(resolver))
+                    }
+                    Input::AbsAddr(..) => Err(Error::with_unsupported(
+                        "Mach-O symbolization does not support absolute address inputs",
+                    )),
+                    Input::FileOffset(offsets) => offsets
+                        .iter()
+                        .map(
+                            |offset| match resolver.file_offset_to_virt_offset(*offset)? {
+                                Some(addr) => {
+                                    self.symbolize_with_resolver(addr, &Resolver::Cached(resolver))
+                                }
+                                None => Ok(Symbolized::Unknown(Reason::InvalidFileOffset)),
+                            },
+                        )
+                        .collect(),
+                }
+            }
+            #[cfg(feature = "pe")]
+            Source::Pe(Pe {
+                path,
+                _non_exhaustive: (),
+            }) => {
+                let resolver = self.pe_resolver(path)?;
+                match input {
+                    Input::VirtOffset(addrs) => {
+                        self.symbolize_addrs(addrs, &Resolver::Cached(resolver))
+                    }
+                    Input::AbsAddr(..) => Err(Error::with_unsupported(
+                        "PE symbolization does not support absolute address inputs",
+                    )),
+                    Input::FileOffset(offsets) => offsets
+                        .iter()
+                        .map(
+                            |offset| match resolver.file_offset_to_virt_offset(*offset)? {
+                                Some(addr) => {
+                                    self.symbolize_with_resolver(addr, &Resolver::Cached(resolver))
+                                }
+                                None => Ok(Symbolized::Unknown(Reason::InvalidFileOffset)),
+                            },
+                        )
+                        .collect(),
+                }
+            }
+            Source::SymbolMap(SymbolMap {
+                path,
+                _non_exhaustive: (),
+            }) => {
+                let addrs = match input {
+                    Input::VirtOffset(addrs) => addrs,
+                    Input::AbsAddr(..) => {
+                        return Err(Error::with_unsupported(
+                            "symbol map symbolization does not support absolute address inputs",
+                        ))
+                    }
+                    Input::FileOffset(..) => {
+                        return Err(Error::with_unsupported(
+                            "symbol map symbolization does not support file offset inputs",
+                        ))
+                    }
+                };
+
+                let resolver = self.symbol_map_resolver(path)?;
+                self.symbolize_addrs(addrs, &Resolver::Cached(resolver))
+            }
+            Source::Composite(Composite {
+                sources,
+                _non_exhaustive: (),
+            }) => self.symbolize_composite(sources, input),
+            #[cfg(feature = "apk")]
+            Source::Zip(Zip {
+                path,
+                member,
+                debug_syms,
+                _non_exhaustive: (),
+            }) => {
+                let path = self.sysroot_path(path);
+                let resolver = self.zip_member_resolver(&path, member, *debug_syms)?;
+                match input {
+                    Input::VirtOffset(addrs) => {
+                        self.symbolize_addrs(addrs, &Resolver::Cached(resolver.as_symbolize()))
+                    }
+                    Input::AbsAddr(..) => Err(Error::with_unsupported(
+                        "zip member symbolization does not support absolute address inputs",
+                    )),
+                    Input::FileOffset(offsets) => offsets
+                        .iter()
+                        .map(
+                            |offset| match resolver.file_offset_to_virt_offset(*offset)? {
+                                Some(addr) => self.symbolize_with_resolver(
+                                    addr,
+                                    &Resolver::Cached(resolver.as_symbolize()),
+                                ),
+                                None => Ok(Symbolized::Unknown(Reason::InvalidFileOffset)),
+                            },
+                        )
+                        .collect(),
+                }
+            }
+            Source::Phantom(()) => unreachable!(),
+        }
+    }
+
+    /// Symbolize a list of addresses, invoking the provided callback
+    /// for each.
+    ///
+    /// The callback is invoked exactly once for each input address, in
+    /// the order of input addresses, and receives the input address
+    /// alongside the result of its symbolization. Please refer to
+    /// [`symbolize`][Self::symbolize] for additional details.
+    pub fn symbolize_with<'slf, F>(
+        &'slf self,
+        src: &Source,
+        input: Input<&[u64]>,
+        mut f: F,
+    ) -> Result<()>
+    where
+        F: FnMut(u64, Symbolized<'slf>),
+    {
+        let addrs = input.into_inner();
+        let syms = self.symbolize(src, input)?;
+        debug_assert_eq!(addrs.len(), syms.len());
+
+        let () = addrs.iter().zip(syms).for_each(|(addr, sym)| f(*addr, sym));
+        Ok(())
+    }
+
+    /// Symbolize a list of virtual offsets using debug information from
+    /// a separate file.
+    ///
+    /// Stripped binaries are commonly accompanied by a separate file
+    /// containing their debug information. This method uses `src` for
+    /// the address layout and ELF symbols, while DWARF debug
+    /// information is sourced from `debug_src`. `addrs` are
+    /// interpreted as virtual offsets, as per
+    /// [`Input::VirtOffset`].
+    ///
+    /// `src` has to be a [`Source::Elf`], while `debug_src` may be
+    /// either a [`Source::Elf`] or a [`Source::Dwarf`]. The
+    /// [`debug_syms`][super::source::Elf::debug_syms] setting of
+    /// either source is ignored.
+    #[cfg(feature = "dwarf")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "dwarf")))]
+    #[cfg_attr(feature = "tracing", crate::log::instrument(skip_all, fields(src = ?src, debug_src = ?debug_src, addrs = format_args!("{addrs:#x?}"))))]
+    pub fn symbolize_with_extra_debug_info<'slf>(
+        &'slf self,
+        src: &Source,
+        addrs: &[Addr],
+        debug_src: &Source,
+    ) -> Result<Vec<Symbolized<'slf>>> {
+        let (path, fd) = match src {
+            Source::Elf(Elf { path, fd, .. }) => (path, fd),
+            _ => {
+                return Err(Error::with_unsupported(
+                    "only ELF sources are supported as primary source",
+                ))
+            }
+        };
+        let debug_path = match debug_src {
+            Source::Elf(Elf { path, fd: None, .. }) | Source::Dwarf(Dwarf { path, .. }) => path,
+            Source::Elf(Elf { fd: Some(..), .. }) => {
+                return Err(Error::with_unsupported(
+                    "file descriptor based debug information sources are not supported",
+                ))
+            }
+            _ => {
+                return Err(Error::with_unsupported(
+                    "only ELF and DWARF sources are supported as debug information source",
+                ))
+            }
+        };
+
+        let elf = self.elf_src_resolver(path, fd.as_deref(), false)?;
+        let debug_path = self.sysroot_path(debug_path);
+        let dwarf = self.dwarf_resolver(&debug_path)?;
+        let resolver = SplitDebugResolver { elf, dwarf };
+        self.symbolize_addrs(addrs, &Resolver::Uncached(&resolver))
+    }
+
+    /// Symbolize a list of offsets relative to the start of a section.
+    ///
+    /// Each offset is interpreted relative to the virtual address of
+    /// the section called `section`, as reported by its section
+    /// header. This can be useful for firmware images, for example,
+    /// where addresses are commonly reported as offsets into `.text`.
+    ///
+    /// Offsets not falling inside the section are reported as
+    /// [`Reason::UnknownAddr`]. An error is returned if the section
+    /// does not exist.
+    ///
+    /// Currently only [`Source::Elf`] is supported.
+    #[cfg_attr(feature = "tracing", crate::log::instrument(skip_all, fields(src = ?src, section = section, offsets = format_args!("{offsets:#x?}"))))]
+    pub fn symbolize_section_offsets<'slf>(
+        &'slf self,
+        src: &Source,
+        section: &str,
+        offsets: &[u64],
+    ) -> Result<Vec<Symbolized<'slf>>> {
+        match src {
+            Source::Elf(Elf {
+                path,
+                fd,
+                debug_syms,
+                base_addr: _,
+                _non_exhaustive: (),
+            }) => {
+                let resolver = self.elf_src_resolver(path, fd.as_deref(), *debug_syms)?;
+                let parser = resolver.parser();
+                let idx = parser.find_section(section)?.ok_or_else(|| {
+                    Error::with_not_found(format!(
+                        "section `{section}` not found in `{}`",
+                        path.display()
+                    ))
+                })?;
+                let shdr = parser
+                    .section_headers()?
+                    .get(idx)
+                    .ok_or_invalid_data(|| format!("failed to retrieve section header {idx}"))?;
+
+                offsets
+                    .iter()
+                    .map(|offset| {
+                        if *offset >= shdr.sh_size {
+                            return Ok(Symbolized::Unknown(Reason::UnknownAddr))
+                        }
+                        self.symbolize_with_resolver(
+                            shdr.sh_addr + offset,
+                            &Resolver::Cached(resolver.deref()),
+                        )
+                    })
+                    .collect()
+            }
+            _ => Err(Error::with_unsupported(
+                "section relative symbolization is only supported for ELF sources",
+            )),
+        }
+    }
+
+    /// Symbolize addresses by consulting the provided sources in order,
+    /// until each address got resolved.
+    fn symbolize_composite<'slf>(
+        &'slf self,
+        sources: &[Source<'_>],
+        input: Input<&[u64]>,
+    ) -> Result<Vec<Symbolized<'slf>>> {
+        let addrs = input.into_inner();
+        let mut results = addrs
+            .iter()
+            .map(|_addr| Symbolized::Unknown(Reason::UnknownAddr))
+            .collect::<Vec<_>>();
+        // The indices of all addresses that have not been resolved yet.
+        let mut pending = (0..addrs.len()).collect::<Vec<_>>();
+
+        for src in sources {
+            if pending.is_empty() {
+                break
             }
-            Source::Phantom(()) => unreachable!(),
+
+            let pending_addrs = pending.iter().map(|idx| addrs[*idx]).collect::<Vec<_>>();
+            let input = input.map(|_addrs| pending_addrs.as_slice());
+            let symbolized = match self.symbolize(src, input) {
+                Ok(symbolized) => symbolized,
+                // Sources not supporting the input type at hand are
+                // just skipped.
+                Err(err) if err.kind() == ErrorKind::Unsupported => {
+                    log::debug!("skipping composite symbolization source {src:?}: {err}");
+                    continue
+                }
+                Err(err) => {
+                    return Err(err).with_context(|| format!("failed to symbolize using {src:?}"))
+                }
+            };
+
+            let mut still_pending = Vec::new();
+            for (idx, symbolized) in pending.into_iter().zip(symbolized) {
+                if let Symbolized::Unknown(..) = symbolized {
+                    let () = still_pending.push(idx);
+                }
+                results[idx] = symbolized;
+            }
+            pending = still_pending;
         }
+        Ok(results)
+    }
+
+    /// Symbolize kernel addresses contained in textual `ftrace` output.
+    ///
+    /// The trace output is read from `src` in its entirety. Kernel
+    /// addresses, printed as 16 hexadecimal digits (optionally
+    /// prefixed with `0x`), are symbolized using the provided
+    /// [`Kernel`] source and replaced in-line with a `symbol+offset`
+    /// representation. Addresses that cannot be symbolized are left
+    /// untouched.
+    ///
+    /// The returned iterator yields the resulting lines, without line
+    /// terminators.
+    pub fn symbolize_ktrace(
+        &self,
+        src: Ktrace,
+        kernel: &Kernel,
+    ) -> Result<impl Iterator<Item = String>> {
+        let Ktrace {
+            mut input,
+            _non_exhaustive: (),
+        } = src;
+
+        let mut data = Vec::new();
+        let _cnt = input
+            .read_to_end(&mut data)
+            .context("failed to read kernel trace output")?;
+        let text = String::from_utf8_lossy(&data);
+        let lines = text.lines().collect::<Vec<_>>();
+        let line_addrs = lines
+            .iter()
+            .map(|line| ktrace::find_addrs(line))
+            .collect::<Vec<_>>();
+
+        let addrs = line_addrs
+            .iter()
+            .flatten()
+            .map(|(_range, addr)| *addr)
+            .collect::<Vec<_>>();
+        let src = Source::Kernel(kernel.clone());
+        let symbolized = self.symbolize(&src, Input::AbsAddr(&addrs))?;
+
+        let mut symbolized = symbolized.iter();
+        let lines = lines
+            .into_iter()
+            .zip(line_addrs)
+            .map(|(line, addrs)| {
+                let addrs = addrs
+                    .into_iter()
+                    .map(|(range, _addr)| range)
+                    // SANITY: We symbolized exactly one address for
+                    //         each address found.
+                    .map(|range| (range, symbolized.next().unwrap()));
+                ktrace::replace_addrs(line, addrs)
+            })
+            .collect::<Vec<_>>();
+        Ok(lines.into_iter())
     }
 
     /// Symbolize a single input address/offset.
@@ -1131,25 +2466,30 @@ impl Symbolizer {
                 path,
                 debug_syms,
                 _non_exhaustive: (),
-            }) => match input {
-                Input::VirtOffset(..) => {
-                    return Err(Error::with_unsupported(
-                        "APK symbolization does not support virtual offset inputs",
-                    ))
-                }
-                Input::AbsAddr(..) => {
-                    return Err(Error::with_unsupported(
-                        "APK symbolization does not support absolute address inputs",
-                    ))
+            }) => {
+                let path = self.sysroot_path(path);
+                match input {
+                    Input::VirtOffset(..) => {
+                        return Err(Error::with_unsupported(
+                            "APK symbolization does not support virtual offset inputs",
+                        ))
+                    }
+                    Input::AbsAddr(..) => {
+                        return Err(Error::with_unsupported(
+                            "APK symbolization does not support absolute address inputs",
+                        ))
+                    }
+                    Input::FileOffset(offset) => {
+                        match self.apk_resolver(&path, offset, *debug_syms)? {
+                            Ok((elf_resolver, elf_addr)) => self.symbolize_with_resolver(
+                                elf_addr,
+                                &Resolver::Cached(elf_resolver.as_symbolize()),
+                            ),
+                            Err(reason) => return Ok(Symbolized::Unknown(reason)),
+                        }
+                    }
                 }
-                Input::FileOffset(offset) => match self.apk_resolver(path, offset, *debug_syms)? {
-                    Some((elf_resolver, elf_addr)) => self.symbolize_with_resolver(
-                        elf_addr,
-                        &Resolver::Cached(elf_resolver.as_symbolize()),
-                    ),
-                    None => return Ok(Symbolized::Unknown(Reason::InvalidFileOffset)),
-                },
-            },
+            }
             #[cfg(feature = "breakpad")]
             Source::Breakpad(Breakpad {
                 path,
@@ -1172,19 +2512,49 @@ impl Symbolizer {
                 let resolver = self.breakpad_resolver(path)?;
                 self.symbolize_with_resolver(addr, &Resolver::Cached(resolver))
             }
-            Source::Elf(Elf {
+            #[cfg(feature = "dwarf")]
+            Source::Dwarf(Dwarf {
                 path,
-                debug_syms,
                 _non_exhaustive: (),
             }) => {
-                let resolver = self.elf_cache.elf_resolver(path, *debug_syms)?;
+                let path = self.sysroot_path(path);
+                let resolver = self.dwarf_resolver(&path)?;
                 let addr = match input {
                     Input::VirtOffset(addr) => addr,
                     Input::AbsAddr(..) => {
                         return Err(Error::with_unsupported(
-                            "ELF symbolization does not support absolute address inputs",
+                            "DWARF symbolization does not support absolute address inputs",
+                        ))
+                    }
+                    Input::FileOffset(..) => {
+                        return Err(Error::with_unsupported(
+                            "DWARF symbolization does not support file offset inputs",
                         ))
                     }
+                };
+                self.symbolize_with_resolver(addr, &Resolver::Cached(resolver))
+            }
+            Source::Elf(Elf {
+                path,
+                fd,
+                debug_syms,
+                base_addr,
+                _non_exhaustive: (),
+            }) => {
+                let resolver = self.elf_src_resolver(path, fd.as_deref(), *debug_syms)?;
+                let addr = match input {
+                    Input::VirtOffset(addr) => addr,
+                    Input::AbsAddr(addr) => match base_addr {
+                        Some(base_addr) => match addr.checked_sub(*base_addr) {
+                            Some(addr) => addr,
+                            None => return Ok(Symbolized::Unknown(Reason::UnknownAddr)),
+                        },
+                        None => {
+                            return Err(Error::with_unsupported(
+                                "ELF symbolization does not support absolute address inputs without a base address",
+                            ))
+                        }
+                    },
                     Input::FileOffset(offset) => {
                         match resolver.file_offset_to_virt_offset(offset)? {
                             Some(addr) => addr,
@@ -1242,6 +2612,34 @@ impl Symbolizer {
                 //         paths, of course).
                 Ok(symbols.pop().unwrap())
             }
+            Source::CoreDump(CoreDump {
+                path,
+                debug_syms,
+                sysroot,
+                _non_exhaustive: (),
+            }) => {
+                let addr = match input {
+                    Input::AbsAddr(addr) => addr,
+                    Input::VirtOffset(..) => {
+                        return Err(Error::with_unsupported(
+                            "core dump symbolization does not support virtual offset inputs",
+                        ))
+                    }
+                    Input::FileOffset(..) => {
+                        return Err(Error::with_unsupported(
+                            "core dump symbolization does not support file offset inputs",
+                        ))
+                    }
+                };
+
+                let mut symbols =
+                    self.symbolize_core_addrs(&[addr], path, *debug_syms, sysroot.as_deref())?;
+                debug_assert!(symbols.len() == 1, "{symbols:#?}");
+                // SANITY: `symbolize_core_addrs` should *always* return
+                //         one result for one input (except on error
+                //         paths, of course).
+                Ok(symbols.pop().unwrap())
+            }
             #[cfg(feature = "gsym")]
             Source::Gsym(Gsym::Data(GsymData {
                 data,
@@ -1286,9 +2684,210 @@ impl Symbolizer {
                 let resolver = self.gsym_resolver(path)?;
                 self.symbolize_with_resolver(addr, &Resolver::Cached(resolver))
             }
+            #[cfg(feature = "macho")]
+            Source::MachO(MachO {
+                path,
+                arch,
+                dsym,
+                _non_exhaustive: (),
+            }) => {
+                let resolver = self.macho_resolver(path, arch.as_deref(), dsym.as_deref())?;
+                let addr = match input {
+                    Input::VirtOffset(addr) => addr,
+                    Input::AbsAddr(..) => {
+                        return Err(Error::with_unsupported(
+                            "Mach-O symbolization does not support absolute address inputs",
+                        ))
+                    }
+                    Input::FileOffset(offset) => {
+                        match resolver.file_offset_to_virt_offset(offset)? {
+                            Some(addr) => addr,
+                            None => return Ok(Symbolized::Unknown(Reason::InvalidFileOffset)),
+                        }
+                    }
+                };
+                self.symbolize_with_resolver(addr, &Resolver::Cached(resolver))
+            }
+            #[cfg(feature = "pe")]
+            Source::Pe(Pe {
+                path,
+                _non_exhaustive: (),
+            }) => {
+                let resolver = self.pe_resolver(path)?;
+                let addr = match input {
+                    Input::VirtOffset(addr) => addr,
+                    Input::AbsAddr(..) => {
+                        return Err(Error::with_unsupported(
+                            "PE symbolization does not support absolute address inputs",
+                        ))
+                    }
+                    Input::FileOffset(offset) => {
+                        match resolver.file_offset_to_virt_offset(offset)? {
+                            Some(addr) => addr,
+                            None => return Ok(Symbolized::Unknown(Reason::InvalidFileOffset)),
+                        }
+                    }
+                };
+                self.symbolize_with_resolver(addr, &Resolver::Cached(resolver))
+            }
+            Source::SymbolMap(SymbolMap {
+                path,
+                _non_exhaustive: (),
+            }) => {
+                let addr = match input {
+                    Input::VirtOffset(addr) => addr,
+                    Input::AbsAddr(..) => {
+                        return Err(Error::with_unsupported(
+                            "symbol map symbolization does not support absolute address inputs",
+                        ))
+                    }
+                    Input::FileOffset(..) => {
+                        return Err(Error::with_unsupported(
+                            "symbol map symbolization does not support file offset inputs",
+                        ))
+                    }
+                };
+
+                let resolver = self.symbol_map_resolver(path)?;
+                self.symbolize_with_resolver(addr, &Resolver::Cached(resolver))
+            }
+            Source::Composite(Composite {
+                sources,
+                _non_exhaustive: (),
+            }) => {
+                let addrs = [input.into_inner()];
+                let mut symbolized =
+                    self.symbolize_composite(sources, input.map(|_addr| addrs.as_slice()))?;
+                // SANITY: We symbolized exactly one address.
+                Ok(symbolized.pop().unwrap())
+            }
+            #[cfg(feature = "apk")]
+            Source::Zip(Zip {
+                path,
+                member,
+                debug_syms,
+                _non_exhaustive: (),
+            }) => {
+                let path = self.sysroot_path(path);
+                let resolver = self.zip_member_resolver(&path, member, *debug_syms)?;
+                let addr = match input {
+                    Input::VirtOffset(addr) => addr,
+                    Input::AbsAddr(..) => {
+                        return Err(Error::with_unsupported(
+                            "zip member symbolization does not support absolute address inputs",
+                        ))
+                    }
+                    Input::FileOffset(offset) => {
+                        match resolver.file_offset_to_virt_offset(offset)? {
+                            Some(addr) => addr,
+                            None => return Ok(Symbolized::Unknown(Reason::InvalidFileOffset)),
+                        }
+                    }
+                };
+
+                self.symbolize_with_resolver(addr, &Resolver::Cached(resolver.as_symbolize()))
+            }
             Source::Phantom(()) => unreachable!(),
         }
     }
+
+    /// Symbolize the output of user space address normalization.
+    ///
+    /// For each meta data entry of `normalized`, the ELF file or APK it
+    /// describes is used to symbolize the file offsets referencing it.
+    /// If an ELF meta data entry carries a build ID, symbolization only
+    /// happens if the file found at the recorded path has a matching
+    /// one. Symbols are reported in the order of
+    /// [`normalized.outputs`][normalize::UserOutput::outputs], i.e.,
+    /// aligned with the addresses originally normalized.
+    ///
+    /// ```no_run
+    /// # use blazesym::normalize::Normalizer;
+    /// # use blazesym::symbolize::Symbolizer;
+    /// # use blazesym::Addr;
+    /// # use blazesym::Pid;
+    /// let normalizer = Normalizer::new();
+    /// let addrs = [libc::fopen as Addr];
+    /// let normalized = normalizer.normalize_user_addrs(Pid::Slf, &addrs).unwrap();
+    ///
+    /// let symbolizer = Symbolizer::new();
+    /// let syms = symbolizer.symbolize_normalized(&normalized).unwrap();
+    /// assert_eq!(syms.len(), addrs.len());
+    /// ```
+    pub fn symbolize_normalized<'slf>(
+        &'slf self,
+        normalized: &normalize::UserOutput<'_>,
+    ) -> Result<Vec<Symbolized<'slf>>> {
+        let normalize::UserOutput { outputs, meta } = normalized;
+
+        // The file offsets referencing each meta data entry, along with
+        // their indices into `outputs`.
+        let mut groups = vec![(Vec::new(), Vec::new()); meta.len()];
+        for (idx, (offset, meta_idx)) in outputs.iter().enumerate() {
+            let (offsets, idxs) = groups.get_mut(*meta_idx).ok_or_invalid_input(|| {
+                format!("normalized output references invalid meta data index {meta_idx}")
+            })?;
+            let () = offsets.push(*offset);
+            let () = idxs.push(idx);
+        }
+
+        let mut results = outputs.iter().map(|_| None).collect::<Vec<_>>();
+        for (meta, (offsets, idxs)) in meta.iter().zip(groups) {
+            if offsets.is_empty() {
+                continue
+            }
+
+            let symbolized = match meta {
+                normalize::UserMeta::Elf(elf) => {
+                    let reason = if let Some(build_id) = &elf.build_id {
+                        let path = self.sysroot_path(&elf.path);
+                        if !path.exists() {
+                            Some(Reason::MissingFile)
+                        } else if read_elf_build_id(&path)?.as_deref() != Some(build_id.as_ref()) {
+                            Some(Reason::BuildIdMismatch)
+                        } else {
+                            None
+                        }
+                    } else {
+                        None
+                    };
+
+                    if let Some(reason) = reason {
+                        vec![Symbolized::Unknown(reason); offsets.len()]
+                    } else {
+                        let src = Source::from(Elf::new(&elf.path));
+                        self.symbolize(&src, Input::FileOffset(&offsets))?
+                    }
+                }
+                #[cfg(feature = "apk")]
+                normalize::UserMeta::Apk(apk) => {
+                    let src = Source::from(Apk::new(&apk.path));
+                    self.symbolize(&src, Input::FileOffset(&offsets))?
+                }
+                normalize::UserMeta::Unknown(unknown) => {
+                    vec![Symbolized::Unknown(unknown.reason.into()); offsets.len()]
+                }
+                // The vDSO image and `vsyscall` page are not
+                // available as files to symbolize from.
+                normalize::UserMeta::Vdso(_) | normalize::UserMeta::Vsyscall(_) => {
+                    vec![Symbolized::Unknown(Reason::Unsupported); offsets.len()]
+                }
+                #[cfg(not(feature = "apk"))]
+                normalize::UserMeta::Apk(_apk) => {
+                    vec![Symbolized::Unknown(Reason::Unsupported); offsets.len()]
+                }
+            };
+
+            for (idx, symbolized) in idxs.into_iter().zip(symbolized) {
+                results[idx] = Some(symbolized);
+            }
+        }
+
+        // SANITY: Every output references a meta data entry, which we
+        //         have symbolized all file offsets of.
+        let results = results.into_iter().map(Option::unwrap).collect();
+        Ok(results)
+    }
 }
 
 impl Default for Symbolizer {
@@ -1302,6 +2901,7 @@ impl Default for Symbolizer {
 mod tests {
     use super::*;
 
+    use std::fs::write;
     use std::mem::transmute;
 
     use crate::inspect;
@@ -1310,6 +2910,8 @@ mod tests {
     use crate::symbolize::CodeInfo;
     use crate::SymType;
 
+    use tempfile::tempdir;
+
     use test_log::test;
 
 
@@ -1331,6 +2933,46 @@ mod tests {
         assert_ne!(format!("{resolver:?}"), "");
     }
 
+    /// Check that inline stacks get truncated as expected.
+    #[test]
+    fn inlined_truncation() {
+        let inlined_fn = |name: &'static str, line| InlinedFn {
+            name: Cow::Borrowed(name),
+            code_info: Some(CodeInfo {
+                dir: None,
+                file: Cow::Borrowed(OsStr::new("test.c")),
+                line: Some(line),
+                column: None,
+                comp_dir: None,
+                _non_exhaustive: (),
+            }),
+            addr: None,
+            offset: None,
+            origin_addr: None,
+            _non_exhaustive: (),
+        };
+        let inlined = vec![
+            inlined_fn("outer", 1),
+            inlined_fn("middle", 2),
+            inlined_fn("inner", 3),
+        ]
+        .into_boxed_slice();
+
+        let result = truncate_inlined(inlined.clone(), 3);
+        assert_eq!(result, inlined);
+
+        let result = truncate_inlined(inlined.clone(), 1);
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0], inlined[0]);
+        assert_eq!(result[1].name, TRUNCATED_INLINED_FN);
+        assert_eq!(result[1].code_info, inlined[2].code_info);
+
+        let result = truncate_inlined(inlined.clone(), 0);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].name, TRUNCATED_INLINED_FN);
+        assert_eq!(result[0].code_info, inlined[2].code_info);
+    }
+
     /// Check that we can create a path to an ELF inside an APK as expected.
     #[test]
     fn elf_apk_path_creation() {
@@ -1348,6 +2990,7 @@ mod tests {
             file: Cow::Borrowed(OsStr::new("source.c")),
             line: Some(1),
             column: Some(2),
+            comp_dir: None,
             _non_exhaustive: (),
         };
         assert_eq!(info.to_path(), Path::new("source.c"));
@@ -1371,6 +3014,42 @@ mod tests {
         );
     }
 
+    /// Check that we correctly identify mangled symbol names.
+    #[test]
+    fn mangled_name_detection() {
+        for name in [
+            "_ZN4core9panicking9panic_fmt17h5f1a6fd39197ad62E",
+            "_ZStlsISt11char_traitsIcEERSt13basic_ostreamIcT_ES5_PKc",
+            "__ZN4test16mangled_functionEi",
+            "_RNvCs69hjMPjVIJK_4test13test_function",
+            "__RNvCs69hjMPjVIJK_4test13test_function",
+        ] {
+            assert!(is_mangled(name), "{name}");
+        }
+
+        for name in ["main", "_start", "_Rust_alloc", "_Zero", "__Z", ""] {
+            assert!(!is_mangled(name), "{name}");
+        }
+    }
+
+    /// Check that we can demangle symbols into a reusable buffer.
+    #[cfg(feature = "demangle")]
+    #[test]
+    fn demangle_into_buffer() {
+        let mut buf = String::new();
+        assert!(demangle_into(
+            "_ZN4core9panicking9panic_fmt17h5f1a6fd39197ad62E",
+            &mut buf
+        ));
+        assert_eq!(buf, "core::panicking::panic_fmt");
+
+        assert!(demangle_into("_Z3fooi", &mut buf));
+        assert_eq!(buf, "foo(int)");
+
+        assert!(!demangle_into("main", &mut buf));
+        assert_eq!(buf, "");
+    }
+
     /// Make sure that we error out as expected on certain input
     /// variants.
     #[test]
@@ -1461,6 +3140,7 @@ mod tests {
         // object.
         let elf_parser = ElfParser::from_mmap(elf_mmap.clone(), Path::new("libtest-so.so"));
         let opts = FindAddrOpts {
+            offset_in_file: true,
             sym_type: SymType::Function,
             ..Default::default()
         };
@@ -1504,6 +3184,48 @@ mod tests {
         assert_eq!(result.addr, sym.addr);
     }
 
+    /// Check that we can symbolize addresses inside a zip archive
+    /// member using a [`Zip`] source.
+    #[test]
+    fn symbolize_zip_member() {
+        let test_zip = Path::new(&env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join("test.zip");
+
+        let mmap = Mmap::builder().exec().open(&test_zip).unwrap();
+        let (sym, _the_answer_addr) = find_the_answer_fn(&mmap);
+
+        let src = symbolize::Source::Zip(Zip::new(&test_zip, "libtest-so.so"));
+        let symbolizer = Symbolizer::new();
+        let result = symbolizer
+            .symbolize_single(&src, Input::VirtOffset(sym.addr))
+            .unwrap()
+            .into_sym()
+            .unwrap();
+        assert_eq!(result.name, "the_answer");
+        assert_eq!(result.addr, sym.addr);
+
+        let file_offset = sym.file_offset.unwrap();
+        let results = symbolizer
+            .symbolize(&src, Input::FileOffset(&[file_offset, u64::MAX]))
+            .unwrap();
+        assert_eq!(results.len(), 2);
+        let result = results[0].as_sym().unwrap();
+        assert_eq!(result.name, "the_answer");
+        assert_eq!(results[1], Symbolized::Unknown(Reason::InvalidFileOffset));
+
+        let err = symbolizer
+            .symbolize_single(&src, Input::AbsAddr(sym.addr))
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Unsupported);
+
+        let src = symbolize::Source::Zip(Zip::new(&test_zip, "does-not-exist.so"));
+        let err = symbolizer
+            .symbolize_single(&src, Input::VirtOffset(sym.addr))
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::NotFound);
+    }
+
     /// Check that we can symbolize an address residing in a zip archive, using
     /// a custom APK dispatcher.
     #[test]
@@ -1601,4 +3323,75 @@ mod tests {
         let () = test(zip_error_dispatch);
         let () = test(zip_delayed_error_dispatch);
     }
+
+    /// Check that addresses in shared objects mapped directly from an
+    /// APK, as reported using `!` paths, and those in ART boot images
+    /// are handled consistently during normalization and symbolization.
+    #[cfg(feature = "apk")]
+    #[test]
+    fn apk_member_normalize_symbolize() {
+        let test_zip = Path::new(&env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join("test.zip");
+
+        let mmap = Mmap::builder().exec().open(&test_zip).unwrap();
+        let (_sym, the_answer_addr) = find_the_answer_fn(&mmap);
+        let the_answer_off = the_answer_addr - mmap.as_ptr() as Addr;
+
+        let maps = format!(
+            "7f0000000000-7f0000100000 r-xp 00000000 00:12 1234                       {}!/libtest-so.so
+7f0000200000-7f0000201000 r-xp 00000000 00:12 1235                       /system/framework/arm64/boot.oat
+",
+            test_zip.display()
+        );
+        let addrs = [0x7f0000000000 + the_answer_off, 0x7f0000200100];
+
+        let dir = tempdir().unwrap();
+        let maps_file = dir.path().join("maps");
+        let () = write(&maps_file, &maps).unwrap();
+
+        let normalizer = normalize::Normalizer::new();
+        let normalized = normalizer
+            .normalize_user_addrs_with_maps_file(&maps_file, addrs.as_slice())
+            .unwrap();
+        assert_eq!(normalized.outputs.len(), 2);
+
+        let (offset, meta_idx) = normalized.outputs[0];
+        assert_eq!(offset, the_answer_off);
+        let apk = normalized.meta[meta_idx].apk().unwrap();
+        assert_eq!(apk.path, test_zip);
+
+        let (_offset, meta_idx) = normalized.outputs[1];
+        let unknown = normalized.meta[meta_idx].unknown().unwrap();
+        assert_eq!(unknown.reason, normalize::Reason::ArtBootImage);
+
+        let symbolizer = Symbolizer::new();
+        let src = symbolize::Source::Apk(Apk::new(&apk.path));
+        let sym = symbolizer
+            .symbolize_single(&src, Input::FileOffset(offset))
+            .unwrap()
+            .into_sym()
+            .unwrap();
+        assert_eq!(sym.name, "the_answer");
+
+        // Now symbolize the absolute addresses using the same proc maps
+        // entries.
+        let entries = maps::parse_file(maps.as_bytes(), Pid::Slf);
+        let handler = SymbolizeHandler {
+            symbolizer: &symbolizer,
+            pid: Pid::Slf,
+            debug_syms: true,
+            perf_map: false,
+            map_files: false,
+            sysroot: None,
+            unavailable: HashMap::new(),
+            all_symbols: Vec::with_capacity(addrs.len()),
+        };
+        let symbolized = symbolizer
+            .symbolize_entry_addrs(addrs.as_slice(), entries, handler)
+            .unwrap();
+        assert_eq!(symbolized.len(), 2);
+        assert_eq!(symbolized[0].as_sym().unwrap().name, "the_answer");
+        assert_eq!(symbolized[1], Symbolized::Unknown(Reason::ArtBootImage));
+    }
 }
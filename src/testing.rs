@@ -0,0 +1,201 @@
+//! Helpers for creating self-contained test data.
+
+use std::mem::size_of;
+use std::slice;
+
+use crate::elf::types::Elf64_Ehdr;
+use crate::elf::types::Elf64_Shdr;
+use crate::elf::types::Elf64_Sym;
+use crate::elf::types::EI_CLASS;
+use crate::elf::types::EI_DATA;
+use crate::elf::types::ELFCLASS64;
+use crate::elf::types::ELFDATA2LSB;
+use crate::elf::types::ELFDATA2MSB;
+use crate::elf::types::EM_X86_64;
+use crate::elf::types::ET_DYN;
+use crate::elf::types::SHF_ALLOC;
+use crate::elf::types::SHF_EXECINSTR;
+use crate::elf::types::SHT_NOBITS;
+use crate::elf::types::SHT_STRTAB;
+use crate::elf::types::SHT_SYMTAB;
+use crate::elf::types::STB_GLOBAL;
+use crate::elf::types::STT_FUNC;
+use crate::util::Pod;
+
+
+/// Append the in-memory representation of `value` to `data`.
+fn push_pod<T>(data: &mut Vec<u8>, value: &T)
+where
+    T: Pod,
+{
+    // SAFETY: All ELF data structures we work with are free of
+    //         padding, meaning that all of their bytes are
+    //         initialized.
+    let bytes = unsafe { slice::from_raw_parts((value as *const T).cast::<u8>(), size_of::<T>()) };
+    let () = data.extend_from_slice(bytes);
+}
+
+/// Pad `data` to a multiple of eight bytes.
+fn align(data: &mut Vec<u8>) {
+    let () = data.resize((data.len() + 7) & !7, 0);
+}
+
+
+/// Construct a minimal but valid 64 bit ELF file, in host byte order,
+/// containing a symbol table with the provided `(name, address,
+/// size)` function symbols.
+///
+/// All symbols are global and are attributed to a `.text` section
+/// spanning all of them. The section is of type `SHT_NOBITS`, so that
+/// no file space is needed for it.
+pub(crate) fn make_elf_bytes(syms: &[(&str, u64, u64)]) -> Vec<u8> {
+    make_elf_bytes_with_sections(syms, &[])
+}
+
+/// Construct an ELF file just like [`make_elf_bytes`], but with the
+/// provided `(name, type, flags, address, size)` sections appended.
+///
+/// The additional sections do not have any data in the file.
+pub(crate) fn make_elf_bytes_with_sections(
+    syms: &[(&str, u64, u64)],
+    sections: &[(&str, u32, u64, u64, u64)],
+) -> Vec<u8> {
+    let text_start = syms.iter().map(|(_, addr, _)| *addr).min().unwrap_or(0);
+    let text_end = syms
+        .iter()
+        .map(|(_, addr, size)| addr + size)
+        .max()
+        .unwrap_or(0);
+
+    let mut strtab = vec![0];
+    let mut symtab = Vec::with_capacity(syms.len() + 1);
+    let () = symtab.push(Elf64_Sym {
+        st_name: 0,
+        st_info: 0,
+        st_other: 0,
+        st_shndx: 0,
+        st_value: 0,
+        st_size: 0,
+    });
+    for (name, addr, size) in syms {
+        let st_name = strtab.len() as u32;
+        let () = strtab.extend_from_slice(name.as_bytes());
+        let () = strtab.push(0);
+        let () = symtab.push(Elf64_Sym {
+            st_name,
+            st_info: (STB_GLOBAL << 4) | STT_FUNC,
+            st_other: 0,
+            // The `.text` section.
+            st_shndx: 1,
+            st_value: *addr,
+            st_size: *size,
+        });
+    }
+
+    let mut shstrtab = b"\0.text\0.symtab\0.strtab\0.shstrtab\0".to_vec();
+    let section_names = sections
+        .iter()
+        .map(|(name, ..)| {
+            let sh_name = shstrtab.len() as u32;
+            let () = shstrtab.extend_from_slice(name.as_bytes());
+            let () = shstrtab.push(0);
+            sh_name
+        })
+        .collect::<Vec<_>>();
+
+    let mut data = Vec::new();
+    // We fill in the ELF header once we know where the section headers
+    // end up.
+    let () = data.resize(size_of::<Elf64_Ehdr>(), 0);
+
+    let symtab_offset = data.len();
+    let () = symtab.iter().for_each(|sym| push_pod(&mut data, sym));
+    let strtab_offset = data.len();
+    let () = data.extend_from_slice(&strtab);
+    let shstrtab_offset = data.len();
+    let () = data.extend_from_slice(&shstrtab);
+    let () = align(&mut data);
+
+    let shdr = |sh_name, sh_type, sh_flags, sh_addr, sh_offset, sh_size| Elf64_Shdr {
+        sh_name,
+        sh_type,
+        sh_flags,
+        sh_addr,
+        sh_offset: sh_offset as u64,
+        sh_size: sh_size as u64,
+        sh_link: 0,
+        sh_info: 0,
+        sh_addralign: 1,
+        sh_entsize: 0,
+    };
+    let mut shdrs = vec![
+        shdr(0, 0, 0, 0, 0, 0),
+        shdr(
+            1,
+            SHT_NOBITS,
+            SHF_ALLOC | SHF_EXECINSTR,
+            text_start,
+            0,
+            (text_end - text_start) as usize,
+        ),
+        Elf64_Shdr {
+            // The `.strtab` section.
+            sh_link: 3,
+            // The index of the first non-local symbol.
+            sh_info: 1,
+            sh_addralign: 8,
+            sh_entsize: size_of::<Elf64_Sym>() as u64,
+            ..shdr(
+                7,
+                SHT_SYMTAB,
+                0,
+                0,
+                symtab_offset,
+                strtab_offset - symtab_offset,
+            )
+        },
+        shdr(15, SHT_STRTAB, 0, 0, strtab_offset, strtab.len()),
+        shdr(23, SHT_STRTAB, 0, 0, shstrtab_offset, shstrtab.len()),
+    ];
+    let () = shdrs.extend(sections.iter().zip(section_names).map(
+        |((_name, sh_type, sh_flags, sh_addr, sh_size), sh_name)| {
+            shdr(sh_name, *sh_type, *sh_flags, *sh_addr, 0, *sh_size as usize)
+        },
+    ));
+
+    let shoff = data.len();
+    let () = shdrs.iter().for_each(|shdr| push_pod(&mut data, shdr));
+
+    let mut e_ident = [0; 16];
+    let () = e_ident[..4].copy_from_slice(b"\x7fELF");
+    e_ident[EI_CLASS] = ELFCLASS64;
+    e_ident[EI_DATA] = if cfg!(target_endian = "little") {
+        ELFDATA2LSB
+    } else {
+        ELFDATA2MSB
+    };
+    // `EV_CURRENT`
+    e_ident[6] = 1;
+
+    let ehdr = Elf64_Ehdr {
+        e_ident,
+        e_type: ET_DYN,
+        e_machine: EM_X86_64,
+        e_version: 1,
+        e_entry: 0,
+        e_phoff: 0,
+        e_shoff: shoff as u64,
+        e_flags: 0,
+        e_ehsize: size_of::<Elf64_Ehdr>() as u16,
+        e_phentsize: 0,
+        e_phnum: 0,
+        e_shentsize: size_of::<Elf64_Shdr>() as u16,
+        e_shnum: shdrs.len() as u16,
+        // The `.shstrtab` section.
+        e_shstrndx: 4,
+    };
+    let mut header = Vec::with_capacity(size_of::<Elf64_Ehdr>());
+    let () = push_pod(&mut header, &ehdr);
+    let () = data[..header.len()].copy_from_slice(&header);
+    data
+}
@@ -5,15 +5,18 @@ use std::ffi::OsStr;
 use std::fmt::Debug;
 use std::fmt::Formatter;
 use std::fmt::Result as FmtResult;
+use std::fs::Metadata;
 use std::io;
 use std::iter;
 use std::mem::align_of;
 use std::mem::size_of;
 use std::mem::MaybeUninit;
 use std::os::unix::ffi::OsStrExt as _;
-#[cfg(test)]
-use std::os::unix::io::RawFd;
+use std::os::unix::fs::MetadataExt as _;
+use std::os::unix::io::AsRawFd as _;
+use std::os::unix::io::BorrowedFd;
 use std::path::Path;
+use std::path::PathBuf;
 use std::slice;
 
 
@@ -146,13 +149,14 @@ fn ascii_to_hexdigit(character: u8) -> Option<u64> {
     }
 }
 
+/// Parse a hexadecimal number, returning `None` if `text` is not a
+/// valid hexadecimal number or if it does not fit into a `u64`.
 pub(crate) fn from_radix_16(text: &[u8]) -> Option<u64> {
     let mut index = 0;
-    let mut number = 0;
+    let mut number = 0u64;
     while index != text.len() {
         if let Some(digit) = ascii_to_hexdigit(text[index]) {
-            number *= 16;
-            number += digit;
+            number = number.checked_mul(16)?.checked_add(digit)?;
             index += 1;
         } else {
             return None
@@ -211,6 +215,32 @@ where
 }
 
 
+/// The identity of a file on disk.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) struct FileId {
+    /// The device containing the file.
+    pub dev: u64,
+    /// The file's inode number.
+    pub inode: u64,
+    /// The file's size.
+    pub size: u64,
+    /// The file's modification time, as seconds and nanoseconds since
+    /// the Unix epoch.
+    pub mtime: (i64, i64),
+}
+
+impl From<&Metadata> for FileId {
+    fn from(other: &Metadata) -> Self {
+        Self {
+            dev: other.dev(),
+            inode: other.ino(),
+            size: other.size(),
+            mtime: (other.mtime(), other.mtime_nsec()),
+        }
+    }
+}
+
+
 pub(crate) fn stat(path: &Path) -> io::Result<libc::stat> {
     let mut dst = MaybeUninit::uninit();
     let mut path = path.as_os_str().as_bytes().to_vec();
@@ -225,11 +255,9 @@ pub(crate) fn stat(path: &Path) -> io::Result<libc::stat> {
     Ok(unsafe { dst.assume_init() })
 }
 
-
-#[cfg(test)]
-fn fstat(fd: RawFd) -> io::Result<libc::stat> {
+pub(crate) fn fstat(fd: BorrowedFd<'_>) -> io::Result<libc::stat> {
     let mut dst = MaybeUninit::uninit();
-    let rc = unsafe { libc::fstat(fd, dst.as_mut_ptr()) };
+    let rc = unsafe { libc::fstat(fd.as_raw_fd(), dst.as_mut_ptr()) };
     if rc < 0 {
         return Err(io::Error::last_os_error())
     }
@@ -254,6 +282,63 @@ pub(crate) fn uname_release() -> io::Result<CString> {
     Ok(release)
 }
 
+/// Resolve `path` as if `root` were the file system root.
+///
+/// Symbolic links encountered along the way are followed, with
+/// absolute link targets being interpreted relative to `root` (and
+/// `..` never leading past it), such that the resulting path does not
+/// escape `root`. The resolved path may not exist.
+pub(crate) fn resolve_in_root(root: &Path, path: &Path) -> io::Result<PathBuf> {
+    // The maximum number of symbolic links we follow, mirroring the
+    // kernel's limit.
+    const MAX_SYMLINKS: usize = 40;
+
+    let mut links = 0;
+    let mut resolved = PathBuf::new();
+    // The components left to resolve, in reverse order. Root, current,
+    // and parent directory components are represented by `/`, `.`, and
+    // `..`, respectively, none of which can be a regular component.
+    let mut pending = path
+        .components()
+        .rev()
+        .map(|component| component.as_os_str().to_os_string())
+        .collect::<Vec<_>>();
+
+    while let Some(component) = pending.pop() {
+        if component == "/" {
+            resolved = PathBuf::new();
+        } else if component == "." {
+            continue
+        } else if component == ".." {
+            let _popped = resolved.pop();
+        } else {
+            let () = resolved.push(&component);
+            let full = root.join(&resolved);
+            match full.symlink_metadata() {
+                Ok(meta) if meta.file_type().is_symlink() => {
+                    links += 1;
+                    if links > MAX_SYMLINKS {
+                        return Err(io::Error::from_raw_os_error(libc::ELOOP))
+                    }
+
+                    let target = full.read_link()?;
+                    let _popped = resolved.pop();
+                    let () = pending.extend(
+                        target
+                            .components()
+                            .rev()
+                            .map(|component| component.as_os_str().to_os_string()),
+                    );
+                }
+                Ok(_) => (),
+                Err(err) if err.kind() == io::ErrorKind::NotFound => (),
+                Err(err) => return Err(err),
+            }
+        }
+    }
+    Ok(root.join(resolved))
+}
+
 pub(crate) fn find_lowest_match_by<T, F>(slice: &[T], mut f: F) -> Option<usize>
 where
     F: FnMut(&T) -> Ordering,
@@ -522,10 +607,14 @@ impl<'data> ReadRaw<'data> for &'data [u8] {
 mod tests {
     use super::*;
 
+    use std::fs::create_dir_all;
+    use std::fs::File;
     #[cfg(feature = "nightly")]
     use std::hint::black_box;
-    use std::os::fd::AsRawFd as _;
+    use std::os::fd::AsFd as _;
+    use std::os::unix::fs::symlink;
 
+    use tempfile::tempdir;
     use tempfile::NamedTempFile;
 
     #[cfg(feature = "nightly")]
@@ -634,7 +723,7 @@ mod tests {
     fn file_stating() {
         let tmpfile = NamedTempFile::new().unwrap();
         let stat1 = stat(tmpfile.path()).unwrap();
-        let stat2 = fstat(tmpfile.as_file().as_raw_fd()).unwrap();
+        let stat2 = fstat(tmpfile.as_file().as_fd()).unwrap();
 
         assert_eq!(stat1.st_dev, stat2.st_dev);
         assert_eq!(stat1.st_ino, stat2.st_ino);
@@ -643,6 +732,39 @@ mod tests {
         assert_eq!(stat1.st_ctime, stat2.st_ctime);
     }
 
+    /// Check that we can resolve paths relative to a root directory,
+    /// without escaping it.
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn root_relative_path_resolution() {
+        let root = tempdir().unwrap();
+        let root = root.path();
+        let () = create_dir_all(root.join("usr").join("lib")).unwrap();
+        let _file = File::create(root.join("usr").join("lib").join("libfoo.so")).unwrap();
+        // An absolute link, which has to be interpreted relative to the
+        // root.
+        let () = symlink("/usr/lib", root.join("lib")).unwrap();
+        // A relative link trying to escape the root.
+        let () = symlink("../../../../../../usr/lib", root.join("usr").join("lib64")).unwrap();
+        let () = symlink("loop", root.join("loop")).unwrap();
+
+        let expected = root.join("usr").join("lib").join("libfoo.so");
+        let path = resolve_in_root(root, Path::new("/usr/lib/libfoo.so")).unwrap();
+        assert_eq!(path, expected);
+        let path = resolve_in_root(root, Path::new("/lib/libfoo.so")).unwrap();
+        assert_eq!(path, expected);
+        let path = resolve_in_root(root, Path::new("/usr/lib64/libfoo.so")).unwrap();
+        assert_eq!(path, expected);
+        let path = resolve_in_root(root, Path::new("/../../lib/./libfoo.so")).unwrap();
+        assert_eq!(path, expected);
+
+        let path = resolve_in_root(root, Path::new("/does/not/exist")).unwrap();
+        assert_eq!(path, root.join("does").join("not").join("exist"));
+
+        let err = resolve_in_root(root, Path::new("/loop/libfoo.so")).unwrap_err();
+        assert_eq!(err.raw_os_error(), Some(libc::ELOOP));
+    }
+
     /// Make sure that `[u8]::ensure` works as expected.
     #[test]
     fn u8_slice_len_ensurance() {
@@ -7,6 +7,7 @@
 /// padding and they might be misaligned. To allow us to safely
 /// operate on pointers to such structures and their members, we
 /// declare the types as packed.
+use std::borrow::Cow;
 use std::cmp::min;
 use std::ffi::OsStr;
 use std::fmt::Debug;
@@ -26,8 +27,32 @@ use crate::Result;
 const CD_FILE_HEADER_MAGIC: u32 = 0x02014b50;
 const END_OF_CD_RECORD_MAGIC: u32 = 0x06054b50;
 const LOCAL_FILE_HEADER_MAGIC: u32 = 0x04034b50;
+const ZIP64_END_OF_CD_LOCATOR_MAGIC: u32 = 0x07064b50;
+const ZIP64_END_OF_CD_RECORD_MAGIC: u32 = 0x06064b50;
+const ZIP64_EXTRA_FIELD_ID: u16 = 0x0001;
 const FLAG_ENCRYPTED: u16 = 1 << 0;
-const FLAG_HAS_DATA_DESCRIPTOR: u16 = 1 << 3;
+const COMPRESSION_STORED: u16 = 0;
+const COMPRESSION_DEFLATE: u16 = 8;
+
+
+#[cfg(feature = "zlib")]
+fn inflate(data: &[u8]) -> Result<Vec<u8>> {
+    use miniz_oxide::inflate::decompress_to_vec;
+
+    match decompress_to_vec(data) {
+        Ok(data) => Ok(data),
+        Err(err) => Err(Error::with_invalid_data(format!(
+            "DEFLATE decompression failed: {err}"
+        ))),
+    }
+}
+
+#[cfg(not(feature = "zlib"))]
+fn inflate(_data: &[u8]) -> Result<Vec<u8>> {
+    Err(Error::with_unsupported(
+        "archive entry is DEFLATE compressed but zlib compression support is not enabled",
+    ))
+}
 
 
 /// See section 4.3.16 of the spec.
@@ -62,6 +87,58 @@ struct EndOfCdRecord {
 unsafe impl Pod for EndOfCdRecord {}
 
 
+/// See section 4.3.14 of the spec.
+#[repr(C, packed)]
+struct Zip64EndOfCdRecord {
+    /// Magic value equal to ZIP64_END_OF_CD_RECORD_MAGIC.
+    magic: u32,
+    /// Size of the remainder of the record, i.e., excluding the
+    /// leading 12 bytes.
+    record_size: u64,
+    version: u16,
+    /// Minimum zip version needed to extract the archive.
+    min_version: u16,
+    /// Number of the file containing this structure.
+    this_disk: u32,
+    /// Number of the file containing the beginning of the central
+    /// directory.
+    cd_disk: u32,
+    /// Number of central directory records on this disk.
+    cd_records: u64,
+    /// Number of central directory records on all disks.
+    cd_records_total: u64,
+    /// Size of the central directory.
+    cd_size: u64,
+    /// Offset of the central directory from the beginning of the
+    /// archive.
+    cd_offset: u64,
+    // Variable size extensible data.
+    // uint8_t data[record_size - 44]
+}
+
+// SAFETY: `Zip64EndOfCdRecord` is valid for any bit pattern.
+unsafe impl Pod for Zip64EndOfCdRecord {}
+
+
+/// See section 4.3.15 of the spec.
+#[repr(C, packed)]
+struct Zip64EndOfCdLocator {
+    /// Magic value equal to ZIP64_END_OF_CD_LOCATOR_MAGIC.
+    magic: u32,
+    /// Number of the file containing the ZIP64 end of central
+    /// directory record.
+    eocd_disk: u32,
+    /// Offset of the ZIP64 end of central directory record from the
+    /// beginning of the archive.
+    eocd_offset: u64,
+    /// Total number of files the archive spans.
+    total_disks: u32,
+}
+
+// SAFETY: `Zip64EndOfCdLocator` is valid for any bit pattern.
+unsafe impl Pod for Zip64EndOfCdLocator {}
+
+
 /// See section 4.3.12 of the spec.
 #[repr(C, packed)]
 struct CdFileHeader {
@@ -75,7 +152,11 @@ struct CdFileHeader {
     last_modified_time: u16,
     last_modified_date: u16,
     crc: u32,
+    /// Compressed size of the file data or 0xFFFFFFFF if stored in
+    /// the ZIP64 extra field.
     compressed_size: u32,
+    /// Uncompressed size of the file data or 0xFFFFFFFF if stored in
+    /// the ZIP64 extra field.
     uncompressed_size: u32,
     file_name_length: u16,
     extra_field_length: u16,
@@ -85,7 +166,8 @@ struct CdFileHeader {
     internal_attributes: u16,
     external_attributes: u32,
     /// Offset from the start of the disk containing the local file header to
-    /// the start of the local file header.
+    /// the start of the local file header or 0xFFFFFFFF if stored in the
+    /// ZIP64 extra field.
     offset: u32,
 }
 
@@ -105,7 +187,11 @@ struct LocalFileHeader {
     last_modified_time: u16,
     last_modified_date: u16,
     crc: u32,
+    /// Compressed size of the file data. May be zero if the entry has a
+    /// trailing data descriptor.
     compressed_size: u32,
+    /// Uncompressed size of the file data. May be zero if the entry has
+    /// a trailing data descriptor.
     uncompressed_size: u32,
     file_name_length: u16,
     extra_field_length: u16,
@@ -115,6 +201,34 @@ struct LocalFileHeader {
 unsafe impl Pod for LocalFileHeader {}
 
 
+/// Read the values stored in the ZIP64 extended information extra
+/// field (see section 4.5.3 of the spec) contained in `extra`.
+///
+/// Only values set to 0xFFFFFFFF are present in the field and they are
+/// stored in the order provided.
+fn read_zip64_extra(mut extra: &[u8], values: [&mut u64; 3]) -> Option<()> {
+    if !values.iter().any(|value| **value == u64::from(u32::MAX)) {
+        return Some(())
+    }
+
+    let mut field = loop {
+        let id = extra.read_u16()?;
+        let size = extra.read_u16()?;
+        let data = extra.read_slice(size.into())?;
+        if id == ZIP64_EXTRA_FIELD_ID {
+            break data
+        }
+    };
+
+    for value in values {
+        if *value == u64::from(u32::MAX) {
+            *value = field.read_u64()?;
+        }
+    }
+    Some(())
+}
+
+
 /// Carries information on path, compression method, and data corresponding to a
 /// file in a zip archive.
 pub struct Entry<'archive> {
@@ -126,7 +240,43 @@ pub struct Entry<'archive> {
     /// The offset of the data from the beginning of the archive.
     pub data_offset: u64,
     /// Pointer to the file data.
+    ///
+    /// If the file is compressed, this is the compressed data.
     pub data: &'archive [u8],
+    /// The size of the file data once uncompressed.
+    pub uncompressed_size: u64,
+}
+
+impl<'archive> Entry<'archive> {
+    /// Check whether the file data are stored in compressed form.
+    #[inline]
+    pub fn is_compressed(&self) -> bool {
+        self.compression != COMPRESSION_STORED
+    }
+
+    /// Retrieve the file data in uncompressed form.
+    ///
+    /// Data of compressed entries are decompressed into memory, whereas
+    /// uncompressed data are referenced directly.
+    pub fn uncompressed_data(&self) -> Result<Cow<'archive, [u8]>> {
+        match self.compression {
+            COMPRESSION_STORED => Ok(Cow::Borrowed(self.data)),
+            COMPRESSION_DEFLATE => {
+                let data = inflate(self.data)?;
+                if data.len() as u64 != self.uncompressed_size {
+                    return Err(Error::with_invalid_data(format!(
+                        "decompressed archive entry has unexpected size: {} (expected {})",
+                        data.len(),
+                        self.uncompressed_size
+                    )))
+                }
+                Ok(Cow::Owned(data))
+            }
+            compression => Err(Error::with_unsupported(format!(
+                "archive entry uses unsupported compression method ({compression})"
+            ))),
+        }
+    }
 }
 
 impl Debug for Entry<'_> {
@@ -136,6 +286,7 @@ impl Debug for Entry<'_> {
             path,
             data_offset,
             data,
+            uncompressed_size,
         } = self;
 
         f.debug_struct(stringify!(Entry))
@@ -143,6 +294,7 @@ impl Debug for Entry<'_> {
             .field("path", path)
             .field("data_offset", data_offset)
             .field("data", &data.get(0..(min(data.len(), 32))))
+            .field("uncompressed_size", uncompressed_size)
             .finish()
     }
 }
@@ -152,18 +304,36 @@ impl Debug for Entry<'_> {
 pub struct EntryIter<'archive> {
     /// The data of the archive.
     archive_data: &'archive [u8],
+    /// The offset of the archive inside `archive_data`, i.e., the size
+    /// of any data prepended to it.
+    base: u64,
     /// Pointer to the central directory records.
     ///
     /// This read pointer will be advanced as entries are read.
     cd_record_data: &'archive [u8],
     /// The number of remaining records.
-    remaining_records: u16,
+    remaining_records: u64,
 }
 
 impl<'archive> EntryIter<'archive> {
-    fn parse_entry_at_offset(data: &[u8], offset: u32) -> Result<Entry<'_>> {
-        fn entry_impl(data: &[u8], offset: u32) -> Option<Result<Entry<'_>>> {
-            let mut data = data.get(offset as usize..)?;
+    /// Parse the entry whose local file header is located at `offset`.
+    ///
+    /// Sizes are taken from the central directory, because the local
+    /// file header may not contain them (e.g., if they are stored in a
+    /// data descriptor following the file data).
+    fn parse_entry_at_offset(
+        data: &[u8],
+        offset: u64,
+        compressed_size: u64,
+        uncompressed_size: u64,
+    ) -> Result<Entry<'_>> {
+        fn entry_impl(
+            data: &[u8],
+            offset: u64,
+            compressed_size: u64,
+            uncompressed_size: u64,
+        ) -> Option<Result<Entry<'_>>> {
+            let mut data = data.get(usize::try_from(offset).ok()?..)?;
             let start = data.as_ptr();
 
             let lfh = data.read_pod::<LocalFileHeader>()?;
@@ -173,7 +343,7 @@ impl<'archive> EntryIter<'archive> {
                 )))
             }
 
-            if (lfh.flags & FLAG_ENCRYPTED) != 0 || (lfh.flags & FLAG_HAS_DATA_DESCRIPTOR) != 0 {
+            if (lfh.flags & FLAG_ENCRYPTED) != 0 {
                 return Some(Err(Error::with_invalid_data(
                     "attempted lookup of unsupported entry",
                 )))
@@ -184,21 +354,22 @@ impl<'archive> EntryIter<'archive> {
 
             let _extra = data.read_slice(lfh.extra_field_length.into())?;
             // SAFETY: Both pointers point into the same underlying byte array.
-            let data_offset = u64::from(offset)
-                + u64::try_from(unsafe { data.as_ptr().offset_from(start) }).unwrap();
-            let data = data.read_slice(lfh.compressed_size as usize)?;
+            let data_offset =
+                offset + u64::try_from(unsafe { data.as_ptr().offset_from(start) }).unwrap();
+            let data = data.read_slice(usize::try_from(compressed_size).ok()?)?;
 
             let entry = Entry {
                 compression: lfh.compression,
                 path,
                 data_offset,
                 data,
+                uncompressed_size,
             };
 
             Some(Ok(entry))
         }
 
-        entry_impl(data, offset)
+        entry_impl(data, offset, compressed_size, uncompressed_size)
             .unwrap_or_else(|| Err(Error::with_invalid_data("failed to read archive entry")))
     }
 
@@ -215,16 +386,27 @@ impl<'archive> EntryIter<'archive> {
             let _name = iter
                 .cd_record_data
                 .read_slice(cdfh.file_name_length.into())?;
-            let _extra = iter
+            let extra = iter
                 .cd_record_data
                 .read_slice(cdfh.extra_field_length.into())?;
             let _comment = iter
                 .cd_record_data
                 .read_slice(cdfh.file_comment_length.into())?;
 
+            let mut uncompressed_size = u64::from(cdfh.uncompressed_size);
+            let mut compressed_size = u64::from(cdfh.compressed_size);
+            let mut offset = u64::from(cdfh.offset);
+            let () = read_zip64_extra(
+                extra,
+                [&mut uncompressed_size, &mut compressed_size, &mut offset],
+            )?;
+            let offset = iter.base.checked_add(offset)?;
+
             Some(EntryIter::parse_entry_at_offset(
                 iter.archive_data,
-                cdfh.offset,
+                offset,
+                compressed_size,
+                uncompressed_size,
             ))
         }
 
@@ -251,14 +433,19 @@ impl<'archive> Iterator for EntryIter<'archive> {
 /// Only basic ZIP files are supported, in particular the following are not
 /// supported:
 /// - encryption
-/// - streaming
 /// - multi-part ZIP files
-/// - ZIP64
+///
+/// Archives may be prefixed by arbitrary data, as is the case for
+/// self-extracting archives, for example.
 #[derive(Debug)]
 pub(crate) struct Archive {
     mmap: Mmap,
-    cd_offset: u32,
-    cd_records: u16,
+    /// The offset of the archive inside `mmap`, i.e., the size of any
+    /// data prepended to it.
+    base: u64,
+    /// The offset of the central directory inside `mmap`.
+    cd_offset: u64,
+    cd_records: u64,
 }
 
 impl Archive {
@@ -276,16 +463,17 @@ impl Archive {
     pub fn with_mmap(mmap: Mmap) -> Result<Self> {
         // Check that a central directory is present as at least some form
         // of validation that we are in fact dealing with a valid zip file.
-        let (cd_offset, cd_records) = Archive::find_cd(&mmap)?;
+        let (base, cd_offset, cd_records) = Archive::find_cd(&mmap)?;
         let slf = Archive {
             mmap,
+            base,
             cd_offset,
             cd_records,
         };
         Ok(slf)
     }
 
-    fn try_parse_end_of_cd(mut data: &[u8]) -> Option<Result<(u32, u16)>> {
+    fn try_parse_end_of_cd(mut data: &[u8]) -> Option<EndOfCdRecord> {
         let eocd = data.read_pod::<EndOfCdRecord>()?;
         if eocd.magic != END_OF_CD_RECORD_MAGIC {
             return None
@@ -294,20 +482,64 @@ impl Archive {
         // Make sure that another `comment_length` bytes exist after the end of
         // cd record.
         let () = data.ensure(eocd.comment_length.into())?;
+        Some(eocd)
+    }
 
-        if eocd.this_disk != 0 || eocd.cd_disk != 0 || eocd.cd_records_total != eocd.cd_records {
-            // This is a valid eocd, but we only support single-file non-ZIP64 archives.
-            Some(Err(Error::with_invalid_data(
+    /// Try to find the ZIP64 end of central directory record, given
+    /// the offset of the end of central directory record.
+    ///
+    /// On success, the offset of the record is reported along with it.
+    fn try_parse_zip64_end_of_cd(
+        data: &[u8],
+        eocd_offset: usize,
+    ) -> Option<Result<(usize, Zip64EndOfCdRecord)>> {
+        let locator_offset = eocd_offset.checked_sub(size_of::<Zip64EndOfCdLocator>())?;
+        let locator = data
+            .get(locator_offset..)?
+            .read_pod::<Zip64EndOfCdLocator>()?;
+        if locator.magic != ZIP64_END_OF_CD_LOCATOR_MAGIC {
+            return None
+        }
+
+        if locator.eocd_disk != 0 || locator.total_disks > 1 {
+            return Some(Err(Error::with_invalid_data(
                 "archive is unsupported and cannot be opened",
             )))
-        } else {
-            Some(Ok((eocd.cd_offset, eocd.cd_records)))
         }
+
+        // The recorded offset is relative to the start of the archive,
+        // which is not necessarily the start of the file. Unless the
+        // record contains extensible data, it is located right in front
+        // of the locator, though.
+        let offsets = [
+            locator_offset.checked_sub(size_of::<Zip64EndOfCdRecord>()),
+            usize::try_from(locator.eocd_offset).ok(),
+        ];
+        for offset in offsets.into_iter().flatten() {
+            let record = match data.get(offset..) {
+                Some(mut data) => data.read_pod::<Zip64EndOfCdRecord>(),
+                None => None,
+            };
+            match record {
+                Some(record) if record.magic == ZIP64_END_OF_CD_RECORD_MAGIC => {
+                    return Some(Ok((offset, record)))
+                }
+                _ => continue,
+            }
+        }
+
+        Some(Err(Error::with_invalid_data(
+            "failed to find ZIP64 end of central directory record; archive is corrupted",
+        )))
     }
 
     /// Search for the central directory at the end of the archive (represented
     /// by the provided slice of bytes).
-    fn find_cd(data: &[u8]) -> Result<(u32, u16)> {
+    ///
+    /// On success, the offset of the archive inside `data`, the offset of
+    /// the central directory (relative to `data`), and the number of
+    /// central directory records are returned.
+    fn find_cd(data: &[u8]) -> Result<(u64, u64, u64)> {
         // Because the end of central directory ends with a variable length
         // array of up to 0xFFFF bytes we can't know exactly where it starts and
         // need to search for it at the end of the file, scanning the [start,
@@ -321,21 +553,77 @@ impl Archive {
         let start = end.saturating_sub(1 << 16);
 
         for offset in (start..=end).rev() {
-            let result = Self::try_parse_end_of_cd(data.get(offset..).unwrap());
-            match result {
+            let eocd = match Self::try_parse_end_of_cd(data.get(offset..).unwrap()) {
+                Some(eocd) => eocd,
                 None => continue,
-                Some(Ok((cd_offset, cd_records))) => {
-                    // Validate the offset and records quickly to eliminate
-                    // potential error cases later on.
-                    let cd_range = cd_offset as usize
-                        ..cd_offset as usize + usize::from(cd_records) * size_of::<CdFileHeader>();
-                    let _cd = data.get(cd_range).ok_or_unexpected_eof(|| {
-                        "failed to retrieve central directory entries; archive is corrupted"
-                    })?;
-                    return Ok((cd_offset, cd_records))
-                }
-                Some(Err(err)) => return Err(err),
-            }
+            };
+
+            let (cd_end, cd_offset, cd_size, cd_records) =
+                match Self::try_parse_zip64_end_of_cd(data, offset) {
+                    Some(result) => {
+                        let (zip64_offset, zip64) = result?;
+                        if zip64.this_disk != 0
+                            || zip64.cd_disk != 0
+                            || zip64.cd_records_total != zip64.cd_records
+                        {
+                            return Err(Error::with_invalid_data(
+                                "archive is unsupported and cannot be opened",
+                            ))
+                        }
+                        (
+                            zip64_offset,
+                            zip64.cd_offset,
+                            zip64.cd_size,
+                            zip64.cd_records,
+                        )
+                    }
+                    None => {
+                        if eocd.this_disk != 0
+                            || eocd.cd_disk != 0
+                            || eocd.cd_records_total != eocd.cd_records
+                        {
+                            // This is a valid eocd, but we only support
+                            // single-file archives.
+                            return Err(Error::with_invalid_data(
+                                "archive is unsupported and cannot be opened",
+                            ))
+                        }
+                        (
+                            offset,
+                            u64::from(eocd.cd_offset),
+                            u64::from(eocd.cd_size),
+                            u64::from(eocd.cd_records),
+                        )
+                    }
+                };
+
+            // The central directory is immediately followed by the
+            // (ZIP64) end of central directory record. Any discrepancy
+            // between where it actually ends and where it is recorded
+            // to end is due to data having been prepended to the
+            // archive.
+            let base = (cd_end as u64)
+                .checked_sub(cd_size)
+                .and_then(|cd_start| cd_start.checked_sub(cd_offset))
+                .ok_or_invalid_data(|| {
+                    "central directory is out of bounds; archive is corrupted"
+                })?;
+            let cd_offset = base + cd_offset;
+
+            // Validate the offset and records quickly to eliminate
+            // potential error cases later on.
+            let cd_range = usize::try_from(cd_offset)
+                .ok()
+                .zip(usize::try_from(cd_records).ok())
+                .and_then(|(cd_offset, cd_records)| {
+                    let cd_len = cd_records.checked_mul(size_of::<CdFileHeader>())?;
+                    Some(cd_offset..cd_offset.checked_add(cd_len)?)
+                })
+                .ok_or_invalid_data(|| "central directory is too large; archive is corrupted")?;
+            let _cd = data.get(cd_range).ok_or_unexpected_eof(|| {
+                "failed to retrieve central directory entries; archive is corrupted"
+            })?;
+            return Ok((base, cd_offset, cd_records))
         }
 
         Err(Error::with_invalid_data(
@@ -352,6 +640,7 @@ impl Archive {
 
         let iter = EntryIter {
             archive_data,
+            base: self.base,
             cd_record_data,
             remaining_records,
         };
@@ -370,6 +659,7 @@ impl Archive {
 mod tests {
     use super::*;
 
+    use std::fs::read;
     use std::io::copy;
     use std::io::Write as _;
     use std::ops::Deref as _;
@@ -379,7 +669,69 @@ mod tests {
     use test_log::test;
 
     use crate::elf::ElfParser;
+    use crate::inspect::FindAddrOpts;
+    use crate::symbolize;
+    use crate::symbolize::Symbolizer;
     use crate::ErrorKind;
+    use crate::SymType;
+
+
+    /// Create a zip archive containing a single DEFLATE compressed entry
+    /// with the provided `data`.
+    #[cfg(feature = "zlib")]
+    fn create_deflated_archive(name: &str, data: &[u8]) -> NamedTempFile {
+        use miniz_oxide::deflate::compress_to_vec;
+
+        let compressed = compress_to_vec(data, 6);
+        let compressed_size = u32::try_from(compressed.len()).unwrap();
+        let uncompressed_size = u32::try_from(data.len()).unwrap();
+        let name_len = u16::try_from(name.len()).unwrap();
+
+        let mut zip = Vec::new();
+        let () = zip.extend_from_slice(&LOCAL_FILE_HEADER_MAGIC.to_le_bytes());
+        // Minimum version, flags.
+        let () = zip.extend_from_slice(&[20, 0, 0, 0]);
+        let () = zip.extend_from_slice(&COMPRESSION_DEFLATE.to_le_bytes());
+        // Last modification time & date, CRC (which we never check).
+        let () = zip.extend_from_slice(&[0; 8]);
+        let () = zip.extend_from_slice(&compressed_size.to_le_bytes());
+        let () = zip.extend_from_slice(&uncompressed_size.to_le_bytes());
+        let () = zip.extend_from_slice(&name_len.to_le_bytes());
+        // Extra field length.
+        let () = zip.extend_from_slice(&[0; 2]);
+        let () = zip.extend_from_slice(name.as_bytes());
+        let () = zip.extend_from_slice(&compressed);
+
+        let cd_offset = u32::try_from(zip.len()).unwrap();
+        let () = zip.extend_from_slice(&CD_FILE_HEADER_MAGIC.to_le_bytes());
+        // Version, minimum version, flags.
+        let () = zip.extend_from_slice(&[20, 0, 20, 0, 0, 0]);
+        let () = zip.extend_from_slice(&COMPRESSION_DEFLATE.to_le_bytes());
+        // Last modification time & date, CRC.
+        let () = zip.extend_from_slice(&[0; 8]);
+        let () = zip.extend_from_slice(&compressed_size.to_le_bytes());
+        let () = zip.extend_from_slice(&uncompressed_size.to_le_bytes());
+        let () = zip.extend_from_slice(&name_len.to_le_bytes());
+        // Extra field length, comment length, disk, internal & external
+        // attributes, and local file header offset.
+        let () = zip.extend_from_slice(&[0; 16]);
+        let () = zip.extend_from_slice(name.as_bytes());
+        let cd_size = u32::try_from(zip.len()).unwrap() - cd_offset;
+
+        let () = zip.extend_from_slice(&END_OF_CD_RECORD_MAGIC.to_le_bytes());
+        // This disk, central directory disk.
+        let () = zip.extend_from_slice(&[0; 4]);
+        let () = zip.extend_from_slice(&1u16.to_le_bytes());
+        let () = zip.extend_from_slice(&1u16.to_le_bytes());
+        let () = zip.extend_from_slice(&cd_size.to_le_bytes());
+        let () = zip.extend_from_slice(&cd_offset.to_le_bytes());
+        // Comment length.
+        let () = zip.extend_from_slice(&[0; 2]);
+
+        let mut file = NamedTempFile::new().unwrap();
+        let () = file.write_all(&zip).unwrap();
+        file
+    }
 
 
     /// Exercise the `Debug` representation of various types.
@@ -396,12 +748,13 @@ mod tests {
             path: Path::new("some-entry-path.so"),
             data_offset: 56,
             data: &[1, 2, 3, 4],
+            uncompressed_size: 4,
         };
 
         let dbg = format!("{entry:?}");
         assert_eq!(
             dbg,
-            r#"Entry { compression: 42, path: "some-entry-path.so", data_offset: 56, data: Some([1, 2, 3, 4]) }"#
+            r#"Entry { compression: 42, path: "some-entry-path.so", data_offset: 56, data: Some([1, 2, 3, 4]), uncompressed_size: 4 }"#
         );
     }
 
@@ -460,13 +813,150 @@ mod tests {
 
         // Sanity check that the entry actually references a valid ELF binary,
         // which is what we expect.
-        let mut file = NamedTempFile::new().unwrap();
-        let () = file.write_all(entry.data).unwrap();
-
-        let elf = ElfParser::open_file(file.as_file(), file.path()).unwrap();
+        let elf = ElfParser::open_from_bytes(entry.data, "test-no-debug.bin").unwrap();
         assert!(elf.find_section(".text").is_ok());
     }
 
+    /// Check that we can read the data of a DEFLATE compressed entry.
+    #[cfg(feature = "zlib")]
+    #[test]
+    fn zip_deflated_entry_reading() {
+        let data = b"Some data, compressed and decompressed. Some data.".repeat(32);
+        let zip = create_deflated_archive("some-data", &data);
+
+        let archive = Archive::open(zip.path()).unwrap();
+        let entry = archive.entries().next().unwrap().unwrap();
+        assert_eq!(entry.path, Path::new("some-data"));
+        assert!(entry.is_compressed());
+        assert_ne!(entry.data, data.as_slice());
+        assert_eq!(entry.uncompressed_size, data.len() as u64);
+        assert_eq!(entry.uncompressed_data().unwrap().deref(), data.as_slice());
+    }
+
+    /// Check that we can symbolize an address inside a DEFLATE
+    /// compressed shared object inside a zip archive, and that APK
+    /// file offsets into it are rejected.
+    #[cfg(feature = "zlib")]
+    #[test]
+    fn deflated_entry_symbolization() {
+        let so = Path::new(&env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join("libtest-so.so");
+        let data = read(&so).unwrap();
+        let zip = create_deflated_archive("libtest-so.so", &data);
+
+        let parser = ElfParser::open(&so).unwrap();
+        let opts = FindAddrOpts {
+            offset_in_file: true,
+            sym_type: SymType::Function,
+            binding: None,
+        };
+        let syms = parser.find_addr("the_answer", &opts).unwrap();
+        assert_eq!(syms.len(), 1);
+        let file_offset = syms[0].file_offset.unwrap();
+
+        let symbolizer = Symbolizer::new();
+        let src = symbolize::Source::Zip(symbolize::Zip::new(zip.path(), "libtest-so.so"));
+        let sym = symbolizer
+            .symbolize_single(&src, symbolize::Input::FileOffset(file_offset))
+            .unwrap()
+            .into_sym()
+            .unwrap();
+        assert_eq!(sym.name, "the_answer");
+
+        // Offsets into the compressed data of the member have no
+        // meaning for the ELF file it contains.
+        let archive = Archive::open(zip.path()).unwrap();
+        let entry = archive.entries().next().unwrap().unwrap();
+        let src = symbolize::Source::Apk(symbolize::Apk::new(zip.path()));
+        let result = symbolizer
+            .symbolize_single(&src, symbolize::Input::FileOffset(entry.data_offset))
+            .unwrap();
+        assert_eq!(
+            result,
+            symbolize::Symbolized::Unknown(symbolize::Reason::Unsupported)
+        );
+
+        // Offsets past the compressed data do not belong to the member.
+        let result = symbolizer
+            .symbolize_single(
+                &src,
+                symbolize::Input::FileOffset(entry.data_offset + entry.data.len() as u64),
+            )
+            .unwrap();
+        assert_eq!(result.into_sym(), None);
+    }
+
+    /// Check that we can read the entries of ZIP64 archives whose
+    /// entries have trailing data descriptors, with and without data
+    /// prepended.
+    #[test]
+    fn zip64_entry_reading() {
+        let data_dir = Path::new(&env!("CARGO_MANIFEST_DIR")).join("data");
+        let so = read(data_dir.join("libtest-so.so")).unwrap();
+        let bin = read(data_dir.join("test-no-debug.bin")).unwrap();
+
+        for name in ["test-zip64.zip", "test-zip64-prefixed.zip"] {
+            let archive = Archive::open(data_dir.join(name)).unwrap();
+            let entries = archive.entries().collect::<Result<Vec<_>>>().unwrap();
+            assert_eq!(entries.len(), 2, "{name}");
+
+            let entry = &entries[0];
+            assert_eq!(entry.path, Path::new("zip-dir/test-no-debug.bin"));
+            assert!(!entry.is_compressed());
+            assert_eq!(entry.data, bin.as_slice());
+            assert_eq!(entry.uncompressed_size, bin.len() as u64);
+
+            let entry = &entries[1];
+            assert_eq!(entry.path, Path::new("libtest-so.so"));
+            assert_eq!(entry.data, so.as_slice());
+            assert_eq!(
+                entry.data,
+                archive
+                    .mmap
+                    .get(entry.data_offset as usize..entry.data_offset as usize + entry.data.len())
+                    .unwrap()
+            );
+        }
+    }
+
+    /// Check that we can symbolize an address inside a shared object
+    /// inside a prefixed ZIP64 APK.
+    #[test]
+    fn zip64_entry_symbolization() {
+        let data_dir = Path::new(&env!("CARGO_MANIFEST_DIR")).join("data");
+        let zip = data_dir.join("test-zip64-prefixed.zip");
+
+        let parser = ElfParser::open(&data_dir.join("libtest-so.so")).unwrap();
+        let opts = FindAddrOpts {
+            offset_in_file: true,
+            sym_type: SymType::Function,
+            binding: None,
+        };
+        let syms = parser.find_addr("the_answer", &opts).unwrap();
+        assert_eq!(syms.len(), 1);
+        let file_offset = syms[0].file_offset.unwrap();
+
+        let archive = Archive::open(&zip).unwrap();
+        let entry = archive
+            .entries()
+            .find(|entry| entry.as_ref().unwrap().path == Path::new("libtest-so.so"))
+            .unwrap()
+            .unwrap();
+
+        let src = symbolize::Source::Apk(symbolize::Apk::new(&zip));
+        let symbolizer = Symbolizer::new();
+        let sym = symbolizer
+            .symbolize_single(
+                &src,
+                symbolize::Input::FileOffset(entry.data_offset + file_offset),
+            )
+            .unwrap()
+            .into_sym()
+            .unwrap();
+        assert_eq!(sym.name, "the_answer");
+    }
+
     /// Check that we fail `Archive` creation for corrupted archives.
     #[test]
     fn zip_creation_corrupted() {
@@ -482,7 +972,7 @@ mod tests {
             .deref()
             .get(
                 ..archive.cd_offset as usize
-                    + usize::from(archive.cd_records) * size_of::<CdFileHeader>()
+                    + archive.cd_records as usize * size_of::<CdFileHeader>()
                     - 1,
             )
             .unwrap();
@@ -10,9 +10,14 @@ use std::alloc::System;
 use std::backtrace::Backtrace;
 use std::backtrace::BacktraceStatus;
 use std::cell::Cell;
+use std::env::current_exe;
 use std::hint::black_box;
+use std::rc::Rc;
 use std::thread_local;
 
+use blazesym::dwarf::AbbreviationsCacheStrategy;
+use blazesym::dwarf::DwarfResolver;
+use blazesym::elf::ElfParser;
 use blazesym::normalize::Normalizer;
 use blazesym::Addr;
 
@@ -85,3 +90,44 @@ fn normalize_process() {
     let stats = region.change();
     println!("Stats: {stats:#?}");
 }
+
+
+/// Check that caching only shared abbreviation tables allocates less
+/// up front than caching all of them.
+#[test]
+fn dwarf_abbrev_cache_strategies() {
+    // Our own test binary consists of a large number of compilation
+    // units, not all of which share abbreviation tables, making it a
+    // reasonable subject.
+    let exe = current_exe().unwrap();
+    let parser = Rc::new(ElfParser::open(&exe).unwrap());
+
+    let build = |strategy| {
+        let region = Region::new(&GLOBAL);
+        let resolver = DwarfResolver::builder()
+            .set_abbreviations_cache_strategy(strategy)
+            .build_from_parser(black_box(parser.clone()))
+            .unwrap();
+        let stats = region.change();
+        drop(resolver);
+        stats
+    };
+
+    // Create a resolver once without measuring, so that one-time
+    // allocations (e.g., of lazily loaded section data) do not skew
+    // the comparison.
+    let _stats = build(AbbreviationsCacheStrategy::default());
+
+    let duplicates = build(AbbreviationsCacheStrategy::Duplicates);
+    let all = build(AbbreviationsCacheStrategy::All);
+    // Tables referenced by only a single unit are parsed eagerly only
+    // when caching all of them.
+    assert!(
+        duplicates.allocations < all.allocations,
+        "{duplicates:#?} vs. {all:#?}"
+    );
+    assert!(
+        duplicates.bytes_allocated < all.bytes_allocated,
+        "{duplicates:#?} vs. {all:#?}"
+    );
+}
@@ -0,0 +1,48 @@
+#![cfg(feature = "backtrace")]
+
+use std::backtrace::BacktraceStatus;
+use std::env;
+use std::io;
+
+use blazesym::Error;
+use blazesym::ErrorExt as _;
+
+
+fn create_error() -> Error {
+    let err = io::Error::new(io::ErrorKind::NotFound, "some error");
+    Error::from(err).context("failed to do something")
+}
+
+
+/// Check that backtraces are captured only when requested.
+// Note that this test lives in a file of its own, because the toggle
+// is process wide and would interfere with other tests checking for
+// exact error output.
+#[test]
+fn backtrace_capture_toggle() {
+    // `std` caches the environment configuration once the first
+    // backtrace got captured, so make sure to clear it out before
+    // doing anything else.
+    let () = env::remove_var("RUST_BACKTRACE");
+    let () = env::remove_var("RUST_LIB_BACKTRACE");
+
+    let err = create_error();
+    let backtrace = err.backtrace().unwrap();
+    assert_ne!(backtrace.status(), BacktraceStatus::Captured);
+    assert!(!format!("{err:?}").contains("Stack backtrace:"));
+
+    let () = Error::force_backtrace_capture(true);
+    let err = create_error();
+    let backtrace = err.backtrace().unwrap();
+    assert_eq!(backtrace.status(), BacktraceStatus::Captured);
+
+    let debug = format!("{err:?}");
+    let cause = debug.find("Caused by:").unwrap();
+    let trace = debug.find("Stack backtrace:").unwrap();
+    assert!(cause < trace, "{debug}");
+
+    let () = Error::force_backtrace_capture(false);
+    let err = create_error();
+    let backtrace = err.backtrace().unwrap();
+    assert_ne!(backtrace.status(), BacktraceStatus::Captured);
+}
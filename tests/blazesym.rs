@@ -4,32 +4,53 @@
     clippy::let_unit_value
 )]
 
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::env;
 use std::ffi::CString;
 use std::ffi::OsStr;
+use std::fs::copy;
+use std::fs::create_dir_all;
 use std::fs::read as read_file;
+use std::fs::remove_file;
+use std::fs::write;
+use std::fs::File;
 use std::io::Error;
 use std::io::Read as _;
 use std::io::Write as _;
 use std::ops::Deref as _;
 use std::os::unix::ffi::OsStringExt as _;
+use std::os::unix::fs::symlink;
+use std::os::unix::io::AsRawFd as _;
+use std::os::unix::io::OwnedFd;
 use std::path::Path;
+use std::path::PathBuf;
 use std::process::Command;
 use std::process::Stdio;
+use std::ptr;
 use std::str;
 
+use blazesym::elf::ElfParser;
 use blazesym::helper::read_elf_build_id;
 use blazesym::helper::ElfResolver;
 use blazesym::inspect;
 use blazesym::inspect::Inspector;
+use blazesym::inspect::LookupOpts;
+use blazesym::inspect::SymBinding;
+use blazesym::inspect::TypeInfo;
+use blazesym::normalize;
 use blazesym::normalize::Normalizer;
+use blazesym::sym;
 use blazesym::symbolize;
 use blazesym::symbolize::ProcessDispatch;
 use blazesym::symbolize::ProcessMemberInfo;
 use blazesym::symbolize::ProcessMemberType;
+use blazesym::symbolize::Provenance;
 use blazesym::symbolize::Reason;
 use blazesym::symbolize::Resolve;
+use blazesym::symbolize::SrcLang;
+use blazesym::symbolize::Sym;
+use blazesym::symbolize::SymFormat;
 use blazesym::symbolize::Symbolized;
 use blazesym::symbolize::Symbolizer;
 use blazesym::Addr;
@@ -43,6 +64,7 @@ use libc::SIGKILL;
 
 use scopeguard::defer;
 
+use tempfile::tempdir;
 use tempfile::NamedTempFile;
 
 use test_log::test;
@@ -144,6 +166,12 @@ fn symbolize_elf_dwarf_gsym() {
         test(src, true);
     }
 
+    let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+        .join("data")
+        .join("test-stable-addrs-stripped-elf-with-dwarf.bin");
+    let src = symbolize::Source::from(symbolize::Dwarf::new(path));
+    test(src, true);
+
     let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
         .join("data")
         .join("test-stable-addrs.gsym");
@@ -155,6 +183,424 @@ fn symbolize_elf_dwarf_gsym() {
     test(src, true);
 }
 
+/// Symbolize the provided addresses and check that the offset of each
+/// found symbol is relative to the reported symbol address.
+fn check_offset_consistency<'addrs>(
+    src: symbolize::Source,
+    addrs: &'addrs [Addr],
+    input: fn(&'addrs [Addr]) -> symbolize::Input<&'addrs [Addr]>,
+) {
+    let symbolizer = Symbolizer::new();
+    let results = symbolizer.symbolize(&src, input(addrs)).unwrap();
+    assert_eq!(results.len(), addrs.len());
+
+    let mut found = 0;
+    for (addr, symbolized) in addrs.iter().zip(results) {
+        if let Symbolized::Sym(sym) = symbolized {
+            assert_eq!(sym.addr + sym.offset as Addr, *addr, "{sym:?}");
+            if let Some(size) = sym.size {
+                assert!(sym.offset < size, "{sym:?}");
+            }
+            found += 1;
+        }
+    }
+    assert_ne!(found, 0);
+}
+
+/// Check that the reported symbol offset is relative to the reported
+/// symbol address for all symbolization sources.
+#[test]
+fn symbolize_offset_consistency() {
+    let data_dir = Path::new(&env!("CARGO_MANIFEST_DIR")).join("data");
+    // The `.text` section of the test binary is located at 0x2000000
+    // and 0x213 bytes in size.
+    let addrs = (0x2000000..0x2000213).collect::<Vec<Addr>>();
+
+    for (file, debug_syms) in [
+        ("test-stable-addrs-no-dwarf.bin", false),
+        ("test-stable-addrs.bin", false),
+        ("test-stable-addrs.bin", true),
+        ("test-stable-addrs-lto.bin", true),
+    ] {
+        let mut elf = symbolize::Elf::new(data_dir.join(file));
+        elf.debug_syms = debug_syms;
+        check_offset_consistency(
+            symbolize::Source::Elf(elf),
+            &addrs,
+            symbolize::Input::VirtOffset,
+        );
+    }
+
+    let src = symbolize::Source::from(symbolize::Dwarf::new(
+        data_dir.join("test-stable-addrs-stripped-elf-with-dwarf.bin"),
+    ));
+    check_offset_consistency(src, &addrs, symbolize::Input::VirtOffset);
+
+    let src = symbolize::Source::from(symbolize::GsymFile::new(
+        data_dir.join("test-stable-addrs.gsym"),
+    ));
+    check_offset_consistency(src, &addrs, symbolize::Input::VirtOffset);
+
+    // For the kernel we use kallsyms as well as the test binary as
+    // "kernel image", with a KASLR offset applied.
+    let mut kallsyms = NamedTempFile::new().unwrap();
+    let () = writeln!(kallsyms, "0000000012000000 T main").unwrap();
+    let () = writeln!(kallsyms, "ffffffffc0000000 t module_fn [module]").unwrap();
+    let () = writeln!(kallsyms, "ffffffffc0000100 t module_fn2 [module]").unwrap();
+    let kernel = symbolize::Kernel {
+        kallsyms: Some(kallsyms.path().to_path_buf()),
+        kernel_image: Some(data_dir.join("test-stable-addrs.bin")),
+        kaslr_offset: Some(0x10000000),
+        ..Default::default()
+    };
+    let addrs = (0x12000000..0x12000213)
+        .chain(0xffffffffc0000000..0xffffffffc0000200)
+        .collect::<Vec<Addr>>();
+    check_offset_consistency(
+        symbolize::Source::Kernel(kernel),
+        &addrs,
+        symbolize::Input::AbsAddr,
+    );
+}
+
+/// Check that the reported symbol offset is relative to the reported
+/// symbol address when using Breakpad.
+#[test]
+fn symbolize_breakpad_offset_consistency() {
+    let content = br#"MODULE Linux x86_64 C00D0279606DFBCD53805DDAD2CA66A30 test-stable-addrs.bin
+FILE 0 data/test-stable-addrs.c
+INLINE_ORIGIN 0 factorial_inline_wrapper
+PUBLIC 0 0 main
+FUNC 34 20 0 factorial_wrapper
+INLINE 0 20 0 0 40 8
+34 c 4 0
+40 8 5 0
+48 c 6 0
+FUNC 100 2b 0 factorial
+100 2b 10 0
+"#;
+
+    let mut tmpfile = NamedTempFile::new().unwrap();
+    let () = tmpfile.write_all(content).unwrap();
+
+    let src = symbolize::Source::Breakpad(symbolize::Breakpad::new(tmpfile.path()));
+    let offsets = (0..0x130).collect::<Vec<Addr>>();
+    check_offset_consistency(src, &offsets, symbolize::Input::FileOffset);
+}
+
+/// Check that we can symbolize file offsets in a 32 bit ELF file.
+#[test]
+fn symbolize_elf32_file_offset() {
+    let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+        .join("data")
+        .join("libtest-so-32.so");
+
+    let src = inspect::Source::Elf(inspect::Elf::new(&path));
+    let inspector = Inspector::new();
+    let results = inspector.lookup(&src, &["the_answer"]).unwrap();
+    assert_eq!(results[0].len(), 1);
+    let the_answer = &results[0][0];
+    let file_offset = the_answer.file_offset.unwrap();
+
+    let src = symbolize::Source::Elf(symbolize::Elf::new(&path));
+    let symbolizer = Symbolizer::new();
+    let sym = symbolizer
+        .symbolize_single(&src, symbolize::Input::FileOffset(file_offset + 2))
+        .unwrap()
+        .into_sym()
+        .unwrap();
+    assert_eq!(sym.name, "the_answer");
+    assert_eq!(sym.addr, the_answer.addr);
+    assert_eq!(sym.offset, 2);
+
+    let build_id = read_elf_build_id(&path).unwrap().unwrap();
+    assert_eq!(build_id.len(), 20);
+}
+
+/// Check that we can symbolize addresses in a 32 bit ELF file using
+/// its (compressed) DWARF debug information.
+#[test]
+fn symbolize_elf32_dwarf() {
+    let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+        .join("data")
+        .join("libtest-so-32-dwarf.so");
+
+    let src = symbolize::Source::Elf(symbolize::Elf::new(&path));
+    let symbolizer = Symbolizer::new();
+    let sym = symbolizer
+        .symbolize_single(&src, symbolize::Input::VirtOffset(0x1016))
+        .unwrap()
+        .into_sym()
+        .unwrap();
+    assert_eq!(sym.name, "the_weak_answer");
+    assert_eq!(sym.addr, 0x1014);
+    assert_eq!(sym.offset, 2);
+    assert!(!sym.is_synthetic);
+    let code_info = sym.code_info.unwrap();
+    assert_eq!(code_info.file, OsStr::new("test-so-32.c"));
+    assert_eq!(code_info.line, Some(8));
+}
+
+
+/// Check that we report the compilation unit a symbol originates from.
+#[test]
+fn symbolize_dwarf_compilation_unit() {
+    let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+        .join("data")
+        .join("test-stable-addrs.bin");
+    let symbolizer = Symbolizer::new();
+
+    let src = symbolize::Source::Elf(symbolize::Elf::new(&path));
+    // `factorial` is defined in `test-stable-addrs.c` while `foo` is
+    // defined in `test-stable-addrs-cu2.c`.
+    for (addr, name, cu) in [
+        (0x2000100, "factorial", "test-stable-addrs.c"),
+        (0x200005f, "foo", "test-stable-addrs-cu2.c"),
+    ] {
+        let sym = symbolizer
+            .symbolize_single(&src, symbolize::Input::VirtOffset(addr))
+            .unwrap()
+            .into_sym()
+            .unwrap();
+        assert_eq!(sym.name, name);
+        let compilation_unit = sym.compilation_unit.unwrap();
+        assert!(compilation_unit.is_absolute(), "{compilation_unit:?}");
+        assert_eq!(compilation_unit.file_name(), Some(OsStr::new(cu)));
+    }
+
+    // Without DWARF we cannot know the compilation unit.
+    let mut elf = symbolize::Elf::new(&path);
+    elf.debug_syms = false;
+    let src = symbolize::Source::Elf(elf);
+    let sym = symbolizer
+        .symbolize_single(&src, symbolize::Input::VirtOffset(0x2000100))
+        .unwrap()
+        .into_sym()
+        .unwrap();
+    assert_eq!(sym.name, "factorial");
+    assert_eq!(sym.compilation_unit, None);
+}
+
+/// Check that we report the section containing a symbol.
+#[test]
+fn symbolize_elf_section() {
+    let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+        .join("data")
+        .join("test-stable-addrs.bin");
+    let symbolizer = Symbolizer::new();
+
+    for debug_syms in [false, true] {
+        let mut elf = symbolize::Elf::new(&path);
+        elf.debug_syms = debug_syms;
+        let src = symbolize::Source::Elf(elf);
+        for (addr, name) in [
+            (0x2000100, "factorial"),
+            (0x2000200, "factorial_inline_test"),
+        ] {
+            let sym = symbolizer
+                .symbolize_single(&src, symbolize::Input::VirtOffset(addr))
+                .unwrap()
+                .into_sym()
+                .unwrap();
+            assert_eq!(sym.name, name);
+            assert_eq!(sym.section.as_deref(), Some(".text"), "{debug_syms}");
+        }
+    }
+
+    // Gsym files don't contain any section information.
+    let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+        .join("data")
+        .join("test-stable-addrs.gsym");
+    let src = symbolize::Source::from(symbolize::GsymFile::new(path));
+    let sym = symbolizer
+        .symbolize_single(&src, symbolize::Input::VirtOffset(0x2000100))
+        .unwrap()
+        .into_sym()
+        .unwrap();
+    assert_eq!(sym.name, "factorial");
+    assert_eq!(sym.section, None);
+}
+
+/// Check that we flag symbols not backed by a DWARF function as
+/// synthetic.
+#[test]
+fn symbolize_synthetic_sym() {
+    let data_dir = Path::new(&env!("CARGO_MANIFEST_DIR")).join("data");
+    let symbolizer = Symbolizer::new();
+
+    for (file, debug_syms, is_synthetic) in [
+        ("test-stable-addrs.bin", true, false),
+        ("test-stable-addrs.bin", false, true),
+        ("test-stable-addrs-stripped-elf-with-dwarf.bin", true, false),
+        ("test-stable-addrs-no-dwarf.bin", true, true),
+    ] {
+        let mut elf = symbolize::Elf::new(data_dir.join(file));
+        elf.debug_syms = debug_syms;
+        let src = symbolize::Source::Elf(elf);
+        let sym = symbolizer
+            .symbolize_single(&src, symbolize::Input::VirtOffset(0x2000100))
+            .unwrap()
+            .into_sym()
+            .unwrap();
+        assert_eq!(sym.name, "factorial", "{file}");
+        assert_eq!(sym.is_synthetic, is_synthetic, "{file}/{debug_syms}");
+    }
+}
+
+/// Check that a `Dwarf` source solely consults DWARF debug information.
+#[test]
+fn symbolize_dwarf_only() {
+    let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+        .join("data")
+        .join("test-stable-addrs.bin");
+    let symbolizer = Symbolizer::new();
+    // `dummy` is covered by an ELF symbol, but not by any DWARF
+    // function.
+    let addr = 0x20000a8;
+
+    let src = symbolize::Source::from(symbolize::Elf::new(&path));
+    let result = symbolizer
+        .symbolize_single(&src, symbolize::Input::VirtOffset(addr))
+        .unwrap()
+        .into_sym()
+        .unwrap();
+    assert_eq!(result.name, "dummy");
+
+    let src = symbolize::Source::from(symbolize::Dwarf::new(&path));
+    let result = symbolizer
+        .symbolize_single(&src, symbolize::Input::VirtOffset(addr))
+        .unwrap();
+    assert!(result.into_sym().is_none());
+
+    let result = symbolizer
+        .symbolize_single(&src, symbolize::Input::VirtOffset(0x2000100))
+        .unwrap()
+        .into_sym()
+        .unwrap();
+    assert_eq!(result.name, "factorial");
+
+    let err = symbolizer
+        .symbolize_single(&src, symbolize::Input::FileOffset(0x100))
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unsupported);
+}
+
+/// Check that we can symbolize addresses in a Go binary lacking DWARF
+/// debug information, based on its PC-line table.
+///
+/// The test binary is only built if a Go toolchain is available.
+#[cfg(feature = "go")]
+#[test]
+fn symbolize_go_pclntab() {
+    let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+        .join("data")
+        .join("test-go.bin");
+    if !path.exists() {
+        return
+    }
+
+    let src = inspect::Source::Elf(inspect::Elf::new(&path));
+    let inspector = Inspector::new();
+    let results = inspector
+        .lookup(&src, &["main.fibonacci"])
+        .unwrap()
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+    assert_eq!(results.len(), 1);
+    let addr = results[0].addr;
+
+    let src = symbolize::Source::Elf(symbolize::Elf::new(&path));
+    let symbolizer = Symbolizer::new();
+    let sym = symbolizer
+        .symbolize_single(&src, symbolize::Input::VirtOffset(addr))
+        .unwrap()
+        .into_sym()
+        .unwrap();
+    assert_eq!(sym.name, "main.fibonacci");
+    assert_eq!(sym.addr, addr);
+    assert!(!sym.is_synthetic);
+    let code_info = sym.code_info.unwrap();
+    assert_eq!(code_info.file, OsStr::new("test.go"));
+    assert_eq!(code_info.line, Some(8));
+}
+
+/// Check that we can symbolize addresses in a stripped binary using
+/// debug information from a separate file.
+#[test]
+fn symbolize_with_extra_debug_info() {
+    let data_dir = Path::new(&env!("CARGO_MANIFEST_DIR")).join("data");
+    let debug_src = symbolize::Source::from(symbolize::Dwarf::new(
+        data_dir.join("test-stable-addrs-stripped-elf-with-dwarf.bin"),
+    ));
+    let symbolizer = Symbolizer::new();
+
+    let src = symbolize::Source::from(symbolize::Elf::new(
+        data_dir.join("test-stable-addrs-stripped.bin"),
+    ));
+    let results = symbolizer
+        .symbolize_with_extra_debug_info(&src, &[0x2000100], &debug_src)
+        .unwrap();
+    assert_eq!(results.len(), 1);
+    let sym = results[0].as_sym().unwrap();
+    assert_eq!(sym.name, "factorial");
+    let code_info = sym.code_info.as_ref().unwrap();
+    assert_eq!(code_info.file, OsStr::new("test-stable-addrs.c"));
+
+    // `dummy` is only covered by an ELF symbol, which has to be
+    // retrieved from the primary source.
+    let src = symbolize::Source::from(symbolize::Elf::new(
+        data_dir.join("test-stable-addrs-no-dwarf.bin"),
+    ));
+    let results = symbolizer
+        .symbolize_with_extra_debug_info(&src, &[0x20000a8, 0x2000100], &debug_src)
+        .unwrap();
+    assert_eq!(results[0].as_sym().unwrap().name, "dummy");
+    let sym = results[1].as_sym().unwrap();
+    assert_eq!(sym.name, "factorial");
+    assert!(sym.code_info.is_some());
+
+    let err = symbolizer
+        .symbolize_with_extra_debug_info(&debug_src, &[0x2000100], &src)
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unsupported);
+}
+
+/// Check that symbolization results are unaffected by the on-disk DWARF
+/// cache, both when populating and when using it.
+#[test]
+fn symbolize_dwarf_cache_dir() {
+    let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+        .join("data")
+        .join("test-stable-addrs.bin");
+    let src = symbolize::Source::from(symbolize::Elf::new(&path));
+    let cache_dir = tempdir().unwrap();
+    let cache_dir = cache_dir.path().join("cache");
+
+    let symbolize = || {
+        let symbolizer = Symbolizer::builder().set_cache_dir(&cache_dir).build();
+        let result = symbolizer
+            .symbolize_single(&src, symbolize::Input::VirtOffset(0x2000100))
+            .unwrap()
+            .into_sym()
+            .unwrap();
+        assert_eq!(result.name, "factorial");
+        let code_info = result.code_info.unwrap();
+        assert_eq!(code_info.file, OsStr::new("test-stable-addrs.c"));
+        assert_ne!(code_info.line, None);
+    };
+
+    // The first run should populate the cache...
+    let () = symbolize();
+    let entries = cache_dir.read_dir().unwrap().count();
+    assert_eq!(entries, 1);
+
+    // ... and the second use it.
+    let () = symbolize();
+    let entries = cache_dir.read_dir().unwrap().count();
+    assert_eq!(entries, 1);
+}
+
 /// Check that we can symbolize an address using Breakpad.
 #[test]
 fn symbolize_breakpad() {
@@ -206,98 +652,1271 @@ fn symbolize_breakpad() {
     }
 }
 
-/// Make sure that Breakpad symbol file errors are reported in a
-/// somewhat decent fashion.
-#[test]
-fn symbolize_breakpad_error() {
-    let content = br#"MODULE Linux x86_64 C00D0279606DFBCD53805DDAD2CA66A30 test-stable-addrs.bin
-FILE 0 data/test-stable-addrs-cu2.c
-PUBLIC 0 0 main
-FUNC 34 11 0 factorial_wrapper
-34 XXX-this-does-not-belong-here-XXX 4 0
-38 a 5 0
-42 3 6 0
-"#;
+/// Pad `name` with NUL bytes to the 16 bytes used for segment and
+/// section names in Mach-O load commands.
+fn name16(name: &str) -> [u8; 16] {
+    let mut buf = [0; 16];
+    let () = buf[..name.len()].copy_from_slice(name.as_bytes());
+    buf
+}
 
-    let mut tmpfile = NamedTempFile::new().unwrap();
-    let () = tmpfile.write_all(content).unwrap();
+/// Create a Mach-O file containing the DWARF sections of the ELF file
+/// at `path` in its `__DWARF` segment, akin to what is found in dSYM
+/// bundles.
+fn macho_from_elf(path: &Path) -> NamedTempFile {
+    const LC_SEGMENT_64: u32 = 0x19;
+    const MH_DSYM: u32 = 0xa;
+    const SEGMENT_SIZE: usize = 72;
+    const SECTION_SIZE: usize = 80;
+
+    let parser = ElfParser::open(path).unwrap();
+    let sections = [
+        ".debug_abbrev",
+        ".debug_addr",
+        ".debug_aranges",
+        ".debug_info",
+        ".debug_line",
+        ".debug_line_str",
+        ".debug_ranges",
+        ".debug_rnglists",
+        ".debug_str",
+        ".debug_str_offsets",
+    ]
+    .into_iter()
+    .filter_map(|name| {
+        let idx = parser.find_section(name).unwrap()?;
+        let data = parser.section_data(idx).unwrap().to_vec();
+        let name = format!("__{}", &name[1..]);
+        Some((name, data))
+    })
+    .collect::<Vec<_>>();
+
+    let cmds_size = 2 * SEGMENT_SIZE + sections.len() * SECTION_SIZE;
+    let data_start = 32 + cmds_size;
+    let data_size = sections.iter().map(|(_, data)| data.len()).sum::<usize>();
+
+    let mut macho = Vec::new();
+    // The Mach-O header.
+    let () = macho.extend_from_slice(&0xfeedfacf_u32.to_ne_bytes());
+    let () = macho.extend_from_slice(&0x01000007_u32.to_ne_bytes());
+    let () = macho.extend_from_slice(&3_u32.to_ne_bytes());
+    let () = macho.extend_from_slice(&MH_DSYM.to_ne_bytes());
+    let () = macho.extend_from_slice(&2_u32.to_ne_bytes());
+    let () = macho.extend_from_slice(&(cmds_size as u32).to_ne_bytes());
+    let () = macho.extend_from_slice(&[0; 8]);
+
+    let mut segment = |name, vmaddr: u64, vmsize: u64, fileoff, filesize, nsects: usize| {
+        let () = macho.extend_from_slice(&LC_SEGMENT_64.to_ne_bytes());
+        let size = SEGMENT_SIZE + nsects * SECTION_SIZE;
+        let () = macho.extend_from_slice(&(size as u32).to_ne_bytes());
+        let () = macho.extend_from_slice(&name16(name));
+        let () = macho.extend_from_slice(&vmaddr.to_ne_bytes());
+        let () = macho.extend_from_slice(&vmsize.to_ne_bytes());
+        let () = macho.extend_from_slice(&(fileoff as u64).to_ne_bytes());
+        let () = macho.extend_from_slice(&(filesize as u64).to_ne_bytes());
+        // maxprot, initprot, nsects, flags
+        let () = macho.extend_from_slice(&7_u32.to_ne_bytes());
+        let () = macho.extend_from_slice(&5_u32.to_ne_bytes());
+        let () = macho.extend_from_slice(&(nsects as u32).to_ne_bytes());
+        let () = macho.extend_from_slice(&0_u32.to_ne_bytes());
+    };
+    let () = segment("__TEXT", 0x2000000, 0x1000, 0, 0, 0);
+    let () = segment(
+        "__DWARF",
+        0,
+        data_size as u64,
+        data_start,
+        data_size,
+        sections.len(),
+    );
 
-    let src = symbolize::Source::Breakpad(symbolize::Breakpad::new(tmpfile.path()));
+    let mut offset = data_start;
+    for (name, data) in &sections {
+        let () = macho.extend_from_slice(&name16(name));
+        let () = macho.extend_from_slice(&name16("__DWARF"));
+        let () = macho.extend_from_slice(&0_u64.to_ne_bytes());
+        let () = macho.extend_from_slice(&(data.len() as u64).to_ne_bytes());
+        let () = macho.extend_from_slice(&(offset as u32).to_ne_bytes());
+        // align, reloff, nreloc, flags, reserved1-3
+        let () = macho.extend_from_slice(&[0; 28]);
+        offset += data.len();
+    }
+    assert_eq!(macho.len(), data_start);
+
+    for (_name, data) in &sections {
+        let () = macho.extend_from_slice(data);
+    }
+
+    let mut file = NamedTempFile::new().unwrap();
+    let () = file.write_all(&macho).unwrap();
+    file
+}
+
+/// Check that we can symbolize an address using DWARF information
+/// contained in a Mach-O file.
+#[test]
+fn symbolize_macho() {
+    let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+        .join("data")
+        .join("test-stable-addrs.bin");
+    let file = macho_from_elf(&path);
+
+    let src = symbolize::Source::from(symbolize::MachO::new(file.path()));
     let symbolizer = Symbolizer::new();
+    let result = symbolizer
+        .symbolize_single(&src, symbolize::Input::VirtOffset(0x2000104))
+        .unwrap()
+        .into_sym()
+        .unwrap();
+
+    assert_eq!(result.name, "factorial");
+    assert_eq!(result.addr, 0x2000100);
+    assert_eq!(result.offset, 4);
+    let code_info = result.code_info.as_ref().unwrap();
+    assert_eq!(code_info.file, OsStr::new("test-stable-addrs.c"));
+    assert!(code_info.line.is_some());
+
     let err = symbolizer
-        .symbolize_single(&src, symbolize::Input::FileOffset(0x100))
+        .symbolize_single(&src, symbolize::Input::AbsAddr(0x2000104))
         .unwrap_err();
-    assert!(format!("{err:?}").contains("34 XXX-this-does-not-belong-here-XXX 4 0"));
+    assert_eq!(err.kind(), ErrorKind::Unsupported);
+
+    let src = inspect::Source::from(inspect::MachO::new(file.path()));
+    let inspector = Inspector::new();
+    let results = inspector
+        .lookup(&src, &["factorial"])
+        .unwrap()
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].addr, 0x2000100);
+}
+
+/// The CPU type and sub-type of x86_64 Mach-O files.
+const CPU_TYPE_X86_64: u32 = 0x01000007;
+const CPU_SUBTYPE_X86_64_ALL: u32 = 0x3;
+/// The CPU type and sub-type of arm64 Mach-O files.
+const CPU_TYPE_ARM64: u32 = 0x0100000c;
+const CPU_SUBTYPE_ARM64_ALL: u32 = 0x0;
+
+/// Create a Mach-O executable containing a `__text` section at
+/// address 0x1000 (of size 0x100) along with an `nlist` symbol table
+/// defining the provided symbols in it.
+fn macho_with_symtab(
+    cputype: u32,
+    cpusubtype: u32,
+    uuid: [u8; 16],
+    syms: &[(&str, u64)],
+) -> Vec<u8> {
+    const LC_SEGMENT_64: u32 = 0x19;
+    const LC_SYMTAB: u32 = 0x2;
+    const LC_UUID: u32 = 0x1b;
+    const MH_EXECUTE: u32 = 0x2;
+    // N_SECT | N_EXT
+    const N_TYPE: u8 = 0xf;
+    // S_ATTR_PURE_INSTRUCTIONS | S_ATTR_SOME_INSTRUCTIONS
+    const TEXT_FLAGS: u32 = 0x80000400;
+
+    let cmds_size = (72 + 80) + 24 + 24;
+    let symoff = 32 + cmds_size;
+    let stroff = symoff + syms.len() * 16;
+
+    let mut strtab = vec![0];
+    let mut nlists = Vec::new();
+    for (name, addr) in syms {
+        let () = nlists.extend_from_slice(&(strtab.len() as u32).to_ne_bytes());
+        let () = nlists.push(N_TYPE);
+        // The (one-based) section index.
+        let () = nlists.push(1);
+        let () = nlists.extend_from_slice(&0_u16.to_ne_bytes());
+        let () = nlists.extend_from_slice(&addr.to_ne_bytes());
+        let () = strtab.extend_from_slice(format!("_{name}\0").as_bytes());
+    }
+
+    let mut macho = Vec::new();
+    // The Mach-O header.
+    let () = macho.extend_from_slice(&0xfeedfacf_u32.to_ne_bytes());
+    let () = macho.extend_from_slice(&cputype.to_ne_bytes());
+    let () = macho.extend_from_slice(&cpusubtype.to_ne_bytes());
+    let () = macho.extend_from_slice(&MH_EXECUTE.to_ne_bytes());
+    let () = macho.extend_from_slice(&3_u32.to_ne_bytes());
+    let () = macho.extend_from_slice(&(cmds_size as u32).to_ne_bytes());
+    let () = macho.extend_from_slice(&[0; 8]);
+
+    // The `__TEXT` segment and its `__text` section.
+    let () = macho.extend_from_slice(&LC_SEGMENT_64.to_ne_bytes());
+    let () = macho.extend_from_slice(&(72_u32 + 80).to_ne_bytes());
+    let () = macho.extend_from_slice(&name16("__TEXT"));
+    let () = macho.extend_from_slice(&0x1000_u64.to_ne_bytes());
+    let () = macho.extend_from_slice(&0x1000_u64.to_ne_bytes());
+    let () = macho.extend_from_slice(&0_u64.to_ne_bytes());
+    let () = macho.extend_from_slice(&0_u64.to_ne_bytes());
+    // maxprot, initprot, nsects, flags
+    let () = macho.extend_from_slice(&7_u32.to_ne_bytes());
+    let () = macho.extend_from_slice(&5_u32.to_ne_bytes());
+    let () = macho.extend_from_slice(&1_u32.to_ne_bytes());
+    let () = macho.extend_from_slice(&0_u32.to_ne_bytes());
+
+    let () = macho.extend_from_slice(&name16("__text"));
+    let () = macho.extend_from_slice(&name16("__TEXT"));
+    let () = macho.extend_from_slice(&0x1000_u64.to_ne_bytes());
+    let () = macho.extend_from_slice(&0x100_u64.to_ne_bytes());
+    // offset, align, reloff, nreloc
+    let () = macho.extend_from_slice(&[0; 16]);
+    let () = macho.extend_from_slice(&TEXT_FLAGS.to_ne_bytes());
+    // reserved1-3
+    let () = macho.extend_from_slice(&[0; 12]);
+
+    let () = macho.extend_from_slice(&LC_SYMTAB.to_ne_bytes());
+    let () = macho.extend_from_slice(&24_u32.to_ne_bytes());
+    let () = macho.extend_from_slice(&(symoff as u32).to_ne_bytes());
+    let () = macho.extend_from_slice(&(syms.len() as u32).to_ne_bytes());
+    let () = macho.extend_from_slice(&(stroff as u32).to_ne_bytes());
+    let () = macho.extend_from_slice(&(strtab.len() as u32).to_ne_bytes());
+
+    let () = macho.extend_from_slice(&LC_UUID.to_ne_bytes());
+    let () = macho.extend_from_slice(&24_u32.to_ne_bytes());
+    let () = macho.extend_from_slice(&uuid);
+    assert_eq!(macho.len(), symoff);
+
+    let () = macho.extend_from_slice(&nlists);
+    let () = macho.extend_from_slice(&strtab);
+    macho
+}
+
+/// Wrap the provided Mach-O files in a universal binary.
+fn macho_fat(slices: &[(u32, u32, Vec<u8>)]) -> Vec<u8> {
+    const ALIGN: usize = 0x1000;
+
+    let mut fat = Vec::new();
+    let () = fat.extend_from_slice(&0xcafebabe_u32.to_be_bytes());
+    let () = fat.extend_from_slice(&(slices.len() as u32).to_be_bytes());
+
+    let mut offset = ALIGN;
+    for (cputype, cpusubtype, data) in slices {
+        let () = fat.extend_from_slice(&cputype.to_be_bytes());
+        let () = fat.extend_from_slice(&cpusubtype.to_be_bytes());
+        let () = fat.extend_from_slice(&(offset as u32).to_be_bytes());
+        let () = fat.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        let () = fat.extend_from_slice(&(ALIGN.trailing_zeros()).to_be_bytes());
+        offset += (data.len() + ALIGN - 1) / ALIGN * ALIGN;
+    }
+
+    for (_cputype, _cpusubtype, data) in slices {
+        let () = fat.resize((fat.len() + ALIGN - 1) / ALIGN * ALIGN, 0);
+        let () = fat.extend_from_slice(data);
+    }
+    fat
+}
+
+/// Check that we can symbolize addresses using the symbol table of a
+/// Mach-O file.
+#[test]
+fn symbolize_macho_symtab() {
+    let macho = macho_with_symtab(
+        CPU_TYPE_X86_64,
+        CPU_SUBTYPE_X86_64_ALL,
+        [0; 16],
+        &[("foo", 0x1000), ("bar", 0x1040)],
+    );
+    let mut file = NamedTempFile::new().unwrap();
+    let () = file.write_all(&macho).unwrap();
+
+    let src = symbolize::Source::from(symbolize::MachO::new(file.path()));
+    let symbolizer = Symbolizer::new();
+    let result = symbolizer
+        .symbolize_single(&src, symbolize::Input::VirtOffset(0x1050))
+        .unwrap()
+        .into_sym()
+        .unwrap();
+    assert_eq!(result.name, "bar");
+    assert_eq!(result.addr, 0x1040);
+    assert_eq!(result.offset, 0x10);
+    assert_eq!(result.size, None);
+    assert_eq!(result.code_info, None);
+
+    // The address is past the end of the `__text` section.
+    let result = symbolizer
+        .symbolize_single(&src, symbolize::Input::VirtOffset(0x1100))
+        .unwrap();
+    assert_eq!(result, Symbolized::Unknown(Reason::UnknownAddr));
+
+    let src = inspect::Source::from(inspect::MachO::new(file.path()));
+    let inspector = Inspector::new();
+    let results = inspector
+        .lookup(&src, &["foo", "baz"])
+        .unwrap()
+        .into_iter()
+        .collect::<Vec<_>>();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].len(), 1);
+    assert_eq!(results[0][0].addr, 0x1000);
+    assert_eq!(results[0][0].size, 0x40);
+    assert_eq!(results[0][0].sym_type, SymType::Function);
+    assert!(results[1].is_empty());
+}
+
+/// Check that we can select the architecture to work with in a
+/// universal Mach-O binary.
+#[test]
+fn symbolize_macho_fat() {
+    let fat = macho_fat(&[
+        (
+            CPU_TYPE_X86_64,
+            CPU_SUBTYPE_X86_64_ALL,
+            macho_with_symtab(
+                CPU_TYPE_X86_64,
+                CPU_SUBTYPE_X86_64_ALL,
+                [0; 16],
+                &[("foo", 0x1000)],
+            ),
+        ),
+        (
+            CPU_TYPE_ARM64,
+            CPU_SUBTYPE_ARM64_ALL,
+            macho_with_symtab(
+                CPU_TYPE_ARM64,
+                CPU_SUBTYPE_ARM64_ALL,
+                [0; 16],
+                &[("bar", 0x1000)],
+            ),
+        ),
+    ]);
+    let mut file = NamedTempFile::new().unwrap();
+    let () = file.write_all(&fat).unwrap();
+
+    let symbolizer = Symbolizer::new();
+    let symbolize = |arch: Option<&str>| {
+        let mut macho = symbolize::MachO::new(file.path());
+        macho.arch = arch.map(str::to_string);
+        let src = symbolize::Source::from(macho);
+        symbolizer
+            .symbolize_single(&src, symbolize::Input::VirtOffset(0x1010))
+            .map(|symbolized| symbolized.into_sym().unwrap().name.into_owned())
+    };
+
+    assert_eq!(symbolize(Some("x86_64")).unwrap(), "foo");
+    assert_eq!(symbolize(Some("arm64")).unwrap(), "bar");
+
+    // Without an architecture being selected we can't decide which
+    // slice to use.
+    let err = symbolize(None).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+    let err = symbolize(Some("i386")).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::NotFound);
+
+    let err = symbolize(Some("not-an-arch")).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+    let inspector = Inspector::new();
+    let mut macho = inspect::MachO::new(file.path());
+    macho.arch = Some("arm64".to_string());
+    let src = inspect::Source::from(macho);
+    let results = inspector
+        .lookup(&src, &["bar"])
+        .unwrap()
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].addr, 0x1000);
+}
+
+/// Check that we can use DWARF debug information from a dSYM bundle
+/// when symbolizing a Mach-O file.
+#[test]
+fn symbolize_macho_dsym() {
+    let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+        .join("data")
+        .join("test-stable-addrs.bin");
+    let dwarf = macho_from_elf(&path);
+
+    let dir = tempdir().unwrap();
+    let dsym = dir.path().join("test.dSYM");
+    let dwarf_dir = dsym.join("Contents").join("Resources").join("DWARF");
+    let () = create_dir_all(&dwarf_dir).unwrap();
+    let _cnt = copy(dwarf.path(), dwarf_dir.join("test")).unwrap();
+
+    let macho = macho_with_symtab(CPU_TYPE_X86_64, CPU_SUBTYPE_X86_64_ALL, [0; 16], &[]);
+    let mut file = NamedTempFile::new().unwrap();
+    let () = file.write_all(&macho).unwrap();
+
+    let symbolizer = Symbolizer::new();
+    let mut src = symbolize::MachO::new(file.path());
+    let result = symbolizer
+        .symbolize_single(
+            &symbolize::Source::from(src.clone()),
+            symbolize::Input::VirtOffset(0x2000104),
+        )
+        .unwrap();
+    assert_eq!(result, Symbolized::Unknown(Reason::MissingSyms));
+
+    src.dsym = Some(dsym);
+    let result = symbolizer
+        .symbolize_single(
+            &symbolize::Source::from(src),
+            symbolize::Input::VirtOffset(0x2000104),
+        )
+        .unwrap()
+        .into_sym()
+        .unwrap();
+    assert_eq!(result.name, "factorial");
+    assert_eq!(result.addr, 0x2000100);
+    let code_info = result.code_info.as_ref().unwrap();
+    assert_eq!(code_info.file, OsStr::new("test-stable-addrs.c"));
+    assert!(code_info.line.is_some());
+}
+
+/// Create a minimal 64 bit PE DLL exporting the functions
+/// `the_answer` (at RVA 0x1000) and `the_question` (at RVA 0x1010),
+/// referencing `test-pe.pdb` with the given age.
+///
+/// The GUID matches the one of the PDB file generated from
+/// `data/test-pe.pdb.yaml`.
+fn pe_dll(age: u32) -> Vec<u8> {
+    const GUID: [u8; 16] = [
+        0x67, 0x45, 0x23, 0x01, 0xab, 0x89, 0xef, 0xcd, 0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd,
+        0xef,
+    ];
+
+    fn put(pe: &mut [u8], offset: usize, data: &[u8]) {
+        let () = pe[offset..offset + data.len()].copy_from_slice(data);
+    }
+
+    let mut pe = vec![0; 0x600];
+    // The DOS header, referencing the PE header.
+    let () = put(&mut pe, 0, b"MZ");
+    let () = put(&mut pe, 0x3c, &0x40_u32.to_le_bytes());
+    let () = put(&mut pe, 0x40, b"PE\0\0");
+
+    // The COFF header: machine, number of sections, size of optional
+    // header, and characteristics (executable, large address aware,
+    // DLL).
+    let coff = 0x44;
+    let () = put(&mut pe, coff, &0x8664_u16.to_le_bytes());
+    let () = put(&mut pe, coff + 2, &2_u16.to_le_bytes());
+    let () = put(&mut pe, coff + 16, &240_u16.to_le_bytes());
+    let () = put(&mut pe, coff + 18, &0x2022_u16.to_le_bytes());
+
+    // The PE32+ optional header.
+    let opt = coff + 20;
+    let () = put(&mut pe, opt, &0x20b_u16.to_le_bytes());
+    let () = put(&mut pe, opt + 4, &0x200_u32.to_le_bytes());
+    let () = put(&mut pe, opt + 20, &0x1000_u32.to_le_bytes());
+    let () = put(&mut pe, opt + 24, &0x180000000_u64.to_le_bytes());
+    let () = put(&mut pe, opt + 32, &0x1000_u32.to_le_bytes());
+    let () = put(&mut pe, opt + 36, &0x200_u32.to_le_bytes());
+    let () = put(&mut pe, opt + 40, &6_u16.to_le_bytes());
+    let () = put(&mut pe, opt + 48, &6_u16.to_le_bytes());
+    let () = put(&mut pe, opt + 56, &0x3000_u32.to_le_bytes());
+    let () = put(&mut pe, opt + 60, &0x200_u32.to_le_bytes());
+    // Subsystem: Windows GUI.
+    let () = put(&mut pe, opt + 68, &2_u16.to_le_bytes());
+    let () = put(&mut pe, opt + 108, &16_u32.to_le_bytes());
+    // The export and debug data directories.
+    let () = put(&mut pe, opt + 112, &0x2000_u32.to_le_bytes());
+    let () = put(&mut pe, opt + 116, &0x60_u32.to_le_bytes());
+    let () = put(&mut pe, opt + 112 + 6 * 8, &0x2060_u32.to_le_bytes());
+    let () = put(&mut pe, opt + 116 + 6 * 8, &28_u32.to_le_bytes());
+
+    // The section headers.
+    let mut section = |idx: usize, name: &[u8], rva: u32, offset: u32, flags: u32| {
+        let hdr = opt + 240 + idx * 40;
+        let () = put(&mut pe, hdr, name);
+        let () = put(&mut pe, hdr + 8, &0x100_u32.to_le_bytes());
+        let () = put(&mut pe, hdr + 12, &rva.to_le_bytes());
+        let () = put(&mut pe, hdr + 16, &0x200_u32.to_le_bytes());
+        let () = put(&mut pe, hdr + 20, &offset.to_le_bytes());
+        let () = put(&mut pe, hdr + 36, &flags.to_le_bytes());
+    };
+    let () = section(0, b".text", 0x1000, 0x200, 0x60000020);
+    let () = section(1, b".rdata", 0x2000, 0x400, 0x40000040);
+
+    // The code: `the_answer` returns 42, `the_question` returns 6 * 9.
+    let () = put(&mut pe, 0x200, &[0xb8, 0x2a, 0x00, 0x00, 0x00, 0xc3]);
+    let () = put(&mut pe, 0x210, &[0xb8, 0x36, 0x00, 0x00, 0x00, 0xc3]);
+
+    // The export directory and the tables it references.
+    let rdata = |rva: usize| rva - 0x2000 + 0x400;
+    let () = put(&mut pe, rdata(0x200c), &0x203c_u32.to_le_bytes());
+    let () = put(&mut pe, rdata(0x2010), &1_u32.to_le_bytes());
+    let () = put(&mut pe, rdata(0x2014), &2_u32.to_le_bytes());
+    let () = put(&mut pe, rdata(0x2018), &2_u32.to_le_bytes());
+    let () = put(&mut pe, rdata(0x201c), &0x2028_u32.to_le_bytes());
+    let () = put(&mut pe, rdata(0x2020), &0x2030_u32.to_le_bytes());
+    let () = put(&mut pe, rdata(0x2024), &0x2038_u32.to_le_bytes());
+    let () = put(&mut pe, rdata(0x2028), &0x1000_u32.to_le_bytes());
+    let () = put(&mut pe, rdata(0x202c), &0x1010_u32.to_le_bytes());
+    let () = put(&mut pe, rdata(0x2030), &0x2048_u32.to_le_bytes());
+    let () = put(&mut pe, rdata(0x2034), &0x2053_u32.to_le_bytes());
+    let () = put(&mut pe, rdata(0x2038), &[0, 0, 1, 0]);
+    let () = put(&mut pe, rdata(0x203c), b"test-pe.dll\0");
+    let () = put(&mut pe, rdata(0x2048), b"the_answer\0");
+    let () = put(&mut pe, rdata(0x2053), b"the_question\0");
+
+    // The debug directory, referencing a CodeView record.
+    let pdb_path = b"C:\\build\\test-pe.pdb\0";
+    let cv_size = 24 + pdb_path.len() as u32;
+    let () = put(&mut pe, rdata(0x206c), &2_u32.to_le_bytes());
+    let () = put(&mut pe, rdata(0x2070), &cv_size.to_le_bytes());
+    let () = put(&mut pe, rdata(0x2074), &0x2080_u32.to_le_bytes());
+    let () = put(
+        &mut pe,
+        rdata(0x2078),
+        &(rdata(0x2080) as u32).to_le_bytes(),
+    );
+    let () = put(&mut pe, rdata(0x2080), b"RSDS");
+    let () = put(&mut pe, rdata(0x2084), &GUID);
+    let () = put(&mut pe, rdata(0x2094), &age.to_le_bytes());
+    let () = put(&mut pe, rdata(0x2098), pdb_path);
+    pe
+}
+
+/// Check that we can symbolize addresses in a PE file, using the
+/// matching PDB file as well as the export table.
+#[test]
+fn symbolize_pe() {
+    let pdb = Path::new(&env!("CARGO_MANIFEST_DIR"))
+        .join("data")
+        .join("test-pe.pdb");
+    let dir = tempdir().unwrap();
+    let dll = dir.path().join("test-pe.dll");
+    let () = write(&dll, pe_dll(1)).unwrap();
+
+    fn symbolize<'slf>(
+        symbolizer: &'slf Symbolizer,
+        dll: &Path,
+        input: symbolize::Input<u64>,
+    ) -> Sym<'slf> {
+        let src = symbolize::Source::from(symbolize::Pe::new(dll));
+        symbolizer
+            .symbolize_single(&src, input)
+            .unwrap()
+            .into_sym()
+            .unwrap()
+    }
+
+    // Without a PDB file only the export table is available.
+    let symbolizer = Symbolizer::new();
+    let sym = symbolize(&symbolizer, &dll, symbolize::Input::VirtOffset(0x1014));
+    assert_eq!(sym.name, "the_question");
+    assert_eq!(sym.addr, 0x1010);
+    assert_eq!(sym.offset, 4);
+    assert_eq!(sym.size, None);
+    assert_eq!(sym.code_info, None);
+    assert_eq!(sym.section.as_deref(), Some(".text"));
+
+    let pdb_dir = tempdir().unwrap();
+    let _count = copy(&pdb, pdb_dir.path().join("test-pe.pdb")).unwrap();
+    let symbolizer = Symbolizer::builder().set_pdb_dirs([pdb_dir.path()]).build();
+    let sym = symbolize(&symbolizer, &dll, symbolize::Input::VirtOffset(0x1009));
+    assert_eq!(sym.name, "the_answer");
+    assert_eq!(sym.addr, 0x1000);
+    assert_eq!(sym.offset, 9);
+    assert_eq!(sym.size, Some(16));
+    let code_info = sym.code_info.as_ref().unwrap();
+    assert_eq!(code_info.dir.as_deref(), Some(Path::new(r"C:\src")));
+    assert_eq!(code_info.file, OsStr::new("test-pe.c"));
+    assert_eq!(code_info.line, Some(4));
+
+    let sym = symbolize(&symbolizer, &dll, symbolize::Input::FileOffset(0x214));
+    assert_eq!(sym.name, "the_question");
+    assert_eq!(sym.offset, 4);
+    assert_eq!(sym.code_info.as_ref().unwrap().line, Some(9));
+
+    // A PDB file next to the PE file is found without further
+    // configuration.
+    let _count = copy(&pdb, dir.path().join("test-pe.pdb")).unwrap();
+    let symbolizer = Symbolizer::new();
+    let sym = symbolize(&symbolizer, &dll, symbolize::Input::VirtOffset(0x1000));
+    assert_eq!(sym.name, "the_answer");
+    assert_eq!(sym.code_info.as_ref().unwrap().line, Some(3));
+
+    // PE files mapped into a process are told apart from ELF ones and
+    // symbolized accordingly.
+    let file = File::open(&dll).unwrap();
+    let len = file.metadata().unwrap().len() as usize;
+    let addr = unsafe {
+        libc::mmap(
+            ptr::null_mut(),
+            len,
+            libc::PROT_READ,
+            libc::MAP_PRIVATE,
+            file.as_raw_fd(),
+            0,
+        )
+    };
+    assert_ne!(addr, libc::MAP_FAILED);
+    defer!({
+        let _rc = unsafe { libc::munmap(addr, len) };
+    });
+
+    let src = symbolize::Source::Process(symbolize::Process::new(Pid::Slf));
+    let sym = symbolizer
+        .symbolize_single(&src, symbolize::Input::AbsAddr(addr as Addr + 0x214))
+        .unwrap()
+        .into_sym()
+        .unwrap();
+    assert_eq!(sym.name, "the_question");
+    assert_eq!(sym.offset, 4);
+    assert_eq!(sym.code_info.as_ref().unwrap().line, Some(9));
+
+    // A PDB file not matching the PE file is ignored.
+    let () = write(&dll, pe_dll(2)).unwrap();
+    let symbolizer = Symbolizer::new();
+    let sym = symbolize(&symbolizer, &dll, symbolize::Input::VirtOffset(0x1009));
+    assert_eq!(sym.name, "the_answer");
+    assert_eq!(sym.code_info, None);
+
+    let src = symbolize::Source::from(symbolize::Pe::new(&dll));
+    let err = symbolizer
+        .symbolize_single(&src, symbolize::Input::AbsAddr(0x180001000))
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unsupported);
+}
+
+/// Check that we can look up symbols in a PE file.
+#[test]
+fn inspect_pe() {
+    let dir = tempdir().unwrap();
+    let dll = dir.path().join("test-pe.dll");
+    let () = write(&dll, pe_dll(1)).unwrap();
+
+    let src = inspect::Source::from(inspect::Pe::new(&dll));
+    let inspector = Inspector::new();
+    let results = inspector
+        .lookup(&src, &["the_question", "the_answer"])
+        .unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].len(), 1);
+    assert_eq!(results[0][0].addr, 0x1010);
+    assert_eq!(results[0][0].file_offset, Some(0x210));
+    assert_eq!(results[1].len(), 1);
+    assert_eq!(results[1][0].addr, 0x1000);
+    assert_eq!(results[1][0].size, 0x10);
+
+    let mut names = Vec::new();
+    let () = inspector
+        .for_each(&src, |sym| names.push(sym.name.to_string()))
+        .unwrap();
+    assert_eq!(names, ["the_answer", "the_question"]);
+}
+
+/// Make sure that Breakpad symbol file errors are reported in a
+/// somewhat decent fashion.
+#[test]
+fn symbolize_breakpad_error() {
+    let content = br#"MODULE Linux x86_64 C00D0279606DFBCD53805DDAD2CA66A30 test-stable-addrs.bin
+FILE 0 data/test-stable-addrs-cu2.c
+PUBLIC 0 0 main
+FUNC 34 11 0 factorial_wrapper
+34 XXX-this-does-not-belong-here-XXX 4 0
+38 a 5 0
+42 3 6 0
+"#;
+
+    let mut tmpfile = NamedTempFile::new().unwrap();
+    let () = tmpfile.write_all(content).unwrap();
+
+    let src = symbolize::Source::Breakpad(symbolize::Breakpad::new(tmpfile.path()));
+    let symbolizer = Symbolizer::new();
+    let err = symbolizer
+        .symbolize_single(&src, symbolize::Input::FileOffset(0x100))
+        .unwrap_err();
+    assert!(format!("{err:?}").contains("34 XXX-this-does-not-belong-here-XXX 4 0"));
+}
+
+/// Check that we report compilation directories only when asked to.
+#[test]
+fn symbolize_dwarf_comp_dirs() {
+    let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+        .join("data")
+        .join("test-stable-addrs.bin");
+    let src = symbolize::Source::from(symbolize::Elf::new(path));
+
+    let symbolizer = Symbolizer::new();
+    let sym = symbolizer
+        .symbolize_single(&src, symbolize::Input::VirtOffset(0x200020a))
+        .unwrap()
+        .into_sym()
+        .unwrap();
+    assert_eq!(sym.code_info.as_ref().unwrap().comp_dir, None);
+
+    let symbolizer = Symbolizer::builder().enable_comp_dirs(true).build();
+    let sym = symbolizer
+        .symbolize_single(&src, symbolize::Input::VirtOffset(0x200020a))
+        .unwrap()
+        .into_sym()
+        .unwrap();
+    assert_eq!(sym.name, "factorial_inline_test");
+    let comp_dir = sym.code_info.as_ref().unwrap().comp_dir.as_deref().unwrap();
+    assert!(comp_dir.is_absolute(), "{comp_dir:?}");
+    assert_eq!(sym.inlined.len(), 2);
+    for inlined_fn in sym.inlined.iter() {
+        let code_info = inlined_fn.code_info.as_ref().unwrap();
+        assert_eq!(code_info.comp_dir.as_deref(), Some(comp_dir));
+    }
+}
+
+/// Check the formatted representation of symbols for a few known
+/// addresses.
+#[test]
+fn symbolize_formatting() {
+    let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+        .join("data")
+        .join("test-stable-addrs.bin");
+    let src = symbolize::Source::from(symbolize::Elf::new(path));
+    let symbolizer = Symbolizer::new();
+    let addrs = [0x2000008, 0x2000100, 0x200020a];
+    let syms = symbolizer
+        .symbolize(&src, symbolize::Input::VirtOffset(&addrs))
+        .unwrap()
+        .into_iter()
+        .map(|sym| {
+            let mut sym = sym.into_sym().unwrap();
+            // Directories depend on the build environment, so strip
+            // them to keep the output stable.
+            let code_infos = sym
+                .code_info
+                .iter_mut()
+                .chain(sym.inlined.iter_mut().filter_map(|f| f.code_info.as_mut()));
+            for code_info in code_infos {
+                code_info.dir = None;
+            }
+            sym
+        })
+        .collect::<Vec<_>>();
+
+    let formatted = syms.iter().map(Sym::to_string).collect::<Vec<_>>();
+    let expected = [
+        "main @ 0x2000000+0x8 test-stable-addrs.c:65:36",
+        "factorial @ 0x2000100+0x0 test-stable-addrs.c:10:27",
+        "factorial_inline_test @ 0x2000200+0xa test-stable-addrs.c:34:3
+  factorial_inline_wrapper @ test-stable-addrs.c:28:3 [inlined]
+  factorial_2nd_layer_inline_wrapper @ test-stable-addrs.c:23:3 [inlined]",
+    ];
+    assert_eq!(formatted, expected);
+
+    let format = SymFormat {
+        addr_width: 16,
+        inlined_fns: false,
+        ..Default::default()
+    };
+    let formatted = syms
+        .iter()
+        .map(|sym| sym.display(&format).to_string())
+        .collect::<Vec<_>>();
+    let expected = [
+        "main @ 0x0000000002000000+0x8 test-stable-addrs.c:65:36",
+        "factorial @ 0x0000000002000100+0x0 test-stable-addrs.c:10:27",
+        "factorial_inline_test @ 0x0000000002000200+0xa test-stable-addrs.c:34:3",
+    ];
+    assert_eq!(formatted, expected);
+}
+
+/// Check that deeply nested inline stacks are reported correctly and
+/// truncated as configured.
+#[test]
+fn symbolize_dwarf_deep_inline() {
+    let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+        .join("data")
+        .join("test-deep-inline.bin");
+    let src = inspect::Source::from(inspect::Elf::new(&path));
+    let inspector = Inspector::new();
+    let results = inspector
+        .lookup(&src, &["deep_inline"])
+        .unwrap()
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+    assert_eq!(results.len(), 1);
+    let deep_inline = &results[0];
+    let addrs = (deep_inline.addr..deep_inline.addr + deep_inline.size as Addr).collect::<Vec<_>>();
+
+    let src = symbolize::Source::from(symbolize::Elf::new(&path));
+    let symbolizer = Symbolizer::builder()
+        .set_max_inlined_depth(usize::MAX)
+        .build();
+    let syms = symbolizer
+        .symbolize(&src, symbolize::Input::VirtOffset(&addrs))
+        .unwrap();
+    // Find the address with the deepest inline stack, which is the
+    // one of the innermost inlined function.
+    let (idx, full) = syms
+        .iter()
+        .map(|symbolized| symbolized.as_sym().unwrap())
+        .enumerate()
+        .max_by_key(|(_idx, sym)| sym.inlined.len())
+        .unwrap();
+    assert_eq!(full.name, "deep_inline");
+    assert_eq!(full.inlined.len(), 80);
+    for (i, inlined_fn) in full.inlined.iter().enumerate() {
+        assert_eq!(inlined_fn.name, format!("inline{}", 79 - i));
+    }
+
+    let symbolizer = Symbolizer::new();
+    let truncated = symbolizer
+        .symbolize_single(&src, symbolize::Input::VirtOffset(addrs[idx]))
+        .unwrap()
+        .into_sym()
+        .unwrap();
+    assert_eq!(truncated.inlined.len(), 65);
+    assert_eq!(truncated.inlined[..64], full.inlined[..64]);
+    let marker = truncated.inlined.last().unwrap();
+    assert_eq!(marker.name, "<truncated>");
+    assert_eq!(marker.code_info, full.inlined.last().unwrap().code_info);
+    assert_eq!(truncated.code_info, full.code_info);
+}
+
+/// Check that we can symbolize addresses using a flat symbol map.
+#[test]
+fn symbolize_symbol_map() {
+    let content = b"addr,size,name\n0x2000100,0x2b,factorial\n200005f,10,foo<a, b>\n";
+    let mut tmpfile = NamedTempFile::new().unwrap();
+    let () = tmpfile.write_all(content).unwrap();
+
+    let src = symbolize::Source::from(symbolize::SymbolMap::new(tmpfile.path()));
+    let symbolizer = Symbolizer::new();
+    let results = symbolizer
+        .symbolize(
+            &src,
+            symbolize::Input::VirtOffset(&[0x2000104, 0x200005f, 0x2000200]),
+        )
+        .unwrap();
+    assert_eq!(results.len(), 3);
+
+    let sym = results[0].as_sym().unwrap();
+    assert_eq!(sym.name, "factorial");
+    assert_eq!(sym.addr, 0x2000100);
+    assert_eq!(sym.offset, 4);
+    assert_eq!(sym.size, Some(0x2b));
+    assert_eq!(sym.code_info, None);
+
+    let sym = results[1].as_sym().unwrap();
+    assert_eq!(sym.name, "foo<a, b>");
+    assert_eq!(sym.offset, 0);
+
+    assert_eq!(
+        results[2],
+        symbolize::Symbolized::Unknown(symbolize::Reason::UnknownAddr)
+    );
+
+    let err = symbolizer
+        .symbolize_single(&src, symbolize::Input::FileOffset(0x100))
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unsupported);
+}
+
+/// Check that we can symbolize addresses using a composite source.
+#[test]
+fn symbolize_composite() {
+    let mut tmpfile = NamedTempFile::new().unwrap();
+    let () = writeln!(tmpfile, "5000000,10,jit_fn").unwrap();
+
+    let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+        .join("data")
+        .join("test-stable-addrs.bin");
+    let src = symbolize::Source::from(symbolize::Composite::new([
+        // The kernel source does not support virtual offsets and
+        // should just be skipped.
+        symbolize::Source::from(symbolize::Kernel::default()),
+        symbolize::Source::from(symbolize::Elf::new(path)),
+        symbolize::Source::from(symbolize::SymbolMap::new(tmpfile.path())),
+    ]));
+    let symbolizer = Symbolizer::new();
+    let results = symbolizer
+        .symbolize(
+            &src,
+            symbolize::Input::VirtOffset(&[0x2000100, 0x5000004, 0x1]),
+        )
+        .unwrap();
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0].as_sym().unwrap().name, "factorial");
+    let sym = results[1].as_sym().unwrap();
+    assert_eq!(sym.name, "jit_fn");
+    assert_eq!(sym.offset, 4);
+    assert!(matches!(results[2], symbolize::Symbolized::Unknown(..)));
+
+    let result = symbolizer
+        .symbolize_single(&src, symbolize::Input::VirtOffset(0x5000000))
+        .unwrap()
+        .into_sym()
+        .unwrap();
+    assert_eq!(result.name, "jit_fn");
+
+    let src = symbolize::Source::from(symbolize::Composite::new([]));
+    let result = symbolizer
+        .symbolize_single(&src, symbolize::Input::VirtOffset(0x2000100))
+        .unwrap();
+    assert_eq!(
+        result,
+        symbolize::Symbolized::Unknown(symbolize::Reason::UnknownAddr)
+    );
+}
+
+/// Check that we can symbolize kernel addresses in `ftrace` output.
+#[test]
+fn symbolize_ktrace() {
+    let mut kallsyms = NamedTempFile::new().unwrap();
+    let () = writeln!(kallsyms, "ffffffffc0000000 t module_fn [module]").unwrap();
+    let () = writeln!(kallsyms, "ffffffffc0000100 t module_fn2 [module]").unwrap();
+    let kernel = symbolize::Kernel {
+        kallsyms: Some(kallsyms.path().to_path_buf()),
+        kernel_image: Some(
+            Path::new(&env!("CARGO_MANIFEST_DIR"))
+                .join("data")
+                .join("test-stable-addrs.bin"),
+        ),
+        kaslr_offset: Some(0x10000000),
+        ..Default::default()
+    };
+
+    let trace = br#"# tracer: function
+  <idle>-0       [001] d.h1.  1234.567890: module_fn2 <-ffffffffc0000010
+ => ffffffffc0000104
+ => 0x0000000000000042
+"#;
+    let src = symbolize::Ktrace::new(trace.as_slice());
+    let symbolizer = Symbolizer::new();
+    let lines = symbolizer
+        .symbolize_ktrace(src, &kernel)
+        .unwrap()
+        .collect::<Vec<_>>();
+    assert_eq!(
+        lines,
+        vec![
+            "# tracer: function",
+            "  <idle>-0       [001] d.h1.  1234.567890: module_fn2 <-module_fn+0x10",
+            " => module_fn2+0x4",
+            " => 0x0000000000000042",
+        ]
+    );
+}
+
+/// Check that `Symbolizer::symbolize_with` invokes the provided
+/// callback once for each input address, in order.
+#[test]
+fn symbolize_with_callback() {
+    let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+        .join("data")
+        .join("test-stable-addrs.bin");
+    let src = symbolize::Source::Elf(symbolize::Elf::new(path));
+    let symbolizer = Symbolizer::new();
+    let addrs = [0x2000100, 0x1, 0x2000200, 0x2000100];
+
+    let mut results = Vec::new();
+    let () = symbolizer
+        .symbolize_with(&src, symbolize::Input::VirtOffset(&addrs), |addr, sym| {
+            let name = sym.into_sym().map(|sym| sym.name.to_string());
+            let () = results.push((addr, name));
+        })
+        .unwrap();
+
+    let expected = [
+        (0x2000100, Some("factorial".to_string())),
+        (0x1, None),
+        (0x2000200, Some("factorial_inline_test".to_string())),
+        (0x2000100, Some("factorial".to_string())),
+    ];
+    assert_eq!(results, expected);
+}
+
+/// Check that we can symbolize an address mapping to a variable in an
+/// ELF file.
+#[test]
+fn symbolize_elf_variable() {
+    fn test(debug_syms: bool) {
+        let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join("test-stable-addrs.bin");
+        let mut elf = symbolize::Elf::new(path);
+        elf.debug_syms = debug_syms;
+        let src = symbolize::Source::Elf(elf);
+        let symbolizer = Symbolizer::new();
+        let result = symbolizer
+            .symbolize_single(&src, symbolize::Input::VirtOffset(0x2001100))
+            .unwrap()
+            .into_sym()
+            .unwrap();
+
+        assert_eq!(result.name, "a_variable");
+        assert_eq!(result.addr, 0x2001100);
+        assert_eq!(result.offset, 0);
+        // Even when using DWARF we don't currently support variable lookup,
+        // so no matter what, we won't have source code information
+        // available at this point.
+        assert_eq!(result.code_info, None);
+
+        let size = result.size.unwrap();
+        assert_eq!(size, 8);
+
+        let offsets = (1..size).collect::<Vec<_>>();
+        let addrs = offsets
+            .iter()
+            .map(|offset| (0x2001100 + offset) as Addr)
+            .collect::<Vec<_>>();
+        let results = symbolizer
+            .symbolize(&src, symbolize::Input::VirtOffset(&addrs))
+            .unwrap()
+            .into_iter()
+            .collect::<Vec<_>>();
+        assert_eq!(results.len(), addrs.len());
+
+        for (i, symbolized) in results.into_iter().enumerate() {
+            let result = symbolized.into_sym().unwrap();
+            assert_eq!(result.name, "a_variable");
+            assert_eq!(result.addr, 0x2001100);
+            assert_eq!(result.offset, offsets[i]);
+            assert_eq!(result.code_info, None);
+        }
+    }
+
+    test(false);
+    test(true);
+}
+
+/// Check that we can report the raw ELF symbol table values of a
+/// symbol, irrespective of the symbolization source used.
+#[test]
+fn symbolize_elf_raw_syms() {
+    fn test(debug_syms: bool) {
+        let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join("test-stable-addrs.bin");
+        let mut elf = symbolize::Elf::new(path);
+        elf.debug_syms = debug_syms;
+        let src = symbolize::Source::Elf(elf);
+
+        let symbolizer = Symbolizer::new();
+        let sym = symbolizer
+            .symbolize_single(&src, symbolize::Input::VirtOffset(0x2000104))
+            .unwrap()
+            .into_sym()
+            .unwrap();
+        assert_eq!(sym.name, "factorial");
+        assert_eq!(sym.raw_elf_sym, None);
+
+        let symbolizer = Symbolizer::builder().enable_raw_elf_syms(true).build();
+        let sym = symbolizer
+            .symbolize_single(&src, symbolize::Input::VirtOffset(0x2000104))
+            .unwrap()
+            .into_sym()
+            .unwrap();
+        assert_eq!(sym.name, "factorial");
+        let raw = sym.raw_elf_sym.unwrap();
+        assert_eq!(raw.st_value, 0x2000100);
+        assert_eq!(raw.st_size, 0x2b);
+    }
+
+    test(false);
+    test(true);
+}
+
+/// Check that we "fail" symbolization as expected on a stripped ELF
+/// binary.
+#[test]
+fn symbolize_elf_stripped() {
+    let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+        .join("data")
+        .join("test-stable-addrs-stripped.bin");
+    let src = symbolize::Source::Elf(symbolize::Elf::new(path));
+    let symbolizer = Symbolizer::new();
+    let result = symbolizer
+        .symbolize_single(&src, symbolize::Input::VirtOffset(0x2000100))
+        .unwrap();
+
+    assert_eq!(result, Symbolized::Unknown(Reason::MissingSyms));
+}
+
+/// Check that we can symbolize an ELF file that is only accessible via
+/// a file descriptor.
+#[test]
+fn symbolize_elf_fd() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("test-stable-addrs.bin");
+    let _size = copy(
+        Path::new(&env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join("test-stable-addrs.bin"),
+        &path,
+    )
+    .unwrap();
+    let fd = OwnedFd::from(File::open(&path).unwrap());
+    // Make sure that the file is no longer accessible by path.
+    let () = remove_file(&path).unwrap();
+
+    let symbolizer = Symbolizer::new();
+    let src = symbolize::Source::from(symbolize::Elf::from_fd(fd, "<fd>"));
+    for _ in 0..2 {
+        let sym = symbolizer
+            .symbolize_single(&src, symbolize::Input::VirtOffset(0x2000104))
+            .unwrap()
+            .into_sym()
+            .unwrap();
+        assert_eq!(sym.name, "factorial");
+        assert_eq!(sym.addr, 0x2000100);
+    }
+    let stats = symbolizer.cache_stats();
+    assert_eq!(stats.hits, 1);
+    assert_eq!(stats.misses, 1);
+
+    // The label should be reported as part of errors.
+    let file = NamedTempFile::new().unwrap();
+    let fd = OwnedFd::from(File::open(file.path()).unwrap());
+    let src = symbolize::Source::from(symbolize::Elf::from_fd(fd, "<invalid>"));
+    let err = symbolizer
+        .symbolize_single(&src, symbolize::Input::VirtOffset(0x2000104))
+        .unwrap_err();
+    assert!(format!("{err:#}").contains("<invalid>"), "{err:#}");
+}
+
+
+/// Check that we can symbolize absolute addresses in an ELF file
+/// loaded at an explicitly provided base address.
+#[test]
+fn symbolize_elf_with_base() {
+    let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+        .join("data")
+        .join("test-stable-addrs.bin");
+    let symbolizer = Symbolizer::new();
+
+    let src = symbolize::Source::from(symbolize::Elf::new(&path));
+    let err = symbolizer
+        .symbolize_single(&src, symbolize::Input::AbsAddr(0x2000104))
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unsupported);
+
+    let src = symbolize::Source::from(symbolize::Elf::with_base(&path, 0x7f0000000000));
+    let sym = symbolizer
+        .symbolize_single(&src, symbolize::Input::AbsAddr(0x7f0002000104))
+        .unwrap()
+        .into_sym()
+        .unwrap();
+    assert_eq!(sym.name, "factorial");
+    assert_eq!(sym.addr, 0x2000100);
+
+    // Inputs relative to the ELF file are unaffected.
+    let sym = symbolizer
+        .symbolize_single(&src, symbolize::Input::VirtOffset(0x2000104))
+        .unwrap()
+        .into_sym()
+        .unwrap();
+    assert_eq!(sym.name, "factorial");
+
+    let addrs = [0x7f0002000104, 0x1000];
+    let syms = symbolizer
+        .symbolize(&src, symbolize::Input::AbsAddr(&addrs))
+        .unwrap();
+    assert_eq!(syms.len(), 2);
+    assert_eq!(syms[0].as_sym().unwrap().name, "factorial");
+    assert_eq!(
+        syms[1],
+        symbolize::Symbolized::Unknown(symbolize::Reason::UnknownAddr)
+    );
 }
 
-/// Check that we can symbolize an address mapping to a variable in an
-/// ELF file.
+
+/// Check that we can synthesize symbols from `.eh_frame` information
+/// for a stripped ELF binary, if asked to.
 #[test]
-fn symbolize_elf_variable() {
-    fn test(debug_syms: bool) {
-        let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
-            .join("data")
-            .join("test-stable-addrs.bin");
-        let mut elf = symbolize::Elf::new(path);
-        elf.debug_syms = debug_syms;
-        let src = symbolize::Source::Elf(elf);
-        let symbolizer = Symbolizer::new();
+fn symbolize_elf_stripped_eh_frame_fallback() {
+    let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+        .join("data")
+        .join("test-stable-addrs-stripped.bin");
+    let src = symbolize::Source::Elf(symbolize::Elf::new(path));
+    let symbolizer = Symbolizer::builder().enable_eh_frame_fallback(true).build();
+
+    // `factorial` is located at 0x2000100 and has a size of 0x2b.
+    for offset in [0, 0x14, 0x2a] {
         let result = symbolizer
-            .symbolize_single(&src, symbolize::Input::VirtOffset(0x2001100))
+            .symbolize_single(&src, symbolize::Input::VirtOffset(0x2000100 + offset))
             .unwrap()
             .into_sym()
             .unwrap();
 
-        assert_eq!(result.name, "a_variable");
-        assert_eq!(result.addr, 0x2001100);
-        assert_eq!(result.offset, 0);
-        // Even when using DWARF we don't currently support variable lookup,
-        // so no matter what, we won't have source code information
-        // available at this point.
+        assert_eq!(result.name, "fn_0x2000100");
+        assert_eq!(result.addr, 0x2000100);
+        assert_eq!(result.offset, offset as usize);
+        assert_eq!(result.size, Some(0x2b));
         assert_eq!(result.code_info, None);
+        assert_eq!(result.provenance, symbolize::Provenance::EhFrame);
+    }
 
-        let size = result.size.unwrap();
-        assert_eq!(size, 8);
+    // Addresses not covered by any FDE should still be reported as
+    // unknown.
+    let result = symbolizer
+        .symbolize_single(&src, symbolize::Input::VirtOffset(0x2000100 + 0x2b))
+        .unwrap();
+    assert_eq!(result, Symbolized::Unknown(Reason::MissingSyms));
+}
 
-        let offsets = (1..size).collect::<Vec<_>>();
-        let addrs = offsets
-            .iter()
-            .map(|offset| (0x2001100 + offset) as Addr)
-            .collect::<Vec<_>>();
-        let results = symbolizer
-            .symbolize(&src, symbolize::Input::VirtOffset(&addrs))
-            .unwrap()
-            .into_iter()
-            .collect::<Vec<_>>();
-        assert_eq!(results.len(), addrs.len());
+/// Check that we report where an address not covered by any symbol
+/// lies relative to the available symbols.
+#[test]
+fn symbolize_unknown_addr_reasons() {
+    let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+        .join("data")
+        .join("test-stable-addrs.bin");
+    let symbolizer = Symbolizer::new();
 
-        for (i, symbolized) in results.into_iter().enumerate() {
-            let result = symbolized.into_sym().unwrap();
-            assert_eq!(result.name, "a_variable");
-            assert_eq!(result.addr, 0x2001100);
-            assert_eq!(result.offset, offsets[i]);
-            assert_eq!(result.code_info, None);
-        }
-    }
+    let src = symbolize::Source::Elf(symbolize::Elf::new(&path));
+    // `factorial` ends at 0x200012b and `factorial_inline_test` starts
+    // at 0x2000200. `a_variable` is the last symbol and ends at
+    // 0x2001108.
+    let addrs = [0x1000, 0x2000150, 0x2001108];
+    let results = symbolizer
+        .symbolize(&src, symbolize::Input::VirtOffset(&addrs))
+        .unwrap();
+    assert_eq!(
+        results,
+        vec![
+            Symbolized::Unknown(Reason::BeforeFirstSym),
+            Symbolized::Unknown(Reason::BetweenSyms),
+            Symbolized::Unknown(Reason::AfterLastSym),
+        ]
+    );
 
-    test(false);
-    test(true);
+    let src = symbolize::Source::Dwarf(symbolize::Dwarf::new(&path));
+    let addrs = [0x1000, 0x3000000];
+    let results = symbolizer
+        .symbolize(&src, symbolize::Input::VirtOffset(&addrs))
+        .unwrap();
+    assert_eq!(
+        results,
+        vec![
+            Symbolized::Unknown(Reason::BeforeFirstSym),
+            Symbolized::Unknown(Reason::AfterLastSym),
+        ]
+    );
 }
 
-/// Check that we "fail" symbolization as expected on a stripped ELF
-/// binary.
+/// Check that we can symbolize offsets relative to a section.
 #[test]
-fn symbolize_elf_stripped() {
+fn symbolize_elf_section_offsets() {
     let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
         .join("data")
-        .join("test-stable-addrs-stripped.bin");
+        .join("test-stable-addrs.bin");
     let src = symbolize::Source::Elf(symbolize::Elf::new(path));
     let symbolizer = Symbolizer::new();
-    let result = symbolizer
-        .symbolize_single(&src, symbolize::Input::VirtOffset(0x2000100))
+    // `.text` starts at 0x2000000 and is 0x213 bytes in size.
+    let results = symbolizer
+        .symbolize_section_offsets(&src, ".text", &[0x100, 0x200, 0x213])
         .unwrap();
+    assert_eq!(results.len(), 3);
 
-    assert_eq!(result, Symbolized::Unknown(Reason::MissingSyms));
+    let sym = results[0].as_sym().unwrap();
+    assert_eq!(sym.name, "factorial");
+    assert_eq!(sym.addr, 0x2000100);
+    let sym = results[1].as_sym().unwrap();
+    assert_eq!(sym.name, "factorial_inline_test");
+    assert_eq!(results[2], Symbolized::Unknown(Reason::UnknownAddr));
+
+    let err = symbolizer
+        .symbolize_section_offsets(&src, ".does-not-exist", &[0x100])
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::NotFound);
 }
 
 /// Make sure that we report (enabled) or don't report (disabled) inlined
@@ -366,6 +1985,143 @@ fn symbolize_dwarf_gsym_inlined() {
     }
 }
 
+/// Check that we report addresses of inlined functions when asked to.
+#[test]
+fn symbolize_dwarf_inlined_fn_addrs() {
+    fn test(inlined_fn_addrs: bool) {
+        let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join("test-stable-addrs-stripped-elf-with-dwarf.bin");
+        let src = symbolize::Source::from(symbolize::Elf::new(path));
+        let symbolizer = Symbolizer::builder()
+            .enable_inlined_fn_addrs(inlined_fn_addrs)
+            .build();
+        let addr = 0x200020a;
+        let result = symbolizer
+            .symbolize_single(&src, symbolize::Input::VirtOffset(addr))
+            .unwrap()
+            .into_sym()
+            .unwrap();
+
+        assert_eq!(result.name, "factorial_inline_test");
+        assert_eq!(result.inlined.len(), 2);
+
+        if inlined_fn_addrs {
+            let mut outer_addr = result.addr;
+            for inlined_fn in result.inlined.iter() {
+                let inlined_addr = inlined_fn.addr.unwrap();
+                let offset = inlined_fn.offset.unwrap();
+                assert_eq!(inlined_addr + offset as Addr, addr);
+                // Inlined code is nested inside the caller's code.
+                assert!(inlined_addr >= outer_addr, "{inlined_fn:#?}");
+                outer_addr = inlined_addr;
+            }
+        } else {
+            for inlined_fn in result.inlined.iter() {
+                assert_eq!(inlined_fn.addr, None);
+                assert_eq!(inlined_fn.offset, None);
+            }
+        }
+    }
+
+    test(true);
+    test(false);
+}
+
+/// Check that we report the addresses of out-of-line definitions of
+/// inlined functions when asked to.
+#[test]
+fn symbolize_dwarf_inlined_fn_origin_addrs() {
+    let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+        .join("data")
+        .join("test-inline-origin.bin");
+    let src = inspect::Source::from(inspect::Elf::new(&path));
+    let inspector = Inspector::new();
+    let results = inspector
+        .lookup(&src, &["caller", "inlined_fn"])
+        .unwrap()
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+    assert_eq!(results.len(), 2);
+    let caller = &results[0];
+    let inlined_fn = &results[1];
+    let addrs = (caller.addr..caller.addr + caller.size as Addr).collect::<Vec<_>>();
+
+    let src = symbolize::Source::from(symbolize::Elf::new(&path));
+    for origin_addrs in [true, false] {
+        let symbolizer = Symbolizer::builder()
+            .enable_inlined_fn_origin_addrs(origin_addrs)
+            .build();
+        let syms = symbolizer
+            .symbolize(&src, symbolize::Input::VirtOffset(&addrs))
+            .unwrap();
+        let inlined = syms
+            .iter()
+            .filter_map(|symbolized| symbolized.as_sym())
+            .flat_map(|sym| sym.inlined.iter())
+            .filter(|inlined| inlined.name == "inlined_fn")
+            .collect::<Vec<_>>();
+        assert!(!inlined.is_empty());
+
+        for inlined in inlined {
+            let expected = origin_addrs.then_some(inlined_fn.addr);
+            assert_eq!(inlined.origin_addr, expected, "{inlined:#?}");
+        }
+    }
+}
+
+/// Check that addresses not covered by any function or symbol are
+/// attributed to a preceding `DW_TAG_label` entry when using the line
+/// table fallback.
+#[test]
+fn symbolize_dwarf_label() {
+    let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+        .join("data")
+        .join("test-label-asm.bin");
+    let src = inspect::Source::from(inspect::Elf::new(&path));
+    let inspector = Inspector::new();
+    let results = inspector.lookup(&src, &["_start"]).unwrap();
+    let start = &results[0][0];
+    // By construction, the label directly follows `_start`.
+    let label_addr = start.addr + start.size as Addr;
+
+    let src = symbolize::Source::from(symbolize::Elf::new(&path));
+    let addrs = [start.addr, label_addr, label_addr + 1];
+
+    let symbolizer = Symbolizer::new();
+    let results = symbolizer
+        .symbolize(&src, symbolize::Input::VirtOffset(&addrs))
+        .unwrap();
+    assert_eq!(results[0].as_sym().unwrap().name, "_start");
+    assert!(results[1].as_sym().is_none(), "{:?}", results[1]);
+    assert!(results[2].as_sym().is_none(), "{:?}", results[2]);
+
+    let symbolizer = Symbolizer::builder()
+        .enable_line_table_fallback(true)
+        .build();
+    let results = symbolizer
+        .symbolize(&src, symbolize::Input::VirtOffset(&addrs))
+        .unwrap();
+
+    let sym = results[0].as_sym().unwrap();
+    assert_eq!(sym.name, "_start");
+    assert_eq!(sym.provenance, Provenance::Symbol);
+
+    for ((symbolized, addr), line) in results[1..].iter().zip(&addrs[1..]).zip([20, 21]) {
+        let sym = symbolized.as_sym().unwrap();
+        assert_eq!(sym.name, "asm_label");
+        assert_eq!(sym.addr, label_addr);
+        assert_eq!(sym.offset, (addr - label_addr) as usize);
+        assert_eq!(sym.size, None);
+        assert_eq!(sym.provenance, Provenance::Label);
+        assert!(sym.is_synthetic);
+        let code_info = sym.code_info.as_ref().unwrap();
+        assert_eq!(code_info.file, OsStr::new("test-label-asm.S"));
+        assert_eq!(code_info.line, Some(line));
+    }
+}
+
 /// Make sure that we report (enabled) or don't report (disabled) inlined
 /// functions with Breakpad sources.
 #[test]
@@ -534,6 +2290,99 @@ fn symbolize_dwarf_demangle() {
     panic!("failed to find inlined function call");
 }
 
+/// Check that we can iterate over symbols along with their demangled
+/// names.
+#[cfg(feature = "demangle")]
+#[test]
+fn inspect_elf_for_each_demangled() {
+    let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+        .join("data")
+        .join("test-rs.bin");
+    let src = inspect::Source::Elf(inspect::Elf::new(path));
+
+    let inspector = Inspector::new();
+    let mut demangled = HashMap::new();
+    let () = inspector
+        .for_each_demangled(&src, |sym, name| {
+            let _prev = demangled.insert(sym.name.to_string(), name.map(str::to_string));
+        })
+        .unwrap();
+
+    assert_eq!(
+        demangled["_RNvCs69hjMPjVIJK_4test13test_function"].as_deref(),
+        Some("test::test_function")
+    );
+    assert_eq!(
+        demangled["_RNvCs4LeBXFJ98md_4test14uninlined_call"].as_deref(),
+        Some("test::uninlined_call")
+    );
+    assert_eq!(demangled["_start"], None);
+}
+
+
+/// Check that we report linkage names of symbols in C++ and Rust
+/// binaries.
+#[test]
+fn symbolize_linkage_name() {
+    fn test(path: &Path, name: &str, demangled: &str, linkage_name: Option<&str>) {
+        let src = inspect::Source::Elf(inspect::Elf::new(path));
+        let inspector = Inspector::new();
+        let results = inspector
+            .lookup(&src, &[name])
+            .unwrap()
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+        assert_eq!(results.len(), 1, "{name}");
+        let addr = results[0].addr;
+
+        let symbolizer = Symbolizer::new();
+        for debug_syms in [true, false] {
+            let mut elf = symbolize::Elf::new(path);
+            elf.debug_syms = debug_syms;
+            let src = symbolize::Source::Elf(elf);
+            let sym = symbolizer
+                .symbolize_single(&src, symbolize::Input::VirtOffset(addr))
+                .unwrap()
+                .into_sym()
+                .unwrap();
+            assert_eq!(sym.name, demangled);
+            assert_eq!(sym.linkage_name.as_deref(), linkage_name);
+        }
+
+        // The linkage name is reported irrespective of whether
+        // demangling is enabled.
+        let symbolizer = Symbolizer::builder().enable_demangling(false).build();
+        let src = symbolize::Source::Elf(symbolize::Elf::new(path));
+        let sym = symbolizer
+            .symbolize_single(&src, symbolize::Input::VirtOffset(addr))
+            .unwrap()
+            .into_sym()
+            .unwrap();
+        assert_eq!(sym.name, name);
+        assert_eq!(sym.linkage_name.as_deref(), linkage_name);
+    }
+
+    let data_dir = Path::new(&env!("CARGO_MANIFEST_DIR")).join("data");
+    let path = data_dir.join("test-cpp.bin");
+    test(
+        &path,
+        "_ZN4test16mangled_functionEi",
+        "test::mangled_function(int)",
+        Some("_ZN4test16mangled_functionEi"),
+    );
+    test(&path, "unmangled_function", "unmangled_function", None);
+
+    let path = data_dir.join("test-rs.bin");
+    test(
+        &path,
+        "_RNvCs69hjMPjVIJK_4test13test_function",
+        "test::test_function",
+        Some("_RNvCs69hjMPjVIJK_4test13test_function"),
+    );
+}
+
+
 /// Check that we can symbolize addresses inside our own process.
 #[test]
 fn symbolize_process() {
@@ -677,42 +2526,258 @@ fn normalize_elf_addr() {
         let the_answer_addr = unsafe { libc::dlsym(handle, "the_answer\0".as_ptr().cast()) };
         assert!(!the_answer_addr.is_null());
 
-        let normalizer = Normalizer::new();
-        let normalized = normalizer
-            .normalize_user_addrs_sorted(Pid::Slf, [the_answer_addr as Addr].as_slice())
-            .unwrap();
-        assert_eq!(normalized.outputs.len(), 1);
-        assert_eq!(normalized.meta.len(), 1);
+        let normalizer = Normalizer::new();
+        let normalized = normalizer
+            .normalize_user_addrs_sorted(Pid::Slf, [the_answer_addr as Addr].as_slice())
+            .unwrap();
+        assert_eq!(normalized.outputs.len(), 1);
+        assert_eq!(normalized.meta.len(), 1);
+
+        let rc = unsafe { libc::dlclose(handle) };
+        assert_eq!(rc, 0, "{}", Error::last_os_error());
+
+        let output = normalized.outputs[0];
+        let meta = &normalized.meta[output.1];
+        assert_eq!(meta.elf().unwrap().path, test_so);
+
+        let elf = symbolize::Elf::new(test_so);
+        let src = symbolize::Source::Elf(elf);
+        let symbolizer = Symbolizer::new();
+        let result = symbolizer
+            .symbolize_single(&src, symbolize::Input::FileOffset(output.0))
+            .unwrap()
+            .into_sym()
+            .unwrap();
+
+        assert_eq!(result.name, "the_answer");
+
+        let results = symbolizer
+            .symbolize(&src, symbolize::Input::FileOffset(&[output.0]))
+            .unwrap();
+        assert_eq!(results.len(), 1);
+
+        let sym = results[0].as_sym().unwrap();
+        assert_eq!(sym.name, "the_answer");
+    }
+
+    test("libtest-so.so");
+    test("libtest-so-no-separate-code.so");
+}
+
+
+/// Check that the `Symbolizer` reports cache statistics as expected.
+#[test]
+fn symbolize_cache_stats() {
+    let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+        .join("data")
+        .join("test-stable-addrs.bin");
+    let src = symbolize::Source::Elf(symbolize::Elf::new(&path));
+    let symbolizer = Symbolizer::new();
+
+    let stats = symbolizer.cache_stats();
+    assert_eq!(stats.entries, 0);
+    assert_eq!(stats.hits, 0);
+    assert_eq!(stats.misses, 0);
+    assert_eq!(stats.bytes_estimate, 0);
+
+    for _ in 0..3 {
+        let sym = symbolizer
+            .symbolize_single(&src, symbolize::Input::VirtOffset(0x2000100))
+            .unwrap()
+            .into_sym()
+            .unwrap();
+        assert_eq!(sym.name, "factorial");
+    }
+
+    let stats = symbolizer.cache_stats();
+    assert_eq!(stats.entries, 1);
+    assert_eq!(stats.hits, 2);
+    assert_eq!(stats.misses, 1);
+    assert_eq!(stats.bytes_estimate, path.metadata().unwrap().len());
+}
+
+
+/// Check that we honor the configured sysroot when symbolizing files
+/// referenced by absolute paths.
+#[test]
+fn symbolize_elf_sysroot() {
+    let test_so = Path::new(&env!("CARGO_MANIFEST_DIR"))
+        .join("data")
+        .join("libtest-so.so");
+    let sysroot = tempdir().unwrap();
+    let lib = sysroot.path().join("usr").join("lib");
+    let () = create_dir_all(&lib).unwrap();
+    let _size = copy(&test_so, lib.join("libtest-so.so")).unwrap();
+    // An absolute symbolic link has to be resolved relative to the
+    // sysroot.
+    let () = symlink("/usr/lib", sysroot.path().join("lib")).unwrap();
+
+    // The file offset of `the_answer`, as a `Normalizer` would have
+    // reported it on the target system.
+    let offset = 0x1129;
+    let elf = symbolize::Elf::new("/lib/libtest-so.so");
+    let src = symbolize::Source::Elf(elf);
+
+    let symbolizer = Symbolizer::new();
+    let err = symbolizer
+        .symbolize_single(&src, symbolize::Input::FileOffset(offset))
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::NotFound);
+
+    let symbolizer = Symbolizer::builder().set_sysroot(sysroot.path()).build();
+    let sym = symbolizer
+        .symbolize_single(&src, symbolize::Input::FileOffset(offset))
+        .unwrap()
+        .into_sym()
+        .unwrap();
+    assert_eq!(sym.name, "the_answer");
+
+    // Files not present in the sysroot are looked up at their original
+    // path.
+    let elf = symbolize::Elf::new(test_so);
+    let src = symbolize::Source::Elf(elf);
+    let sym = symbolizer
+        .symbolize_single(&src, symbolize::Input::FileOffset(offset))
+        .unwrap()
+        .into_sym()
+        .unwrap();
+    assert_eq!(sym.name, "the_answer");
+}
+
+
+/// Check that we can symbolize normalized addresses by means of the
+/// symbolization groups created from them.
+#[test]
+fn normalize_symbolize_groups() {
+    let test_so = Path::new(&env!("CARGO_MANIFEST_DIR"))
+        .join("data")
+        .join("libtest-so.so");
+    let so_cstr = CString::new(test_so.clone().into_os_string().into_vec()).unwrap();
+    let handle = unsafe { libc::dlopen(so_cstr.as_ptr(), libc::RTLD_NOW) };
+    assert!(!handle.is_null());
+
+    let the_answer_addr = unsafe { libc::dlsym(handle, "the_answer\0".as_ptr().cast()) };
+    assert!(!the_answer_addr.is_null());
+
+    let addrs = [
+        the_answer_addr as Addr,
+        libc::fopen as *const () as Addr,
+        // The very first page of the address space should never be
+        // mapped.
+        0x500,
+        the_answer_addr as Addr,
+    ];
+    let normalizer = Normalizer::new();
+    let normalized = normalizer
+        .normalize_user_addrs(Pid::Slf, addrs.as_slice())
+        .unwrap();
+    assert_eq!(normalized.outputs.len(), 4);
+
+    let rc = unsafe { libc::dlclose(handle) };
+    assert_eq!(rc, 0, "{}", Error::last_os_error());
 
-        let rc = unsafe { libc::dlclose(handle) };
-        assert_eq!(rc, 0, "{}", Error::last_os_error());
+    let groups = normalized.into_symbolizer_groups();
+    // The unknown address is not reported.
+    assert_eq!(groups.len(), 2);
 
-        let output = normalized.outputs[0];
-        let meta = &normalized.meta[output.1];
-        assert_eq!(meta.elf().unwrap().path, test_so);
+    let elf_path = |src: &symbolize::Source| match src {
+        symbolize::Source::Elf(elf) => elf.path.clone(),
+        _ => panic!("encountered unexpected source: {src:?}"),
+    };
 
-        let elf = symbolize::Elf::new(test_so);
-        let src = symbolize::Source::Elf(elf);
-        let symbolizer = Symbolizer::new();
-        let result = symbolizer
-            .symbolize_single(&src, symbolize::Input::FileOffset(output.0))
-            .unwrap()
-            .into_sym()
-            .unwrap();
+    let (src, offsets, idxs) = &groups[0];
+    assert_eq!(elf_path(src), test_so);
+    assert_eq!(offsets.len(), 2);
+    assert_eq!(offsets[0], offsets[1]);
+    assert_eq!(idxs, &[0, 3]);
 
-        assert_eq!(result.name, "the_answer");
+    let (src, offsets, idxs) = &groups[1];
+    assert!(elf_path(src)
+        .file_name()
+        .unwrap()
+        .to_string_lossy()
+        .contains("libc.so"));
+    assert_eq!(offsets.len(), 1);
+    assert_eq!(idxs, &[1]);
 
+    // Symbolize each group and use the reported indices to map the
+    // results back to the order of the input addresses.
+    let symbolizer = Symbolizer::new();
+    let mut names = vec![None; addrs.len()];
+    for (src, offsets, idxs) in &groups {
         let results = symbolizer
-            .symbolize(&src, symbolize::Input::FileOffset(&[output.0]))
+            .symbolize(src, symbolize::Input::FileOffset(offsets))
             .unwrap();
-        assert_eq!(results.len(), 1);
-
-        let sym = results[0].as_sym().unwrap();
-        assert_eq!(sym.name, "the_answer");
+        assert_eq!(results.len(), offsets.len());
+        for (idx, result) in idxs.iter().zip(results) {
+            names[*idx] = Some(result.into_sym().unwrap().name.to_string());
+        }
     }
 
-    test("libtest-so.so");
-    test("libtest-so-no-separate-code.so");
+    assert_eq!(names[0].as_deref(), Some("the_answer"));
+    assert!(
+        names[1].as_deref().unwrap().contains("fopen"),
+        "{:?}",
+        names[1]
+    );
+    assert_eq!(names[2], None);
+    assert_eq!(names[3].as_deref(), Some("the_answer"));
+}
+
+/// Check that we can symbolize normalized addresses directly.
+#[test]
+fn normalize_symbolize_normalized() {
+    let test_so = Path::new(&env!("CARGO_MANIFEST_DIR"))
+        .join("data")
+        .join("libtest-so.so");
+    let so_cstr = CString::new(test_so.clone().into_os_string().into_vec()).unwrap();
+    let handle = unsafe { libc::dlopen(so_cstr.as_ptr(), libc::RTLD_NOW) };
+    assert!(!handle.is_null());
+
+    let the_answer_addr = unsafe { libc::dlsym(handle, "the_answer\0".as_ptr().cast()) };
+    assert!(!the_answer_addr.is_null());
+
+    let addrs = [
+        the_answer_addr as Addr,
+        libc::fopen as *const () as Addr,
+        // The very first page of the address space should never be
+        // mapped.
+        0x500,
+        the_answer_addr as Addr,
+    ];
+    let normalizer = Normalizer::new();
+    let mut normalized = normalizer
+        .normalize_user_addrs(Pid::Slf, addrs.as_slice())
+        .unwrap();
+
+    let rc = unsafe { libc::dlclose(handle) };
+    assert_eq!(rc, 0, "{}", Error::last_os_error());
+
+    let symbolizer = Symbolizer::new();
+    let results = symbolizer.symbolize_normalized(&normalized).unwrap();
+    assert_eq!(results.len(), addrs.len());
+    let sym = results[0].as_sym().unwrap();
+    assert_eq!(sym.name, "the_answer");
+    let sym = results[1].as_sym().unwrap();
+    assert!(sym.name.contains("fopen"), "{}", sym.name);
+    assert_eq!(results[2], Symbolized::Unknown(Reason::Unmapped));
+    let sym = results[3].as_sym().unwrap();
+    assert_eq!(sym.name, "the_answer");
+
+    // Symbolization of a file with a build ID other than the
+    // recorded one is refused.
+    let meta_idx = normalized.outputs[0].1;
+    let elf = match &mut normalized.meta[meta_idx] {
+        normalize::UserMeta::Elf(elf) => elf,
+        meta => panic!("encountered unexpected meta data: {meta:?}"),
+    };
+    assert!(elf.build_id.is_some());
+    elf.build_id = Some(Cow::Borrowed(&[0xde, 0xad, 0xbe, 0xef]));
+
+    let results = symbolizer.symbolize_normalized(&normalized).unwrap();
+    assert_eq!(results[0], Symbolized::Unknown(Reason::BuildIdMismatch));
+    assert!(results[1].as_sym().is_some());
+    assert_eq!(results[3], Symbolized::Unknown(Reason::BuildIdMismatch));
 }
 
 
@@ -759,6 +2824,67 @@ fn normalize_build_id_reading() {
 }
 
 
+/// Check that we can normalize addresses in a 32 bit process.
+#[test]
+fn normalize_process_32() {
+    let test_bin = Path::new(&env!("CARGO_MANIFEST_DIR"))
+        .join("data")
+        .join("test-wait-32.bin");
+
+    let mut child = Command::new(&test_bin)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .unwrap();
+    let pid = child.id();
+    defer!({
+        // Best effort only. The child may end up terminating gracefully
+        // if everything goes as planned.
+        let _rc = unsafe { kill(pid as _, SIGKILL) };
+    });
+
+    let mut buf = [0u8; 64];
+    let count = child
+        .stdout
+        .as_mut()
+        .unwrap()
+        .read(&mut buf)
+        .expect("failed to read child output");
+    let addr_str = str::from_utf8(&buf[0..count]).unwrap().trim_end();
+    let addr = Addr::from_str_radix(addr_str.trim_start_matches("0x"), 16).unwrap();
+
+    {
+        let normalizer = Normalizer::new();
+        let normalized = normalizer
+            .normalize_user_addrs_sorted(Pid::from(pid), [addr].as_slice())
+            .unwrap();
+        assert_eq!(normalized.outputs.len(), 1);
+        assert_eq!(normalized.meta.len(), 1);
+
+        let (file_offset, meta_idx) = normalized.outputs[0];
+        let elf = normalized.meta[meta_idx].elf().unwrap();
+        assert_eq!(elf.path, test_bin);
+        let expected = read_elf_build_id(&test_bin).unwrap().unwrap();
+        assert_eq!(elf.build_id.as_ref().unwrap(), &expected);
+
+        let src = symbolize::Source::Elf(symbolize::Elf::new(&test_bin));
+        let symbolizer = Symbolizer::new();
+        let sym = symbolizer
+            .symbolize_single(&src, symbolize::Input::FileOffset(file_offset))
+            .unwrap()
+            .into_sym()
+            .unwrap();
+        assert_eq!(sym.name, "the_answer");
+        assert_eq!(sym.addr, addr);
+    }
+
+    // "Signal" the child to terminate gracefully.
+    let () = child.stdin.as_ref().unwrap().write_all(&[0x04]).unwrap();
+    let _status = child.wait().unwrap();
+}
+
+
 /// Check that we can look up an address.
 #[test]
 fn inspect_elf() {
@@ -824,6 +2950,134 @@ fn inspect_elf() {
 }
 
 
+/// Check that we handle duplicate and unknown names when looking up
+/// multiple symbols at once.
+#[test]
+fn inspect_elf_batch_lookup() {
+    fn test(src: inspect::Source) {
+        let inspector = Inspector::new();
+        let results = inspector
+            .lookup(
+                &src,
+                &[
+                    "factorial",
+                    "does_not_exist",
+                    "factorial_wrapper",
+                    "factorial",
+                ],
+            )
+            .unwrap();
+        assert_eq!(results.len(), 4);
+
+        assert_eq!(results[0].len(), 1);
+        assert_eq!(results[0][0].name, "factorial");
+        assert_eq!(results[0][0].addr, 0x2000100);
+        assert!(results[1].is_empty(), "{:#x?}", results[1]);
+        assert!(!results[2].is_empty());
+        assert!(results[2].iter().all(|sym| sym.name == "factorial_wrapper"));
+        assert_eq!(results[3], results[0]);
+
+        let results = inspector.lookup(&src, &[]).unwrap();
+        assert!(results.is_empty());
+    }
+
+    for file in [
+        "test-stable-addrs.bin",
+        "test-stable-addrs-no-dwarf.bin",
+        "test-stable-addrs-stripped-elf-with-dwarf.bin",
+    ] {
+        let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join(file);
+        let src = inspect::Source::Elf(inspect::Elf::new(path));
+        let () = test(src);
+    }
+}
+
+
+/// Check that we can restrict symbol lookup to symbols with a certain
+/// binding.
+#[test]
+fn inspect_elf_lookup_binding() {
+    let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+        .join("data")
+        .join("libtest-so.so");
+    let src = inspect::Source::Elf(inspect::Elf::new(path));
+    let inspector = Inspector::new();
+
+    let names = ["the_answer", "the_weak_answer"];
+    let opts = LookupOpts {
+        binding: Some(SymBinding::Weak),
+        ..Default::default()
+    };
+    let results = inspector.lookup_with_opts(&src, &names, &opts).unwrap();
+    assert!(results[0].is_empty(), "{:#x?}", results[0]);
+    assert_eq!(results[1].len(), 1);
+    assert_eq!(results[1][0].binding, Some(SymBinding::Weak));
+
+    let opts = LookupOpts {
+        binding: Some(SymBinding::Global),
+        ..Default::default()
+    };
+    let results = inspector.lookup_with_opts(&src, &names, &opts).unwrap();
+    assert_eq!(results[0].len(), 1);
+    assert_eq!(results[0][0].binding, Some(SymBinding::Global));
+    assert!(results[1].is_empty(), "{:#x?}", results[1]);
+}
+
+
+/// Check that symbols reported by inspection and symbolization can be
+/// merged into a consistent unified representation.
+#[test]
+fn inspect_symbolize_sym_conversion() {
+    let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+        .join("data")
+        .join("test-stable-addrs.bin");
+
+    let inspector = Inspector::new();
+    let src = inspect::Source::Elf(inspect::Elf::new(&path));
+    let info = inspector.lookup(&src, &["factorial"]).unwrap()[0][0].clone();
+
+    let symbolizer = Symbolizer::new();
+    let src = symbolize::Source::Elf(symbolize::Elf::new(&path));
+    let symbolized = symbolizer
+        .symbolize_single(&src, symbolize::Input::VirtOffset(info.addr))
+        .unwrap()
+        .into_sym()
+        .unwrap();
+
+    let code_sym = sym::CodeSym::from(symbolized.clone());
+    assert_eq!(code_sym.code_info, symbolized.code_info);
+
+    let sym1 = sym::Sym::from(info.clone());
+    let sym2 = sym::Sym::from(symbolized.clone());
+    assert_eq!(sym1.name, sym2.name);
+    assert_eq!(sym1.addr, sym2.addr);
+    assert_eq!(sym1.size, sym2.size);
+    assert_eq!(sym2.sym_type, SymType::Undefined);
+    assert_eq!(sym2.module, None);
+
+    let merged = sym2.merge(sym1);
+    assert_eq!(merged.name, "factorial");
+    assert_eq!(merged.addr, 0x2000100);
+    assert_eq!(merged.size, Some(0x2b));
+    assert_eq!(merged.sym_type, SymType::Function);
+    assert_eq!(merged.module.as_deref(), Some(path.as_path()));
+
+    let info2 = inspect::SymInfo::try_from(merged).unwrap();
+    assert_eq!(info2.name, info.name);
+    assert_eq!(info2.addr, info.addr);
+    assert_eq!(info2.size, info.size);
+    assert_eq!(info2.sym_type, info.sym_type);
+    assert_eq!(info2.obj_file_name, info.obj_file_name);
+
+    let info3 = inspect::SymInfo::try_from(symbolized).unwrap();
+    assert_eq!(info3.name, info.name);
+    assert_eq!(info3.addr, info.addr);
+    assert_eq!(info3.size, info.size);
+}
+
+
 /// Check that we can look up a symbol by name in a Breakpad file.
 #[test]
 fn inspect_breakpad() {
@@ -851,6 +3105,128 @@ fn inspect_breakpad() {
 }
 
 
+/// Check that we can look up type information in an ELF file.
+#[test]
+fn inspect_elf_type_info() {
+    let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+        .join("data")
+        .join("test-types.bin");
+    let src = inspect::Source::Elf(inspect::Elf::new(&path));
+    let inspector = Inspector::new();
+
+    let info = inspector.type_info(&src, "node").unwrap().unwrap();
+    let (name, size, fields) = match info {
+        TypeInfo::Struct { name, size, fields } => (name, size, fields),
+        info => panic!("unexpected type info: {info:?}"),
+    };
+    assert_eq!(name.as_deref(), Some("node"));
+    assert_eq!(size, 48);
+    assert_eq!(fields.len(), 4);
+
+    let next = &fields[0];
+    assert_eq!(next.name.as_deref(), Some("next"));
+    assert_eq!(next.offset, 0);
+    // The named struct being pointed to is only referenced by name.
+    assert_eq!(
+        next.ty,
+        TypeInfo::Pointer {
+            pointee: Box::new(TypeInfo::Opaque {
+                name: Some("node".to_string()),
+            })
+        }
+    );
+
+    let uint = TypeInfo::Typedef {
+        name: "uint32_t".to_string(),
+        ty: Box::new(TypeInfo::Typedef {
+            name: "__uint32_t".to_string(),
+            ty: Box::new(TypeInfo::Primitive {
+                name: "unsigned int".to_string(),
+                size: 4,
+            }),
+        }),
+    };
+    let values = &fields[1];
+    assert_eq!(values.name.as_deref(), Some("values"));
+    assert_eq!(values.offset, 8);
+    assert_eq!(
+        values.ty,
+        TypeInfo::Array {
+            elem: Box::new(uint.clone()),
+            count: 4,
+        }
+    );
+
+    let color = &fields[2];
+    assert_eq!(color.name.as_deref(), Some("color"));
+    assert_eq!(color.offset, 24);
+    let variants = match &color.ty {
+        TypeInfo::Enum {
+            name,
+            size,
+            variants,
+        } => {
+            assert_eq!(name.as_deref(), Some("color"));
+            assert_eq!(*size, 4);
+            variants
+        }
+        ty => panic!("unexpected type info: {ty:?}"),
+    };
+    let variants = variants
+        .iter()
+        .map(|variant| (variant.name.as_str(), variant.value))
+        .collect::<Vec<_>>();
+    assert_eq!(variants, [("RED", 0), ("GREEN", 5), ("BLUE", 6)]);
+
+    let names = &fields[3];
+    assert_eq!(names.name.as_deref(), Some("names"));
+    assert_eq!(names.offset, 28);
+    assert_eq!(
+        names.ty,
+        TypeInfo::Array {
+            elem: Box::new(TypeInfo::Array {
+                elem: Box::new(TypeInfo::Primitive {
+                    name: "char".to_string(),
+                    size: 1,
+                }),
+                count: 8,
+            }),
+            count: 2,
+        }
+    );
+
+    // Typedefs retain their name.
+    let info = inspector.type_info(&src, "uint32_t").unwrap();
+    assert_eq!(info, Some(uint));
+
+    // Older DWARF versions describe member offsets as expressions,
+    // which should be evaluated.
+    let path_v2 = Path::new(&env!("CARGO_MANIFEST_DIR"))
+        .join("data")
+        .join("test-types-dwarf-v2.bin");
+    let src_v2 = inspect::Source::Elf(inspect::Elf::new(path_v2));
+    let info_v2 = inspector.type_info(&src_v2, "node").unwrap().unwrap();
+    let fields_v2 = match info_v2 {
+        TypeInfo::Struct { fields, .. } => fields,
+        info => panic!("unexpected type info: {info:?}"),
+    };
+    let offsets = fields_v2
+        .iter()
+        .map(|field| field.offset)
+        .collect::<Vec<_>>();
+    assert_eq!(offsets, [0, 8, 24, 28]);
+
+    let info = inspector.type_info(&src, "does_not_exist").unwrap();
+    assert_eq!(info, None);
+
+    let mut elf = inspect::Elf::new(path);
+    elf.debug_syms = false;
+    let src = inspect::Source::Elf(elf);
+    let err = inspector.type_info(&src, "node").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unsupported);
+}
+
+
 /// Make sure that we can look up a dynamic symbol in an ELF file.
 #[test]
 fn inspect_elf_dynamic_symbol() {
@@ -887,6 +3263,54 @@ fn inspect_elf_dynamic_symbol() {
     test("libtest-so-partly-stripped.so");
 }
 
+/// Check that we can look up and symbolize versioned symbols.
+#[test]
+fn inspect_elf_versioned_symbols() {
+    let bin = Path::new(&env!("CARGO_MANIFEST_DIR"))
+        .join("data")
+        .join("libtest-versioned-stripped.so");
+
+    let src = inspect::Source::Elf(inspect::Elf::new(&bin));
+    let inspector = Inspector::new();
+    let results = inspector
+        .lookup(&src, &["versioned@VERS_1.0", "versioned@@VERS_2.0"])
+        .unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].len(), 1);
+    assert_eq!(results[1].len(), 1);
+
+    let v1 = &results[0][0];
+    assert_eq!(v1.name, "versioned");
+    let version = v1.version.as_ref().unwrap();
+    assert_eq!(version.name, "VERS_1.0");
+    assert!(version.hidden);
+
+    let v2 = &results[1][0];
+    assert_eq!(v2.name, "versioned");
+    let version = v2.version.as_ref().unwrap();
+    assert_eq!(version.name, "VERS_2.0");
+    assert!(!version.hidden);
+    assert_ne!(v1.addr, v2.addr);
+
+    let src = symbolize::Source::Elf(symbolize::Elf::new(&bin));
+    let symbolizer = Symbolizer::new();
+    let result = symbolizer
+        .symbolize_single(&src, symbolize::Input::VirtOffset(v1.addr))
+        .unwrap()
+        .into_sym()
+        .unwrap();
+    assert_eq!(result.name, "versioned");
+
+    let symbolizer = Symbolizer::builder().enable_sym_versions(true).build();
+    let results = symbolizer
+        .symbolize(&src, symbolize::Input::VirtOffset(&[v1.addr, v2.addr]))
+        .unwrap()
+        .into_iter()
+        .map(|result| result.into_sym().unwrap().name)
+        .collect::<Vec<_>>();
+    assert_eq!(results, vec!["versioned@VERS_1.0", "versioned@@VERS_2.0"]);
+}
+
 /// Make sure that we can look up an indirect in an ELF file.
 #[test]
 fn inspect_elf_indirect_function() {
@@ -897,12 +3321,14 @@ fn inspect_elf_indirect_function() {
     let src = inspect::Source::Elf(inspect::Elf::new(&bin));
     let inspector = Inspector::new();
     let results = inspector
-        .lookup(&src, &["indirect_func"])
+        .lookup(&src, &["indirect_func", "my_indirect_func"])
         .unwrap()
         .into_iter()
         .flatten()
         .collect::<Vec<_>>();
-    assert_eq!(results.len(), 1);
+    assert_eq!(results.len(), 2);
+    assert!(results[0].is_ifunc);
+    assert!(!results[1].is_ifunc);
 
     let src = symbolize::Source::Elf(symbolize::Elf::new(&bin));
     let symbolizer = Symbolizer::new();
@@ -952,6 +3378,175 @@ fn inspect_elf_file_offset() {
 }
 
 
+/// Check that we can probe ELF files for the debug information they
+/// contain.
+#[test]
+fn inspect_elf_debug_info() {
+    fn probe(file: &str) -> inspect::DebugInfo {
+        let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join(file);
+        let src = inspect::Source::Elf(inspect::Elf::new(path));
+        let inspector = Inspector::new();
+        inspector.debug_info(&src).unwrap()
+    }
+
+    for (file, version) in [
+        ("test-dwarf-v2.bin", 2),
+        ("test-dwarf-v3.bin", 3),
+        ("test-dwarf-v4.bin", 4),
+        ("test-dwarf-v5.bin", 5),
+    ] {
+        let info = probe(file);
+        assert!(info.symtab, "{file}");
+        assert!(info.dynsym, "{file}");
+        assert_eq!(info.dwarf_version, Some(version), "{file}");
+        assert_eq!(info.dwarf_compression, None, "{file}");
+        assert!(!info.gsym, "{file}");
+    }
+
+    let info = probe("test-dwarf-v5-zlib.bin");
+    assert_eq!(
+        info.dwarf_compression,
+        Some(inspect::DebugCompression::Zlib)
+    );
+    if cfg!(feature = "zlib") {
+        assert_eq!(info.dwarf_version, Some(5));
+    }
+
+    let info = probe("test-stable-addrs.bin");
+    assert!(info.symtab);
+    assert!(info.dwarf_version.is_some());
+    assert!(!info.debug_link);
+    assert!(!info.mini_debug_info);
+    assert!(info.gsym);
+
+    let info = probe("test-stable-addrs-no-dwarf.bin");
+    assert!(info.symtab);
+    assert_eq!(info.dwarf_version, None);
+    assert_eq!(info.dwarf_compression, None);
+
+    let info = probe("test-stable-addrs-stripped-elf-with-dwarf.bin");
+    assert!(!info.symtab);
+    assert!(info.dwarf_version.is_some());
+
+    let info = probe("test-stable-addrs-stripped.bin");
+    assert_eq!(
+        info,
+        inspect::DebugInfo {
+            symtab: false,
+            dynsym: false,
+            dwarf_version: None,
+            dwarf_compression: None,
+            debug_link: false,
+            mini_debug_info: false,
+            gsym: false,
+            _non_exhaustive: (),
+        }
+    );
+}
+
+
+/// Check that we can enumerate the source files that contributed to an
+/// ELF file.
+#[test]
+fn inspect_elf_source_files() {
+    fn source_files(file: &str, debug_syms: bool) -> Vec<PathBuf> {
+        let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join(file);
+        let mut elf = inspect::Elf::new(path);
+        elf.debug_syms = debug_syms;
+        let src = inspect::Source::Elf(elf);
+        let inspector = Inspector::new();
+        let mut files = Vec::new();
+        let () = inspector
+            .for_each_source_file(&src, |path| files.push(path.to_path_buf()))
+            .unwrap();
+        files
+    }
+
+    let files = source_files("test-stable-addrs.bin", true);
+    assert!(
+        files
+            .iter()
+            .any(|path| path.ends_with("test-stable-addrs.c") && path.is_absolute()),
+        "{files:?}"
+    );
+    let mut deduped = files.clone();
+    let () = deduped.sort();
+    let () = deduped.dedup();
+    assert_eq!(deduped.len(), files.len(), "{files:?}");
+
+    assert_eq!(
+        source_files("test-stable-addrs.bin", false),
+        Vec::<PathBuf>::new()
+    );
+    assert_eq!(
+        source_files("test-stable-addrs-no-dwarf.bin", true),
+        Vec::<PathBuf>::new()
+    );
+}
+
+/// Check that we can retrieve information about the compilation unit
+/// containing an address.
+#[test]
+fn inspect_elf_unit_info() {
+    let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+        .join("data")
+        .join("test-stable-addrs.bin");
+    let src = inspect::Source::Elf(inspect::Elf::new(&path));
+    let inspector = Inspector::new();
+
+    for (addr, file) in [
+        (0x2000100, "test-stable-addrs.c"),
+        (0x200005f, "test-stable-addrs-cu2.c"),
+    ] {
+        let info = inspector.unit_for_addr(&src, addr).unwrap().unwrap();
+        assert_eq!(info.path.unwrap().file_name(), Some(OsStr::new(file)));
+        // The binary is built by the system's C compiler, which is
+        // either GCC or clang.
+        let producer = info.producer.unwrap();
+        assert!(
+            producer.starts_with("GNU C") || producer.contains("clang"),
+            "{producer}"
+        );
+        assert_eq!(info.lang, SrcLang::C);
+        assert!(info.language.is_some());
+        assert!(info.comp_dir.is_some());
+        assert_eq!(info.version, 4);
+    }
+
+    // Addresses not covered by any unit have no unit information.
+    assert_eq!(inspector.unit_for_addr(&src, 0x1000).unwrap(), None);
+
+    let mut elf = inspect::Elf::new(&path);
+    elf.debug_syms = false;
+    let src = inspect::Source::Elf(elf);
+    assert_eq!(inspector.unit_for_addr(&src, 0x2000100).unwrap(), None);
+
+    let path = Path::new(&env!("CARGO_MANIFEST_DIR"))
+        .join("data")
+        .join("test-rs.bin");
+    let src = inspect::Source::Elf(inspect::Elf::new(&path));
+    let results = inspector
+        .lookup(&src, &["_RNvCs69hjMPjVIJK_4test13test_function"])
+        .unwrap()
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+    let info = inspector
+        .unit_for_addr(&src, results[0].addr)
+        .unwrap()
+        .unwrap();
+    let producer = info.producer.unwrap();
+    assert!(producer.contains("rustc"), "{producer}");
+    assert_eq!(info.lang, SrcLang::Rust);
+    // `DW_LANG_Rust`
+    assert_eq!(info.language, Some(0x1c));
+}
+
+
 /// Check that we can iterate over all symbols in an ELF file.
 #[test]
 fn inspect_elf_breakpad_all_symbols() {
@@ -984,6 +3579,7 @@ fn inspect_elf_breakpad_all_symbols() {
         if !breakpad {
             let sym = syms.get("indirect_func").unwrap();
             assert_eq!(sym.sym_type, SymType::Function);
+            assert!(sym.is_ifunc);
         }
 
         let sym = syms.get("my_indirect_func").unwrap();